@@ -0,0 +1,273 @@
+//! Reference swap quotes of the AMM, computed off-chain with the program's own curve and fee
+//! math, for integrators to diff their quoting against
+//!
+//! Reads the config and curve accounts from the JSON of a `getAccountInfo` RPC response with
+//! base64 encoding (or `solana account --output json`) and prints the quote as JSON:
+//!
+//! ```shell
+//! amm-quote --config config.json --curve curve.json --direction buy --amount 1000000000
+//! ```
+
+use std::{
+    env, fs, process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use amm::{
+    params::swap::TradeDirection,
+    states::{BondingCurve, CashbackTier, Config},
+};
+use anchor_lang::{Discriminator, Space};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+
+const USAGE: &str = "usage: amm-quote --config <file> --curve <file> --direction <buy|sell> \
+--amount <amount> [--exact-out] [--l1-referral] [--l2-referral] [--l3-referral] \
+[--cashback-tier <0-6>] [--timestamp <unix timestamp>]";
+
+struct Args {
+    config_path: String,
+    curve_path: String,
+    trade_direction: TradeDirection,
+    amount: u64,
+    is_exact_out: bool,
+    has_l1_referral: bool,
+    has_l2_referral: bool,
+    has_l3_referral: bool,
+    cashback_tier: Option<CashbackTier>,
+    /// time the launch fee is quoted at, now by default
+    timestamp: Option<u64>,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut config_path = None;
+    let mut curve_path = None;
+    let mut trade_direction = None;
+    let mut amount = None;
+    let mut is_exact_out = false;
+    let mut has_l1_referral = false;
+    let mut has_l2_referral = false;
+    let mut has_l3_referral = false;
+    let mut cashback_tier = None;
+    let mut timestamp = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value of {arg}"));
+        match arg.as_str() {
+            "--config" => config_path = Some(value()?),
+            "--curve" => curve_path = Some(value()?),
+            "--direction" => {
+                trade_direction = Some(match value()?.as_str() {
+                    "buy" => TradeDirection::QuoteToBase,
+                    "sell" => TradeDirection::BaseToQuote,
+                    direction => return Err(format!("unknown direction {direction}")),
+                })
+            }
+            "--amount" => {
+                amount = Some(value()?.parse().map_err(|_| "invalid amount".to_string())?)
+            }
+            "--cashback-tier" => {
+                let tier: u8 = value()?
+                    .parse()
+                    .map_err(|_| "invalid cashback tier".to_string())?;
+                cashback_tier = Some(
+                    CashbackTier::try_from(tier)
+                        .map_err(|_| "invalid cashback tier".to_string())?,
+                );
+            }
+            "--timestamp" => {
+                timestamp = Some(
+                    value()?
+                        .parse()
+                        .map_err(|_| "invalid timestamp".to_string())?,
+                )
+            }
+            "--exact-out" => is_exact_out = true,
+            "--l1-referral" => has_l1_referral = true,
+            "--l2-referral" => has_l2_referral = true,
+            "--l3-referral" => has_l3_referral = true,
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+
+    Ok(Args {
+        config_path: config_path.ok_or("missing --config")?,
+        curve_path: curve_path.ok_or("missing --curve")?,
+        trade_direction: trade_direction.ok_or("missing --direction")?,
+        amount: amount.ok_or("missing --amount")?,
+        is_exact_out,
+        has_l1_referral,
+        has_l2_referral,
+        has_l3_referral,
+        cashback_tier,
+        timestamp,
+    })
+}
+
+/// Account data of a `getAccountInfo` response, its `value`, or a `solana account` JSON
+fn get_account_data(account_json: &Value) -> Result<Vec<u8>, String> {
+    let account = account_json
+        .pointer("/result/value")
+        .or_else(|| account_json.get("account"))
+        .unwrap_or(account_json);
+    let data = account
+        .pointer("/data/0")
+        .and_then(Value::as_str)
+        .ok_or("account data has to be base64 encoded")?;
+    STANDARD
+        .decode(data)
+        .map_err(|err| format!("invalid base64 account data: {err}"))
+}
+
+/// Zero copy account from its data. Accounts created on an older, smaller layout are zero
+/// padded to the current one, like `realloc_curve` does
+fn decode_account<T: bytemuck::Pod + Discriminator + Space>(data: &[u8]) -> Result<T, String> {
+    let discriminator = T::DISCRIMINATOR;
+    if !data.starts_with(discriminator) {
+        return Err("account discriminator doesn't match".to_string());
+    }
+    let mut account_data = data[discriminator.len()..].to_vec();
+    if account_data.len() > T::INIT_SPACE {
+        return Err("account data is larger than the account layout".to_string());
+    }
+    account_data.resize(T::INIT_SPACE, 0);
+    Ok(bytemuck::pod_read_unaligned(&account_data))
+}
+
+fn load_account<T: bytemuck::Pod + Discriminator + Space>(path: &str) -> Result<T, String> {
+    let file = fs::read_to_string(path).map_err(|err| format!("can't read {path}: {err}"))?;
+    let account_json = serde_json::from_str(&file).map_err(|err| format!("{path}: {err}"))?;
+    decode_account(&get_account_data(&account_json)?)
+}
+
+fn quote(args: &Args, config: &Config, curve: &BondingCurve) -> Result<Value, String> {
+    let timestamp = match args.timestamp {
+        Some(timestamp) => timestamp,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| err.to_string())?
+            .as_secs(),
+    };
+    let launch_fee_config = config
+        .get_launch_fee_config(curve.get_launch_elapsed_seconds(timestamp))
+        .map_err(|err| err.to_string())?;
+    let config = &launch_fee_config;
+    let cashback_tier = args.cashback_tier.map(Into::into);
+    let amount_in = if args.is_exact_out {
+        curve
+            .get_amount_in_for_exact_out(
+                config,
+                args.amount,
+                args.trade_direction,
+                args.has_l1_referral,
+                args.has_l2_referral,
+                args.has_l3_referral,
+                cashback_tier,
+            )
+            .map_err(|err| err.to_string())?
+    } else {
+        args.amount
+    };
+    let swap_result = curve
+        .get_swap_result(
+            config,
+            amount_in,
+            args.trade_direction,
+            args.has_l1_referral,
+            args.has_l2_referral,
+            args.has_l3_referral,
+            cashback_tier,
+        )
+        .map_err(|err| err.to_string())?;
+    let total_input_amount = swap_result
+        .get_total_input_amount(args.trade_direction)
+        .map_err(|err| err.to_string())?;
+    let trading_fee_basis_points = config
+        .get_fee_schedule(
+            args.has_l1_referral,
+            args.has_l2_referral,
+            args.has_l3_referral,
+            cashback_tier,
+            args.trade_direction,
+        )
+        .map_err(|err| err.to_string())?
+        .trading_fee_basis_points;
+    let virtual_price = curve.get_virtual_price().map_err(|err| err.to_string())?;
+
+    Ok(json!({
+        "direction": match args.trade_direction {
+            TradeDirection::QuoteToBase => "buy",
+            TradeDirection::BaseToQuote => "sell",
+        },
+        "swap_mode": if args.is_exact_out { "exact_out" } else { "exact_in" },
+        "amount": args.amount,
+        "actual_input_amount": swap_result.actual_input_amount,
+        "total_input_amount": total_input_amount,
+        "output_amount": swap_result.output_amount,
+        "timestamp": timestamp,
+        "fees": {
+            "trading_fee_basis_points": trading_fee_basis_points,
+            "trading_fee": swap_result.trading_fee,
+            "protocol_fee": swap_result.protocol_fee,
+            "cashback_fee": swap_result.cashback_fee,
+            "creator_fee": swap_result.creator_fee,
+            "l1_referral_fee": swap_result.l1_referral_fee,
+            "l2_referral_fee": swap_result.l2_referral_fee,
+            "l3_referral_fee": swap_result.l3_referral_fee,
+        },
+        "curve": {
+            "virtual_quote_reserve": curve.virtual_quote_reserve,
+            "virtual_base_reserve": curve.virtual_base_reserve,
+            "base_reserve": curve.base_reserve,
+            "quote_reserve": curve.quote_reserve,
+            // Q64.64 exceeds JSON safe integers
+            "virtual_price_q64": virtual_price.to_string(),
+        },
+    }))
+}
+
+fn run() -> Result<Value, String> {
+    let args = parse_args(env::args().skip(1))?;
+    let config = load_account::<Config>(&args.config_path)?;
+    let curve = load_account::<BondingCurve>(&args.curve_path)?;
+    quote(&args, &config, &curve)
+}
+
+fn main() {
+    match run() {
+        Ok(quote) => println!("{quote:#}"),
+        Err(err) => {
+            eprintln!("{err}\n{USAGE}");
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_rpc_account_json_padding_older_layouts() {
+        let curve = BondingCurve {
+            virtual_quote_reserve: 30_000_000_000,
+            virtual_base_reserve: 1_073_000_000_000_000,
+            ..Default::default()
+        };
+        let mut data = BondingCurve::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&curve));
+        // A curve created before the last fields were added
+        data.truncate(data.len() - 16);
+        let account_json = json!({
+            "jsonrpc": "2.0",
+            "result": { "value": { "data": [STANDARD.encode(&data), "base64"] } },
+        });
+
+        let decoded: BondingCurve =
+            decode_account(&get_account_data(&account_json).unwrap()).unwrap();
+        assert_eq!(decoded.virtual_quote_reserve, curve.virtual_quote_reserve);
+        assert_eq!(decoded.virtual_base_reserve, curve.virtual_base_reserve);
+        assert!(decode_account::<Config>(&data).is_err());
+    }
+}
@@ -13,6 +13,42 @@ pub mod curve_authority {
     pub const BUMP: u8 = CURVE_AUTHORITY_AND_BUMP.1;
 }
 
+pub mod protocol_stats {
+    use super::*;
+
+    const PROTOCOL_STATS_AND_BUMP: ([u8; 32], u8) = ed25519::derive_program_address(
+        &[crate::constants::seeds::PROTOCOL_STATS_PREFIX],
+        &crate::ID_CONST.to_bytes(),
+    );
+
+    pub const ID: Pubkey = Pubkey::new_from_array(PROTOCOL_STATS_AND_BUMP.0);
+    pub const BUMP: u8 = PROTOCOL_STATS_AND_BUMP.1;
+}
+
+pub mod cashback_config {
+    use super::*;
+
+    const CASHBACK_CONFIG_AND_BUMP: ([u8; 32], u8) = ed25519::derive_program_address(
+        &[crate::constants::seeds::CASHBACK_CONFIG_PREFIX],
+        &crate::ID_CONST.to_bytes(),
+    );
+
+    pub const ID: Pubkey = Pubkey::new_from_array(CASHBACK_CONFIG_AND_BUMP.0);
+    pub const BUMP: u8 = CASHBACK_CONFIG_AND_BUMP.1;
+}
+
+pub mod protocol_authority {
+    use super::*;
+
+    const PROTOCOL_AUTHORITY_AND_BUMP: ([u8; 32], u8) = ed25519::derive_program_address(
+        &[crate::constants::seeds::PROTOCOL_AUTHORITY_PREFIX],
+        &crate::ID_CONST.to_bytes(),
+    );
+
+    pub const ID: Pubkey = Pubkey::new_from_array(PROTOCOL_AUTHORITY_AND_BUMP.0);
+    pub const BUMP: u8 = PROTOCOL_AUTHORITY_AND_BUMP.1;
+}
+
 pub mod cashback {
     use super::*;
 
@@ -28,6 +64,49 @@ pub mod cashback {
     }
 }
 
+pub mod protection {
+    use super::*;
+
+    /// Derive protection PDA for a given curve and user
+    pub fn derive_pda(curve: &Pubkey, user_pubkey: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                crate::constants::seeds::PROTECTION_PREFIX,
+                curve.as_ref(),
+                user_pubkey.as_ref(),
+            ],
+            &crate::ID,
+        )
+    }
+}
+
+pub mod referral {
+    use super::*;
+
+    /// Derive referral account PDA for a given user
+    pub fn derive_pda(user_pubkey: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                crate::constants::seeds::REFERRAL_PREFIX,
+                user_pubkey.as_ref(),
+            ],
+            &crate::ID,
+        )
+    }
+}
+
+pub mod bonus_pool {
+    use super::*;
+
+    /// Derive bonus pool PDA for a given curve
+    pub fn derive_pda(curve: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[crate::constants::seeds::BONUS_POOL_PREFIX, curve.as_ref()],
+            &crate::ID,
+        )
+    }
+}
+
 // Potential optimization on event authority too since anchor internally do Pubkey::find_program_address during runtime.
 
 #[cfg(test)]
@@ -3,10 +3,10 @@ use anchor_lang::solana_program::native_token::LAMPORTS_PER_SOL;
 pub const MIN_SQRT_PRICE: u128 = 4295048016;
 pub const MAX_SQRT_PRICE: u128 = 79226673521066979257578248091;
 
-// Token supply configuration (values include decimals)
-pub const TOKEN_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000; // 1B tokens with 6 decimals
-pub const INITIAL_REAL_TOKEN_RESERVES: u64 = 793_100_000_000_000; // ~793.1M tokens with 6 decimals
-pub const INITIAL_VIRTUAL_TOKEN_RESERVES: u64 = 1_073_000_000_000_000; // ~1.073B tokens with 6 decimals
+// Token supply configuration, base amounts scale with the config's base decimal
+pub const TOKEN_TOTAL_SUPPLY_WITHOUT_DECIMALS: u64 = 1_000_000_000; // 1B tokens
+pub const MIN_BASE_DECIMAL: u8 = 6;
+pub const MAX_BASE_DECIMAL: u8 = 9;
 pub const INITIAL_VIRTUAL_SOL_RESERVES: u64 = 30 * LAMPORTS_PER_SOL; // 30 SOL with 9 decimals
 pub const MAX_VIRTUAL_SOL_RESERVES: u64 = 115_005_359_056;
 
@@ -25,15 +25,170 @@ pub mod cashback {
     pub const CASHBACK_DIAMOND_BPS: u16 = 200; // 0.20% of sol amount
     pub const CASHBACK_CHAMPION_BPS: u16 = 250; // 0.25% of sol amount
 
+    pub const CASHBACK_TIER_COUNT: usize = 7;
+
     // Claim restrictions
-    pub const CASHBACK_CLAIM_COOLDOWN: i64 = 7 * 24 * 60 * 60; // 7 days in seconds
+    pub const MAX_CASHBACK_CLAIM_COOLDOWN: i64 = 30 * 24 * 60 * 60; // 30 days in seconds
     pub const CASHBACK_INACTIVE_PERIOD: i64 = 365 * 24 * 60 * 60; // 365 days in seconds
+
+    // Tier decay
+    pub const CASHBACK_TIER_DECAY_SECONDS: i64 = 30 * 24 * 60 * 60; // 30 days in seconds
+
+    // Rolling volume the tier is refreshed from
+    pub const CASHBACK_VOLUME_BUCKET_SECONDS: i64 = 24 * 60 * 60; // 1 day in seconds
+    pub const CASHBACK_VOLUME_BUCKET_COUNT: usize = 30; // 30 days rolling window
+    /// Upper bound for the per-direction cashback multipliers of a config
+    pub const MAX_CASHBACK_MULTIPLIER_BASIS_POINTS: u32 = 100_000; // 1x the tier cashback
 }
 
 pub mod fee {
     /// Default fee denominator. DO NOT simply update it as it will break logic that depends on it as default value.
     pub const FEE_DENOMINATOR: u64 = 100_000;
     pub const MAX_FEE_BASIS_POINTS: u16 = 10_000;
+    /// Upper bound for the protocol's cut of creator fee claims
+    pub const MAX_CREATOR_FEE_PROTOCOL_BASIS_POINTS: u16 = 50_000; // 50% of creator claims
+    /// Upper bound for the curves of one `claim_protocol_fee_batch`, keeps the transaction under
+    /// the account lock limit with an address lookup table
+    pub const MAX_PROTOCOL_FEE_CLAIM_BATCH_CURVES: usize = 20;
+    /// Upper bound for the creator's share of the DAMM position fees of a migrated curve
+    pub const MAX_CREATOR_LP_FEE_SHARE_BASIS_POINTS: u16 = 50_000; // 50% of the position fees
+    /// Upper bound for the trading fee of a freshly launched curve
+    pub const MAX_LAUNCH_FEE_BASIS_POINTS: u16 = 50_000; // 50% of the amount
+    /// Upper bound for the time the launch fee takes to decay to the trading fee
+    pub const MAX_LAUNCH_FEE_DECAY_SECONDS: u64 = 24 * 60 * 60; // 1 day in seconds
+}
+
+pub mod migration {
+    /// Upper bound for the share of the curve reserves kept out of the pool at migration
+    pub const MAX_MIGRATION_FEE_BASIS_POINTS: u16 = 10_000; // 10% of the reserves
+    /// Upper bound for the cooldown between curve completion and migration
+    pub const MAX_PRE_MIGRATION_COOLDOWN_SECONDS: u64 = 24 * 60 * 60; // 1 day in seconds
+    /// Lowest share of the migration quote threshold a decaying threshold can reach
+    pub const MIN_THRESHOLD_DECAY_FLOOR_BASIS_POINTS: u16 = 50_000; // 50% of the threshold
+    /// Upper bound for the time the migration quote threshold takes to decay to its floor
+    pub const MAX_THRESHOLD_DECAY_SECONDS: u64 = 90 * 24 * 60 * 60; // 90 days in seconds
+    /// Number of equal steps of a stepwise threshold decay
+    pub const THRESHOLD_DECAY_STEPS: u64 = 10;
+    /// Upper bound for the share of the migrated liquidity in the creator's unlocked position
+    pub const MAX_CREATOR_LP_BASIS_POINTS: u16 = 50_000; // 50% of the liquidity
+}
+
+pub mod vesting {
+    /// Upper bound for the cliff plus all the periods of the creator's locked vesting
+    pub const MAX_LOCKED_VESTING_DURATION_SECONDS: u64 = 2 * 365 * 24 * 60 * 60;
+    // 2 years in seconds
+}
+
+pub mod bonus {
+    /// Upper bound for the early buyer window of the creator bonus pool
+    pub const MAX_EARLY_BUYER_WINDOW_SECONDS: u64 = 24 * 60 * 60; // 1 day in seconds
+}
+
+pub mod protection {
+    /// Upper bound for the diamond hands sell lockup after a wallet's buy
+    pub const MAX_MIN_HOLD_SECONDS: u64 = 24 * 60 * 60; // 1 day in seconds
+    /// Upper bound for the anti-snipe window after curve creation
+    pub const MAX_ANTI_SNIPE_WINDOW_SLOTS: u64 = 9_000; // ~1 hour of 400ms slots
+}
+
+pub mod governance {
+    /// Upper bound for the delay between proposing and executing a config change
+    pub const MAX_PARAM_CHANGE_DELAY_SECONDS: u64 = 7 * 24 * 60 * 60; // 7 days in seconds
+}
+
+pub mod insurance {
+    /// Upper bound for the share of protocol fee routed to the insurance fund
+    pub const MAX_INSURANCE_FUND_BASIS_POINTS: u16 = 20_000; // 20% of protocol fee
+}
+
+pub mod partner {
+    /// Upper bound for the share of protocol fee paid to the partner of a curve
+    pub const MAX_PARTNER_FEE_SHARE_BASIS_POINTS: u16 = 50_000; // 50% of protocol fee
+}
+
+pub mod authority {
+    use anchor_lang::{prelude::Pubkey, solana_program::pubkey};
+
+    /// Upgradeable BPF loader, owns the program data holding the upgrade authority of the program
+    pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey =
+        pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+}
+
+pub mod config {
+    /// Layout version of config accounts, legacy configs are migrated to it by `migrate_config`
+    pub const CONFIG_VERSION: u8 = 1;
+    /// Maximum length in bytes of the label of a config
+    pub const MAX_CONFIG_LABEL_LENGTH: usize = 32;
+}
+
+pub mod support_fund {
+    /// Upper bound for the share of the migration fee routed to the support fund
+    pub const MAX_SUPPORT_FUND_BASIS_POINTS: u16 = 50_000; // 50% of migration fee
+}
+
+pub mod limit_order {
+    /// Upper bound for the filler reward of a limit order, relative to its input
+    pub const MAX_FILLER_REWARD_BASIS_POINTS: u16 = 1_000; // 1% of the order
+}
+
+pub mod curve {
+    /// Layout version of bonding curve accounts, curves created on a smaller layout are grown to
+    /// it by `realloc_curve`
+    pub const CURVE_VERSION: u8 = 8;
+    /// Seconds holders can still sell after the trading end of a curve, buys are closed
+    pub const TRADING_END_SELL_GRACE_SECONDS: u64 = 259_200; // 3 days
+    /// Seconds a completed dual listed curve waits for the other curve of the listing to complete
+    /// before it can migrate alone
+    pub const DUAL_LISTING_GRADUATION_WINDOW_SECONDS: u64 = 604_800; // 7 days
+    /// Upper bound for how far after creation a curve activated by timestamp can open
+    pub const MAX_ACTIVATION_DELAY_SECONDS: u64 = 604_800; // 7 days
+    /// Upper bound for how far after creation a curve activated by slot can open
+    pub const MAX_ACTIVATION_DELAY_SLOTS: u64 = 1_512_000; // ~7 days of 400ms slots
+}
+
+pub mod raffle {
+    use anchor_lang::{prelude::Pubkey, solana_program::pubkey};
+
+    /// Switchboard on-demand program owning the randomness accounts raffles are drawn from
+    #[cfg(not(feature = "devnet"))]
+    pub const RANDOMNESS_PROGRAM_ID: Pubkey =
+        pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+    #[cfg(feature = "devnet")]
+    pub const RANDOMNESS_PROGRAM_ID: Pubkey =
+        pubkey!("Aio4gaXjXzJNVLtzwMNVmdD2uorefLm4XzyF76o6SK5D");
+    /// Maximum time deposits of a raffle stay open
+    pub const MAX_RAFFLE_DEPOSIT_SECONDS: u64 = 604_800; // 7 days
+    /// Time after the ticket sales close an oversubscribed raffle has to be drawn in, it is
+    /// cancelled and refunded after it
+    pub const RAFFLE_DRAW_TIMEOUT_SECONDS: u64 = 3_600; // 1 hour
+    /// Bond the creator puts up when committing the randomness, in bps of the raffle's largest
+    /// allocation. Returned on the draw, shared by the ticket holders when the draw times out
+    pub const RAFFLE_RANDOMNESS_BOND_BASIS_POINTS: u64 = 20_000; // 20% of the allocation quote
+}
+
+pub mod curve_metadata {
+    /// Maximum length in bytes of the extended metadata URI, the token metadata URI is capped at
+    /// `MAX_URI_LENGTH` by the metadata program
+    pub const MAX_EXTENDED_URI_LENGTH: usize = 512;
+    /// Maximum length in bytes of the partner defined extension data
+    pub const MAX_METADATA_EXTENSION_LENGTH: usize = 256;
+}
+
+pub mod swap_budget {
+    /// Compute units `swap` may use, checked in `swap-budget-guard` builds. Keeps headroom under
+    /// the 200k default per instruction limit for CPI callers and priority fee instructions
+    pub const MAX_SWAP_COMPUTE_UNITS: u64 = 150_000;
+    /// Accounts `swap` may take, remaining accounts included. Every account of the instruction
+    /// with all the optional ones passed, a new account has to raise this on purpose
+    pub const MAX_SWAP_ACCOUNTS: usize = 25;
+}
+
+pub mod quote_mint {
+    use anchor_lang::prelude::Pubkey;
+
+    /// Token-2022 quote mints that are trusted despite carrying a permanent delegate, a mint
+    /// close authority or a transfer hook. Empty until such a mint has been vetted.
+    pub const WHITELISTED_QUOTE_MINTS: &[Pubkey] = &[];
 }
 
 pub mod seeds {
@@ -42,4 +197,30 @@ pub mod seeds {
     pub const TOKEN_VAULT_PREFIX: &[u8] = b"token_vault";
     pub const CASHBACK_PREFIX: &[u8] = b"cashback";
     pub const CURVE_AUTHORITY_PREFIX: &[u8] = b"curve_authority";
+    pub const PROTECTION_PREFIX: &[u8] = b"protection";
+    pub const BONUS_POOL_PREFIX: &[u8] = b"bonus_pool";
+    pub const CONFIG_CHANGE_PREFIX: &[u8] = b"config_change";
+    pub const INSURANCE_FUND_PREFIX: &[u8] = b"insurance_fund";
+    pub const DUAL_LISTING_PREFIX: &[u8] = b"dual_listing";
+    pub const MIGRATION_DELEGATE_PREFIX: &[u8] = b"migration_delegate";
+    pub const MIGRATION_PROGRESS_PREFIX: &[u8] = b"migration_progress";
+    pub const REFERRAL_PREFIX: &[u8] = b"referral";
+    pub const REWARD_VAULT_PREFIX: &[u8] = b"reward_vault";
+    pub const REWARD_CLAIM_PREFIX: &[u8] = b"reward_claim";
+    pub const SUPPORT_FUND_PREFIX: &[u8] = b"support_fund";
+    pub const CONFIG_LABEL_PREFIX: &[u8] = b"config_label";
+    pub const LIMIT_ORDER_PREFIX: &[u8] = b"limit_order";
+    pub const PROTOCOL_STATS_PREFIX: &[u8] = b"protocol_stats";
+    pub const CONFIG_STATS_PREFIX: &[u8] = b"config_stats";
+    pub const TEST_HOOKS_PREFIX: &[u8] = b"test_hooks";
+    pub const CURVE_METADATA_PREFIX: &[u8] = b"curve_metadata";
+    pub const CASHBACK_CONFIG_PREFIX: &[u8] = b"cashback_config";
+    pub const MINT_INDEX_PREFIX: &[u8] = b"mint_index";
+    pub const WHITELIST_ENTRY_PREFIX: &[u8] = b"whitelist_entry";
+    pub const CURVE_ARCHIVE_PREFIX: &[u8] = b"curve_archive";
+    pub const RAFFLE_PREFIX: &[u8] = b"raffle";
+    pub const RAFFLE_TICKET_PREFIX: &[u8] = b"raffle_ticket";
+    pub const PARTNER_PREFIX: &[u8] = b"partner";
+    pub const PARTNER_EARNINGS_PREFIX: &[u8] = b"partner_earnings";
+    pub const PROTOCOL_AUTHORITY_PREFIX: &[u8] = b"protocol_authority";
 }
@@ -30,6 +30,21 @@ pub enum AmmError {
     #[msg("Invalid cashback tier")]
     InvalidCashbackTier,
 
+    #[msg("Cashback tier is already the lowest")]
+    CashbackTierAtMinimum,
+
+    #[msg("Relayer tip exceeds the claim or has no relayer account")]
+    InvalidRelayerTip,
+
+    #[msg("Invalid cashback mode")]
+    InvalidCashbackMode,
+
+    #[msg("Native cashback needs the native mode and a native SOL swap of a WSOL quoted curve")]
+    NativeCashbackUnavailable,
+
+    #[msg("Tier update sequence number isn't above the last one")]
+    StaleTierUpdate,
+
     // Math and type conversion errors
     #[msg("Math operation overflow")]
     MathOverflow,
@@ -37,6 +52,15 @@ pub enum AmmError {
     #[msg("Type cast error")]
     TypeCastFailed,
 
+    #[msg("Liquidity does not fit in u128")]
+    LiquidityOverflow,
+
+    #[msg("Liquidity rounds down to zero")]
+    LiquidityPrecisionLoss,
+
+    #[msg("Sqrt price is outside of the pool price range")]
+    SqrtPriceOutOfRange,
+
     // Swap and trading errors
     #[msg("Amount is zero")]
     AmountIsZero,
@@ -53,6 +77,45 @@ pub enum AmmError {
     #[msg("Swap amount is over a threshold")]
     SwapAmountIsOverAThreshold,
 
+    #[msg("Price impact of the swap is over the limit")]
+    PriceImpactTooHigh,
+
+    #[msg("Trading is paused in this direction")]
+    TradingPaused,
+
+    #[msg("Invalid trading pause")]
+    InvalidTradingPause,
+
+    #[msg("Trading end timestamp must be in the future")]
+    InvalidTradingEndTimestamp,
+
+    #[msg("Buys are closed, the curve reached its trading end")]
+    TradingEnded,
+
+    #[msg("Trading is closed, the sell grace period after the curve's trading end is over")]
+    TradingClosed,
+
+    #[msg("Refunds open once the sell grace period after the curve's trading end is over")]
+    RefundNotOpen,
+
+    #[msg("Refund exceeds the base sold out of the curve")]
+    RefundExceedsSoldBase,
+
+    #[msg("Dual listed curves share their base mint and can't be refunded")]
+    DualListedRefund,
+
+    #[msg("Curve isn't activated yet")]
+    CurveNotActivated,
+
+    #[msg("Invalid activation type")]
+    InvalidActivationType,
+
+    #[msg("Activation point must be in the future, within the maximum activation delay")]
+    InvalidActivationPoint,
+
+    #[msg("Invalid swap mode")]
+    InvalidSwapMode,
+
     #[msg("Not enough liquidity")]
     NotEnoughLiquidity,
 
@@ -102,6 +165,29 @@ pub enum AmmError {
     #[msg("Missing pool config in remaining account")]
     MissingPoolConfigInRemainingAccount,
 
+    #[msg("Pre-migration cooldown has not passed yet")]
+    MigrationCooldownNotMet,
+
+    #[msg("Second position accounts are required by the config")]
+    SecondPositionRequired,
+
+    #[msg("Token account doesn't belong to the fee claimer of the config")]
+    InvalidFeeClaimerTokenAccount,
+
+    /// Governance errors
+    #[msg("Config change timelock has not passed yet")]
+    ConfigChangeTimelockNotMet,
+
+    #[msg("Insurance payout timelock has not passed yet")]
+    InsurancePayoutTimelockNotMet,
+
+    /// Protection errors
+    #[msg("Protection account is required by the curve")]
+    ProtectionAccountRequired,
+
+    #[msg("Sell is locked until the minimum hold time after the last buy has passed")]
+    SellLockupActive,
+
     /// Token 2022 specific errors
     #[msg("Unsupport native mint token 2022")]
     UnsupportNativeMintToken2022,
@@ -110,7 +196,237 @@ pub enum AmmError {
     #[msg("Nothing to claim")]
     NothingToClaim,
 
-    /// Invalid fee_type, only supports 0, 1, and 2.
+    #[msg(
+        "Protocol fee claim batch has to pass curve and quote vault pairs, up to the batch limit"
+    )]
+    InvalidProtocolFeeClaimBatch,
+
+    /// Dual listing errors
+    #[msg("Curve has already traded")]
+    CurveAlreadyTraded,
+
+    #[msg("Configs can't share a base mint")]
+    InvalidDualListingConfig,
+
+    #[msg("Dual listing supply split leaves a curve below its migration threshold")]
+    InvalidDualListingSupply,
+
+    #[msg("Dual listing account is required by the curve")]
+    DualListingRequired,
+
+    #[msg("Dual listed curve waits for the other curve of the listing to complete")]
+    DualListingGraduationPending,
+
+    #[msg("Invalid creator fee transfer mode")]
+    InvalidCreatorFeeTransferMode,
+
+    #[msg("Params hash doesn't match the config's curve template")]
+    InvalidCurveTemplateHash,
+
+    #[msg("Creator LP fee share exceeds the bound of the config")]
+    InvalidCreatorLpFeeShare,
+
+    #[msg("Curve takes no creator share of the DAMM position fees")]
+    NoCreatorLpFeeShare,
+
+    #[msg("Creator isn't whitelisted on the config")]
+    CreatorNotWhitelisted,
+
+    /// Migration delegation errors
+    #[msg("Migration delegation expiry must be in the future")]
+    InvalidMigrationDelegationExpiry,
+
+    #[msg("Migration delegation has expired")]
+    MigrationDelegationExpired,
+
+    #[msg("Previous migration step has not been completed")]
+    MigrationStepNotReady,
+
+    /// Referral errors
+    #[msg("A user can't be part of its own referrer chain")]
+    SelfReferral,
+
+    #[msg("Referral token accounts don't match the user's referrer chain")]
+    InvalidReferralTokenAccount,
+
+    /// Reward vault errors
+    #[msg("Reward merkle proof doesn't match the distribution")]
+    InvalidRewardProof,
+
+    #[msg("Reward distribution can't change once holders started claiming")]
+    RewardDistributionStarted,
+
+    #[msg("Reward vault is not funded enough to pay the claim")]
+    InsufficientRewardFunds,
+
+    /// Swap budget errors
+    #[msg("Swap exceeded its compute unit or account budget")]
+    SwapBudgetExceeded,
+
+    /// Token to token swap errors
+    #[msg("Buy leg of the route is capped by the migration threshold of its curve")]
+    RoutedBuyCapped,
+
+    /// Anti-snipe errors
+    #[msg("Buys of the wallet exceed the anti-snipe cap of the curve's launch window")]
+    AntiSnipeBuyCapExceeded,
+
+    /// Migration status errors
+    #[msg("Curve has no migration status transition to advance")]
+    MigrationStatusNotAdvanceable,
+
+    /// Leftover errors
+    #[msg("Leftover token account doesn't belong to the leftover receiver of the config")]
+    InvalidLeftoverReceiver,
+
+    #[msg("Leftover of the config goes to its leftover receiver, it can't be burned")]
+    LeftoverHasReceiver,
+
+    #[msg("Base vault holds less than the amount to burn")]
+    InsufficientLeftover,
+
+    /// Archive errors
+    #[msg("Curve still owes fees, escrows or vault tokens, it can't be archived")]
+    CurveNotSettled,
+
+    /// Raffle errors
+    #[msg("Invalid raffle parameters")]
+    InvalidRaffleParameters,
+
+    #[msg("A raffle can only launch a curve that hasn't traded yet")]
+    RaffleCurveAlreadyTraded,
+
+    #[msg("Raffle allocation would complete the curve")]
+    RaffleCompletesCurve,
+
+    #[msg("Raffle ticket sales are closed")]
+    RaffleDepositClosed,
+
+    #[msg("Raffle ticket sales are still open")]
+    RaffleDepositOpen,
+
+    #[msg("Ticket count exceeds the raffle's tickets per wallet")]
+    RaffleTicketLimitExceeded,
+
+    #[msg("Raffle is already drawn")]
+    RaffleAlreadyDrawn,
+
+    #[msg("Raffle isn't drawn yet")]
+    RaffleNotDrawn,
+
+    #[msg("Invalid randomness account")]
+    InvalidRandomnessAccount,
+
+    #[msg("Randomness account has to be committed in the slot after its seed slot")]
+    RandomnessNotFresh,
+
+    #[msg("Raffle already waits for the reveal of a committed randomness account")]
+    RandomnessAlreadyCommitted,
+
+    #[msg("Committed randomness isn't revealed yet")]
+    RandomnessNotRevealed,
+
+    /// Partner errors
+    #[msg("Partner fee share is above its bound")]
+    InvalidPartnerFeeShare,
+
+    #[msg("Partner isn't approved in the partner registry")]
+    PartnerNotApproved,
+
+    #[msg("Curves with a partner need its partner earnings accounts to claim the protocol fee")]
+    PartnerEarningsRequired,
+
+    #[msg("Configs with an insurance fund share need its insurance fund accounts to claim the protocol fee")]
+    InsuranceFundRequired,
+
+    /// Protocol authority errors
+    #[msg("Invalid authority type")]
+    InvalidAuthorityType,
+
+    /// Swap expiry errors
+    #[msg("Swap landed after its valid until slot")]
+    SwapExpired,
+
+    /// Initial buy errors
+    #[msg("Initial buy needs the creator's quote and base token accounts")]
+    InitialBuyAccountsRequired,
+
+    #[msg("Initial buy would complete the curve")]
+    InitialBuyCompletesCurve,
+
+    #[msg("Initial buy is only supported for SPL token curves")]
+    InitialBuyNotSupported,
+
+    /// Support fund errors
+    #[msg("Support fund accounts are required by the config")]
+    SupportFundRequired,
+
+    #[msg("Support fund doesn't hold enough quote for the deployment")]
+    InsufficientSupportFund,
+
+    /// Config versioning errors
+    #[msg("Config is already on the current layout version")]
+    ConfigAlreadyMigrated,
+
+    #[msg("Bonding curve is already on the current layout version")]
+    CurveAlreadyReallocated,
+
+    /// Config label errors
+    #[msg("Invalid config label: must be 1-32 bytes")]
+    InvalidConfigLabel,
+
+    /// Curve metadata errors
+    #[msg("Invalid extended metadata URI: must be 1-512 bytes")]
+    InvalidExtendedUri,
+
+    #[msg("Metadata extension exceeds 256 bytes")]
+    MetadataExtensionTooLong,
+
+    #[msg("Curve isn't waiting for its metadata")]
+    CurveMetadataNotPending,
+
+    /// Pause errors
+    #[msg("Config is paused")]
+    ConfigPaused,
+
+    #[msg("Curve is paused")]
+    CurvePaused,
+
+    /// Test hook errors
+    #[msg("Invalid fault point")]
+    InvalidFaultPoint,
+
+    #[msg("Fault injected by the test hooks")]
+    InjectedFault,
+
+    /// Cashback config errors
+    #[msg("Claim cooldowns must be at most 30 days and not increase with the tier")]
+    InvalidClaimCooldown,
+
+    #[msg("Tier volume thresholds must start at zero and increase with the tier")]
+    InvalidTierVolumeThresholds,
+
+    #[msg("Fee discounts must be bounded and never decrease with the tier")]
+    InvalidFeeDiscount,
+
+    /// Token program errors
+    #[msg("Token program doesn't own the mint it is passed for")]
+    InvalidTokenProgram,
+
+    /// Limit order errors
+    #[msg("Invalid limit order parameters")]
+    InvalidLimitOrder,
+
+    #[msg("Limit order filler reward is above the maximum")]
+    FillerRewardTooHigh,
+
+    #[msg("Curve price hasn't reached the limit order target")]
+    LimitOrderPriceNotReached,
+
+    #[msg("Limit order buy is capped at the migration threshold")]
+    LimitOrderBuyCapped,
+
+    /// Invalid fee_type, only supports 0 and 1.
     #[msg("Invalid fee type")]
     InvalidFeeType,
 
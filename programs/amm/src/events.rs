@@ -1,10 +1,19 @@
-use crate::{states::bonding_curve::SwapResult, SwapParameters};
+use crate::{
+    params::config::{ConfigChangeParameters, LockedVestingParams},
+    states::bonding_curve::SwapResult,
+    VersionedSwapParameters,
+};
 use anchor_lang::prelude::*;
 
 /// Create config
 #[event]
 pub struct EvtCreateConfig {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
     pub config: Pubkey,
+    /// index of the config among the PDA configs of its quote mint
+    pub index: u16,
+    pub version: u8,
 
     /* Token configurations */
     pub base_token_flag: u8,
@@ -20,6 +29,16 @@ pub struct EvtCreateConfig {
     pub l3_referral_fee_basis_points: u16,
     pub creator_fee_basis_points: u16,
     pub migration_fee_basis_points: u16,
+    pub insurance_fund_basis_points: u16,
+    pub creator_fee_protocol_basis_points: u16,
+    pub support_fund_basis_points: u16,
+    pub max_creator_lp_fee_share_basis_points: u16,
+    pub creator_lp_basis_points: u16,
+    pub buy_cashback_multiplier_basis_points: u32,
+    pub sell_cashback_multiplier_basis_points: u32,
+    pub fee_collection_mode: u8,
+    pub migration_fee_mode: u8,
+    pub fee_remainder_mode: u8,
     pub fee_claimer: Pubkey,
 
     /* Price configurations */
@@ -27,10 +46,42 @@ pub struct EvtCreateConfig {
     pub migration_quote_threshold: u64,
     pub initial_virtual_quote_reserve: u64,
     pub initial_virtual_base_reserve: u64,
+
+    /* Migration configurations */
+    pub pre_migration_cooldown_seconds: u64,
+    pub permissionless_migration: u8,
+    pub migration_crank_reward: u64,
+    pub min_creator_fee_amount: u64,
+    pub migration_token_order: u8,
+    pub routed_swap_fee_mode: u8,
+    pub damm_config: Pubkey,
+    pub leftover_receiver: Pubkey,
+    pub threshold_decay_mode: u8,
+    pub threshold_decay_floor_basis_points: u16,
+    pub threshold_decay_seconds: u64,
+
+    /* Bonus configurations */
+    pub early_buyer_window_seconds: u64,
+
+    /* Protection configurations */
+    pub min_hold_seconds: u64,
+    pub anti_snipe_window_slots: u64,
+    pub max_buy_amount_first_window: u64,
+    pub launch_fee_basis_points: u16,
+    pub launch_fee_periods: u16,
+    pub launch_fee_decay_seconds: u64,
+
+    /* Governance configurations */
+    pub param_change_delay_seconds: u64,
+
+    /* Vesting configurations */
+    pub locked_vesting: LockedVestingParams,
 }
 
 #[event]
 pub struct EvtInitializeCurve {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
     pub curve: Pubkey,
     pub config: Pubkey,
     pub creator: Pubkey,
@@ -42,32 +93,135 @@ pub struct EvtInitializeCurve {
     pub uri: String,
     pub initial_virtual_quote_reserve: u64,
     pub initial_virtual_base_reserve: u64,
+    pub min_hold_seconds: u64,
+    /// time buys close, 0 for curves that trade until migration
+    pub trading_end_timestamp: u64,
+    /// unit of the activation point (0: Timestamp, 1: Slot)
+    pub activation_type: u8,
+    /// timestamp or slot trading opens at, 0 for curves that trade from creation
+    pub activation_point: u64,
+    /// partner frontend the curve was created through, all zeros without one
+    pub partner: Pubkey,
+    /// partner's share of the protocol fee of the curve
+    pub partner_fee_share_basis_points: u16,
+}
+
+#[event]
+pub struct EvtCreateDualListing {
+    /// sequence number of this event within the secondary config
+    pub event_sequence: u64,
+    pub dual_listing: Pubkey,
+    pub base_mint: Pubkey,
+    pub primary_curve: Pubkey,
+    pub primary_config: Pubkey,
+    pub secondary_curve: Pubkey,
+    pub secondary_config: Pubkey,
+    pub secondary_quote_mint: Pubkey,
+    pub primary_base_supply: u64,
+    pub secondary_base_supply: u64,
+    pub initial_virtual_quote_reserve: u64,
+    pub initial_virtual_base_reserve: u64,
+    pub min_hold_seconds: u64,
 }
 
 #[event]
 pub struct EvtSwap {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
     pub curve: Pubkey,
+    pub config: Pubkey,
+    /// curve creator, so volume can be attributed without joining `EvtInitializeCurve`
+    pub creator: Pubkey,
     pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
     pub trade_direction: u8,
     pub has_referral: bool,
-    pub params: SwapParameters,
+    pub params: VersionedSwapParameters,
     pub swap_result: SwapResult,
+    /// virtual base reserve of the curve after the swap
     pub virtual_base_reserve: u64,
+    /// virtual quote reserve of the curve after the swap
     pub virtual_quote_reserve: u64,
+    /// base token balance of the trader after the swap
     pub remaining_tokens: u64,
+    /// spot price of the virtual reserves after the swap, quote per base as a Q64.64 of raw
+    /// amounts
+    pub virtual_price: u128,
+    /// base left to buy before the curve reaches its migration base threshold
+    pub base_until_migration: u64,
+    /// approximate number of unique holders after the swap
+    pub holder_count: u64,
+    /// owner of the cashback account receiving `swap_result.cashback_fee`, none without cashback
+    pub cashback_owner: Option<Pubkey>,
+    /// cashback token account balance after the swap, ie: the total claimable cashback. The
+    /// lamports above rent of the cashback account in the native cashback mode
+    pub claimable_cashback: Option<u64>,
+}
+
+/// Summary of a token to token swap, each leg also emits its own `EvtSwap`
+#[event]
+pub struct EvtSwapTokenToToken {
+    /// sequence number of this event within the config of curve b
+    pub event_sequence: u64,
+    pub curve_a: Pubkey,
+    pub curve_b: Pubkey,
+    pub trader: Pubkey,
+    /// base amount of curve a sold
+    pub amount_in: u64,
+    /// quote moved from curve a to curve b
+    pub quote_amount: u64,
+    /// base amount of curve b bought
+    pub amount_out: u64,
+    /// `RoutedSwapFeeMode` of the buy leg
+    pub routed_swap_fee_mode: u8,
 }
 
 #[event]
 pub struct EvtCurveComplete {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
     pub curve: Pubkey,
     pub config: Pubkey,
     pub base_mint: Pubkey,
     pub base_reserve: u64,
     pub quote_reserve: u64,
+    /// migration quote threshold in effect when the curve completed, lower than the config's
+    /// with threshold decay
+    pub effective_migration_quote_threshold: u64,
+}
+
+/// Result of `verify_curve_invariants`
+#[event]
+pub struct EvtVerifyCurveInvariants {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    /// whether every invariant below holds
+    pub passed: bool,
+    /// virtual quote reserve * virtual base reserve
+    pub k: u128,
+    /// k of the initial virtual reserves of the config, rounding in favor of the curve keeps k
+    /// from ever falling below it
+    pub initial_k: u128,
+    pub k_holds: bool,
+    /// virtual quote reserve = quote reserve + initial virtual quote reserve
+    pub quote_reserve_offset_holds: bool,
+    /// the base vault holds at least the base reserve, always true once migrated
+    pub base_vault_covers_reserve: bool,
+    /// the quote vault holds at least the quote reserve and the unclaimed protocol and creator
+    /// fees, always true once migrated
+    pub quote_vault_covers_reserve_and_fees: bool,
+    /// the unclaimed creator fee doesn't exceed the creator fee accrued over the curve's life
+    pub creator_fee_within_total: bool,
+    pub base_vault_balance: u64,
+    pub quote_vault_balance: u64,
 }
 
 #[event]
 pub struct EvtMigrateDammV2 {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
     pub curve: Pubkey,
     pub config: Pubkey,
     pub pool: Pubkey,
@@ -76,20 +230,250 @@ pub struct EvtMigrateDammV2 {
     pub deposited_base_amount: u64,
     pub deposited_quote_amount: u64,
     pub initial_liquidity: u128,
+    /// sqrt price of token b in token a
     pub sqrt_price: u128,
+    /// whether the base mint is token a of the pool
+    pub is_base_token_a: bool,
+    pub damm_config: Pubkey,
+    /// whether the admin migrated with a DAMM config other than the approved one
+    pub damm_config_overridden: bool,
+    /// unlocked position of the curve creator, none when the config gives it no liquidity
+    pub second_position: Option<Pubkey>,
+    pub creator_liquidity: u128,
+    /// migration fee paid to the fee claimer, zero for a side whose fee stayed in the vault
+    pub migration_quote_fee: u64,
+    pub migration_base_fee: u64,
+    /// quote migration fee left in the vault for `claim_migration_fee`
+    pub migration_fee_collected: u64,
+}
+
+#[event]
+pub struct EvtMigrationCrankReward {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    /// signer of the permissionless migration
+    pub cranker: Pubkey,
+    /// quote paid out of the curve's protocol fee
+    pub amount: u64,
+}
+
+#[event]
+pub struct EvtCreatorFeeTopUp {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    pub creator: Pubkey,
+    /// creator fee accrued by trading before the top up
+    pub accrued_creator_fee: u64,
+    /// quote moved from the migration fee to the creator fee
+    pub top_up_amount: u64,
+}
+
+#[event]
+pub struct EvtBurnCurveBaseTokens {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    pub base_mint: Pubkey,
+    /// base amount burned from the vault
+    pub amount: u64,
+    /// supply of the base mint after the burn
+    pub supply: u64,
+}
+
+#[event]
+pub struct EvtArchiveCurve {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    pub base_mint: Pubkey,
+    pub curve_archive: Pubkey,
+    /// quote left in the vault that nobody was owed, sent to the fee claimer
+    pub swept_quote_amount: u64,
+    /// rent of the curve and its vaults returned to the creator
+    pub reclaimed_lamports: u64,
+}
+
+#[event]
+pub struct EvtWithdrawLeftover {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    pub base_mint: Pubkey,
+    /// leftover receiver of the config, none if the leftover was burned
+    pub receiver: Option<Pubkey>,
+    /// base amount withdrawn from the vault
+    pub amount: u64,
+}
+
+#[event]
+pub struct EvtFundSupport {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    /// quote moved from the migration fee to the support fund
+    pub amount: u64,
+}
+
+#[event]
+pub struct EvtDeploySupportFund {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    pub pool: Pubkey,
+    /// quote of the fund swapped on the pool
+    pub quote_amount: u64,
+    /// base bought back into the curve's base vault
+    pub base_amount: u64,
+    /// quote left in the fund
+    pub remaining_quote_amount: u64,
+}
+
+#[event]
+pub struct EvtClaimPositionFee {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    pub pool: Pubkey,
+    /// receiver of the creator share, the creator unless it set a recipient
+    pub creator_fee_recipient: Pubkey,
+    pub creator_base_amount: u64,
+    pub creator_quote_amount: u64,
+    /// protocol share paid to the fee claimer, it keeps the rounding
+    pub protocol_base_amount: u64,
+    pub protocol_quote_amount: u64,
+}
+
+/// Best-effort trace of a migration crank. Logged with `emit!` instead of `emit_cpi!` so it
+/// survives in the logs of a failed transaction, hence it carries no event sequence.
+#[event]
+pub struct EvtMigrationAttempt {
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    /// `MigrationStage` that was reached or failed
+    pub stage: u8,
+    /// error code of the failed check, none if the stage was merely reached
+    pub error_code: Option<u64>,
+}
+
+/// Compute units around a migration CPI, logged when the config has the CPI telemetry on. Logged
+/// with `emit!` like `EvtMigrationAttempt`, so the event before a CPI that runs out of compute
+/// units survives in the logs
+#[event]
+pub struct EvtCpiTelemetry {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    /// `MigrationStage` of the CPI
+    pub stage: u8,
+    /// accounts passed to the CPI
+    pub account_count: u8,
+    /// compute units left before the CPI
+    pub compute_units_remaining: u64,
+    /// compute units the CPI consumed, none in the event logged before it
+    pub compute_units_consumed: Option<u64>,
+}
+
+#[event]
+pub struct EvtClaimMigrationFee {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    pub fee_claimer: Pubkey,
+    /// quote migration fee claimed
+    pub amount: u64,
+}
+
+#[event]
+pub struct EvtClaimMemeFee {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    /// fee type admin the meme fee is paid to
+    pub claimer: Pubkey,
+    /// meme fee claimed
+    pub amount: u64,
+    /// creator fee left to claim on the curve, untouched by the claim
+    pub remaining_creator_fee: u64,
 }
 
 #[event]
 pub struct EvtClaimTradingFee {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
     pub curve: Pubkey,
     pub quote_token_claim_amount: u64,
+    /// part of the protocol fee routed to the insurance fund
+    pub insurance_fund_amount: u64,
+    /// part of the protocol fee paid to the partner of the curve
+    pub partner_fee_amount: u64,
+    /// quote vault balance of the curve after the claim
+    pub quote_vault_balance: u64,
+    /// protocol fee left to claim on the curve
+    pub remaining_protocol_fee: u64,
+    /// creator fee left to claim on the curve
+    pub remaining_creator_fee: u64,
 }
 
 #[event]
 pub struct EvtClaimCreatorTradingFee {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
     pub curve: Pubkey,
     pub creator: Pubkey,
+    /// wallet the claim is paid to, the creator unless it set a recipient
+    pub recipient: Pubkey,
+    /// amount received by the recipient
     pub quote_token_claim_amount: u64,
+    /// protocol's cut of the claim, sent to the fee claimer
+    pub protocol_fee_amount: u64,
+    /// quote vault balance of the curve after the claim
+    pub quote_vault_balance: u64,
+    /// protocol fee left to claim on the curve
+    pub remaining_protocol_fee: u64,
+    /// creator fee left to claim on the curve
+    pub remaining_creator_fee: u64,
+}
+
+#[event]
+pub struct EvtSetCreatorFeeRecipient {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub creator: Pubkey,
+    /// default pubkey when the creator was the recipient
+    pub old_recipient: Pubkey,
+    /// default pubkey when the fee goes back to the creator
+    pub new_recipient: Pubkey,
+}
+
+#[event]
+pub struct EvtTransferCreator {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub old_creator: Pubkey,
+    pub new_creator: Pubkey,
+    /// what happened to the accrued creator fee (0: moved with the role, 1: claimed to the old creator)
+    pub creator_fee_transfer_mode: u8,
+    /// accrued creator fee left on the curve for the new creator
+    pub moved_creator_fee: u64,
+    /// amount paid out to the old creator
+    pub claimed_creator_fee: u64,
+    /// protocol's cut of the payout to the old creator, sent to the fee claimer
+    pub protocol_fee_amount: u64,
 }
 
 #[event]
@@ -98,10 +482,41 @@ pub struct EvtCreateCashback {
     pub tier: u8,
 }
 
+#[event]
+pub struct EvtCloseCashback {
+    pub owner: Pubkey,
+    /// quote mint of the closed cashback vault
+    pub quote_mint: Pubkey,
+    /// vault balance swept to the owner before the vault was closed
+    pub swept_amount: u64,
+    /// lamports of the native cashback mode closed to the owner with the cashback account
+    pub native_cashback: u64,
+}
+
+#[event]
+pub struct EvtSetCashbackMode {
+    pub owner: Pubkey,
+    pub cashback_mode: u8,
+}
+
+#[event]
+pub struct EvtSetReferrer {
+    pub user: Pubkey,
+    pub l1_referrer: Pubkey,
+    pub l2_referrer: Pubkey,
+    pub l3_referrer: Pubkey,
+}
+
 #[event]
 pub struct EvtClaimCashback {
     pub owner: Pubkey,
-    pub wsol_claim_amount: u64,
+    /// quote mint of the claimed cashback vault
+    pub quote_mint: Pubkey,
+    pub claim_amount: u64,
+    /// part of the claim paid to the relayer that submitted the transaction
+    pub relayer_tip: u64,
+    /// balance of the claimed cashback vault after the claim
+    pub vault_balance: u64,
 }
 
 #[event]
@@ -109,4 +524,511 @@ pub struct EvtUpdateCashbackTier {
     pub owner: Pubkey,
     pub old_tier: u8,
     pub new_tier: u8,
+    /// sequence number of the update, none for unsequenced updates
+    pub update_seq: Option<u64>,
+}
+
+#[event]
+pub struct EvtInitializeAuthority {
+    pub global_authority: Pubkey,
+    pub migration_authority: Pubkey,
+    pub fee_type_authority: Pubkey,
+}
+
+#[event]
+pub struct EvtTransferAuthority {
+    /// role being transferred (0: Global, 1: Migration, 2: FeeType)
+    pub authority_type: u8,
+    pub authority: Pubkey,
+    /// key that has to accept the role, default when the transfer is cancelled
+    pub pending_authority: Pubkey,
+}
+
+#[event]
+pub struct EvtAcceptAuthority {
+    /// role being transferred (0: Global, 1: Migration, 2: FeeType)
+    pub authority_type: u8,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct EvtSetCashbackConfig {
+    /// seconds between two claims, indexed by cashback tier
+    pub claim_cooldown_seconds: [i64; 7],
+    /// rolling quote volume needed for each tier, indexed by cashback tier
+    pub tier_volume_thresholds: [u64; 7],
+    /// trading fee discount of each tier, indexed by cashback tier
+    pub fee_discount_basis_points: [u16; 7],
+}
+
+#[event]
+pub struct EvtReallocCashbackConfig {
+    /// account data length after the realloc
+    pub size: u64,
+}
+
+#[event]
+pub struct EvtRefreshCashbackTier {
+    pub owner: Pubkey,
+    pub old_tier: u8,
+    pub new_tier: u8,
+    /// quote traded over the rolling window the tier was computed from
+    pub rolling_volume: u64,
+}
+
+#[event]
+pub struct EvtDecayCashbackTier {
+    pub owner: Pubkey,
+    pub old_tier: u8,
+    pub new_tier: u8,
+    pub last_trade_timestamp: i64,
+}
+
+#[event]
+pub struct EvtCreateProtection {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct EvtDepositBonusPool {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub total_deposit: u64,
+}
+
+#[event]
+pub struct EvtClaimBonus {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub user: Pubkey,
+    pub early_buy_quote_amount: u64,
+    pub bonus_amount: u64,
+}
+
+#[event]
+pub struct EvtWithdrawBonusPool {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct EvtCreateRaffle {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub raffle: Pubkey,
+    pub creator: Pubkey,
+    pub ticket_price: u64,
+    pub winning_ticket_count: u64,
+    pub max_tickets_per_wallet: u64,
+    pub deposit_end_timestamp: u64,
+}
+
+#[event]
+pub struct EvtBuyRaffleTickets {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub raffle: Pubkey,
+    pub raffle_ticket: Pubkey,
+    pub owner: Pubkey,
+    pub first_ticket: u64,
+    pub ticket_count: u64,
+    pub total_tickets: u64,
+}
+
+#[event]
+pub struct EvtCommitRaffleRandomness {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub raffle: Pubkey,
+    pub randomness_account: Pubkey,
+    pub seed_slot: u64,
+    /// quote the creator put up, refunded to the ticket holders if the draw times out
+    pub randomness_bond: u64,
+}
+
+#[event]
+pub struct EvtDrawRaffle {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub raffle: Pubkey,
+    pub total_tickets: u64,
+    pub drawn_ticket_count: u64,
+    pub winning_offset: u64,
+    /// buy of the winning tickets' allocation, none when no ticket was sold
+    pub swap_result: Option<SwapResult>,
+    pub virtual_base_reserve: u64,
+    pub virtual_quote_reserve: u64,
+}
+
+#[event]
+pub struct EvtClaimRaffle {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub raffle: Pubkey,
+    pub owner: Pubkey,
+    pub won_ticket_count: u64,
+    pub base_amount: u64,
+    pub refund_amount: u64,
+}
+
+#[event]
+pub struct EvtFundRewardVault {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub reward_mint: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub total_funded: u64,
+}
+
+#[event]
+pub struct EvtSetRewardDistribution {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub old_merkle_root: [u8; 32],
+    pub new_merkle_root: [u8; 32],
+}
+
+#[event]
+pub struct EvtClaimReward {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct EvtParamChangeProposed {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub params: ConfigChangeParameters,
+    /// the earliest time the change can be executed
+    pub execute_after: u64,
+}
+
+#[event]
+pub struct EvtParamChangeExecuted {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub params: ConfigChangeParameters,
+}
+
+#[event]
+pub struct EvtParamChangeCancelled {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+}
+
+#[event]
+pub struct EvtRotateFeeClaimer {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub old_fee_claimer: Pubkey,
+    pub new_fee_claimer: Pubkey,
+    /// whether an admin co-signed the rotation
+    pub admin_cosigned: bool,
+}
+
+#[event]
+pub struct EvtMigrateConfig {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub version: u8,
+}
+
+#[event]
+pub struct EvtReallocCurve {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub config: Pubkey,
+    /// payer of the rent of the larger account
+    pub sponsor: Pubkey,
+    pub version: u8,
+    /// account data length after the realloc
+    pub size: u64,
+}
+
+#[event]
+pub struct EvtSetDammConfig {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub old_damm_config: Pubkey,
+    pub new_damm_config: Pubkey,
+}
+
+#[event]
+pub struct EvtFinalizeCurveMetadata {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub curve_metadata: Pubkey,
+    pub uri: String,
+    pub extension: Vec<u8>,
+}
+
+#[event]
+pub struct EvtSetConfigLabel {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    /// empty when the config had no label yet
+    pub old_label: String,
+    pub new_label: String,
+}
+
+#[event]
+pub struct EvtSetCurveTemplate {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub old_curve_template_hash: [u8; 32],
+    pub new_curve_template_hash: [u8; 32],
+}
+
+#[event]
+pub struct EvtSetTradingPause {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    /// the paused curve, none if the pause applies to the whole config
+    pub curve: Option<Pubkey>,
+    pub old_trading_pause: u8,
+    pub new_trading_pause: u8,
+}
+
+#[event]
+pub struct EvtSetFeeType {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub curve: Pubkey,
+    pub old_fee_type: u8,
+    pub new_fee_type: u8,
+}
+
+#[event]
+pub struct EvtSetTradingEnd {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub curve: Pubkey,
+    pub old_trading_end_timestamp: u64,
+    pub new_trading_end_timestamp: u64,
+}
+
+#[event]
+pub struct EvtRefund {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub curve: Pubkey,
+    pub owner: Pubkey,
+    /// base returned to the curve
+    pub base_amount: u64,
+    /// quote refunded for it
+    pub quote_amount: u64,
+    /// quote reserve left for the other holders
+    pub remaining_quote_reserve: u64,
+}
+
+#[event]
+pub struct EvtSetPause {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    /// the paused curve, none if the pause applies to the whole config
+    pub curve: Option<Pubkey>,
+    pub paused: bool,
+}
+
+#[event]
+pub struct EvtSetCpiTelemetry {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct EvtSetCreatorWhitelistMode {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct EvtAddWhitelistedCreator {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub creator: Pubkey,
+}
+
+#[event]
+pub struct EvtRemoveWhitelistedCreator {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub creator: Pubkey,
+}
+
+#[event]
+pub struct EvtAddPartner {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub partner: Pubkey,
+    /// share of the protocol fee of the partner's curves
+    pub fee_share_basis_points: u16,
+}
+
+#[event]
+pub struct EvtRemovePartner {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub partner: Pubkey,
+}
+
+#[event]
+pub struct EvtCreatePartnerEarnings {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub partner_earnings: Pubkey,
+    pub partner: Pubkey,
+    pub quote_mint: Pubkey,
+}
+
+#[event]
+pub struct EvtClaimPartnerEarnings {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub partner_earnings: Pubkey,
+    pub partner: Pubkey,
+    pub amount: u64,
+    /// total quote claimed by the partner in this quote mint
+    pub total_claimed: u64,
+}
+
+#[event]
+pub struct EvtCreateInsuranceFund {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub insurance_fund: Pubkey,
+    pub quote_vault: Pubkey,
+}
+
+#[event]
+pub struct EvtInsurancePayoutProposed {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub recipient_token_account: Pubkey,
+    pub amount: u64,
+    /// the earliest time the payout can be executed
+    pub execute_after: u64,
+}
+
+#[event]
+pub struct EvtInsurancePayoutExecuted {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+    pub recipient_token_account: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct EvtInsurancePayoutCancelled {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub config: Pubkey,
+}
+
+#[event]
+pub struct EvtDelegateMigrationAuthority {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub operator: Pubkey,
+    pub delegator: Pubkey,
+    pub expiry_timestamp: u64,
+}
+
+#[event]
+pub struct EvtRevokeMigrationAuthority {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub operator: Pubkey,
+}
+
+#[event]
+pub struct EvtPlaceLimitOrder {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub limit_order: Pubkey,
+    pub owner: Pubkey,
+    pub order_id: u64,
+    pub trade_direction: u8,
+    pub amount_in: u64,
+    pub target_price: u128,
+    pub filler_reward: u64,
+}
+
+#[event]
+pub struct EvtCancelLimitOrder {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub limit_order: Pubkey,
+    pub owner: Pubkey,
+    /// input and filler reward sent back to the owner
+    pub refunded_amount: u64,
+}
+
+#[event]
+pub struct EvtFillLimitOrder {
+    /// sequence number of this event within the config
+    pub event_sequence: u64,
+    pub curve: Pubkey,
+    pub limit_order: Pubkey,
+    pub owner: Pubkey,
+    pub filler: Pubkey,
+    pub trade_direction: u8,
+    pub filler_reward: u64,
+    /// virtual price of the curve before the fill, Q64.64
+    pub virtual_price: u128,
+    pub swap_result: SwapResult,
+    pub virtual_base_reserve: u64,
+    pub virtual_quote_reserve: u64,
+    pub holder_count: u64,
 }
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::{partner::MAX_PARTNER_FEE_SHARE_BASIS_POINTS, seeds::PARTNER_PREFIX},
+    errors::AmmError,
+    events::EvtAddPartner,
+    states::{Config, Partner, ProtocolAuthority},
+};
+
+/// Accounts to approve a partner frontend in the partner registry
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(partner: Pubkey)]
+pub struct AddPartnerCtx<'info> {
+    /// config the event is sequenced in, the partner registry itself is protocol wide
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Partner::INIT_SPACE,
+        seeds = [
+            PARTNER_PREFIX,
+            partner.as_ref(),
+        ],
+        bump,
+    )]
+    pub partner_entry: AccountLoader<'info, Partner>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can approve partners, pays for the entry
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_add_partner(
+    ctx: Context<AddPartnerCtx>,
+    partner: Pubkey,
+    fee_share_basis_points: u16,
+) -> Result<()> {
+    require!(
+        fee_share_basis_points <= MAX_PARTNER_FEE_SHARE_BASIS_POINTS,
+        AmmError::InvalidPartnerFeeShare
+    );
+    ctx.accounts
+        .partner_entry
+        .load_init()?
+        .init(partner, fee_share_basis_points);
+
+    emit_cpi!(EvtAddPartner {
+        event_sequence: ctx.accounts.config.load_mut()?.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        partner,
+        fee_share_basis_points,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::WHITELIST_ENTRY_PREFIX,
+    errors::AmmError,
+    events::EvtAddWhitelistedCreator,
+    states::{Config, ProtocolAuthority, WhitelistEntry},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct AddWhitelistedCreatorCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + WhitelistEntry::INIT_SPACE,
+        seeds = [
+            WHITELIST_ENTRY_PREFIX,
+            config.key().as_ref(),
+            creator.as_ref(),
+        ],
+        bump,
+    )]
+    pub whitelist_entry: AccountLoader<'info, WhitelistEntry>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can whitelist creators, pays for the entry
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_add_whitelisted_creator(
+    ctx: Context<AddWhitelistedCreatorCtx>,
+    creator: Pubkey,
+) -> Result<()> {
+    ctx.accounts
+        .whitelist_entry
+        .load_init()?
+        .init(ctx.accounts.config.key(), creator);
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    emit_cpi!(EvtAddWhitelistedCreator {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        creator,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::CONFIG_CHANGE_PREFIX,
+    errors::AmmError,
+    events::EvtParamChangeCancelled,
+    states::{Config, ConfigChange, ProtocolAuthority},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelConfigChangeCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [
+            CONFIG_CHANGE_PREFIX,
+            config.key().as_ref(),
+        ],
+        bump,
+        has_one = config,
+    )]
+    pub config_change: AccountLoader<'info, ConfigChange>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can cancel config changes
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_cancel_config_change(ctx: Context<CancelConfigChangeCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+
+    emit_cpi!(EvtParamChangeCancelled {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+    });
+
+    Ok(())
+}
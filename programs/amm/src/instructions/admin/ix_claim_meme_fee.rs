@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    assert_eq_fee_type_admin, const_pda,
+    errors::AmmError,
+    events::EvtClaimMemeFee,
+    states::{BondingCurve, Config, ProtocolAuthority},
+    utils::token::{is_token_program_of, transfer_from_curve},
+};
+
+/// Accounts for the fee type admin to claim the meme fee of a curve for the community
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimMemeFeeCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        has_one = quote_vault,
+        has_one = config,
+        constraint = is_token_program_of(
+            &quote_mint.to_account_info(),
+            &token_quote_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
+    )]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// Claimer's token account to receive the meme fee, doesn't have to be the ATA
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = claimer,
+        token::token_program = token_quote_program,
+    )]
+    pub claimer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for quote token
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only the fee type admin can claim the meme fee
+    #[account(
+        constraint = assert_eq_fee_type_admin(&protocol_authority, claimer.key())? @ AmmError::Unauthorized,
+    )]
+    pub claimer: Signer<'info>,
+
+    /// Quote token program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handle_claim_meme_fee(ctx: Context<ClaimMemeFeeCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+
+    let amount = curve.claim_meme_fee();
+    require!(amount > 0, AmmError::NothingToClaim);
+
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.quote_vault,
+        &ctx.accounts.claimer_token_account,
+        &ctx.accounts.token_quote_program,
+        amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    emit_cpi!(EvtClaimMemeFee {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        config: ctx.accounts.config.key(),
+        claimer: ctx.accounts.claimer.key(),
+        amount,
+        remaining_creator_fee: curve.creator_fee,
+    });
+
+    Ok(())
+}
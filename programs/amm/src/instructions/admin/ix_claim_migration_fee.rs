@@ -0,0 +1,85 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    errors::AmmError,
+    events::EvtClaimMigrationFee,
+    states::{BondingCurve, Config},
+    utils::token::{is_token_program_of, transfer_from_curve},
+};
+
+/// Accounts for the fee claimer to claim the quote migration fee of a migrated curve
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimMigrationFeeCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    #[account(mut, has_one=quote_mint, has_one=fee_claimer)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        has_one = quote_vault,
+        has_one = config,
+        constraint = is_token_program_of(
+            &quote_mint.to_account_info(),
+            &token_quote_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
+    )]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// Fee claimer's token account to receive the migration fee, doesn't have to be the ATA
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = fee_claimer,
+        token::token_program = token_quote_program,
+    )]
+    pub fee_claimer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for quote token
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The fee claimer
+    pub fee_claimer: Signer<'info>,
+
+    /// Quote token program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handle_claim_migration_fee(ctx: Context<ClaimMigrationFeeCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+
+    let amount = curve.claim_migration_fee();
+    require!(amount > 0, AmmError::NothingToClaim);
+
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.quote_vault,
+        &ctx.accounts.fee_claimer_token_account,
+        &ctx.accounts.token_quote_program,
+        amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    emit_cpi!(EvtClaimMigrationFee {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        config: ctx.accounts.config.key(),
+        fee_claimer: ctx.accounts.fee_claimer.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -4,10 +4,14 @@ use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::{
     const_pda,
+    constants::seeds::INSURANCE_FUND_PREFIX,
     errors::AmmError,
     events::EvtClaimTradingFee,
-    states::{BondingCurve, Config, MigrationStatus},
-    utils::token::transfer_from_curve,
+    safe_math::SafeMath,
+    states::{
+        BondingCurve, Config, InsuranceFund, MigrationStatus, PartnerEarnings, ProtocolStats,
+    },
+    utils::token::{is_token_program_of, transfer_from_curve},
 };
 
 /// Accounts for protocol admin to claim fees
@@ -20,23 +24,26 @@ pub struct ClaimProtocolFeeCtx<'info> {
     )]
     pub curve_authority: UncheckedAccount<'info>,
 
-    #[account(has_one=quote_mint, has_one=fee_claimer)]
+    #[account(mut, has_one=quote_mint, has_one=fee_claimer)]
     pub config: AccountLoader<'info, Config>,
 
     #[account(
         mut,
         has_one = quote_vault,
         has_one = config,
+        constraint = is_token_program_of(
+            &quote_mint.to_account_info(),
+            &token_quote_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
     )]
     pub curve: AccountLoader<'info, BondingCurve>,
 
-    /// Fee claimer's token account to receive the claimed fees
+    /// Fee claimer's token account to receive the claimed fees, doesn't have to be the ATA
     #[account(
-        init_if_needed,
-        payer = fee_claimer,
-        associated_token::mint = quote_mint,
-        associated_token::authority = fee_claimer,
-        associated_token::token_program = token_quote_program,
+        mut,
+        token::mint = quote_mint,
+        token::authority = fee_claimer,
+        token::token_program = token_quote_program,
     )]
     pub fee_claimer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
@@ -44,6 +51,36 @@ pub struct ClaimProtocolFeeCtx<'info> {
     #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
     pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Insurance fund of the config, receives a share of the claimed fees. Required for configs
+    /// with an insurance fund share, created with `create_insurance_fund`
+    #[account(
+        mut,
+        seeds = [
+            INSURANCE_FUND_PREFIX,
+            config.key().as_ref(),
+        ],
+        bump,
+        has_one = config,
+    )]
+    pub insurance_fund: Option<AccountLoader<'info, InsuranceFund>>,
+
+    /// Quote token vault of the insurance fund
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub insurance_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Earnings of the curve's partner in the quote mint, receive its share of the protocol fee.
+    /// Required for curves with a partner
+    #[account(mut, has_one = quote_mint)]
+    pub partner_earnings: Option<AccountLoader<'info, PartnerEarnings>>,
+
+    /// Quote token vault of the partner earnings
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub partner_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// protocol wide stats, counts the claimed fees
+    #[account(mut, address = const_pda::protocol_stats::ID)]
+    pub protocol_stats: AccountLoader<'info, ProtocolStats>,
+
     /// The mint of quote token
     pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
@@ -58,22 +95,90 @@ pub struct ClaimProtocolFeeCtx<'info> {
 }
 
 pub fn handle_claim_protocol_fee(ctx: Context<ClaimProtocolFeeCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
     let mut curve = ctx.accounts.curve.load_mut()?;
 
     // Check if migration is complete
     let migration_status = curve.get_migration_progress()?;
 
+    let protocol_fee = curve.claim_protocol_fee();
     let quote_token_claim_amount = if migration_status == MigrationStatus::CreatedPool {
-        // If migration is complete, claim all remaining tokens in quote vault
-        // Also clear the protocol_fee to avoid confusion
-        curve.claim_protocol_fee();
-        ctx.accounts.quote_vault.amount
+        // If migration is complete, claim all remaining tokens in quote vault but the migration
+        // fee, it is claimed on its own
+        ctx.accounts
+            .quote_vault
+            .amount
+            .safe_sub(curve.migration_fee_collected)?
     } else {
         // Normal protocol fee claim
-        curve.claim_protocol_fee()
+        protocol_fee
     };
 
     require!(quote_token_claim_amount > 0, AmmError::NothingToClaim);
+    ctx.accounts
+        .protocol_stats
+        .load_mut()?
+        .record_protocol_fee_claim(quote_token_claim_amount)?;
+
+    // The insurance fund takes its share of everything claimed, the post migration sweep
+    // included, only the protocol fee is shared with the partner of the curve
+    let insurance_fund_amount = config.get_insurance_fund_amount(quote_token_claim_amount)?;
+    let partner_fee_amount = curve.get_partner_fee_amount(protocol_fee)?;
+    let quote_token_claim_amount = quote_token_claim_amount
+        .safe_sub(insurance_fund_amount)?
+        .safe_sub(partner_fee_amount)?;
+
+    if curve.has_partner() {
+        let (Some(partner_earnings), Some(partner_vault)) =
+            (&ctx.accounts.partner_earnings, &ctx.accounts.partner_vault)
+        else {
+            return err!(AmmError::PartnerEarningsRequired);
+        };
+        let mut partner_earnings = partner_earnings.load_mut()?;
+        require!(
+            partner_earnings.partner == curve.partner
+                && partner_earnings.quote_vault == partner_vault.key(),
+            AmmError::InvalidAccount
+        );
+
+        if partner_fee_amount > 0 {
+            partner_earnings.accrue(partner_fee_amount)?;
+
+            transfer_from_curve(
+                ctx.accounts.curve_authority.to_account_info(),
+                &ctx.accounts.quote_mint,
+                &ctx.accounts.quote_vault,
+                partner_vault,
+                &ctx.accounts.token_quote_program,
+                partner_fee_amount,
+                const_pda::curve_authority::BUMP,
+            )?;
+        }
+    }
+
+    if insurance_fund_amount > 0 {
+        let (Some(insurance_fund), Some(insurance_vault)) =
+            (&ctx.accounts.insurance_fund, &ctx.accounts.insurance_vault)
+        else {
+            return err!(AmmError::InsuranceFundRequired);
+        };
+        let mut insurance_fund = insurance_fund.load_mut()?;
+        require!(
+            insurance_fund.quote_vault == insurance_vault.key(),
+            AmmError::InvalidAccount
+        );
+        insurance_fund.accrue(insurance_fund_amount)?;
+
+        transfer_from_curve(
+            ctx.accounts.curve_authority.to_account_info(),
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.quote_vault,
+            insurance_vault,
+            &ctx.accounts.token_quote_program,
+            insurance_fund_amount,
+            const_pda::curve_authority::BUMP,
+        )?;
+    }
 
     transfer_from_curve(
         ctx.accounts.curve_authority.to_account_info(),
@@ -85,9 +190,16 @@ pub fn handle_claim_protocol_fee(ctx: Context<ClaimProtocolFeeCtx>) -> Result<()
         const_pda::curve_authority::BUMP,
     )?;
 
+    ctx.accounts.quote_vault.reload()?;
     emit_cpi!(EvtClaimTradingFee {
+        event_sequence: config.next_event_sequence()?,
         curve: ctx.accounts.curve.key(),
         quote_token_claim_amount,
+        insurance_fund_amount,
+        partner_fee_amount,
+        quote_vault_balance: ctx.accounts.quote_vault.amount,
+        remaining_protocol_fee: curve.protocol_fee,
+        remaining_creator_fee: curve.creator_fee,
     });
 
     Ok(())
@@ -0,0 +1,186 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::{fee::MAX_PROTOCOL_FEE_CLAIM_BATCH_CURVES, seeds::INSURANCE_FUND_PREFIX},
+    errors::AmmError,
+    events::EvtClaimTradingFee,
+    safe_math::SafeMath,
+    states::{BondingCurve, Config, InsuranceFund, MigrationStatus, ProtocolStats},
+    utils::token::transfer_from_curve,
+};
+
+/// Accounts for protocol admin to claim fees from many curves of a config, the curves and their
+/// quote vaults are passed as `[curve, quote_vault]` pairs of remaining accounts
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimProtocolFeeBatchCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    #[account(mut, has_one=quote_mint, has_one=fee_claimer)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// Fee claimer's token account to receive the claimed fees, doesn't have to be the ATA
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = fee_claimer,
+        token::token_program = token_quote_program,
+    )]
+    pub fee_claimer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Insurance fund of the config, receives a share of the claimed fees. Required for configs
+    /// with an insurance fund share, created with `create_insurance_fund`
+    #[account(
+        mut,
+        seeds = [
+            INSURANCE_FUND_PREFIX,
+            config.key().as_ref(),
+        ],
+        bump,
+        has_one = config,
+    )]
+    pub insurance_fund: Option<AccountLoader<'info, InsuranceFund>>,
+
+    /// Quote token vault of the insurance fund
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub insurance_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// protocol wide stats, counts the claimed fees
+    #[account(mut, address = const_pda::protocol_stats::ID)]
+    pub protocol_stats: AccountLoader<'info, ProtocolStats>,
+
+    /// The mint of quote token
+    #[account(mint::token_program = token_quote_program)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The fee claimer
+    #[account(mut)]
+    pub fee_claimer: Signer<'info>,
+
+    /// Quote token program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Claim the protocol fee of every curve passed, curves with nothing to claim are skipped so a
+/// drained curve doesn't fail the whole batch. Curves with a partner are skipped the same way,
+/// they are claimed on their own with `claim_protocol_fee`, which takes the partner earnings
+/// accounts
+pub fn handle_claim_protocol_fee_batch<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ClaimProtocolFeeBatchCtx<'info>>,
+) -> Result<()> {
+    let remaining_accounts = ctx.remaining_accounts;
+    require!(
+        !remaining_accounts.is_empty()
+            && remaining_accounts.chunks_exact(2).remainder().is_empty()
+            && remaining_accounts.len() / 2 <= MAX_PROTOCOL_FEE_CLAIM_BATCH_CURVES,
+        AmmError::InvalidProtocolFeeClaimBatch
+    );
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut insurance_fund = match (&ctx.accounts.insurance_fund, &ctx.accounts.insurance_vault) {
+        (Some(insurance_fund), Some(insurance_vault)) => {
+            let insurance_fund = insurance_fund.load_mut()?;
+            require!(
+                insurance_fund.quote_vault == insurance_vault.key(),
+                AmmError::InvalidAccount
+            );
+            Some((insurance_fund, insurance_vault))
+        }
+        _ => {
+            require!(
+                config.insurance_fund_basis_points == 0,
+                AmmError::InsuranceFundRequired
+            );
+            None
+        }
+    };
+
+    let mut total_claim_amount = 0u64;
+    for accounts in remaining_accounts.chunks_exact(2) {
+        let curve_loader = AccountLoader::<BondingCurve>::try_from(&accounts[0])?;
+        let mut curve = curve_loader.load_mut()?;
+        require!(
+            curve.config == ctx.accounts.config.key() && curve.quote_vault == accounts[1].key(),
+            AmmError::InvalidAccount
+        );
+        if curve.has_partner() {
+            continue;
+        }
+        let mut quote_vault = InterfaceAccount::<TokenAccount>::try_from(&accounts[1])?;
+
+        let protocol_fee = curve.claim_protocol_fee();
+        let quote_token_claim_amount =
+            if curve.get_migration_progress()? == MigrationStatus::CreatedPool {
+                // If migration is complete, claim all remaining tokens in quote vault but the
+                // migration fee, it is claimed on its own
+                quote_vault.amount.safe_sub(curve.migration_fee_collected)?
+            } else {
+                protocol_fee
+            };
+        if quote_token_claim_amount == 0 {
+            continue;
+        }
+        total_claim_amount = total_claim_amount.safe_add(quote_token_claim_amount)?;
+
+        // The insurance fund takes its share of everything claimed, the post migration sweep
+        // included
+        let insurance_fund_amount = config.get_insurance_fund_amount(quote_token_claim_amount)?;
+        let quote_token_claim_amount = quote_token_claim_amount.safe_sub(insurance_fund_amount)?;
+
+        if insurance_fund_amount > 0 {
+            let (insurance_fund, insurance_vault) = insurance_fund
+                .as_mut()
+                .ok_or(AmmError::InsuranceFundRequired)?;
+            insurance_fund.accrue(insurance_fund_amount)?;
+
+            transfer_from_curve(
+                ctx.accounts.curve_authority.to_account_info(),
+                &ctx.accounts.quote_mint,
+                &quote_vault,
+                insurance_vault,
+                &ctx.accounts.token_quote_program,
+                insurance_fund_amount,
+                const_pda::curve_authority::BUMP,
+            )?;
+        }
+
+        transfer_from_curve(
+            ctx.accounts.curve_authority.to_account_info(),
+            &ctx.accounts.quote_mint,
+            &quote_vault,
+            &ctx.accounts.fee_claimer_token_account,
+            &ctx.accounts.token_quote_program,
+            quote_token_claim_amount,
+            const_pda::curve_authority::BUMP,
+        )?;
+
+        quote_vault.reload()?;
+        emit_cpi!(EvtClaimTradingFee {
+            event_sequence: config.next_event_sequence()?,
+            curve: curve_loader.key(),
+            quote_token_claim_amount,
+            insurance_fund_amount,
+            partner_fee_amount: 0,
+            quote_vault_balance: quote_vault.amount,
+            remaining_protocol_fee: curve.protocol_fee,
+            remaining_creator_fee: curve.creator_fee,
+        });
+    }
+
+    require!(total_claim_amount > 0, AmmError::NothingToClaim);
+    ctx.accounts
+        .protocol_stats
+        .load_mut()?
+        .record_protocol_fee_claim(total_claim_amount)?;
+
+    Ok(())
+}
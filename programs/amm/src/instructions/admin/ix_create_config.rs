@@ -7,49 +7,38 @@ use anchor_spl::{
 };
 
 use crate::{
-    assert_eq_admin,
-    constants::{cashback::CASHBACK_CHAMPION_BPS, fee::MAX_FEE_BASIS_POINTS},
+    assert_eq_admin, const_pda,
+    constants::{
+        bonus::MAX_EARLY_BUYER_WINDOW_SECONDS,
+        cashback::{CASHBACK_CHAMPION_BPS, MAX_CASHBACK_MULTIPLIER_BASIS_POINTS},
+        fee::{
+            FEE_DENOMINATOR, MAX_CREATOR_FEE_PROTOCOL_BASIS_POINTS,
+            MAX_CREATOR_LP_FEE_SHARE_BASIS_POINTS, MAX_FEE_BASIS_POINTS,
+            MAX_LAUNCH_FEE_BASIS_POINTS, MAX_LAUNCH_FEE_DECAY_SECONDS,
+        },
+        governance::MAX_PARAM_CHANGE_DELAY_SECONDS,
+        insurance::MAX_INSURANCE_FUND_BASIS_POINTS,
+        migration::{
+            MAX_CREATOR_LP_BASIS_POINTS, MAX_MIGRATION_FEE_BASIS_POINTS,
+            MAX_PRE_MIGRATION_COOLDOWN_SECONDS, MAX_THRESHOLD_DECAY_SECONDS,
+            MIN_THRESHOLD_DECAY_FLOOR_BASIS_POINTS, THRESHOLD_DECAY_STEPS,
+        },
+        protection::{MAX_ANTI_SNIPE_WINDOW_SLOTS, MAX_MIN_HOLD_SECONDS},
+        seeds::{CONFIG_PREFIX, CONFIG_STATS_PREFIX, PROTOCOL_STATS_PREFIX},
+        support_fund::MAX_SUPPORT_FUND_BASIS_POINTS,
+        MAX_BASE_DECIMAL, MIN_BASE_DECIMAL,
+    },
     errors::AmmError,
+    params::config::ConfigParameters,
     safe_math::SafeMath,
-    states::{Config, TokenType},
+    states::{
+        get_base_scale, Config, ConfigStats, FeeCollectionMode, FeeRemainderMode, MigrationFeeMode,
+        MigrationTokenOrder, ProtocolAuthority, ProtocolStats, RoutedSwapFeeMode,
+        ThresholdDecayMode, TokenType,
+    },
     utils::{get_token_program_flags, is_supported_quote_mint},
 };
 
-#[derive(AnchorSerialize, AnchorDeserialize, Debug)]
-pub struct ConfigParameters {
-    /* Token configurations */
-    /// token type (0 | 1), 0: SPL Token, 1: Token2022
-    pub base_token_flag: u8,
-    /// token decimal, (6 | 9)
-    pub base_decimal: u8,
-
-    /* Fee configurations */
-    /// Trading fee in bps
-    pub fee_basis_points: u16,
-    /// Level 1 referral fee in bps
-    pub l1_referral_fee_basis_points: u16,
-    /// Level 2 referral fee in bps
-    pub l2_referral_fee_basis_points: u16,
-    /// Level 3 referral fee in bps
-    pub l3_referral_fee_basis_points: u16,
-    /// Referee discount in bps
-    pub referee_discount_basis_points: u16,
-    /// creator fee in bps
-    pub creator_fee_basis_points: u16,
-    /// migration fee in bps (quote token fee)
-    pub migration_fee_basis_points: u16,
-
-    /* Price configurations */
-    /// migration base threshold (the amount of token to migrate)
-    pub migration_base_threshold: u64,
-    /// migration quote threshold
-    pub migration_quote_threshold: u64,
-    /// initial virtual quote reserve to boost the initial liquidity
-    pub initial_virtual_quote_reserve: u64,
-    /// initial virtual base reserve to boost the initial liquidity
-    pub initial_virtual_base_reserve: u64,
-}
-
 impl ConfigParameters {
     pub fn validate<'info>(
         &self,
@@ -66,48 +55,147 @@ impl ConfigParameters {
 
         // validate token decimals
         require!(
-            self.base_decimal >= 6 && self.base_decimal <= 9,
+            self.base_decimal >= MIN_BASE_DECIMAL && self.base_decimal <= MAX_BASE_DECIMAL,
             AmmError::InvalidTokenDecimals
         );
 
-        let other_fee_basis_points_sum = self
-            .l1_referral_fee_basis_points
-            .safe_add(self.l2_referral_fee_basis_points)?
-            .safe_add(self.l3_referral_fee_basis_points)?
-            .safe_add(self.creator_fee_basis_points)?
-            .safe_add(CASHBACK_CHAMPION_BPS)?; // assume max cashback fee bps
+        validate_fee_basis_points(
+            self.fee_basis_points,
+            self.l1_referral_fee_basis_points,
+            self.l2_referral_fee_basis_points,
+            self.l3_referral_fee_basis_points,
+            self.creator_fee_basis_points,
+        )?;
+
+        require!(
+            self.insurance_fund_basis_points <= MAX_INSURANCE_FUND_BASIS_POINTS,
+            AmmError::InvalidAmmConfig
+        );
+
+        require!(
+            self.creator_fee_protocol_basis_points <= MAX_CREATOR_FEE_PROTOCOL_BASIS_POINTS,
+            AmmError::InvalidAmmConfig
+        );
+
+        require!(
+            self.support_fund_basis_points <= MAX_SUPPORT_FUND_BASIS_POINTS,
+            AmmError::InvalidAmmConfig
+        );
+
+        // the tier cashback is carved out of the trading fee, it can only be reduced
+        require!(
+            self.buy_cashback_multiplier_basis_points <= MAX_CASHBACK_MULTIPLIER_BASIS_POINTS
+                && self.sell_cashback_multiplier_basis_points
+                    <= MAX_CASHBACK_MULTIPLIER_BASIS_POINTS,
+            AmmError::InvalidAmmConfig
+        );
+
+        require!(
+            self.max_creator_lp_fee_share_basis_points <= MAX_CREATOR_LP_FEE_SHARE_BASIS_POINTS,
+            AmmError::InvalidAmmConfig
+        );
+
+        // k = virtual quote * scaled virtual base has to fit into u128 for the whole curve
+        let base_scale = get_base_scale(self.base_decimal, quote_mint.decimals)?;
         require!(
-            self.fee_basis_points > other_fee_basis_points_sum,
-            AmmError::InvalidFeeBasisPoints
+            (self.initial_virtual_base_reserve as u128)
+                .checked_mul(base_scale)
+                .and_then(|scaled_base| scaled_base.checked_mul(u64::MAX as u128))
+                .is_some(),
+            AmmError::InvalidAmmConfig
         );
 
-        // validate referral fee hierarchy
         require!(
-            self.l1_referral_fee_basis_points > self.l2_referral_fee_basis_points,
+            self.pre_migration_cooldown_seconds <= MAX_PRE_MIGRATION_COOLDOWN_SECONDS,
             AmmError::InvalidAmmConfig
         );
+
         require!(
-            self.l2_referral_fee_basis_points > self.l3_referral_fee_basis_points,
+            self.migration_fee_basis_points <= MAX_MIGRATION_FEE_BASIS_POINTS,
             AmmError::InvalidAmmConfig
         );
 
-        // validate creator trading fee percentage
+        // the locked first position keeps most of the liquidity
         require!(
-            self.creator_fee_basis_points <= 1000,
-            AmmError::InvalidCreatorTradingFeePercentage
+            self.creator_lp_basis_points <= MAX_CREATOR_LP_BASIS_POINTS,
+            AmmError::InvalidAmmConfig
         );
 
-        // fee basis points configurations
+        // only the permissionless crank is rewarded
+        require!(
+            self.permissionless_migration <= 1
+                && (self.permissionless_migration == 1 || self.migration_crank_reward == 0),
+            AmmError::InvalidAmmConfig
+        );
+
+        MigrationTokenOrder::try_from(self.migration_token_order)
+            .map_err(|_| AmmError::InvalidAmmConfig)?;
+
+        RoutedSwapFeeMode::try_from(self.routed_swap_fee_mode)
+            .map_err(|_| AmmError::InvalidAmmConfig)?;
+
+        FeeCollectionMode::try_from(self.fee_collection_mode)
+            .map_err(|_| AmmError::InvalidAmmConfig)?;
+
+        MigrationFeeMode::try_from(self.migration_fee_mode)
+            .map_err(|_| AmmError::InvalidAmmConfig)?;
+
+        FeeRemainderMode::try_from(self.fee_remainder_mode)
+            .map_err(|_| AmmError::InvalidAmmConfig)?;
+
+        let threshold_decay_mode = ThresholdDecayMode::try_from(self.threshold_decay_mode)
+            .map_err(|_| AmmError::InvalidAmmConfig)?;
+        if threshold_decay_mode != ThresholdDecayMode::None {
+            require!(
+                self.threshold_decay_floor_basis_points >= MIN_THRESHOLD_DECAY_FLOOR_BASIS_POINTS
+                    && (self.threshold_decay_floor_basis_points as u64) < FEE_DENOMINATOR,
+                AmmError::InvalidAmmConfig
+            );
+            require!(
+                self.threshold_decay_seconds >= THRESHOLD_DECAY_STEPS
+                    && self.threshold_decay_seconds <= MAX_THRESHOLD_DECAY_SECONDS,
+                AmmError::InvalidAmmConfig
+            );
+        }
+
         require!(
-            self.fee_basis_points <= MAX_FEE_BASIS_POINTS,
+            self.early_buyer_window_seconds <= MAX_EARLY_BUYER_WINDOW_SECONDS,
             AmmError::InvalidAmmConfig
         );
 
         require!(
-            self.initial_virtual_quote_reserve > 0
-                && self.initial_virtual_base_reserve > 0
-                && self.migration_base_threshold > 0
-                && self.migration_quote_threshold > 0,
+            self.min_hold_seconds <= MAX_MIN_HOLD_SECONDS,
+            AmmError::InvalidAmmConfig
+        );
+
+        // the window and the cap are set together, a window without a cap would block all buys
+        require!(
+            self.anti_snipe_window_slots <= MAX_ANTI_SNIPE_WINDOW_SLOTS
+                && (self.anti_snipe_window_slots == 0) == (self.max_buy_amount_first_window == 0),
+            AmmError::InvalidAmmConfig
+        );
+
+        // the launch fee is settled at creation, a disabled one can't carry a decay
+        if self.launch_fee_basis_points > 0 {
+            require!(
+                self.launch_fee_basis_points <= MAX_LAUNCH_FEE_BASIS_POINTS,
+                AmmError::InvalidAmmConfig
+            );
+            require!(
+                self.launch_fee_decay_seconds > 0
+                    && self.launch_fee_decay_seconds <= MAX_LAUNCH_FEE_DECAY_SECONDS
+                    && self.launch_fee_periods as u64 <= self.launch_fee_decay_seconds,
+                AmmError::InvalidAmmConfig
+            );
+        } else {
+            require!(
+                self.launch_fee_decay_seconds == 0 && self.launch_fee_periods == 0,
+                AmmError::InvalidAmmConfig
+            );
+        }
+
+        require!(
+            self.param_change_delay_seconds <= MAX_PARAM_CHANGE_DELAY_SECONDS,
             AmmError::InvalidAmmConfig
         );
 
@@ -115,23 +203,106 @@ impl ConfigParameters {
     }
 }
 
+/// Validate the fee split, shared by config creation and timelocked config changes
+pub fn validate_fee_basis_points(
+    fee_basis_points: u16,
+    l1_referral_fee_basis_points: u16,
+    l2_referral_fee_basis_points: u16,
+    l3_referral_fee_basis_points: u16,
+    creator_fee_basis_points: u16,
+) -> Result<()> {
+    let other_fee_basis_points_sum = l1_referral_fee_basis_points
+        .safe_add(l2_referral_fee_basis_points)?
+        .safe_add(l3_referral_fee_basis_points)?
+        .safe_add(creator_fee_basis_points)?
+        .safe_add(CASHBACK_CHAMPION_BPS)?; // assume max cashback fee bps
+    require!(
+        fee_basis_points > other_fee_basis_points_sum,
+        AmmError::InvalidFeeBasisPoints
+    );
+
+    // validate referral fee hierarchy
+    require!(
+        l1_referral_fee_basis_points > l2_referral_fee_basis_points,
+        AmmError::InvalidAmmConfig
+    );
+    require!(
+        l2_referral_fee_basis_points > l3_referral_fee_basis_points,
+        AmmError::InvalidAmmConfig
+    );
+
+    // validate creator trading fee percentage
+    require!(
+        creator_fee_basis_points <= 1000,
+        AmmError::InvalidCreatorTradingFeePercentage
+    );
+
+    // fee basis points configurations
+    require!(
+        fee_basis_points <= MAX_FEE_BASIS_POINTS,
+        AmmError::InvalidAmmConfig
+    );
+
+    Ok(())
+}
+
 #[event_cpi]
 #[derive(Accounts)]
+#[instruction(index: u16)]
 pub struct CreateConfigCtx<'info> {
     /// Initialize config state account to store protocol owner address and fee rates.
+    /// Derived from the index and the quote mint so configs can be found without an index
     #[account(
         init,
-        signer,
+        seeds = [
+            CONFIG_PREFIX,
+            index.to_le_bytes().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
         payer = payer,
         space = 8 + Config::INIT_SPACE,
     )]
     pub config: AccountLoader<'info, Config>,
 
+    /// Protocol wide stats, created along with the first config. Curves can only be created on
+    /// a config, so it exists before anything is counted into it
+    #[account(
+        init_if_needed,
+        seeds = [PROTOCOL_STATS_PREFIX],
+        bump,
+        payer = payer,
+        space = 8 + ProtocolStats::INIT_SPACE,
+    )]
+    pub protocol_stats: AccountLoader<'info, ProtocolStats>,
+
+    /// Stats of the config
+    #[account(
+        init,
+        seeds = [
+            CONFIG_STATS_PREFIX,
+            config.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = 8 + ConfigStats::INIT_SPACE,
+    )]
+    pub config_stats: AccountLoader<'info, ConfigStats>,
+
     /// CHECK: fee_claimer
     /// fee claimer, doesn't have to be a signer
     pub fee_claimer: UncheckedAccount<'info>,
 
-    /// fee claimer token account
+    /// CHECK: approved DAMM v2 config for migrations, validated against the pool requirements
+    /// at migration time
+    #[account(owner = damm_v2::ID @ AmmError::InvalidConfigAccount)]
+    pub damm_config: UncheckedAccount<'info>,
+
+    /// CHECK: receiver of the base tokens left in the vaults after migration, omit it to burn them
+    pub leftover_receiver: Option<UncheckedAccount<'info>>,
+
+    /// fee claimer token account, omit it for fee claimers that manage their own token accounts
+    /// (eg: program owned PDAs), the destination is then only validated when claiming
     #[account(
         init_if_needed,
         payer = payer,
@@ -139,15 +310,19 @@ pub struct CreateConfigCtx<'info> {
         associated_token::authority = fee_claimer,
         associated_token::token_program = token_program,
     )]
-    pub fee_claimer_token_account: InterfaceAccount<'info, TokenAccountInterface>,
+    pub fee_claimer_token_account: Option<InterfaceAccount<'info, TokenAccountInterface>>,
 
     /// quote mint
     pub quote_mint: Box<InterfaceAccount<'info, MintInterface>>,
 
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
     /// only admin can create config
     #[account(
         mut,
-        constraint = assert_eq_admin(payer.key()) @ AmmError::Unauthorized,
+        constraint = assert_eq_admin(&protocol_authority, payer.key())? @ AmmError::Unauthorized,
     )]
     pub payer: Signer<'info>,
 
@@ -158,33 +333,31 @@ pub struct CreateConfigCtx<'info> {
 
 pub fn handle_create_config(
     ctx: Context<CreateConfigCtx>,
+    index: u16,
     config_params: ConfigParameters,
 ) -> Result<()> {
     config_params.validate(&ctx.accounts.quote_mint)?;
 
+    ctx.accounts
+        .config_stats
+        .load_init()?
+        .init(ctx.accounts.config.key());
+
     let mut config = ctx.accounts.config.load_init()?;
     config.init(
         &ctx.accounts.quote_mint.key(),
         &ctx.accounts.fee_claimer.key(),
-        /* Token configurations */
-        config_params.base_token_flag,
+        &ctx.accounts.damm_config.key(),
         get_token_program_flags(&ctx.accounts.quote_mint).into(),
-        config_params.base_decimal,
         ctx.accounts.quote_mint.decimals,
-        /* Fee configurations */
-        config_params.fee_basis_points,
-        config_params.l1_referral_fee_basis_points,
-        config_params.l2_referral_fee_basis_points,
-        config_params.l3_referral_fee_basis_points,
-        config_params.referee_discount_basis_points,
-        config_params.creator_fee_basis_points,
-        config_params.migration_fee_basis_points,
-        /* Price configurations */
-        config_params.migration_base_threshold,
-        config_params.migration_quote_threshold,
-        config_params.initial_virtual_quote_reserve,
-        config_params.initial_virtual_base_reserve,
+        &config_params,
     );
-    emit_cpi!(config.event(ctx.accounts.config.key()));
+    config.index = index;
+    if let Some(ref leftover_receiver) = ctx.accounts.leftover_receiver {
+        config.leftover_receiver = leftover_receiver.key();
+    }
+    config.validate_invariants()?;
+    let event_sequence = config.next_event_sequence()?;
+    emit_cpi!(config.event(ctx.accounts.config.key(), event_sequence));
     Ok(())
 }
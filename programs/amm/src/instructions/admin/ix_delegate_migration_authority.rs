@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::MIGRATION_DELEGATE_PREFIX,
+    errors::AmmError,
+    events::{EvtDelegateMigrationAuthority, EvtRevokeMigrationAuthority},
+    states::{BondingCurve, Config, MigrationDelegate, ProtocolAuthority},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DelegateMigrationAuthorityCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// delegation of the curve, a new delegation replaces the previous one
+    #[account(
+        init_if_needed,
+        payer = admin,
+        seeds = [
+            MIGRATION_DELEGATE_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        space = 8 + MigrationDelegate::INIT_SPACE,
+    )]
+    pub migration_delegate: AccountLoader<'info, MigrationDelegate>,
+
+    /// CHECK: operator receiving the migration rights, any key
+    pub operator: UncheckedAccount<'info>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can delegate migration rights
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_delegate_migration_authority(
+    ctx: Context<DelegateMigrationAuthorityCtx>,
+    expiry_timestamp: u64,
+) -> Result<()> {
+    require!(
+        expiry_timestamp > Clock::get()?.unix_timestamp as u64,
+        AmmError::InvalidMigrationDelegationExpiry
+    );
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut migration_delegate = match ctx.accounts.migration_delegate.load_init() {
+        Ok(mut migration_delegate) => {
+            migration_delegate.init(ctx.accounts.curve.key());
+            migration_delegate
+        }
+        Err(_) => ctx.accounts.migration_delegate.load_mut()?,
+    };
+    migration_delegate.delegate(
+        ctx.accounts.operator.key(),
+        ctx.accounts.admin.key(),
+        expiry_timestamp,
+    );
+
+    emit_cpi!(EvtDelegateMigrationAuthority {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        operator: ctx.accounts.operator.key(),
+        delegator: ctx.accounts.admin.key(),
+        expiry_timestamp,
+    });
+
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RevokeMigrationAuthorityCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [
+            MIGRATION_DELEGATE_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        has_one = curve,
+    )]
+    pub migration_delegate: AccountLoader<'info, MigrationDelegate>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can revoke migration rights
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_revoke_migration_authority(ctx: Context<RevokeMigrationAuthorityCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let operator = ctx.accounts.migration_delegate.load()?.operator;
+
+    emit_cpi!(EvtRevokeMigrationAuthority {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        operator,
+    });
+
+    Ok(())
+}
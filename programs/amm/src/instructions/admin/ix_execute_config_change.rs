@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::CONFIG_CHANGE_PREFIX,
+    errors::AmmError,
+    events::EvtParamChangeExecuted,
+    states::{Config, ConfigChange, ProtocolAuthority},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteConfigChangeCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [
+            CONFIG_CHANGE_PREFIX,
+            config.key().as_ref(),
+        ],
+        bump,
+        has_one = config,
+    )]
+    pub config_change: AccountLoader<'info, ConfigChange>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can execute config changes
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_execute_config_change(ctx: Context<ExecuteConfigChangeCtx>) -> Result<()> {
+    let config_change = ctx.accounts.config_change.load()?;
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    require!(
+        config_change.is_executable(current_timestamp),
+        AmmError::ConfigChangeTimelockNotMet
+    );
+
+    let params = config_change.params();
+    let mut config = ctx.accounts.config.load_mut()?;
+    config.apply_change(&params);
+    config.validate_invariants()?;
+
+    emit_cpi!(EvtParamChangeExecuted {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        params,
+    });
+
+    Ok(())
+}
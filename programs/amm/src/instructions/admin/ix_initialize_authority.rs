@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{authority::BPF_LOADER_UPGRADEABLE_ID, seeds::PROTOCOL_AUTHORITY_PREFIX},
+    errors::AmmError,
+    events::EvtInitializeAuthority,
+    states::ProtocolAuthority,
+};
+
+/// Accounts to create the protocol authority, once, by the upgrade authority of the program
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InitializeAuthorityCtx<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ProtocolAuthority::INIT_SPACE,
+        seeds = [PROTOCOL_AUTHORITY_PREFIX],
+        bump,
+    )]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// program data of this program, holds its upgrade authority
+    #[account(
+        seeds = [crate::ID.as_ref()],
+        bump,
+        seeds::program = BPF_LOADER_UPGRADEABLE_ID,
+        constraint = program_data.upgrade_authority_address == Some(payer.key()) @ AmmError::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// only the upgrade authority of the program can set the first authorities
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_initialize_authority(
+    ctx: Context<InitializeAuthorityCtx>,
+    global_authority: Pubkey,
+    migration_authority: Pubkey,
+    fee_type_authority: Pubkey,
+) -> Result<()> {
+    ctx.accounts.protocol_authority.load_init()?.init(
+        global_authority,
+        migration_authority,
+        fee_type_authority,
+    );
+
+    emit_cpi!(EvtInitializeAuthority {
+        global_authority,
+        migration_authority,
+        fee_type_authority,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    errors::AmmError,
+    events::EvtMigrateConfig,
+    states::{Config, LegacyConfig, ProtocolAuthority},
+};
+
+/// Accounts to bring a config created as a keypair account, before configs were PDAs, to the
+/// current layout. Legacy configs can't be loaded until they are migrated, their curves keep
+/// pointing at them
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateConfigCtx<'info> {
+    #[account(
+        mut,
+        realloc = 8 + Config::INIT_SPACE,
+        realloc::payer = admin,
+        realloc::zero = true,
+    )]
+    pub config: AccountLoader<'info, Config>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can migrate configs, pays for the larger account
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_migrate_config(ctx: Context<MigrateConfigCtx>) -> Result<()> {
+    // the realloc only zeroed the grown tail, the legacy fields are still at the start
+    let legacy =
+        LegacyConfig::try_from_account_data(&ctx.accounts.config.as_ref().try_borrow_data()?)?;
+    let mut config = ctx.accounts.config.load_mut()?;
+    config.migrate_legacy(&legacy)?;
+
+    emit_cpi!(EvtMigrateConfig {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        version: config.version,
+    });
+
+    Ok(())
+}
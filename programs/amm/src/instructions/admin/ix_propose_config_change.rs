@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::{
+        migration::{MAX_MIGRATION_FEE_BASIS_POINTS, MAX_PRE_MIGRATION_COOLDOWN_SECONDS},
+        seeds::CONFIG_CHANGE_PREFIX,
+    },
+    errors::AmmError,
+    events::EvtParamChangeProposed,
+    instructions::validate_fee_basis_points,
+    params::config::ConfigChangeParameters,
+    safe_math::SafeMath,
+    states::{Config, ConfigChange, ProtocolAuthority},
+};
+
+impl ConfigChangeParameters {
+    pub fn validate(&self) -> Result<()> {
+        validate_fee_basis_points(
+            self.fee_basis_points,
+            self.l1_referral_fee_basis_points,
+            self.l2_referral_fee_basis_points,
+            self.l3_referral_fee_basis_points,
+            self.creator_fee_basis_points,
+        )?;
+
+        require!(
+            self.migration_base_threshold > 0 && self.migration_quote_threshold > 0,
+            AmmError::InvalidAmmConfig
+        );
+
+        require!(
+            self.pre_migration_cooldown_seconds <= MAX_PRE_MIGRATION_COOLDOWN_SECONDS,
+            AmmError::InvalidAmmConfig
+        );
+
+        require!(
+            self.migration_fee_basis_points <= MAX_MIGRATION_FEE_BASIS_POINTS,
+            AmmError::InvalidAmmConfig
+        );
+
+        Ok(())
+    }
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeConfigChangeCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// Pending change of the config, only one change can be pending at a time
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + ConfigChange::INIT_SPACE,
+        seeds = [
+            CONFIG_CHANGE_PREFIX,
+            config.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub config_change: AccountLoader<'info, ConfigChange>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can propose config changes
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_propose_config_change(
+    ctx: Context<ProposeConfigChangeCtx>,
+    params: ConfigChangeParameters,
+) -> Result<()> {
+    params.validate()?;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    // fail early, the invariants are checked again on execution against the config by then
+    let mut changed_config = *config;
+    changed_config.apply_change(&params);
+    changed_config.validate_invariants()?;
+
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let execute_after = current_timestamp.safe_add(config.param_change_delay_seconds)?;
+
+    let mut config_change = ctx.accounts.config_change.load_init()?;
+    config_change.init(ctx.accounts.config.key(), execute_after, &params);
+
+    emit_cpi!(EvtParamChangeProposed {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        params,
+        execute_after,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::PARTNER_PREFIX,
+    errors::AmmError,
+    events::EvtRemovePartner,
+    states::{Config, Partner, ProtocolAuthority},
+};
+
+/// Accounts to remove a partner from the partner registry, the entry rent goes back to the admin.
+/// Curves already created through the partner keep paying it
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemovePartnerCtx<'info> {
+    /// config the event is sequenced in, the partner registry itself is protocol wide
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [
+            PARTNER_PREFIX,
+            partner_entry.load()?.partner.as_ref(),
+        ],
+        bump,
+    )]
+    pub partner_entry: AccountLoader<'info, Partner>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can remove partners
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_remove_partner(ctx: Context<RemovePartnerCtx>) -> Result<()> {
+    let partner = ctx.accounts.partner_entry.load()?.partner;
+
+    emit_cpi!(EvtRemovePartner {
+        event_sequence: ctx.accounts.config.load_mut()?.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        partner,
+    });
+
+    Ok(())
+}
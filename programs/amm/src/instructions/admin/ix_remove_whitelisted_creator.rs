@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::WHITELIST_ENTRY_PREFIX,
+    errors::AmmError,
+    events::EvtRemoveWhitelistedCreator,
+    states::{Config, ProtocolAuthority, WhitelistEntry},
+};
+
+/// Accounts to remove a creator from the whitelist of a config, the entry rent goes back to the
+/// admin
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RemoveWhitelistedCreatorCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [
+            WHITELIST_ENTRY_PREFIX,
+            config.key().as_ref(),
+            whitelist_entry.load()?.creator.as_ref(),
+        ],
+        bump,
+        has_one = config,
+    )]
+    pub whitelist_entry: AccountLoader<'info, WhitelistEntry>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can remove whitelisted creators
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_remove_whitelisted_creator(ctx: Context<RemoveWhitelistedCreatorCtx>) -> Result<()> {
+    let creator = ctx.accounts.whitelist_entry.load()?.creator;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    emit_cpi!(EvtRemoveWhitelistedCreator {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        creator,
+    });
+
+    Ok(())
+}
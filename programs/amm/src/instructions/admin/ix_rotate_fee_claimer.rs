@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    errors::AmmError,
+    events::EvtRotateFeeClaimer,
+    states::{Config, ProtocolAuthority},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RotateFeeClaimerCtx<'info> {
+    #[account(mut, has_one = fee_claimer)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// the current fee claimer has to sign off on the rotation
+    pub fee_claimer: Signer<'info>,
+
+    /// CHECK: the new fee claimer, any key that can sign later claims
+    #[account(constraint = new_fee_claimer.key() != fee_claimer.key() @ AmmError::InvalidAccount)]
+    pub new_fee_claimer: UncheckedAccount<'info>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// optional admin co-sign, recorded in the event
+    #[account(
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Option<Signer<'info>>,
+}
+
+pub fn handle_rotate_fee_claimer(ctx: Context<RotateFeeClaimerCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let old_fee_claimer = config.fee_claimer;
+    config.fee_claimer = ctx.accounts.new_fee_claimer.key();
+
+    emit_cpi!(EvtRotateFeeClaimer {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        old_fee_claimer,
+        new_fee_claimer: config.fee_claimer,
+        admin_cosigned: ctx.accounts.admin.is_some(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::CONFIG_LABEL_PREFIX,
+    errors::AmmError,
+    events::EvtSetConfigLabel,
+    states::{Config, ConfigLabel, ProtocolAuthority},
+};
+
+/// Accounts to set the label of a config, the label account is created on the first set
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetConfigLabelCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + ConfigLabel::INIT_SPACE,
+        seeds = [
+            CONFIG_LABEL_PREFIX,
+            config.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub config_label: AccountLoader<'info, ConfigLabel>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can label configs, pays for the label account
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_set_config_label(ctx: Context<SetConfigLabelCtx>, label: String) -> Result<()> {
+    let mut config_label = match ctx.accounts.config_label.load_init() {
+        Ok(mut config_label) => {
+            config_label.init(ctx.accounts.config.key());
+            config_label
+        }
+        Err(_) => ctx.accounts.config_label.load_mut()?,
+    };
+    let old_label = config_label.set_label(&label)?;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    emit_cpi!(EvtSetConfigLabel {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        old_label,
+        new_label: label,
+    });
+
+    Ok(())
+}
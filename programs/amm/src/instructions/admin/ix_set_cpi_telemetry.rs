@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    errors::AmmError,
+    events::EvtSetCpiTelemetry,
+    states::{Config, ProtocolAuthority},
+};
+
+/// Accounts to turn the compute unit telemetry of the migration CPIs on or off
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCpiTelemetryCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can set the telemetry
+    #[account(
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_set_cpi_telemetry(ctx: Context<SetCpiTelemetryCtx>, enabled: bool) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    config.cpi_telemetry = enabled.into();
+
+    emit_cpi!(EvtSetCpiTelemetry {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        enabled,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    errors::AmmError,
+    events::EvtSetCreatorWhitelistMode,
+    states::{Config, ProtocolAuthority},
+};
+
+/// Accounts to restrict the curve creation of a config to whitelisted creators, or lift it
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCreatorWhitelistModeCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can set the whitelist mode
+    #[account(
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_set_creator_whitelist_mode(
+    ctx: Context<SetCreatorWhitelistModeCtx>,
+    enabled: bool,
+) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    config.creator_whitelist_mode = enabled.into();
+
+    emit_cpi!(EvtSetCreatorWhitelistMode {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        enabled,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    errors::AmmError,
+    events::EvtSetCurveTemplate,
+    states::{Config, ProtocolAuthority},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCurveTemplateCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can publish curve templates
+    #[account(
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_set_curve_template(
+    ctx: Context<SetCurveTemplateCtx>,
+    curve_template_hash: [u8; 32],
+) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let old_curve_template_hash =
+        std::mem::replace(&mut config.curve_template_hash, curve_template_hash);
+
+    emit_cpi!(EvtSetCurveTemplate {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        old_curve_template_hash,
+        new_curve_template_hash: curve_template_hash,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    errors::AmmError,
+    events::EvtSetDammConfig,
+    states::{Config, ProtocolAuthority},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetDammConfigCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// CHECK: the new approved DAMM v2 config, validated against the pool requirements at
+    /// migration time
+    #[account(owner = damm_v2::ID @ AmmError::InvalidConfigAccount)]
+    pub damm_config: UncheckedAccount<'info>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can change the approved DAMM config
+    #[account(
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_set_damm_config(ctx: Context<SetDammConfigCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let old_damm_config = config.damm_config;
+    config.damm_config = ctx.accounts.damm_config.key();
+
+    emit_cpi!(EvtSetDammConfig {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        old_damm_config,
+        new_damm_config: config.damm_config,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_fee_type_admin, const_pda,
+    errors::AmmError,
+    events::EvtSetFeeType,
+    states::{BondingCurve, Config, FeeType, ProtocolAuthority},
+};
+
+/// Accounts for the fee type admin to switch where the creator part of a curve's trading fee
+/// accrues
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetFeeTypeCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(mut, has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only the fee type admin can switch the fee type
+    #[account(
+        constraint = assert_eq_fee_type_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_set_fee_type(ctx: Context<SetFeeTypeCtx>, fee_type: u8) -> Result<()> {
+    let fee_type = FeeType::try_from(fee_type).map_err(|_| AmmError::InvalidFeeType)?;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+    let old_fee_type = curve.fee_type;
+    curve.set_fee_type(fee_type)?;
+
+    emit_cpi!(EvtSetFeeType {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        curve: ctx.accounts.curve.key(),
+        old_fee_type,
+        new_fee_type: curve.fee_type,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    errors::AmmError,
+    events::EvtSetPause,
+    states::{BondingCurve, Config, ProtocolAuthority},
+};
+
+/// Accounts for the emergency brake, unlike the trading pause it also halts curve creation and
+/// rejects with its own errors
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPauseCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// the curve to pause, the whole config is paused if not present
+    #[account(mut, has_one = config)]
+    pub curve: Option<AccountLoader<'info, BondingCurve>>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can pause
+    #[account(
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_set_pause(ctx: Context<SetPauseCtx>, paused: bool) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    match ctx.accounts.curve {
+        Some(ref curve) => curve.load_mut()?.paused = paused.into(),
+        None => config.paused = paused.into(),
+    }
+
+    emit_cpi!(EvtSetPause {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        curve: ctx.accounts.curve.as_ref().map(|curve| curve.key()),
+        paused,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::TEST_HOOKS_PREFIX,
+    errors::AmmError,
+    states::{FaultPoint, ProtocolAuthority, TestHooks},
+};
+
+#[derive(Accounts)]
+pub struct SetTestHooksCtx<'info> {
+    #[account(
+        init_if_needed,
+        seeds = [TEST_HOOKS_PREFIX],
+        bump,
+        payer = admin,
+        space = 8 + TestHooks::INIT_SPACE,
+    )]
+    pub test_hooks: AccountLoader<'info, TestHooks>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can set the test hooks
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_set_test_hooks(
+    ctx: Context<SetTestHooksCtx>,
+    fault_point: u8,
+    clock_skew_seconds: i64,
+) -> Result<()> {
+    FaultPoint::try_from(fault_point).map_err(|_| AmmError::InvalidFaultPoint)?;
+
+    let mut test_hooks = ctx.accounts.test_hooks.load_mut()?;
+    test_hooks.fault_point = fault_point;
+    test_hooks.clock_skew_seconds = clock_skew_seconds;
+
+    Ok(())
+}
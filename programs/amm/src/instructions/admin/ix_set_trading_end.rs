@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    errors::AmmError,
+    events::EvtSetTradingEnd,
+    states::{BondingCurve, Config, ProtocolAuthority},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetTradingEndCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(mut, has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can set the trading end of a curve
+    #[account(
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_set_trading_end(
+    ctx: Context<SetTradingEndCtx>,
+    trading_end_timestamp: u64,
+) -> Result<()> {
+    require!(
+        trading_end_timestamp == 0 || trading_end_timestamp > Clock::get()?.unix_timestamp as u64,
+        AmmError::InvalidTradingEndTimestamp
+    );
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+    let old_trading_end_timestamp = curve.trading_end_timestamp;
+    curve.set_trading_end_timestamp(trading_end_timestamp);
+
+    emit_cpi!(EvtSetTradingEnd {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        curve: ctx.accounts.curve.key(),
+        old_trading_end_timestamp,
+        new_trading_end_timestamp: trading_end_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    errors::AmmError,
+    events::EvtSetTradingPause,
+    states::{BondingCurve, Config, ProtocolAuthority, TradingPause},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetTradingPauseCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// the curve to pause, the whole config is paused if not present
+    #[account(mut, has_one = config)]
+    pub curve: Option<AccountLoader<'info, BondingCurve>>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can pause trading
+    #[account(
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_set_trading_pause(ctx: Context<SetTradingPauseCtx>, trading_pause: u8) -> Result<()> {
+    TradingPause::try_from(trading_pause).map_err(|_| AmmError::InvalidTradingPause)?;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let old_trading_pause = match ctx.accounts.curve {
+        Some(ref curve) => {
+            let mut curve = curve.load_mut()?;
+            std::mem::replace(&mut curve.trading_pause, trading_pause)
+        }
+        None => std::mem::replace(&mut config.trading_pause, trading_pause),
+    };
+
+    emit_cpi!(EvtSetTradingPause {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        curve: ctx.accounts.curve.as_ref().map(|curve| curve.key()),
+        old_trading_pause,
+        new_trading_pause: trading_pause,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    const_pda,
+    errors::AmmError,
+    events::{EvtAcceptAuthority, EvtTransferAuthority},
+    states::{AuthorityType, ProtocolAuthority},
+};
+
+/// Accounts to nominate the next key of a protocol authority role
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferAuthorityCtx<'info> {
+    #[account(mut, address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// current key of the role, or the global authority
+    pub authority: Signer<'info>,
+}
+
+pub fn handle_transfer_authority(
+    ctx: Context<TransferAuthorityCtx>,
+    authority_type: u8,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let authority_type =
+        AuthorityType::try_from(authority_type).map_err(|_| AmmError::InvalidAuthorityType)?;
+    let authority = ctx.accounts.authority.key();
+
+    let mut protocol_authority = ctx.accounts.protocol_authority.load_mut()?;
+    require!(
+        protocol_authority.get_authority(authority_type) == authority
+            || protocol_authority.is_global_authority(authority),
+        AmmError::Unauthorized
+    );
+    protocol_authority.transfer(authority_type, new_authority);
+
+    emit_cpi!(EvtTransferAuthority {
+        authority_type: authority_type.into(),
+        authority,
+        pending_authority: new_authority,
+    });
+
+    Ok(())
+}
+
+/// Accounts for a nominated key to take over its protocol authority role
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAuthorityCtx<'info> {
+    #[account(mut, address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// key nominated by the transfer
+    pub new_authority: Signer<'info>,
+}
+
+pub fn handle_accept_authority(ctx: Context<AcceptAuthorityCtx>, authority_type: u8) -> Result<()> {
+    let authority_type =
+        AuthorityType::try_from(authority_type).map_err(|_| AmmError::InvalidAuthorityType)?;
+    let new_authority = ctx.accounts.new_authority.key();
+
+    let old_authority = ctx
+        .accounts
+        .protocol_authority
+        .load_mut()?
+        .accept(authority_type, new_authority)?;
+
+    emit_cpi!(EvtAcceptAuthority {
+        authority_type: authority_type.into(),
+        old_authority,
+        new_authority,
+    });
+
+    Ok(())
+}
@@ -1,7 +1,59 @@
 pub mod auth;
+pub mod ix_add_partner;
+pub mod ix_add_whitelisted_creator;
+pub mod ix_cancel_config_change;
+pub mod ix_claim_meme_fee;
+pub mod ix_claim_migration_fee;
 pub mod ix_claim_protocol_fee;
+pub mod ix_claim_protocol_fee_batch;
 pub mod ix_create_config;
+pub mod ix_delegate_migration_authority;
+pub mod ix_execute_config_change;
+pub mod ix_initialize_authority;
+pub mod ix_migrate_config;
+pub mod ix_propose_config_change;
+pub mod ix_remove_partner;
+pub mod ix_remove_whitelisted_creator;
+pub mod ix_rotate_fee_claimer;
+pub mod ix_set_config_label;
+pub mod ix_set_cpi_telemetry;
+pub mod ix_set_creator_whitelist_mode;
+pub mod ix_set_curve_template;
+pub mod ix_set_damm_config;
+pub mod ix_set_fee_type;
+pub mod ix_set_pause;
+#[cfg(feature = "test-hooks")]
+pub mod ix_set_test_hooks;
+pub mod ix_set_trading_end;
+pub mod ix_set_trading_pause;
+pub mod ix_transfer_authority;
 
 pub use auth::*;
+pub use ix_add_partner::*;
+pub use ix_add_whitelisted_creator::*;
+pub use ix_cancel_config_change::*;
+pub use ix_claim_meme_fee::*;
+pub use ix_claim_migration_fee::*;
 pub use ix_claim_protocol_fee::*;
+pub use ix_claim_protocol_fee_batch::*;
 pub use ix_create_config::*;
+pub use ix_delegate_migration_authority::*;
+pub use ix_execute_config_change::*;
+pub use ix_initialize_authority::*;
+pub use ix_migrate_config::*;
+pub use ix_propose_config_change::*;
+pub use ix_remove_partner::*;
+pub use ix_remove_whitelisted_creator::*;
+pub use ix_rotate_fee_claimer::*;
+pub use ix_set_config_label::*;
+pub use ix_set_cpi_telemetry::*;
+pub use ix_set_creator_whitelist_mode::*;
+pub use ix_set_curve_template::*;
+pub use ix_set_damm_config::*;
+pub use ix_set_fee_type::*;
+pub use ix_set_pause::*;
+#[cfg(feature = "test-hooks")]
+pub use ix_set_test_hooks::*;
+pub use ix_set_trading_end::*;
+pub use ix_set_trading_pause::*;
+pub use ix_transfer_authority::*;
@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::seeds::{BONUS_POOL_PREFIX, PROTECTION_PREFIX},
+    errors::AmmError,
+    events::EvtClaimBonus,
+    safe_math::SafeMath,
+    states::{BondingCurve, BonusPool, Config, MigrationStatus, Protection},
+    utils::token::transfer_from_curve,
+};
+
+/// Accounts for an early buyer to claim their share of the bonus pool
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimBonusCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config the bonding curve belongs to
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [
+            BONUS_POOL_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        has_one = curve,
+        constraint = bonus_pool.load()?.quote_vault == bonus_vault.key() @ AmmError::InvalidAccount,
+    )]
+    pub bonus_pool: AccountLoader<'info, BonusPool>,
+
+    #[account(
+        mut,
+        seeds = [
+            PROTECTION_PREFIX,
+            curve.key().as_ref(),
+            user.key().as_ref(),
+        ],
+        bump,
+        has_one = curve,
+        constraint = protection.load()?.owner == user.key() @ AmmError::Unauthorized,
+    )]
+    pub protection: AccountLoader<'info, Protection>,
+
+    /// User's base token account, only the early bought base it still holds earns the bonus
+    #[account(
+        token::authority = user,
+        constraint = user_base_token_account.mint == curve.load()?.base_mint @ AmmError::InvalidAccount,
+    )]
+    pub user_base_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account holding the bonus deposits
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub bonus_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// User's token account to receive the bonus
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = quote_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_quote_program,
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_claim_bonus(ctx: Context<ClaimBonusCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let curve = ctx.accounts.curve.load()?;
+    require!(
+        curve.get_migration_progress()? != MigrationStatus::PreBondingCurve,
+        AmmError::PoolIsIncompleted
+    );
+
+    let mut bonus_pool = ctx.accounts.bonus_pool.load_mut()?;
+    let mut protection = ctx.accounts.protection.load_mut()?;
+    require!(!protection.is_bonus_claimed(), AmmError::NothingToClaim);
+    // sells made without the protection accounts, or routed through another curve, were never
+    // settled, the share only counts what is still held
+    bonus_pool.settle_early_buys(
+        protection.settle_early_buys(ctx.accounts.user_base_token_account.amount)?,
+    )?;
+
+    let bonus_amount = bonus_pool
+        .get_bonus_amount(protection.early_buy_quote_amount)?
+        .min(
+            bonus_pool
+                .total_deposit
+                .safe_sub(bonus_pool.total_claimed)?,
+        );
+    require!(bonus_amount > 0, AmmError::NothingToClaim);
+
+    protection.mark_bonus_claimed();
+    bonus_pool.claim(bonus_amount)?;
+
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.bonus_vault,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.token_quote_program,
+        bonus_amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    emit_cpi!(EvtClaimBonus {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        user: ctx.accounts.user.key(),
+        early_buy_quote_amount: protection.early_buy_quote_amount,
+        bonus_amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::seeds::{BONUS_POOL_PREFIX, TOKEN_VAULT_PREFIX},
+    errors::AmmError,
+    events::EvtDepositBonusPool,
+    states::{BondingCurve, BonusPool, Config, MigrationStatus},
+    utils::{sync_native_if_needed, transfer_from_user, wrap_sol},
+};
+
+/// Accounts for creator to fund the early buyer bonus pool
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DepositBonusPoolCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config the bonding curve belongs to
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(has_one = creator, has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// The bonus pool PDA of the curve
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + BonusPool::INIT_SPACE,
+        seeds = [
+            BONUS_POOL_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub bonus_pool: AccountLoader<'info, BonusPool>,
+
+    /// Quote token vault holding the bonus deposits
+    #[account(
+        init_if_needed,
+        seeds = [
+            TOKEN_VAULT_PREFIX,
+            quote_mint.key().as_ref(),
+            bonus_pool.key().as_ref(),
+        ],
+        token::mint = quote_mint,
+        token::authority = curve_authority,
+        token::token_program = token_quote_program,
+        payer = creator,
+        bump,
+    )]
+    pub bonus_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Creator's token account funding the deposit, a WSOL account is topped up from the
+    /// creator's lamports
+    #[account(mut, token::mint = quote_mint, token::token_program = token_quote_program)]
+    pub creator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_deposit_bonus_pool(ctx: Context<DepositBonusPoolCtx>, amount: u64) -> Result<()> {
+    require!(amount > 0, AmmError::AmountIsZero);
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let curve = ctx.accounts.curve.load()?;
+    require!(
+        curve.get_migration_progress()? == MigrationStatus::PreBondingCurve,
+        AmmError::PoolIsCompleted
+    );
+
+    let mut bonus_pool = match ctx.accounts.bonus_pool.load_init() {
+        Ok(mut bonus_pool) => {
+            bonus_pool.init(ctx.accounts.curve.key(), ctx.accounts.bonus_vault.key());
+            bonus_pool
+        }
+        Err(_) => ctx.accounts.bonus_pool.load_mut()?,
+    };
+    bonus_pool.deposit(amount)?;
+
+    // lamports sent to a WSOL account count once synced, the creator's lamports wrap the rest
+    if ctx.accounts.creator_token_account.is_native.is_some() {
+        sync_native_if_needed(
+            &mut ctx.accounts.creator_token_account,
+            &ctx.accounts.token_quote_program,
+        )?;
+        let wrap_amount = amount.saturating_sub(ctx.accounts.creator_token_account.amount);
+        if wrap_amount > 0 {
+            wrap_sol(
+                ctx.accounts.creator.to_account_info(),
+                &mut ctx.accounts.creator_token_account,
+                ctx.accounts.system_program.to_account_info(),
+                &ctx.accounts.token_quote_program,
+                wrap_amount,
+            )?;
+        }
+    }
+
+    transfer_from_user(
+        &ctx.accounts.creator,
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.creator_token_account,
+        &ctx.accounts.bonus_vault,
+        &ctx.accounts.token_quote_program,
+        amount,
+    )?;
+
+    emit_cpi!(EvtDepositBonusPool {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        creator: ctx.accounts.creator.key(),
+        amount,
+        total_deposit: bonus_pool.total_deposit,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::seeds::BONUS_POOL_PREFIX,
+    errors::AmmError,
+    events::EvtWithdrawBonusPool,
+    states::{BondingCurve, BonusPool, Config, MigrationStatus},
+    utils::token::transfer_from_curve,
+};
+
+/// Accounts for creator to take back a bonus pool nobody qualified for
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawBonusPoolCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config the bonding curve belongs to
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(has_one = creator, has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [
+            BONUS_POOL_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        has_one = curve,
+        constraint = bonus_pool.load()?.quote_vault == bonus_vault.key() @ AmmError::InvalidAccount,
+    )]
+    pub bonus_pool: AccountLoader<'info, BonusPool>,
+
+    /// The vault token account holding the bonus deposits
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub bonus_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Creator's token account to receive the withdrawn deposit
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = quote_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_quote_program,
+    )]
+    pub creator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_withdraw_bonus_pool(ctx: Context<WithdrawBonusPoolCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let curve = ctx.accounts.curve.load()?;
+    require!(
+        curve.get_migration_progress()? != MigrationStatus::PreBondingCurve,
+        AmmError::PoolIsIncompleted
+    );
+
+    let mut bonus_pool = ctx.accounts.bonus_pool.load_mut()?;
+    // once somebody qualified, the whole deposit belongs to the early buyers
+    require!(
+        bonus_pool.total_eligible_quote_amount == 0,
+        AmmError::NotPermitToDoThisAction
+    );
+
+    let amount = bonus_pool.withdraw_unallocated();
+    require!(amount > 0, AmmError::NothingToClaim);
+
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.bonus_vault,
+        &ctx.accounts.creator_token_account,
+        &ctx.accounts.token_quote_program,
+        amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    emit_cpi!(EvtWithdrawBonusPool {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        creator: ctx.accounts.creator.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,7 @@
+pub mod ix_claim_bonus;
+pub mod ix_deposit_bonus_pool;
+pub mod ix_withdraw_bonus_pool;
+
+pub use ix_claim_bonus::*;
+pub use ix_deposit_bonus_pool::*;
+pub use ix_withdraw_bonus_pool::*;
@@ -9,15 +9,19 @@ use anchor_spl::{
 
 use crate::events::EvtClaimCashback;
 use crate::{
-    constants::{cashback::CASHBACK_CLAIM_COOLDOWN, seeds::CASHBACK_PREFIX},
+    const_pda,
+    constants::seeds::CASHBACK_PREFIX,
     errors::AmmError,
-    states::CashbackAccount,
+    safe_math::SafeMath,
+    states::{CashbackAccount, CashbackConfig},
+    utils::{is_token_program_of, sync_native_if_needed},
 };
 
 #[event_cpi]
 #[derive(Accounts)]
 pub struct ClaimCashback<'info> {
-    #[account(mut)]
+    /// The cashback owner. It does not pay for anything, so a relayer can be the fee payer of
+    /// the transaction while the user only signs.
     pub user: Signer<'info>,
 
     #[account(
@@ -27,49 +31,78 @@ pub struct ClaimCashback<'info> {
             user.key().as_ref()
         ],
         bump,
-        constraint = cashback_account.load()?.owner == user.key() @ AmmError::Unauthorized
+        constraint = cashback_account.load()?.owner == user.key() @ AmmError::Unauthorized,
+        constraint = is_token_program_of(
+            &quote_mint.to_account_info(),
+            &token_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
     )]
     pub cashback_account: AccountLoader<'info, CashbackAccount>,
 
-    /// WSOL mint
-    pub wsol_mint: InterfaceAccount<'info, MintInterface>,
+    /// Cashback settings, the claim cooldown of the tier comes from it
+    #[account(address = const_pda::cashback_config::ID)]
+    pub cashback_config: AccountLoader<'info, CashbackConfig>,
 
-    /// WSOL vault for the cashback account (ATA)
+    /// Quote mint of the cashback vault to claim, every quote mint has its own vault
+    pub quote_mint: InterfaceAccount<'info, MintInterface>,
+
+    /// Cashback vault of the quote mint (ATA of the cashback account)
     #[account(
         mut,
-        associated_token::mint = wsol_mint,
+        associated_token::mint = quote_mint,
         associated_token::authority = cashback_account,
         associated_token::token_program = token_program,
     )]
-    pub wsol_vault: InterfaceAccount<'info, TokenAccountInterface>,
+    pub cashback_vault: InterfaceAccount<'info, TokenAccountInterface>,
 
-    /// User's WSOL token account to receive the cashback
+    /// User's token account of the quote mint to receive the cashback
     #[account(
         mut,
-        token::mint = wsol_mint,
+        token::mint = quote_mint,
         token::authority = user,
     )]
-    pub user_wsol_account: InterfaceAccount<'info, TokenAccountInterface>,
+    pub user_token_account: InterfaceAccount<'info, TokenAccountInterface>,
+
+    /// Optional token account of the quote mint of the relayer that receives `relayer_tip`
+    #[account(
+        mut,
+        token::mint = quote_mint,
+    )]
+    pub relayer_token_account: Option<InterfaceAccount<'info, TokenAccountInterface>>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn handle_claim_cashback(ctx: Context<ClaimCashback>) -> Result<()> {
+pub fn handle_claim_cashback(ctx: Context<ClaimCashback>, relayer_tip: u64) -> Result<()> {
+    // lamports sent straight to a WSOL vault are claimable too
+    sync_native_if_needed(
+        &mut ctx.accounts.cashback_vault,
+        &ctx.accounts.token_program,
+    )?;
+
     let mut cashback_account = ctx.accounts.cashback_account.load_mut()?;
+    let cashback_config = ctx.accounts.cashback_config.load()?;
     let current_timestamp = Clock::get()?.unix_timestamp;
-    let time_since_last_claim = current_timestamp - cashback_account.last_claim_timestamp;
 
+    // the cooldown of the current tier, higher tiers can claim more often
     require!(
-        time_since_last_claim >= CASHBACK_CLAIM_COOLDOWN,
+        cashback_account.can_claim(current_timestamp, &cashback_config)?,
         AmmError::ClaimCooldownNotMet
     );
 
-    // Get claimable amounts from both vaults
-    let wsol_claimable = ctx.accounts.wsol_vault.amount;
+    let claimable = ctx.accounts.cashback_vault.amount;
 
-    require!(wsol_claimable > 0, AmmError::NoCashbackToClaim);
+    require!(claimable > 0, AmmError::NoCashbackToClaim);
+
+    // The tip is part of the signed instruction data, so the user agreed to it
+    require!(
+        relayer_tip <= claimable
+            && (relayer_tip == 0 || ctx.accounts.relayer_token_account.is_some()),
+        AmmError::InvalidRelayerTip
+    );
+    let user_claim_amount = claimable.safe_sub(relayer_tip)?;
 
     // Get the bump for cashback account PDA
     let cashback_bump = ctx.bumps.cashback_account;
@@ -80,31 +113,56 @@ pub fn handle_claim_cashback(ctx: Context<ClaimCashback>) -> Result<()> {
         &[cashback_bump],
     ];
 
-    // Transfer WSOL if available
-    if wsol_claimable > 0 {
+    // Transfer the cashback if available
+    if user_claim_amount > 0 {
         transfer_checked(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 TransferChecked {
-                    from: ctx.accounts.wsol_vault.to_account_info(),
-                    to: ctx.accounts.user_wsol_account.to_account_info(),
+                    from: ctx.accounts.cashback_vault.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
                     authority: ctx.accounts.cashback_account.to_account_info(),
-                    mint: ctx.accounts.wsol_mint.to_account_info(),
+                    mint: ctx.accounts.quote_mint.to_account_info(),
                 },
                 &[signer_seeds],
             ),
-            wsol_claimable,
-            ctx.accounts.wsol_mint.decimals,
+            user_claim_amount,
+            ctx.accounts.quote_mint.decimals,
         )?;
-        msg!("Claimed {} WSOL cashback", wsol_claimable);
+        msg!("Claimed {} cashback", user_claim_amount);
+    }
+
+    // Pay the relayer out of the claimed cashback
+    if let Some(ref relayer_token_account) = ctx.accounts.relayer_token_account {
+        if relayer_tip > 0 {
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.cashback_vault.to_account_info(),
+                        to: relayer_token_account.to_account_info(),
+                        authority: ctx.accounts.cashback_account.to_account_info(),
+                        mint: ctx.accounts.quote_mint.to_account_info(),
+                    },
+                    &[signer_seeds],
+                ),
+                relayer_tip,
+                ctx.accounts.quote_mint.decimals,
+            )?;
+            msg!("Paid {} relayer tip", relayer_tip);
+        }
     }
 
-    // Update last claim timestamp
-    cashback_account.update_claim_timestamp()?;
+    // Update last claim timestamp and the lifetime claimed amount
+    cashback_account.record_claim(claimable, &cashback_config)?;
 
+    ctx.accounts.cashback_vault.reload()?;
     emit_cpi!(EvtClaimCashback {
         owner: user_key,
-        wsol_claim_amount: wsol_claimable,
+        quote_mint: ctx.accounts.quote_mint.key(),
+        claim_amount: user_claim_amount,
+        relayer_tip,
+        vault_balance: ctx.accounts.cashback_vault.amount,
     });
 
     Ok(())
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token::native_mint;
+
+use crate::{
+    const_pda,
+    constants::seeds::CASHBACK_PREFIX,
+    errors::AmmError,
+    events::EvtClaimCashback,
+    safe_math::SafeMath,
+    states::{CashbackAccount, CashbackConfig},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimNativeCashback<'info> {
+    /// The cashback owner, receives the claim as lamports. Like token claims, a relayer can be
+    /// the fee payer of the transaction while the user only signs
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            CASHBACK_PREFIX,
+            user.key().as_ref()
+        ],
+        bump,
+        constraint = cashback_account.load()?.owner == user.key() @ AmmError::Unauthorized,
+    )]
+    pub cashback_account: AccountLoader<'info, CashbackAccount>,
+
+    /// Cashback settings, the claim cooldown of the tier comes from it
+    #[account(address = const_pda::cashback_config::ID)]
+    pub cashback_config: AccountLoader<'info, CashbackConfig>,
+
+    /// CHECK: optional relayer receiving `relayer_tip` as lamports
+    #[account(mut)]
+    pub relayer: Option<UncheckedAccount<'info>>,
+}
+
+/// Claim the lamports of the native cashback mode, everything the cashback account holds above
+/// its rent exemption
+pub fn handle_claim_native_cashback(
+    ctx: Context<ClaimNativeCashback>,
+    relayer_tip: u64,
+) -> Result<()> {
+    let cashback_info = ctx.accounts.cashback_account.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(cashback_info.data_len());
+    let claimable = cashback_info.lamports().saturating_sub(rent_exempt);
+
+    let mut cashback_account = ctx.accounts.cashback_account.load_mut()?;
+    let cashback_config = ctx.accounts.cashback_config.load()?;
+    let current_timestamp = Clock::get()?.unix_timestamp;
+
+    // claims of the token vaults and of the lamports share the cooldown
+    require!(
+        cashback_account.can_claim(current_timestamp, &cashback_config)?,
+        AmmError::ClaimCooldownNotMet
+    );
+
+    require!(claimable > 0, AmmError::NoCashbackToClaim);
+
+    require!(
+        relayer_tip <= claimable && (relayer_tip == 0 || ctx.accounts.relayer.is_some()),
+        AmmError::InvalidRelayerTip
+    );
+    let user_claim_amount = claimable.safe_sub(relayer_tip)?;
+
+    // the cashback account is owned by the program, its lamports move without a CPI
+    cashback_info.sub_lamports(claimable)?;
+    ctx.accounts.user.add_lamports(user_claim_amount)?;
+    if let Some(ref relayer) = ctx.accounts.relayer {
+        relayer.add_lamports(relayer_tip)?;
+    }
+    msg!(
+        "Claimed {} lamports cashback, {} relayer tip",
+        user_claim_amount,
+        relayer_tip
+    );
+
+    cashback_account.record_claim(claimable, &cashback_config)?;
+
+    emit_cpi!(EvtClaimCashback {
+        owner: ctx.accounts.user.key(),
+        quote_mint: native_mint::ID,
+        claim_amount: user_claim_amount,
+        relayer_tip,
+        vault_balance: 0,
+    });
+
+    Ok(())
+}
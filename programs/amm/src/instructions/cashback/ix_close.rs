@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{
+    close_account, transfer_checked, CloseAccount, Mint as MintInterface,
+    TokenAccount as TokenAccountInterface, TokenInterface, TransferChecked,
+};
+
+use crate::{
+    const_pda,
+    constants::seeds::CASHBACK_PREFIX,
+    errors::AmmError,
+    events::EvtCloseCashback,
+    states::{CashbackAccount, CashbackConfig},
+    utils::{is_token_program_of, sync_native_if_needed},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseCashback<'info> {
+    /// The cashback owner, receives the rent of the cashback account and its vault
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            CASHBACK_PREFIX,
+            user.key().as_ref()
+        ],
+        bump,
+        constraint = cashback_account.load()?.owner == user.key() @ AmmError::Unauthorized,
+        constraint = is_token_program_of(
+            &quote_mint.to_account_info(),
+            &token_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
+        close = user,
+    )]
+    pub cashback_account: AccountLoader<'info, CashbackAccount>,
+
+    /// Cashback settings, a last sweep of the vault has to wait for the claim cooldown
+    #[account(address = const_pda::cashback_config::ID)]
+    pub cashback_config: AccountLoader<'info, CashbackConfig>,
+
+    /// Quote mint of the cashback vault to close
+    pub quote_mint: InterfaceAccount<'info, MintInterface>,
+
+    /// Cashback vault of the quote mint (ATA of the cashback account). Vaults of other quote
+    /// mints stay open, recreating the cashback account gives access to them again
+    #[account(
+        mut,
+        associated_token::mint = quote_mint,
+        associated_token::authority = cashback_account,
+        associated_token::token_program = token_program,
+    )]
+    pub cashback_vault: InterfaceAccount<'info, TokenAccountInterface>,
+
+    /// User's token account of the quote mint, receives what is left in the vault. Only needed
+    /// when the vault isn't empty
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = user,
+    )]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccountInterface>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Close the cashback account and one of its vaults, the rent goes back to the user. What is
+/// left in the vault, or as lamports in the native cashback mode, is swept to the user like a
+/// claim, so it has to wait for the claim cooldown
+pub fn handle_close_cashback(ctx: Context<CloseCashback>) -> Result<()> {
+    sync_native_if_needed(
+        &mut ctx.accounts.cashback_vault,
+        &ctx.accounts.token_program,
+    )?;
+
+    let cashback_info = ctx.accounts.cashback_account.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(cashback_info.data_len());
+    let native_cashback = cashback_info.lamports().saturating_sub(rent_exempt);
+    let swept_amount = ctx.accounts.cashback_vault.amount;
+
+    if swept_amount > 0 || native_cashback > 0 {
+        let cashback_account = ctx.accounts.cashback_account.load()?;
+        let cashback_config = ctx.accounts.cashback_config.load()?;
+        require!(
+            cashback_account.can_claim(Clock::get()?.unix_timestamp, &cashback_config)?,
+            AmmError::ClaimCooldownNotMet
+        );
+    }
+
+    let cashback_bump = ctx.bumps.cashback_account;
+    let user_key = ctx.accounts.user.key();
+    let signer_seeds = &[CASHBACK_PREFIX, user_key.as_ref(), &[cashback_bump]];
+
+    if swept_amount > 0 {
+        let user_token_account = ctx
+            .accounts
+            .user_token_account
+            .as_ref()
+            .ok_or(AmmError::InvalidAccount)?;
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.cashback_vault.to_account_info(),
+                    to: user_token_account.to_account_info(),
+                    authority: ctx.accounts.cashback_account.to_account_info(),
+                    mint: ctx.accounts.quote_mint.to_account_info(),
+                },
+                &[signer_seeds],
+            ),
+            swept_amount,
+            ctx.accounts.quote_mint.decimals,
+        )?;
+    }
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.cashback_vault.to_account_info(),
+            destination: ctx.accounts.user.to_account_info(),
+            authority: ctx.accounts.cashback_account.to_account_info(),
+        },
+        &[signer_seeds],
+    ))?;
+
+    // the cashback account itself, native cashback included, is closed to the user by the
+    // `close` constraint
+    emit_cpi!(EvtCloseCashback {
+        owner: user_key,
+        quote_mint: ctx.accounts.quote_mint.key(),
+        swept_amount,
+        native_cashback,
+    });
+
+    Ok(())
+}
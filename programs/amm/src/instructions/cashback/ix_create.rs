@@ -7,7 +7,10 @@ use anchor_spl::{
 };
 
 use crate::{
-    constants::seeds::CASHBACK_PREFIX, events::EvtCreateCashback, states::CashbackAccount,
+    const_pda,
+    constants::seeds::CASHBACK_PREFIX,
+    events::EvtCreateCashback,
+    states::{CashbackAccount, CashbackConfig},
 };
 
 #[event_cpi]
@@ -31,18 +34,23 @@ pub struct CreateCashback<'info> {
     )]
     pub cashback_account: AccountLoader<'info, CashbackAccount>,
 
-    /// WSOL mint
-    pub wsol_mint: InterfaceAccount<'info, MintInterface>,
+    /// Cashback settings, the claim cooldown of the tier comes from it
+    #[account(address = const_pda::cashback_config::ID)]
+    pub cashback_config: AccountLoader<'info, CashbackConfig>,
 
-    /// WSOL vault for the cashback account (ATA)
+    /// Quote mint of the first cashback vault, vaults of other quote mints are created with
+    /// `create_cashback_vault`
+    pub quote_mint: InterfaceAccount<'info, MintInterface>,
+
+    /// Cashback vault of the quote mint (ATA of the cashback account)
     #[account(
         init,
         payer = payer,
-        associated_token::mint = wsol_mint,
+        associated_token::mint = quote_mint,
         associated_token::authority = cashback_account,
         associated_token::token_program = token_program,
     )]
-    pub wsol_vault: InterfaceAccount<'info, TokenAccountInterface>,
+    pub cashback_vault: InterfaceAccount<'info, TokenAccountInterface>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -51,7 +59,10 @@ pub struct CreateCashback<'info> {
 
 pub fn handle_create_cashback(ctx: Context<CreateCashback>) -> Result<()> {
     let mut cashback_account = ctx.accounts.cashback_account.load_init()?;
-    cashback_account.init(ctx.accounts.payer.key())?;
+    cashback_account.init(
+        ctx.accounts.payer.key(),
+        &*ctx.accounts.cashback_config.load()?,
+    )?;
 
     emit_cpi!(EvtCreateCashback {
         owner: ctx.accounts.payer.key(),
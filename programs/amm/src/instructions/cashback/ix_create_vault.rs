@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{
+        Mint as MintInterface, TokenAccount as TokenAccountInterface, TokenInterface,
+    },
+};
+
+use crate::{constants::seeds::CASHBACK_PREFIX, states::CashbackAccount};
+
+#[derive(Accounts)]
+pub struct CreateCashbackVault<'info> {
+    /// Address paying for the vault creation, anyone can open a vault for a user
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The user owning the cashback account
+    /// CHECK: We only need this for PDA derivation
+    pub user: AccountInfo<'info>,
+
+    /// The user's cashback account
+    #[account(
+        seeds = [
+            CASHBACK_PREFIX,
+            user.key().as_ref()
+        ],
+        bump,
+    )]
+    pub cashback_account: AccountLoader<'info, CashbackAccount>,
+
+    /// Quote mint of the new cashback vault
+    pub quote_mint: InterfaceAccount<'info, MintInterface>,
+
+    /// Cashback vault of the quote mint (ATA of the cashback account)
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = cashback_account,
+        associated_token::token_program = token_program,
+    )]
+    pub cashback_vault: InterfaceAccount<'info, TokenAccountInterface>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_create_cashback_vault(ctx: Context<CreateCashbackVault>) -> Result<()> {
+    msg!(
+        "Cashback vault {} of {}",
+        ctx.accounts.cashback_vault.key(),
+        ctx.accounts.quote_mint.key()
+    );
+    Ok(())
+}
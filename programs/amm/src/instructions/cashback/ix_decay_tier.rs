@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    const_pda,
+    constants::seeds::CASHBACK_PREFIX,
+    events::EvtDecayCashbackTier,
+    states::{CashbackAccount, CashbackConfig},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DecayCashbackTier<'info> {
+    /// Anyone can crank the decay
+    pub cranker: Signer<'info>,
+
+    /// The cashback account to decay
+    #[account(
+        mut,
+        seeds = [
+            CASHBACK_PREFIX,
+            user.key().as_ref()
+        ],
+        bump,
+    )]
+    pub cashback_account: AccountLoader<'info, CashbackAccount>,
+
+    /// Cashback settings, the claim cooldown of the tier comes from it
+    #[account(address = const_pda::cashback_config::ID)]
+    pub cashback_config: AccountLoader<'info, CashbackConfig>,
+
+    /// The user whose tier is decaying
+    /// CHECK: This is just for the PDA derivation
+    pub user: AccountInfo<'info>,
+}
+
+pub fn handle_decay_cashback_tier(ctx: Context<DecayCashbackTier>) -> Result<()> {
+    let mut cashback_account = ctx.accounts.cashback_account.load_mut()?;
+    let old_tier = cashback_account.current_tier;
+    let new_tier = cashback_account.decay_tier(
+        Clock::get()?.unix_timestamp,
+        &*ctx.accounts.cashback_config.load()?,
+    )?;
+
+    emit_cpi!(EvtDecayCashbackTier {
+        owner: ctx.accounts.user.key(),
+        old_tier,
+        new_tier,
+        last_trade_timestamp: cashback_account.last_trade_timestamp,
+    });
+
+    Ok(())
+}
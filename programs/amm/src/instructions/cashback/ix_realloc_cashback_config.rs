@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    errors::AmmError,
+    events::EvtReallocCashbackConfig,
+    states::{CashbackConfig, ProtocolAuthority},
+};
+
+/// Accounts to grow the cashback settings created on an older layout to the current one. The
+/// new fields are zeroed until the next `set_cashback_config`
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReallocCashbackConfig<'info> {
+    #[account(
+        mut,
+        address = const_pda::cashback_config::ID,
+        realloc = 8 + CashbackConfig::INIT_SPACE,
+        realloc::payer = admin,
+        realloc::zero = true,
+    )]
+    pub cashback_config: AccountLoader<'info, CashbackConfig>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can grow the cashback settings, pays for the larger account
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_realloc_cashback_config(ctx: Context<ReallocCashbackConfig>) -> Result<()> {
+    emit_cpi!(EvtReallocCashbackConfig {
+        size: ctx.accounts.cashback_config.to_account_info().data_len() as u64,
+    });
+
+    Ok(())
+}
@@ -8,18 +8,23 @@ use anchor_spl::{
 };
 
 use crate::{
-    assert_eq_admin,
-    constants::{cashback::CASHBACK_INACTIVE_PERIOD, seeds::CASHBACK_PREFIX},
+    assert_eq_admin, const_pda,
+    constants::seeds::CASHBACK_PREFIX,
     errors::AmmError,
-    states::CashbackAccount,
+    states::{CashbackAccount, ProtocolAuthority},
+    utils::sync_native_if_needed,
 };
 
 #[derive(Accounts)]
 pub struct ReclaimInactiveCashback<'info> {
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
     /// Address to be set as global authority.
     #[account(
         mut,
-        constraint = assert_eq_admin(global_authority.key()) @ AmmError::Unauthorized,
+        constraint = assert_eq_admin(&protocol_authority, global_authority.key())? @ AmmError::Unauthorized,
     )]
     pub global_authority: Signer<'info>,
 
@@ -38,24 +43,24 @@ pub struct ReclaimInactiveCashback<'info> {
     )]
     pub cashback_account: AccountLoader<'info, CashbackAccount>,
 
-    /// WSOL mint
-    pub wsol_mint: InterfaceAccount<'info, MintInterface>,
+    /// Quote mint of the cashback vault to reclaim, each quote mint is reclaimed separately
+    pub quote_mint: InterfaceAccount<'info, MintInterface>,
 
-    /// WSOL vault for the cashback account (ATA)
+    /// Cashback vault of the quote mint (ATA of the cashback account)
     #[account(
         mut,
-        associated_token::mint = wsol_mint,
+        associated_token::mint = quote_mint,
         associated_token::authority = cashback_account,
         associated_token::token_program = token_program,
     )]
-    pub wsol_vault: InterfaceAccount<'info, TokenAccountInterface>,
+    pub cashback_vault: InterfaceAccount<'info, TokenAccountInterface>,
 
-    /// The protocol fee recipient's WSOL token account
+    /// The protocol fee recipient's token account of the quote mint
     #[account(
         mut,
-        token::mint = wsol_mint,
+        token::mint = quote_mint,
     )]
-    pub fee_recipient_wsol_account: InterfaceAccount<'info, TokenAccountInterface>,
+    pub fee_recipient_token_account: InterfaceAccount<'info, TokenAccountInterface>,
 
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -64,22 +69,25 @@ pub struct ReclaimInactiveCashback<'info> {
 
 /// reclaim unclaimed cashback from an inactive account
 pub fn handle_reclaim_cashback(ctx: Context<ReclaimInactiveCashback>) -> Result<()> {
+    sync_native_if_needed(
+        &mut ctx.accounts.cashback_vault,
+        &ctx.accounts.token_program,
+    )?;
+
     let cashback_account = ctx.accounts.cashback_account.load()?;
     let current_timestamp = Clock::get()?.unix_timestamp;
 
-    // Check if account has been inactive for more than a year
-    let time_since_last_claim = current_timestamp - cashback_account.last_claim_timestamp;
+    // Check if account has been neither claimed from nor traded with for more than a year
     require!(
-        time_since_last_claim >= CASHBACK_INACTIVE_PERIOD,
+        cashback_account.is_inactive(current_timestamp),
         AmmError::AccountNotInactive
     );
 
-    // Get reclaimable amount from WSOL vault
-    let wsol_reclaimable = ctx.accounts.wsol_vault.amount;
+    let reclaimable = ctx.accounts.cashback_vault.amount;
 
-    require!(wsol_reclaimable > 0, AmmError::NoCashbackToClaim);
+    require!(reclaimable > 0, AmmError::NoCashbackToClaim);
 
-    // Transfer WSOL to protocol fee recipient
+    // Transfer the cashback to protocol fee recipient
     let cashback_bump = ctx.bumps.cashback_account;
     let inactive_user_key = ctx.accounts.inactive_user.key();
     let signer_seeds = &[
@@ -92,18 +100,22 @@ pub fn handle_reclaim_cashback(ctx: Context<ReclaimInactiveCashback>) -> Result<
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             TransferChecked {
-                from: ctx.accounts.wsol_vault.to_account_info(),
-                to: ctx.accounts.fee_recipient_wsol_account.to_account_info(),
+                from: ctx.accounts.cashback_vault.to_account_info(),
+                to: ctx.accounts.fee_recipient_token_account.to_account_info(),
                 authority: ctx.accounts.cashback_account.to_account_info(),
-                mint: ctx.accounts.wsol_mint.to_account_info(),
+                mint: ctx.accounts.quote_mint.to_account_info(),
             },
             &[signer_seeds],
         ),
-        wsol_reclaimable,
-        ctx.accounts.wsol_mint.decimals,
+        reclaimable,
+        ctx.accounts.quote_mint.decimals,
     )?;
 
-    msg!("Reclaimed {} WSOL from inactive account", wsol_reclaimable);
+    msg!(
+        "Reclaimed {} of {} from inactive account",
+        reclaimable,
+        ctx.accounts.quote_mint.key()
+    );
 
     Ok(())
 }
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    const_pda,
+    constants::seeds::CASHBACK_PREFIX,
+    events::EvtRefreshCashbackTier,
+    states::{CashbackAccount, CashbackConfig},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefreshCashbackTier<'info> {
+    /// Anyone can crank the refresh
+    pub cranker: Signer<'info>,
+
+    /// The cashback account to refresh
+    #[account(
+        mut,
+        seeds = [
+            CASHBACK_PREFIX,
+            user.key().as_ref()
+        ],
+        bump,
+    )]
+    pub cashback_account: AccountLoader<'info, CashbackAccount>,
+
+    /// Cashback settings, the tier volume thresholds come from it
+    #[account(address = const_pda::cashback_config::ID)]
+    pub cashback_config: AccountLoader<'info, CashbackConfig>,
+
+    /// The user whose tier is refreshed
+    /// CHECK: This is just for the PDA derivation
+    pub user: AccountInfo<'info>,
+}
+
+pub fn handle_refresh_cashback_tier(ctx: Context<RefreshCashbackTier>) -> Result<()> {
+    let mut cashback_account = ctx.accounts.cashback_account.load_mut()?;
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    let old_tier = cashback_account.current_tier;
+    let new_tier =
+        cashback_account.refresh_tier(current_timestamp, &*ctx.accounts.cashback_config.load()?)?;
+
+    emit_cpi!(EvtRefreshCashbackTier {
+        owner: ctx.accounts.user.key(),
+        old_tier,
+        new_tier,
+        rolling_volume: cashback_account.get_rolling_volume(current_timestamp)?,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::{cashback::CASHBACK_TIER_COUNT, seeds::CASHBACK_CONFIG_PREFIX},
+    errors::AmmError,
+    events::EvtSetCashbackConfig,
+    states::{CashbackConfig, ProtocolAuthority},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCashbackConfig<'info> {
+    /// Cashback settings, created by the first call. Settings created on an older layout have
+    /// to go through `realloc_cashback_config` first
+    #[account(
+        init_if_needed,
+        seeds = [CASHBACK_CONFIG_PREFIX],
+        bump,
+        payer = admin,
+        space = 8 + CashbackConfig::INIT_SPACE,
+    )]
+    pub cashback_config: AccountLoader<'info, CashbackConfig>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can set the cashback settings
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_set_cashback_config(
+    ctx: Context<SetCashbackConfig>,
+    claim_cooldown_seconds: [i64; CASHBACK_TIER_COUNT],
+    tier_volume_thresholds: [u64; CASHBACK_TIER_COUNT],
+    fee_discount_basis_points: [u16; CASHBACK_TIER_COUNT],
+) -> Result<()> {
+    CashbackConfig::validate_claim_cooldowns(&claim_cooldown_seconds)?;
+    CashbackConfig::validate_tier_volume_thresholds(&tier_volume_thresholds)?;
+    CashbackConfig::validate_fee_discounts(&fee_discount_basis_points)?;
+
+    // the next claim timestamps and tiers of the cashback accounts are refreshed on their next
+    // write, claims check the new cooldowns right away
+    let mut cashback_config = match ctx.accounts.cashback_config.load_init() {
+        Ok(cashback_config) => cashback_config,
+        Err(_) => ctx.accounts.cashback_config.load_mut()?,
+    };
+    cashback_config.claim_cooldown_seconds = claim_cooldown_seconds;
+    cashback_config.tier_volume_thresholds = tier_volume_thresholds;
+    // swaps pick the new discounts up right away, when they pass the cashback config
+    cashback_config.fee_discount_basis_points = fee_discount_basis_points;
+
+    emit_cpi!(EvtSetCashbackConfig {
+        claim_cooldown_seconds,
+        tier_volume_thresholds,
+        fee_discount_basis_points,
+    });
+
+    Ok(())
+}
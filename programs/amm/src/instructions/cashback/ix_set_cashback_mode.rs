@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::seeds::CASHBACK_PREFIX,
+    errors::AmmError,
+    events::EvtSetCashbackMode,
+    states::{CashbackAccount, CashbackMode},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCashbackMode<'info> {
+    /// The cashback owner
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            CASHBACK_PREFIX,
+            user.key().as_ref()
+        ],
+        bump,
+        constraint = cashback_account.load()?.owner == user.key() @ AmmError::Unauthorized,
+    )]
+    pub cashback_account: AccountLoader<'info, CashbackAccount>,
+}
+
+/// Switching back to the token mode leaves the accrued lamports claimable with
+/// `claim_native_cashback`
+pub fn handle_set_cashback_mode(ctx: Context<SetCashbackMode>, cashback_mode: u8) -> Result<()> {
+    CashbackMode::try_from(cashback_mode).map_err(|_| AmmError::InvalidCashbackMode)?;
+
+    let mut cashback_account = ctx.accounts.cashback_account.load_mut()?;
+    cashback_account.cashback_mode = cashback_mode;
+
+    emit_cpi!(EvtSetCashbackMode {
+        owner: cashback_account.owner,
+        cashback_mode,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::seeds::REFERRAL_PREFIX,
+    errors::AmmError,
+    events::EvtSetReferrer,
+    states::{layout, CashbackAccount, ReferralAccount},
+};
+
+/// Accounts for a user to record who referred them, once
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetReferrerCtx<'info> {
+    /// The referral account PDA of the user
+    #[account(
+        init,
+        payer = user,
+        space = 8 + ReferralAccount::INIT_SPACE,
+        seeds = [
+            REFERRAL_PREFIX,
+            user.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub referral: AccountLoader<'info, ReferralAccount>,
+
+    /// Cashback account of the referrer, referral fees are paid to its token account
+    #[account(constraint = referrer_cashback.load()?.owner == referrer.key() @ AmmError::InvalidAccount)]
+    pub referrer_cashback: AccountLoader<'info, CashbackAccount>,
+
+    /// CHECK: the referrer wallet, checked against its cashback account
+    pub referrer: UncheckedAccount<'info>,
+
+    /// CHECK: referral account PDA of the referrer, extends the chain when it exists
+    #[account(
+        seeds = [
+            REFERRAL_PREFIX,
+            referrer.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub referrer_referral: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_set_referrer(ctx: Context<SetReferrerCtx>) -> Result<()> {
+    // the referrer's chain can't be left out, its PDA is checked and only skipped when empty
+    let referrer_referral = &ctx.accounts.referrer_referral;
+    let referrer_chain = if referrer_referral.data_is_empty() {
+        None
+    } else {
+        require_keys_eq!(
+            *referrer_referral.owner,
+            crate::ID,
+            AmmError::InvalidAccount
+        );
+        let data = referrer_referral.try_borrow_data()?;
+        require!(
+            data.len() == layout::referral_account::SIZE
+                && data.starts_with(layout::referral_account::DISCRIMINATOR),
+            AmmError::InvalidAccount
+        );
+        Some(bytemuck::pod_read_unaligned::<ReferralAccount>(
+            &data[layout::referral_account::DISCRIMINATOR.len()..],
+        ))
+    };
+
+    let mut referral = ctx.accounts.referral.load_init()?;
+    referral.init(
+        ctx.accounts.user.key(),
+        ctx.accounts.referrer.key(),
+        referrer_chain.as_ref(),
+    );
+    require!(!referral.is_self_referral(), AmmError::SelfReferral);
+
+    emit_cpi!(EvtSetReferrer {
+        user: referral.user,
+        l1_referrer: referral.l1_referrer,
+        l2_referrer: referral.l2_referrer,
+        l3_referrer: referral.l3_referrer,
+    });
+
+    Ok(())
+}
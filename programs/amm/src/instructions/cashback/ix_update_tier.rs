@@ -1,17 +1,24 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    assert_eq_admin, constants::seeds::CASHBACK_PREFIX, errors::AmmError,
-    events::EvtUpdateCashbackTier, states::CashbackAccount,
+    assert_eq_admin, const_pda,
+    constants::seeds::CASHBACK_PREFIX,
+    errors::AmmError,
+    events::EvtUpdateCashbackTier,
+    states::{CashbackAccount, CashbackConfig, ProtocolAuthority},
 };
 
 #[event_cpi]
 #[derive(Accounts)]
 pub struct UpdateCashbackTier<'info> {
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
     /// Admin who can update tiers
     #[account(
         mut,
-        constraint = assert_eq_admin(admin.key()) @ AmmError::Unauthorized,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
     )]
     pub admin: Signer<'info>,
 
@@ -26,20 +33,34 @@ pub struct UpdateCashbackTier<'info> {
     )]
     pub cashback_account: AccountLoader<'info, CashbackAccount>,
 
+    /// Cashback settings, the claim cooldown of the tier comes from it
+    #[account(address = const_pda::cashback_config::ID)]
+    pub cashback_config: AccountLoader<'info, CashbackConfig>,
+
     /// The user whose tier is being updated
     /// CHECK: This is just for the PDA derivation
     pub user: AccountInfo<'info>,
 }
 
-pub fn handle_update_cashback_tier(ctx: Context<UpdateCashbackTier>, new_tier: u8) -> Result<()> {
+/// A sequenced update is only applied if `update_seq` is above the last one, so a backend
+/// retrying updates can't double apply them or apply them out of order
+pub fn handle_update_cashback_tier(
+    ctx: Context<UpdateCashbackTier>,
+    new_tier: u8,
+    update_seq: Option<u64>,
+) -> Result<()> {
     let mut cashback_account = ctx.accounts.cashback_account.load_mut()?;
+    if let Some(update_seq) = update_seq {
+        cashback_account.record_tier_update_seq(update_seq)?;
+    }
     let old_tier = cashback_account.current_tier;
-    cashback_account.update_tier(new_tier)?;
+    cashback_account.update_tier(new_tier, &*ctx.accounts.cashback_config.load()?)?;
 
     emit_cpi!(EvtUpdateCashbackTier {
         owner: ctx.accounts.user.key(),
         old_tier,
         new_tier,
+        update_seq,
     });
 
     Ok(())
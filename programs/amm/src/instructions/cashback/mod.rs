@@ -1,9 +1,27 @@
 pub mod ix_claim;
+pub mod ix_claim_native;
+pub mod ix_close;
 pub mod ix_create;
+pub mod ix_create_vault;
+pub mod ix_decay_tier;
+pub mod ix_realloc_cashback_config;
 pub mod ix_reclaim;
+pub mod ix_refresh_tier;
+pub mod ix_set_cashback_config;
+pub mod ix_set_cashback_mode;
+pub mod ix_set_referrer;
 pub mod ix_update_tier;
 
 pub use ix_claim::*;
+pub use ix_claim_native::*;
+pub use ix_close::*;
 pub use ix_create::*;
+pub use ix_create_vault::*;
+pub use ix_decay_tier::*;
+pub use ix_realloc_cashback_config::*;
 pub use ix_reclaim::*;
+pub use ix_refresh_tier::*;
+pub use ix_set_cashback_config::*;
+pub use ix_set_cashback_mode::*;
+pub use ix_set_referrer::*;
 pub use ix_update_tier::*;
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::INSURANCE_FUND_PREFIX,
+    errors::AmmError,
+    events::EvtInsurancePayoutCancelled,
+    states::{Config, InsuranceFund, ProtocolAuthority},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelInsurancePayoutCtx<'info> {
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [
+            INSURANCE_FUND_PREFIX,
+            config.key().as_ref(),
+        ],
+        bump,
+        has_one = config,
+    )]
+    pub insurance_fund: AccountLoader<'info, InsuranceFund>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can cancel insurance payouts
+    #[account(
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_cancel_insurance_payout(ctx: Context<CancelInsurancePayoutCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut insurance_fund = ctx.accounts.insurance_fund.load_mut()?;
+    require!(
+        insurance_fund.has_pending_payout(),
+        AmmError::NothingToClaim
+    );
+    insurance_fund.clear_pending_payout();
+
+    emit_cpi!(EvtInsurancePayoutCancelled {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+    });
+
+    Ok(())
+}
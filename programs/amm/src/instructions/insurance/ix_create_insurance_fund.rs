@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::{INSURANCE_FUND_PREFIX, TOKEN_VAULT_PREFIX},
+    errors::AmmError,
+    events::EvtCreateInsuranceFund,
+    states::{Config, InsuranceFund, ProtocolAuthority},
+};
+
+/// Accounts to create the insurance fund of a config, it has to exist before protocol fees of a
+/// config with an insurance fund share can be claimed
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateInsuranceFundCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + InsuranceFund::INIT_SPACE,
+        seeds = [
+            INSURANCE_FUND_PREFIX,
+            config.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub insurance_fund: AccountLoader<'info, InsuranceFund>,
+
+    /// Quote token vault of the insurance fund
+    #[account(
+        init,
+        seeds = [
+            TOKEN_VAULT_PREFIX,
+            quote_mint.key().as_ref(),
+            insurance_fund.key().as_ref(),
+        ],
+        token::mint = quote_mint,
+        token::authority = curve_authority,
+        token::token_program = token_quote_program,
+        payer = admin,
+        bump,
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token
+    #[account(mint::token_program = token_quote_program)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can create insurance funds
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_create_insurance_fund(ctx: Context<CreateInsuranceFundCtx>) -> Result<()> {
+    ctx.accounts
+        .insurance_fund
+        .load_init()?
+        .init(ctx.accounts.config.key(), ctx.accounts.quote_vault.key());
+
+    emit_cpi!(EvtCreateInsuranceFund {
+        event_sequence: ctx.accounts.config.load_mut()?.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        insurance_fund: ctx.accounts.insurance_fund.key(),
+        quote_vault: ctx.accounts.quote_vault.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::INSURANCE_FUND_PREFIX,
+    errors::AmmError,
+    events::EvtInsurancePayoutExecuted,
+    states::{Config, InsuranceFund, ProtocolAuthority},
+    utils::token::transfer_from_curve,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ExecuteInsurancePayoutCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [
+            INSURANCE_FUND_PREFIX,
+            config.key().as_ref(),
+        ],
+        bump,
+        has_one = config,
+        has_one = quote_vault,
+        constraint = insurance_fund.load()?.pending_payout_recipient == recipient_token_account.key() @ AmmError::InvalidAccount,
+    )]
+    pub insurance_fund: AccountLoader<'info, InsuranceFund>,
+
+    /// The vault token account of the insurance fund
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token account receiving the payout
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can execute insurance payouts
+    #[account(
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handle_execute_insurance_payout(ctx: Context<ExecuteInsurancePayoutCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut insurance_fund = ctx.accounts.insurance_fund.load_mut()?;
+
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    require!(
+        insurance_fund.is_payout_executable(current_timestamp),
+        AmmError::InsurancePayoutTimelockNotMet
+    );
+    let amount = insurance_fund.execute_payout()?;
+
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.quote_vault,
+        &ctx.accounts.recipient_token_account,
+        &ctx.accounts.token_quote_program,
+        amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    emit_cpi!(EvtInsurancePayoutExecuted {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        recipient_token_account: ctx.accounts.recipient_token_account.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::INSURANCE_FUND_PREFIX,
+    errors::AmmError,
+    events::EvtInsurancePayoutProposed,
+    safe_math::SafeMath,
+    states::{Config, InsuranceFund, ProtocolAuthority},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeInsurancePayoutCtx<'info> {
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [
+            INSURANCE_FUND_PREFIX,
+            config.key().as_ref(),
+        ],
+        bump,
+        has_one = config,
+        has_one = quote_vault,
+    )]
+    pub insurance_fund: AccountLoader<'info, InsuranceFund>,
+
+    /// The vault token account of the insurance fund
+    #[account(token::mint = quote_mint, token::token_program = token_quote_program)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token account receiving the payout
+    #[account(token::mint = quote_mint, token::token_program = token_quote_program)]
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token
+    #[account(mint::token_program = token_quote_program)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can propose insurance payouts
+    #[account(
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handle_propose_insurance_payout(
+    ctx: Context<ProposeInsurancePayoutCtx>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, AmmError::AmountIsZero);
+    require!(
+        amount <= ctx.accounts.quote_vault.amount,
+        AmmError::NotEnoughLiquidity
+    );
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut insurance_fund = ctx.accounts.insurance_fund.load_mut()?;
+    require!(
+        !insurance_fund.has_pending_payout(),
+        AmmError::NotPermitToDoThisAction
+    );
+
+    // payouts go through the same timelock as config changes
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    let execute_after = current_timestamp.safe_add(config.param_change_delay_seconds)?;
+    let recipient_token_account = ctx.accounts.recipient_token_account.key();
+    insurance_fund.propose_payout(recipient_token_account, amount, execute_after);
+
+    emit_cpi!(EvtInsurancePayoutProposed {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        recipient_token_account,
+        amount,
+        execute_after,
+    });
+
+    Ok(())
+}
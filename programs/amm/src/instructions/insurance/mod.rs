@@ -0,0 +1,9 @@
+pub mod ix_cancel_insurance_payout;
+pub mod ix_create_insurance_fund;
+pub mod ix_execute_insurance_payout;
+pub mod ix_propose_insurance_payout;
+
+pub use ix_cancel_insurance_payout::*;
+pub use ix_create_insurance_fund::*;
+pub use ix_execute_insurance_payout::*;
+pub use ix_propose_insurance_payout::*;
@@ -3,11 +3,15 @@ use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 use crate::{
-    const_pda, errors::AmmError, events::EvtClaimCreatorTradingFee, states::BondingCurve,
-    utils::token::transfer_from_curve,
+    const_pda,
+    errors::AmmError,
+    events::EvtClaimCreatorTradingFee,
+    safe_math::SafeMath,
+    states::{BondingCurve, Config, ProtocolStats},
+    utils::token::{is_token_program_of, transfer_from_curve},
 };
 
-/// Accounts for creator to claim trading fees
+/// Accounts for the creator, or the fee recipient it set, to claim trading fees
 #[event_cpi]
 #[derive(Accounts)]
 pub struct ClaimCreatorFeeCtx<'info> {
@@ -17,32 +21,66 @@ pub struct ClaimCreatorFeeCtx<'info> {
     )]
     pub curve_authority: UncheckedAccount<'info>,
 
+    /// config the bonding curve belongs to
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
     #[account(
         mut,
         has_one = quote_vault,
-        has_one = creator,
+        has_one = config,
+        constraint = is_token_program_of(
+            &quote_mint.to_account_info(),
+            &token_quote_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
     )]
     pub curve: AccountLoader<'info, BondingCurve>,
 
-    /// Creator's token account to receive the claimed fees
+    /// CHECK: receiver of the creator fee, the creator unless it set a recipient
+    #[account(address = curve.load()?.get_creator_fee_recipient() @ AmmError::InvalidAccount)]
+    pub creator_fee_recipient: UncheckedAccount<'info>,
+
+    /// Recipient's token account to receive the claimed fees
     #[account(
         init_if_needed,
-        payer = creator,
+        payer = signer,
         associated_token::mint = quote_mint,
-        associated_token::authority = creator,
+        associated_token::authority = creator_fee_recipient,
         associated_token::token_program = token_quote_program,
     )]
-    pub creator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: fee claimer of the config
+    #[account(address = config.load()?.fee_claimer @ AmmError::InvalidAccount)]
+    pub fee_claimer: UncheckedAccount<'info>,
+
+    /// Fee claimer's token account to receive the protocol's cut
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = quote_mint,
+        associated_token::authority = fee_claimer,
+        associated_token::token_program = token_quote_program,
+    )]
+    pub fee_claimer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// The vault token account for output token
     #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
     pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    /// protocol wide stats, counts the claimed fees
+    #[account(mut, address = const_pda::protocol_stats::ID)]
+    pub protocol_stats: AccountLoader<'info, ProtocolStats>,
+
     /// The mint of quote token
     pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    #[account(mut)]
-    pub creator: Signer<'info>,
+    /// creator of the curve or its creator fee recipient
+    #[account(
+        mut,
+        constraint = curve.load()?.is_creator_fee_authority(&signer.key()) @ AmmError::Unauthorized,
+    )]
+    pub signer: Signer<'info>,
 
     /// Token quote program
     pub token_quote_program: Interface<'info, TokenInterface>,
@@ -51,25 +89,52 @@ pub struct ClaimCreatorFeeCtx<'info> {
 }
 
 pub fn handle_claim_creator_fee(ctx: Context<ClaimCreatorFeeCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
     let mut curve = ctx.accounts.curve.load_mut()?;
-    let quote_token_claim_amount = curve.claim_creator_fee();
+    let claimed_amount = curve.claim_creator_fee();
 
-    require!(quote_token_claim_amount > 0, AmmError::NothingToClaim);
+    require!(claimed_amount > 0, AmmError::NothingToClaim);
+    ctx.accounts
+        .protocol_stats
+        .load_mut()?
+        .record_creator_fee_claim(claimed_amount)?;
+
+    let protocol_fee_amount = config.get_creator_fee_protocol_amount(claimed_amount)?;
+    let quote_token_claim_amount = claimed_amount.safe_sub(protocol_fee_amount)?;
 
     transfer_from_curve(
         ctx.accounts.curve_authority.to_account_info(),
         &ctx.accounts.quote_mint,
         &ctx.accounts.quote_vault,
-        &ctx.accounts.creator_token_account,
+        &ctx.accounts.recipient_token_account,
         &ctx.accounts.token_quote_program,
         quote_token_claim_amount,
         const_pda::curve_authority::BUMP,
     )?;
 
+    if protocol_fee_amount > 0 {
+        transfer_from_curve(
+            ctx.accounts.curve_authority.to_account_info(),
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.fee_claimer_token_account,
+            &ctx.accounts.token_quote_program,
+            protocol_fee_amount,
+            const_pda::curve_authority::BUMP,
+        )?;
+    }
+
+    ctx.accounts.quote_vault.reload()?;
     emit_cpi!(EvtClaimCreatorTradingFee {
+        event_sequence: config.next_event_sequence()?,
         curve: ctx.accounts.curve.key(),
-        creator: ctx.accounts.creator.key(),
+        creator: curve.creator,
+        recipient: ctx.accounts.creator_fee_recipient.key(),
         quote_token_claim_amount,
+        protocol_fee_amount,
+        quote_vault_balance: ctx.accounts.quote_vault.amount,
+        remaining_protocol_fee: curve.protocol_fee,
+        remaining_creator_fee: curve.creator_fee,
     });
 
     Ok(())
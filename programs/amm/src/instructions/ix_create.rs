@@ -1,8 +1,9 @@
 use {
     anchor_lang::prelude::*,
     anchor_spl::{
-        token::{Mint, MintTo, Token, TokenAccount},
-        token_2022::spl_token_2022::instruction::AuthorityType,
+        associated_token::AssociatedToken,
+        token::{Mint, MintTo, Token, TokenAccount, TransferChecked},
+        token_2022::{spl_token_2022::instruction::AuthorityType, Token2022},
         token_interface::{
             Mint as MintInterface, TokenAccount as TokenAccountInterface, TokenInterface,
         },
@@ -13,12 +14,26 @@ use {
 use crate::{
     const_pda,
     constants::{
-        seeds::{CURVE_PREFIX, TOKEN_VAULT_PREFIX},
-        MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH, TOKEN_TOTAL_SUPPLY,
+        curve::{MAX_ACTIVATION_DELAY_SECONDS, MAX_ACTIVATION_DELAY_SLOTS},
+        seeds::{
+            CURVE_PREFIX, MINT_INDEX_PREFIX, PARTNER_PREFIX, PROTECTION_PREFIX, TOKEN_VAULT_PREFIX,
+            WHITELIST_ENTRY_PREFIX,
+        },
+        MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH,
     },
     errors::AmmError,
-    states::{BondingCurve, Config, CurveType, TokenType},
-    utils::{process_create_token_metadata, ProcessCreateTokenMetadataParams},
+    events::{EvtCreateProtection, EvtSwap},
+    params::swap::TradeDirection,
+    states::{
+        ActivationType, BondingCurve, Config, ConfigStats, CurveEventParams, CurveInitParams,
+        CurveType, MintIndex, Partner, Protection, ProtocolStats, TokenType, WhitelistEntry,
+    },
+    utils::{
+        is_token_program_of, process_create_token_2022_metadata, process_create_token_metadata,
+        record_curve_created, record_swap, transfer_from_user,
+        ProcessCreateToken2022MetadataParams, ProcessCreateTokenMetadataParams,
+    },
+    SwapParameters, VersionedSwapParameters,
 };
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -29,6 +44,26 @@ pub struct CreateCurveParams {
     pub symbol: String,
     /// URI for the token metadata
     pub uri: String,
+    /// hash of the curve template the creation follows, has to match the config's template
+    pub params_hash: Option<[u8; 32]>,
+    /// quote amount the creator buys with right after the curve is created, none skips the buy
+    pub initial_buy_amount_in: Option<u64>,
+    /// minimum base amount of the initial buy. The curve is fresh so its price is known
+    /// up front, none accepts any output
+    pub minimum_amount_out: Option<u64>,
+    /// creator's share of the DAMM position fees once migrated, in bps, up to the config's
+    /// bound. None takes no share
+    pub creator_lp_fee_share_basis_points: Option<u16>,
+    /// time buys close for a limited time launch, sells stay open for a grace period after it.
+    /// None trades until migration
+    pub trading_end_timestamp: Option<u64>,
+    /// timestamp or slot trading opens at for a scheduled launch, none trades from creation
+    pub activation_point: Option<u64>,
+    /// unit of the activation point (0: Timestamp, 1: Slot), none is Timestamp
+    pub activation_type: Option<u8>,
+    /// partner frontend the curve is created through, paid a share of the curve's protocol fee.
+    /// Has to be in the partner registry, none creates the curve without a partner
+    pub partner: Option<Pubkey>,
 }
 
 impl CreateCurveParams {
@@ -47,6 +82,87 @@ impl CreateCurveParams {
         );
         Ok(())
     }
+
+    /// A frontend can't drop or swap the template of a config that has one, nor claim a template
+    /// on a config without one
+    pub fn validate_template(&self, config: &Config) -> Result<()> {
+        let expected_hash = config
+            .has_curve_template()
+            .then_some(config.curve_template_hash);
+        require!(
+            self.params_hash == expected_hash,
+            AmmError::InvalidCurveTemplateHash
+        );
+        Ok(())
+    }
+
+    pub fn get_creator_lp_fee_share(&self, config: &Config) -> Result<u16> {
+        let creator_lp_fee_share_basis_points = self.creator_lp_fee_share_basis_points.unwrap_or(0);
+        require!(
+            creator_lp_fee_share_basis_points <= config.max_creator_lp_fee_share_basis_points,
+            AmmError::InvalidCreatorLpFeeShare
+        );
+        Ok(creator_lp_fee_share_basis_points)
+    }
+
+    /// Activation stored on the curve, a 0 point when none is set. Bounded so a launch can't be
+    /// scheduled out of reach
+    pub fn get_activation(
+        &self,
+        current_timestamp: u64,
+        current_slot: u64,
+    ) -> Result<(ActivationType, u64)> {
+        let activation_type = ActivationType::try_from(self.activation_type.unwrap_or_default())
+            .map_err(|_| AmmError::InvalidActivationType)?;
+        let Some(activation_point) = self.activation_point else {
+            return Ok((activation_type, 0));
+        };
+        let (current_point, max_delay) = match activation_type {
+            ActivationType::Timestamp => (current_timestamp, MAX_ACTIVATION_DELAY_SECONDS),
+            ActivationType::Slot => (current_slot, MAX_ACTIVATION_DELAY_SLOTS),
+        };
+        require!(
+            activation_point > current_point
+                && activation_point <= current_point.saturating_add(max_delay),
+            AmmError::InvalidActivationPoint
+        );
+        // a timestamp launch has to open before its trading end
+        if let (ActivationType::Timestamp, Some(trading_end_timestamp)) =
+            (activation_type, self.trading_end_timestamp)
+        {
+            require!(
+                activation_point < trading_end_timestamp,
+                AmmError::InvalidTradingEndTimestamp
+            );
+        }
+        Ok((activation_type, activation_point))
+    }
+
+    /// Partner stored on the curve with its fee share from the registry, all zeros when none is
+    /// set
+    pub fn get_partner(&self, partner_entry: Option<Partner>) -> Result<(Pubkey, u16)> {
+        let Some(partner) = self.partner else {
+            return Ok((Pubkey::default(), 0));
+        };
+        let partner_entry = partner_entry.ok_or(AmmError::PartnerNotApproved)?;
+        require!(
+            partner_entry.partner == partner,
+            AmmError::PartnerNotApproved
+        );
+        Ok((partner, partner_entry.fee_share_basis_points))
+    }
+
+    /// Trading end stored on the curve, 0 when none is set
+    pub fn get_trading_end_timestamp(&self, current_timestamp: u64) -> Result<u64> {
+        let Some(trading_end_timestamp) = self.trading_end_timestamp else {
+            return Ok(0);
+        };
+        require!(
+            trading_end_timestamp > current_timestamp,
+            AmmError::InvalidTradingEndTimestamp
+        );
+        Ok(trading_end_timestamp)
+    }
 }
 
 // To fix IDL generation: https://github.com/coral-xyz/anchor/issues/3209
@@ -60,12 +176,14 @@ pub fn min_key(left: &Pubkey, right: &Pubkey) -> [u8; 32] {
 
 #[event_cpi]
 #[derive(Accounts)]
+#[instruction(curve_params: CreateCurveParams)]
 pub struct CreateCurveCtx<'info> {
     /// Address paying for the bonding curve creation
     #[account(mut)]
     pub creator: Signer<'info>,
 
     /// config the boding curve belongs to
+    #[account(mut)]
     pub config: AccountLoader<'info, Config>,
 
     /// CHECK: curve authority
@@ -74,6 +192,15 @@ pub struct CreateCurveCtx<'info> {
     )]
     pub curve_authority: AccountInfo<'info>,
 
+    /// protocol wide stats, counts the curve and its initial buy
+    #[account(mut, address = const_pda::protocol_stats::ID)]
+    pub protocol_stats: AccountLoader<'info, ProtocolStats>,
+
+    /// stats of the config, counts the curve and its initial buy
+    #[account(mut, has_one = config)]
+    pub config_stats: AccountLoader<'info, ConfigStats>,
+
+    /// the quote token program is checked here, before the vaults are initialized with it
     #[account(
         init,
         signer,
@@ -81,6 +208,10 @@ pub struct CreateCurveCtx<'info> {
         mint::decimals = config.load()?.base_decimal,
         mint::authority = curve_authority,
         mint::token_program = token_program,
+        constraint = is_token_program_of(
+            &quote_mint.to_account_info(),
+            &token_quote_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
     )]
     pub base_mint: Box<Account<'info, Mint>>,
 
@@ -104,6 +235,16 @@ pub struct CreateCurveCtx<'info> {
     )]
     pub curve: AccountLoader<'info, BondingCurve>,
 
+    /// lookup of the curve by its base mint
+    #[account(
+        init,
+        payer = creator,
+        seeds = [MINT_INDEX_PREFIX, base_mint.key().as_ref()],
+        bump,
+        space = 8 + MintIndex::INIT_SPACE,
+    )]
+    pub mint_index: AccountLoader<'info, MintIndex>,
+
     /// Base token vault for the curve
     #[account(
         init,
@@ -148,14 +289,220 @@ pub struct CreateCurveCtx<'info> {
     pub token_quote_program: Interface<'info, TokenInterface>,
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+
+    /// creator's quote token account paying for the initial buy
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = creator,
+        token::token_program = token_quote_program,
+    )]
+    pub creator_quote_token_account: Option<Box<InterfaceAccount<'info, TokenAccountInterface>>>,
+
+    /// CHECK: creator's base token ATA receiving the initial buy, the mint is new so the initial
+    /// buy creates it, which also validates the address
+    #[account(mut)]
+    pub creator_base_token_account: Option<UncheckedAccount<'info>>,
+
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    /// creator's protection account, created with the curve. Required for an initial buy on
+    /// curves with a sell lockup or during the config's anti-snipe window
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + Protection::INIT_SPACE,
+        seeds = [
+            PROTECTION_PREFIX,
+            curve.key().as_ref(),
+            creator.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub creator_protection: Option<AccountLoader<'info, Protection>>,
+
+    /// creator's whitelist entry, required when the config is in creator whitelist mode
+    #[account(
+        seeds = [WHITELIST_ENTRY_PREFIX, config.key().as_ref(), creator.key().as_ref()],
+        bump,
+    )]
+    pub whitelist_entry: Option<AccountLoader<'info, WhitelistEntry>>,
+
+    /// registry entry of the partner, required when the curve is created through one
+    #[account(
+        seeds = [PARTNER_PREFIX, curve_params.partner.unwrap_or_default().as_ref()],
+        bump,
+    )]
+    pub partner_entry: Option<AccountLoader<'info, Partner>>,
+}
+
+/// Buy on the curve right after its creation, so the creator's first buy can't be sniped. The buy
+/// goes through the guards of a swap: it waits for the activation, counts towards the anti-snipe
+/// cap and starts the sell lockup of the creator
+fn process_initial_buy(
+    ctx: &Context<CreateCurveCtx>,
+    config: &mut Config,
+    curve: &mut BondingCurve,
+    creator_protection: Option<&mut Protection>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    require!(amount_in > 0, AmmError::AmountIsZero);
+    let (
+        Some(creator_quote_token_account),
+        Some(creator_base_token_account),
+        Some(associated_token_program),
+    ) = (
+        ctx.accounts.creator_quote_token_account.as_ref(),
+        ctx.accounts.creator_base_token_account.as_ref(),
+        ctx.accounts.associated_token_program.as_ref(),
+    )
+    else {
+        return err!(AmmError::InitialBuyAccountsRequired);
+    };
+    require!(
+        !config
+            .get_trading_pause()?
+            .is_paused(TradeDirection::QuoteToBase),
+        AmmError::TradingPaused
+    );
+    let clock = Clock::get()?;
+    curve.check_activated(clock.unix_timestamp as u64, clock.slot)?;
+
+    // the creator's buy is the first of the launch and pays the full launch fee
+    let swap_result = curve.get_swap_result(
+        &config.get_launch_fee_config(0)?,
+        amount_in,
+        TradeDirection::QuoteToBase,
+        false,
+        false,
+        false,
+        None,
+    )?;
+    require!(
+        swap_result.output_amount >= minimum_amount_out,
+        AmmError::ExceededSlippage
+    );
+    let total_input_amount = swap_result.get_total_input_amount(TradeDirection::QuoteToBase)?;
+    // the creator's buy lands inside the anti-snipe window like any other launch buy
+    let is_in_anti_snipe_window =
+        curve.is_in_anti_snipe_window(clock.slot, config.anti_snipe_window_slots);
+    if curve.min_hold_seconds > 0 || is_in_anti_snipe_window {
+        let protection = creator_protection.ok_or(AmmError::ProtectionAccountRequired)?;
+        if curve.min_hold_seconds > 0 {
+            protection.record_buy(clock.unix_timestamp as u64);
+        }
+        if is_in_anti_snipe_window {
+            protection
+                .accrue_anti_snipe_buy(total_input_amount, config.max_buy_amount_first_window)?;
+        }
+    }
+    curve.apply_swap_result(
+        &swap_result,
+        TradeDirection::QuoteToBase,
+        clock.unix_timestamp as u64,
+    )?;
+    record_swap(
+        &ctx.accounts.config_stats,
+        &swap_result,
+        TradeDirection::QuoteToBase,
+    )?;
+    // a curve completing at creation would skip its whole trading phase
+    require!(
+        !curve.is_curve_complete(curve.get_migration_base_threshold(config), None),
+        AmmError::InitialBuyCompletesCurve
+    );
+
+    transfer_from_user(
+        &ctx.accounts.creator,
+        &ctx.accounts.quote_mint,
+        creator_quote_token_account,
+        &ctx.accounts.quote_vault,
+        &ctx.accounts.token_quote_program,
+        total_input_amount,
+    )?;
+    anchor_spl::associated_token::create(CpiContext::new(
+        associated_token_program.to_account_info(),
+        anchor_spl::associated_token::Create {
+            payer: ctx.accounts.creator.to_account_info(),
+            associated_token: creator_base_token_account.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+            mint: ctx.accounts.base_mint.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        },
+    ))?;
+    let seeds = curve_authority_seeds!(const_pda::curve_authority::BUMP);
+    anchor_spl::token::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.base_vault.to_account_info(),
+                mint: ctx.accounts.base_mint.to_account_info(),
+                to: creator_base_token_account.to_account_info(),
+                authority: ctx.accounts.curve_authority.to_account_info(),
+            },
+            &[&seeds[..]],
+        ),
+        swap_result.output_amount,
+        ctx.accounts.base_mint.decimals,
+    )?;
+    let amount_out = swap_result.output_amount;
+    curve.update_holder_count(0, amount_out)?;
+
+    emit_cpi!(EvtSwap {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        config: ctx.accounts.config.key(),
+        creator: curve.creator,
+        base_mint: ctx.accounts.base_mint.key(),
+        quote_mint: ctx.accounts.quote_mint.key(),
+        trade_direction: TradeDirection::QuoteToBase.into(),
+        has_referral: false,
+        params: VersionedSwapParameters::V1(SwapParameters {
+            amount_in,
+            minimum_amount_out,
+        }),
+        swap_result,
+        virtual_base_reserve: curve.virtual_base_reserve,
+        virtual_quote_reserve: curve.virtual_quote_reserve,
+        remaining_tokens: amount_out,
+        virtual_price: curve.get_virtual_price()?,
+        base_until_migration: curve
+            .get_base_until_migration(curve.get_migration_base_threshold(config))
+            .get(),
+        holder_count: curve.holder_count,
+        cashback_owner: None,
+        claimable_cashback: None,
+    });
+    Ok(())
 }
 
 pub fn handle_create_curve_spl_token(
     ctx: Context<CreateCurveCtx>,
     params: CreateCurveParams,
 ) -> Result<()> {
-    let config = ctx.accounts.config.load()?;
-    let initial_base_supply = TOKEN_TOTAL_SUPPLY;
+    create_curve_spl_token(ctx, params, false)
+}
+
+/// First step of the two step creation, the curve is created without trading until its
+/// creator writes the extended metadata with `finalize_curve_metadata`
+pub fn handle_init_curve(ctx: Context<CreateCurveCtx>, params: CreateCurveParams) -> Result<()> {
+    require!(
+        params.initial_buy_amount_in.is_none(),
+        AmmError::InitialBuyNotSupported
+    );
+    create_curve_spl_token(ctx, params, true)
+}
+
+fn create_curve_spl_token(
+    ctx: Context<CreateCurveCtx>,
+    params: CreateCurveParams,
+    is_metadata_pending: bool,
+) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    require!(!config.is_paused(), AmmError::ConfigPaused);
+    let initial_base_supply = config.get_token_total_supply()?;
 
     let token_type =
         TokenType::try_from(config.base_token_flag).map_err(|_| AmmError::InvalidTokenType)?;
@@ -166,6 +513,23 @@ pub fn handle_create_curve_spl_token(
 
     // Validate input parameters
     params.validate()?;
+    params.validate_template(&config)?;
+    config.validate_creator_whitelisted(ctx.accounts.whitelist_entry.is_some())?;
+    let creator_lp_fee_share_basis_points = params.get_creator_lp_fee_share(&config)?;
+    let clock = Clock::get()?;
+    let trading_end_timestamp = params.get_trading_end_timestamp(clock.unix_timestamp as u64)?;
+    let (activation_type, activation_point) =
+        params.get_activation(clock.unix_timestamp as u64, clock.slot)?;
+    let partner_entry = match ctx.accounts.partner_entry {
+        Some(ref partner_entry) => Some(*partner_entry.load()?),
+        None => None,
+    };
+    let (partner, partner_fee_share_basis_points) = params.get_partner(partner_entry)?;
+    let CreateCurveParams {
+        initial_buy_amount_in,
+        minimum_amount_out,
+        ..
+    } = params;
 
     // don't run this yet
     // Validate vanity address ends with "kfun"
@@ -223,26 +587,314 @@ pub fn handle_create_curve_spl_token(
     // init curve
     let mut curve = ctx.accounts.curve.load_init()?;
 
-    curve.init(
-        ctx.accounts.config.key(),
-        ctx.accounts.creator.key(),
+    curve.init(CurveInitParams {
+        config: ctx.accounts.config.key(),
+        creator: ctx.accounts.creator.key(),
+        base_mint: ctx.accounts.base_mint.key(),
+        base_vault: ctx.accounts.base_vault.key(),
+        quote_vault: ctx.accounts.quote_vault.key(),
+        curve_type: CurveType::SplToken.into(),
+        base_reserve: initial_base_supply,
+        virtual_quote_reserve: config.initial_virtual_quote_reserve,
+        virtual_base_reserve: config.initial_virtual_base_reserve,
+        creation_timestamp: clock.unix_timestamp as u64,
+        creation_slot: clock.slot,
+        min_hold_seconds: config.min_hold_seconds,
+    });
+    curve.set_creator_lp_fee_share(creator_lp_fee_share_basis_points);
+    curve.set_trading_end_timestamp(trading_end_timestamp);
+    curve.set_activation(activation_type, activation_point);
+    curve.set_partner(partner, partner_fee_share_basis_points);
+    curve.set_metadata_pending(is_metadata_pending);
+    ctx.accounts.mint_index.load_init()?.init(
         ctx.accounts.base_mint.key(),
-        ctx.accounts.base_vault.key(),
-        ctx.accounts.quote_vault.key(),
-        CurveType::SplToken.into(),
+        ctx.accounts.curve.key(),
+        ctx.accounts.config.key(),
+    );
+
+    record_curve_created(&ctx.accounts.protocol_stats, &ctx.accounts.config_stats)?;
+
+    let event_sequence = config.next_event_sequence()?;
+    emit_cpi!(curve.event(
+        event_sequence,
+        ctx.accounts.curve.key(),
+        ctx.accounts.quote_mint.key(),
+        CurveEventParams {
+            name: params.name,
+            symbol: params.symbol,
+            uri: params.uri,
+            initial_virtual_quote_reserve: config.initial_virtual_quote_reserve,
+            initial_virtual_base_reserve: config.initial_virtual_base_reserve,
+        },
+    ));
+
+    let mut creator_protection = match ctx.accounts.creator_protection {
+        Some(ref protection) => Some(protection.load_init()?),
+        None => None,
+    };
+    if let Some(ref mut protection) = creator_protection {
+        protection.init(ctx.accounts.curve.key(), ctx.accounts.creator.key());
+        emit_cpi!(EvtCreateProtection {
+            event_sequence: config.next_event_sequence()?,
+            curve: ctx.accounts.curve.key(),
+            owner: ctx.accounts.creator.key(),
+        });
+    }
+
+    if let Some(amount_in) = initial_buy_amount_in {
+        process_initial_buy(
+            &ctx,
+            &mut config,
+            &mut curve,
+            creator_protection.as_deref_mut(),
+            amount_in,
+            minimum_amount_out.unwrap_or_default(),
+        )?;
+    }
+    Ok(())
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(curve_params: CreateCurveParams)]
+pub struct CreateCurveToken2022Ctx<'info> {
+    /// Address paying for the bonding curve creation
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// config the boding curve belongs to
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: AccountInfo<'info>,
+
+    /// protocol wide stats, counts the curve and its initial buy
+    #[account(mut, address = const_pda::protocol_stats::ID)]
+    pub protocol_stats: AccountLoader<'info, ProtocolStats>,
+
+    /// stats of the config, counts the curve and its initial buy
+    #[account(mut, has_one = config)]
+    pub config_stats: AccountLoader<'info, ConfigStats>,
+
+    /// Base mint, the token metadata lives in the mint itself. The quote token program is
+    /// checked here, before the vaults are initialized with it
+    #[account(
+        init,
+        signer,
+        payer = creator,
+        mint::decimals = config.load()?.base_decimal,
+        mint::authority = curve_authority,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = curve_authority,
+        extensions::metadata_pointer::metadata_address = base_mint,
+        constraint = is_token_program_of(
+            &quote_mint.to_account_info(),
+            &token_quote_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
+    )]
+    pub base_mint: Box<InterfaceAccount<'info, MintInterface>>,
+
+    #[account(
+        mint::token_program = token_quote_program,
+    )]
+    pub quote_mint: Box<InterfaceAccount<'info, MintInterface>>,
+
+    /// Bonding curve PDA
+    #[account(
+        init,
+        payer = creator,
+        seeds = [
+            CURVE_PREFIX,
+            config.key().as_ref(),
+            &max_key(&base_mint.key(), &quote_mint.key()),
+            &min_key(&base_mint.key(), &quote_mint.key()),
+        ],
+        bump,
+        space = 8 + BondingCurve::INIT_SPACE,
+    )]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// lookup of the curve by its base mint
+    #[account(
+        init,
+        payer = creator,
+        seeds = [MINT_INDEX_PREFIX, base_mint.key().as_ref()],
+        bump,
+        space = 8 + MintIndex::INIT_SPACE,
+    )]
+    pub mint_index: AccountLoader<'info, MintIndex>,
+
+    /// Base token vault for the curve
+    #[account(
+        init,
+        seeds = [
+            TOKEN_VAULT_PREFIX,
+            base_mint.key().as_ref(),
+            curve.key().as_ref(),
+        ],
+        token::mint = base_mint,
+        token::authority = curve_authority,
+        token::token_program = token_program,
+        payer = creator,
+        bump,
+    )]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccountInterface>>,
+
+    /// Quote token vault for the curve
+    #[account(
+        init,
+        seeds = [
+            TOKEN_VAULT_PREFIX,
+            quote_mint.key().as_ref(),
+            curve.key().as_ref(),
+        ],
+        token::mint = quote_mint,
+        token::authority = curve_authority,
+        token::token_program = token_quote_program,
+        payer = creator,
+        bump,
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccountInterface>>,
+
+    /// Program to create mint account and mint tokens
+    pub token_quote_program: Interface<'info, TokenInterface>,
+    pub token_program: Program<'info, Token2022>,
+    pub system_program: Program<'info, System>,
+
+    /// creator's whitelist entry, required when the config is in creator whitelist mode
+    #[account(
+        seeds = [WHITELIST_ENTRY_PREFIX, config.key().as_ref(), creator.key().as_ref()],
+        bump,
+    )]
+    pub whitelist_entry: Option<AccountLoader<'info, WhitelistEntry>>,
+
+    /// registry entry of the partner, required when the curve is created through one
+    #[account(
+        seeds = [PARTNER_PREFIX, curve_params.partner.unwrap_or_default().as_ref()],
+        bump,
+    )]
+    pub partner_entry: Option<AccountLoader<'info, Partner>>,
+}
+
+pub fn handle_create_curve_token_2022(
+    ctx: Context<CreateCurveToken2022Ctx>,
+    params: CreateCurveParams,
+) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    require!(!config.is_paused(), AmmError::ConfigPaused);
+    let initial_base_supply = config.get_token_total_supply()?;
+
+    let token_type =
+        TokenType::try_from(config.base_token_flag).map_err(|_| AmmError::InvalidTokenType)?;
+    require!(
+        token_type == TokenType::Token2022,
+        AmmError::InvalidTokenType
+    );
+
+    params.validate()?;
+    params.validate_template(&config)?;
+    config.validate_creator_whitelisted(ctx.accounts.whitelist_entry.is_some())?;
+    let creator_lp_fee_share_basis_points = params.get_creator_lp_fee_share(&config)?;
+    let clock = Clock::get()?;
+    let trading_end_timestamp = params.get_trading_end_timestamp(clock.unix_timestamp as u64)?;
+    let (activation_type, activation_point) =
+        params.get_activation(clock.unix_timestamp as u64, clock.slot)?;
+    let partner_entry = match ctx.accounts.partner_entry {
+        Some(ref partner_entry) => Some(*partner_entry.load()?),
+        None => None,
+    };
+    let (partner, partner_fee_share_basis_points) = params.get_partner(partner_entry)?;
+    require!(
+        params.initial_buy_amount_in.is_none(),
+        AmmError::InitialBuyNotSupported
+    );
+
+    process_create_token_2022_metadata(ProcessCreateToken2022MetadataParams {
+        system_program: ctx.accounts.system_program.to_account_info(),
+        payer: ctx.accounts.creator.to_account_info(),
+        curve_authority: ctx.accounts.curve_authority.to_account_info(),
+        mint: ctx.accounts.base_mint.to_account_info(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+        name: &params.name,
+        symbol: &params.symbol,
+        uri: &params.uri,
+        curve_authority_bump: const_pda::curve_authority::BUMP,
+    })?;
+
+    // mint token
+    let seeds = curve_authority_seeds!(const_pda::curve_authority::BUMP);
+    anchor_spl::token_interface::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token_interface::MintTo {
+                mint: ctx.accounts.base_mint.to_account_info(),
+                to: ctx.accounts.base_vault.to_account_info(),
+                authority: ctx.accounts.curve_authority.to_account_info(),
+            },
+            &[&seeds[..]],
+        ),
         initial_base_supply,
-        config.initial_virtual_quote_reserve,
-        config.initial_virtual_base_reserve,
+    )?;
+
+    // update mint authority
+    anchor_spl::token_interface::set_authority(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token_interface::SetAuthority {
+                current_authority: ctx.accounts.curve_authority.to_account_info(),
+                account_or_mint: ctx.accounts.base_mint.to_account_info(),
+            },
+            &[&seeds[..]],
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )?;
+
+    // init curve
+    let mut curve = ctx.accounts.curve.load_init()?;
+
+    curve.init(CurveInitParams {
+        config: ctx.accounts.config.key(),
+        creator: ctx.accounts.creator.key(),
+        base_mint: ctx.accounts.base_mint.key(),
+        base_vault: ctx.accounts.base_vault.key(),
+        quote_vault: ctx.accounts.quote_vault.key(),
+        curve_type: CurveType::Token2022.into(),
+        base_reserve: initial_base_supply,
+        virtual_quote_reserve: config.initial_virtual_quote_reserve,
+        virtual_base_reserve: config.initial_virtual_base_reserve,
+        creation_timestamp: clock.unix_timestamp as u64,
+        creation_slot: clock.slot,
+        min_hold_seconds: config.min_hold_seconds,
+    });
+    curve.set_creator_lp_fee_share(creator_lp_fee_share_basis_points);
+    curve.set_trading_end_timestamp(trading_end_timestamp);
+    curve.set_activation(activation_type, activation_point);
+    curve.set_partner(partner, partner_fee_share_basis_points);
+    ctx.accounts.mint_index.load_init()?.init(
+        ctx.accounts.base_mint.key(),
+        ctx.accounts.curve.key(),
+        ctx.accounts.config.key(),
     );
 
+    record_curve_created(&ctx.accounts.protocol_stats, &ctx.accounts.config_stats)?;
+
+    let event_sequence = config.next_event_sequence()?;
     emit_cpi!(curve.event(
+        event_sequence,
         ctx.accounts.curve.key(),
         ctx.accounts.quote_mint.key(),
-        params.name,
-        params.symbol,
-        params.uri,
-        config.initial_virtual_quote_reserve,
-        config.initial_virtual_base_reserve,
+        CurveEventParams {
+            name: params.name,
+            symbol: params.symbol,
+            uri: params.uri,
+            initial_virtual_quote_reserve: config.initial_virtual_quote_reserve,
+            initial_virtual_base_reserve: config.initial_virtual_base_reserve,
+        },
     ));
     Ok(())
 }
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::seeds::CONFIG_STATS_PREFIX,
+    states::{Config, ConfigStats},
+};
+
+/// Accounts to create the stats of a config created before configs came with them. Anyone can
+/// pay for it, the stats start at zero
+#[derive(Accounts)]
+pub struct CreateConfigStatsCtx<'info> {
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        init,
+        seeds = [
+            CONFIG_STATS_PREFIX,
+            config.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = 8 + ConfigStats::INIT_SPACE,
+    )]
+    pub config_stats: AccountLoader<'info, ConfigStats>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_create_config_stats(ctx: Context<CreateConfigStatsCtx>) -> Result<()> {
+    ctx.accounts
+        .config_stats
+        .load_init()?
+        .init(ctx.accounts.config.key());
+    Ok(())
+}
@@ -0,0 +1,254 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::seeds::{
+        CURVE_PREFIX, DUAL_LISTING_PREFIX, TOKEN_VAULT_PREFIX, WHITELIST_ENTRY_PREFIX,
+    },
+    errors::AmmError,
+    events::EvtCreateDualListing,
+    instructions::{max_key, min_key},
+    safe_math::SafeMath,
+    states::{BondingCurve, Config, CurveInitParams, CurveType, DualListing, WhitelistEntry},
+    utils::token::{
+        get_token_program_flags, is_token_program_of, transfer_from_curve, TokenProgramFlags,
+    },
+};
+
+/// Accounts for the creator to list a base mint on a second config
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateDualListingCtx<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config of the curve the base mint was launched on
+    pub primary_config: AccountLoader<'info, Config>,
+
+    /// curve the base mint was launched on, gives up a share of its supply
+    #[account(
+        mut,
+        has_one = creator,
+        has_one = base_mint,
+        constraint = primary_curve.load()?.config == primary_config.key() @ AmmError::InvalidAccount,
+        constraint = primary_curve.load()?.base_vault == primary_base_vault.key() @ AmmError::InvalidAccount,
+    )]
+    pub primary_curve: AccountLoader<'info, BondingCurve>,
+
+    /// base vault of the primary curve
+    #[account(mut)]
+    pub primary_base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// config of the new curve, quoted in another mint
+    #[account(
+        mut,
+        has_one = quote_mint,
+        constraint = secondary_config.key() != primary_config.key() @ AmmError::InvalidDualListingConfig,
+    )]
+    pub secondary_config: AccountLoader<'info, Config>,
+
+    #[account(mint::token_program = token_base_program)]
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// quote mint of the secondary config
+    #[account(mint::token_program = token_quote_program)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Bonding curve PDA on the secondary config, the token programs are checked here, before
+    /// the vaults are initialized with them
+    #[account(
+        init,
+        payer = creator,
+        seeds = [
+            CURVE_PREFIX,
+            secondary_config.key().as_ref(),
+            &max_key(&base_mint.key(), &quote_mint.key()),
+            &min_key(&base_mint.key(), &quote_mint.key()),
+        ],
+        bump,
+        space = 8 + BondingCurve::INIT_SPACE,
+        constraint = is_token_program_of(
+            &base_mint.to_account_info(),
+            &token_base_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
+        constraint = is_token_program_of(
+            &quote_mint.to_account_info(),
+            &token_quote_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
+    )]
+    pub secondary_curve: AccountLoader<'info, BondingCurve>,
+
+    /// Base token vault for the secondary curve
+    #[account(
+        init,
+        seeds = [
+            TOKEN_VAULT_PREFIX,
+            base_mint.key().as_ref(),
+            secondary_curve.key().as_ref(),
+        ],
+        token::mint = base_mint,
+        token::authority = curve_authority,
+        token::token_program = token_base_program,
+        payer = creator,
+        bump,
+    )]
+    pub secondary_base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Quote token vault for the secondary curve
+    #[account(
+        init,
+        seeds = [
+            TOKEN_VAULT_PREFIX,
+            quote_mint.key().as_ref(),
+            secondary_curve.key().as_ref(),
+        ],
+        token::mint = quote_mint,
+        token::authority = curve_authority,
+        token::token_program = token_quote_program,
+        payer = creator,
+        bump,
+    )]
+    pub secondary_quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared supply accounting of the base mint, one per base mint
+    #[account(
+        init,
+        payer = creator,
+        seeds = [
+            DUAL_LISTING_PREFIX,
+            base_mint.key().as_ref(),
+        ],
+        bump,
+        space = 8 + DualListing::INIT_SPACE,
+    )]
+    pub dual_listing: AccountLoader<'info, DualListing>,
+
+    pub token_base_program: Interface<'info, TokenInterface>,
+    pub token_quote_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    /// creator's whitelist entry on the secondary config, required when it is in creator
+    /// whitelist mode
+    #[account(
+        seeds = [
+            WHITELIST_ENTRY_PREFIX,
+            secondary_config.key().as_ref(),
+            creator.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub whitelist_entry: Option<AccountLoader<'info, WhitelistEntry>>,
+}
+
+pub fn handle_create_dual_listing(
+    ctx: Context<CreateDualListingCtx>,
+    secondary_base_supply: u64,
+) -> Result<()> {
+    let primary_config = ctx.accounts.primary_config.load()?;
+    let mut secondary_config = ctx.accounts.secondary_config.load_mut()?;
+    let mut primary_curve = ctx.accounts.primary_curve.load_mut()?;
+    require!(!secondary_config.is_paused(), AmmError::ConfigPaused);
+    secondary_config.validate_creator_whitelisted(ctx.accounts.whitelist_entry.is_some())?;
+
+    // the supply can only be split before anyone traded against the full supply
+    require!(
+        primary_curve.quote_reserve == 0
+            && primary_curve.base_reserve == primary_config.get_token_total_supply()?,
+        AmmError::CurveAlreadyTraded
+    );
+
+    // same token, different quote
+    require!(
+        primary_config.base_token_flag == secondary_config.base_token_flag
+            && primary_config.base_decimal == secondary_config.base_decimal
+            && primary_config.quote_mint != secondary_config.quote_mint,
+        AmmError::InvalidDualListingConfig
+    );
+
+    // each curve keeps at least a migration threshold of its config, smaller shares would scale
+    // down to a degenerate curve
+    let primary_base_supply = primary_curve.base_reserve.safe_sub(secondary_base_supply)?;
+    require!(
+        primary_base_supply > primary_config.migration_base_threshold
+            && secondary_base_supply > secondary_config.migration_base_threshold,
+        AmmError::InvalidDualListingSupply
+    );
+
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.base_mint,
+        &ctx.accounts.primary_base_vault,
+        &ctx.accounts.secondary_base_vault,
+        &ctx.accounts.token_base_program,
+        secondary_base_supply,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    // both curves keep the price path of their config on their share of the supply
+    primary_curve.set_supply_share(&primary_config, primary_base_supply)?;
+    primary_curve.is_dual_listed = 1;
+
+    // the curve type follows the token program of the base mint, as on creation
+    let curve_type = match get_token_program_flags(&ctx.accounts.base_mint) {
+        TokenProgramFlags::TokenProgram => CurveType::SplToken,
+        TokenProgramFlags::TokenProgram2022 => CurveType::Token2022,
+    };
+
+    let mut secondary_curve = ctx.accounts.secondary_curve.load_init()?;
+    secondary_curve.init(CurveInitParams {
+        config: ctx.accounts.secondary_config.key(),
+        creator: ctx.accounts.creator.key(),
+        base_mint: ctx.accounts.base_mint.key(),
+        base_vault: ctx.accounts.secondary_base_vault.key(),
+        quote_vault: ctx.accounts.secondary_quote_vault.key(),
+        curve_type: curve_type.into(),
+        base_reserve: secondary_base_supply,
+        virtual_quote_reserve: secondary_config.initial_virtual_quote_reserve,
+        virtual_base_reserve: secondary_config.initial_virtual_base_reserve,
+        creation_timestamp: Clock::get()?.unix_timestamp as u64,
+        creation_slot: Clock::get()?.slot,
+        min_hold_seconds: secondary_config.min_hold_seconds,
+    });
+    secondary_curve.set_supply_share(&secondary_config, secondary_base_supply)?;
+    // the creator keeps its share on the secondary curve, within the bound of its config
+    secondary_curve.set_creator_lp_fee_share(
+        primary_curve
+            .creator_lp_fee_share_basis_points
+            .min(secondary_config.max_creator_lp_fee_share_basis_points),
+    );
+    secondary_curve.is_dual_listed = 1;
+
+    let mut dual_listing = ctx.accounts.dual_listing.load_init()?;
+    dual_listing.init(
+        ctx.accounts.base_mint.key(),
+        ctx.accounts.primary_curve.key(),
+        ctx.accounts.secondary_curve.key(),
+        primary_base_supply,
+        secondary_base_supply,
+    );
+
+    emit_cpi!(EvtCreateDualListing {
+        event_sequence: secondary_config.next_event_sequence()?,
+        dual_listing: ctx.accounts.dual_listing.key(),
+        base_mint: ctx.accounts.base_mint.key(),
+        primary_curve: ctx.accounts.primary_curve.key(),
+        primary_config: ctx.accounts.primary_config.key(),
+        secondary_curve: ctx.accounts.secondary_curve.key(),
+        secondary_config: ctx.accounts.secondary_config.key(),
+        secondary_quote_mint: ctx.accounts.quote_mint.key(),
+        primary_base_supply,
+        secondary_base_supply,
+        initial_virtual_quote_reserve: secondary_curve.virtual_quote_reserve,
+        initial_virtual_base_reserve: secondary_curve.virtual_base_reserve,
+        min_hold_seconds: secondary_config.min_hold_seconds,
+    });
+
+    Ok(())
+}
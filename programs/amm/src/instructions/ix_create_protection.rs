@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::seeds::PROTECTION_PREFIX,
+    events::EvtCreateProtection,
+    states::{BondingCurve, Config, Protection},
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateProtectionCtx<'info> {
+    /// Address paying for the protection account creation, also the owner of the record
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// config of the bonding curve, sequences the event
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve the protection account is tracked for
+    #[account(has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// The protection account PDA
+    /// This tracks per wallet launch protection stats for a single curve
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Protection::INIT_SPACE,
+        seeds = [
+            PROTECTION_PREFIX,
+            curve.key().as_ref(),
+            payer.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub protection: AccountLoader<'info, Protection>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_create_protection(ctx: Context<CreateProtectionCtx>) -> Result<()> {
+    let mut protection = ctx.accounts.protection.load_init()?;
+    protection.init(ctx.accounts.curve.key(), ctx.accounts.payer.key());
+
+    emit_cpi!(EvtCreateProtection {
+        event_sequence: ctx.accounts.config.load_mut()?.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        owner: ctx.accounts.payer.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::seeds::CURVE_METADATA_PREFIX,
+    errors::AmmError,
+    events::EvtFinalizeCurveMetadata,
+    states::{BondingCurve, Config, CurveMetadata},
+};
+
+/// Accounts for the creator to write the metadata of a curve created with `init_curve`
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizeCurveMetadataCtx<'info> {
+    /// config the bonding curve belongs to
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve waiting for its metadata
+    #[account(mut, has_one = creator, has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// metadata of the curve, can only be written once
+    #[account(
+        init,
+        seeds = [
+            CURVE_METADATA_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        payer = creator,
+        space = 8 + CurveMetadata::INIT_SPACE,
+    )]
+    pub curve_metadata: AccountLoader<'info, CurveMetadata>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_finalize_curve_metadata(
+    ctx: Context<FinalizeCurveMetadataCtx>,
+    uri: String,
+    extension: Vec<u8>,
+) -> Result<()> {
+    let mut curve = ctx.accounts.curve.load_mut()?;
+    require!(
+        curve.is_metadata_pending(),
+        AmmError::CurveMetadataNotPending
+    );
+
+    ctx.accounts
+        .curve_metadata
+        .load_init()?
+        .init(ctx.accounts.curve.key(), &uri, &extension)?;
+    curve.set_metadata_pending(false);
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    emit_cpi!(EvtFinalizeCurveMetadata {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        curve_metadata: ctx.accounts.curve_metadata.key(),
+        uri,
+        extension,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    const_pda,
+    errors::AmmError,
+    params::swap::TradeDirection,
+    states::{BondingCurve, CashbackAccount, CashbackConfig, Config, FeeSchedule, ReferralAccount},
+};
+
+/// Accounts to read the fee rates a wallet pays on the swaps of a config, nothing is written
+#[derive(Accounts)]
+pub struct GetFeeScheduleCtx<'info> {
+    /// config the fee rates are read from
+    pub config: AccountLoader<'info, Config>,
+
+    /// CHECK: wallet the fee rates are computed for, doesn't have to be a signer
+    pub wallet: UncheckedAccount<'info>,
+
+    /// optional cashback account of the wallet, its tier sets the cashback rate
+    /// PDA validation is done manually in the handler
+    pub cashback: Option<AccountLoader<'info, CashbackAccount>>,
+
+    /// optional referral account of the wallet, its referrer chain gets the referral fees and
+    /// the wallet the referee discount
+    /// PDA validation is done manually in the handler
+    pub referral: Option<AccountLoader<'info, ReferralAccount>>,
+
+    /// optional curve of the config, its launch fee is included in the trading fee
+    #[account(has_one = config @ AmmError::InvalidAccount)]
+    pub curve: Option<AccountLoader<'info, BondingCurve>>,
+
+    /// optional cashback settings, with the cashback account the wallet gets its tier's trading
+    /// fee discount
+    #[account(address = const_pda::cashback_config::ID)]
+    pub cashback_config: Option<AccountLoader<'info, CashbackConfig>>,
+}
+
+/// Fee rates are returned with the return data, swaps in `trade_direction` with the same
+/// accounts are charged them
+pub fn handle_get_fee_schedule(
+    ctx: Context<GetFeeScheduleCtx>,
+    trade_direction: u8,
+) -> Result<FeeSchedule> {
+    let trade_direction =
+        TradeDirection::try_from(trade_direction).map_err(|_| AmmError::TypeCastFailed)?;
+    let wallet = ctx.accounts.wallet.key();
+
+    let cashback_tier = match ctx.accounts.cashback {
+        Some(ref cashback) => {
+            let (expected_cashback_pda, _bump) = const_pda::cashback::derive_pda(&wallet);
+            require!(
+                cashback.key() == expected_cashback_pda,
+                AmmError::InvalidAccount
+            );
+            let tier = cashback.load()?.get_tier()?;
+            Some(match ctx.accounts.cashback_config {
+                Some(ref cashback_config) => cashback_config.load()?.get_fee_tier(tier),
+                None => tier.into(),
+            })
+        }
+        None => None,
+    };
+
+    let [l1_referrer, l2_referrer, l3_referrer] = match ctx.accounts.referral {
+        Some(ref referral) => {
+            let (expected_referral_pda, _bump) = const_pda::referral::derive_pda(&wallet);
+            require!(
+                referral.key() == expected_referral_pda,
+                AmmError::InvalidAccount
+            );
+            referral.load()?.get_referrers()
+        }
+        None => [None; 3],
+    };
+
+    let mut config = *ctx.accounts.config.load()?;
+    if let Some(ref curve) = ctx.accounts.curve {
+        let current_timestamp = Clock::get()?.unix_timestamp as u64;
+        config = config
+            .get_launch_fee_config(curve.load()?.get_launch_elapsed_seconds(current_timestamp))?;
+    }
+
+    // fees taken in base are charged the full rate, the tier discount only applies in quote
+    let cashback_tier = if config
+        .get_fee_collection_mode()?
+        .is_fee_on_base(trade_direction)
+    {
+        cashback_tier.map(|cashback_tier| cashback_tier.tier.into())
+    } else {
+        cashback_tier
+    };
+    config.get_fee_schedule(
+        l1_referrer.is_some(),
+        l2_referrer.is_some(),
+        l3_referrer.is_some(),
+        cashback_tier,
+        trade_direction,
+    )
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::EvtReallocCurve,
+    states::{BondingCurve, Config},
+};
+
+/// Accounts to grow a bonding curve created on an older layout to the current one, so new curve
+/// fields roll out without new curve accounts. Anyone can sponsor the rent of the larger account
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReallocCurveCtx<'info> {
+    /// bonding curve config key
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve, reallocated before it is loaded by the other constraints
+    #[account(
+        mut,
+        realloc = 8 + BondingCurve::INIT_SPACE,
+        realloc::payer = sponsor,
+        realloc::zero = true,
+        has_one = config,
+    )]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// pays for the larger account
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_realloc_curve(ctx: Context<ReallocCurveCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+    let clock = Clock::get()?;
+    curve.migrate_layout(clock.unix_timestamp as u64, clock.slot)?;
+
+    emit_cpi!(EvtReallocCurve {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        config: ctx.accounts.config.key(),
+        sponsor: ctx.accounts.sponsor.key(),
+        version: curve.version,
+        size: ctx.accounts.curve.to_account_info().data_len() as u64,
+    });
+
+    Ok(())
+}
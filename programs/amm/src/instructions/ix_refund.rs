@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    events::EvtRefund,
+    states::{BondingCurve, Config},
+    utils::{get_current_timestamp, is_token_program_of, transfer_from_curve, transfer_from_user},
+    AmmError,
+};
+
+/// Accounts for a holder to return base to a curve whose trading closed after its trading end,
+/// once the sell grace period is over
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RefundCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// bonding curve config key
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        has_one = config,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+        constraint = is_token_program_of(
+            &base_mint.to_account_info(),
+            &token_base_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
+        constraint = is_token_program_of(
+            &quote_mint.to_account_info(),
+            &token_quote_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
+    )]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// Owner's base token account, the base returned to the curve
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::authority = owner,
+        token::token_program = token_base_program,
+    )]
+    pub base_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner's quote token account to receive the refund, doesn't have to be the ATA
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::token_program = token_quote_program,
+    )]
+    pub quote_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for base token
+    #[account(mut, token::token_program = token_base_program, token::mint = base_mint)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for quote token
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of base token
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// holder returning the base
+    pub owner: Signer<'info>,
+
+    /// Token base program
+    pub token_base_program: Interface<'info, TokenInterface>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+}
+
+/// Holders can't sell once the grace period after the trading end is over, so the quote reserve
+/// is handed back to them instead. No fee is taken, the curve never migrates
+pub fn handle_refund(ctx: Context<RefundCtx>, base_amount: u64) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+    require!(!config.is_paused(), AmmError::ConfigPaused);
+    require!(!curve.is_paused(), AmmError::CurvePaused);
+
+    let current_timestamp = get_current_timestamp(ctx.remaining_accounts)?;
+    let quote_amount = curve.refund(&config, base_amount, current_timestamp)?;
+
+    transfer_from_user(
+        &ctx.accounts.owner,
+        &ctx.accounts.base_mint,
+        &ctx.accounts.base_token_account,
+        &ctx.accounts.base_vault,
+        &ctx.accounts.token_base_program,
+        base_amount,
+    )?;
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.quote_vault,
+        &ctx.accounts.quote_token_account,
+        &ctx.accounts.token_quote_program,
+        quote_amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    emit_cpi!(EvtRefund {
+        event_sequence: config.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        curve: ctx.accounts.curve.key(),
+        owner: ctx.accounts.owner.key(),
+        base_amount,
+        quote_amount,
+        remaining_quote_reserve: curve.quote_reserve,
+    });
+
+    Ok(())
+}
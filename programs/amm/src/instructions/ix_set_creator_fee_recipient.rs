@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::EvtSetCreatorFeeRecipient,
+    states::{BondingCurve, Config},
+};
+
+/// Accounts for the creator to have its fee claimed to another wallet, eg: a DAO or multisig
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCreatorFeeRecipientCtx<'info> {
+    /// config the bonding curve belongs to
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(mut, has_one = creator, has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    pub creator: Signer<'info>,
+}
+
+/// The default pubkey sends the creator fee back to the creator. The recipient can claim by
+/// itself, the creator keeps the right to claim to it
+pub fn handle_set_creator_fee_recipient(
+    ctx: Context<SetCreatorFeeRecipientCtx>,
+    recipient: Pubkey,
+) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+
+    let old_recipient = curve.creator_fee_recipient;
+    curve.creator_fee_recipient = recipient;
+
+    emit_cpi!(EvtSetCreatorFeeRecipient {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        creator: curve.creator,
+        old_recipient,
+        new_recipient: recipient,
+    });
+
+    Ok(())
+}
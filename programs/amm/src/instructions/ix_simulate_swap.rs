@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    const_pda,
+    errors::AmmError,
+    params::swap::TradeDirection,
+    states::{
+        BondingCurve, CashbackAccount, CashbackConfig, Config, MigrationStatus, ReferralAccount,
+        SwapSimulation,
+    },
+    utils::get_current_timestamp,
+};
+
+/// Accounts to quote a swap of a wallet on a curve, nothing is written
+#[derive(Accounts)]
+pub struct SimulateSwapCtx<'info> {
+    /// config of the curve
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve the swap is quoted on
+    #[account(has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// CHECK: wallet the swap is quoted for, doesn't have to be a signer
+    pub wallet: UncheckedAccount<'info>,
+
+    /// optional cashback account of the wallet, its tier sets the cashback fee
+    /// PDA validation is done manually in the handler
+    pub cashback: Option<AccountLoader<'info, CashbackAccount>>,
+
+    /// optional referral account of the wallet, its referrer chain gets the referral fees
+    /// PDA validation is done manually in the handler
+    pub referral: Option<AccountLoader<'info, ReferralAccount>>,
+
+    /// optional cashback settings, with the cashback account the wallet gets its tier's trading
+    /// fee discount
+    #[account(address = const_pda::cashback_config::ID)]
+    pub cashback_config: Option<AccountLoader<'info, CashbackConfig>>,
+}
+
+/// The swap is returned with the return data, a swap of `amount_in` in `trade_direction` with
+/// the same accounts in the same slot gets the same result. Fails like the swap would on a
+/// paused or completed curve
+pub fn handle_simulate_swap(
+    ctx: Context<SimulateSwapCtx>,
+    amount_in: u64,
+    trade_direction: u8,
+) -> Result<SwapSimulation> {
+    let trade_direction =
+        TradeDirection::try_from(trade_direction).map_err(|_| AmmError::TypeCastFailed)?;
+    require!(amount_in > 0, AmmError::AmountIsZero);
+    let wallet = ctx.accounts.wallet.key();
+
+    let cashback_tier = match ctx.accounts.cashback {
+        Some(ref cashback) => {
+            let (expected_cashback_pda, _bump) = const_pda::cashback::derive_pda(&wallet);
+            require!(
+                cashback.key() == expected_cashback_pda,
+                AmmError::InvalidAccount
+            );
+            let tier = cashback.load()?.get_tier()?;
+            Some(match ctx.accounts.cashback_config {
+                Some(ref cashback_config) => cashback_config.load()?.get_fee_tier(tier),
+                None => tier.into(),
+            })
+        }
+        None => None,
+    };
+
+    let [l1_referrer, l2_referrer, l3_referrer] = match ctx.accounts.referral {
+        Some(ref referral) => {
+            let (expected_referral_pda, _bump) = const_pda::referral::derive_pda(&wallet);
+            require!(
+                referral.key() == expected_referral_pda,
+                AmmError::InvalidAccount
+            );
+            referral.load()?.get_referrers()
+        }
+        None => [None; 3],
+    };
+
+    let config = ctx.accounts.config.load()?;
+    // a copy of the curve, the swap is applied to it to read the state after the swap
+    let mut curve = *ctx.accounts.curve.load()?;
+
+    require!(!config.is_paused(), AmmError::ConfigPaused);
+    require!(!curve.is_paused(), AmmError::CurvePaused);
+    require!(
+        !config.get_trading_pause()?.is_paused(trade_direction)
+            && !curve.get_trading_pause()?.is_paused(trade_direction),
+        AmmError::TradingPaused
+    );
+    let clock = Clock::get()?;
+    let current_timestamp = get_current_timestamp(ctx.remaining_accounts)?;
+    curve.check_activated(current_timestamp, clock.slot)?;
+    curve.check_trading_end(current_timestamp, trade_direction)?;
+    let fee_config =
+        config.get_launch_fee_config(curve.get_launch_elapsed_seconds(current_timestamp))?;
+    require!(
+        curve.get_migration_progress()? == MigrationStatus::PreBondingCurve
+            && !curve.is_curve_complete(curve.get_migration_base_threshold(&config), None),
+        AmmError::PoolIsCompleted
+    );
+
+    let swap_result = curve.get_swap_result(
+        &fee_config,
+        amount_in,
+        trade_direction,
+        l1_referrer.is_some(),
+        l2_referrer.is_some(),
+        l3_referrer.is_some(),
+        cashback_tier,
+    )?;
+    curve.apply_swap_result(&swap_result, trade_direction, current_timestamp)?;
+
+    let decayed_quote_threshold =
+        curve.get_decayed_migration_quote_threshold(&config, current_timestamp)?;
+    Ok(SwapSimulation {
+        swap_result,
+        virtual_base_reserve: curve.virtual_base_reserve,
+        virtual_quote_reserve: curve.virtual_quote_reserve,
+        virtual_price: curve.get_virtual_price()?,
+        completes_curve: curve.is_curve_complete(
+            curve.get_migration_base_threshold(&config),
+            decayed_quote_threshold,
+        ),
+    })
+}
@@ -1,23 +1,198 @@
 use anchor_lang::prelude::*;
-use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use anchor_lang::{
+    solana_program::{program::invoke, system_instruction::transfer},
+    AnchorDeserialize, AnchorSerialize,
+};
 use anchor_spl::{
-    associated_token::get_associated_token_address,
+    associated_token::get_associated_token_address_with_program_id,
+    token::spl_token::native_mint,
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
 use crate::{
     const_pda,
     events::{EvtCurveComplete, EvtSwap},
-    params::swap::TradeDirection,
-    states::{BondingCurve, CashbackAccount, Config, MigrationStatus},
-    utils::{transfer_from_curve, transfer_from_user},
+    params::swap::{SwapMode, TradeDirection},
+    safe_math::SafeMath,
+    states::{
+        BondingCurve, BonusPool, CashbackAccount, CashbackConfig, CashbackMode, Config,
+        ConfigStats, MigrationStatus, Protection, ReferralAccount, SwapResult,
+    },
+    utils::{
+        get_current_timestamp, is_token_program_of, record_curve_graduated, record_swap,
+        transfer_from_curve, transfer_from_user, unwrap_sol, wrap_sol,
+    },
     AmmError,
 };
 
-#[derive(AnchorSerialize, AnchorDeserialize)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
 pub struct SwapParameters {
-    amount_in: u64,
-    minimum_amount_out: u64,
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct SwapParametersV2 {
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+    /// max move of the curve's spot price caused by the swap in bps, 0 disables the guard
+    pub max_price_impact_basis_points: u16,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct SwapParametersV3 {
+    /// input amount, the output amount in exact out mode
+    pub amount: u64,
+    /// minimum output amount, the maximum input amount in exact out mode
+    pub other_amount_threshold: u64,
+    /// max move of the curve's spot price caused by the swap in bps, 0 disables the guard
+    pub max_price_impact_basis_points: u16,
+    /// swap mode (0: ExactIn, 1: ExactOut)
+    pub swap_mode: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct SwapParametersV4 {
+    /// input amount, the output amount in exact out mode
+    pub amount: u64,
+    /// minimum output amount, the maximum input amount in exact out mode
+    pub other_amount_threshold: u64,
+    /// max move of the curve's spot price caused by the swap in bps, 0 disables the guard
+    pub max_price_impact_basis_points: u16,
+    /// swap mode (0: ExactIn, 1: ExactOut)
+    pub swap_mode: u8,
+    /// trade with plain SOL on WSOL quoted curves, the payer's lamports are wrapped into the
+    /// quote token account of the swap, which is closed back to the payer afterwards
+    pub use_native_sol: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct SwapParametersV5 {
+    /// input amount, the output amount in exact out mode
+    pub amount: u64,
+    /// minimum output amount, the maximum input amount in exact out mode
+    pub other_amount_threshold: u64,
+    /// max move of the curve's spot price caused by the swap in bps, 0 disables the guard
+    pub max_price_impact_basis_points: u16,
+    /// swap mode (0: ExactIn, 1: ExactOut)
+    pub swap_mode: u8,
+    /// trade with plain SOL on WSOL quoted curves, the payer's lamports are wrapped into the
+    /// quote token account of the swap, which is closed back to the payer afterwards
+    pub use_native_sol: bool,
+    /// last slot the swap may execute in, 0 never expires
+    pub valid_until_slot: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct SwapParametersV6 {
+    /// input amount, the output amount in exact out mode
+    pub amount: u64,
+    /// minimum output amount, the maximum input amount in exact out mode
+    pub other_amount_threshold: u64,
+    /// max move of the curve's spot price caused by the swap in bps, 0 disables the guard
+    pub max_price_impact_basis_points: u16,
+    /// swap mode (0: ExactIn, 1: ExactOut)
+    pub swap_mode: u8,
+    /// trade with plain SOL on WSOL quoted curves, the payer's lamports are wrapped into the
+    /// quote token account of the swap, which is closed back to the payer afterwards
+    pub use_native_sol: bool,
+    /// last slot the swap may execute in, 0 never expires
+    pub valid_until_slot: u64,
+    /// max shortfall of the curve output against the output at the spot price the swap
+    /// executes at, in bps. Checked against the reserves at execution instead of an absolute
+    /// bound signed ahead, 0 disables the guard
+    pub max_slippage_basis_points: u16,
+}
+
+/// Swap parameters with a leading version byte (the borsh variant index), new swap options
+/// get a new version instead of a new swap instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub enum VersionedSwapParameters {
+    V1(SwapParameters),
+    V2(SwapParametersV2),
+    V3(SwapParametersV3),
+    V4(SwapParametersV4),
+    V5(SwapParametersV5),
+    V6(SwapParametersV6),
+}
+
+impl VersionedSwapParameters {
+    /// Upgrade to the latest version, options missing in older versions are disabled
+    pub fn into_latest(self) -> SwapParametersV6 {
+        match self {
+            VersionedSwapParameters::V1(SwapParameters {
+                amount_in,
+                minimum_amount_out,
+            }) => SwapParametersV6 {
+                amount: amount_in,
+                other_amount_threshold: minimum_amount_out,
+                max_price_impact_basis_points: 0,
+                swap_mode: SwapMode::ExactIn.into(),
+                use_native_sol: false,
+                valid_until_slot: 0,
+                max_slippage_basis_points: 0,
+            },
+            VersionedSwapParameters::V2(SwapParametersV2 {
+                amount_in,
+                minimum_amount_out,
+                max_price_impact_basis_points,
+            }) => SwapParametersV6 {
+                amount: amount_in,
+                other_amount_threshold: minimum_amount_out,
+                max_price_impact_basis_points,
+                swap_mode: SwapMode::ExactIn.into(),
+                use_native_sol: false,
+                valid_until_slot: 0,
+                max_slippage_basis_points: 0,
+            },
+            VersionedSwapParameters::V3(SwapParametersV3 {
+                amount,
+                other_amount_threshold,
+                max_price_impact_basis_points,
+                swap_mode,
+            }) => SwapParametersV6 {
+                amount,
+                other_amount_threshold,
+                max_price_impact_basis_points,
+                swap_mode,
+                use_native_sol: false,
+                valid_until_slot: 0,
+                max_slippage_basis_points: 0,
+            },
+            VersionedSwapParameters::V4(SwapParametersV4 {
+                amount,
+                other_amount_threshold,
+                max_price_impact_basis_points,
+                swap_mode,
+                use_native_sol,
+            }) => SwapParametersV6 {
+                amount,
+                other_amount_threshold,
+                max_price_impact_basis_points,
+                swap_mode,
+                use_native_sol,
+                valid_until_slot: 0,
+                max_slippage_basis_points: 0,
+            },
+            VersionedSwapParameters::V5(SwapParametersV5 {
+                amount,
+                other_amount_threshold,
+                max_price_impact_basis_points,
+                swap_mode,
+                use_native_sol,
+                valid_until_slot,
+            }) => SwapParametersV6 {
+                amount,
+                other_amount_threshold,
+                max_price_impact_basis_points,
+                swap_mode,
+                use_native_sol,
+                valid_until_slot,
+                max_slippage_basis_points: 0,
+            },
+            VersionedSwapParameters::V6(params) => params,
+        }
+    }
 }
 
 #[event_cpi]
@@ -30,10 +205,25 @@ pub struct SwapCtx<'info> {
     pub curve_authority: AccountInfo<'info>,
 
     /// config key
+    #[account(mut)]
     pub config: AccountLoader<'info, Config>,
 
-    /// bonding curve account
-    #[account(mut, has_one = base_vault, has_one = quote_vault, has_one = config)]
+    /// bonding curve account, the token programs are checked against the mints before any token
+    /// account is
+    #[account(
+        mut,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = config,
+        constraint = is_token_program_of(
+            &base_mint.to_account_info(),
+            &token_base_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
+        constraint = is_token_program_of(
+            &quote_mint.to_account_info(),
+            &token_quote_program.key(),
+        ) @ AmmError::InvalidTokenProgram,
+    )]
     pub curve: AccountLoader<'info, BondingCurve>,
 
     /// The user token account for input token
@@ -58,7 +248,9 @@ pub struct SwapCtx<'info> {
     /// The mint of quote token
     pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    /// The user performing the swap
+    /// The user performing the swap.
+    /// This can be a program derived address when the swap is invoked via CPI, as long as the
+    /// calling program signs for it with `invoke_signed`. It only has to own `input_token_account`.
     pub payer: Signer<'info>,
 
     /// Token base program
@@ -108,6 +300,32 @@ pub struct SwapCtx<'info> {
         token::token_program = token_quote_program,
     )]
     pub l3_referral_cashback_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// optional user protection account of the curve (must be initialized, if present)
+    /// Required for every swap when the curve has a sell lockup (`min_hold_seconds`), and for buys
+    /// during the config's anti-snipe window
+    /// PDA validation is done manually in the handler
+    #[account(mut)]
+    pub protection: Option<AccountLoader<'info, Protection>>,
+
+    /// optional bonus pool of the curve, early buys are accrued into it with the protection account
+    /// PDA validation is done manually in the handler
+    #[account(mut)]
+    pub bonus_pool: Option<AccountLoader<'info, BonusPool>>,
+
+    /// optional referral account of the user, required to pay referral fees, which only go to
+    /// the cashback token accounts of its referrer chain
+    /// PDA validation is done manually in the handler
+    pub referral: Option<AccountLoader<'info, ReferralAccount>>,
+
+    /// stats of the config, every swap is counted into it
+    #[account(mut, has_one = config)]
+    pub config_stats: AccountLoader<'info, ConfigStats>,
+
+    /// optional cashback settings, with the cashback account the wallet gets its tier's trading
+    /// fee discount
+    #[account(address = const_pda::cashback_config::ID)]
+    pub cashback_config: Option<AccountLoader<'info, CashbackConfig>>,
 }
 
 impl<'info> SwapCtx<'info> {
@@ -120,18 +338,20 @@ impl<'info> SwapCtx<'info> {
     }
 }
 
-pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()> {
-    // Validate that both cashback account and token account are provided together or both are None
+/// The swap result is returned with the return data, so CPI callers read the output and the
+/// fees without parsing logs
+pub fn handle_swap(ctx: Context<SwapCtx>, params: VersionedSwapParameters) -> Result<SwapResult> {
+    #[cfg(feature = "swap-budget-guard")]
+    let budget_guard = crate::utils::SwapBudgetGuard::start();
+
+    // A cashback token account needs the cashback account, the cashback account alone is the
+    // native cashback mode, checked with the swap parameters below
     require!(
-        (ctx.accounts.cashback.is_some() && ctx.accounts.cashback_token_account.is_some())
-            || (ctx.accounts.cashback.is_none() && ctx.accounts.cashback_token_account.is_none()),
+        ctx.accounts.cashback.is_some() || ctx.accounts.cashback_token_account.is_none(),
         AmmError::InvalidCashbackTokenAccount
     );
 
-    // Validate cashback token account is the correct ATA if both are provided
-    if let (Some(ref cashback), Some(ref cashback_token_account)) =
-        (&ctx.accounts.cashback, &ctx.accounts.cashback_token_account)
-    {
+    if let Some(ref cashback) = ctx.accounts.cashback {
         // Manually validate cashback account PDA
         let (expected_cashback_pda, _bump) =
             const_pda::cashback::derive_pda(&ctx.accounts.payer.key());
@@ -140,17 +360,88 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
             AmmError::InvalidCashbackTokenAccount
         );
 
-        let expected_cashback_ata =
-            get_associated_token_address(&cashback.key(), &ctx.accounts.quote_mint.key());
+        // Validate cashback token account is the correct ATA if provided
+        if let Some(ref cashback_token_account) = ctx.accounts.cashback_token_account {
+            let expected_cashback_ata = get_associated_token_address_with_program_id(
+                &cashback.key(),
+                &ctx.accounts.quote_mint.key(),
+                &ctx.accounts.token_quote_program.key(),
+            );
+            require!(
+                cashback_token_account.key() == expected_cashback_ata,
+                AmmError::InvalidCashbackTokenAccount
+            );
+
+            // Validate token account authority matches cashback PDA
+            require!(
+                cashback_token_account.owner == cashback.key(),
+                AmmError::InvalidCashbackTokenAccount
+            );
+        }
+    }
+
+    // Referral fees only go to the recorded referrer chain of the user, every level of the chain
+    // needs its token account so fees can't be redirected or skipped
+    let referrers = match ctx.accounts.referral {
+        Some(ref referral) => {
+            let (expected_referral_pda, _bump) =
+                const_pda::referral::derive_pda(&ctx.accounts.payer.key());
+            require!(
+                referral.key() == expected_referral_pda,
+                AmmError::InvalidAccount
+            );
+            referral.load()?.get_referrers()
+        }
+        None => [None; 3],
+    };
+    let referral_token_accounts = [
+        &ctx.accounts.l1_referral_cashback_token_account,
+        &ctx.accounts.l2_referral_cashback_token_account,
+        &ctx.accounts.l3_referral_cashback_token_account,
+    ];
+    // like the user's cashback token account, each one has to be the ATA of the referrer's
+    // cashback PDA and still be owned by it
+    for (referrer, referral_token_account) in referrers.iter().zip(referral_token_accounts) {
+        match (referrer, referral_token_account) {
+            (Some(referrer), Some(referral_token_account)) => {
+                let (referrer_cashback_pda, _bump) = const_pda::cashback::derive_pda(referrer);
+                let expected_referral_ata = get_associated_token_address_with_program_id(
+                    &referrer_cashback_pda,
+                    &ctx.accounts.quote_mint.key(),
+                    &ctx.accounts.token_quote_program.key(),
+                );
+                require!(
+                    referral_token_account.key() == expected_referral_ata
+                        && referral_token_account.owner == referrer_cashback_pda,
+                    AmmError::InvalidReferralTokenAccount
+                );
+            }
+            (None, None) => {}
+            _ => return err!(AmmError::InvalidReferralTokenAccount),
+        }
+    }
+
+    // Validate that the bonus pool is only provided together with the protection account
+    require!(
+        ctx.accounts.protection.is_some() || ctx.accounts.bonus_pool.is_none(),
+        AmmError::InvalidAccount
+    );
+
+    // Manually validate protection and bonus pool PDAs
+    let curve_key = ctx.accounts.curve.key();
+    if let Some(ref protection) = ctx.accounts.protection {
+        let (expected_protection_pda, _bump) =
+            const_pda::protection::derive_pda(&curve_key, &ctx.accounts.payer.key());
         require!(
-            cashback_token_account.key() == expected_cashback_ata,
-            AmmError::InvalidCashbackTokenAccount
+            protection.key() == expected_protection_pda,
+            AmmError::InvalidAccount
         );
-
-        // Validate token account authority matches cashback PDA
+    }
+    if let Some(ref bonus_pool) = ctx.accounts.bonus_pool {
+        let (expected_bonus_pool_pda, _bump) = const_pda::bonus_pool::derive_pda(&curve_key);
         require!(
-            cashback_token_account.owner == cashback.key(),
-            AmmError::InvalidCashbackTokenAccount
+            bonus_pool.key() == expected_bonus_pool_pda,
+            AmmError::InvalidAccount
         );
     }
 
@@ -179,10 +470,37 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
         }
     }
 
-    let SwapParameters {
-        amount_in,
-        minimum_amount_out,
-    } = params;
+    let SwapParametersV6 {
+        amount,
+        other_amount_threshold,
+        max_price_impact_basis_points,
+        swap_mode,
+        use_native_sol,
+        valid_until_slot,
+        max_slippage_basis_points,
+    } = params.into_latest();
+    // a transaction landing long after it was signed may trade at prices its slippage bound
+    // no longer reflects
+    require!(
+        valid_until_slot == 0 || Clock::get()?.slot <= valid_until_slot,
+        AmmError::SwapExpired
+    );
+    let swap_mode = SwapMode::try_from(swap_mode).map_err(|_| AmmError::InvalidSwapMode)?;
+    require!(
+        !use_native_sol || ctx.accounts.quote_mint.key() == native_mint::ID,
+        AmmError::InvalidQuoteMint
+    );
+    // the native cashback is unwrapped along the quote token account of a native SOL swap
+    let native_cashback = match ctx.accounts.cashback {
+        Some(ref cashback) if ctx.accounts.cashback_token_account.is_none() => {
+            require!(
+                use_native_sol && cashback.load()?.get_cashback_mode()? == CashbackMode::Native,
+                AmmError::NativeCashbackUnavailable
+            );
+            true
+        }
+        _ => false,
+    };
     let (
         token_in_mint,
         token_out_mint,
@@ -208,41 +526,182 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
             &ctx.accounts.token_base_program,
         ),
     };
-    require!(amount_in > 0, AmmError::AmountIsZero);
+    require!(amount > 0, AmmError::AmountIsZero);
 
-    let config = ctx.accounts.config.load()?;
+    let mut config = ctx.accounts.config.load_mut()?;
     let mut curve = ctx.accounts.curve.load_mut()?;
 
+    require!(!config.is_paused(), AmmError::ConfigPaused);
+    require!(!curve.is_paused(), AmmError::CurvePaused);
+    require!(
+        !config.get_trading_pause()?.is_paused(trade_direction)
+            && !curve.get_trading_pause()?.is_paused(trade_direction),
+        AmmError::TradingPaused
+    );
+    let clock = Clock::get()?;
+    let current_timestamp = get_current_timestamp(ctx.remaining_accounts)?;
+    curve.check_activated(current_timestamp, clock.slot)?;
+    curve.check_trading_end(current_timestamp, trade_direction)?;
+    let fee_config =
+        config.get_launch_fee_config(curve.get_launch_elapsed_seconds(current_timestamp))?;
+
     // validate if it is over threshold (aka ready for migration)
     require!(
-        !curve.is_curve_complete(config.migration_base_threshold),
+        curve.get_migration_progress()? == MigrationStatus::PreBondingCurve
+            && !curve.is_curve_complete(curve.get_migration_base_threshold(&config), None),
         AmmError::PoolIsCompleted
     );
 
     // Get cashback tier if user has a cashback account
     let cashback_tier = if let Some(ref cashback_account) = ctx.accounts.cashback {
-        let account = cashback_account.load()?;
-        Some(account.get_tier()?)
+        let tier = cashback_account.load()?.get_tier()?;
+        Some(match ctx.accounts.cashback_config {
+            Some(ref cashback_config) => cashback_config.load()?.get_fee_tier(tier),
+            None => tier.into(),
+        })
     } else {
         None
     };
 
+    let has_l1_referral = ctx.accounts.l1_referral_cashback_token_account.is_some();
+    let has_l2_referral = ctx.accounts.l2_referral_cashback_token_account.is_some();
+    let has_l3_referral = ctx.accounts.l3_referral_cashback_token_account.is_some();
+    let has_referral = has_l1_referral || has_l2_referral || has_l3_referral;
+    let amount_in = match swap_mode {
+        SwapMode::ExactIn => amount,
+        SwapMode::ExactOut => curve.get_amount_in_for_exact_out(
+            &fee_config,
+            amount,
+            trade_direction,
+            has_l1_referral,
+            has_l2_referral,
+            has_l3_referral,
+            cashback_tier,
+        )?,
+    };
     let swap_result = curve.get_swap_result(
-        &config,
+        &fee_config,
         amount_in,
         trade_direction,
-        ctx.accounts.l1_referral_cashback_token_account.is_some(),
-        ctx.accounts.l2_referral_cashback_token_account.is_some(),
-        ctx.accounts.l3_referral_cashback_token_account.is_some(),
+        has_l1_referral,
+        has_l2_referral,
+        has_l3_referral,
         cashback_tier,
     )?;
 
-    require!(
-        swap_result.output_amount >= minimum_amount_out,
-        AmmError::ExceededSlippage
-    );
+    match swap_mode {
+        SwapMode::ExactIn => require!(
+            swap_result.output_amount >= other_amount_threshold,
+            AmmError::ExceededSlippage
+        ),
+        // a buy capped at the migration threshold falls short of the requested output
+        SwapMode::ExactOut => require!(
+            swap_result.output_amount >= amount
+                && swap_result.get_total_input_amount(trade_direction)? <= other_amount_threshold,
+            AmmError::ExceededSlippage
+        ),
+    }
+
+    let virtual_quote_reserve_before = curve.get_virtual_quote_reserve();
+    let virtual_base_reserve_before = curve.get_virtual_base_reserve();
+    curve.apply_swap_result(&swap_result, trade_direction, current_timestamp)?;
+    record_swap(&ctx.accounts.config_stats, &swap_result, trade_direction)?;
 
-    curve.apply_swap_result(&swap_result, trade_direction)?;
+    if max_slippage_basis_points > 0 {
+        let slippage = curve.get_slippage_basis_points(
+            virtual_quote_reserve_before,
+            virtual_base_reserve_before,
+            trade_direction,
+        )?;
+        require!(
+            slippage <= max_slippage_basis_points as u64,
+            AmmError::ExceededSlippage
+        );
+    }
+
+    if max_price_impact_basis_points > 0 {
+        let price_impact = curve.get_price_impact_basis_points(
+            virtual_quote_reserve_before,
+            virtual_base_reserve_before,
+        )?;
+        require!(
+            price_impact <= max_price_impact_basis_points as u64,
+            AmmError::PriceImpactTooHigh
+        );
+    }
+
+    let pre_base_balance = match trade_direction {
+        TradeDirection::BaseToQuote => ctx.accounts.input_token_account.amount,
+        TradeDirection::QuoteToBase => ctx.accounts.output_token_account.amount,
+    };
+
+    // Diamond hands: the last buy of a wallet locks its sells for `min_hold_seconds`
+    if curve.min_hold_seconds > 0 {
+        let protection = ctx
+            .accounts
+            .protection
+            .as_ref()
+            .ok_or(AmmError::ProtectionAccountRequired)?;
+        let mut protection = protection.load_mut()?;
+        match trade_direction {
+            TradeDirection::QuoteToBase => protection.record_buy(current_timestamp),
+            TradeDirection::BaseToQuote => require!(
+                !curve.is_sell_locked(protection.last_buy_timestamp, current_timestamp),
+                AmmError::SellLockupActive
+            ),
+        }
+    }
+
+    // Anti-snipe: buys of a wallet are capped during the first slots after curve creation
+    if trade_direction == TradeDirection::QuoteToBase
+        && curve.is_in_anti_snipe_window(Clock::get()?.slot, config.anti_snipe_window_slots)
+    {
+        let protection = ctx
+            .accounts
+            .protection
+            .as_ref()
+            .ok_or(AmmError::ProtectionAccountRequired)?;
+        protection.load_mut()?.accrue_anti_snipe_buy(
+            swap_result.get_total_input_amount(trade_direction)?,
+            config.max_buy_amount_first_window,
+        )?;
+    }
+
+    // Accrue early buys for the creator bonus pool, net of the early bought base sold since
+    if let (Some(ref protection), Some(ref bonus_pool)) =
+        (&ctx.accounts.protection, &ctx.accounts.bonus_pool)
+    {
+        let mut protection = protection.load_mut()?;
+        let mut bonus_pool = bonus_pool.load_mut()?;
+        let base_balance = match trade_direction {
+            TradeDirection::BaseToQuote => pre_base_balance
+                .saturating_sub(swap_result.get_total_input_amount(trade_direction)?),
+            TradeDirection::QuoteToBase => pre_base_balance,
+        };
+        bonus_pool.settle_early_buys(protection.settle_early_buys(base_balance)?)?;
+
+        if trade_direction == TradeDirection::QuoteToBase
+            && curve.is_in_early_buyer_window(current_timestamp, config.early_buyer_window_seconds)
+        {
+            protection
+                .accrue_early_buy(swap_result.actual_input_amount, swap_result.output_amount)?;
+            bonus_pool.accrue_early_buy(swap_result.actual_input_amount)?;
+        }
+    }
+
+    // native SOL buys wrap the payer's lamports into the input token account first
+    if use_native_sol && trade_direction == TradeDirection::QuoteToBase {
+        let wrap_amount = swap_result
+            .get_total_input_amount(trade_direction)?
+            .saturating_sub(ctx.accounts.input_token_account.amount);
+        wrap_sol(
+            ctx.accounts.payer.to_account_info(),
+            &mut ctx.accounts.input_token_account,
+            ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.token_quote_program,
+            wrap_amount,
+        )?;
+    }
 
     // send to reserve
     transfer_from_user(
@@ -251,11 +710,7 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
         &ctx.accounts.input_token_account,
         input_vault_account,
         input_program,
-        if trade_direction == TradeDirection::QuoteToBase {
-            swap_result.actual_input_amount + swap_result.trading_fee
-        } else {
-            amount_in
-        },
+        swap_result.get_total_input_amount(trade_direction)?,
     )?;
 
     // send to user
@@ -269,9 +724,6 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
         const_pda::curve_authority::BUMP,
     )?;
 
-    let has_referral = ctx.accounts.l1_referral_cashback_token_account.is_some()
-        || ctx.accounts.l2_referral_cashback_token_account.is_some()
-        || ctx.accounts.l3_referral_cashback_token_account.is_some();
     if has_referral {
         if let Some(l1_referral_cashback_token_account) =
             ctx.accounts.l1_referral_cashback_token_account.as_ref()
@@ -315,7 +767,36 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
     }
 
     // Transfer cashback to user if cashback account is provided
-    if let Some(ref cashback_token_account) = ctx.accounts.cashback_token_account {
+    let claimable_cashback = if native_cashback {
+        // the cashback joins the quote token account of the swap, closed to the payer below,
+        // and the payer forwards it as lamports once unwrapped
+        let quote_token_account = match trade_direction {
+            TradeDirection::QuoteToBase => &ctx.accounts.input_token_account,
+            TradeDirection::BaseToQuote => &ctx.accounts.output_token_account,
+        };
+        transfer_from_curve(
+            ctx.accounts.curve_authority.to_account_info(),
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.quote_vault,
+            quote_token_account,
+            &ctx.accounts.token_quote_program,
+            swap_result.cashback_fee,
+            const_pda::curve_authority::BUMP,
+        )?;
+        let cashback_info = ctx
+            .accounts
+            .cashback
+            .as_ref()
+            .ok_or(AmmError::InvalidAccount)?
+            .to_account_info();
+        let rent_exempt = Rent::get()?.minimum_balance(cashback_info.data_len());
+        Some(
+            cashback_info
+                .lamports()
+                .saturating_sub(rent_exempt)
+                .safe_add(swap_result.cashback_fee)?,
+        )
+    } else if let Some(ref mut cashback_token_account) = ctx.accounts.cashback_token_account {
         transfer_from_curve(
             ctx.accounts.curve_authority.to_account_info(),
             &ctx.accounts.quote_mint,
@@ -325,7 +806,25 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
             swap_result.cashback_fee,
             const_pda::curve_authority::BUMP,
         )?;
-    }
+        cashback_token_account.reload()?;
+        Some(cashback_token_account.amount)
+    } else {
+        None
+    };
+    let cashback_fee = swap_result.cashback_fee;
+    // trading keeps the tier from decaying and counts into the lifetime stats
+    let cashback_owner = match ctx.accounts.cashback {
+        Some(ref cashback) => {
+            let mut cashback = cashback.load_mut()?;
+            cashback.record_trade(
+                Clock::get()?.unix_timestamp,
+                swap_result.get_quote_volume(trade_direction)?,
+                swap_result.cashback_fee,
+            )?;
+            Some(cashback.owner)
+        }
+        None => None,
+    };
 
     // Reload the user's base token account to get updated balance
     let user_base_token_account = match trade_direction {
@@ -338,10 +837,15 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
             &ctx.accounts.output_token_account
         }
     };
+    curve.update_holder_count(pre_base_balance, user_base_token_account.amount)?;
 
     emit_cpi!(EvtSwap {
+        event_sequence: config.next_event_sequence()?,
         curve: ctx.accounts.curve.key(),
+        config: ctx.accounts.config.key(),
+        creator: curve.creator,
         base_mint: ctx.accounts.base_mint.key(),
+        quote_mint: ctx.accounts.quote_mint.key(),
         trade_direction: trade_direction.into(),
         has_referral,
         params,
@@ -349,30 +853,64 @@ pub fn handle_swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()>
         virtual_base_reserve: curve.virtual_base_reserve,
         virtual_quote_reserve: curve.virtual_quote_reserve,
         remaining_tokens: user_base_token_account.amount,
+        virtual_price: curve.get_virtual_price()?,
+        base_until_migration: curve
+            .get_base_until_migration(curve.get_migration_base_threshold(&config))
+            .get(),
+        holder_count: curve.holder_count,
+        cashback_owner,
+        claimable_cashback,
     });
 
-    if curve.is_curve_complete(config.migration_base_threshold) {
+    if let Some(quote_threshold) =
+        curve.get_completed_quote_threshold(&config, current_timestamp)?
+    {
         ctx.accounts.base_vault.reload()?;
-        // validate if base reserve is enough token for migration
-        let base_vault_balance = ctx.accounts.base_vault.amount;
-        require!(
-            base_vault_balance >= config.migration_base_threshold,
-            AmmError::InsufficientLiquidityForMigration
-        );
-
-        // set finish time and migration progress
-        let current_timestamp = Clock::get()?.unix_timestamp as u64;
-        curve.curve_finish_timestamp = current_timestamp;
-        curve.set_migration_status(MigrationStatus::PostBondingCurve.into());
+        curve.complete(&config, ctx.accounts.base_vault.amount, current_timestamp)?;
+        record_curve_graduated(&ctx.accounts.config_stats)?;
 
         emit_cpi!(EvtCurveComplete {
+            event_sequence: config.next_event_sequence()?,
             curve: ctx.accounts.curve.key(),
             config: ctx.accounts.config.key(),
             base_mint: ctx.accounts.base_mint.key(),
             base_reserve: curve.base_reserve,
             quote_reserve: curve.quote_reserve,
+            effective_migration_quote_threshold: quote_threshold.get(),
         })
     }
 
-    Ok(())
+    // native SOL: the quote token account of the swap is unwrapped back to the payer
+    if use_native_sol {
+        let quote_token_account = match trade_direction {
+            TradeDirection::QuoteToBase => &ctx.accounts.input_token_account,
+            TradeDirection::BaseToQuote => &ctx.accounts.output_token_account,
+        };
+        unwrap_sol(
+            ctx.accounts.payer.to_account_info(),
+            quote_token_account,
+            &ctx.accounts.token_quote_program,
+        )?;
+    }
+
+    if native_cashback && cashback_fee > 0 {
+        let cashback = ctx
+            .accounts
+            .cashback
+            .as_ref()
+            .ok_or(AmmError::InvalidAccount)?;
+        invoke(
+            &transfer(&ctx.accounts.payer.key(), &cashback.key(), cashback_fee),
+            &[
+                ctx.accounts.payer.to_account_info(),
+                cashback.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    #[cfg(feature = "swap-budget-guard")]
+    budget_guard.check(ctx.accounts.to_account_infos().len() + ctx.remaining_accounts.len())?;
+
+    Ok(swap_result)
 }
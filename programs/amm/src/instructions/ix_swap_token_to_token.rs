@@ -0,0 +1,475 @@
+use anchor_lang::prelude::*;
+use anchor_lang::{AnchorDeserialize, AnchorSerialize};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    events::{EvtCurveComplete, EvtSwap, EvtSwapTokenToToken},
+    instructions::{SwapParameters, VersionedSwapParameters},
+    params::swap::TradeDirection,
+    states::{BondingCurve, BonusPool, Config, ConfigStats, MigrationStatus, Protection},
+    utils::{
+        get_current_timestamp, record_curve_graduated, record_swap, transfer_from_curve,
+        transfer_from_user,
+    },
+    AmmError,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct SwapTokenToTokenParameters {
+    /// base amount of curve a to sell
+    pub amount_in: u64,
+    /// minimum base amount of curve b to buy
+    pub minimum_amount_out: u64,
+}
+
+/// Sell on curve a and buy on curve b with the proceeds, both curves have to share the quote mint.
+/// The quote leg moves from vault to vault and never touches the user.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SwapTokenToTokenCtx<'info> {
+    /// CHECK: curve authority is validated by address constraint to match predefined PDA
+    #[account(
+        address = const_pda::curve_authority::ID,
+    )]
+    pub curve_authority: AccountInfo<'info>,
+
+    /// config of the curve sold on
+    #[account(mut)]
+    pub config_a: AccountLoader<'info, Config>,
+
+    /// bonding curve sold on
+    #[account(mut)]
+    pub curve_a: AccountLoader<'info, BondingCurve>,
+
+    /// The vault token account for base token of curve a
+    #[account(mut, token::token_program = token_base_a_program, token::mint = base_mint_a)]
+    pub base_vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for quote token of curve a
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub quote_vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of base token of curve a
+    pub base_mint_a: Box<InterfaceAccount<'info, Mint>>,
+
+    /// config of the curve bought on, can be the config of curve a
+    #[account(mut)]
+    pub config_b: AccountLoader<'info, Config>,
+
+    /// bonding curve bought on
+    #[account(mut)]
+    pub curve_b: AccountLoader<'info, BondingCurve>,
+
+    /// The vault token account for base token of curve b
+    #[account(mut, token::token_program = token_base_b_program, token::mint = base_mint_b)]
+    pub base_vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for quote token of curve b
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub quote_vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of base token of curve b
+    pub base_mint_b: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The quote mint shared by both curves
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The user token account for base token of curve a
+    #[account(mut, token::mint = base_mint_a)]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The user token account for base token of curve b
+    #[account(mut, token::mint = base_mint_b)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The user performing the swap, it only has to own `input_token_account`
+    pub payer: Signer<'info>,
+
+    /// Token program of base token of curve a
+    pub token_base_a_program: Interface<'info, TokenInterface>,
+
+    /// Token program of base token of curve b
+    pub token_base_b_program: Interface<'info, TokenInterface>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+
+    /// optional user protection account of curve a, required when curve a has a sell lockup
+    /// PDA validation is done manually in the handler
+    #[account(mut)]
+    pub protection_a: Option<AccountLoader<'info, Protection>>,
+
+    /// optional bonus pool of curve a, the early buys sold are settled out of it with the
+    /// protection account
+    /// PDA validation is done manually in the handler
+    #[account(mut)]
+    pub bonus_pool_a: Option<AccountLoader<'info, BonusPool>>,
+
+    /// optional user protection account of curve b, required when curve b has a sell lockup or is
+    /// in its anti-snipe window
+    /// PDA validation is done manually in the handler
+    #[account(mut)]
+    pub protection_b: Option<AccountLoader<'info, Protection>>,
+
+    /// stats of config a, the sell leg is counted into it
+    #[account(
+        mut,
+        constraint = config_stats_a.load()?.config == config_a.key() @ AmmError::InvalidAccount,
+    )]
+    pub config_stats_a: AccountLoader<'info, ConfigStats>,
+
+    /// stats of config b, the buy leg is counted into it, can be the stats of config a
+    #[account(
+        mut,
+        constraint = config_stats_b.load()?.config == config_b.key() @ AmmError::InvalidAccount,
+    )]
+    pub config_stats_b: AccountLoader<'info, ConfigStats>,
+}
+
+/// Checks the accounts passed for a leg belong to its curve
+fn validate_leg(
+    curve: &BondingCurve,
+    config: &Config,
+    config_key: Pubkey,
+    base_vault_key: Pubkey,
+    quote_vault_key: Pubkey,
+    quote_mint_key: Pubkey,
+) -> Result<()> {
+    require!(
+        curve.config == config_key
+            && curve.base_vault == base_vault_key
+            && curve.quote_vault == quote_vault_key
+            && config.quote_mint == quote_mint_key,
+        AmmError::InvalidAccount
+    );
+    Ok(())
+}
+
+/// Checks the curve of a leg still trades in the leg's direction
+fn check_leg_trading(
+    curve: &BondingCurve,
+    config: &Config,
+    trade_direction: TradeDirection,
+    current_timestamp: u64,
+) -> Result<()> {
+    require!(!config.is_paused(), AmmError::ConfigPaused);
+    require!(!curve.is_paused(), AmmError::CurvePaused);
+    require!(
+        !config.get_trading_pause()?.is_paused(trade_direction)
+            && !curve.get_trading_pause()?.is_paused(trade_direction),
+        AmmError::TradingPaused
+    );
+    curve.check_activated(current_timestamp, Clock::get()?.slot)?;
+    curve.check_trading_end(current_timestamp, trade_direction)?;
+    require!(
+        curve.get_migration_progress()? == MigrationStatus::PreBondingCurve
+            && !curve.is_curve_complete(curve.get_migration_base_threshold(config), None),
+        AmmError::PoolIsCompleted
+    );
+    Ok(())
+}
+
+/// Sells base a for quote on curve a then buys base b on curve b with all of it. The sell leg
+/// settles the early buys sold out of curve a's bonus pool like a direct sell, but routed swaps
+/// don't pay referral fees or cashback and the buy leg doesn't accrue early buys. The buy leg fee
+/// follows the `routed_swap_fee_mode` of curve b's config
+pub fn handle_swap_token_to_token(
+    ctx: Context<SwapTokenToTokenCtx>,
+    params: SwapTokenToTokenParameters,
+) -> Result<()> {
+    let SwapTokenToTokenParameters {
+        amount_in,
+        minimum_amount_out,
+    } = params;
+    require!(amount_in > 0, AmmError::AmountIsZero);
+
+    let curve_a_key = ctx.accounts.curve_a.key();
+    let curve_b_key = ctx.accounts.curve_b.key();
+    require_keys_neq!(curve_a_key, curve_b_key, AmmError::InvalidAccount);
+
+    // Validate that the bonus pool is only provided together with the protection account
+    require!(
+        ctx.accounts.protection_a.is_some() || ctx.accounts.bonus_pool_a.is_none(),
+        AmmError::InvalidAccount
+    );
+
+    // Manually validate protection and bonus pool PDAs
+    let payer_key = ctx.accounts.payer.key();
+    if let Some(ref protection) = ctx.accounts.protection_a {
+        let (expected_protection_pda, _bump) =
+            const_pda::protection::derive_pda(&curve_a_key, &payer_key);
+        require!(
+            protection.key() == expected_protection_pda,
+            AmmError::InvalidAccount
+        );
+    }
+    if let Some(ref bonus_pool) = ctx.accounts.bonus_pool_a {
+        let (expected_bonus_pool_pda, _bump) = const_pda::bonus_pool::derive_pda(&curve_a_key);
+        require!(
+            bonus_pool.key() == expected_bonus_pool_pda,
+            AmmError::InvalidAccount
+        );
+    }
+    if let Some(ref protection) = ctx.accounts.protection_b {
+        let (expected_protection_pda, _bump) =
+            const_pda::protection::derive_pda(&curve_b_key, &payer_key);
+        require!(
+            protection.key() == expected_protection_pda,
+            AmmError::InvalidAccount
+        );
+    }
+
+    let current_timestamp = get_current_timestamp(ctx.remaining_accounts)?;
+
+    // sell leg, both configs can be the same account so each leg only borrows its own
+    let quote_amount = {
+        let mut config = ctx.accounts.config_a.load_mut()?;
+        let mut curve = ctx.accounts.curve_a.load_mut()?;
+        validate_leg(
+            &curve,
+            &config,
+            ctx.accounts.config_a.key(),
+            ctx.accounts.base_vault_a.key(),
+            ctx.accounts.quote_vault_a.key(),
+            ctx.accounts.quote_mint.key(),
+        )?;
+        check_leg_trading(
+            &curve,
+            &config,
+            TradeDirection::BaseToQuote,
+            current_timestamp,
+        )?;
+
+        if curve.min_hold_seconds > 0 {
+            let protection = ctx
+                .accounts
+                .protection_a
+                .as_ref()
+                .ok_or(AmmError::ProtectionAccountRequired)?;
+            require!(
+                !curve.is_sell_locked(protection.load()?.last_buy_timestamp, current_timestamp),
+                AmmError::SellLockupActive
+            );
+        }
+
+        let swap_result = curve.get_swap_result(
+            &config.get_launch_fee_config(curve.get_launch_elapsed_seconds(current_timestamp))?,
+            amount_in,
+            TradeDirection::BaseToQuote,
+            false,
+            false,
+            false,
+            None,
+        )?;
+        curve.apply_swap_result(&swap_result, TradeDirection::BaseToQuote, current_timestamp)?;
+        record_swap(
+            &ctx.accounts.config_stats_a,
+            &swap_result,
+            TradeDirection::BaseToQuote,
+        )?;
+
+        let pre_base_balance = ctx.accounts.input_token_account.amount;
+        transfer_from_user(
+            &ctx.accounts.payer,
+            &ctx.accounts.base_mint_a,
+            &ctx.accounts.input_token_account,
+            &ctx.accounts.base_vault_a,
+            &ctx.accounts.token_base_a_program,
+            amount_in,
+        )?;
+        // the proceeds go straight to the quote vault of curve b
+        transfer_from_curve(
+            ctx.accounts.curve_authority.to_account_info(),
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.quote_vault_a,
+            &ctx.accounts.quote_vault_b,
+            &ctx.accounts.token_quote_program,
+            swap_result.output_amount,
+            const_pda::curve_authority::BUMP,
+        )?;
+
+        ctx.accounts.input_token_account.reload()?;
+        curve.update_holder_count(pre_base_balance, ctx.accounts.input_token_account.amount)?;
+
+        // Settle the early buys sold out of the creator bonus pool, like a direct sell
+        if let (Some(ref protection), Some(ref bonus_pool)) =
+            (&ctx.accounts.protection_a, &ctx.accounts.bonus_pool_a)
+        {
+            let settled_quote_amount = protection
+                .load_mut()?
+                .settle_early_buys(ctx.accounts.input_token_account.amount)?;
+            bonus_pool
+                .load_mut()?
+                .settle_early_buys(settled_quote_amount)?;
+        }
+
+        let quote_amount = swap_result.output_amount;
+        emit_cpi!(EvtSwap {
+            event_sequence: config.next_event_sequence()?,
+            curve: curve_a_key,
+            config: ctx.accounts.config_a.key(),
+            creator: curve.creator,
+            base_mint: ctx.accounts.base_mint_a.key(),
+            quote_mint: ctx.accounts.quote_mint.key(),
+            trade_direction: TradeDirection::BaseToQuote.into(),
+            has_referral: false,
+            params: VersionedSwapParameters::V1(SwapParameters {
+                amount_in,
+                minimum_amount_out: 0,
+            }),
+            swap_result,
+            virtual_base_reserve: curve.virtual_base_reserve,
+            virtual_quote_reserve: curve.virtual_quote_reserve,
+            remaining_tokens: ctx.accounts.input_token_account.amount,
+            virtual_price: curve.get_virtual_price()?,
+            base_until_migration: curve
+                .get_base_until_migration(curve.get_migration_base_threshold(&config))
+                .get(),
+            holder_count: curve.holder_count,
+            cashback_owner: None,
+            claimable_cashback: None,
+        });
+        quote_amount
+    };
+    require!(quote_amount > 0, AmmError::AmountIsZero);
+
+    // buy leg
+    let mut config = ctx.accounts.config_b.load_mut()?;
+    let mut curve = ctx.accounts.curve_b.load_mut()?;
+    validate_leg(
+        &curve,
+        &config,
+        ctx.accounts.config_b.key(),
+        ctx.accounts.base_vault_b.key(),
+        ctx.accounts.quote_vault_b.key(),
+        ctx.accounts.quote_mint.key(),
+    )?;
+    check_leg_trading(
+        &curve,
+        &config,
+        TradeDirection::QuoteToBase,
+        current_timestamp,
+    )?;
+
+    let swap_result = curve.get_swap_result(
+        &config
+            .get_launch_fee_config(curve.get_launch_elapsed_seconds(current_timestamp))?
+            .get_routed_buy_config()?,
+        quote_amount,
+        TradeDirection::QuoteToBase,
+        false,
+        false,
+        false,
+        None,
+    )?;
+    // the quote already sits in the vault, a capped buy would leave part of it unaccounted for
+    require!(
+        swap_result.get_total_input_amount(TradeDirection::QuoteToBase)? == quote_amount,
+        AmmError::RoutedBuyCapped
+    );
+    require!(
+        swap_result.output_amount >= minimum_amount_out,
+        AmmError::ExceededSlippage
+    );
+    curve.apply_swap_result(&swap_result, TradeDirection::QuoteToBase, current_timestamp)?;
+    record_swap(
+        &ctx.accounts.config_stats_b,
+        &swap_result,
+        TradeDirection::QuoteToBase,
+    )?;
+
+    // Diamond hands: the last buy of a wallet locks its sells for `min_hold_seconds`
+    if curve.min_hold_seconds > 0 {
+        let protection = ctx
+            .accounts
+            .protection_b
+            .as_ref()
+            .ok_or(AmmError::ProtectionAccountRequired)?;
+        protection.load_mut()?.record_buy(current_timestamp);
+    }
+
+    // Anti-snipe: routed buys count towards the wallet's cap like direct buys
+    if curve.is_in_anti_snipe_window(Clock::get()?.slot, config.anti_snipe_window_slots) {
+        let protection = ctx
+            .accounts
+            .protection_b
+            .as_ref()
+            .ok_or(AmmError::ProtectionAccountRequired)?;
+        protection
+            .load_mut()?
+            .accrue_anti_snipe_buy(quote_amount, config.max_buy_amount_first_window)?;
+    }
+
+    let pre_base_balance = ctx.accounts.output_token_account.amount;
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.base_mint_b,
+        &ctx.accounts.base_vault_b,
+        &ctx.accounts.output_token_account,
+        &ctx.accounts.token_base_b_program,
+        swap_result.output_amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    ctx.accounts.output_token_account.reload()?;
+    curve.update_holder_count(pre_base_balance, ctx.accounts.output_token_account.amount)?;
+
+    let amount_out = swap_result.output_amount;
+    emit_cpi!(EvtSwap {
+        event_sequence: config.next_event_sequence()?,
+        curve: curve_b_key,
+        config: ctx.accounts.config_b.key(),
+        creator: curve.creator,
+        base_mint: ctx.accounts.base_mint_b.key(),
+        quote_mint: ctx.accounts.quote_mint.key(),
+        trade_direction: TradeDirection::QuoteToBase.into(),
+        has_referral: false,
+        params: VersionedSwapParameters::V1(SwapParameters {
+            amount_in: quote_amount,
+            minimum_amount_out,
+        }),
+        swap_result,
+        virtual_base_reserve: curve.virtual_base_reserve,
+        virtual_quote_reserve: curve.virtual_quote_reserve,
+        remaining_tokens: ctx.accounts.output_token_account.amount,
+        virtual_price: curve.get_virtual_price()?,
+        base_until_migration: curve
+            .get_base_until_migration(curve.get_migration_base_threshold(&config))
+            .get(),
+        holder_count: curve.holder_count,
+        cashback_owner: None,
+        claimable_cashback: None,
+    });
+
+    emit_cpi!(EvtSwapTokenToToken {
+        event_sequence: config.next_event_sequence()?,
+        curve_a: curve_a_key,
+        curve_b: curve_b_key,
+        trader: ctx.accounts.payer.key(),
+        amount_in,
+        quote_amount,
+        amount_out,
+        routed_swap_fee_mode: config.routed_swap_fee_mode,
+    });
+
+    if let Some(quote_threshold) =
+        curve.get_completed_quote_threshold(&config, current_timestamp)?
+    {
+        ctx.accounts.base_vault_b.reload()?;
+        curve.complete(&config, ctx.accounts.base_vault_b.amount, current_timestamp)?;
+        record_curve_graduated(&ctx.accounts.config_stats_b)?;
+
+        emit_cpi!(EvtCurveComplete {
+            event_sequence: config.next_event_sequence()?,
+            curve: curve_b_key,
+            config: ctx.accounts.config_b.key(),
+            base_mint: ctx.accounts.base_mint_b.key(),
+            base_reserve: curve.base_reserve,
+            quote_reserve: curve.quote_reserve,
+            effective_migration_quote_threshold: quote_threshold.get(),
+        })
+    }
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    errors::AmmError,
+    events::EvtTransferCreator,
+    safe_math::SafeMath,
+    states::{BondingCurve, Config, CreatorFeeTransferMode},
+    utils::token::transfer_from_curve,
+};
+
+/// Accounts for the creator to hand the creator role over
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferCreatorCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config the bonding curve belongs to
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        has_one = quote_vault,
+        has_one = creator,
+        has_one = config,
+    )]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// CHECK: new creator, doesn't have to be a signer
+    #[account(constraint = new_creator.key() != creator.key() @ AmmError::InvalidAccount)]
+    pub new_creator: UncheckedAccount<'info>,
+
+    /// Old creator's token account to receive the accrued fee when it's claimed first
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = quote_mint,
+        associated_token::authority = creator,
+        associated_token::token_program = token_quote_program,
+    )]
+    pub creator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: fee claimer of the config
+    #[account(address = config.load()?.fee_claimer @ AmmError::InvalidAccount)]
+    pub fee_claimer: UncheckedAccount<'info>,
+
+    /// Fee claimer's token account to receive the protocol's cut when the fee is claimed first
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = quote_mint,
+        associated_token::authority = fee_claimer,
+        associated_token::token_program = token_quote_program,
+    )]
+    pub fee_claimer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for output token
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// current creator
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_transfer_creator(
+    ctx: Context<TransferCreatorCtx>,
+    creator_fee_transfer_mode: u8,
+) -> Result<()> {
+    let creator_fee_transfer_mode = CreatorFeeTransferMode::try_from(creator_fee_transfer_mode)
+        .map_err(|_| AmmError::InvalidCreatorFeeTransferMode)?;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+
+    let (moved_creator_fee, claimed_creator_fee, protocol_fee_amount) =
+        match creator_fee_transfer_mode {
+            CreatorFeeTransferMode::MoveWithRole => (curve.creator_fee, 0, 0),
+            CreatorFeeTransferMode::ClaimToOldCreator => {
+                // same split as a regular creator fee claim
+                let claimed_amount = curve.claim_creator_fee();
+                let protocol_fee_amount = config.get_creator_fee_protocol_amount(claimed_amount)?;
+                let claimed_creator_fee = claimed_amount.safe_sub(protocol_fee_amount)?;
+
+                if claimed_creator_fee > 0 {
+                    transfer_from_curve(
+                        ctx.accounts.curve_authority.to_account_info(),
+                        &ctx.accounts.quote_mint,
+                        &ctx.accounts.quote_vault,
+                        &ctx.accounts.creator_token_account,
+                        &ctx.accounts.token_quote_program,
+                        claimed_creator_fee,
+                        const_pda::curve_authority::BUMP,
+                    )?;
+                }
+                if protocol_fee_amount > 0 {
+                    transfer_from_curve(
+                        ctx.accounts.curve_authority.to_account_info(),
+                        &ctx.accounts.quote_mint,
+                        &ctx.accounts.quote_vault,
+                        &ctx.accounts.fee_claimer_token_account,
+                        &ctx.accounts.token_quote_program,
+                        protocol_fee_amount,
+                        const_pda::curve_authority::BUMP,
+                    )?;
+                }
+                (0, claimed_creator_fee, protocol_fee_amount)
+            }
+        };
+
+    curve.creator = ctx.accounts.new_creator.key();
+    // the recipient was picked by the old creator
+    curve.creator_fee_recipient = Pubkey::default();
+
+    emit_cpi!(EvtTransferCreator {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        old_creator: ctx.accounts.creator.key(),
+        new_creator: curve.creator,
+        creator_fee_transfer_mode: creator_fee_transfer_mode.into(),
+        moved_creator_fee,
+        claimed_creator_fee,
+        protocol_fee_amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::states::{BondingCurve, Config};
+
+/// Accounts to check the invariants of a curve, anyone can run the check and only the event
+/// sequence of the config is written
+#[event_cpi]
+#[derive(Accounts)]
+pub struct VerifyCurveInvariantsCtx<'info> {
+    /// config of the curve, holds the initial virtual reserves and sequences the event
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(has_one = config, has_one = base_vault, has_one = quote_vault)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// base vault of the curve
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// quote vault of the curve
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+/// Emits `EvtVerifyCurveInvariants` with the outcome of every check, a broken invariant doesn't
+/// fail the instruction so monitoring bots get the details
+pub fn handle_verify_curve_invariants(ctx: Context<VerifyCurveInvariantsCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let curve = ctx.accounts.curve.load()?;
+
+    let event = curve.verify_invariants(
+        config.next_event_sequence()?,
+        ctx.accounts.curve.key(),
+        config.initial_virtual_quote_reserve,
+        config.initial_virtual_base_reserve,
+        ctx.accounts.base_vault.amount,
+        ctx.accounts.quote_vault.amount,
+    )?;
+    if !event.passed {
+        msg!("curve invariants broken");
+    }
+    emit_cpi!(event);
+
+    Ok(())
+}
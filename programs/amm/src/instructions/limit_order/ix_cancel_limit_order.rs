@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    events::EvtCancelLimitOrder,
+    states::{BondingCurve, Config, LimitOrder},
+    utils::{close_from_curve, transfer_from_curve},
+};
+
+/// Accounts for the owner of a limit order to take its escrow back
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelLimitOrderCtx<'info> {
+    /// CHECK: curve authority, owns the escrow vault
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config the bonding curve belongs to
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve the order trades on
+    #[account(mut, has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        has_one = curve,
+        has_one = owner,
+        has_one = escrow_vault,
+        close = owner,
+    )]
+    pub limit_order: AccountLoader<'info, LimitOrder>,
+
+    /// Token vault escrowing the input and the filler reward
+    #[account(mut, token::mint = input_mint, token::token_program = token_program)]
+    pub escrow_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner's token account receiving the escrow
+    #[account(mut, token::mint = input_mint, token::token_program = token_program)]
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Quote mint for buy orders, base mint for sell orders
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// owner of the order, receives the rent back
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Token program of the input mint
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/// Orders can be cancelled at any time, also after the curve completed
+pub fn handle_cancel_limit_order(ctx: Context<CancelLimitOrderCtx>) -> Result<()> {
+    let refunded_amount = ctx.accounts.escrow_vault.amount;
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.input_mint,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.owner_token_account,
+        &ctx.accounts.token_program,
+        refunded_amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+    close_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.escrow_vault,
+        ctx.accounts.owner.to_account_info(),
+        &ctx.accounts.token_program,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    ctx.accounts.curve.load_mut()?.close_limit_order();
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    emit_cpi!(EvtCancelLimitOrder {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        limit_order: ctx.accounts.limit_order.key(),
+        owner: ctx.accounts.owner.key(),
+        refunded_amount,
+    });
+
+    Ok(())
+}
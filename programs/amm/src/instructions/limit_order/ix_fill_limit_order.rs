@@ -0,0 +1,316 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    errors::AmmError,
+    events::{EvtCurveComplete, EvtFillLimitOrder},
+    params::swap::TradeDirection,
+    states::{BondingCurve, Config, ConfigStats, LimitOrder, MigrationStatus, Protection},
+    utils::{
+        close_from_curve, get_current_timestamp, record_curve_graduated, record_swap,
+        transfer_from_curve,
+    },
+};
+
+/// Accounts for anyone to fill a limit order whose target price the curve reached
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FillLimitOrderCtx<'info> {
+    /// CHECK: curve authority, owns the escrow vault and the curve vaults
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config the bonding curve belongs to
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve the order trades on
+    #[account(
+        mut,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+        has_one = config,
+    )]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        has_one = curve,
+        has_one = owner,
+        has_one = escrow_vault,
+        close = owner,
+    )]
+    pub limit_order: AccountLoader<'info, LimitOrder>,
+
+    /// Token vault escrowing the input and the filler reward
+    #[account(mut)]
+    pub escrow_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: owner of the order, receives the rent of the order and its escrow vault
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+
+    /// Owner's token account receiving the output
+    #[account(mut, token::authority = owner)]
+    pub owner_output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Filler's token account receiving the filler reward, in the input token
+    #[account(mut)]
+    pub filler_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for base token
+    #[account(mut, token::token_program = token_base_program, token::mint = base_mint)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for quote token
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of base token
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Anyone can fill an order once its target price is reached
+    pub filler: Signer<'info>,
+
+    /// Token base program
+    pub token_base_program: Interface<'info, TokenInterface>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+
+    /// optional protection account of the owner, required when the curve has a sell lockup or
+    /// is in its anti-snipe window
+    /// PDA validation is done manually in the handler
+    #[account(mut)]
+    pub protection: Option<AccountLoader<'info, Protection>>,
+
+    /// stats of the config, fills are counted as swaps
+    #[account(mut, has_one = config)]
+    pub config_stats: AccountLoader<'info, ConfigStats>,
+}
+
+/// Fills swap the whole input without referral fees or cashback, at a price no worse than the
+/// target, fees included
+pub fn handle_fill_limit_order(ctx: Context<FillLimitOrderCtx>) -> Result<()> {
+    let curve_key = ctx.accounts.curve.key();
+    let owner_key = ctx.accounts.owner.key();
+    if let Some(ref protection) = ctx.accounts.protection {
+        let (expected_protection_pda, _bump) =
+            const_pda::protection::derive_pda(&curve_key, &owner_key);
+        require!(
+            protection.key() == expected_protection_pda,
+            AmmError::InvalidAccount
+        );
+    }
+
+    let limit_order = *ctx.accounts.limit_order.load()?;
+    let trade_direction = limit_order.get_trade_direction()?;
+    let (
+        token_in_mint,
+        token_out_mint,
+        input_vault_account,
+        output_vault_account,
+        input_program,
+        output_program,
+    ) = match trade_direction {
+        TradeDirection::BaseToQuote => (
+            &ctx.accounts.base_mint,
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.base_vault,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.token_base_program,
+            &ctx.accounts.token_quote_program,
+        ),
+        TradeDirection::QuoteToBase => (
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.base_mint,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.base_vault,
+            &ctx.accounts.token_quote_program,
+            &ctx.accounts.token_base_program,
+        ),
+    };
+    require!(
+        ctx.accounts.owner_output_token_account.mint == token_out_mint.key()
+            && ctx.accounts.filler_token_account.mint == token_in_mint.key(),
+        AmmError::InvalidAccount
+    );
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+    require!(!config.is_paused(), AmmError::ConfigPaused);
+    require!(!curve.is_paused(), AmmError::CurvePaused);
+    require!(
+        !config.get_trading_pause()?.is_paused(trade_direction)
+            && !curve.get_trading_pause()?.is_paused(trade_direction),
+        AmmError::TradingPaused
+    );
+    let clock = Clock::get()?;
+    let current_timestamp = get_current_timestamp(ctx.remaining_accounts)?;
+    curve.check_activated(current_timestamp, clock.slot)?;
+    curve.check_trading_end(current_timestamp, trade_direction)?;
+    let fee_config =
+        config.get_launch_fee_config(curve.get_launch_elapsed_seconds(current_timestamp))?;
+    require!(
+        curve.get_migration_progress()? == MigrationStatus::PreBondingCurve
+            && !curve.is_curve_complete(curve.get_migration_base_threshold(&config), None),
+        AmmError::PoolIsCompleted
+    );
+
+    let virtual_price = curve.get_virtual_price()?;
+    require!(
+        limit_order.is_price_reached(virtual_price)?,
+        AmmError::LimitOrderPriceNotReached
+    );
+
+    let amount_in = limit_order.amount_in;
+    let swap_result = curve.get_swap_result(
+        &fee_config,
+        amount_in,
+        trade_direction,
+        false,
+        false,
+        false,
+        None,
+    )?;
+    // the escrow is swapped whole, a capped buy would leave part of it in the escrow vault
+    require!(
+        swap_result.get_total_input_amount(trade_direction)? == amount_in,
+        AmmError::LimitOrderBuyCapped
+    );
+    require!(
+        limit_order.is_filled_at_target(&swap_result)?,
+        AmmError::LimitOrderPriceNotReached
+    );
+    curve.apply_swap_result(&swap_result, trade_direction, current_timestamp)?;
+    curve.close_limit_order();
+    record_swap(&ctx.accounts.config_stats, &swap_result, trade_direction)?;
+
+    // Diamond hands: the last buy of a wallet locks its sells for `min_hold_seconds`
+    if curve.min_hold_seconds > 0 {
+        let protection = ctx
+            .accounts
+            .protection
+            .as_ref()
+            .ok_or(AmmError::ProtectionAccountRequired)?;
+        let mut protection = protection.load_mut()?;
+        match trade_direction {
+            TradeDirection::QuoteToBase => protection.record_buy(current_timestamp),
+            TradeDirection::BaseToQuote => require!(
+                !curve.is_sell_locked(protection.last_buy_timestamp, current_timestamp),
+                AmmError::SellLockupActive
+            ),
+        }
+    }
+
+    // Anti-snipe: filled buys count towards the owner's cap like direct buys
+    if trade_direction == TradeDirection::QuoteToBase
+        && curve.is_in_anti_snipe_window(Clock::get()?.slot, config.anti_snipe_window_slots)
+    {
+        let protection = ctx
+            .accounts
+            .protection
+            .as_ref()
+            .ok_or(AmmError::ProtectionAccountRequired)?;
+        protection
+            .load_mut()?
+            .accrue_anti_snipe_buy(amount_in, config.max_buy_amount_first_window)?;
+    }
+
+    let pre_output_balance = ctx.accounts.owner_output_token_account.amount;
+
+    // send the escrow to the reserve and the reward to the filler
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        token_in_mint,
+        &ctx.accounts.escrow_vault,
+        input_vault_account,
+        input_program,
+        amount_in,
+        const_pda::curve_authority::BUMP,
+    )?;
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        token_in_mint,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.filler_token_account,
+        input_program,
+        limit_order.filler_reward,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    // send to owner
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        token_out_mint,
+        output_vault_account,
+        &ctx.accounts.owner_output_token_account,
+        output_program,
+        swap_result.output_amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    ctx.accounts.escrow_vault.reload()?;
+    require!(
+        ctx.accounts.escrow_vault.amount == 0,
+        AmmError::InvalidAccount
+    );
+    close_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.escrow_vault,
+        ctx.accounts.owner.to_account_info(),
+        input_program,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    // only buys change the owner's base balance, escrowed base still counted as held
+    if trade_direction == TradeDirection::QuoteToBase {
+        ctx.accounts.owner_output_token_account.reload()?;
+        curve.update_holder_count(
+            pre_output_balance,
+            ctx.accounts.owner_output_token_account.amount,
+        )?;
+    }
+
+    emit_cpi!(EvtFillLimitOrder {
+        event_sequence: config.next_event_sequence()?,
+        curve: curve_key,
+        limit_order: ctx.accounts.limit_order.key(),
+        owner: owner_key,
+        filler: ctx.accounts.filler.key(),
+        trade_direction: trade_direction.into(),
+        filler_reward: limit_order.filler_reward,
+        virtual_price,
+        swap_result,
+        virtual_base_reserve: curve.virtual_base_reserve,
+        virtual_quote_reserve: curve.virtual_quote_reserve,
+        holder_count: curve.holder_count,
+    });
+
+    if let Some(quote_threshold) =
+        curve.get_completed_quote_threshold(&config, current_timestamp)?
+    {
+        ctx.accounts.base_vault.reload()?;
+        curve.complete(&config, ctx.accounts.base_vault.amount, current_timestamp)?;
+        record_curve_graduated(&ctx.accounts.config_stats)?;
+
+        emit_cpi!(EvtCurveComplete {
+            event_sequence: config.next_event_sequence()?,
+            curve: curve_key,
+            config: ctx.accounts.config.key(),
+            base_mint: ctx.accounts.base_mint.key(),
+            base_reserve: curve.base_reserve,
+            quote_reserve: curve.quote_reserve,
+            effective_migration_quote_threshold: quote_threshold.get(),
+        })
+    }
+
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::{
+        fee::FEE_DENOMINATOR,
+        limit_order::MAX_FILLER_REWARD_BASIS_POINTS,
+        seeds::{LIMIT_ORDER_PREFIX, TOKEN_VAULT_PREFIX},
+    },
+    errors::AmmError,
+    events::EvtPlaceLimitOrder,
+    params::swap::TradeDirection,
+    safe_math::{safe_mul_div_cast_u64, SafeMath},
+    states::{BondingCurve, Config, LimitOrder, MigrationStatus},
+    u128x128_math::Rounding,
+    utils::transfer_from_user,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct PlaceLimitOrderParameters {
+    /// picked by the owner, tells the orders of an owner on a curve apart
+    pub order_id: u64,
+    /// trade direction of the order (0: BaseToQuote, 1: QuoteToBase)
+    pub trade_direction: u8,
+    /// input swapped once the target price is reached
+    pub amount_in: u64,
+    /// virtual price the order fills at, quote per base as a Q64.64 of raw amounts
+    pub target_price: u128,
+    /// escrowed on top of `amount_in`, paid to the filler
+    pub filler_reward: u64,
+}
+
+/// Accounts for a user to escrow the input of a limit order on a curve
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(params: PlaceLimitOrderParameters)]
+pub struct PlaceLimitOrderCtx<'info> {
+    /// CHECK: curve authority, owns the escrow vault
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config the bonding curve belongs to
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve the order trades on
+    #[account(mut, has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// The limit order PDA
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + LimitOrder::INIT_SPACE,
+        seeds = [
+            LIMIT_ORDER_PREFIX,
+            curve.key().as_ref(),
+            owner.key().as_ref(),
+            params.order_id.to_le_bytes().as_ref(),
+        ],
+        bump,
+    )]
+    pub limit_order: AccountLoader<'info, LimitOrder>,
+
+    /// Token vault escrowing the input and the filler reward
+    #[account(
+        init,
+        seeds = [
+            TOKEN_VAULT_PREFIX,
+            input_mint.key().as_ref(),
+            limit_order.key().as_ref(),
+        ],
+        token::mint = input_mint,
+        token::authority = curve_authority,
+        token::token_program = token_program,
+        payer = owner,
+        bump,
+    )]
+    pub escrow_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner's token account paying the escrow
+    #[account(mut, token::mint = input_mint, token::token_program = token_program)]
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Quote mint for buy orders, base mint for sell orders
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Token program of the input mint
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_place_limit_order(
+    ctx: Context<PlaceLimitOrderCtx>,
+    params: PlaceLimitOrderParameters,
+) -> Result<()> {
+    let PlaceLimitOrderParameters {
+        order_id,
+        trade_direction,
+        amount_in,
+        target_price,
+        filler_reward,
+    } = params;
+    let trade_direction =
+        TradeDirection::try_from(trade_direction).map_err(|_| AmmError::InvalidLimitOrder)?;
+    require!(amount_in > 0, AmmError::AmountIsZero);
+    require!(target_price > 0, AmmError::InvalidLimitOrder);
+    let max_filler_reward: u64 = safe_mul_div_cast_u64(
+        amount_in,
+        MAX_FILLER_REWARD_BASIS_POINTS as u64,
+        FEE_DENOMINATOR,
+        Rounding::Down,
+    )?;
+    require!(
+        filler_reward <= max_filler_reward,
+        AmmError::FillerRewardTooHigh
+    );
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+    let expected_input_mint = match trade_direction {
+        TradeDirection::QuoteToBase => config.quote_mint,
+        TradeDirection::BaseToQuote => curve.base_mint,
+    };
+    require_keys_eq!(
+        ctx.accounts.input_mint.key(),
+        expected_input_mint,
+        AmmError::InvalidAccount
+    );
+    require!(
+        curve.get_migration_progress()? == MigrationStatus::PreBondingCurve,
+        AmmError::PoolIsCompleted
+    );
+
+    transfer_from_user(
+        &ctx.accounts.owner,
+        &ctx.accounts.input_mint,
+        &ctx.accounts.owner_token_account,
+        &ctx.accounts.escrow_vault,
+        &ctx.accounts.token_program,
+        amount_in.safe_add(filler_reward)?,
+    )?;
+
+    let mut limit_order = ctx.accounts.limit_order.load_init()?;
+    limit_order.init(
+        ctx.accounts.curve.key(),
+        ctx.accounts.owner.key(),
+        ctx.accounts.escrow_vault.key(),
+        order_id,
+        Clock::get()?.unix_timestamp as u64,
+    );
+    limit_order.set_order(trade_direction, amount_in, target_price, filler_reward);
+    curve.open_limit_order()?;
+
+    emit_cpi!(EvtPlaceLimitOrder {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        limit_order: ctx.accounts.limit_order.key(),
+        owner: ctx.accounts.owner.key(),
+        order_id,
+        trade_direction: trade_direction.into(),
+        amount_in,
+        target_price,
+        filler_reward,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,7 @@
+pub mod ix_cancel_limit_order;
+pub mod ix_fill_limit_order;
+pub mod ix_place_limit_order;
+
+pub use ix_cancel_limit_order::*;
+pub use ix_fill_limit_order::*;
+pub use ix_place_limit_order::*;
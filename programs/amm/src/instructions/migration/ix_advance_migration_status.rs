@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{
+    events::EvtCurveComplete,
+    states::{BondingCurve, Config, ConfigStats, MigrationStatus},
+    utils::{get_current_timestamp, record_curve_graduated},
+    AmmError,
+};
+
+/// Accounts to advance the migration status of a curve without trading or moving funds.
+/// Anyone can crank it, the transition only depends on the curve's state and the clock
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AdvanceMigrationStatusCtx<'info> {
+    /// bonding curve
+    #[account(mut, has_one = config, has_one = base_vault)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// bonding curve config key
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// base vault of the curve
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// stats of the config, the curve is counted as graduated
+    #[account(mut, has_one = config)]
+    pub config_stats: AccountLoader<'info, ConfigStats>,
+}
+
+/// Curves only complete at the end of a swap, so a curve whose decayed quote threshold was
+/// reached by age alone stays in `PreBondingCurve` until someone trades. This moves it to
+/// `PostBondingCurve` like the completing swap would have
+pub fn handle_advance_migration_status(ctx: Context<AdvanceMigrationStatusCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+
+    let current_timestamp = get_current_timestamp(ctx.remaining_accounts)?;
+    let decayed_quote_threshold =
+        curve.get_decayed_migration_quote_threshold(&config, current_timestamp)?;
+    require!(
+        curve.get_migration_progress()? == MigrationStatus::PreBondingCurve
+            && curve.is_curve_complete(
+                curve.get_migration_base_threshold(&config),
+                decayed_quote_threshold,
+            ),
+        AmmError::MigrationStatusNotAdvanceable
+    );
+
+    // validate if base reserve is enough token for migration
+    require!(
+        ctx.accounts.base_vault.amount >= curve.get_migration_base_threshold(&config).get(),
+        AmmError::InsufficientLiquidityForMigration
+    );
+
+    // set finish time and migration progress
+    curve.curve_finish_timestamp = current_timestamp;
+    curve.set_migration_status(MigrationStatus::PostBondingCurve.into());
+    record_curve_graduated(&ctx.accounts.config_stats)?;
+
+    emit_cpi!(EvtCurveComplete {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        config: ctx.accounts.config.key(),
+        base_mint: curve.base_mint,
+        base_reserve: curve.base_reserve,
+        quote_reserve: curve.quote_reserve,
+        effective_migration_quote_threshold: decayed_quote_threshold
+            .unwrap_or(curve.get_migration_quote_threshold(&config))
+            .get(),
+    });
+
+    Ok(())
+}
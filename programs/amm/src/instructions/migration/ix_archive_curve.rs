@@ -0,0 +1,229 @@
+use anchor_lang::{prelude::*, Discriminator};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::seeds::{
+        BONUS_POOL_PREFIX, CURVE_ARCHIVE_PREFIX, REWARD_VAULT_PREFIX, SUPPORT_FUND_PREFIX,
+    },
+    events::EvtArchiveCurve,
+    states::{
+        BondingCurve, BonusPool, Config, CurveArchive, MigrationStatus, RewardVault, SupportFund,
+    },
+    utils::{close_from_curve, transfer_from_curve},
+    AmmError,
+};
+
+/// Accounts to archive a migrated curve nothing is owed out of anymore. Anyone can crank it,
+/// the payer funds the archive and the rent of the curve and its vaults goes back to the creator
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ArchiveCurveCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// bonding curve config key
+    #[account(mut, has_one = quote_mint, has_one = fee_claimer)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve, closed to the creator
+    #[account(
+        mut,
+        close = creator,
+        has_one = config,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+        has_one = creator,
+    )]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// The archive PDA of the curve
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CurveArchive::INIT_SPACE,
+        seeds = [
+            CURVE_ARCHIVE_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub curve_archive: AccountLoader<'info, CurveArchive>,
+
+    /// The vault token account for base token, emptied by `withdraw_leftover` beforehand
+    #[account(mut, token::token_program = token_base_program, token::mint = base_mint)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The vault token account for quote token
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: bonus pool PDA of the curve, has to be settled if the curve has one
+    #[account(
+        seeds = [
+            BONUS_POOL_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub bonus_pool: UncheckedAccount<'info>,
+
+    /// CHECK: support fund PDA of the curve, has to be settled if the curve has one
+    #[account(
+        seeds = [
+            SUPPORT_FUND_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub support_fund: UncheckedAccount<'info>,
+
+    /// CHECK: reward vault PDA of the curve, has to be settled if the curve has one
+    #[account(
+        seeds = [
+            REWARD_VAULT_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub reward_vault: UncheckedAccount<'info>,
+
+    /// Fee claimer's token account receiving the quote nobody is owed
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = fee_claimer,
+        token::token_program = token_quote_program,
+    )]
+    pub fee_claimer_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: fee claimer of the config
+    pub fee_claimer: UncheckedAccount<'info>,
+
+    /// The mint of base token
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: creator of the curve, receives the rent of the curve and its vaults
+    #[account(mut)]
+    pub creator: UncheckedAccount<'info>,
+
+    /// Address paying for the archive
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Token base program
+    pub token_base_program: Interface<'info, TokenInterface>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// A side account of the curve is settled once drained, or if the curve never had one
+fn is_side_account_settled<T: bytemuck::Pod + Discriminator + Space>(
+    account: &AccountInfo,
+    is_settled: fn(&T) -> bool,
+) -> Result<bool> {
+    if account.data_is_empty() {
+        return Ok(true);
+    }
+    require_keys_eq!(*account.owner, crate::ID, AmmError::InvalidAccount);
+    let data = account.try_borrow_data()?;
+    let discriminator = T::DISCRIMINATOR;
+    require!(
+        data.starts_with(discriminator) && data.len() >= discriminator.len() + T::INIT_SPACE,
+        AmmError::InvalidAccount
+    );
+    let side_account: T = bytemuck::pod_read_unaligned(
+        &data[discriminator.len()..discriminator.len() + T::INIT_SPACE],
+    );
+    Ok(is_settled(&side_account))
+}
+
+pub fn handle_archive_curve(ctx: Context<ArchiveCurveCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let curve = ctx.accounts.curve.load()?;
+
+    require!(
+        curve.get_migration_progress()? == MigrationStatus::CreatedPool,
+        AmmError::PoolIsIncompleted
+    );
+    // the bonus, support fund and reward claims all go through the curve account
+    require!(
+        curve.is_settled()
+            && ctx.accounts.base_vault.amount == 0
+            && is_side_account_settled::<BonusPool>(
+                &ctx.accounts.bonus_pool,
+                BonusPool::is_settled
+            )?
+            && is_side_account_settled::<SupportFund>(
+                &ctx.accounts.support_fund,
+                SupportFund::is_settled
+            )?
+            && is_side_account_settled::<RewardVault>(
+                &ctx.accounts.reward_vault,
+                RewardVault::is_settled
+            )?,
+        AmmError::CurveNotSettled
+    );
+
+    // migration rounding and donations, every fee bucket is already claimed
+    let swept_quote_amount = ctx.accounts.quote_vault.amount;
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.quote_vault,
+        &ctx.accounts.fee_claimer_token_account,
+        &ctx.accounts.token_quote_program,
+        swept_quote_amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    let reclaimed_lamports = ctx
+        .accounts
+        .curve
+        .to_account_info()
+        .lamports()
+        .saturating_add(ctx.accounts.base_vault.to_account_info().lamports())
+        .saturating_add(ctx.accounts.quote_vault.to_account_info().lamports());
+    close_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.base_vault,
+        ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.token_base_program,
+        const_pda::curve_authority::BUMP,
+    )?;
+    close_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.quote_vault,
+        ctx.accounts.creator.to_account_info(),
+        &ctx.accounts.token_quote_program,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    ctx.accounts.curve_archive.load_init()?.init(
+        ctx.accounts.curve.key(),
+        &curve,
+        Clock::get()?.unix_timestamp as u64,
+    );
+
+    emit_cpi!(EvtArchiveCurve {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        config: ctx.accounts.config.key(),
+        base_mint: ctx.accounts.base_mint.key(),
+        curve_archive: ctx.accounts.curve_archive.key(),
+        swept_quote_amount,
+        reclaimed_lamports,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    events::EvtBurnCurveBaseTokens,
+    states::{BondingCurve, Config, MigrationStatus},
+    utils::burn_from_curve,
+    AmmError,
+};
+
+/// Accounts for the creator to burn base tokens a migrated curve left in its vault, signed over
+/// by the curve authority. Only when the config has no leftover receiver to send them to
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BurnCurveBaseTokensCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// bonding curve config key
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve
+    #[account(has_one = config, has_one = base_vault, has_one = base_mint, has_one = creator)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// The vault token account for base token
+    #[account(mut, token::token_program = token_base_program, token::mint = base_mint)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of base token, mutable for the burn
+    #[account(mut)]
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// creator of the curve
+    pub creator: Signer<'info>,
+
+    /// Token base program
+    pub token_base_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handle_burn_curve_base_tokens(
+    ctx: Context<BurnCurveBaseTokensCtx>,
+    amount: u64,
+) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let curve = ctx.accounts.curve.load()?;
+
+    require!(
+        curve.get_migration_progress()? == MigrationStatus::CreatedPool,
+        AmmError::PoolIsIncompleted
+    );
+    require!(
+        !config.has_leftover_receiver(),
+        AmmError::LeftoverHasReceiver
+    );
+    require!(amount > 0, AmmError::AmountIsZero);
+    require!(
+        amount <= ctx.accounts.base_vault.amount,
+        AmmError::InsufficientLeftover
+    );
+
+    burn_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.base_mint,
+        &ctx.accounts.base_vault,
+        &ctx.accounts.token_base_program,
+        amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    ctx.accounts.base_mint.reload()?;
+    emit_cpi!(EvtBurnCurveBaseTokens {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        config: ctx.accounts.config.key(),
+        base_mint: ctx.accounts.base_mint.key(),
+        amount,
+        supply: ctx.accounts.base_mint.supply,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,280 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::seeds::MIGRATION_PROGRESS_PREFIX,
+    events::EvtClaimPositionFee,
+    safe_math::SafeMath,
+    states::{BondingCurve, Config, MigrationProgress, MigrationStatus},
+    utils::transfer_from_curve,
+    AmmError,
+};
+
+/// Accounts to claim the fees of the first DAMM v2 position of a migrated curve whose creator
+/// takes a share of them. The program keeps such positions, anyone can crank the claim since it
+/// only pays the creator fee recipient and the fee claimer of the config
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimPositionFeeCtx<'info> {
+    /// CHECK: curve authority, owner of the position
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// bonding curve config key
+    #[account(mut, has_one = quote_mint, has_one = fee_claimer)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve
+    #[account(
+        has_one = config,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+    )]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// migration progress of the curve, pins the pool and the position
+    #[account(
+        has_one = curve,
+        has_one = pool,
+        has_one = first_position,
+        has_one = first_position_nft_account,
+        seeds = [
+            MIGRATION_PROGRESS_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub migration_progress: AccountLoader<'info, MigrationProgress>,
+
+    /// CHECK: DAMM v2 pool of the migrated curve
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: first position, checked against the migration progress
+    #[account(mut)]
+    pub first_position: UncheckedAccount<'info>,
+
+    /// CHECK: first position nft account, checked against the migration progress
+    pub first_position_nft_account: UncheckedAccount<'info>,
+
+    /// CHECK: damm pool authority
+    pub damm_pool_authority: UncheckedAccount<'info>,
+
+    /// CHECK: token a vault of the pool, validated by DAMM v2
+    #[account(mut)]
+    pub token_a_vault: UncheckedAccount<'info>,
+
+    /// CHECK: token b vault of the pool, validated by DAMM v2
+    #[account(mut)]
+    pub token_b_vault: UncheckedAccount<'info>,
+
+    /// base token vault of the curve, receives the base fees before the split
+    #[account(mut, token::mint = base_mint, token::token_program = token_base_program)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// quote token vault of the curve, receives the quote fees before the split
+    #[account(mut, token::mint = quote_mint, token::token_program = token_quote_program)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: receiver of the creator share, the creator unless it set a recipient
+    #[account(address = curve.load()?.get_creator_fee_recipient() @ AmmError::InvalidAccount)]
+    pub creator_fee_recipient: UncheckedAccount<'info>,
+
+    /// Recipient's base token account for the creator share
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::authority = creator_fee_recipient,
+        token::token_program = token_base_program,
+    )]
+    pub creator_base_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Recipient's quote token account for the creator share
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = creator_fee_recipient,
+        token::token_program = token_quote_program,
+    )]
+    pub creator_quote_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: fee claimer of the config, receives the protocol share
+    pub fee_claimer: UncheckedAccount<'info>,
+
+    /// Fee claimer's base token account for the protocol share
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::authority = fee_claimer,
+        token::token_program = token_base_program,
+    )]
+    pub fee_claimer_base_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Fee claimer's quote token account for the protocol share
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::authority = fee_claimer,
+        token::token_program = token_quote_program,
+    )]
+    pub fee_claimer_quote_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of base token
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Token base program
+    pub token_base_program: Interface<'info, TokenInterface>,
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: damm event authority
+    pub damm_event_authority: UncheckedAccount<'info>,
+
+    /// CHECK:
+    #[account(address = damm_v2::ID)]
+    pub amm_program: UncheckedAccount<'info>,
+}
+
+/// The position fees land in the curve vaults first, DAMM v2 pays a single account per token,
+/// and are split out of them right away so the vault balances are left as they were
+pub fn handle_claim_position_fee(ctx: Context<ClaimPositionFeeCtx>) -> Result<()> {
+    let curve = ctx.accounts.curve.load()?;
+    require!(
+        curve.get_migration_progress()? == MigrationStatus::CreatedPool,
+        AmmError::PoolIsIncompleted
+    );
+    require!(
+        curve.creator_lp_fee_share_basis_points > 0,
+        AmmError::NoCreatorLpFeeShare
+    );
+
+    let is_base_token_a = ctx.accounts.migration_progress.load()?.is_base_token_a != 0;
+    let (
+        token_a_account,
+        token_b_account,
+        token_a_mint,
+        token_b_mint,
+        token_a_program,
+        token_b_program,
+    ) = if is_base_token_a {
+        (
+            ctx.accounts.base_vault.to_account_info(),
+            ctx.accounts.quote_vault.to_account_info(),
+            ctx.accounts.base_mint.to_account_info(),
+            ctx.accounts.quote_mint.to_account_info(),
+            ctx.accounts.token_base_program.to_account_info(),
+            ctx.accounts.token_quote_program.to_account_info(),
+        )
+    } else {
+        (
+            ctx.accounts.quote_vault.to_account_info(),
+            ctx.accounts.base_vault.to_account_info(),
+            ctx.accounts.quote_mint.to_account_info(),
+            ctx.accounts.base_mint.to_account_info(),
+            ctx.accounts.token_quote_program.to_account_info(),
+            ctx.accounts.token_base_program.to_account_info(),
+        )
+    };
+
+    let pre_base_vault_amount = ctx.accounts.base_vault.amount;
+    let pre_quote_vault_amount = ctx.accounts.quote_vault.amount;
+    let curve_authority_seeds = curve_authority_seeds!(const_pda::curve_authority::BUMP);
+    damm_v2::cpi::claim_position_fee(CpiContext::new_with_signer(
+        ctx.accounts.amm_program.to_account_info(),
+        damm_v2::cpi::accounts::ClaimPositionFee {
+            pool_authority: ctx.accounts.damm_pool_authority.to_account_info(),
+            pool: ctx.accounts.pool.to_account_info(),
+            position: ctx.accounts.first_position.to_account_info(),
+            token_a_account,
+            token_b_account,
+            token_a_vault: ctx.accounts.token_a_vault.to_account_info(),
+            token_b_vault: ctx.accounts.token_b_vault.to_account_info(),
+            token_a_mint,
+            token_b_mint,
+            position_nft_account: ctx.accounts.first_position_nft_account.to_account_info(),
+            owner: ctx.accounts.curve_authority.to_account_info(),
+            token_a_program,
+            token_b_program,
+            event_authority: ctx.accounts.damm_event_authority.to_account_info(),
+            program: ctx.accounts.amm_program.to_account_info(),
+        },
+        &[&curve_authority_seeds[..]],
+    ))?;
+
+    ctx.accounts.base_vault.reload()?;
+    ctx.accounts.quote_vault.reload()?;
+    let base_fee = ctx
+        .accounts
+        .base_vault
+        .amount
+        .safe_sub(pre_base_vault_amount)?;
+    let quote_fee = ctx
+        .accounts
+        .quote_vault
+        .amount
+        .safe_sub(pre_quote_vault_amount)?;
+    require!(base_fee > 0 || quote_fee > 0, AmmError::NothingToClaim);
+
+    let creator_base_amount = curve.get_creator_lp_fee(base_fee)?;
+    let creator_quote_amount = curve.get_creator_lp_fee(quote_fee)?;
+    let protocol_base_amount = base_fee.safe_sub(creator_base_amount)?;
+    let protocol_quote_amount = quote_fee.safe_sub(creator_quote_amount)?;
+
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.base_mint,
+        &ctx.accounts.base_vault,
+        &ctx.accounts.creator_base_token_account,
+        &ctx.accounts.token_base_program,
+        creator_base_amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.quote_vault,
+        &ctx.accounts.creator_quote_token_account,
+        &ctx.accounts.token_quote_program,
+        creator_quote_amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.base_mint,
+        &ctx.accounts.base_vault,
+        &ctx.accounts.fee_claimer_base_token_account,
+        &ctx.accounts.token_base_program,
+        protocol_base_amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.quote_vault,
+        &ctx.accounts.fee_claimer_quote_token_account,
+        &ctx.accounts.token_quote_program,
+        protocol_quote_amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    emit_cpi!(EvtClaimPositionFee {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        config: ctx.accounts.config.key(),
+        pool: ctx.accounts.pool.key(),
+        creator_fee_recipient: ctx.accounts.creator_fee_recipient.key(),
+        creator_base_amount,
+        creator_quote_amount,
+        protocol_base_amount,
+        protocol_quote_amount,
+    });
+
+    Ok(())
+}
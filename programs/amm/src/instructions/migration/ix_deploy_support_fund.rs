@@ -0,0 +1,199 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::{MIGRATION_PROGRESS_PREFIX, SUPPORT_FUND_PREFIX},
+    events::EvtDeploySupportFund,
+    safe_math::SafeMath,
+    states::{
+        BondingCurve, Config, MigrationProgress, MigrationStatus, ProtocolAuthority, SupportFund,
+    },
+    AmmError,
+};
+
+/// Accounts to spend the support fund of a migrated curve on a buyback in its DAMM v2 pool. The
+/// bought base lands in the curve's base vault, where `withdraw_leftover` burns it or sends it
+/// to the leftover receiver
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DeploySupportFundCtx<'info> {
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can deploy the support fund
+    #[account(
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// bonding curve config key
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve
+    #[account(has_one = config, has_one = base_vault, has_one = base_mint)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// support fund of the curve
+    #[account(
+        mut,
+        has_one = curve,
+        constraint = support_fund.load()?.quote_vault == support_fund_vault.key() @ AmmError::InvalidAccount,
+        seeds = [
+            SUPPORT_FUND_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub support_fund: AccountLoader<'info, SupportFund>,
+
+    /// quote token vault of the support fund, the input of the buyback
+    #[account(mut, token::mint = quote_mint, token::token_program = token_quote_program)]
+    pub support_fund_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// base token vault of the curve, the output of the buyback
+    #[account(mut, token::mint = base_mint, token::token_program = token_base_program)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// migration progress of the curve, pins the pool the curve migrated to
+    #[account(
+        has_one = curve,
+        has_one = pool,
+        seeds = [
+            MIGRATION_PROGRESS_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub migration_progress: AccountLoader<'info, MigrationProgress>,
+
+    /// CHECK: DAMM v2 pool of the migrated curve
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: damm pool authority
+    pub damm_pool_authority: UncheckedAccount<'info>,
+
+    /// CHECK: token a vault of the pool, validated by DAMM v2
+    #[account(mut)]
+    pub token_a_vault: UncheckedAccount<'info>,
+
+    /// CHECK: token b vault of the pool, validated by DAMM v2
+    #[account(mut)]
+    pub token_b_vault: UncheckedAccount<'info>,
+
+    /// The mint of base token
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Token base program
+    pub token_base_program: Interface<'info, TokenInterface>,
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: damm event authority
+    pub damm_event_authority: UncheckedAccount<'info>,
+
+    /// CHECK:
+    #[account(address = damm_v2::ID)]
+    pub amm_program: UncheckedAccount<'info>,
+}
+
+/// DAMM v2 pools have a single price range, so the fund can't sit as single-sided liquidity
+/// below the price. It supports the price by buying back instead, whenever the admin decides
+pub fn handle_deploy_support_fund(
+    ctx: Context<DeploySupportFundCtx>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Result<()> {
+    require!(amount_in > 0, AmmError::AmountIsZero);
+    require!(
+        ctx.accounts.curve.load()?.get_migration_progress()? == MigrationStatus::CreatedPool,
+        AmmError::PoolIsIncompleted
+    );
+    require!(
+        amount_in <= ctx.accounts.support_fund_vault.amount,
+        AmmError::InsufficientSupportFund
+    );
+
+    let is_base_token_a = ctx.accounts.migration_progress.load()?.is_base_token_a != 0;
+    let (token_a_mint, token_b_mint, token_a_program, token_b_program) = if is_base_token_a {
+        (
+            ctx.accounts.base_mint.to_account_info(),
+            ctx.accounts.quote_mint.to_account_info(),
+            ctx.accounts.token_base_program.to_account_info(),
+            ctx.accounts.token_quote_program.to_account_info(),
+        )
+    } else {
+        (
+            ctx.accounts.quote_mint.to_account_info(),
+            ctx.accounts.base_mint.to_account_info(),
+            ctx.accounts.token_quote_program.to_account_info(),
+            ctx.accounts.token_base_program.to_account_info(),
+        )
+    };
+
+    let pre_base_vault_amount = ctx.accounts.base_vault.amount;
+    let curve_authority_seeds = curve_authority_seeds!(const_pda::curve_authority::BUMP);
+    damm_v2::cpi::swap(
+        CpiContext::new_with_signer(
+            ctx.accounts.amm_program.to_account_info(),
+            damm_v2::cpi::accounts::Swap {
+                pool_authority: ctx.accounts.damm_pool_authority.to_account_info(),
+                pool: ctx.accounts.pool.to_account_info(),
+                input_token_account: ctx.accounts.support_fund_vault.to_account_info(),
+                output_token_account: ctx.accounts.base_vault.to_account_info(),
+                token_a_vault: ctx.accounts.token_a_vault.to_account_info(),
+                token_b_vault: ctx.accounts.token_b_vault.to_account_info(),
+                token_a_mint,
+                token_b_mint,
+                payer: ctx.accounts.curve_authority.to_account_info(),
+                token_a_program,
+                token_b_program,
+                referral_token_account: None,
+                event_authority: ctx.accounts.damm_event_authority.to_account_info(),
+                program: ctx.accounts.amm_program.to_account_info(),
+            },
+            &[&curve_authority_seeds[..]],
+        ),
+        damm_v2::types::SwapParameters {
+            amount_in,
+            minimum_amount_out,
+        },
+    )?;
+
+    ctx.accounts.base_vault.reload()?;
+    ctx.accounts.support_fund_vault.reload()?;
+    let base_amount = ctx
+        .accounts
+        .base_vault
+        .amount
+        .safe_sub(pre_base_vault_amount)?;
+    ctx.accounts
+        .support_fund
+        .load_mut()?
+        .deploy(amount_in, base_amount)?;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    emit_cpi!(EvtDeploySupportFund {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        config: ctx.accounts.config.key(),
+        pool: ctx.accounts.pool.key(),
+        quote_amount: amount_in,
+        base_amount,
+        remaining_quote_amount: ctx.accounts.support_fund_vault.amount,
+    });
+
+    Ok(())
+}
@@ -1,23 +1,153 @@
 use anchor_lang::prelude::*;
-use anchor_lang::solana_program::{program::invoke, system_instruction::transfer};
+use anchor_lang::solana_program::{
+    compute_units::sol_remaining_compute_units, program::invoke, system_instruction::transfer,
+};
 use anchor_spl::{
     token_2022::{set_authority, spl_token_2022::instruction::AuthorityType, SetAuthority},
-    token_interface::{TokenAccount, TokenInterface},
+    token_interface::{transfer_checked, TokenAccount, TokenInterface, TransferChecked},
 };
-use damm_v2::types::InitializePoolParameters;
-use ruint::aliases::{U256, U512};
+use damm_v2::types::{AddLiquidityParameters, InitializePoolParameters};
+use num_enum::IntoPrimitive;
+use std::cell::RefMut;
 use std::u64;
 
 use crate::{
-    assert_eq_admin, const_pda,
-    constants::{MAX_SQRT_PRICE, MIN_SQRT_PRICE},
+    assert_eq_migration_admin, const_pda,
+    constants::{
+        seeds::{
+            DUAL_LISTING_PREFIX, MIGRATION_DELEGATE_PREFIX, MIGRATION_PROGRESS_PREFIX,
+            SUPPORT_FUND_PREFIX, TOKEN_VAULT_PREFIX,
+        },
+        MAX_SQRT_PRICE, MIN_SQRT_PRICE,
+    },
     errors::AmmError,
-    events::EvtMigrateDammV2,
+    events::{
+        EvtCpiTelemetry, EvtCreatorFeeTopUp, EvtFundSupport, EvtMigrateDammV2, EvtMigrationAttempt,
+        EvtMigrationCrankReward,
+    },
+    math::curve::get_liquidity_for_adding_liquidity,
     params::liquidity_distribution::get_sqrt_price_from_amounts,
     safe_math::SafeMath,
-    states::{BondingCurve, Config, MigrationAmount, MigrationStatus},
+    states::{
+        BondingCurve, Config, DualListing, FaultPoint, MigrationAmount, MigrationDelegate,
+        MigrationProgress, MigrationStatus, MigrationStep, MigrationTokenOrder, ProtocolAuthority,
+        SupportFund,
+    },
+    utils::{get_current_timestamp, inject_fault, without_test_hooks},
 };
 
+/// Stages of a migration, reported through `EvtMigrationAttempt`
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive)]
+pub enum MigrationStage {
+    /// DAMM v2 config passed in the remaining accounts
+    ValidateDammConfig,
+    /// Curve is in the post bonding curve state
+    CheckMigrationStatus,
+    /// Curve reached the migration threshold
+    CheckCurveComplete,
+    /// Pre-migration cooldown has passed
+    CheckCooldown,
+    /// Migration amounts, token order, price and liquidity
+    ComputeLiquidity,
+    /// DAMM v2 pool initialization CPI
+    CreatePool,
+    /// Permanent liquidity lock CPI
+    LockLiquidity,
+    /// Position ownership transfer CPI
+    TransferPosition,
+    /// Creation, deposit and handover CPIs of the creator's second position
+    CreateCreatorPosition,
+    /// Other curve of a dual listing completed, or the graduation window passed
+    CheckDualListing,
+}
+
+/// Logs migration progress for cranks. A failed CPI aborts the whole transaction without
+/// returning to us, so CPI stages are logged before they run and the last one in the logs is
+/// where the crank died. Failed checks are logged together with their error code.
+pub struct MigrationTrace<'info> {
+    pub curve: Pubkey,
+    /// config of the curve, sequences the `EvtCpiTelemetry` events
+    pub config: AccountLoader<'info, Config>,
+    /// whether the CPIs are wrapped with `EvtCpiTelemetry`, see `Config::cpi_telemetry`
+    pub cpi_telemetry: bool,
+}
+
+impl<'info> MigrationTrace<'info> {
+    pub fn new(curve: Pubkey, config: &AccountLoader<'info, Config>) -> Result<Self> {
+        let cpi_telemetry = config.load()?.has_cpi_telemetry();
+        Ok(Self {
+            curve,
+            config: config.clone(),
+            cpi_telemetry,
+        })
+    }
+
+    pub fn emit(&self, stage: MigrationStage, error_code: Option<u64>) {
+        emit!(EvtMigrationAttempt {
+            curve: self.curve,
+            config: self.config.key(),
+            stage: stage.into(),
+            error_code,
+        });
+    }
+
+    pub fn reached(&self, stage: MigrationStage) {
+        self.emit(stage, None);
+    }
+
+    pub fn check<T>(&self, stage: MigrationStage, result: Result<T>) -> Result<T> {
+        if let Err(err) = &result {
+            let error_code = match err {
+                Error::AnchorError(err) => err.error_code_number as u64,
+                Error::ProgramError(err) => u64::from(err.program_error.clone()),
+            };
+            self.emit(stage, Some(error_code));
+        }
+        result
+    }
+
+    pub fn require(&self, stage: MigrationStage, condition: bool, error: AmmError) -> Result<()> {
+        self.check(stage, if condition { Ok(()) } else { Err(error.into()) })
+    }
+
+    /// Runs the CPI of `stage`. With the CPI telemetry on, the compute units left before it are
+    /// logged, then the compute units it consumed once it returns. The config can't be borrowed
+    /// by the caller while the telemetry is sequenced
+    pub fn cpi<T>(
+        &self,
+        stage: MigrationStage,
+        account_count: usize,
+        cpi: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        if !self.cpi_telemetry {
+            return cpi();
+        }
+
+        let emit_telemetry = |compute_units_remaining, compute_units_consumed| -> Result<()> {
+            emit!(EvtCpiTelemetry {
+                event_sequence: self.config.load_mut()?.next_event_sequence()?,
+                curve: self.curve,
+                config: self.config.key(),
+                stage: stage.into(),
+                account_count: account_count.min(u8::MAX as usize) as u8,
+                compute_units_remaining,
+                compute_units_consumed,
+            });
+            Ok(())
+        };
+        emit_telemetry(sol_remaining_compute_units(), None)?;
+
+        // measured after the log above so only the CPI is counted
+        let compute_units_remaining = sol_remaining_compute_units();
+        let result = cpi()?;
+        let compute_units_consumed =
+            compute_units_remaining.saturating_sub(sol_remaining_compute_units());
+        emit_telemetry(compute_units_remaining, Some(compute_units_consumed))?;
+        Ok(result)
+    }
+}
+
 #[event_cpi]
 #[derive(Accounts)]
 pub struct MigrateDammV2Ctx<'info> {
@@ -26,6 +156,7 @@ pub struct MigrateDammV2Ctx<'info> {
     pub curve: AccountLoader<'info, BondingCurve>,
 
     /// bonding curve config key
+    #[account(mut)]
     pub config: AccountLoader<'info, Config>,
 
     /// CHECK: curve authority
@@ -35,11 +166,13 @@ pub struct MigrateDammV2Ctx<'info> {
     )]
     pub curve_authority: AccountInfo<'info>,
 
-    /// migration authority
-    #[account(
-        mut,
-        constraint = assert_eq_admin(migration_authority.key()) @ AmmError::Unauthorized,
-    )]
+    /// protocol authorities, holds the migration admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// migration authority, a migration admin, the operator the curve is delegated to or anyone
+    /// when the config allows permissionless migrations
+    #[account(mut)]
     pub migration_authority: Signer<'info>,
 
     /// CHECK: pool
@@ -119,8 +252,104 @@ pub struct MigrateDammV2Ctx<'info> {
     pub damm_event_authority: UncheckedAccount<'info>,
     /// System program.
     pub system_program: Program<'info, System>,
-    // CHECK: damm-v2 config key, use remaining accounts
-    // pub damm_config: AccountLoader<'info, damm_v2::accounts::Config>,
+
+    /// progress of the migration, lets a crank resume after a failed step
+    #[account(
+        init_if_needed,
+        payer = migration_authority,
+        space = 8 + MigrationProgress::INIT_SPACE,
+        seeds = [
+            MIGRATION_PROGRESS_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub migration_progress: AccountLoader<'info, MigrationProgress>,
+
+    /// shared supply accounting of the base mint, required when the curve is dual listed
+    #[account(
+        mut,
+        seeds = [
+            DUAL_LISTING_PREFIX,
+            base_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub dual_listing: Option<AccountLoader<'info, DualListing>>,
+
+    /// other curve of the dual listing, required when the curve is dual listed
+    pub sibling_curve: Option<AccountLoader<'info, BondingCurve>>,
+
+    /// migration rights of an operator over the curve, required when not migrated by an admin
+    #[account(
+        seeds = [
+            MIGRATION_DELEGATE_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub migration_delegate: Option<AccountLoader<'info, MigrationDelegate>>,
+
+    /// quote token account of the cranker receiving the reward of a permissionless migration
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::token_program = token_quote_program
+    )]
+    pub crank_reward_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// support fund of the curve, required when the config routes part of the migration fee to it
+    #[account(
+        init_if_needed,
+        payer = migration_authority,
+        space = 8 + SupportFund::INIT_SPACE,
+        seeds = [
+            SUPPORT_FUND_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub support_fund: Option<AccountLoader<'info, SupportFund>>,
+
+    /// quote token vault of the support fund
+    #[account(
+        init_if_needed,
+        seeds = [
+            TOKEN_VAULT_PREFIX,
+            quote_mint.key().as_ref(),
+            support_fund
+                .as_ref()
+                .map(|support_fund| support_fund.key())
+                .unwrap_or_default()
+                .as_ref(),
+        ],
+        token::mint = quote_mint,
+        token::authority = curve_authority,
+        token::token_program = token_quote_program,
+        payer = migration_authority,
+        bump,
+    )]
+    pub support_fund_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// fee claimer's quote token account receiving the quote migration fee, the fee accrues
+    /// in the quote vault for `claim_migration_fee` without it
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::token_program = token_quote_program
+    )]
+    pub fee_claimer_quote_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// fee claimer's base token account receiving the base migration fee, the fee is left over
+    /// in the base vault without it
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::token_program = token_base_program
+    )]
+    pub fee_claimer_base_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    // CHECK: damm-v2 config key, use remaining accounts, has to be the config's approved
+    // damm_config unless the admin explicitly overrides it
 }
 
 impl<'info> MigrateDammV2Ctx<'info> {
@@ -152,9 +381,580 @@ impl<'info> MigrateDammV2Ctx<'info> {
         Ok(())
     }
 
+    /// Checks the signer can migrate the curve and returns the owner of the first position and
+    /// whether it is a permissionless crank. An operator migrates on behalf of the admin who
+    /// delegated the curve, a permissionless crank on behalf of the config's fee claimer
+    fn authorize_migration(
+        &self,
+        config: &Config,
+        current_timestamp: u64,
+    ) -> Result<(Pubkey, bool)> {
+        let migration_authority = self.migration_authority.key();
+        if assert_eq_migration_admin(&self.protocol_authority, migration_authority)? {
+            return Ok((migration_authority, false));
+        }
+
+        let Some(migration_delegate) = self.migration_delegate.as_ref() else {
+            require!(config.is_permissionless_migration(), AmmError::Unauthorized);
+            return Ok((config.fee_claimer, true));
+        };
+        let migration_delegate = migration_delegate.load()?;
+        require_keys_eq!(
+            migration_delegate.operator,
+            migration_authority,
+            AmmError::Unauthorized
+        );
+        require!(
+            !migration_delegate.is_expired(current_timestamp),
+            AmmError::MigrationDelegationExpired
+        );
+        Ok((migration_delegate.delegator, false))
+    }
+
+    /// Pays the crank reward out of the curve's protocol fee, returns the amount paid
+    fn pay_crank_reward(&self, config: &Config) -> Result<u64> {
+        let Some(crank_reward_token_account) = self.crank_reward_token_account.as_ref() else {
+            return Ok(0);
+        };
+        let mut curve = self.curve.load_mut()?;
+        let amount = config.get_migration_crank_reward(curve.protocol_fee);
+        if amount == 0 {
+            return Ok(0);
+        }
+        curve.protocol_fee = curve.protocol_fee.safe_sub(amount)?;
+
+        let curve_authority_seeds = curve_authority_seeds!(const_pda::curve_authority::BUMP);
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_quote_program.to_account_info(),
+                TransferChecked {
+                    from: self.quote_vault.to_account_info(),
+                    mint: self.quote_mint.to_account_info(),
+                    to: crank_reward_token_account.to_account_info(),
+                    authority: self.curve_authority.to_account_info(),
+                },
+                &[&curve_authority_seeds[..]],
+            ),
+            amount,
+            config.quote_decimal,
+        )?;
+        Ok(amount)
+    }
+
+    /// Dual listed curves graduate together, see `DualListing::can_migrate`
+    fn check_dual_listing_graduation(
+        &self,
+        curve: &BondingCurve,
+        current_timestamp: u64,
+    ) -> Result<()> {
+        let (Some(dual_listing), Some(sibling_curve)) = (&self.dual_listing, &self.sibling_curve)
+        else {
+            return err!(AmmError::DualListingRequired);
+        };
+        require_keys_eq!(
+            dual_listing.load()?.get_sibling_curve(self.curve.key())?,
+            sibling_curve.key(),
+            AmmError::InvalidAccount
+        );
+        let sibling_completed =
+            sibling_curve.load()?.get_migration_progress()? != MigrationStatus::PreBondingCurve;
+        require!(
+            DualListing::can_migrate(
+                sibling_completed,
+                curve.curve_finish_timestamp,
+                current_timestamp
+            )?,
+            AmmError::DualListingGraduationPending
+        );
+        Ok(())
+    }
+
+    /// Whether the base mint is token a of the DAMM v2 pool
+    fn is_base_token_a(&self, token_order: MigrationTokenOrder) -> bool {
+        match token_order {
+            MigrationTokenOrder::BaseQuote => true,
+            MigrationTokenOrder::Sorted => self.base_mint.key() < self.quote_mint.key(),
+        }
+    }
+
+    /// Creates the pool with the curve reserves, skipped when a previous attempt already did.
+    /// Returns the crank reward paid to a permissionless cranker and the creator fee top up
+    fn create_pool_step(
+        &mut self,
+        progress: &mut MigrationProgress,
+        remaining_accounts: &'info [AccountInfo<'info>],
+        allow_damm_config_override: bool,
+    ) -> Result<CreatePoolPayouts> {
+        if !progress.should_run(MigrationStep::PoolCreated)? {
+            msg!("pool already created");
+            return Ok(CreatePoolPayouts::default());
+        }
+
+        let current_timestamp = get_current_timestamp(remaining_accounts)?;
+        // a copy, the CPI telemetry borrows the config to sequence its events
+        let config = *self.config.load()?;
+        let trace = MigrationTrace::new(self.curve.key(), &self.config)?;
+        let (position_owner, is_permissionless) =
+            self.authorize_migration(&config, current_timestamp)?;
+        // the program keeps the first position when the creator takes a share of its fees, see
+        // `claim_position_fee`
+        let position_owner = if self.curve.load()?.creator_lp_fee_share_basis_points > 0 {
+            const_pda::curve_authority::ID
+        } else {
+            position_owner
+        };
+
+        let damm_config_check = || -> Result<(Pubkey, bool)> {
+            require!(
+                without_test_hooks(remaining_accounts)?.len() == 1,
+                AmmError::MissingPoolConfigInRemainingAccount
+            );
+            // a DAMM config other than the approved one (eg: another fee tier) needs the admin to
+            // opt in, delegated operators always use the approved one
+            let damm_config_key = remaining_accounts[0].key();
+            let damm_config_overridden = damm_config_key != config.damm_config;
+            require!(
+                !damm_config_overridden
+                    || (allow_damm_config_override
+                        && assert_eq_migration_admin(
+                            &self.protocol_authority,
+                            self.migration_authority.key(),
+                        )?),
+                AmmError::InvalidConfigAccount
+            );
+            let damm_config_loader: AccountLoader<'_, damm_v2::accounts::Config> =
+                AccountLoader::try_from(&remaining_accounts[0])?;
+            let damm_config = damm_config_loader.load()?;
+            self.validate_config_key(&damm_config)?;
+            Ok((damm_config_key, damm_config_overridden))
+        };
+        let (damm_config, damm_config_overridden) =
+            trace.check(MigrationStage::ValidateDammConfig, damm_config_check())?;
+
+        let curve = self.curve.load()?;
+
+        trace.require(
+            MigrationStage::CheckMigrationStatus,
+            curve.get_migration_progress()? == MigrationStatus::PostBondingCurve,
+            AmmError::NotPermitToDoThisAction,
+        )?;
+
+        // the decayed threshold only goes down, a curve completed through decay stays complete
+        trace.require(
+            MigrationStage::CheckCurveComplete,
+            curve.is_curve_complete(
+                curve.get_migration_base_threshold(&config),
+                curve.get_decayed_migration_quote_threshold(&config, current_timestamp)?,
+            ),
+            AmmError::PoolIsIncompleted,
+        )?;
+
+        // give users and operators a predictable window before liquidity moves
+        trace.require(
+            MigrationStage::CheckCooldown,
+            current_timestamp
+                >= curve
+                    .curve_finish_timestamp
+                    .safe_add(config.pre_migration_cooldown_seconds)?,
+            AmmError::MigrationCooldownNotMet,
+        )?;
+
+        if curve.is_dual_listed == 1 {
+            trace.check(
+                MigrationStage::CheckDualListing,
+                self.check_dual_listing_graduation(&curve, current_timestamp),
+            )?;
+        }
+
+        let initial_quote_vault_amount = self.quote_vault.amount;
+        let initial_base_vault_amount = self.base_vault.amount;
+        let liquidity_computation = || -> Result<(bool, u128, u128, u128, MigrationAmount)> {
+            let MigrationAmount {
+                quote_amount,
+                base_amount,
+            } = curve.get_migration_amount(
+                config.migration_fee_basis_points,
+                config.get_migration_fee_mode()?,
+            )?;
+
+            let is_base_token_a = self.is_base_token_a(config.get_migration_token_order()?);
+            let (token_a_amount, token_b_amount) = if is_base_token_a {
+                (base_amount, quote_amount)
+            } else {
+                (quote_amount, base_amount)
+            };
+
+            // Calculate the sqrt price from the amounts, the pool prices token b in token a so
+            // the price is inverted when the quote mint is token a
+            let migration_sqrt_price =
+                get_sqrt_price_from_amounts(token_a_amount as u128, token_b_amount as u128)?;
+
+            // calculate initial liquidity, this also rejects a sqrt price outside of the pool range
+            let initial_liquidity = get_liquidity_for_adding_liquidity(
+                token_a_amount,
+                token_b_amount,
+                migration_sqrt_price,
+            )?;
+            // the creator's share goes into a second position, the first one gets the rest
+            let creator_liquidity = config.get_creator_liquidity(initial_liquidity)?;
+            Ok((
+                is_base_token_a,
+                migration_sqrt_price,
+                initial_liquidity.safe_sub(creator_liquidity)?,
+                creator_liquidity,
+                MigrationAmount {
+                    quote_amount,
+                    base_amount,
+                },
+            ))
+        };
+        let (
+            is_base_token_a,
+            migration_sqrt_price,
+            initial_liquidity,
+            creator_liquidity,
+            migration_amount,
+        ) = trace.check(MigrationStage::ComputeLiquidity, liquidity_computation())?;
+
+        // create pool
+        msg!("create pool");
+        trace.reached(MigrationStage::CreatePool);
+        self.create_pool(
+            &trace,
+            remaining_accounts[0].clone(),
+            is_base_token_a,
+            initial_liquidity,
+            migration_sqrt_price,
+            const_pda::curve_authority::BUMP,
+        )?;
+
+        if creator_liquidity > 0 {
+            // what the first position took of the migration amounts is left for the second one
+            self.quote_vault.reload()?;
+            self.base_vault.reload()?;
+            let base_amount_limit = migration_amount
+                .base_amount
+                .safe_sub(initial_base_vault_amount.safe_sub(self.base_vault.amount)?)?;
+            let quote_amount_limit = migration_amount
+                .quote_amount
+                .safe_sub(initial_quote_vault_amount.safe_sub(self.quote_vault.amount)?)?;
+            let (token_a_amount_limit, token_b_amount_limit) = if is_base_token_a {
+                (base_amount_limit, quote_amount_limit)
+            } else {
+                (quote_amount_limit, base_amount_limit)
+            };
+
+            msg!("create creator position");
+            trace.reached(MigrationStage::CreateCreatorPosition);
+            self.create_creator_position(
+                &trace,
+                is_base_token_a,
+                creator_liquidity,
+                token_a_amount_limit,
+                token_b_amount_limit,
+                curve.creator,
+            )?;
+            progress.second_position = self
+                .second_position
+                .as_ref()
+                .ok_or(AmmError::SecondPositionRequired)?
+                .key();
+            progress.creator_liquidity = creator_liquidity;
+        }
+
+        // reload quote reserve and base reserve
+        self.quote_vault.reload()?;
+        self.base_vault.reload()?;
+        progress.deposited_base_amount =
+            initial_base_vault_amount.safe_sub(self.base_vault.amount)?;
+        progress.deposited_quote_amount =
+            initial_quote_vault_amount.safe_sub(self.quote_vault.amount)?;
+
+        progress.pool = self.pool.key();
+        progress.first_position = self.first_position.key();
+        progress.first_position_nft_account = self.first_position_nft_account.key();
+        progress.position_owner = position_owner;
+        progress.damm_config = damm_config;
+        progress.initial_liquidity = initial_liquidity;
+        progress.sqrt_price = migration_sqrt_price;
+        progress.is_base_token_a = is_base_token_a.into();
+        progress.damm_config_overridden = damm_config_overridden.into();
+        progress.set_step(MigrationStep::PoolCreated);
+        inject_fault(remaining_accounts, FaultPoint::AfterPoolCreation)?;
+
+        drop(curve);
+        let (accrued_creator_fee, creator_fee_top_up) = self.top_up_creator_fee(&config)?;
+        let support_fund_deposit = self.fund_support(&config, creator_fee_top_up)?;
+        let base_migration_fee = self
+            .curve
+            .load()?
+            .base_reserve
+            .safe_sub(migration_amount.base_amount)?;
+        self.pay_migration_fee(
+            &config,
+            creator_fee_top_up.safe_add(support_fund_deposit)?,
+            base_migration_fee,
+        )?;
+        let crank_reward = if is_permissionless {
+            self.pay_crank_reward(&config)?
+        } else {
+            0
+        };
+        Ok(CreatePoolPayouts {
+            crank_reward,
+            accrued_creator_fee,
+            creator_fee_top_up,
+            support_fund_deposit,
+        })
+    }
+
+    /// Tops the creator fee up to the config's guarantee out of the migration fee left in the
+    /// quote vault. Returns the creator fee accrued before and the top up
+    fn top_up_creator_fee(&self, config: &Config) -> Result<(u64, u64)> {
+        let mut curve = self.curve.load_mut()?;
+        let accrued_creator_fee = curve.total_creator_fee;
+        let top_up = curve.get_creator_fee_top_up(
+            config.min_creator_fee_amount,
+            config.migration_fee_basis_points,
+        )?;
+        curve.add_creator_fee_top_up(top_up)?;
+        Ok((accrued_creator_fee, top_up))
+    }
+
+    /// Moves the config's share of the migration fee left after the creator fee top up to the
+    /// support fund of the curve. Returns the amount moved
+    fn fund_support(&self, config: &Config, creator_fee_top_up: u64) -> Result<u64> {
+        if config.support_fund_basis_points == 0 {
+            return Ok(0);
+        }
+        let (Some(support_fund), Some(support_fund_vault)) =
+            (self.support_fund.as_ref(), self.support_fund_vault.as_ref())
+        else {
+            return err!(AmmError::SupportFundRequired);
+        };
+
+        let migration_fee = self
+            .curve
+            .load()?
+            .get_migration_fee(config.migration_fee_basis_points)?;
+        let amount = config.get_support_fund_amount(migration_fee.safe_sub(creator_fee_top_up)?)?;
+        let mut support_fund = match support_fund.load_init() {
+            Ok(mut support_fund) => {
+                support_fund.init(self.curve.key(), support_fund_vault.key());
+                support_fund
+            }
+            Err(_) => support_fund.load_mut()?,
+        };
+        support_fund.deposit(amount)?;
+
+        let curve_authority_seeds = curve_authority_seeds!(const_pda::curve_authority::BUMP);
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_quote_program.to_account_info(),
+                TransferChecked {
+                    from: self.quote_vault.to_account_info(),
+                    mint: self.quote_mint.to_account_info(),
+                    to: support_fund_vault.to_account_info(),
+                    authority: self.curve_authority.to_account_info(),
+                },
+                &[&curve_authority_seeds[..]],
+            ),
+            amount,
+            config.quote_decimal,
+        )?;
+        Ok(amount)
+    }
+
+    /// Pays the migration fee left after the creator fee top up and the support fund deposit to
+    /// the fee claimer token accounts that were passed and records it on the curve. A quote fee
+    /// not paid out accrues for `claim_migration_fee`
+    fn pay_migration_fee(
+        &self,
+        config: &Config,
+        quote_fee_spent: u64,
+        base_migration_fee: u64,
+    ) -> Result<()> {
+        let mut curve = self.curve.load_mut()?;
+        let quote_migration_fee = curve
+            .get_migration_fee(config.migration_fee_basis_points)?
+            .safe_sub(quote_fee_spent)?;
+        let quote_amount = self.pay_fee_claimer(config, true, quote_migration_fee)?;
+        let base_amount = self.pay_fee_claimer(config, false, base_migration_fee)?;
+        curve.accrue_migration_fee(quote_migration_fee.safe_sub(quote_amount)?)?;
+        curve.record_migration_fee(quote_amount, base_amount)
+    }
+
+    /// Transfers `amount` from the quote or base vault to the fee claimer token account of that
+    /// side, returns the amount paid, zero when the token account wasn't passed
+    fn pay_fee_claimer(&self, config: &Config, is_quote: bool, amount: u64) -> Result<u64> {
+        let (vault, fee_claimer_token_account, mint, token_program, decimals) = if is_quote {
+            (
+                &self.quote_vault,
+                self.fee_claimer_quote_token_account.as_ref(),
+                &self.quote_mint,
+                &self.token_quote_program,
+                config.quote_decimal,
+            )
+        } else {
+            (
+                &self.base_vault,
+                self.fee_claimer_base_token_account.as_ref(),
+                &self.base_mint,
+                &self.token_base_program,
+                config.base_decimal,
+            )
+        };
+        let Some(fee_claimer_token_account) = fee_claimer_token_account else {
+            return Ok(0);
+        };
+        require_keys_eq!(
+            fee_claimer_token_account.owner,
+            config.fee_claimer,
+            AmmError::InvalidFeeClaimerTokenAccount
+        );
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        let curve_authority_seeds = curve_authority_seeds!(const_pda::curve_authority::BUMP);
+        transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: vault.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: fee_claimer_token_account.to_account_info(),
+                    authority: self.curve_authority.to_account_info(),
+                },
+                &[&curve_authority_seeds[..]],
+            ),
+            amount,
+            decimals,
+        )?;
+        Ok(amount)
+    }
+
+    /// Opens the second position, deposits the creator's liquidity from the curve vaults and
+    /// hands the position over to the creator. It is not locked, the creator can withdraw it
+    fn create_creator_position(
+        &self,
+        trace: &MigrationTrace,
+        is_base_token_a: bool,
+        liquidity: u128,
+        token_a_amount_limit: u64,
+        token_b_amount_limit: u64,
+        creator: Pubkey,
+    ) -> Result<()> {
+        let (Some(position_nft_mint), Some(position_nft_account), Some(position)) = (
+            self.second_position_nft_mint.as_ref(),
+            self.second_position_nft_account.as_ref(),
+            self.second_position.as_ref(),
+        ) else {
+            return err!(AmmError::SecondPositionRequired);
+        };
+        let curve_authority_seeds = curve_authority_seeds!(const_pda::curve_authority::BUMP);
+
+        let accounts = damm_v2::cpi::accounts::CreatePosition {
+            owner: self.curve_authority.to_account_info(),
+            position_nft_mint: position_nft_mint.to_account_info(),
+            position_nft_account: position_nft_account.to_account_info(),
+            pool: self.pool.to_account_info(),
+            position: position.to_account_info(),
+            pool_authority: self.damm_pool_authority.to_account_info(),
+            payer: self.curve_authority.to_account_info(),
+            token_program: self.token_2022_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.damm_event_authority.to_account_info(),
+            program: self.amm_program.to_account_info(),
+        };
+        trace.cpi(
+            MigrationStage::CreateCreatorPosition,
+            accounts.to_account_infos().len(),
+            || {
+                damm_v2::cpi::create_position(CpiContext::new_with_signer(
+                    self.amm_program.to_account_info(),
+                    accounts,
+                    &[&curve_authority_seeds[..]],
+                ))
+            },
+        )?;
+
+        let (token_a_mint, token_b_mint, token_a_account, token_b_account) = if is_base_token_a {
+            (
+                self.base_mint.to_account_info(),
+                self.quote_mint.to_account_info(),
+                self.base_vault.to_account_info(),
+                self.quote_vault.to_account_info(),
+            )
+        } else {
+            (
+                self.quote_mint.to_account_info(),
+                self.base_mint.to_account_info(),
+                self.quote_vault.to_account_info(),
+                self.base_vault.to_account_info(),
+            )
+        };
+        let (token_a_program, token_b_program) = if is_base_token_a {
+            (
+                self.token_base_program.to_account_info(),
+                self.token_quote_program.to_account_info(),
+            )
+        } else {
+            (
+                self.token_quote_program.to_account_info(),
+                self.token_base_program.to_account_info(),
+            )
+        };
+        let accounts = damm_v2::cpi::accounts::AddLiquidity {
+            pool: self.pool.to_account_info(),
+            position: position.to_account_info(),
+            token_a_account,
+            token_b_account,
+            token_a_vault: self.token_a_vault.to_account_info(),
+            token_b_vault: self.token_b_vault.to_account_info(),
+            token_a_mint,
+            token_b_mint,
+            position_nft_account: position_nft_account.to_account_info(),
+            owner: self.curve_authority.to_account_info(),
+            token_a_program,
+            token_b_program,
+            event_authority: self.damm_event_authority.to_account_info(),
+            program: self.amm_program.to_account_info(),
+        };
+        trace.cpi(
+            MigrationStage::CreateCreatorPosition,
+            accounts.to_account_infos().len(),
+            || {
+                damm_v2::cpi::add_liquidity(
+                    CpiContext::new_with_signer(
+                        self.amm_program.to_account_info(),
+                        accounts,
+                        &[&curve_authority_seeds[..]],
+                    ),
+                    AddLiquidityParameters {
+                        liquidity_delta: liquidity,
+                        token_a_amount_threshold: token_a_amount_limit,
+                        token_b_amount_threshold: token_b_amount_limit,
+                    },
+                )
+            },
+        )?;
+
+        set_position_authority(
+            trace,
+            MigrationStage::CreateCreatorPosition,
+            self.token_2022_program.to_account_info(),
+            self.curve_authority.to_account_info(),
+            position_nft_account.to_account_info(),
+            creator,
+        )
+    }
+
     fn create_pool(
         &self,
+        trace: &MigrationTrace,
         pool_config: AccountInfo<'info>,
+        is_base_token_a: bool,
         liquidity: u128,
         sqrt_price: u128,
         bump: u8,
@@ -176,221 +976,354 @@ impl<'info> MigrateDammV2Ctx<'info> {
             ],
         )?;
 
+        let (
+            token_a_mint,
+            token_b_mint,
+            payer_token_a,
+            payer_token_b,
+            token_a_program,
+            token_b_program,
+        ) = if is_base_token_a {
+            (
+                self.base_mint.to_account_info(),
+                self.quote_mint.to_account_info(),
+                self.base_vault.to_account_info(),
+                self.quote_vault.to_account_info(),
+                self.token_base_program.to_account_info(),
+                self.token_quote_program.to_account_info(),
+            )
+        } else {
+            (
+                self.quote_mint.to_account_info(),
+                self.base_mint.to_account_info(),
+                self.quote_vault.to_account_info(),
+                self.base_vault.to_account_info(),
+                self.token_quote_program.to_account_info(),
+                self.token_base_program.to_account_info(),
+            )
+        };
+
         msg!("initialize pool");
-        damm_v2::cpi::initialize_pool(
-            CpiContext::new_with_signer(
-                self.amm_program.to_account_info(),
-                damm_v2::cpi::accounts::InitializePool {
-                    creator: self.curve_authority.to_account_info(),
-                    position_nft_mint: self.first_position_nft_mint.to_account_info(),
-                    position_nft_account: self.first_position_nft_account.to_account_info(),
-                    payer: self.curve_authority.to_account_info(),
-                    config: pool_config.to_account_info(),
-                    pool_authority: self.damm_pool_authority.to_account_info(),
-                    pool: self.pool.to_account_info(),
-                    position: self.first_position.to_account_info(),
-                    token_a_mint: self.base_mint.to_account_info(),
-                    token_b_mint: self.quote_mint.to_account_info(),
-                    token_a_vault: self.token_a_vault.to_account_info(),
-                    token_b_vault: self.token_b_vault.to_account_info(),
-                    payer_token_a: self.base_vault.to_account_info(),
-                    payer_token_b: self.quote_vault.to_account_info(),
-                    token_a_program: self.token_base_program.to_account_info(),
-                    token_b_program: self.token_quote_program.to_account_info(),
-                    token_2022_program: self.token_2022_program.to_account_info(),
-                    system_program: self.system_program.to_account_info(),
-                    event_authority: self.damm_event_authority.to_account_info(),
-                    program: self.amm_program.to_account_info(),
-                },
-                &[&curve_authority_seeds[..]],
-            ),
-            InitializePoolParameters {
-                liquidity,
-                sqrt_price,
-                activation_point: None,
+        let accounts = damm_v2::cpi::accounts::InitializePool {
+            creator: self.curve_authority.to_account_info(),
+            position_nft_mint: self.first_position_nft_mint.to_account_info(),
+            position_nft_account: self.first_position_nft_account.to_account_info(),
+            payer: self.curve_authority.to_account_info(),
+            config: pool_config.to_account_info(),
+            pool_authority: self.damm_pool_authority.to_account_info(),
+            pool: self.pool.to_account_info(),
+            position: self.first_position.to_account_info(),
+            token_a_mint,
+            token_b_mint,
+            token_a_vault: self.token_a_vault.to_account_info(),
+            token_b_vault: self.token_b_vault.to_account_info(),
+            payer_token_a,
+            payer_token_b,
+            token_a_program,
+            token_b_program,
+            token_2022_program: self.token_2022_program.to_account_info(),
+            system_program: self.system_program.to_account_info(),
+            event_authority: self.damm_event_authority.to_account_info(),
+            program: self.amm_program.to_account_info(),
+        };
+        trace.cpi(
+            MigrationStage::CreatePool,
+            accounts.to_account_infos().len(),
+            || {
+                damm_v2::cpi::initialize_pool(
+                    CpiContext::new_with_signer(
+                        self.amm_program.to_account_info(),
+                        accounts,
+                        &[&curve_authority_seeds[..]],
+                    ),
+                    InitializePoolParameters {
+                        liquidity,
+                        sqrt_price,
+                        activation_point: None,
+                    },
+                )
             },
-        )?;
-
-        Ok(())
+        )
     }
+}
 
-    fn lock_permanent_liquidity_for_first_position(
-        &self,
-        permanent_lock_liquidity: u128,
-        bump: u8,
-    ) -> Result<()> {
-        let curve_authority_seeds = curve_authority_seeds!(bump);
-        msg!("lock permanent liquidity for first position");
-        damm_v2::cpi::permanent_lock_position(
-            CpiContext::new_with_signer(
-                self.amm_program.to_account_info(),
-                damm_v2::cpi::accounts::PermanentLockPosition {
-                    pool: self.pool.to_account_info(),
-                    position: self.first_position.to_account_info(),
-                    position_nft_account: self.first_position_nft_account.to_account_info(),
-                    owner: self.curve_authority.to_account_info(),
-                    event_authority: self.damm_event_authority.to_account_info(),
-                    program: self.amm_program.to_account_info(),
-                },
-                &[&curve_authority_seeds[..]],
-            ),
-            permanent_lock_liquidity,
-        )?;
-        Ok(())
-    }
+/// Permanently locks the liquidity of the first position, signed by the curve authority
+pub fn lock_permanent_liquidity_for_first_position<'info>(
+    trace: &MigrationTrace,
+    accounts: damm_v2::cpi::accounts::PermanentLockPosition<'info>,
+    permanent_lock_liquidity: u128,
+) -> Result<()> {
+    let curve_authority_seeds = curve_authority_seeds!(const_pda::curve_authority::BUMP);
+    msg!("lock permanent liquidity for first position");
+    let amm_program = accounts.program.clone();
+    trace.cpi(
+        MigrationStage::LockLiquidity,
+        accounts.to_account_infos().len(),
+        || {
+            damm_v2::cpi::permanent_lock_position(
+                CpiContext::new_with_signer(amm_program, accounts, &[&curve_authority_seeds[..]]),
+                permanent_lock_liquidity,
+            )
+        },
+    )
+}
 
-    fn set_authority_for_first_position(&self, new_authority: Pubkey, bump: u8) -> Result<()> {
-        let curve_authority_seeds = curve_authority_seeds!(bump);
-        msg!("set authority for first position");
+/// Hands the nft account of the first position over to its owner
+pub fn set_authority_for_first_position<'info>(
+    trace: &MigrationTrace,
+    token_2022_program: AccountInfo<'info>,
+    curve_authority: AccountInfo<'info>,
+    first_position_nft_account: AccountInfo<'info>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    msg!("set authority for first position");
+    set_position_authority(
+        trace,
+        MigrationStage::TransferPosition,
+        token_2022_program,
+        curve_authority,
+        first_position_nft_account,
+        new_authority,
+    )
+}
+
+/// Hands the nft account of a position held by the curve authority over to `new_authority`
+fn set_position_authority<'info>(
+    trace: &MigrationTrace,
+    stage: MigrationStage,
+    token_2022_program: AccountInfo<'info>,
+    curve_authority: AccountInfo<'info>,
+    position_nft_account: AccountInfo<'info>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let curve_authority_seeds = curve_authority_seeds!(const_pda::curve_authority::BUMP);
+    let accounts = SetAuthority {
+        current_authority: curve_authority,
+        account_or_mint: position_nft_account,
+    };
+    trace.cpi(stage, accounts.to_account_infos().len(), || {
         set_authority(
             CpiContext::new_with_signer(
-                self.token_2022_program.to_account_info(),
-                SetAuthority {
-                    current_authority: self.curve_authority.to_account_info(),
-                    account_or_mint: self.first_position_nft_account.to_account_info(),
-                },
+                token_2022_program,
+                accounts,
                 &[&curve_authority_seeds[..]],
             ),
             AuthorityType::AccountOwner,
             Some(new_authority),
-        )?;
-        Ok(())
+        )
+    })
+}
+
+/// Marks the curve as migrated once the first position is handed over
+pub fn finalize_curve_migration(
+    curve: &mut BondingCurve,
+    curve_key: Pubkey,
+    dual_listing: Option<&AccountLoader<DualListing>>,
+    current_timestamp: u64,
+) -> Result<()> {
+    curve.update_after_migration();
+
+    // the sibling curve migrates on its own, the shared accounting records both graduations
+    if curve.is_dual_listed == 1 {
+        let dual_listing = dual_listing.ok_or(AmmError::DualListingRequired)?;
+        dual_listing
+            .load_mut()?
+            .record_migration(curve_key, current_timestamp)?;
     }
+
+    curve.set_migration_status(MigrationStatus::CreatedPool.into());
+    Ok(())
+}
+
+pub fn get_migration_event(
+    event_sequence: u64,
+    curve_key: Pubkey,
+    config_key: Pubkey,
+    curve: &BondingCurve,
+    config: &Config,
+    progress: &MigrationProgress,
+) -> EvtMigrateDammV2 {
+    EvtMigrateDammV2 {
+        event_sequence,
+        curve: curve_key,
+        config: config_key,
+        pool: progress.pool,
+        base_mint: curve.base_mint,
+        quote_mint: config.quote_mint,
+        deposited_base_amount: progress.deposited_base_amount,
+        deposited_quote_amount: progress.deposited_quote_amount,
+        initial_liquidity: progress.initial_liquidity,
+        sqrt_price: progress.sqrt_price,
+        is_base_token_a: progress.is_base_token_a == 1,
+        damm_config: progress.damm_config,
+        damm_config_overridden: progress.damm_config_overridden == 1,
+        second_position: (progress.second_position != Pubkey::default())
+            .then_some(progress.second_position),
+        creator_liquidity: progress.creator_liquidity,
+        migration_quote_fee: curve.migration_quote_fee,
+        migration_base_fee: curve.migration_base_fee,
+        migration_fee_collected: curve.migration_fee_collected,
+    }
+}
+
+/// The progress account is created by the migration itself, on creation the discriminator is
+/// only written when the instruction exits so the single borrow has to last for all the steps
+fn load_migration_progress<'a>(
+    migration_progress: &'a AccountLoader<MigrationProgress>,
+    curve: Pubkey,
+) -> Result<RefMut<'a, MigrationProgress>> {
+    Ok(match migration_progress.load_init() {
+        Ok(mut progress) => {
+            progress.init(curve);
+            progress
+        }
+        Err(_) => migration_progress.load_mut()?,
+    })
+}
+
+/// Quote paid out or credited by the create pool step
+#[derive(Default)]
+struct CreatePoolPayouts {
+    crank_reward: u64,
+    accrued_creator_fee: u64,
+    creator_fee_top_up: u64,
+    support_fund_deposit: u64,
+}
+
+fn emit_create_pool_payouts(
+    ctx: &Context<MigrateDammV2Ctx>,
+    payouts: &CreatePoolPayouts,
+) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    if payouts.crank_reward > 0 {
+        emit_cpi!(EvtMigrationCrankReward {
+            event_sequence: config.next_event_sequence()?,
+            curve: ctx.accounts.curve.key(),
+            config: ctx.accounts.config.key(),
+            cranker: ctx.accounts.migration_authority.key(),
+            amount: payouts.crank_reward,
+        });
+    }
+    if payouts.creator_fee_top_up > 0 {
+        emit_cpi!(EvtCreatorFeeTopUp {
+            event_sequence: config.next_event_sequence()?,
+            curve: ctx.accounts.curve.key(),
+            config: ctx.accounts.config.key(),
+            creator: ctx.accounts.curve.load()?.creator,
+            accrued_creator_fee: payouts.accrued_creator_fee,
+            top_up_amount: payouts.creator_fee_top_up,
+        });
+    }
+    if payouts.support_fund_deposit > 0 {
+        emit_cpi!(EvtFundSupport {
+            event_sequence: config.next_event_sequence()?,
+            curve: ctx.accounts.curve.key(),
+            config: ctx.accounts.config.key(),
+            amount: payouts.support_fund_deposit,
+        });
+    }
+    Ok(())
 }
 
+/// Runs every remaining migration step in a single transaction
 pub fn handle_migrate_damm_v2<'c: 'info, 'info>(
     ctx: Context<'_, '_, 'c, 'info, MigrateDammV2Ctx<'info>>,
+    allow_damm_config_override: bool,
 ) -> Result<()> {
-    let config = ctx.accounts.config.load()?;
-    {
-        require!(
-            ctx.remaining_accounts.len() == 1,
-            AmmError::MissingPoolConfigInRemainingAccount
-        );
-        let damm_config_loader: AccountLoader<'_, damm_v2::accounts::Config> =
-            AccountLoader::try_from(&ctx.remaining_accounts[0])?; // TODO fix damm config in remaining accounts
-        let damm_config = damm_config_loader.load()?;
-        ctx.accounts.validate_config_key(&damm_config)?;
+    let migration_progress = ctx.accounts.migration_progress.clone();
+    let mut progress = load_migration_progress(&migration_progress, ctx.accounts.curve.key())?;
+    if progress.get_step()? == MigrationStep::Finalized {
+        msg!("migration already finalized");
+        return Ok(());
     }
+    let payouts = ctx.accounts.create_pool_step(
+        &mut progress,
+        ctx.remaining_accounts,
+        allow_damm_config_override,
+    )?;
+    emit_create_pool_payouts(&ctx, &payouts)?;
 
-    let mut curve = ctx.accounts.curve.load_mut()?;
-
-    require!(
-        curve.get_migration_progress()? == MigrationStatus::PostBondingCurve,
-        AmmError::NotPermitToDoThisAction
+    // a resumed migration goes on with the pool and position of the first attempt
+    require_keys_eq!(
+        progress.pool,
+        ctx.accounts.pool.key(),
+        AmmError::InvalidAccount
     );
-
-    require!(
-        curve.is_curve_complete(config.migration_base_threshold),
-        AmmError::PoolIsIncompleted
+    require_keys_eq!(
+        progress.first_position,
+        ctx.accounts.first_position.key(),
+        AmmError::InvalidAccount
+    );
+    require_keys_eq!(
+        progress.first_position_nft_account,
+        ctx.accounts.first_position_nft_account.key(),
+        AmmError::InvalidAccount
     );
 
-    let initial_quote_vault_amount = ctx.accounts.quote_vault.amount;
-    let initial_base_vault_amount = ctx.accounts.base_vault.amount;
-    let MigrationAmount {
-        quote_amount,
-        base_amount,
-    } = curve.get_migration_amount(config.migration_fee_basis_points)?;
-
-    // Calculate the sqrt price from the amounts
-    let migration_sqrt_price =
-        get_sqrt_price_from_amounts(base_amount as u128, quote_amount as u128)?;
-
-    // calculate initial liquidity
-    let initial_liquidity =
-        get_liquidity_for_adding_liquidity(base_amount, quote_amount, migration_sqrt_price)?;
-
-    // create pool
-    msg!("create pool");
-    ctx.accounts.create_pool(
-        ctx.remaining_accounts[0].clone(),
-        initial_liquidity,
-        migration_sqrt_price,
-        const_pda::curve_authority::BUMP,
-    )?;
-    // lock permanent liquidity
-    msg!("lock permanent liquidity for first position");
-    ctx.accounts.lock_permanent_liquidity_for_first_position(
-        initial_liquidity,
-        const_pda::curve_authority::BUMP,
-    )?;
+    let trace = MigrationTrace::new(ctx.accounts.curve.key(), &ctx.accounts.config)?;
+    if progress.should_run(MigrationStep::LiquidityLocked)? {
+        inject_fault(ctx.remaining_accounts, FaultPoint::BeforeLiquidityLock)?;
+        // lock permanent liquidity
+        trace.reached(MigrationStage::LockLiquidity);
+        lock_permanent_liquidity_for_first_position(
+            &trace,
+            damm_v2::cpi::accounts::PermanentLockPosition {
+                pool: ctx.accounts.pool.to_account_info(),
+                position: ctx.accounts.first_position.to_account_info(),
+                position_nft_account: ctx.accounts.first_position_nft_account.to_account_info(),
+                owner: ctx.accounts.curve_authority.to_account_info(),
+                event_authority: ctx.accounts.damm_event_authority.to_account_info(),
+                program: ctx.accounts.amm_program.to_account_info(),
+            },
+            progress.initial_liquidity,
+        )?;
+        progress.set_step(MigrationStep::LiquidityLocked);
+    }
 
     msg!("transfer ownership of the first position");
-    ctx.accounts.set_authority_for_first_position(
-        ctx.accounts.migration_authority.key(),
-        const_pda::curve_authority::BUMP,
+    trace.reached(MigrationStage::TransferPosition);
+    set_authority_for_first_position(
+        &trace,
+        ctx.accounts.token_2022_program.to_account_info(),
+        ctx.accounts.curve_authority.to_account_info(),
+        ctx.accounts.first_position_nft_account.to_account_info(),
+        progress.position_owner,
     )?;
 
-    // reload quote reserve and base reserve
-    ctx.accounts.quote_vault.reload()?;
-    ctx.accounts.base_vault.reload()?;
-    let deposited_base_amount =
-        initial_base_vault_amount.safe_sub(ctx.accounts.base_vault.amount)?;
-    let deposited_quote_amount =
-        initial_quote_vault_amount.safe_sub(ctx.accounts.quote_vault.amount)?;
-
-    curve.update_after_migration();
-
-    // burn the rest of token in pool authority after migrated amount and fee
-    ctx.accounts.base_vault.reload()?;
-
-    curve.set_migration_status(MigrationStatus::CreatedPool.into());
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+    finalize_curve_migration(
+        &mut curve,
+        ctx.accounts.curve.key(),
+        ctx.accounts.dual_listing.as_ref(),
+        get_current_timestamp(ctx.remaining_accounts)?,
+    )?;
+    progress.set_step(MigrationStep::Finalized);
 
-    emit_cpi!(EvtMigrateDammV2 {
-        curve: ctx.accounts.curve.key(),
-        config: ctx.accounts.config.key(),
-        pool: ctx.accounts.pool.key(),
-        base_mint: ctx.accounts.base_mint.key(),
-        quote_mint: ctx.accounts.quote_mint.key(),
-        deposited_base_amount,
-        deposited_quote_amount,
-        initial_liquidity,
-        sqrt_price: migration_sqrt_price,
-    });
+    emit_cpi!(get_migration_event(
+        config.next_event_sequence()?,
+        ctx.accounts.curve.key(),
+        ctx.accounts.config.key(),
+        &curve,
+        &config,
+        &progress,
+    ));
 
     Ok(())
 }
 
-fn get_liquidity_for_adding_liquidity(
-    base_amount: u64,
-    quote_amount: u64,
-    sqrt_price: u128,
-) -> Result<u128> {
-    let liquidity_from_base =
-        get_initial_liquidity_from_delta_base(base_amount, MAX_SQRT_PRICE, sqrt_price)?;
-    let liquidity_from_quote =
-        get_initial_liquidity_from_delta_quote(quote_amount, MIN_SQRT_PRICE, sqrt_price)?;
-    if liquidity_from_base > U512::from(liquidity_from_quote) {
-        Ok(liquidity_from_quote)
-    } else {
-        Ok(liquidity_from_base
-            .try_into()
-            .map_err(|_| AmmError::TypeCastFailed)?)
-    }
-}
-
-// Δa = L * (1 / √P_lower - 1 / √P_upper) => L = Δa / (1 / √P_lower - 1 / √P_upper)
-fn get_initial_liquidity_from_delta_base(
-    base_amount: u64,
-    sqrt_max_price: u128,
-    sqrt_price: u128,
-) -> Result<U512> {
-    let price_delta = U512::from(sqrt_max_price.safe_sub(sqrt_price)?);
-    let prod = U512::from(base_amount)
-        .safe_mul(U512::from(sqrt_price))?
-        .safe_mul(U512::from(sqrt_max_price))?;
-    let liquidity = prod.safe_div(price_delta)?; // round down
-    Ok(liquidity)
-}
-
-// Δb = L (√P_upper - √P_lower) => L = Δb / (√P_upper - √P_lower)
-fn get_initial_liquidity_from_delta_quote(
-    quote_amount: u64,
-    sqrt_min_price: u128,
-    sqrt_price: u128,
-) -> Result<u128> {
-    let price_delta = U256::from(sqrt_price.safe_sub(sqrt_min_price)?);
-    let quote_amount = U256::from(quote_amount).safe_shl(128)?;
-    let liquidity = quote_amount.safe_div(price_delta)?; // round down
-    Ok(liquidity.try_into().map_err(|_| AmmError::TypeCastFailed)?)
+/// First migration step on its own, creates the pool and records the progress
+pub fn handle_migrate_damm_v2_create_pool<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, MigrateDammV2Ctx<'info>>,
+    allow_damm_config_override: bool,
+) -> Result<()> {
+    let migration_progress = ctx.accounts.migration_progress.clone();
+    let mut progress = load_migration_progress(&migration_progress, ctx.accounts.curve.key())?;
+    let payouts = ctx.accounts.create_pool_step(
+        &mut progress,
+        ctx.remaining_accounts,
+        allow_damm_config_override,
+    )?;
+    emit_create_pool_payouts(&ctx, &payouts)?;
+    Ok(())
 }
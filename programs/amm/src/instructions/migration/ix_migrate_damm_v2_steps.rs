@@ -0,0 +1,186 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenInterface;
+
+use crate::{
+    const_pda,
+    constants::seeds::{DUAL_LISTING_PREFIX, MIGRATION_PROGRESS_PREFIX},
+    instructions::migration::{
+        finalize_curve_migration, get_migration_event, lock_permanent_liquidity_for_first_position,
+        set_authority_for_first_position, MigrationStage, MigrationTrace,
+    },
+    states::{BondingCurve, Config, DualListing, FaultPoint, MigrationProgress, MigrationStep},
+    utils::{get_current_timestamp, inject_fault},
+};
+
+/// Accounts to resume a migration at the liquidity lock. Anyone can crank it, the pool and the
+/// position were fixed by the create pool step
+#[derive(Accounts)]
+pub struct MigrateDammV2LockLiquidityCtx<'info> {
+    /// bonding curve
+    #[account(has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// bonding curve config key
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [
+            MIGRATION_PROGRESS_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        has_one = curve,
+        has_one = pool,
+        has_one = first_position,
+        has_one = first_position_nft_account,
+    )]
+    pub migration_progress: AccountLoader<'info, MigrationProgress>,
+
+    /// CHECK: curve authority
+    #[account(
+        mut,
+        address = const_pda::curve_authority::ID,
+    )]
+    pub curve_authority: AccountInfo<'info>,
+
+    /// CHECK: pool, checked against the migration progress
+    #[account(mut)]
+    pub pool: UncheckedAccount<'info>,
+
+    /// CHECK: first position, checked against the migration progress
+    #[account(mut)]
+    pub first_position: UncheckedAccount<'info>,
+
+    /// CHECK: first position nft account, checked against the migration progress
+    pub first_position_nft_account: UncheckedAccount<'info>,
+
+    /// CHECK:
+    #[account(address = damm_v2::ID)]
+    pub amm_program: UncheckedAccount<'info>,
+
+    /// CHECK: damm event authority
+    pub damm_event_authority: UncheckedAccount<'info>,
+}
+
+/// Accounts to finish a migration, hands the first position over and marks the curve migrated.
+/// Anyone can crank it, the position owner was fixed by the create pool step
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateDammV2FinalizeCtx<'info> {
+    /// bonding curve
+    #[account(mut, has_one = config, has_one = base_mint)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// bonding curve config key
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [
+            MIGRATION_PROGRESS_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        has_one = curve,
+        has_one = first_position_nft_account,
+    )]
+    pub migration_progress: AccountLoader<'info, MigrationProgress>,
+
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID,
+    )]
+    pub curve_authority: AccountInfo<'info>,
+
+    /// CHECK: first position nft account, checked against the migration progress
+    #[account(mut)]
+    pub first_position_nft_account: UncheckedAccount<'info>,
+
+    /// CHECK: base token mint
+    pub base_mint: UncheckedAccount<'info>,
+
+    /// CHECK: token_program
+    pub token_2022_program: Interface<'info, TokenInterface>,
+
+    /// shared supply accounting of the base mint, required when the curve is dual listed
+    #[account(
+        mut,
+        seeds = [
+            DUAL_LISTING_PREFIX,
+            base_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub dual_listing: Option<AccountLoader<'info, DualListing>>,
+}
+
+pub fn handle_migrate_damm_v2_lock_liquidity(
+    ctx: Context<MigrateDammV2LockLiquidityCtx>,
+) -> Result<()> {
+    let mut progress = ctx.accounts.migration_progress.load_mut()?;
+    if !progress.should_run(MigrationStep::LiquidityLocked)? {
+        msg!("liquidity already locked");
+        return Ok(());
+    }
+
+    let trace = MigrationTrace::new(ctx.accounts.curve.key(), &ctx.accounts.config)?;
+    inject_fault(ctx.remaining_accounts, FaultPoint::BeforeLiquidityLock)?;
+    trace.reached(MigrationStage::LockLiquidity);
+    lock_permanent_liquidity_for_first_position(
+        &trace,
+        damm_v2::cpi::accounts::PermanentLockPosition {
+            pool: ctx.accounts.pool.to_account_info(),
+            position: ctx.accounts.first_position.to_account_info(),
+            position_nft_account: ctx.accounts.first_position_nft_account.to_account_info(),
+            owner: ctx.accounts.curve_authority.to_account_info(),
+            event_authority: ctx.accounts.damm_event_authority.to_account_info(),
+            program: ctx.accounts.amm_program.to_account_info(),
+        },
+        progress.initial_liquidity,
+    )?;
+    progress.set_step(MigrationStep::LiquidityLocked);
+
+    Ok(())
+}
+
+pub fn handle_migrate_damm_v2_finalize(ctx: Context<MigrateDammV2FinalizeCtx>) -> Result<()> {
+    let mut progress = ctx.accounts.migration_progress.load_mut()?;
+    if !progress.should_run(MigrationStep::Finalized)? {
+        msg!("migration already finalized");
+        return Ok(());
+    }
+
+    let trace = MigrationTrace::new(ctx.accounts.curve.key(), &ctx.accounts.config)?;
+    msg!("transfer ownership of the first position");
+    trace.reached(MigrationStage::TransferPosition);
+    set_authority_for_first_position(
+        &trace,
+        ctx.accounts.token_2022_program.to_account_info(),
+        ctx.accounts.curve_authority.to_account_info(),
+        ctx.accounts.first_position_nft_account.to_account_info(),
+        progress.position_owner,
+    )?;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+    finalize_curve_migration(
+        &mut curve,
+        ctx.accounts.curve.key(),
+        ctx.accounts.dual_listing.as_ref(),
+        get_current_timestamp(ctx.remaining_accounts)?,
+    )?;
+    progress.set_step(MigrationStep::Finalized);
+
+    emit_cpi!(get_migration_event(
+        config.next_event_sequence()?,
+        ctx.accounts.curve.key(),
+        ctx.accounts.config.key(),
+        &curve,
+        &config,
+        &progress,
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    events::EvtWithdrawLeftover,
+    states::{BondingCurve, Config, MigrationStatus},
+    utils::{burn_from_curve, transfer_from_curve},
+    AmmError,
+};
+
+/// Accounts to clear the base tokens a migrated curve left in its vault. Anyone can crank it,
+/// the leftover either goes to the leftover receiver of the config or is burned
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawLeftoverCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// bonding curve config key
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve
+    #[account(has_one = config, has_one = base_vault, has_one = base_mint)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// The vault token account for base token
+    #[account(mut, token::token_program = token_base_program, token::mint = base_mint)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of base token, mutable for the burn
+    #[account(mut)]
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// Leftover receiver's base token account, required when the config has a leftover receiver
+    #[account(
+        mut,
+        token::mint = base_mint,
+        token::token_program = token_base_program,
+    )]
+    pub leftover_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Token base program
+    pub token_base_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handle_withdraw_leftover(ctx: Context<WithdrawLeftoverCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let curve = ctx.accounts.curve.load()?;
+
+    require!(
+        curve.get_migration_progress()? == MigrationStatus::CreatedPool,
+        AmmError::PoolIsIncompleted
+    );
+
+    let amount = ctx.accounts.base_vault.amount;
+    require!(amount > 0, AmmError::AmountIsZero);
+
+    let receiver = if config.has_leftover_receiver() {
+        let leftover_token_account = ctx
+            .accounts
+            .leftover_token_account
+            .as_ref()
+            .ok_or(AmmError::InvalidLeftoverReceiver)?;
+        require!(
+            leftover_token_account.owner == config.leftover_receiver,
+            AmmError::InvalidLeftoverReceiver
+        );
+        transfer_from_curve(
+            ctx.accounts.curve_authority.to_account_info(),
+            &ctx.accounts.base_mint,
+            &ctx.accounts.base_vault,
+            leftover_token_account,
+            &ctx.accounts.token_base_program,
+            amount,
+            const_pda::curve_authority::BUMP,
+        )?;
+        Some(config.leftover_receiver)
+    } else {
+        burn_from_curve(
+            ctx.accounts.curve_authority.to_account_info(),
+            &ctx.accounts.base_mint,
+            &ctx.accounts.base_vault,
+            &ctx.accounts.token_base_program,
+            amount,
+            const_pda::curve_authority::BUMP,
+        )?;
+        None
+    };
+
+    emit_cpi!(EvtWithdrawLeftover {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        config: ctx.accounts.config.key(),
+        base_mint: ctx.accounts.base_mint.key(),
+        receiver,
+        amount,
+    });
+
+    Ok(())
+}
@@ -1,3 +1,17 @@
+pub mod ix_advance_migration_status;
+pub mod ix_archive_curve;
+pub mod ix_burn_curve_base_tokens;
+pub mod ix_claim_position_fee;
+pub mod ix_deploy_support_fund;
 pub mod ix_migrate_damm_v2;
+pub mod ix_migrate_damm_v2_steps;
+pub mod ix_withdraw_leftover;
 
+pub use ix_advance_migration_status::*;
+pub use ix_archive_curve::*;
+pub use ix_burn_curve_base_tokens::*;
+pub use ix_claim_position_fee::*;
+pub use ix_deploy_support_fund::*;
 pub use ix_migrate_damm_v2::*;
+pub use ix_migrate_damm_v2_steps::*;
+pub use ix_withdraw_leftover::*;
@@ -1,13 +1,49 @@
 pub mod admin;
+pub mod bonus;
 pub mod cashback;
+pub mod insurance;
 pub mod ix_claim_creator_fee;
 pub mod ix_create;
+pub mod ix_create_config_stats;
+pub mod ix_create_dual_listing;
+pub mod ix_create_protection;
+pub mod ix_finalize_curve_metadata;
+pub mod ix_get_fee_schedule;
+pub mod ix_realloc_curve;
+pub mod ix_refund;
+pub mod ix_set_creator_fee_recipient;
+pub mod ix_simulate_swap;
 pub mod ix_swap;
+pub mod ix_swap_token_to_token;
+pub mod ix_transfer_creator;
+pub mod ix_verify_curve_invariants;
+pub mod limit_order;
 pub mod migration;
+pub mod partner;
+pub mod raffle;
+pub mod reward;
 
 pub use admin::*;
+pub use bonus::*;
 pub use cashback::*;
+pub use insurance::*;
 pub use ix_claim_creator_fee::*;
 pub use ix_create::*;
+pub use ix_create_config_stats::*;
+pub use ix_create_dual_listing::*;
+pub use ix_create_protection::*;
+pub use ix_finalize_curve_metadata::*;
+pub use ix_get_fee_schedule::*;
+pub use ix_realloc_curve::*;
+pub use ix_refund::*;
+pub use ix_set_creator_fee_recipient::*;
+pub use ix_simulate_swap::*;
 pub use ix_swap::*;
+pub use ix_swap_token_to_token::*;
+pub use ix_transfer_creator::*;
+pub use ix_verify_curve_invariants::*;
+pub use limit_order::*;
 pub use migration::*;
+pub use partner::*;
+pub use raffle::*;
+pub use reward::*;
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::seeds::PARTNER_EARNINGS_PREFIX,
+    errors::AmmError,
+    events::EvtClaimPartnerEarnings,
+    states::{Config, PartnerEarnings},
+    utils::token::transfer_from_curve,
+};
+
+/// Accounts for a partner to claim its earnings in a quote mint
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimPartnerEarningsCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config in the quote mint of the earnings, sequences the event
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [
+            PARTNER_EARNINGS_PREFIX,
+            partner.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+        has_one = partner,
+        has_one = quote_mint,
+        has_one = quote_vault,
+    )]
+    pub partner_earnings: AccountLoader<'info, PartnerEarnings>,
+
+    /// Quote token vault holding the unclaimed earnings
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Partner's token account to receive the earnings, doesn't have to be the ATA
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub partner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub partner: Signer<'info>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handle_claim_partner_earnings(ctx: Context<ClaimPartnerEarningsCtx>) -> Result<()> {
+    let mut partner_earnings = ctx.accounts.partner_earnings.load_mut()?;
+    let amount = partner_earnings.claim()?;
+    require!(amount > 0, AmmError::NothingToClaim);
+
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.quote_vault,
+        &ctx.accounts.partner_token_account,
+        &ctx.accounts.token_quote_program,
+        amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    emit_cpi!(EvtClaimPartnerEarnings {
+        event_sequence: ctx.accounts.config.load_mut()?.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        partner_earnings: ctx.accounts.partner_earnings.key(),
+        partner: ctx.accounts.partner.key(),
+        amount,
+        total_claimed: partner_earnings.total_claimed,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::seeds::{PARTNER_EARNINGS_PREFIX, TOKEN_VAULT_PREFIX},
+    events::EvtCreatePartnerEarnings,
+    states::{Config, PartnerEarnings},
+};
+
+/// Accounts to create the earnings of a partner in a quote mint. Permissionless, so the fee
+/// claimer can create them for a partner that didn't before claiming the protocol fee of its
+/// curves
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreatePartnerEarningsCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config in the quote mint of the earnings, sequences the event
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// CHECK: partner the earnings belong to, doesn't have to be in the registry anymore
+    pub partner: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PartnerEarnings::INIT_SPACE,
+        seeds = [
+            PARTNER_EARNINGS_PREFIX,
+            partner.key().as_ref(),
+            quote_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub partner_earnings: AccountLoader<'info, PartnerEarnings>,
+
+    /// Quote token vault holding the unclaimed earnings
+    #[account(
+        init,
+        seeds = [
+            TOKEN_VAULT_PREFIX,
+            quote_mint.key().as_ref(),
+            partner_earnings.key().as_ref(),
+        ],
+        token::mint = quote_mint,
+        token::authority = curve_authority,
+        token::token_program = token_quote_program,
+        payer = payer,
+        bump,
+    )]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token
+    #[account(mint::token_program = token_quote_program)]
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_create_partner_earnings(ctx: Context<CreatePartnerEarningsCtx>) -> Result<()> {
+    ctx.accounts.partner_earnings.load_init()?.init(
+        ctx.accounts.partner.key(),
+        ctx.accounts.quote_mint.key(),
+        ctx.accounts.quote_vault.key(),
+    );
+
+    emit_cpi!(EvtCreatePartnerEarnings {
+        event_sequence: ctx.accounts.config.load_mut()?.next_event_sequence()?,
+        config: ctx.accounts.config.key(),
+        partner_earnings: ctx.accounts.partner_earnings.key(),
+        partner: ctx.accounts.partner.key(),
+        quote_mint: ctx.accounts.quote_mint.key(),
+    });
+
+    Ok(())
+}
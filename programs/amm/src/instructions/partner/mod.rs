@@ -0,0 +1,5 @@
+pub mod ix_claim_partner_earnings;
+pub mod ix_create_partner_earnings;
+
+pub use ix_claim_partner_earnings::*;
+pub use ix_create_partner_earnings::*;
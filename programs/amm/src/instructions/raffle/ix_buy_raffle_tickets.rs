@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::seeds::{RAFFLE_PREFIX, RAFFLE_TICKET_PREFIX},
+    errors::AmmError,
+    events::EvtBuyRaffleTickets,
+    safe_math::SafeMath,
+    states::{BondingCurve, Config, Raffle, RaffleTicket},
+    utils::transfer_from_user,
+};
+
+/// Accounts for a wallet to buy its tickets of a launch raffle, once per wallet
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BuyRaffleTicketsCtx<'info> {
+    /// config the bonding curve belongs to
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [
+            RAFFLE_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        has_one = curve,
+        has_one = quote_vault,
+    )]
+    pub raffle: AccountLoader<'info, Raffle>,
+
+    /// tickets of the wallet, their numbers are consecutive
+    #[account(
+        init,
+        seeds = [
+            RAFFLE_TICKET_PREFIX,
+            raffle.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = 8 + RaffleTicket::INIT_SPACE,
+    )]
+    pub raffle_ticket: AccountLoader<'info, RaffleTicket>,
+
+    /// Quote token vault of the raffle holding the ticket deposits
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner's token account paying for the tickets
+    #[account(mut, token::mint = quote_mint, token::token_program = token_quote_program)]
+    pub owner_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_buy_raffle_tickets(
+    ctx: Context<BuyRaffleTicketsCtx>,
+    ticket_count: u64,
+) -> Result<()> {
+    require!(ticket_count > 0, AmmError::AmountIsZero);
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut raffle = ctx.accounts.raffle.load_mut()?;
+    require!(
+        raffle.is_deposit_open(Clock::get()?.unix_timestamp as u64),
+        AmmError::RaffleDepositClosed
+    );
+    require!(
+        ticket_count <= raffle.max_tickets_per_wallet,
+        AmmError::RaffleTicketLimitExceeded
+    );
+
+    let first_ticket = raffle.buy_tickets(ticket_count)?;
+    ctx.accounts.raffle_ticket.load_init()?.init(
+        ctx.accounts.raffle.key(),
+        ctx.accounts.owner.key(),
+        first_ticket,
+        ticket_count,
+    );
+
+    transfer_from_user(
+        &ctx.accounts.owner,
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.owner_token_account,
+        &ctx.accounts.quote_vault,
+        &ctx.accounts.token_quote_program,
+        ticket_count.safe_mul(raffle.ticket_price)?,
+    )?;
+
+    emit_cpi!(EvtBuyRaffleTickets {
+        event_sequence: config.next_event_sequence()?,
+        raffle: ctx.accounts.raffle.key(),
+        raffle_ticket: ctx.accounts.raffle_ticket.key(),
+        owner: ctx.accounts.owner.key(),
+        first_ticket,
+        ticket_count,
+        total_tickets: raffle.total_tickets,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::seeds::{RAFFLE_PREFIX, RAFFLE_TICKET_PREFIX},
+    errors::AmmError,
+    events::EvtClaimRaffle,
+    safe_math::SafeMath,
+    states::{BondingCurve, Config, Raffle, RaffleTicket},
+    utils::token::transfer_from_curve,
+};
+
+/// Accounts for a wallet to claim the allocation of its winning tickets and the refund of the
+/// others once the raffle is drawn
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimRaffleCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config the bonding curve belongs to
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve the raffle launched, counts the winners as holders
+    #[account(mut, has_one = config, has_one = base_mint)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    #[account(
+        seeds = [
+            RAFFLE_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        has_one = curve,
+        constraint = raffle.load()?.quote_vault == raffle_quote_vault.key() @ AmmError::InvalidAccount,
+        constraint = raffle.load()?.base_vault == raffle_base_vault.key() @ AmmError::InvalidAccount,
+    )]
+    pub raffle: AccountLoader<'info, Raffle>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            RAFFLE_TICKET_PREFIX,
+            raffle.key().as_ref(),
+            owner.key().as_ref(),
+        ],
+        bump,
+        has_one = raffle,
+        has_one = owner,
+    )]
+    pub raffle_ticket: AccountLoader<'info, RaffleTicket>,
+
+    /// Quote token vault of the raffle holding the ticket deposits
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub raffle_quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Base token vault of the raffle holding the allocation of the winning tickets
+    #[account(mut, token::token_program = token_base_program, token::mint = base_mint)]
+    pub raffle_base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner's token account to receive the allocation
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = base_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_base_program,
+    )]
+    pub owner_base_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Owner's token account to receive the refund
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = quote_mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_quote_program,
+    )]
+    pub owner_quote_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of base token
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// Token base program
+    pub token_base_program: Interface<'info, TokenInterface>,
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_claim_raffle(ctx: Context<ClaimRaffleCtx>) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let raffle = ctx.accounts.raffle.load()?;
+    require!(raffle.is_drawn(), AmmError::RaffleNotDrawn);
+
+    let raffle_ticket = ctx.accounts.raffle_ticket.load()?;
+    let won_ticket_count =
+        raffle.get_won_ticket_count(raffle_ticket.first_ticket, raffle_ticket.ticket_count)?;
+    let (base_amount, refund_amount) =
+        raffle.get_claim_amounts(raffle_ticket.first_ticket, raffle_ticket.ticket_count)?;
+
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.base_mint,
+        &ctx.accounts.raffle_base_vault,
+        &ctx.accounts.owner_base_token_account,
+        &ctx.accounts.token_base_program,
+        base_amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.raffle_quote_vault,
+        &ctx.accounts.owner_quote_token_account,
+        &ctx.accounts.token_quote_program,
+        refund_amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    let pre_base_balance = ctx.accounts.owner_base_token_account.amount;
+    ctx.accounts
+        .curve
+        .load_mut()?
+        .update_holder_count(pre_base_balance, pre_base_balance.safe_add(base_amount)?)?;
+
+    emit_cpi!(EvtClaimRaffle {
+        event_sequence: config.next_event_sequence()?,
+        raffle: ctx.accounts.raffle.key(),
+        owner: ctx.accounts.owner.key(),
+        won_ticket_count,
+        base_amount,
+        refund_amount,
+    });
+
+    Ok(())
+}
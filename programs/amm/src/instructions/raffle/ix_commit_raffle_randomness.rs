@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::seeds::RAFFLE_PREFIX,
+    errors::AmmError,
+    events::EvtCommitRaffleRandomness,
+    states::{BondingCurve, Config, Raffle},
+    utils::{transfer_from_user, RandomnessData},
+};
+
+/// Accounts for the creator to commit the randomness an oversubscribed raffle is drawn with, and
+/// put up the bond the ticket holders get if it isn't revealed in time
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CommitRaffleRandomnessCtx<'info> {
+    /// config the bonding curve belongs to
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(has_one = config, has_one = creator)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [
+            RAFFLE_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        has_one = curve,
+        has_one = quote_vault,
+    )]
+    pub raffle: AccountLoader<'info, Raffle>,
+
+    /// CHECK: Switchboard on-demand randomness account, checked by `RandomnessData::load`
+    pub randomness_account: UncheckedAccount<'info>,
+
+    /// Quote token vault of the raffle, holds the bond until the draw
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Creator's token account paying the bond
+    #[account(mut, token::mint = quote_mint, token::token_program = token_quote_program)]
+    pub creator_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    pub creator: Signer<'info>,
+
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handle_commit_raffle_randomness(ctx: Context<CommitRaffleRandomnessCtx>) -> Result<()> {
+    let clock = Clock::get()?;
+    let mut raffle = ctx.accounts.raffle.load_mut()?;
+    require!(
+        !raffle.is_deposit_open(clock.unix_timestamp as u64),
+        AmmError::RaffleDepositOpen
+    );
+    require!(!raffle.is_drawn(), AmmError::RaffleAlreadyDrawn);
+    require!(
+        !raffle.is_randomness_committed(),
+        AmmError::RandomnessAlreadyCommitted
+    );
+
+    // committed right after its seed slot, before the oracle could reveal the value
+    let randomness = RandomnessData::load(&ctx.accounts.randomness_account)?;
+    require!(
+        randomness.seed_slot.saturating_add(1) == clock.slot && !randomness.is_revealed(),
+        AmmError::RandomnessNotFresh
+    );
+    let randomness_bond = raffle.get_randomness_bond_amount()?;
+    raffle.commit_randomness(
+        ctx.accounts.randomness_account.key(),
+        randomness.seed_slot,
+        clock.slot,
+        randomness_bond,
+    );
+
+    transfer_from_user(
+        &ctx.accounts.creator,
+        &ctx.accounts.quote_mint,
+        &ctx.accounts.creator_token_account,
+        &ctx.accounts.quote_vault,
+        &ctx.accounts.token_quote_program,
+        randomness_bond,
+    )?;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    emit_cpi!(EvtCommitRaffleRandomness {
+        event_sequence: config.next_event_sequence()?,
+        raffle: ctx.accounts.raffle.key(),
+        randomness_account: ctx.accounts.randomness_account.key(),
+        seed_slot: randomness.seed_slot,
+        randomness_bond,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::{
+        raffle::MAX_RAFFLE_DEPOSIT_SECONDS,
+        seeds::{RAFFLE_PREFIX, TOKEN_VAULT_PREFIX},
+    },
+    errors::AmmError,
+    events::EvtCreateRaffle,
+    states::{BondingCurve, Config, MigrationStatus, Raffle},
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CreateRaffleParams {
+    /// quote paid per ticket
+    pub ticket_price: u64,
+    /// tickets winning an allocation
+    pub winning_ticket_count: u64,
+    /// tickets a wallet can buy
+    pub max_tickets_per_wallet: u64,
+    /// time ticket sales close
+    pub deposit_end_timestamp: u64,
+}
+
+impl CreateRaffleParams {
+    pub fn validate(&self, current_timestamp: u64) -> Result<()> {
+        require!(
+            self.ticket_price > 0
+                && self.winning_ticket_count > 0
+                && self.max_tickets_per_wallet > 0,
+            AmmError::InvalidRaffleParameters
+        );
+        require!(
+            self.deposit_end_timestamp > current_timestamp
+                && self.deposit_end_timestamp
+                    <= current_timestamp.saturating_add(MAX_RAFFLE_DEPOSIT_SECONDS),
+            AmmError::InvalidRaffleParameters
+        );
+        Ok(())
+    }
+}
+
+/// Accounts for the creator to open a launch raffle on a curve that hasn't traded yet
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CreateRaffleCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config the bonding curve belongs to
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    /// bonding curve the raffle launches, trading waits for the draw
+    #[account(mut, has_one = creator, has_one = config, has_one = base_mint)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    #[account(
+        init,
+        seeds = [
+            RAFFLE_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        payer = creator,
+        space = 8 + Raffle::INIT_SPACE,
+    )]
+    pub raffle: AccountLoader<'info, Raffle>,
+
+    /// Quote token vault holding the ticket deposits
+    #[account(
+        init,
+        seeds = [
+            TOKEN_VAULT_PREFIX,
+            quote_mint.key().as_ref(),
+            raffle.key().as_ref(),
+        ],
+        token::mint = quote_mint,
+        token::authority = curve_authority,
+        token::token_program = token_quote_program,
+        payer = creator,
+        bump,
+    )]
+    pub raffle_quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Base token vault holding the allocation of the winning tickets
+    #[account(
+        init,
+        seeds = [
+            TOKEN_VAULT_PREFIX,
+            base_mint.key().as_ref(),
+            raffle.key().as_ref(),
+        ],
+        token::mint = base_mint,
+        token::authority = curve_authority,
+        token::token_program = token_base_program,
+        payer = creator,
+        bump,
+    )]
+    pub raffle_base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of base token
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    /// Token base program
+    pub token_base_program: Interface<'info, TokenInterface>,
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_create_raffle(
+    ctx: Context<CreateRaffleCtx>,
+    params: CreateRaffleParams,
+) -> Result<()> {
+    let current_timestamp = Clock::get()?.unix_timestamp as u64;
+    params.validate(current_timestamp)?;
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+    // the raffle allocation is the first buy of the curve, at its starting price
+    require!(
+        curve.get_migration_progress()? == MigrationStatus::PreBondingCurve
+            && curve.buy_count == 0
+            && curve.sell_count == 0,
+        AmmError::RaffleCurveAlreadyTraded
+    );
+
+    let mut raffle = ctx.accounts.raffle.load_init()?;
+    raffle.init(
+        ctx.accounts.curve.key(),
+        ctx.accounts.raffle_quote_vault.key(),
+        ctx.accounts.raffle_base_vault.key(),
+        &params,
+    );
+    // a sold out raffle has to be drawable on the curve as it is now
+    require!(
+        curve
+            .get_raffle_allocation(&config, raffle.get_max_allocation_quote_amount()?)?
+            .is_some(),
+        AmmError::RaffleCompletesCurve
+    );
+    curve.set_raffle_pending(true);
+
+    emit_cpi!(EvtCreateRaffle {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        raffle: ctx.accounts.raffle.key(),
+        creator: ctx.accounts.creator.key(),
+        ticket_price: params.ticket_price,
+        winning_ticket_count: params.winning_ticket_count,
+        max_tickets_per_wallet: params.max_tickets_per_wallet,
+        deposit_end_timestamp: params.deposit_end_timestamp,
+    });
+
+    Ok(())
+}
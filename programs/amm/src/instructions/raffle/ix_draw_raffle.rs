@@ -0,0 +1,219 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::seeds::RAFFLE_PREFIX,
+    errors::AmmError,
+    events::EvtDrawRaffle,
+    params::swap::TradeDirection,
+    states::{BondingCurve, Config, ConfigStats, Raffle},
+    utils::{get_current_timestamp, record_swap, transfer_from_curve, RandomnessData},
+};
+
+/// Accounts to draw a launch raffle once its ticket sales closed and open the curve's trading,
+/// permissionless
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DrawRaffleCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config the bonding curve belongs to
+    #[account(mut, has_one = quote_mint)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(
+        mut,
+        has_one = config,
+        has_one = base_vault,
+        has_one = quote_vault,
+        has_one = base_mint,
+    )]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [
+            RAFFLE_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        has_one = curve,
+        constraint = raffle.load()?.quote_vault == raffle_quote_vault.key() @ AmmError::InvalidAccount,
+        constraint = raffle.load()?.base_vault == raffle_base_vault.key() @ AmmError::InvalidAccount,
+    )]
+    pub raffle: AccountLoader<'info, Raffle>,
+
+    /// Base token vault of the curve
+    #[account(mut, token::token_program = token_base_program, token::mint = base_mint)]
+    pub base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Quote token vault of the curve
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Quote token vault of the raffle holding the ticket deposits
+    #[account(mut, token::token_program = token_quote_program, token::mint = quote_mint)]
+    pub raffle_quote_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Base token vault of the raffle receiving the allocation of the winning tickets
+    #[account(mut, token::token_program = token_base_program, token::mint = base_mint)]
+    pub raffle_base_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Creator's token account the randomness bond goes back to. Required once randomness is
+    /// committed, unless the draw timed out
+    #[account(
+        mut,
+        token::mint = quote_mint,
+        token::token_program = token_quote_program,
+        constraint = creator_token_account.owner == curve.load()?.creator @ AmmError::InvalidAccount,
+    )]
+    pub creator_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// The mint of base token
+    pub base_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// The mint of quote token
+    pub quote_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// stats of the config, counts the allocation buy
+    #[account(mut, has_one = config)]
+    pub config_stats: AccountLoader<'info, ConfigStats>,
+
+    /// Token base program
+    pub token_base_program: Interface<'info, TokenInterface>,
+    /// Token quote program
+    pub token_quote_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: randomness account committed to the raffle, checked by `RandomnessData::load`.
+    /// Required once randomness is committed
+    pub randomness_account: Option<UncheckedAccount<'info>>,
+}
+
+pub fn handle_draw_raffle(ctx: Context<DrawRaffleCtx>) -> Result<()> {
+    let current_timestamp = get_current_timestamp(ctx.remaining_accounts)?;
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut curve = ctx.accounts.curve.load_mut()?;
+    let mut raffle = ctx.accounts.raffle.load_mut()?;
+    require!(!raffle.is_drawn(), AmmError::RaffleAlreadyDrawn);
+    require!(
+        !raffle.is_deposit_open(current_timestamp),
+        AmmError::RaffleDepositOpen
+    );
+
+    let mut is_randomness_withheld = false;
+    if raffle.is_oversubscribed() {
+        // a committed randomness can't be skipped for free, the creator holding its reveal back
+        // until the draw times out cancels the raffle but forfeits the bond to the ticket holders
+        let random_value = if raffle.is_randomness_committed() {
+            let randomness_account = ctx
+                .accounts
+                .randomness_account
+                .as_ref()
+                .ok_or(AmmError::InvalidRandomnessAccount)?;
+            require_keys_eq!(
+                randomness_account.key(),
+                raffle.randomness_account,
+                AmmError::InvalidRandomnessAccount
+            );
+            let randomness = RandomnessData::load(randomness_account)?;
+            (randomness.seed_slot == raffle.randomness_seed_slot && randomness.is_revealed())
+                .then(|| randomness.get_random_u64())
+        } else {
+            None
+        };
+        match random_value {
+            Some(random_value) => raffle.draw(random_value)?,
+            None => {
+                require!(
+                    raffle.is_draw_expired(current_timestamp),
+                    AmmError::RandomnessNotRevealed
+                );
+                is_randomness_withheld = raffle.is_randomness_committed();
+                raffle.cancel();
+            }
+        }
+    } else {
+        raffle.draw(0)?;
+    }
+
+    let quote_amount = raffle.get_drawn_quote_amount()?;
+    let swap_result = if quote_amount > 0 {
+        curve.get_raffle_allocation(&config, quote_amount)?
+    } else {
+        None
+    };
+    if let Some(swap_result) = swap_result {
+        curve.apply_swap_result(&swap_result, TradeDirection::QuoteToBase, current_timestamp)?;
+        record_swap(
+            &ctx.accounts.config_stats,
+            &swap_result,
+            TradeDirection::QuoteToBase,
+        )?;
+        raffle.set_total_base_amount(swap_result.output_amount);
+
+        transfer_from_curve(
+            ctx.accounts.curve_authority.to_account_info(),
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.raffle_quote_vault,
+            &ctx.accounts.quote_vault,
+            &ctx.accounts.token_quote_program,
+            quote_amount,
+            const_pda::curve_authority::BUMP,
+        )?;
+        transfer_from_curve(
+            ctx.accounts.curve_authority.to_account_info(),
+            &ctx.accounts.base_mint,
+            &ctx.accounts.base_vault,
+            &ctx.accounts.raffle_base_vault,
+            &ctx.accounts.token_base_program,
+            swap_result.output_amount,
+            const_pda::curve_authority::BUMP,
+        )?;
+    } else if quote_amount > 0 {
+        // the fee or the threshold of the config changed since the raffle was created and the
+        // allocation doesn't fit anymore, nobody gets one rather than everyone being stuck
+        raffle.cancel();
+    }
+    curve.set_raffle_pending(false);
+
+    let randomness_bond = if is_randomness_withheld {
+        0
+    } else {
+        raffle.release_randomness_bond()
+    };
+    if randomness_bond > 0 {
+        let creator_token_account = ctx
+            .accounts
+            .creator_token_account
+            .as_ref()
+            .ok_or(AmmError::InvalidAccount)?;
+        transfer_from_curve(
+            ctx.accounts.curve_authority.to_account_info(),
+            &ctx.accounts.quote_mint,
+            &ctx.accounts.raffle_quote_vault,
+            creator_token_account,
+            &ctx.accounts.token_quote_program,
+            randomness_bond,
+            const_pda::curve_authority::BUMP,
+        )?;
+    }
+
+    emit_cpi!(EvtDrawRaffle {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        raffle: ctx.accounts.raffle.key(),
+        total_tickets: raffle.total_tickets,
+        drawn_ticket_count: raffle.drawn_ticket_count,
+        winning_offset: raffle.winning_offset,
+        swap_result,
+        virtual_base_reserve: curve.virtual_base_reserve,
+        virtual_quote_reserve: curve.virtual_quote_reserve,
+    });
+
+    Ok(())
+}
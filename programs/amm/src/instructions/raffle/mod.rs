@@ -0,0 +1,11 @@
+pub mod ix_buy_raffle_tickets;
+pub mod ix_claim_raffle;
+pub mod ix_commit_raffle_randomness;
+pub mod ix_create_raffle;
+pub mod ix_draw_raffle;
+
+pub use ix_buy_raffle_tickets::*;
+pub use ix_claim_raffle::*;
+pub use ix_commit_raffle_randomness::*;
+pub use ix_create_raffle::*;
+pub use ix_draw_raffle::*;
@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    const_pda,
+    constants::seeds::{REWARD_CLAIM_PREFIX, REWARD_VAULT_PREFIX},
+    errors::AmmError,
+    events::EvtClaimReward,
+    states::{BondingCurve, Config, MigrationStatus, RewardClaim, RewardVault},
+    utils::token::transfer_from_curve,
+};
+
+/// Accounts for a holder to claim their reward from the distribution
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimRewardCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config the bonding curve belongs to
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [
+            REWARD_VAULT_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        has_one = curve,
+        has_one = reward_mint,
+        constraint = reward_vault.load()?.token_vault == reward_token_vault.key() @ AmmError::InvalidAccount,
+    )]
+    pub reward_vault: AccountLoader<'info, RewardVault>,
+
+    /// Receipt of the claim, can only be created once per holder
+    #[account(
+        init,
+        payer = user,
+        space = 8 + RewardClaim::INIT_SPACE,
+        seeds = [
+            REWARD_CLAIM_PREFIX,
+            reward_vault.key().as_ref(),
+            user.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub reward_claim: AccountLoader<'info, RewardClaim>,
+
+    /// The vault token account holding the rewards
+    #[account(mut, token::token_program = token_program, token::mint = reward_mint)]
+    pub reward_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// User's token account to receive the reward
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = reward_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of reward token
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Token program of the reward mint
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_claim_reward(
+    ctx: Context<ClaimRewardCtx>,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    require!(amount > 0, AmmError::AmountIsZero);
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let curve = ctx.accounts.curve.load()?;
+    require!(
+        curve.get_migration_progress()? != MigrationStatus::PreBondingCurve,
+        AmmError::PoolIsIncompleted
+    );
+
+    let mut reward_vault = ctx.accounts.reward_vault.load_mut()?;
+    require!(
+        reward_vault.verify_claim(&ctx.accounts.user.key(), amount, &proof),
+        AmmError::InvalidRewardProof
+    );
+    require!(
+        amount <= reward_vault.get_unclaimed_amount()?,
+        AmmError::InsufficientRewardFunds
+    );
+    reward_vault.claim(amount)?;
+
+    ctx.accounts.reward_claim.load_init()?.init(
+        ctx.accounts.reward_vault.key(),
+        ctx.accounts.user.key(),
+        amount,
+    );
+
+    transfer_from_curve(
+        ctx.accounts.curve_authority.to_account_info(),
+        &ctx.accounts.reward_mint,
+        &ctx.accounts.reward_token_vault,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.token_program,
+        amount,
+        const_pda::curve_authority::BUMP,
+    )?;
+
+    emit_cpi!(EvtClaimReward {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        user: ctx.accounts.user.key(),
+        amount,
+    });
+
+    Ok(())
+}
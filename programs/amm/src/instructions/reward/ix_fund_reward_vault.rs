@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::{REWARD_VAULT_PREFIX, TOKEN_VAULT_PREFIX},
+    errors::AmmError,
+    events::EvtFundRewardVault,
+    states::{BondingCurve, Config, ProtocolAuthority, RewardVault},
+    utils::transfer_from_user,
+};
+
+/// Accounts for an admin to fund the holder rewards of a curve
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FundRewardVaultCtx<'info> {
+    /// CHECK: curve authority
+    #[account(
+        address = const_pda::curve_authority::ID
+    )]
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// config the bonding curve belongs to
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    /// The reward vault PDA of the curve
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = 8 + RewardVault::INIT_SPACE,
+        seeds = [
+            REWARD_VAULT_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub reward_vault: AccountLoader<'info, RewardVault>,
+
+    /// Token vault holding the rewards
+    #[account(
+        init_if_needed,
+        seeds = [
+            TOKEN_VAULT_PREFIX,
+            reward_mint.key().as_ref(),
+            reward_vault.key().as_ref(),
+        ],
+        token::mint = reward_mint,
+        token::authority = curve_authority,
+        token::token_program = token_program,
+        payer = admin,
+        bump,
+    )]
+    pub reward_token_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Admin's token account funding the rewards
+    #[account(mut, token::mint = reward_mint, token::token_program = token_program)]
+    pub admin_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// The mint of reward token
+    pub reward_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can fund holder rewards
+    #[account(
+        mut,
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+
+    /// Token program of the reward mint
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handle_fund_reward_vault(ctx: Context<FundRewardVaultCtx>, amount: u64) -> Result<()> {
+    require!(amount > 0, AmmError::AmountIsZero);
+
+    let mut config = ctx.accounts.config.load_mut()?;
+    let mut reward_vault = match ctx.accounts.reward_vault.load_init() {
+        Ok(mut reward_vault) => {
+            reward_vault.init(
+                ctx.accounts.curve.key(),
+                ctx.accounts.reward_mint.key(),
+                ctx.accounts.reward_token_vault.key(),
+            );
+            reward_vault
+        }
+        Err(_) => ctx.accounts.reward_vault.load_mut()?,
+    };
+    // one reward mint per curve, a vault of another mint would never be paid out
+    require_keys_eq!(
+        reward_vault.token_vault,
+        ctx.accounts.reward_token_vault.key(),
+        AmmError::InvalidAccount
+    );
+    reward_vault.fund(amount)?;
+
+    transfer_from_user(
+        &ctx.accounts.admin,
+        &ctx.accounts.reward_mint,
+        &ctx.accounts.admin_token_account,
+        &ctx.accounts.reward_token_vault,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+
+    emit_cpi!(EvtFundRewardVault {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        reward_mint: ctx.accounts.reward_mint.key(),
+        admin: ctx.accounts.admin.key(),
+        amount,
+        total_funded: reward_vault.total_funded,
+    });
+
+    Ok(())
+}
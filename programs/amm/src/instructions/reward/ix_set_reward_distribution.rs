@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    assert_eq_admin, const_pda,
+    constants::seeds::REWARD_VAULT_PREFIX,
+    errors::AmmError,
+    events::EvtSetRewardDistribution,
+    states::{BondingCurve, Config, MigrationStatus, ProtocolAuthority, RewardVault},
+};
+
+/// Accounts for an admin to publish the holder snapshot of a graduated curve
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetRewardDistributionCtx<'info> {
+    /// config the bonding curve belongs to
+    #[account(mut)]
+    pub config: AccountLoader<'info, Config>,
+
+    #[account(has_one = config)]
+    pub curve: AccountLoader<'info, BondingCurve>,
+
+    #[account(
+        mut,
+        seeds = [
+            REWARD_VAULT_PREFIX,
+            curve.key().as_ref(),
+        ],
+        bump,
+        has_one = curve,
+    )]
+    pub reward_vault: AccountLoader<'info, RewardVault>,
+
+    /// protocol authorities, holds the admin keys
+    #[account(address = const_pda::protocol_authority::ID)]
+    pub protocol_authority: AccountLoader<'info, ProtocolAuthority>,
+
+    /// only admin can set the distribution
+    #[account(
+        constraint = assert_eq_admin(&protocol_authority, admin.key())? @ AmmError::Unauthorized,
+    )]
+    pub admin: Signer<'info>,
+}
+
+pub fn handle_set_reward_distribution(
+    ctx: Context<SetRewardDistributionCtx>,
+    merkle_root: [u8; 32],
+) -> Result<()> {
+    let mut config = ctx.accounts.config.load_mut()?;
+    let curve = ctx.accounts.curve.load()?;
+    // holders are snapshotted once the curve graduated
+    require!(
+        curve.get_migration_progress()? != MigrationStatus::PreBondingCurve,
+        AmmError::PoolIsIncompleted
+    );
+
+    let mut reward_vault = ctx.accounts.reward_vault.load_mut()?;
+    // a new root could pay the same holder twice
+    require!(
+        reward_vault.total_claimed == 0,
+        AmmError::RewardDistributionStarted
+    );
+    let old_merkle_root = std::mem::replace(&mut reward_vault.merkle_root, merkle_root);
+
+    emit_cpi!(EvtSetRewardDistribution {
+        event_sequence: config.next_event_sequence()?,
+        curve: ctx.accounts.curve.key(),
+        old_merkle_root,
+        new_merkle_root: merkle_root,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,7 @@
+pub mod ix_claim_reward;
+pub mod ix_fund_reward_vault;
+pub mod ix_set_reward_distribution;
+
+pub use ix_claim_reward::*;
+pub use ix_fund_reward_vault::*;
+pub use ix_set_reward_distribution::*;
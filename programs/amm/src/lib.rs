@@ -18,6 +18,7 @@ pub mod states;
 pub mod utils;
 
 pub mod params;
+pub use params::config::*;
 
 // declare_id!("6eqkYbNVgXs3yWPXtBdnyGiNPaoMzTLJySuYjqPykZmv");
 declare_id!("4RAA1rYL3U1dFmbTTMJnu8SA1bkyJjSpWvLkZAHcjoLm");
@@ -26,50 +27,714 @@ declare_id!("4RAA1rYL3U1dFmbTTMJnu8SA1bkyJjSpWvLkZAHcjoLm");
 pub mod amm {
     use super::*;
 
+    /// Create the protocol authority holding the admin keys (upgrade authority of the program
+    /// only, once)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `global_authority` - The admin of configs, curves and the protocol wide settings.
+    /// * `migration_authority` - The admin migrating curves without a delegation.
+    /// * `fee_type_authority` - The admin switching fee types and claiming the meme fee.
+    ///
+    pub fn initialize_authority(
+        ctx: Context<InitializeAuthorityCtx>,
+        global_authority: Pubkey,
+        migration_authority: Pubkey,
+        fee_type_authority: Pubkey,
+    ) -> Result<()> {
+        handle_initialize_authority(
+            ctx,
+            global_authority,
+            migration_authority,
+            fee_type_authority,
+        )
+    }
+
+    /// Nominate the next key of a protocol authority role, it takes over once it accepts
+    /// (current key of the role or global authority only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `authority_type` - The role to transfer (0: Global, 1: Migration, 2: FeeType).
+    /// * `new_authority` - The key nominated for the role, default cancels a pending transfer.
+    ///
+    pub fn transfer_authority(
+        ctx: Context<TransferAuthorityCtx>,
+        authority_type: u8,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        handle_transfer_authority(ctx, authority_type, new_authority)
+    }
+
+    /// Accept a protocol authority role nominated by `transfer_authority` (nominated key only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `authority_type` - The role to accept (0: Global, 1: Migration, 2: FeeType).
+    ///
+    pub fn accept_authority(ctx: Context<AcceptAuthorityCtx>, authority_type: u8) -> Result<()> {
+        handle_accept_authority(ctx, authority_type)
+    }
+
     /// The configuration of the AMM
     ///
     /// # Arguments
     ///
     /// * `ctx` - The accounts needed by the instruction.
-    /// * `config_params` - The parameters for the configuration creation.
+    /// * `index` - The index of the config among the configs of its quote mint.
+    /// * `config_params` - The parameters for the configuration creation.
+    ///
+    pub fn create_config(
+        ctx: Context<CreateConfigCtx>,
+        index: u16,
+        config_params: ConfigParameters,
+    ) -> Result<()> {
+        handle_create_config(ctx, index, config_params)
+    }
+
+    /// Reallocate a legacy keypair config to the current config layout (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn migrate_config(ctx: Context<MigrateConfigCtx>) -> Result<()> {
+        handle_migrate_config(ctx)
+    }
+
+    /// Propose a timelocked fee/migration parameter change (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `params` - The proposed fee and migration parameters.
+    ///
+    pub fn propose_config_change(
+        ctx: Context<ProposeConfigChangeCtx>,
+        params: ConfigChangeParameters,
+    ) -> Result<()> {
+        handle_propose_config_change(ctx, params)
+    }
+
+    /// Execute a pending config change once its timelock has passed (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChangeCtx>) -> Result<()> {
+        handle_execute_config_change(ctx)
+    }
+
+    /// Cancel a pending config change (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn cancel_config_change(ctx: Context<CancelConfigChangeCtx>) -> Result<()> {
+        handle_cancel_config_change(ctx)
+    }
+
+    /// Hand the protocol fee claim rights of a config over to a new key
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn rotate_fee_claimer(ctx: Context<RotateFeeClaimerCtx>) -> Result<()> {
+        handle_rotate_fee_claimer(ctx)
+    }
+
+    /// Change the approved DAMM v2 config for migrations of a config's curves (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn set_damm_config(ctx: Context<SetDammConfigCtx>) -> Result<()> {
+        handle_set_damm_config(ctx)
+    }
+
+    /// Set the human-readable label of a config, creating its label account on the first set
+    /// (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `label` - The label, 1 to 32 bytes.
+    ///
+    pub fn set_config_label(ctx: Context<SetConfigLabelCtx>, label: String) -> Result<()> {
+        handle_set_config_label(ctx, label)
+    }
+
+    /// Create the stats account of a config created before configs came with one
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn create_config_stats(ctx: Context<CreateConfigStatsCtx>) -> Result<()> {
+        handle_create_config_stats(ctx)
+    }
+
+    /// Publish the hash of the curve creation template of a config, all zeros removes it (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `curve_template_hash` - The hash of the template covering metadata rules and launch options.
+    ///
+    pub fn set_curve_template(
+        ctx: Context<SetCurveTemplateCtx>,
+        curve_template_hash: [u8; 32],
+    ) -> Result<()> {
+        handle_set_curve_template(ctx, curve_template_hash)
+    }
+
+    /// Halt buys, sells or both on a whole config or a single curve (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `trading_pause` - 0: None, 1: Buys, 2: Sells, 3: All.
+    ///
+    pub fn set_trading_pause(ctx: Context<SetTradingPauseCtx>, trading_pause: u8) -> Result<()> {
+        handle_set_trading_pause(ctx, trading_pause)
+    }
+
+    /// Set the time buys close on a curve, sells stay open for a grace period after it (admin
+    /// only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `trading_end_timestamp` - The time buys close, 0 trades until migration.
+    ///
+    pub fn set_trading_end(
+        ctx: Context<SetTradingEndCtx>,
+        trading_end_timestamp: u64,
+    ) -> Result<()> {
+        handle_set_trading_end(ctx, trading_end_timestamp)
+    }
+
+    /// Switch where the creator part of a curve's trading fee accrues (fee type admin only).
+    /// Fees accrued before the switch stay in their bucket
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `fee_type` - 0: Creator, 1: Meme.
+    ///
+    pub fn set_fee_type(ctx: Context<SetFeeTypeCtx>, fee_type: u8) -> Result<()> {
+        handle_set_fee_type(ctx, fee_type)
+    }
+
+    /// Restrict the curve creation of a config to whitelisted creators, or lift it (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `enabled` - Whether only whitelisted creators can create curves.
+    ///
+    pub fn set_creator_whitelist_mode(
+        ctx: Context<SetCreatorWhitelistModeCtx>,
+        enabled: bool,
+    ) -> Result<()> {
+        handle_set_creator_whitelist_mode(ctx, enabled)
+    }
+
+    /// Whitelist a creator on a config (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `creator` - The creator allowed to create curves in whitelist mode.
+    ///
+    pub fn add_whitelisted_creator(
+        ctx: Context<AddWhitelistedCreatorCtx>,
+        creator: Pubkey,
+    ) -> Result<()> {
+        handle_add_whitelisted_creator(ctx, creator)
+    }
+
+    /// Remove a creator from the whitelist of a config, closing its entry (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn remove_whitelisted_creator(ctx: Context<RemoveWhitelistedCreatorCtx>) -> Result<()> {
+        handle_remove_whitelisted_creator(ctx)
+    }
+
+    /// Approve a partner frontend in the partner registry (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `partner` - The wallet of the partner.
+    /// * `fee_share_basis_points` - The share of the protocol fee of the curves created through
+    ///   the partner.
+    ///
+    pub fn add_partner(
+        ctx: Context<AddPartnerCtx>,
+        partner: Pubkey,
+        fee_share_basis_points: u16,
+    ) -> Result<()> {
+        handle_add_partner(ctx, partner, fee_share_basis_points)
+    }
+
+    /// Remove a partner from the partner registry, closing its entry (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn remove_partner(ctx: Context<RemovePartnerCtx>) -> Result<()> {
+        handle_remove_partner(ctx)
+    }
+
+    /// Create the earnings account and vault of a partner in a quote mint (permissionless)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn create_partner_earnings(ctx: Context<CreatePartnerEarningsCtx>) -> Result<()> {
+        handle_create_partner_earnings(ctx)
+    }
+
+    /// Claim the earnings of a partner in a quote mint (partner only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn claim_partner_earnings(ctx: Context<ClaimPartnerEarningsCtx>) -> Result<()> {
+        handle_claim_partner_earnings(ctx)
+    }
+
+    /// Log the compute units around the migration CPIs of a config's curves (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `enabled` - Whether the migration CPIs log `EvtCpiTelemetry`.
+    ///
+    pub fn set_cpi_telemetry(ctx: Context<SetCpiTelemetryCtx>, enabled: bool) -> Result<()> {
+        handle_set_cpi_telemetry(ctx, enabled)
+    }
+
+    /// Pause or unpause a config or a single curve, a paused config also halts curve creation
+    /// (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `paused` - Whether the config or the curve is paused.
+    ///
+    pub fn set_pause(ctx: Context<SetPauseCtx>, paused: bool) -> Result<()> {
+        handle_set_pause(ctx, paused)
+    }
+
+    /// Create the insurance fund of a config and its quote vault (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn create_insurance_fund(ctx: Context<CreateInsuranceFundCtx>) -> Result<()> {
+        handle_create_insurance_fund(ctx)
+    }
+
+    /// Propose a timelocked payout from the insurance fund (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `amount` - The amount of quote token to pay out.
+    ///
+    pub fn propose_insurance_payout(
+        ctx: Context<ProposeInsurancePayoutCtx>,
+        amount: u64,
+    ) -> Result<()> {
+        handle_propose_insurance_payout(ctx, amount)
+    }
+
+    /// Execute the pending insurance payout once its timelock has passed (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn execute_insurance_payout(ctx: Context<ExecuteInsurancePayoutCtx>) -> Result<()> {
+        handle_execute_insurance_payout(ctx)
+    }
+
+    /// Cancel the pending insurance payout (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn cancel_insurance_payout(ctx: Context<CancelInsurancePayoutCtx>) -> Result<()> {
+        handle_cancel_insurance_payout(ctx)
+    }
+
+    /// Create a new token and bonding curve, optionally with the creator's first buy
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `curve_params` - The parameters for the bonding curve creation.
+    ///
+    pub fn create_curve_with_spl_token(
+        ctx: Context<CreateCurveCtx>,
+        curve_params: CreateCurveParams,
+    ) -> Result<()> {
+        handle_create_curve_spl_token(ctx, curve_params)
+    }
+
+    /// Create a new token and bonding curve without trading, for metadata too large for the
+    /// creation transaction. Trading starts with `finalize_curve_metadata`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `curve_params` - The parameters for the bonding curve creation, without an initial buy.
+    ///
+    pub fn init_curve(ctx: Context<CreateCurveCtx>, curve_params: CreateCurveParams) -> Result<()> {
+        handle_init_curve(ctx, curve_params)
+    }
+
+    /// Write the extended metadata of a curve created with `init_curve` and enable its trading
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `uri` - The extended metadata URI.
+    /// * `extension` - Partner defined extension data.
+    ///
+    pub fn finalize_curve_metadata(
+        ctx: Context<FinalizeCurveMetadataCtx>,
+        uri: String,
+        extension: Vec<u8>,
+    ) -> Result<()> {
+        handle_finalize_curve_metadata(ctx, uri, extension)
+    }
+
+    /// Create a new Token-2022 token and bonding curve, the metadata is stored in the mint
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `curve_params` - The parameters for the bonding curve creation.
+    ///
+    pub fn create_curve_with_token_2022(
+        ctx: Context<CreateCurveToken2022Ctx>,
+        curve_params: CreateCurveParams,
+    ) -> Result<()> {
+        handle_create_curve_token_2022(ctx, curve_params)
+    }
+
+    /// List a freshly created base mint on a second config with another quote mint, moving a
+    /// share of the supply to the new curve
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `secondary_base_supply` - The base supply moved from the primary curve to the secondary curve.
+    ///
+    pub fn create_dual_listing(
+        ctx: Context<CreateDualListingCtx>,
+        secondary_base_supply: u64,
+    ) -> Result<()> {
+        handle_create_dual_listing(ctx, secondary_base_supply)
+    }
+
+    /// Swap tokens
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `params` - The parameters for the swap operation.
+    ///
+    /// Returns the swap result with the return data.
+    ///
+    pub fn swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<states::SwapResult> {
+        handle_swap(ctx, VersionedSwapParameters::V1(params))
+    }
+
+    /// Swap tokens with versioned parameters, new swap options are added as new versions
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `params` - The parameters for the swap operation, prefixed by their version.
+    ///
+    /// Returns the swap result with the return data.
+    ///
+    pub fn swap_versioned(
+        ctx: Context<SwapCtx>,
+        params: VersionedSwapParameters,
+    ) -> Result<states::SwapResult> {
+        handle_swap(ctx, params)
+    }
+
+    /// Read the fee rates a wallet pays on swaps of a config, given its cashback and referral
+    /// accounts, so clients can show personalized fees. With a curve, the rates include its
+    /// launch fee. Returned with the return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `trade_direction` - 0 for sells, 1 for buys, the cashback rate depends on it.
+    ///
+    pub fn get_fee_schedule(
+        ctx: Context<GetFeeScheduleCtx>,
+        trade_direction: u8,
+    ) -> Result<states::FeeSchedule> {
+        handle_get_fee_schedule(ctx, trade_direction)
+    }
+
+    /// Quote a swap of a wallet on a curve against its current state, so clients get exact
+    /// outputs without reimplementing the curve math. Returned with the return data
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `amount_in` - Input amount of the swap.
+    /// * `trade_direction` - 0 for sells, 1 for buys.
+    ///
+    pub fn simulate_swap(
+        ctx: Context<SimulateSwapCtx>,
+        amount_in: u64,
+        trade_direction: u8,
+    ) -> Result<states::SwapSimulation> {
+        handle_simulate_swap(ctx, amount_in, trade_direction)
+    }
+
+    /// Check the constant product, the reserves and the fee buckets of a curve against its
+    /// config and vaults, for monitoring bots and auditors. Reported with
+    /// `EvtVerifyCurveInvariants`
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn verify_curve_invariants(ctx: Context<VerifyCurveInvariantsCtx>) -> Result<()> {
+        handle_verify_curve_invariants(ctx)
+    }
+
+    /// Grow a bonding curve created on an older layout to the current one (permissionless), the
+    /// sponsor pays the rent of the larger account
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn realloc_curve(ctx: Context<ReallocCurveCtx>) -> Result<()> {
+        handle_realloc_curve(ctx)
+    }
+
+    /// Sell the base token of a curve and buy the base token of another curve with the proceeds,
+    /// both curves share the quote mint
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `params` - The amount to sell and the minimum amount to buy.
+    ///
+    pub fn swap_token_to_token(
+        ctx: Context<SwapTokenToTokenCtx>,
+        params: SwapTokenToTokenParameters,
+    ) -> Result<()> {
+        handle_swap_token_to_token(ctx, params)
+    }
+
+    /// Return base to a curve whose trading closed after its trading end and the sell grace
+    /// period, for a pro rata share of its quote reserve
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `base_amount` - The base amount to return.
+    ///
+    pub fn refund(ctx: Context<RefundCtx>, base_amount: u64) -> Result<()> {
+        handle_refund(ctx, base_amount)
+    }
+
+    /// Move a curve that completed without a swap, e.g. by the decay of its migration quote
+    /// threshold, to PostBondingCurve so it can be migrated. Anyone can crank it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn advance_migration_status(ctx: Context<AdvanceMigrationStatusCtx>) -> Result<()> {
+        handle_advance_migration_status(ctx)
+    }
+
+    /// migrate the bonding curve to Meteora DAMM v2. Configs with permissionless migration let
+    /// anyone crank it and pay the crank reward from the curve's protocol fee
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction, the DAMM v2 config goes in the remaining accounts.
+    /// * `allow_damm_config_override` - Migrate with a DAMM v2 config other than the approved one of the config.
+    ///
+    pub fn migrate_damm_v2<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, MigrateDammV2Ctx<'info>>,
+        allow_damm_config_override: bool,
+    ) -> Result<()> {
+        handle_migrate_damm_v2(ctx, allow_damm_config_override)
+    }
+
+    /// First step of a staged migration, creates the Meteora DAMM v2 pool. Every step records
+    /// its progress and is a no-op once done, so a crank can resume a migration that failed
+    /// halfway
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction, the DAMM v2 config goes in the remaining accounts.
+    /// * `allow_damm_config_override` - Migrate with a DAMM v2 config other than the approved one of the config.
+    ///
+    pub fn migrate_damm_v2_create_pool<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, MigrateDammV2Ctx<'info>>,
+        allow_damm_config_override: bool,
+    ) -> Result<()> {
+        handle_migrate_damm_v2_create_pool(ctx, allow_damm_config_override)
+    }
+
+    /// Second step of a staged migration, permanently locks the liquidity of the first position
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn migrate_damm_v2_lock_liquidity(
+        ctx: Context<MigrateDammV2LockLiquidityCtx>,
+    ) -> Result<()> {
+        handle_migrate_damm_v2_lock_liquidity(ctx)
+    }
+
+    /// Last step of a staged migration, hands the first position over and marks the curve migrated
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn migrate_damm_v2_finalize(ctx: Context<MigrateDammV2FinalizeCtx>) -> Result<()> {
+        handle_migrate_damm_v2_finalize(ctx)
+    }
+
+    /// Set the fault point and clock skew the migration instructions read from the test hooks
+    /// (admin only, `test-hooks` builds only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `fault_point` - 0: None, 1: AfterPoolCreation, 2: BeforeLiquidityLock.
+    /// * `clock_skew_seconds` - Seconds added to the clock the migration reads.
+    ///
+    #[cfg(feature = "test-hooks")]
+    pub fn set_test_hooks(
+        ctx: Context<SetTestHooksCtx>,
+        fault_point: u8,
+        clock_skew_seconds: i64,
+    ) -> Result<()> {
+        handle_set_test_hooks(ctx, fault_point, clock_skew_seconds)
+    }
+
+    /// Send the base tokens left in the vault of a migrated curve to the leftover receiver of
+    /// the config, or burn them if the config has none. Anyone can crank it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
     ///
-    pub fn create_config(
-        ctx: Context<CreateConfigCtx>,
-        config_params: ConfigParameters,
-    ) -> Result<()> {
-        handle_create_config(ctx, config_params)
+    pub fn withdraw_leftover(ctx: Context<WithdrawLeftoverCtx>) -> Result<()> {
+        handle_withdraw_leftover(ctx)
     }
 
-    /// Create a new token and bonding curve
+    /// Snapshot a migrated curve nothing is owed out of anymore into a `CurveArchive`, then
+    /// close the curve and its vaults, their rent goes back to the creator. Anyone can crank it
     ///
     /// # Arguments
     ///
     /// * `ctx` - The accounts needed by the instruction.
-    /// * `curve_params` - The parameters for the bonding curve creation.
     ///
-    pub fn create_curve_with_spl_token(
-        ctx: Context<CreateCurveCtx>,
-        curve_params: CreateCurveParams,
+    pub fn archive_curve(ctx: Context<ArchiveCurveCtx>) -> Result<()> {
+        handle_archive_curve(ctx)
+    }
+
+    /// Burn base tokens a migrated curve left in its vault, signed by the curve creator. Only for
+    /// configs without a leftover receiver
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `amount` - The base amount to burn.
+    ///
+    pub fn burn_curve_base_tokens(ctx: Context<BurnCurveBaseTokensCtx>, amount: u64) -> Result<()> {
+        handle_burn_curve_base_tokens(ctx, amount)
+    }
+
+    /// Spend the support fund of a migrated curve on a buyback in its DAMM v2 pool, the fund is
+    /// filled with a share of the migration fee at migration
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `amount_in` - The quote amount of the fund to swap.
+    /// * `minimum_amount_out` - The minimum base amount to buy back.
+    ///
+    pub fn deploy_support_fund(
+        ctx: Context<DeploySupportFundCtx>,
+        amount_in: u64,
+        minimum_amount_out: u64,
     ) -> Result<()> {
-        handle_create_curve_spl_token(ctx, curve_params)
+        handle_deploy_support_fund(ctx, amount_in, minimum_amount_out)
     }
 
-    /// Swap tokens
+    /// Claim the fees of the first position of a migrated curve whose creator takes a share of
+    /// them, split between the creator fee recipient and the fee claimer
     ///
     /// # Arguments
     ///
     /// * `ctx` - The accounts needed by the instruction.
-    /// * `params` - The parameters for the swap operation.
     ///
-    pub fn swap(ctx: Context<SwapCtx>, params: SwapParameters) -> Result<()> {
-        handle_swap(ctx, params)
+    pub fn claim_position_fee(ctx: Context<ClaimPositionFeeCtx>) -> Result<()> {
+        handle_claim_position_fee(ctx)
     }
 
-    /// migrate the bonding curve to Meteora DAMM v2
-    pub fn migrate_damm_v2<'c: 'info, 'info>(
-        ctx: Context<'_, '_, 'c, 'info, MigrateDammV2Ctx<'info>>,
+    /// Let an operator migrate a single curve until the expiry, the first position of the pool
+    /// still goes to the delegating admin. Replaces a previous delegation of the curve
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `expiry_timestamp` - The time the delegation stops being valid.
+    ///
+    pub fn delegate_migration_authority(
+        ctx: Context<DelegateMigrationAuthorityCtx>,
+        expiry_timestamp: u64,
     ) -> Result<()> {
-        handle_migrate_damm_v2(ctx)
+        handle_delegate_migration_authority(ctx, expiry_timestamp)
+    }
+
+    /// Revoke the migration delegation of a curve before it expires
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn revoke_migration_authority(ctx: Context<RevokeMigrationAuthorityCtx>) -> Result<()> {
+        handle_revoke_migration_authority(ctx)
     }
 
     /// Create a cashback account for a user
@@ -82,17 +747,77 @@ pub mod amm {
         handle_create_cashback(ctx)
     }
 
-    /// Claim accumulated cashback rewards
+    /// Create the cashback vault of a quote mint for a user's cashback account, cashback of
+    /// curves quoted in that mint accrues there
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn create_cashback_vault(ctx: Context<CreateCashbackVault>) -> Result<()> {
+        handle_create_cashback_vault(ctx)
+    }
+
+    /// Record the referrer of the signing user, once. The referrer's own chain is extended
+    /// by a level and the referral fees of the user's swaps can only go to that chain
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn set_referrer(ctx: Context<SetReferrerCtx>) -> Result<()> {
+        handle_set_referrer(ctx)
+    }
+
+    /// Claim accumulated cashback rewards of one quote mint vault
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `relayer_tip` - The amount of the claim paid to the relayer, if any.
+    ///
+    pub fn claim_cashback(ctx: Context<ClaimCashback>, relayer_tip: u64) -> Result<()> {
+        handle_claim_cashback(ctx, relayer_tip)
+    }
+
+    /// Claim the cashback accrued as lamports in the native cashback mode
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `relayer_tip` - The lamports of the claim paid to the relayer, if any.
+    ///
+    pub fn claim_native_cashback(
+        ctx: Context<ClaimNativeCashback>,
+        relayer_tip: u64,
+    ) -> Result<()> {
+        handle_claim_native_cashback(ctx, relayer_tip)
+    }
+
+    /// Close a user's cashback account and one of its vaults, refunding the rent to the user.
+    /// Cashback left in them is swept to the user once the claim cooldown allows it
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn close_cashback(ctx: Context<CloseCashback>) -> Result<()> {
+        handle_close_cashback(ctx)
+    }
+
+    /// Choose whether the cashback of WSOL quoted curves accrues in the WSOL vault or as
+    /// lamports on the cashback account
     ///
     /// # Arguments
     ///
     /// * `ctx` - The accounts needed by the instruction.
+    /// * `cashback_mode` - 0 for the WSOL vault, 1 for lamports.
     ///
-    pub fn claim_cashback(ctx: Context<ClaimCashback>) -> Result<()> {
-        handle_claim_cashback(ctx)
+    pub fn set_cashback_mode(ctx: Context<SetCashbackMode>, cashback_mode: u8) -> Result<()> {
+        handle_set_cashback_mode(ctx, cashback_mode)
     }
 
-    /// Reclaim inactive cashback (admin only)
+    /// Reclaim inactive cashback of one quote mint vault (admin only)
     ///
     /// # Arguments
     ///
@@ -102,15 +827,75 @@ pub mod amm {
         handle_reclaim_cashback(ctx)
     }
 
-    /// Update cashback tier (admin only)
+    /// Override a user's cashback tier (admin only), the next refresh recomputes it from volume
     ///
     /// # Arguments
     ///
     /// * `ctx` - The accounts needed by the instruction.
     /// * `new_tier` - The new tier to assign to the user.
+    /// * `update_seq` - Sequence number of the update, has to be above the last one. None skips
+    ///   the check.
+    ///
+    pub fn update_cashback_tier(
+        ctx: Context<UpdateCashbackTier>,
+        new_tier: u8,
+        update_seq: Option<u64>,
+    ) -> Result<()> {
+        handle_update_cashback_tier(ctx, new_tier, update_seq)
+    }
+
+    /// Set the claim cooldown, the volume threshold and the trading fee discount of every
+    /// cashback tier (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `claim_cooldown_seconds` - The seconds between two claims, indexed by tier.
+    /// * `tier_volume_thresholds` - The rolling quote volume needed for each tier, indexed by tier.
+    /// * `fee_discount_basis_points` - The trading fee discount of each tier, indexed by tier.
+    ///
+    pub fn set_cashback_config(
+        ctx: Context<SetCashbackConfig>,
+        claim_cooldown_seconds: [i64; 7],
+        tier_volume_thresholds: [u64; 7],
+        fee_discount_basis_points: [u16; 7],
+    ) -> Result<()> {
+        handle_set_cashback_config(
+            ctx,
+            claim_cooldown_seconds,
+            tier_volume_thresholds,
+            fee_discount_basis_points,
+        )
+    }
+
+    /// Grow the cashback settings to the current layout (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn realloc_cashback_config(ctx: Context<ReallocCashbackConfig>) -> Result<()> {
+        handle_realloc_cashback_config(ctx)
+    }
+
+    /// Set a user's cashback tier to the one their rolling 30 day volume reaches (permissionless)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
     ///
-    pub fn update_cashback_tier(ctx: Context<UpdateCashbackTier>, new_tier: u8) -> Result<()> {
-        handle_update_cashback_tier(ctx, new_tier)
+    pub fn refresh_cashback_tier(ctx: Context<RefreshCashbackTier>) -> Result<()> {
+        handle_refresh_cashback_tier(ctx)
+    }
+
+    /// Drop an inactive user's cashback tier by one level (permissionless)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn decay_cashback_tier(ctx: Context<DecayCashbackTier>) -> Result<()> {
+        handle_decay_cashback_tier(ctx)
     }
 
     /// Claim protocol fee from the bonding curve
@@ -123,7 +908,40 @@ pub mod amm {
         handle_claim_protocol_fee(ctx)
     }
 
-    /// Claim creator fee from the bonding curve
+    /// Claim protocol fee from many bonding curves of a config in one call
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction, the curves and their quote vaults as
+    ///   `[curve, quote_vault]` pairs of remaining accounts.
+    ///
+    pub fn claim_protocol_fee_batch<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ClaimProtocolFeeBatchCtx<'info>>,
+    ) -> Result<()> {
+        handle_claim_protocol_fee_batch(ctx)
+    }
+
+    /// Claim the quote migration fee of a migrated bonding curve, apart from the trading fees
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn claim_migration_fee(ctx: Context<ClaimMigrationFeeCtx>) -> Result<()> {
+        handle_claim_migration_fee(ctx)
+    }
+
+    /// Claim the meme fee of a bonding curve for the community (fee type admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn claim_meme_fee(ctx: Context<ClaimMemeFeeCtx>) -> Result<()> {
+        handle_claim_meme_fee(ctx)
+    }
+
+    /// Claim creator fee from the bonding curve, signed by the creator or its fee recipient
     ///
     /// # Arguments
     ///
@@ -132,4 +950,201 @@ pub mod amm {
     pub fn claim_creator_fee(ctx: Context<ClaimCreatorFeeCtx>) -> Result<()> {
         handle_claim_creator_fee(ctx)
     }
+
+    /// Have the creator fee of a bonding curve claimed to another wallet
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `recipient` - The wallet receiving the creator fee, the default pubkey for the creator.
+    ///
+    pub fn set_creator_fee_recipient(
+        ctx: Context<SetCreatorFeeRecipientCtx>,
+        recipient: Pubkey,
+    ) -> Result<()> {
+        handle_set_creator_fee_recipient(ctx, recipient)
+    }
+
+    /// Hand the creator role of a bonding curve over to a new key
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `creator_fee_transfer_mode` - 0: the accrued creator fee moves with the role, 1: it's claimed to the old creator first.
+    ///
+    pub fn transfer_creator(
+        ctx: Context<TransferCreatorCtx>,
+        creator_fee_transfer_mode: u8,
+    ) -> Result<()> {
+        handle_transfer_creator(ctx, creator_fee_transfer_mode)
+    }
+
+    /// Escrow the input of a limit order, swapped once the curve price reaches the target
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `params` - The order id, direction, input, target price and filler reward.
+    ///
+    pub fn place_limit_order(
+        ctx: Context<PlaceLimitOrderCtx>,
+        params: PlaceLimitOrderParameters,
+    ) -> Result<()> {
+        handle_place_limit_order(ctx, params)
+    }
+
+    /// Cancel a limit order and take its escrow back (owner only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn cancel_limit_order(ctx: Context<CancelLimitOrderCtx>) -> Result<()> {
+        handle_cancel_limit_order(ctx)
+    }
+
+    /// Fill a limit order whose target price the curve reached, for the filler reward
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn fill_limit_order(ctx: Context<FillLimitOrderCtx>) -> Result<()> {
+        handle_fill_limit_order(ctx)
+    }
+
+    /// Create a protection account for a user on a bonding curve
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn create_protection(ctx: Context<CreateProtectionCtx>) -> Result<()> {
+        handle_create_protection(ctx)
+    }
+
+    /// Deposit quote into the early buyer bonus pool (creator only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `amount` - The amount of quote token to deposit.
+    ///
+    pub fn deposit_bonus_pool(ctx: Context<DepositBonusPoolCtx>, amount: u64) -> Result<()> {
+        handle_deposit_bonus_pool(ctx, amount)
+    }
+
+    /// Claim the pro-rata early buyer bonus after the curve graduates
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn claim_bonus(ctx: Context<ClaimBonusCtx>) -> Result<()> {
+        handle_claim_bonus(ctx)
+    }
+
+    /// Withdraw the bonus pool when no early buyer qualified (creator only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn withdraw_bonus_pool(ctx: Context<WithdrawBonusPoolCtx>) -> Result<()> {
+        handle_withdraw_bonus_pool(ctx)
+    }
+
+    /// Open a launch raffle on a curve that hasn't traded yet, trading waits for its draw
+    /// (creator only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `params` - The ticket price, winners, tickets per wallet and end of the ticket sales.
+    ///
+    pub fn create_raffle(ctx: Context<CreateRaffleCtx>, params: CreateRaffleParams) -> Result<()> {
+        handle_create_raffle(ctx, params)
+    }
+
+    /// Buy the tickets of a wallet in a launch raffle, once per wallet
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `ticket_count` - The number of tickets to buy.
+    ///
+    pub fn buy_raffle_tickets(ctx: Context<BuyRaffleTicketsCtx>, ticket_count: u64) -> Result<()> {
+        handle_buy_raffle_tickets(ctx, ticket_count)
+    }
+
+    /// Commit the Switchboard randomness an oversubscribed raffle is drawn with (creator only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn commit_raffle_randomness(ctx: Context<CommitRaffleRandomnessCtx>) -> Result<()> {
+        handle_commit_raffle_randomness(ctx)
+    }
+
+    /// Draw a launch raffle, buy the allocation of the winning tickets and open trading
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn draw_raffle(ctx: Context<DrawRaffleCtx>) -> Result<()> {
+        handle_draw_raffle(ctx)
+    }
+
+    /// Claim the allocation of the winning tickets and the refund of the others
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    ///
+    pub fn claim_raffle(ctx: Context<ClaimRaffleCtx>) -> Result<()> {
+        handle_claim_raffle(ctx)
+    }
+
+    /// Fund the holder rewards of a curve (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `amount` - The amount of reward token to deposit.
+    ///
+    pub fn fund_reward_vault(ctx: Context<FundRewardVaultCtx>, amount: u64) -> Result<()> {
+        handle_fund_reward_vault(ctx, amount)
+    }
+
+    /// Set the merkle root of the holder reward distribution of a graduated curve (admin only)
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `merkle_root` - Root over the `(holder, amount)` leaves of the holder snapshot.
+    ///
+    pub fn set_reward_distribution(
+        ctx: Context<SetRewardDistributionCtx>,
+        merkle_root: [u8; 32],
+    ) -> Result<()> {
+        handle_set_reward_distribution(ctx, merkle_root)
+    }
+
+    /// Claim a holder reward from the distribution of a graduated curve
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `amount` - The amount of reward token of the holder's leaf.
+    /// * `proof` - Merkle proof of the holder's leaf.
+    ///
+    pub fn claim_reward(
+        ctx: Context<ClaimRewardCtx>,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        handle_claim_reward(ctx, amount, proof)
+    }
 }
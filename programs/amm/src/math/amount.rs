@@ -0,0 +1,90 @@
+use crate::{
+    safe_math::{safe_mul_div_cast_u64, SafeMath},
+    u128x128_math::Rounding,
+    AmmError,
+};
+
+macro_rules! amount_impl {
+    ($name:ident, $token:literal) => {
+        #[doc = concat!("An amount of ", $token, " token, in raw units (with decimals)")]
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+        pub struct $name(pub u64);
+
+        impl $name {
+            pub const ZERO: Self = Self(0);
+
+            /// Raw value, only to be used at the account/instruction boundary
+            #[inline(always)]
+            pub fn get(self) -> u64 {
+                self.0
+            }
+
+            #[inline(always)]
+            pub fn safe_add(self, rhs: Self) -> Result<Self, AmmError> {
+                Ok(Self(self.0.safe_add(rhs.0)?))
+            }
+
+            #[inline(always)]
+            pub fn safe_sub(self, rhs: Self) -> Result<Self, AmmError> {
+                Ok(Self(self.0.safe_sub(rhs.0)?))
+            }
+
+            /// (self * numerator) / denominator, eg: a fee in bps of the amount
+            #[inline(always)]
+            pub fn safe_mul_div(
+                self,
+                numerator: u64,
+                denominator: u64,
+                rounding: Rounding,
+            ) -> anchor_lang::Result<Self> {
+                Ok(Self(safe_mul_div_cast_u64(
+                    self.0,
+                    numerator,
+                    denominator,
+                    rounding,
+                )?))
+            }
+        }
+
+        impl From<$name> for u64 {
+            fn from(amount: $name) -> u64 {
+                amount.0
+            }
+        }
+    };
+}
+
+amount_impl!(QuoteAmount, "quote");
+amount_impl!(BaseAmount, "base");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safe_add_and_sub() {
+        assert_eq!(
+            QuoteAmount(100).safe_add(QuoteAmount(100)),
+            Ok(QuoteAmount(200))
+        );
+        assert_eq!(
+            BaseAmount(200).safe_sub(BaseAmount(100)),
+            Ok(BaseAmount(100))
+        );
+        assert!(QuoteAmount(u64::MAX).safe_add(QuoteAmount(1)).is_err());
+        assert!(BaseAmount(0).safe_sub(BaseAmount(1)).is_err());
+    }
+
+    #[test]
+    fn safe_mul_div() {
+        let amount = QuoteAmount(1_000_000);
+        assert_eq!(
+            amount.safe_mul_div(1_000, 100_000, Rounding::Down).unwrap(),
+            QuoteAmount(10_000)
+        );
+        assert_eq!(
+            QuoteAmount(1).safe_mul_div(1, 3, Rounding::Up).unwrap(),
+            QuoteAmount(1)
+        );
+    }
+}
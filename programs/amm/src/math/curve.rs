@@ -0,0 +1,281 @@
+//! Liquidity math for the DAMM v2 pool created at migration
+//!
+//! The whole migrated amount is deposited as a single position over the full price range
+//! `[MIN_SQRT_PRICE, MAX_SQRT_PRICE]`, sqrt prices are Q64.64.
+//!
+//! Bounds, with `amount < 2^64` and `MIN_SQRT_PRICE < sqrt_price < MAX_SQRT_PRICE < 2^96`:
+//! - liquidity from a: `amount * sqrt_price * sqrt_max_price < 2^64 * 2^96 * 2^96 = 2^256`
+//! - liquidity from b: `amount << 128 < 2^192`
+//!
+//! Both fit in a U512 with room to spare, so the intermediates can't overflow. Each side on its
+//! own can exceed u128 at extreme prices (a near the max price, b near the min price), only the
+//! smaller of the two is the pool liquidity and it is narrowed to u128 once, at the end.
+
+use crate::{
+    constants::{MAX_SQRT_PRICE, MIN_SQRT_PRICE},
+    safe_math::SafeMath,
+    u128x128_math::Rounding,
+    AmmError,
+};
+use ruint::aliases::U512;
+
+/// Liquidity of a full range position holding `token_a_amount` and `token_b_amount` at `sqrt_price`.
+///
+/// Rounds down, so the pool never owes the position more than was deposited.
+pub fn get_liquidity_for_adding_liquidity(
+    token_a_amount: u64,
+    token_b_amount: u64,
+    sqrt_price: u128,
+) -> Result<u128, AmmError> {
+    if sqrt_price <= MIN_SQRT_PRICE || sqrt_price >= MAX_SQRT_PRICE {
+        return Err(AmmError::SqrtPriceOutOfRange);
+    }
+
+    let liquidity_from_a = get_initial_liquidity_from_delta_a(
+        token_a_amount,
+        MAX_SQRT_PRICE,
+        sqrt_price,
+        Rounding::Down,
+    )?;
+    let liquidity_from_b = get_initial_liquidity_from_delta_b(
+        token_b_amount,
+        MIN_SQRT_PRICE,
+        sqrt_price,
+        Rounding::Down,
+    )?;
+    let liquidity = liquidity_from_a.min(liquidity_from_b);
+
+    // a side rounded away entirely, the position would be created empty
+    if liquidity == U512::ZERO {
+        return Err(AmmError::LiquidityPrecisionLoss);
+    }
+    liquidity
+        .try_into()
+        .map_err(|_| AmmError::LiquidityOverflow)
+}
+
+/// Δa = L * (1 / √P_lower - 1 / √P_upper) => L = Δa * √P_lower * √P_upper / (√P_upper - √P_lower)
+pub fn get_initial_liquidity_from_delta_a(
+    token_a_amount: u64,
+    sqrt_max_price: u128,
+    sqrt_price: u128,
+    rounding: Rounding,
+) -> Result<U512, AmmError> {
+    let price_delta = U512::from(sqrt_max_price.safe_sub(sqrt_price)?);
+    let prod = U512::from(token_a_amount)
+        .safe_mul(U512::from(sqrt_price))?
+        .safe_mul(U512::from(sqrt_max_price))?;
+    div_with_rounding(prod, price_delta, rounding)
+}
+
+/// Δb = L * (√P_upper - √P_lower) => L = Δb / (√P_upper - √P_lower)
+pub fn get_initial_liquidity_from_delta_b(
+    token_b_amount: u64,
+    sqrt_min_price: u128,
+    sqrt_price: u128,
+    rounding: Rounding,
+) -> Result<U512, AmmError> {
+    let price_delta = U512::from(sqrt_price.safe_sub(sqrt_min_price)?);
+    let token_b_amount = U512::from(token_b_amount).safe_shl(128)?;
+    div_with_rounding(token_b_amount, price_delta, rounding)
+}
+
+fn div_with_rounding(
+    numerator: U512,
+    denominator: U512,
+    rounding: Rounding,
+) -> Result<U512, AmmError> {
+    if denominator == U512::ZERO {
+        return Err(AmmError::SqrtPriceOutOfRange);
+    }
+    Ok(match rounding {
+        Rounding::Up => numerator.div_ceil(denominator),
+        Rounding::Down => numerator.safe_div(denominator)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_Q64: u128 = 1 << 64;
+
+    const SQRT_PRICES: [u128; 7] = [
+        MIN_SQRT_PRICE + 1,
+        MIN_SQRT_PRICE * 2,
+        1 << 48,
+        ONE_Q64, // price 1
+        1 << 80,
+        MAX_SQRT_PRICE / 2,
+        MAX_SQRT_PRICE - 1,
+    ];
+
+    const AMOUNTS: [u64; 5] = [1, 1_000, 1_000_000_000, 1 << 48, u64::MAX];
+
+    // Δa = L * (√P_upper - √P_lower) / (√P_lower * √P_upper), rounded up
+    fn token_a_for_liquidity(liquidity: u128, sqrt_price: u128) -> U512 {
+        let num = U512::from(liquidity) * U512::from(MAX_SQRT_PRICE - sqrt_price);
+        num.div_ceil(U512::from(sqrt_price) * U512::from(MAX_SQRT_PRICE))
+    }
+
+    // Δb = L * (√P_upper - √P_lower) >> 128, rounded up
+    fn token_b_for_liquidity(liquidity: u128, sqrt_price: u128) -> U512 {
+        let num = U512::from(liquidity) * U512::from(sqrt_price - MIN_SQRT_PRICE);
+        num.div_ceil(U512::from(1u8) << 128)
+    }
+
+    #[test]
+    fn liquidity_never_needs_more_than_deposited() {
+        for sqrt_price in SQRT_PRICES {
+            for token_a_amount in AMOUNTS {
+                for token_b_amount in AMOUNTS {
+                    let liquidity = match get_liquidity_for_adding_liquidity(
+                        token_a_amount,
+                        token_b_amount,
+                        sqrt_price,
+                    ) {
+                        Ok(liquidity) => liquidity,
+                        Err(err) => {
+                            assert_eq!(err, AmmError::LiquidityOverflow);
+                            continue;
+                        }
+                    };
+                    assert!(liquidity > 0);
+                    assert!(
+                        token_a_for_liquidity(liquidity, sqrt_price) <= U512::from(token_a_amount)
+                    );
+                    assert!(
+                        token_b_for_liquidity(liquidity, sqrt_price) <= U512::from(token_b_amount)
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn liquidity_is_bounded_by_the_smaller_side() {
+        for sqrt_price in SQRT_PRICES {
+            let from_a = get_initial_liquidity_from_delta_a(
+                u64::MAX,
+                MAX_SQRT_PRICE,
+                sqrt_price,
+                Rounding::Down,
+            )
+            .unwrap();
+            let from_b = get_initial_liquidity_from_delta_b(
+                u64::MAX,
+                MIN_SQRT_PRICE,
+                sqrt_price,
+                Rounding::Down,
+            )
+            .unwrap();
+            // each side alone stays well inside U512
+            assert!(from_a < U512::from(1u8) << 256);
+            assert!(from_b < U512::from(1u8) << 192);
+
+            match get_liquidity_for_adding_liquidity(u64::MAX, u64::MAX, sqrt_price) {
+                Ok(liquidity) => assert_eq!(U512::from(liquidity), from_a.min(from_b)),
+                Err(err) => {
+                    assert_eq!(err, AmmError::LiquidityOverflow);
+                    assert!(from_a.min(from_b) > U512::from(u128::MAX));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn one_sided_overflow_takes_the_other_side() {
+        // near the min price, b alone is far above u128 but a is small
+        let sqrt_price = MIN_SQRT_PRICE + 1;
+        let from_b = get_initial_liquidity_from_delta_b(
+            u64::MAX,
+            MIN_SQRT_PRICE,
+            sqrt_price,
+            Rounding::Down,
+        )
+        .unwrap();
+        assert!(from_b > U512::from(u128::MAX));
+        assert!(get_liquidity_for_adding_liquidity(1_000_000, u64::MAX, sqrt_price).is_ok());
+
+        // near the max price, it's the other way around
+        let sqrt_price = MAX_SQRT_PRICE - 1;
+        let from_a = get_initial_liquidity_from_delta_a(
+            u64::MAX,
+            MAX_SQRT_PRICE,
+            sqrt_price,
+            Rounding::Down,
+        )
+        .unwrap();
+        assert!(from_a > U512::from(u128::MAX));
+        assert!(get_liquidity_for_adding_liquidity(u64::MAX, 1_000_000, sqrt_price).is_ok());
+    }
+
+    #[test]
+    fn both_sides_at_max_overflow_u128() {
+        assert_eq!(
+            get_liquidity_for_adding_liquidity(u64::MAX, u64::MAX, ONE_Q64),
+            Err(AmmError::LiquidityOverflow)
+        );
+    }
+
+    #[test]
+    fn rounding_direction() {
+        for sqrt_price in SQRT_PRICES {
+            for amount in AMOUNTS {
+                let a_down = get_initial_liquidity_from_delta_a(
+                    amount,
+                    MAX_SQRT_PRICE,
+                    sqrt_price,
+                    Rounding::Down,
+                )
+                .unwrap();
+                let a_up = get_initial_liquidity_from_delta_a(
+                    amount,
+                    MAX_SQRT_PRICE,
+                    sqrt_price,
+                    Rounding::Up,
+                )
+                .unwrap();
+                assert!(a_up >= a_down && a_up - a_down <= U512::from(1u8));
+
+                let b_down = get_initial_liquidity_from_delta_b(
+                    amount,
+                    MIN_SQRT_PRICE,
+                    sqrt_price,
+                    Rounding::Down,
+                )
+                .unwrap();
+                let b_up = get_initial_liquidity_from_delta_b(
+                    amount,
+                    MIN_SQRT_PRICE,
+                    sqrt_price,
+                    Rounding::Up,
+                )
+                .unwrap();
+                assert!(b_up >= b_down && b_up - b_down <= U512::from(1u8));
+            }
+        }
+    }
+
+    #[test]
+    fn zero_amount_is_precision_loss() {
+        assert_eq!(
+            get_liquidity_for_adding_liquidity(0, 1_000_000, ONE_Q64),
+            Err(AmmError::LiquidityPrecisionLoss)
+        );
+        assert_eq!(
+            get_liquidity_for_adding_liquidity(1_000_000, 0, ONE_Q64),
+            Err(AmmError::LiquidityPrecisionLoss)
+        );
+    }
+
+    #[test]
+    fn sqrt_price_out_of_range() {
+        for sqrt_price in [0, MIN_SQRT_PRICE, MAX_SQRT_PRICE, u128::MAX] {
+            assert_eq!(
+                get_liquidity_for_adding_liquidity(1_000_000, 1_000_000, sqrt_price),
+                Err(AmmError::SqrtPriceOutOfRange)
+            );
+        }
+    }
+}
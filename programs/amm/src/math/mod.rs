@@ -1,5 +1,9 @@
 //! Math module
 
+/// quote and base amount newtypes
+pub mod amount;
+/// migration liquidity math
+pub mod curve;
 /// safe math
 pub mod safe_math;
 /// u128x128 math
@@ -0,0 +1,186 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::vesting::MAX_LOCKED_VESTING_DURATION_SECONDS, errors::AmmError, safe_math::SafeMath,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Debug)]
+pub struct ConfigParameters {
+    /* Token configurations */
+    /// token type (0 | 1), 0: SPL Token, 1: Token2022
+    pub base_token_flag: u8,
+    /// token decimal, (6 | 9)
+    pub base_decimal: u8,
+
+    /* Fee configurations */
+    /// Trading fee in bps
+    pub fee_basis_points: u16,
+    /// Level 1 referral fee in bps
+    pub l1_referral_fee_basis_points: u16,
+    /// Level 2 referral fee in bps
+    pub l2_referral_fee_basis_points: u16,
+    /// Level 3 referral fee in bps
+    pub l3_referral_fee_basis_points: u16,
+    /// Referee discount in bps
+    pub referee_discount_basis_points: u16,
+    /// creator fee in bps
+    pub creator_fee_basis_points: u16,
+    /// migration fee in bps (quote token fee)
+    pub migration_fee_basis_points: u16,
+    /// share of the protocol fee routed to the insurance fund, in bps
+    pub insurance_fund_basis_points: u16,
+    /// protocol's cut of creator fee claims, in bps
+    pub creator_fee_protocol_basis_points: u16,
+    /// share of the migration fee routed to the curve's support fund, in bps
+    pub support_fund_basis_points: u16,
+    /// highest share of the DAMM position fees a curve creator can pick, in bps
+    pub max_creator_lp_fee_share_basis_points: u16,
+    /// share of the migrated liquidity put in an unlocked position of the curve creator, in bps
+    pub creator_lp_basis_points: u16,
+    /// share of the tier cashback paid on buys, in bps (100_000 pays the full tier cashback)
+    pub buy_cashback_multiplier_basis_points: u32,
+    /// share of the tier cashback paid on sells, in bps (0 pays no cashback on sells)
+    pub sell_cashback_multiplier_basis_points: u32,
+    /// side of a swap the trading fee is taken from (0: AlwaysOnQuote, 1: FeeOnInput, 2: FeeOnOutput)
+    pub fee_collection_mode: u8,
+    /// reserves the migration fee is kept from (0: BothSides, 1: QuoteOnly)
+    pub migration_fee_mode: u8,
+    /// fee the rounding remainder of a fee split goes to (0: Protocol, 1: Cashback)
+    pub fee_remainder_mode: u8,
+
+    /* Price configurations */
+    /// migration base threshold (the amount of token to migrate)
+    pub migration_base_threshold: u64,
+    /// migration quote threshold
+    pub migration_quote_threshold: u64,
+    /// initial virtual quote reserve to boost the initial liquidity
+    pub initial_virtual_quote_reserve: u64,
+    /// initial virtual base reserve to boost the initial liquidity
+    pub initial_virtual_base_reserve: u64,
+
+    /* Migration configurations */
+    /// seconds to wait after the curve completes before it can be migrated
+    pub pre_migration_cooldown_seconds: u64,
+    /// whether anyone can crank the migration of a complete curve (0 | 1)
+    pub permissionless_migration: u8,
+    /// quote paid from the curve's protocol fee to whoever cranks a permissionless migration
+    pub migration_crank_reward: u64,
+    /// quote fee the creator is guaranteed by graduation, shortfalls are topped up from the
+    /// migration fee. 0 disables the guarantee
+    pub min_creator_fee_amount: u64,
+    /// mint order of the migrated pool (0 | 1), 0: base/quote, 1: sorted by key
+    pub migration_token_order: u8,
+    /// fee of the buy leg of token to token swaps into this config's curves (0: Charged, 1: Waived)
+    pub routed_swap_fee_mode: u8,
+    /// migration quote threshold decay mode (0: None, 1: Linear, 2: Stepwise)
+    pub threshold_decay_mode: u8,
+    /// floor of a decaying migration quote threshold, in bps of the threshold
+    pub threshold_decay_floor_basis_points: u16,
+    /// seconds after curve creation for the migration quote threshold to decay to its floor
+    pub threshold_decay_seconds: u64,
+
+    /* Bonus configurations */
+    /// seconds after curve creation during which buys are eligible for the creator bonus pool
+    pub early_buyer_window_seconds: u64,
+
+    /* Protection configurations */
+    /// seconds a wallet has to hold after a buy before it can sell, 0 disables the lockup
+    pub min_hold_seconds: u64,
+    /// slots after curve creation during which buys of a wallet are capped, 0 disables the cap
+    pub anti_snipe_window_slots: u64,
+    /// max quote amount a wallet can spend on buys during the anti-snipe window
+    pub max_buy_amount_first_window: u64,
+    /// trading fee in bps of a freshly launched curve, above `fee_basis_points`. 0 disables it
+    pub launch_fee_basis_points: u16,
+    /// number of equal steps the launch fee decays in, 0 decays it linearly
+    pub launch_fee_periods: u16,
+    /// seconds after a curve's launch for the launch fee to decay to `fee_basis_points`
+    pub launch_fee_decay_seconds: u64,
+
+    /* Governance configurations */
+    /// minimum delay between proposing and executing a fee or migration parameter change
+    pub param_change_delay_seconds: u64,
+
+    /* Vesting configurations */
+    /// vesting of the creator's locked base tokens
+    pub locked_vesting: LockedVestingParams,
+}
+
+/// Vesting of the creator's locked base tokens, all zeros when the config has no vesting
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct LockedVestingParams {
+    /// seconds after migration before the first period unlocks
+    pub cliff_duration_seconds: u64,
+    /// seconds between two unlocks
+    pub period_seconds: u64,
+    /// base amount unlocked every period
+    pub amount_per_period: u64,
+    /// number of unlocks
+    pub number_of_periods: u64,
+}
+
+impl LockedVestingParams {
+    pub fn is_enabled(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Total base amount locked by the vesting
+    pub fn get_total_amount(&self) -> Result<u64> {
+        Ok(self.amount_per_period.safe_mul(self.number_of_periods)?)
+    }
+
+    pub fn validate(&self, token_total_supply: u64, migration_base_threshold: u64) -> Result<()> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        require!(
+            self.period_seconds > 0 && self.amount_per_period > 0 && self.number_of_periods > 0,
+            AmmError::InvalidAmmConfig
+        );
+
+        let duration = self
+            .period_seconds
+            .safe_mul(self.number_of_periods)?
+            .safe_add(self.cliff_duration_seconds)?;
+        require!(
+            duration <= MAX_LOCKED_VESTING_DURATION_SECONDS,
+            AmmError::InvalidAmmConfig
+        );
+
+        // the locked tokens come out of the supply, the curve still needs its migration amount
+        require!(
+            self.get_total_amount()? < token_total_supply.safe_sub(migration_base_threshold)?,
+            AmmError::InvalidAmmConfig
+        );
+        Ok(())
+    }
+}
+
+/// Fee and migration parameters that can only change through the propose/execute timelock
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy)]
+pub struct ConfigChangeParameters {
+    /* Fee configurations */
+    /// Trading fee in bps
+    pub fee_basis_points: u16,
+    /// Level 1 referral fee in bps
+    pub l1_referral_fee_basis_points: u16,
+    /// Level 2 referral fee in bps
+    pub l2_referral_fee_basis_points: u16,
+    /// Level 3 referral fee in bps
+    pub l3_referral_fee_basis_points: u16,
+    /// Referee discount in bps
+    pub referee_discount_basis_points: u16,
+    /// creator fee in bps
+    pub creator_fee_basis_points: u16,
+    /// migration fee in bps (quote token fee)
+    pub migration_fee_basis_points: u16,
+
+    /* Migration configurations */
+    /// migration base threshold (the amount of token to migrate)
+    pub migration_base_threshold: u64,
+    /// migration quote threshold
+    pub migration_quote_threshold: u64,
+    /// seconds to wait after the curve completes before it can be migrated
+    pub pre_migration_cooldown_seconds: u64,
+}
@@ -1,2 +1,3 @@
+pub mod config;
 pub mod liquidity_distribution;
 pub mod swap;
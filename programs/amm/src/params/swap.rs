@@ -9,3 +9,13 @@ pub enum TradeDirection {
     /// Input base token, output quote token
     QuoteToBase,
 }
+
+/// Side of the swap the trader fixes
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, IntoPrimitive, TryFromPrimitive)]
+pub enum SwapMode {
+    /// Fixed input, with a minimum output
+    ExactIn,
+    /// Fixed output, with a maximum input
+    ExactOut,
+}
@@ -1,15 +1,20 @@
-use crate::constants::fee::FEE_DENOMINATOR;
-use crate::events::EvtInitializeCurve;
+use crate::constants::{
+    curve::{CURVE_VERSION, TRADING_END_SELL_GRACE_SECONDS},
+    fee::FEE_DENOMINATOR,
+};
+use crate::events::{EvtInitializeCurve, EvtVerifyCurveInvariants};
 use crate::safe_math::safe_mul_div_cast_u64;
-use crate::u128x128_math::Rounding;
+use crate::u128x128_math::{mul_div_u256, Rounding};
 use crate::{
+    amount::{BaseAmount, QuoteAmount},
     params::swap::TradeDirection,
     safe_math::SafeMath,
-    states::{CashbackTier, Config},
+    states::{CashbackFeeTier, Config, FeeBreakdown, MigrationFeeMode},
     AmmError,
 };
 use anchor_lang::prelude::*;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use ruint::aliases::U256;
 
 /// Represents the result of checking graduation status
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -53,6 +58,100 @@ pub enum MigrationStatus {
     CreatedPool,
 }
 
+/// Which swap directions are halted, set on a config (all of its curves) or on a single curve
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum TradingPause {
+    #[default]
+    None,
+    /// QuoteToBase is halted, holders can still exit
+    Buys,
+    /// BaseToQuote is halted
+    Sells,
+    All,
+}
+
+impl TradingPause {
+    pub fn is_paused(&self, trade_direction: TradeDirection) -> bool {
+        match self {
+            TradingPause::None => false,
+            TradingPause::Buys => trade_direction == TradeDirection::QuoteToBase,
+            TradingPause::Sells => trade_direction == TradeDirection::BaseToQuote,
+            TradingPause::All => true,
+        }
+    }
+}
+
+/// What happens to the accrued, unclaimed creator fee when the creator role is transferred
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum CreatorFeeTransferMode {
+    /// the accrued fee stays on the curve and becomes claimable by the new creator
+    MoveWithRole,
+    /// the accrued fee is paid out to the old creator before the role moves
+    ClaimToOldCreator,
+}
+
+/// Unit of the activation point of a curve
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum ActivationType {
+    #[default]
+    Timestamp,
+    Slot,
+}
+
+/// Who the creator part of the trading fee accrues to
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum FeeType {
+    /// accrues to `creator_fee`, claimed by the creator with `claim_creator_fee`
+    #[default]
+    Creator,
+    /// accrues to `meme_fee`, claimed by the fee type admin for the community with
+    /// `claim_meme_fee`
+    Meme,
+}
+
 #[account(zero_copy)]
 #[derive(InitSpace, Debug, Default)]
 pub struct BondingCurve {
@@ -80,29 +179,130 @@ pub struct BondingCurve {
     pub is_migrated: u8,
     /// migration status enum (0: PreBondingCurve, 1: PostBondingCurve, 2: CreatedPool)
     pub migration_status: u8,
-    /// padding 1
-    pub _padding_1: [u8; 5],
+    /// trading pause of this curve (0: None, 1: Buys, 2: Sells, 3: All)
+    pub trading_pause: u8,
+    /// whether the base mint is also listed on a curve of another config (0 | 1)
+    pub is_dual_listed: u8,
+    /// whether trading waits for `finalize_curve_metadata` of a curve created with `init_curve`
+    /// (0 | 1)
+    pub is_metadata_pending: u8,
+    /// emergency pause of this curve (0 | 1), halts every swap of the curve
+    pub paused: u8,
+    /// whether trading waits for the draw of the curve's launch raffle (0 | 1)
+    pub is_raffle_pending: u8,
     /// The time curve is finished
     pub curve_finish_timestamp: u64,
     /// The protocol fee
     pub protocol_fee: u64,
     /// The creator/meme fee reserve
     pub creator_fee: u64,
+    /// The time curve is created
+    pub creation_timestamp: u64,
+    /// approximate number of unique holders, counted on base balance 0 -> >0 and >0 -> 0 transitions
+    pub holder_count: u64,
+    /// seconds a wallet has to hold after a buy before it can sell, 0 disables the lockup
+    pub min_hold_seconds: u64,
+    /// The slot curve is created
+    pub creation_slot: u64,
+    /// creator fee accrued over the life of the curve, claims don't reduce it
+    pub total_creator_fee: u64,
+    /// quote traded on the curve, fees included
+    pub total_quote_volume: u64,
+    /// number of buys, the initial buy at creation included
+    pub buy_count: u64,
+    /// number of sells
+    pub sell_count: u64,
+    /// approximate number of unique traders, counted on base balance 0 -> >0 transitions, so a
+    /// wallet that sells out and buys back is counted again
+    pub unique_trader_estimate: u64,
+    /// The time of the last trade
+    pub last_trade_timestamp: u64,
+    /// receiver of the creator fee set by the creator, the default pubkey pays the creator
+    pub creator_fee_recipient: Pubkey,
+    /// creator's share of the DAMM position fees once migrated, in bps of `FEE_DENOMINATOR`,
+    /// picked at creation within the config's bound
+    pub creator_lp_fee_share_basis_points: u16,
+    /// layout version of the account, see `CURVE_VERSION`. 0 for curves created before it
+    pub version: u8,
+    /// fee type enum (0: Creator, 1: Meme), where the creator part of the trading fee accrues
+    pub fee_type: u8,
+    /// activation type enum (0: Timestamp, 1: Slot), the unit of `activation_point`
+    pub activation_type: u8,
+    /// padding 2
+    pub _padding_2: u8,
+    /// partner's share of the protocol fee in bps of `FEE_DENOMINATOR`, taken from the partner
+    /// registry at creation
+    pub partner_fee_share_basis_points: u16,
+    /// quote of the migration fee paid to the fee claimer at migration
+    pub migration_quote_fee: u64,
+    /// base of the migration fee paid to the fee claimer at migration
+    pub migration_base_fee: u64,
+    /// quote of the migration fee kept in the quote vault for `claim_migration_fee`, apart from
+    /// the trading fees
+    pub migration_fee_collected: u64,
+    /// The meme fee reserve, the creator part of the trading fee accrued while the fee type is
+    /// `Meme`
+    pub meme_fee: u64,
+    /// time buys close, sells stay open for `TRADING_END_SELL_GRACE_SECONDS` after it. 0 for
+    /// curves that trade until migration
+    pub trading_end_timestamp: u64,
+    /// timestamp or slot, per `activation_type`, trading opens at. 0 for curves that trade from
+    /// creation. The launch windows of the same unit start from it instead of the creation
+    pub activation_point: u64,
+    /// limit orders placed on the curve that are neither filled nor cancelled, their cancellation
+    /// needs the curve
+    pub open_limit_order_count: u64,
+    /// partner frontend the curve was created through, all zeros for curves without one
+    pub partner: Pubkey,
+    /// migration base threshold of a dual listed curve, scaled to its share of the supply. 0 for
+    /// curves on the threshold of their config
+    pub migration_base_threshold: u64,
+    /// migration quote threshold of a dual listed curve, scaled like `migration_base_threshold`
+    pub migration_quote_threshold: u64,
+}
+
+/// Accounts, reserves and creation time of a new curve, see `BondingCurve::init`
+#[derive(Default)]
+pub struct CurveInitParams {
+    pub config: Pubkey,
+    pub creator: Pubkey,
+    pub base_mint: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub curve_type: u8,
+    pub base_reserve: u64,
+    pub virtual_quote_reserve: u64,
+    pub virtual_base_reserve: u64,
+    pub creation_timestamp: u64,
+    pub creation_slot: u64,
+    pub min_hold_seconds: u64,
+}
+
+/// Token metadata and starting reserves of a new curve, see `BondingCurve::event`
+pub struct CurveEventParams {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub initial_virtual_quote_reserve: u64,
+    pub initial_virtual_base_reserve: u64,
 }
 
 impl BondingCurve {
-    pub fn init(
-        &mut self,
-        config: Pubkey,
-        creator: Pubkey,
-        base_mint: Pubkey,
-        base_vault: Pubkey,
-        quote_vault: Pubkey,
-        curve_type: u8,
-        base_reserve: u64,
-        virtual_quote_reserve: u64,
-        virtual_base_reserve: u64,
-    ) {
+    pub fn init(&mut self, params: CurveInitParams) {
+        let CurveInitParams {
+            config,
+            creator,
+            base_mint,
+            base_vault,
+            quote_vault,
+            curve_type,
+            base_reserve,
+            virtual_quote_reserve,
+            virtual_base_reserve,
+            creation_timestamp,
+            creation_slot,
+            min_hold_seconds,
+        } = params;
         self.config = config;
         self.creator = creator;
         self.base_mint = base_mint;
@@ -112,308 +312,2469 @@ impl BondingCurve {
         self.base_reserve = base_reserve;
         self.virtual_quote_reserve = virtual_quote_reserve;
         self.virtual_base_reserve = virtual_base_reserve;
+        self.creation_timestamp = creation_timestamp;
+        self.creation_slot = creation_slot;
+        self.min_hold_seconds = min_hold_seconds;
+        self.version = CURVE_VERSION;
     }
 
-    pub fn get_swap_result(
-        &self,
-        config: &Config,
-        amount_in: u64,
-        trade_direction: TradeDirection,
-        has_l1_referral: bool,
-        has_l2_referral: bool,
-        has_l3_referral: bool,
-        cashback_tier: Option<CashbackTier>,
-    ) -> Result<SwapResult> {
-        let mut protocol_fee = 0u64;
-        let mut trading_fee = 0u64;
-        let mut l1_referral_fee = 0u64;
-        let mut l2_referral_fee = 0u64;
-        let mut l3_referral_fee = 0u64;
-        let mut creator_fee = 0u64;
-        let mut cashback_fee = 0u64;
-
-        let mut actual_amount_in = if trade_direction == TradeDirection::QuoteToBase {
-            let fee_breakdown = config.get_fee_on_amount(
-                amount_in,
-                has_l1_referral,
-                has_l2_referral,
-                has_l3_referral,
-                cashback_tier,
-            )?;
-
-            protocol_fee = fee_breakdown.protocol_fee;
-            trading_fee = fee_breakdown.sum();
-            l1_referral_fee = fee_breakdown.l1_referral_fee;
-            l2_referral_fee = fee_breakdown.l2_referral_fee;
-            l3_referral_fee = fee_breakdown.l3_referral_fee;
-            creator_fee = fee_breakdown.creator_fee;
-            cashback_fee = fee_breakdown.cashback_fee;
-
-            fee_breakdown.amount
-        } else {
-            amount_in
+    /// Bring a curve, reallocated to the current layout, to the current version. A creation
+    /// time the older layout didn't record is backfilled from the activation point of the same
+    /// unit, or from the clock for curves activated otherwise
+    pub fn migrate_layout(&mut self, current_timestamp: u64, current_slot: u64) -> Result<()> {
+        require!(
+            self.version < CURVE_VERSION,
+            AmmError::CurveAlreadyReallocated
+        );
+        let (activation_type, activation_point) =
+            (self.get_activation_type()?, self.activation_point);
+        let backfill = |unit: ActivationType, current_point: u64| {
+            if activation_type == unit && activation_point > 0 {
+                activation_point.min(current_point)
+            } else {
+                current_point
+            }
         };
+        if self.creation_timestamp == 0 {
+            self.creation_timestamp = backfill(ActivationType::Timestamp, current_timestamp);
+        }
+        if self.creation_slot == 0 {
+            self.creation_slot = backfill(ActivationType::Slot, current_slot);
+        }
+        self.version = CURVE_VERSION;
+        Ok(())
+    }
 
-        let output_amount = match trade_direction {
-            TradeDirection::QuoteToBase => get_swap_amount_from_quote_to_base(
-                self.virtual_quote_reserve as u128,
-                self.virtual_base_reserve as u128,
-                actual_amount_in,
-            ),
-            TradeDirection::BaseToQuote => get_swap_amount_from_base_to_quote(
-                self.virtual_quote_reserve as u128,
-                self.virtual_base_reserve as u128,
-                actual_amount_in,
-            ),
-        }?;
-
-        let actual_amount_out = if trade_direction == TradeDirection::QuoteToBase {
-            // Check if output_amount exceeds base_reserve first
-            if output_amount >= self.base_reserve
-                || self.base_reserve.safe_sub(output_amount)? < config.migration_base_threshold
-            {
-                let new_base_output_amount = self
-                    .base_reserve
-                    .safe_sub(config.migration_base_threshold)?;
-
-                let new_virtual_base =
-                    self.virtual_base_reserve.safe_sub(new_base_output_amount)?;
-
-                let capped_amount_in = get_swap_amount_from_base_to_quote(
-                    config.migration_quote_threshold as u128,
-                    new_virtual_base as u128,
-                    new_base_output_amount,
-                )?;
+    pub fn get_fee_type(&self) -> Result<FeeType> {
+        Ok(FeeType::try_from(self.fee_type).map_err(|_| AmmError::InvalidFeeType)?)
+    }
 
-                let fee_breakdown = config.get_fee_on_amount(
-                    capped_amount_in,
-                    has_l1_referral,
-                    has_l2_referral,
-                    has_l3_referral,
-                    cashback_tier,
-                )?;
+    /// Switching only changes where new fees accrue, the fees accrued so far stay owed to the
+    /// party they accrued for
+    pub fn set_fee_type(&mut self, fee_type: FeeType) -> Result<()> {
+        require!(
+            self.get_fee_type()? != fee_type,
+            AmmError::FeeTypeAlreadySet
+        );
+        self.fee_type = fee_type.into();
+        Ok(())
+    }
 
-                protocol_fee = fee_breakdown.protocol_fee;
-                trading_fee = fee_breakdown.sum();
-                l1_referral_fee = fee_breakdown.l1_referral_fee;
-                l2_referral_fee = fee_breakdown.l2_referral_fee;
-                l3_referral_fee = fee_breakdown.l3_referral_fee;
-                creator_fee = fee_breakdown.creator_fee;
-                cashback_fee = fee_breakdown.cashback_fee;
-                actual_amount_in = capped_amount_in;
+    pub fn set_creator_lp_fee_share(&mut self, creator_lp_fee_share_basis_points: u16) {
+        self.creator_lp_fee_share_basis_points = creator_lp_fee_share_basis_points;
+    }
 
-                new_base_output_amount
-            } else {
-                output_amount
-            }
-        } else {
-            let fee_breakdown = config.get_fee_on_amount(
-                output_amount,
-                has_l1_referral,
-                has_l2_referral,
-                has_l3_referral,
-                cashback_tier,
-            )?;
+    /// Creator's part of DAMM position fees claimed for the curve, the protocol keeps the rest
+    /// and the rounding
+    pub fn get_creator_lp_fee(&self, position_fee: u64) -> Result<u64> {
+        safe_mul_div_cast_u64(
+            position_fee,
+            self.creator_lp_fee_share_basis_points as u64,
+            FEE_DENOMINATOR,
+            Rounding::Down,
+        )
+    }
+
+    pub fn set_partner(&mut self, partner: Pubkey, partner_fee_share_basis_points: u16) {
+        self.partner = partner;
+        self.partner_fee_share_basis_points = partner_fee_share_basis_points;
+    }
 
-            protocol_fee = fee_breakdown.protocol_fee;
-            trading_fee = fee_breakdown.sum();
-            l1_referral_fee = fee_breakdown.l1_referral_fee;
-            l2_referral_fee = fee_breakdown.l2_referral_fee;
-            l3_referral_fee = fee_breakdown.l3_referral_fee;
-            creator_fee = fee_breakdown.creator_fee;
-            cashback_fee = fee_breakdown.cashback_fee;
+    pub fn has_partner(&self) -> bool {
+        self.partner != Pubkey::default()
+    }
 
-            fee_breakdown.amount
+    /// Scale the virtual reserves and migration thresholds of the curve to `base_supply`, its
+    /// share of the config's supply once dual listed, so it keeps the price path of the config
+    pub fn set_supply_share(&mut self, config: &Config, base_supply: u64) -> Result<()> {
+        let total_supply = config.get_token_total_supply()?;
+        let scale = |amount: u64| -> Result<u64> {
+            safe_mul_div_cast_u64(amount, base_supply, total_supply, Rounding::Down)
         };
+        self.base_reserve = base_supply;
+        self.virtual_quote_reserve = scale(config.initial_virtual_quote_reserve)?;
+        self.virtual_base_reserve = scale(config.initial_virtual_base_reserve)?;
+        self.migration_base_threshold = scale(config.migration_base_threshold)?;
+        self.migration_quote_threshold = scale(config.migration_quote_threshold)?;
+        require!(
+            self.migration_base_threshold > 0 && self.migration_quote_threshold > 0,
+            AmmError::InvalidDualListingSupply
+        );
+        Ok(())
+    }
 
-        Ok(SwapResult {
-            actual_input_amount: actual_amount_in,
-            output_amount: actual_amount_out,
-            trading_fee,
+    /// Migration base threshold of the curve, the config's unless the curve is dual listed
+    pub fn get_migration_base_threshold(&self, config: &Config) -> BaseAmount {
+        match self.migration_base_threshold {
+            0 => config.get_migration_base_threshold(),
+            threshold => BaseAmount(threshold),
+        }
+    }
+
+    /// Migration quote threshold of the curve, the config's unless the curve is dual listed
+    pub fn get_migration_quote_threshold(&self, config: &Config) -> QuoteAmount {
+        match self.migration_quote_threshold {
+            0 => config.get_migration_quote_threshold(),
+            threshold => QuoteAmount(threshold),
+        }
+    }
+
+    /// Migration quote threshold of the curve decayed to `current_timestamp`, `None` if the
+    /// config's threshold doesn't decay
+    pub fn get_decayed_migration_quote_threshold(
+        &self,
+        config: &Config,
+        current_timestamp: u64,
+    ) -> Result<Option<QuoteAmount>> {
+        config.get_decayed_migration_quote_threshold(
+            self.get_migration_quote_threshold(config),
+            self.get_age_seconds(current_timestamp),
+        )
+    }
+
+    /// Partner's part of a protocol fee claim, the protocol keeps the rounding
+    pub fn get_partner_fee_amount(&self, protocol_fee: u64) -> Result<u64> {
+        safe_mul_div_cast_u64(
             protocol_fee,
-            cashback_fee,
-            creator_fee,
-            l1_referral_fee,
-            l2_referral_fee,
-            l3_referral_fee,
-        })
+            self.partner_fee_share_basis_points as u64,
+            FEE_DENOMINATOR,
+            Rounding::Down,
+        )
     }
 
-    pub fn apply_swap_result(
-        &mut self,
-        swap_result: &SwapResult,
-        trade_direction: TradeDirection,
-    ) -> Result<()> {
-        if trade_direction == TradeDirection::BaseToQuote {
-            self.base_reserve = self
-                .base_reserve
-                .safe_add(swap_result.actual_input_amount)?;
-            self.virtual_base_reserve = self
-                .virtual_base_reserve
-                .safe_add(swap_result.actual_input_amount)?;
-
-            self.quote_reserve = self.quote_reserve.safe_sub(swap_result.output_amount)?;
-            self.virtual_quote_reserve = self
-                .virtual_quote_reserve
-                .safe_sub(swap_result.output_amount)?;
-        } else {
-            self.quote_reserve = self
-                .quote_reserve
-                .safe_add(swap_result.actual_input_amount)?;
-            self.virtual_quote_reserve = self
-                .virtual_quote_reserve
-                .safe_add(swap_result.actual_input_amount)?;
-            self.base_reserve = self.base_reserve.safe_sub(swap_result.output_amount)?;
-            self.virtual_base_reserve = self
-                .virtual_base_reserve
-                .safe_sub(swap_result.output_amount)?;
+    pub fn is_raffle_pending(&self) -> bool {
+        self.is_raffle_pending != 0
+    }
+
+    pub fn set_raffle_pending(&mut self, is_raffle_pending: bool) {
+        self.is_raffle_pending = is_raffle_pending.into();
+    }
+
+    /// A curve waiting for its metadata or its raffle draw is paused for both directions
+    pub fn get_trading_pause(&self) -> Result<TradingPause> {
+        if self.is_metadata_pending() || self.is_raffle_pending() {
+            return Ok(TradingPause::All);
         }
+        Ok(TradingPause::try_from(self.trading_pause).map_err(|_| AmmError::TypeCastFailed)?)
+    }
 
-        self.creator_fee = self.creator_fee.safe_add(swap_result.creator_fee)?;
-        self.protocol_fee = self.protocol_fee.safe_add(swap_result.protocol_fee)?;
+    /// Wallet the creator fee is claimed to, the creator unless a recipient is set
+    pub fn get_creator_fee_recipient(&self) -> Pubkey {
+        if self.creator_fee_recipient == Pubkey::default() {
+            return self.creator;
+        }
+        self.creator_fee_recipient
+    }
 
-        Ok(())
+    /// Both the creator and the recipient it set can claim the creator fee
+    pub fn is_creator_fee_authority(&self, key: &Pubkey) -> bool {
+        *key == self.creator || *key == self.get_creator_fee_recipient()
     }
 
-    pub fn is_curve_complete(&self, migration_base_threshold: u64) -> bool {
-        self.base_reserve <= migration_base_threshold
+    pub fn is_paused(&self) -> bool {
+        self.paused != 0
     }
 
-    pub fn set_migration_status(&mut self, status: u8) {
-        self.migration_status = status;
+    pub fn is_metadata_pending(&self) -> bool {
+        self.is_metadata_pending != 0
     }
 
-    pub fn get_migration_progress(&self) -> Result<MigrationStatus> {
-        let migration_progress = MigrationStatus::try_from(self.migration_status)
-            .map_err(|_| AmmError::TypeCastFailed)?;
-        Ok(migration_progress)
+    pub fn set_metadata_pending(&mut self, is_metadata_pending: bool) {
+        self.is_metadata_pending = is_metadata_pending.into();
     }
 
-    pub fn update_after_migration(&mut self) {
-        self.is_migrated = 1;
+    pub fn set_activation(&mut self, activation_type: ActivationType, activation_point: u64) {
+        self.activation_type = activation_type.into();
+        self.activation_point = activation_point;
     }
 
-    pub fn claim_protocol_fee(&mut self) -> u64 {
-        let claim_amount = self.protocol_fee;
-        self.protocol_fee = 0u64;
-        claim_amount
+    pub fn get_activation_type(&self) -> Result<ActivationType> {
+        Ok(ActivationType::try_from(self.activation_type)
+            .map_err(|_| AmmError::InvalidActivationType)?)
     }
 
-    pub fn claim_creator_fee(&mut self) -> u64 {
-        let claim_amount = self.creator_fee;
-        self.creator_fee = 0u64;
-        claim_amount
+    /// Swaps are rejected until the activation point is reached
+    pub fn check_activated(&self, current_timestamp: u64, current_slot: u64) -> Result<()> {
+        let current_point = match self.get_activation_type()? {
+            ActivationType::Timestamp => current_timestamp,
+            ActivationType::Slot => current_slot,
+        };
+        require!(
+            current_point >= self.activation_point,
+            AmmError::CurveNotActivated
+        );
+        Ok(())
     }
 
-    pub fn get_migration_amount(&self, migration_fee_basis_points: u16) -> Result<MigrationAmount> {
-        let quote_amount: u64 = safe_mul_div_cast_u64(
-            self.quote_reserve,
-            FEE_DENOMINATOR.safe_sub(migration_fee_basis_points as u64)?,
-            FEE_DENOMINATOR,
-            Rounding::Up,
-        )?;
-        let base_amount = safe_mul_div_cast_u64(
-            self.base_reserve,
-            FEE_DENOMINATOR.safe_sub(migration_fee_basis_points as u64)?,
-            FEE_DENOMINATOR,
-            Rounding::Up,
-        )?;
-        Ok(MigrationAmount {
-            quote_amount,
-            base_amount,
-        })
+    /// Timestamp the launch windows count from, the activation for curves activated by timestamp
+    fn get_launch_timestamp(&self) -> u64 {
+        match self.get_activation_type() {
+            Ok(ActivationType::Timestamp) => self.creation_timestamp.max(self.activation_point),
+            _ => self.creation_timestamp,
+        }
     }
 
-    pub fn event(
+    /// Slot the launch windows count from, the activation for curves activated by slot
+    fn get_launch_slot(&self) -> u64 {
+        match self.get_activation_type() {
+            Ok(ActivationType::Slot) => self.creation_slot.max(self.activation_point),
+            _ => self.creation_slot,
+        }
+    }
+
+    pub fn set_trading_end_timestamp(&mut self, trading_end_timestamp: u64) {
+        self.trading_end_timestamp = trading_end_timestamp;
+    }
+
+    pub fn has_trading_end(&self) -> bool {
+        self.trading_end_timestamp != 0
+    }
+
+    /// Whether the sell grace period after the trading end is over, no swap is accepted anymore
+    pub fn is_trading_closed(&self, current_timestamp: u64) -> bool {
+        self.has_trading_end()
+            && current_timestamp
+                >= self
+                    .trading_end_timestamp
+                    .saturating_add(TRADING_END_SELL_GRACE_SECONDS)
+    }
+
+    /// Buys are rejected from the trading end, sells once the grace period after it is over
+    pub fn check_trading_end(
         &self,
-        curve_key: Pubkey,
-        quote_mint: Pubkey,
-        name: String,
-        symbol: String,
-        uri: String,
-        initial_virtual_quote_reserve: u64,
-        initial_virtual_base_reserve: u64,
-    ) -> EvtInitializeCurve {
-        EvtInitializeCurve {
-            curve: curve_key.key(),
-            config: self.config,
-            creator: self.creator,
-            base_mint: self.base_mint,
-            quote_mint: quote_mint.key(),
-            curve_type: self.curve_type,
-            name,
-            symbol,
-            uri,
-            initial_virtual_quote_reserve,
-            initial_virtual_base_reserve,
+        current_timestamp: u64,
+        trade_direction: TradeDirection,
+    ) -> Result<()> {
+        if !self.has_trading_end() {
+            return Ok(());
         }
+        require!(
+            !self.is_trading_closed(current_timestamp),
+            AmmError::TradingClosed
+        );
+        require!(
+            trade_direction == TradeDirection::BaseToQuote
+                || current_timestamp < self.trading_end_timestamp,
+            AmmError::TradingEnded
+        );
+        Ok(())
     }
-}
 
-pub struct MigrationAmount {
-    pub quote_amount: u64,
-    pub base_amount: u64,
-}
+    /// Quote refunded for `base_amount` returned once trading is closed, a pro rata share of the
+    /// quote reserve over the base sold out of the curve, so the order of the refunds doesn't
+    /// matter. The sold base comes from the virtual base reserve, which moves with the real one
+    pub fn refund(
+        &mut self,
+        config: &Config,
+        base_amount: u64,
+        current_timestamp: u64,
+    ) -> Result<u64> {
+        require!(base_amount > 0, AmmError::AmountIsZero);
+        require!(
+            self.is_trading_closed(current_timestamp),
+            AmmError::RefundNotOpen
+        );
+        require!(
+            self.get_migration_progress()? == MigrationStatus::PreBondingCurve,
+            AmmError::PoolIsCompleted
+        );
+        // the other curve's holders share the base mint, their base isn't backed by this curve
+        require!(self.is_dual_listed == 0, AmmError::DualListedRefund);
 
-/// Encodes all results of swapping
-#[derive(Debug, PartialEq, AnchorDeserialize, AnchorSerialize)]
-pub struct SwapResult {
-    pub actual_input_amount: u64,
-    pub output_amount: u64,
-    pub trading_fee: u64,
-    pub protocol_fee: u64,
-    pub cashback_fee: u64,
-    pub creator_fee: u64,
-    pub l1_referral_fee: u64,
-    pub l2_referral_fee: u64,
-    pub l3_referral_fee: u64,
-}
+        let sold_base = config
+            .initial_virtual_base_reserve
+            .safe_sub(self.virtual_base_reserve)?;
+        require!(base_amount <= sold_base, AmmError::RefundExceedsSoldBase);
+        let quote_amount =
+            safe_mul_div_cast_u64(base_amount, self.quote_reserve, sold_base, Rounding::Down)?;
 
-/// aka buy
-fn get_swap_amount_from_quote_to_base(
-    virtual_quote: u128,
-    virtual_base: u128,
-    amount_in: u64,
-) -> Result<u64> {
-    // Scale tokens for precision
-    // TODO: we are assuming that the quote token has 9 decimals and the base token has 6 decimals.
-    // This should be configurable in the future.
-    let virtual_base_scaled = virtual_base.safe_mul(1000)?;
-    let k = virtual_quote.safe_mul(virtual_base_scaled)?;
-    let new_virtual_quote = virtual_quote.safe_add(amount_in as u128)?;
-    let new_virtual_base_scaled = k.safe_div(new_virtual_quote)?;
-    let base_out_amount = virtual_base_scaled
-        .safe_sub(new_virtual_base_scaled)?
-        .safe_div(1000)?;
+        self.add_base_reserve(BaseAmount(base_amount))?;
+        self.sub_quote_reserve(QuoteAmount(quote_amount))?;
+        Ok(quote_amount)
+    }
 
-    Ok(base_out_amount as u64)
-}
+    /// Seconds since the launch, the launch fee decays from it
+    pub fn get_launch_elapsed_seconds(&self, current_timestamp: u64) -> u64 {
+        current_timestamp.saturating_sub(self.get_launch_timestamp())
+    }
 
-/// aka sell
-fn get_swap_amount_from_base_to_quote(
-    virtual_quote: u128,
-    virtual_base: u128,
-    amount_in: u64,
-) -> Result<u64> {
-    // Scale tokens for precision
-    // TODO: we are assuming that the quote token has 9 decimals and the base token has 6 decimals.
-    // This should be configurable in the future.
-    let virtual_base_scaled = virtual_base.safe_mul(1000)?;
-    let amount_in_scaled = (amount_in as u128).safe_mul(1000)?;
-    let new_virtual_base_scaled = virtual_base_scaled.safe_add(amount_in_scaled)?;
+    /// Whether a buy at `current_timestamp` falls into the early buyer window
+    pub fn is_in_early_buyer_window(&self, current_timestamp: u64, window_seconds: u64) -> bool {
+        current_timestamp < self.get_launch_timestamp().saturating_add(window_seconds)
+    }
 
-    // Calculate using x*y=k
-    let k = virtual_base_scaled.safe_mul(virtual_quote)?;
-    let new_quote = k.safe_div(new_virtual_base_scaled)?;
-    let quote_out_amount = virtual_quote.safe_sub(new_quote)?;
-    new_quote.safe_div(new_virtual_base_scaled)?;
+    /// Whether a buy at `current_slot` falls into the anti-snipe window
+    pub fn is_in_anti_snipe_window(&self, current_slot: u64, window_slots: u64) -> bool {
+        current_slot < self.get_launch_slot().saturating_add(window_slots)
+    }
 
-    Ok(quote_out_amount as u64)
-}
+    /// Whether sells of a wallet that last bought at `last_buy_timestamp` are still locked
+    pub fn is_sell_locked(&self, last_buy_timestamp: u64, current_timestamp: u64) -> bool {
+        self.min_hold_seconds > 0
+            && current_timestamp < last_buy_timestamp.saturating_add(self.min_hold_seconds)
+    }
 
-pub fn get_price(virtual_quote: u128, virtual_base: u128) -> Result<u128> {
-    // Scale the price to account for different decimals
-    let virtual_base_scaled = virtual_base.safe_mul(1000)?;
-    let price = virtual_quote.safe_div(virtual_base_scaled)?;
-    Ok(price)
+    /// Update the approximate holder and unique trader counts from a trader's base balance before
+    /// and after a swap
+    pub fn update_holder_count(
+        &mut self,
+        pre_base_balance: u64,
+        post_base_balance: u64,
+    ) -> Result<()> {
+        if pre_base_balance == 0 && post_base_balance > 0 {
+            self.holder_count = self.holder_count.safe_add(1)?;
+            self.unique_trader_estimate = self.unique_trader_estimate.safe_add(1)?;
+        } else if pre_base_balance > 0 && post_base_balance == 0 {
+            self.holder_count = self.holder_count.saturating_sub(1);
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_swap_result(
+        &self,
+        config: &Config,
+        amount_in: u64,
+        trade_direction: TradeDirection,
+        has_l1_referral: bool,
+        has_l2_referral: bool,
+        has_l3_referral: bool,
+        cashback_tier: Option<CashbackFeeTier>,
+    ) -> Result<SwapResult> {
+        let is_fee_on_base = config
+            .get_fee_collection_mode()?
+            .is_fee_on_base(trade_direction);
+        match (trade_direction, is_fee_on_base) {
+            (TradeDirection::QuoteToBase, false) => self.get_buy_result(
+                config,
+                QuoteAmount(amount_in),
+                has_l1_referral,
+                has_l2_referral,
+                has_l3_referral,
+                cashback_tier,
+            ),
+            (TradeDirection::QuoteToBase, true) => self.get_buy_result_with_base_fee(
+                config,
+                QuoteAmount(amount_in),
+                has_l1_referral,
+                has_l2_referral,
+                has_l3_referral,
+                cashback_tier,
+            ),
+            (TradeDirection::BaseToQuote, false) => self.get_sell_result(
+                config,
+                BaseAmount(amount_in),
+                has_l1_referral,
+                has_l2_referral,
+                has_l3_referral,
+                cashback_tier,
+            ),
+            (TradeDirection::BaseToQuote, true) => self.get_sell_result_with_base_fee(
+                config,
+                BaseAmount(amount_in),
+                has_l1_referral,
+                has_l2_referral,
+                has_l3_referral,
+                cashback_tier,
+            ),
+        }
+    }
+
+    /// Buy of the winning tickets of a launch raffle, fee free at the curve price. None when the
+    /// allocation doesn't fit in the curve whole, a capped buy would leave winners' quote unspent
+    pub fn get_raffle_allocation(
+        &self,
+        config: &Config,
+        quote_amount: u64,
+    ) -> Result<Option<SwapResult>> {
+        let swap_result = self.get_swap_result(
+            &config.get_fee_free_config(),
+            quote_amount,
+            TradeDirection::QuoteToBase,
+            false,
+            false,
+            false,
+            None,
+        )?;
+        let mut curve = *self;
+        curve.apply_swap_result(
+            &swap_result,
+            TradeDirection::QuoteToBase,
+            self.last_trade_timestamp,
+        )?;
+        let fits = swap_result.get_total_input_amount(TradeDirection::QuoteToBase)? == quote_amount
+            && !curve.is_curve_complete(curve.get_migration_base_threshold(config), None);
+        Ok(fits.then_some(swap_result))
+    }
+
+    /// Smallest input amount that swaps to at least `amount_out` at the fee rates of the same
+    /// referrals and tier, the output can exceed `amount_out` by rounding. The swap result still comes from `get_swap_result`, which also
+    /// caps buys at the migration threshold
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_amount_in_for_exact_out(
+        &self,
+        config: &Config,
+        amount_out: u64,
+        trade_direction: TradeDirection,
+        has_l1_referral: bool,
+        has_l2_referral: bool,
+        has_l3_referral: bool,
+        cashback_tier: Option<CashbackFeeTier>,
+    ) -> Result<u64> {
+        let base_scale = config.get_base_scale()?;
+        let is_fee_on_base = config
+            .get_fee_collection_mode()?
+            .is_fee_on_base(trade_direction);
+        let schedule = config.get_fee_schedule(
+            has_l1_referral,
+            has_l2_referral,
+            has_l3_referral,
+            cashback_tier,
+            trade_direction,
+        )?;
+        Ok(match (trade_direction, is_fee_on_base) {
+            (TradeDirection::QuoteToBase, false) => {
+                let amount_in = get_swap_amount_in_from_quote_to_base(
+                    self.get_virtual_quote_reserve(),
+                    self.get_virtual_base_reserve(),
+                    BaseAmount(amount_out),
+                    base_scale,
+                )?;
+                // the fee is taken from the quote input
+                config.get_amount_before_fee(amount_in, &schedule)?.get()
+            }
+            (TradeDirection::QuoteToBase, true) => {
+                // the fee is taken from the base output
+                let amount_out =
+                    config.get_base_amount_before_fee(BaseAmount(amount_out), &schedule)?;
+                get_swap_amount_in_from_quote_to_base(
+                    self.get_virtual_quote_reserve(),
+                    self.get_virtual_base_reserve(),
+                    amount_out,
+                    base_scale,
+                )?
+                .get()
+            }
+            (TradeDirection::BaseToQuote, false) => {
+                // the fee is taken from the quote output
+                let amount_out =
+                    config.get_amount_before_fee(QuoteAmount(amount_out), &schedule)?;
+                get_swap_amount_in_from_base_to_quote(
+                    self.get_virtual_quote_reserve(),
+                    self.get_virtual_base_reserve(),
+                    amount_out,
+                    base_scale,
+                )?
+                .get()
+            }
+            (TradeDirection::BaseToQuote, true) => {
+                let amount_in = get_swap_amount_in_from_base_to_quote(
+                    self.get_virtual_quote_reserve(),
+                    self.get_virtual_base_reserve(),
+                    QuoteAmount(amount_out),
+                    base_scale,
+                )?;
+                // the fee is taken from the base input
+                config
+                    .get_base_amount_before_fee(amount_in, &schedule)?
+                    .get()
+            }
+        })
+    }
+
+    /// Whether a buy of `amount_out` would take the base reserve below the migration threshold
+    fn exceeds_migration_threshold(&self, config: &Config, amount_out: BaseAmount) -> Result<bool> {
+        let base_reserve = self.get_base_reserve();
+        Ok(amount_out >= base_reserve
+            || base_reserve.safe_sub(amount_out)? < self.get_migration_base_threshold(config))
+    }
+
+    /// The buy that takes the base reserve exactly to the migration threshold, as the quote
+    /// input it needs and its base output
+    fn get_migration_capped_buy(
+        &self,
+        config: &Config,
+        base_scale: u128,
+    ) -> Result<(QuoteAmount, BaseAmount)> {
+        let new_base_output_amount = self
+            .get_base_reserve()
+            .safe_sub(self.get_migration_base_threshold(config))?;
+
+        let new_virtual_base = self
+            .get_virtual_base_reserve()
+            .safe_sub(new_base_output_amount)?;
+
+        // the quote needed to reach the threshold, rounded in favor of the curve
+        let capped_amount_in = get_swap_amount_from_base_to_quote(
+            self.get_migration_quote_threshold(config),
+            new_virtual_base,
+            new_base_output_amount,
+            base_scale,
+            Rounding::Up,
+        )?;
+
+        Ok((capped_amount_in, new_base_output_amount))
+    }
+
+    /// aka buy, the fee is taken from the quote input
+    fn get_buy_result(
+        &self,
+        config: &Config,
+        amount_in: QuoteAmount,
+        has_l1_referral: bool,
+        has_l2_referral: bool,
+        has_l3_referral: bool,
+        cashback_tier: Option<CashbackFeeTier>,
+    ) -> Result<SwapResult> {
+        let base_scale = config.get_base_scale()?;
+        let mut fee_breakdown = config.get_fee_on_amount(
+            amount_in,
+            has_l1_referral,
+            has_l2_referral,
+            has_l3_referral,
+            cashback_tier,
+            TradeDirection::QuoteToBase,
+        )?;
+        let mut actual_amount_in = fee_breakdown.amount;
+
+        let output_amount = get_swap_amount_from_quote_to_base(
+            self.get_virtual_quote_reserve(),
+            self.get_virtual_base_reserve(),
+            actual_amount_in,
+            base_scale,
+        )?;
+
+        // Check if output_amount exceeds base_reserve first
+        let actual_amount_out = if self.exceeds_migration_threshold(config, output_amount)? {
+            let (capped_amount_in, new_base_output_amount) =
+                self.get_migration_capped_buy(config, base_scale)?;
+
+            fee_breakdown = config.get_fee_on_amount(
+                capped_amount_in,
+                has_l1_referral,
+                has_l2_referral,
+                has_l3_referral,
+                cashback_tier,
+                TradeDirection::QuoteToBase,
+            )?;
+            actual_amount_in = capped_amount_in;
+
+            new_base_output_amount
+        } else {
+            output_amount
+        };
+
+        SwapResult::new(
+            actual_amount_in.get(),
+            actual_amount_out.get(),
+            &fee_breakdown,
+        )
+    }
+
+    /// aka buy, the fee is taken from the base output. The fee base stays in the curve, the
+    /// trader pays its quote price instead
+    fn get_buy_result_with_base_fee(
+        &self,
+        config: &Config,
+        amount_in: QuoteAmount,
+        has_l1_referral: bool,
+        has_l2_referral: bool,
+        has_l3_referral: bool,
+        cashback_tier: Option<CashbackFeeTier>,
+    ) -> Result<SwapResult> {
+        let base_scale = config.get_base_scale()?;
+        let schedule = config.get_fee_schedule(
+            has_l1_referral,
+            has_l2_referral,
+            has_l3_referral,
+            cashback_tier,
+            TradeDirection::QuoteToBase,
+        )?;
+        let quote_in_for = |amount_out: BaseAmount| {
+            get_swap_amount_in_from_quote_to_base(
+                self.get_virtual_quote_reserve(),
+                self.get_virtual_base_reserve(),
+                amount_out,
+                base_scale,
+            )
+        };
+
+        let gross_amount_out = get_swap_amount_from_quote_to_base(
+            self.get_virtual_quote_reserve(),
+            self.get_virtual_base_reserve(),
+            amount_in,
+            base_scale,
+        )?;
+        let output_amount = gross_amount_out
+            .safe_sub(config.get_base_fee_on_amount(gross_amount_out, &schedule)?)?;
+
+        let (actual_amount_in, actual_amount_out, trading_fee) =
+            if self.exceeds_migration_threshold(config, output_amount)? {
+                let (capped_amount_in, new_base_output_amount) =
+                    self.get_migration_capped_buy(config, base_scale)?;
+                let gross_amount_out =
+                    config.get_base_amount_before_fee(new_base_output_amount, &schedule)?;
+                let trading_fee = quote_in_for(gross_amount_out)?
+                    .safe_sub(quote_in_for(new_base_output_amount)?)?;
+                (capped_amount_in, new_base_output_amount, trading_fee)
+            } else {
+                let actual_amount_in = quote_in_for(output_amount)?;
+                (
+                    actual_amount_in,
+                    output_amount,
+                    amount_in.safe_sub(actual_amount_in)?,
+                )
+            };
+
+        let fee_breakdown = config.get_fee_split(actual_amount_in, trading_fee, &schedule)?;
+
+        SwapResult::new(
+            actual_amount_in.get(),
+            actual_amount_out.get(),
+            &fee_breakdown,
+        )
+    }
+
+    /// aka sell, the fee is taken from the quote output
+    fn get_sell_result(
+        &self,
+        config: &Config,
+        amount_in: BaseAmount,
+        has_l1_referral: bool,
+        has_l2_referral: bool,
+        has_l3_referral: bool,
+        cashback_tier: Option<CashbackFeeTier>,
+    ) -> Result<SwapResult> {
+        let output_amount = get_swap_amount_from_base_to_quote(
+            self.get_virtual_quote_reserve(),
+            self.get_virtual_base_reserve(),
+            amount_in,
+            config.get_base_scale()?,
+            Rounding::Down,
+        )?;
+
+        let fee_breakdown = config.get_fee_on_amount(
+            output_amount,
+            has_l1_referral,
+            has_l2_referral,
+            has_l3_referral,
+            cashback_tier,
+            TradeDirection::BaseToQuote,
+        )?;
+
+        SwapResult::new(amount_in.get(), fee_breakdown.amount.get(), &fee_breakdown)
+    }
+
+    /// aka sell, the fee is taken from the base input. The fee base is sold into the curve
+    /// after the trader's base, its quote is the fee
+    fn get_sell_result_with_base_fee(
+        &self,
+        config: &Config,
+        amount_in: BaseAmount,
+        has_l1_referral: bool,
+        has_l2_referral: bool,
+        has_l3_referral: bool,
+        cashback_tier: Option<CashbackFeeTier>,
+    ) -> Result<SwapResult> {
+        let base_scale = config.get_base_scale()?;
+        let schedule = config.get_fee_schedule(
+            has_l1_referral,
+            has_l2_referral,
+            has_l3_referral,
+            cashback_tier,
+            TradeDirection::BaseToQuote,
+        )?;
+        let quote_out_for = |amount_in: BaseAmount| {
+            get_swap_amount_from_base_to_quote(
+                self.get_virtual_quote_reserve(),
+                self.get_virtual_base_reserve(),
+                amount_in,
+                base_scale,
+                Rounding::Down,
+            )
+        };
+
+        let fee_amount = config.get_base_fee_on_amount(amount_in, &schedule)?;
+        let output_amount = quote_out_for(amount_in.safe_sub(fee_amount)?)?;
+        let trading_fee = quote_out_for(amount_in)?.safe_sub(output_amount)?;
+
+        let fee_breakdown = config.get_fee_split(output_amount, trading_fee, &schedule)?;
+
+        SwapResult::new(amount_in.get(), output_amount.get(), &fee_breakdown)
+    }
+
+    pub fn apply_swap_result(
+        &mut self,
+        swap_result: &SwapResult,
+        trade_direction: TradeDirection,
+        current_timestamp: u64,
+    ) -> Result<()> {
+        if trade_direction == TradeDirection::BaseToQuote {
+            self.add_base_reserve(BaseAmount(swap_result.actual_input_amount))?;
+            self.sub_quote_reserve(QuoteAmount(swap_result.output_amount))?;
+        } else {
+            self.add_quote_reserve(QuoteAmount(swap_result.actual_input_amount))?;
+            self.sub_base_reserve(BaseAmount(swap_result.output_amount))?;
+        }
+
+        self.accrue_creator_fee(swap_result.creator_fee)?;
+        self.protocol_fee = self.protocol_fee.safe_add(swap_result.protocol_fee)?;
+
+        self.total_quote_volume = self
+            .total_quote_volume
+            .safe_add(swap_result.get_quote_volume(trade_direction)?)?;
+        match trade_direction {
+            TradeDirection::QuoteToBase => self.buy_count = self.buy_count.safe_add(1)?,
+            TradeDirection::BaseToQuote => self.sell_count = self.sell_count.safe_add(1)?,
+        }
+        self.last_trade_timestamp = current_timestamp;
+
+        Ok(())
+    }
+
+    /// Accrue the creator part of a trading fee to the bucket of the current fee type
+    fn accrue_creator_fee(&mut self, amount: u64) -> Result<()> {
+        match self.get_fee_type()? {
+            FeeType::Creator => {
+                self.creator_fee = self.creator_fee.safe_add(amount)?;
+                self.total_creator_fee = self.total_creator_fee.safe_add(amount)?;
+            }
+            FeeType::Meme => self.meme_fee = self.meme_fee.safe_add(amount)?,
+        }
+        Ok(())
+    }
+
+    /// How far the spot price moved from the given reserves to the current ones, in bps
+    ///
+    /// Rounds up, it only feeds the price impact guard.
+    pub fn get_price_impact_basis_points(
+        &self,
+        virtual_quote_reserve_before: QuoteAmount,
+        virtual_base_reserve_before: BaseAmount,
+    ) -> Result<u64> {
+        // price_after / price_before - 1 = (q1 * b0 - q0 * b1) / (q0 * b1)
+        let price_after_scaled = U256::from(self.virtual_quote_reserve)
+            .safe_mul(U256::from(virtual_base_reserve_before.get()))?;
+        let price_before_scaled = U256::from(virtual_quote_reserve_before.get())
+            .safe_mul(U256::from(self.virtual_base_reserve))?;
+        let price_delta = if price_after_scaled > price_before_scaled {
+            price_after_scaled.safe_sub(price_before_scaled)?
+        } else {
+            price_before_scaled.safe_sub(price_after_scaled)?
+        };
+        let price_impact = mul_div_u256(
+            price_delta,
+            U256::from(FEE_DENOMINATOR),
+            price_before_scaled,
+            Rounding::Up,
+        )
+        .ok_or(AmmError::MathOverflow)?;
+        Ok(price_impact
+            .try_into()
+            .map_err(|_| AmmError::TypeCastFailed)?)
+    }
+
+    /// How far the curve output of the swap from the given reserves to the current ones fell
+    /// short of the output at the spot price before it, in bps
+    ///
+    /// With x the input side virtual reserve and dx the curve input, the spot price gives
+    /// dx * y / x while the curve gives dx * y / (x + dx), a shortfall of dx / (x + dx). Rounds
+    /// up, it only feeds the slippage guard.
+    pub fn get_slippage_basis_points(
+        &self,
+        virtual_quote_reserve_before: QuoteAmount,
+        virtual_base_reserve_before: BaseAmount,
+        trade_direction: TradeDirection,
+    ) -> Result<u64> {
+        let (reserve_in_before, reserve_in_after) = match trade_direction {
+            TradeDirection::QuoteToBase => (
+                virtual_quote_reserve_before.get(),
+                self.virtual_quote_reserve,
+            ),
+            TradeDirection::BaseToQuote => {
+                (virtual_base_reserve_before.get(), self.virtual_base_reserve)
+            }
+        };
+        safe_mul_div_cast_u64(
+            reserve_in_after.safe_sub(reserve_in_before)?,
+            FEE_DENOMINATOR,
+            reserve_in_after,
+            Rounding::Up,
+        )
+    }
+
+    /// Spot price of the virtual reserves, quote per base as a Q64.64 of raw amounts
+    pub fn get_virtual_price(&self) -> Result<u128> {
+        Ok((self.virtual_quote_reserve as u128)
+            .safe_shl(64)?
+            .safe_div(self.virtual_base_reserve as u128)?)
+    }
+
+    pub fn get_base_reserve(&self) -> BaseAmount {
+        BaseAmount(self.base_reserve)
+    }
+
+    /// Base left to buy before the base reserve reaches the migration threshold
+    pub fn get_base_until_migration(&self, migration_base_threshold: BaseAmount) -> BaseAmount {
+        BaseAmount(
+            self.base_reserve
+                .saturating_sub(migration_base_threshold.get()),
+        )
+    }
+
+    pub fn get_virtual_base_reserve(&self) -> BaseAmount {
+        BaseAmount(self.virtual_base_reserve)
+    }
+
+    pub fn get_virtual_quote_reserve(&self) -> QuoteAmount {
+        QuoteAmount(self.virtual_quote_reserve)
+    }
+
+    /// Check the reserves and fee buckets against the initial virtual reserves of the config
+    /// and the vault balances
+    pub fn verify_invariants(
+        &self,
+        event_sequence: u64,
+        curve_key: Pubkey,
+        initial_virtual_quote_reserve: u64,
+        initial_virtual_base_reserve: u64,
+        base_vault_balance: u64,
+        quote_vault_balance: u64,
+    ) -> Result<EvtVerifyCurveInvariants> {
+        let k = (self.virtual_quote_reserve as u128).safe_mul(self.virtual_base_reserve as u128)?;
+        let initial_k = (initial_virtual_quote_reserve as u128)
+            .safe_mul(initial_virtual_base_reserve as u128)?;
+        let k_holds = k >= initial_k;
+        let quote_reserve_offset_holds = (self.quote_reserve as u128)
+            .safe_add(initial_virtual_quote_reserve as u128)?
+            == self.virtual_quote_reserve as u128;
+
+        // the vaults are emptied into the pool at migration
+        let is_migrated = self.is_migrated == 1;
+        let base_vault_covers_reserve = is_migrated || base_vault_balance >= self.base_reserve;
+        let quote_owed = (self.quote_reserve as u128)
+            .safe_add(self.protocol_fee as u128)?
+            .safe_add(self.creator_fee as u128)?
+            .safe_add(self.meme_fee as u128)?;
+        let quote_vault_covers_reserve_and_fees =
+            is_migrated || quote_vault_balance as u128 >= quote_owed;
+        let creator_fee_within_total = self.creator_fee <= self.total_creator_fee;
+
+        Ok(EvtVerifyCurveInvariants {
+            event_sequence,
+            curve: curve_key,
+            config: self.config,
+            passed: k_holds
+                && quote_reserve_offset_holds
+                && base_vault_covers_reserve
+                && quote_vault_covers_reserve_and_fees
+                && creator_fee_within_total,
+            k,
+            initial_k,
+            k_holds,
+            quote_reserve_offset_holds,
+            base_vault_covers_reserve,
+            quote_vault_covers_reserve_and_fees,
+            creator_fee_within_total,
+            base_vault_balance,
+            quote_vault_balance,
+        })
+    }
+
+    /// Add to both the real and the virtual base reserve
+    fn add_base_reserve(&mut self, amount: BaseAmount) -> Result<()> {
+        self.base_reserve = self.base_reserve.safe_add(amount.get())?;
+        self.virtual_base_reserve = self.virtual_base_reserve.safe_add(amount.get())?;
+        Ok(())
+    }
+
+    /// Subtract from both the real and the virtual base reserve
+    fn sub_base_reserve(&mut self, amount: BaseAmount) -> Result<()> {
+        self.base_reserve = self.base_reserve.safe_sub(amount.get())?;
+        self.virtual_base_reserve = self.virtual_base_reserve.safe_sub(amount.get())?;
+        Ok(())
+    }
+
+    /// Add to both the real and the virtual quote reserve
+    fn add_quote_reserve(&mut self, amount: QuoteAmount) -> Result<()> {
+        self.quote_reserve = self.quote_reserve.safe_add(amount.get())?;
+        self.virtual_quote_reserve = self.virtual_quote_reserve.safe_add(amount.get())?;
+        Ok(())
+    }
+
+    /// Subtract from both the real and the virtual quote reserve
+    fn sub_quote_reserve(&mut self, amount: QuoteAmount) -> Result<()> {
+        self.quote_reserve = self.quote_reserve.safe_sub(amount.get())?;
+        self.virtual_quote_reserve = self.virtual_quote_reserve.safe_sub(amount.get())?;
+        Ok(())
+    }
+
+    /// Whether the curve sold down to the base threshold or, with threshold decay, raised the
+    /// decayed quote threshold
+    pub fn is_curve_complete(
+        &self,
+        migration_base_threshold: BaseAmount,
+        decayed_quote_threshold: Option<QuoteAmount>,
+    ) -> bool {
+        self.get_base_reserve() <= migration_base_threshold
+            || decayed_quote_threshold
+                .is_some_and(|threshold| self.get_virtual_quote_reserve() >= threshold)
+    }
+
+    /// Quote threshold the curve completed at after a trade, `None` while it keeps trading. Stale
+    /// curves graduate at a lower cap when the config decays the quote threshold
+    pub fn get_completed_quote_threshold(
+        &self,
+        config: &Config,
+        current_timestamp: u64,
+    ) -> Result<Option<QuoteAmount>> {
+        let decayed_quote_threshold =
+            self.get_decayed_migration_quote_threshold(config, current_timestamp)?;
+        if !self.is_curve_complete(
+            self.get_migration_base_threshold(config),
+            decayed_quote_threshold,
+        ) {
+            return Ok(None);
+        }
+        Ok(Some(
+            decayed_quote_threshold.unwrap_or(self.get_migration_quote_threshold(config)),
+        ))
+    }
+
+    /// Ends the bonding curve phase, the base vault has to hold enough tokens for the migration
+    pub fn complete(
+        &mut self,
+        config: &Config,
+        base_vault_amount: u64,
+        current_timestamp: u64,
+    ) -> Result<()> {
+        require!(
+            base_vault_amount >= self.get_migration_base_threshold(config).get(),
+            AmmError::InsufficientLiquidityForMigration
+        );
+        self.curve_finish_timestamp = current_timestamp;
+        self.set_migration_status(MigrationStatus::PostBondingCurve.into());
+        Ok(())
+    }
+
+    /// Seconds since the curve was created
+    pub fn get_age_seconds(&self, current_timestamp: u64) -> u64 {
+        current_timestamp.saturating_sub(self.creation_timestamp)
+    }
+
+    pub fn set_migration_status(&mut self, status: u8) {
+        self.migration_status = status;
+    }
+
+    pub fn get_migration_progress(&self) -> Result<MigrationStatus> {
+        let migration_progress = MigrationStatus::try_from(self.migration_status)
+            .map_err(|_| AmmError::TypeCastFailed)?;
+        Ok(migration_progress)
+    }
+
+    pub fn update_after_migration(&mut self) {
+        self.is_migrated = 1;
+    }
+
+    /// Records the migration fee paid to the fee claimer
+    pub fn record_migration_fee(&mut self, quote_amount: u64, base_amount: u64) -> Result<()> {
+        self.migration_quote_fee = self.migration_quote_fee.safe_add(quote_amount)?;
+        self.migration_base_fee = self.migration_base_fee.safe_add(base_amount)?;
+        Ok(())
+    }
+
+    /// Accrues the quote migration fee that wasn't paid out at migration
+    pub fn accrue_migration_fee(&mut self, amount: u64) -> Result<()> {
+        self.migration_fee_collected = self.migration_fee_collected.safe_add(amount)?;
+        Ok(())
+    }
+
+    pub fn claim_migration_fee(&mut self) -> u64 {
+        let claim_amount = self.migration_fee_collected;
+        self.migration_fee_collected = 0u64;
+        claim_amount
+    }
+
+    pub fn claim_protocol_fee(&mut self) -> u64 {
+        let claim_amount = self.protocol_fee;
+        self.protocol_fee = 0u64;
+        claim_amount
+    }
+
+    pub fn claim_creator_fee(&mut self) -> u64 {
+        let claim_amount = self.creator_fee;
+        self.creator_fee = 0u64;
+        claim_amount
+    }
+
+    pub fn claim_meme_fee(&mut self) -> u64 {
+        let claim_amount = self.meme_fee;
+        self.meme_fee = 0u64;
+        claim_amount
+    }
+
+    pub fn open_limit_order(&mut self) -> Result<()> {
+        self.open_limit_order_count = self.open_limit_order_count.safe_add(1)?;
+        Ok(())
+    }
+
+    /// Orders placed before the curve counted them close without ever being counted
+    pub fn close_limit_order(&mut self) {
+        self.open_limit_order_count = self.open_limit_order_count.saturating_sub(1);
+    }
+
+    /// Whether nothing is owed out of the curve anymore: every fee bucket is claimed, no limit
+    /// order waits for a cancellation and no creator share of the position fees goes through
+    /// the curve vaults
+    pub fn is_settled(&self) -> bool {
+        self.creator_lp_fee_share_basis_points == 0
+            && self.protocol_fee == 0
+            && self.creator_fee == 0
+            && self.meme_fee == 0
+            && self.migration_fee_collected == 0
+            && self.open_limit_order_count == 0
+    }
+
+    /// Quote the config guarantees the creator on top of its accrued fee, taken from the
+    /// migration fee that stays in the quote vault, never from the pool liquidity
+    pub fn get_creator_fee_top_up(
+        &self,
+        min_creator_fee_amount: u64,
+        migration_fee_basis_points: u16,
+    ) -> Result<u64> {
+        let migration_fee = self.get_migration_fee(migration_fee_basis_points)?;
+        Ok(min_creator_fee_amount
+            .saturating_sub(self.total_creator_fee)
+            .min(migration_fee))
+    }
+
+    /// Credit a creator fee top up, claimable like the trading creator fee
+    pub fn add_creator_fee_top_up(&mut self, amount: u64) -> Result<()> {
+        self.creator_fee = self.creator_fee.safe_add(amount)?;
+        self.total_creator_fee = self.total_creator_fee.safe_add(amount)?;
+        Ok(())
+    }
+
+    /// Quote reserve kept out of the pool at migration, the migration fee mode doesn't change it
+    pub fn get_migration_fee(&self, migration_fee_basis_points: u16) -> Result<u64> {
+        Ok(self.quote_reserve.safe_sub(
+            self.get_migration_amount(migration_fee_basis_points, MigrationFeeMode::QuoteOnly)?
+                .quote_amount,
+        )?)
+    }
+
+    /// Reserves deposited into the pool at migration. They round up, so the fee kept rounds
+    /// down and the pool gets the rounding. With `QuoteOnly` the whole base reserve is deposited
+    pub fn get_migration_amount(
+        &self,
+        migration_fee_basis_points: u16,
+        migration_fee_mode: MigrationFeeMode,
+    ) -> Result<MigrationAmount> {
+        let after_fee = |reserve: u64| {
+            safe_mul_div_cast_u64(
+                reserve,
+                FEE_DENOMINATOR.safe_sub(migration_fee_basis_points as u64)?,
+                FEE_DENOMINATOR,
+                Rounding::Up,
+            )
+        };
+        let quote_amount = after_fee(self.quote_reserve)?;
+        let base_amount = match migration_fee_mode {
+            MigrationFeeMode::BothSides => after_fee(self.base_reserve)?,
+            MigrationFeeMode::QuoteOnly => self.base_reserve,
+        };
+        Ok(MigrationAmount {
+            quote_amount,
+            base_amount,
+        })
+    }
+
+    pub fn event(
+        &self,
+        event_sequence: u64,
+        curve_key: Pubkey,
+        quote_mint: Pubkey,
+        params: CurveEventParams,
+    ) -> EvtInitializeCurve {
+        let CurveEventParams {
+            name,
+            symbol,
+            uri,
+            initial_virtual_quote_reserve,
+            initial_virtual_base_reserve,
+        } = params;
+        EvtInitializeCurve {
+            event_sequence,
+            curve: curve_key.key(),
+            config: self.config,
+            creator: self.creator,
+            base_mint: self.base_mint,
+            quote_mint: quote_mint.key(),
+            curve_type: self.curve_type,
+            name,
+            symbol,
+            uri,
+            initial_virtual_quote_reserve,
+            initial_virtual_base_reserve,
+            min_hold_seconds: self.min_hold_seconds,
+            trading_end_timestamp: self.trading_end_timestamp,
+            activation_type: self.activation_type,
+            activation_point: self.activation_point,
+            partner: self.partner,
+            partner_fee_share_basis_points: self.partner_fee_share_basis_points,
+        }
+    }
+}
+
+pub struct MigrationAmount {
+    pub quote_amount: u64,
+    pub base_amount: u64,
+}
+
+/// Outcome of a swap against the current state of a curve, returned by `simulate_swap`
+#[derive(Debug, PartialEq, AnchorDeserialize, AnchorSerialize)]
+pub struct SwapSimulation {
+    pub swap_result: SwapResult,
+    /// virtual base reserve of the curve after the swap
+    pub virtual_base_reserve: u64,
+    /// virtual quote reserve of the curve after the swap
+    pub virtual_quote_reserve: u64,
+    /// spot price of the virtual reserves after the swap, quote per base as a Q64.64 of raw
+    /// amounts
+    pub virtual_price: u128,
+    /// whether the swap completes the curve, ie: the curve graduates once migrated
+    pub completes_curve: bool,
+}
+
+/// Encodes all results of swapping
+#[derive(Clone, Copy, Debug, PartialEq, AnchorDeserialize, AnchorSerialize)]
+pub struct SwapResult {
+    pub actual_input_amount: u64,
+    pub output_amount: u64,
+    pub trading_fee: u64,
+    pub protocol_fee: u64,
+    pub cashback_fee: u64,
+    pub creator_fee: u64,
+    pub l1_referral_fee: u64,
+    pub l2_referral_fee: u64,
+    pub l3_referral_fee: u64,
+}
+
+impl SwapResult {
+    /// Amount the trader pays in, the buy fee comes on top of the input
+    pub fn get_total_input_amount(&self, trade_direction: TradeDirection) -> Result<u64> {
+        Ok(match trade_direction {
+            TradeDirection::QuoteToBase => self.actual_input_amount.safe_add(self.trading_fee)?,
+            TradeDirection::BaseToQuote => self.actual_input_amount,
+        })
+    }
+
+    /// Quote side of the swap, fees included: paid in on buys, paid out plus the fee on sells
+    pub fn get_quote_volume(&self, trade_direction: TradeDirection) -> Result<u64> {
+        match trade_direction {
+            TradeDirection::QuoteToBase => self.get_total_input_amount(trade_direction),
+            TradeDirection::BaseToQuote => Ok(self.output_amount.safe_add(self.trading_fee)?),
+        }
+    }
+
+    fn new(
+        actual_input_amount: u64,
+        output_amount: u64,
+        fee_breakdown: &FeeBreakdown,
+    ) -> Result<Self> {
+        Ok(SwapResult {
+            actual_input_amount,
+            output_amount,
+            trading_fee: fee_breakdown.sum()?.get(),
+            protocol_fee: fee_breakdown.protocol_fee.get(),
+            cashback_fee: fee_breakdown.cashback_fee.get(),
+            creator_fee: fee_breakdown.creator_fee.get(),
+            l1_referral_fee: fee_breakdown.l1_referral_fee.get(),
+            l2_referral_fee: fee_breakdown.l2_referral_fee.get(),
+            l3_referral_fee: fee_breakdown.l3_referral_fee.get(),
+        })
+    }
+}
+
+/// aka buy
+fn get_swap_amount_from_quote_to_base(
+    virtual_quote: QuoteAmount,
+    virtual_base: BaseAmount,
+    amount_in: QuoteAmount,
+    base_scale: u128,
+) -> Result<BaseAmount> {
+    let virtual_quote = virtual_quote.get() as u128;
+    // Scale base tokens by the decimal gap to the quote token for precision
+    let virtual_base_scaled = (virtual_base.get() as u128).safe_mul(base_scale)?;
+    let k = virtual_quote.safe_mul(virtual_base_scaled)?;
+    let new_virtual_quote = virtual_quote.safe_add(amount_in.get() as u128)?;
+    // the reserve left in the curve rounds up so the output rounds down, whatever the scale
+    let new_virtual_base_scaled = div_with_rounding(k, new_virtual_quote, Rounding::Up)?;
+    let base_out_amount = virtual_base_scaled
+        .safe_sub(new_virtual_base_scaled)?
+        .safe_div(base_scale)?;
+
+    Ok(BaseAmount(base_out_amount as u64))
+}
+
+/// aka sell, `rounding` is the rounding of the quote output
+fn get_swap_amount_from_base_to_quote(
+    virtual_quote: QuoteAmount,
+    virtual_base: BaseAmount,
+    amount_in: BaseAmount,
+    base_scale: u128,
+    rounding: Rounding,
+) -> Result<QuoteAmount> {
+    let virtual_quote = virtual_quote.get() as u128;
+    // Scale base tokens by the decimal gap to the quote token for precision
+    let virtual_base_scaled = (virtual_base.get() as u128).safe_mul(base_scale)?;
+    let amount_in_scaled = (amount_in.get() as u128).safe_mul(base_scale)?;
+    let new_virtual_base_scaled = virtual_base_scaled.safe_add(amount_in_scaled)?;
+
+    // Calculate using x*y=k
+    let k = virtual_base_scaled.safe_mul(virtual_quote)?;
+    let new_quote_rounding = match rounding {
+        Rounding::Up => Rounding::Down,
+        Rounding::Down => Rounding::Up,
+    };
+    let new_quote = div_with_rounding(k, new_virtual_base_scaled, new_quote_rounding)?;
+    let quote_out_amount = virtual_quote.safe_sub(new_quote)?;
+
+    Ok(QuoteAmount(quote_out_amount as u64))
+}
+
+/// Smallest quote input of a buy that gets at least `amount_out`
+fn get_swap_amount_in_from_quote_to_base(
+    virtual_quote: QuoteAmount,
+    virtual_base: BaseAmount,
+    amount_out: BaseAmount,
+    base_scale: u128,
+) -> Result<QuoteAmount> {
+    let virtual_quote = virtual_quote.get() as u128;
+    let virtual_base_scaled = (virtual_base.get() as u128).safe_mul(base_scale)?;
+    let amount_out_scaled = (amount_out.get() as u128).safe_mul(base_scale)?;
+    let k = virtual_quote.safe_mul(virtual_base_scaled)?;
+    let new_virtual_base_scaled = virtual_base_scaled.safe_sub(amount_out_scaled)?;
+    let new_virtual_quote = div_with_rounding(k, new_virtual_base_scaled, Rounding::Up)?;
+    let quote_in_amount = new_virtual_quote.safe_sub(virtual_quote)?;
+
+    Ok(QuoteAmount(
+        u64::try_from(quote_in_amount).map_err(|_| AmmError::MathOverflow)?,
+    ))
+}
+
+/// Smallest base input of a sell that gets at least `amount_out`, before the fee
+fn get_swap_amount_in_from_base_to_quote(
+    virtual_quote: QuoteAmount,
+    virtual_base: BaseAmount,
+    amount_out: QuoteAmount,
+    base_scale: u128,
+) -> Result<BaseAmount> {
+    let virtual_quote = virtual_quote.get() as u128;
+    let virtual_base_scaled = (virtual_base.get() as u128).safe_mul(base_scale)?;
+    let k = virtual_base_scaled.safe_mul(virtual_quote)?;
+    let new_quote = virtual_quote.safe_sub(amount_out.get() as u128)?;
+    let new_virtual_base_scaled = div_with_rounding(k, new_quote, Rounding::Up)?;
+    let base_in_amount = div_with_rounding(
+        new_virtual_base_scaled.safe_sub(virtual_base_scaled)?,
+        base_scale,
+        Rounding::Up,
+    )?;
+
+    Ok(BaseAmount(
+        u64::try_from(base_in_amount).map_err(|_| AmmError::MathOverflow)?,
+    ))
+}
+
+fn div_with_rounding(numerator: u128, denominator: u128, rounding: Rounding) -> Result<u128> {
+    Ok(match rounding {
+        Rounding::Up => numerator
+            .safe_add(denominator.safe_sub(1)?)?
+            .safe_div(denominator)?,
+        Rounding::Down => numerator.safe_div(denominator)?,
+    })
+}
+
+pub fn get_price(virtual_quote: u128, virtual_base: u128, base_scale: u128) -> Result<u128> {
+    // Scale the price to account for different decimals
+    let virtual_base_scaled = virtual_base.safe_mul(base_scale)?;
+    let price = virtual_quote.safe_div(virtual_base_scaled)?;
+    Ok(price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::{get_base_scale, CashbackTier, FeeCollectionMode, FeeRemainderMode};
+
+    /// (base decimal, quote decimal)
+    const DECIMAL_PAIRS: [(u8, u8); 3] = [(6, 6), (9, 6), (6, 9)];
+
+    /// 30 quote tokens against 1.073B base tokens, like the default config
+    fn virtual_reserves(base_decimal: u8, quote_decimal: u8) -> (QuoteAmount, BaseAmount) {
+        (
+            QuoteAmount(30 * 10u64.pow(quote_decimal as u32)),
+            BaseAmount(1_073_000_000 * 10u64.pow(base_decimal as u32)),
+        )
+    }
+
+    #[test]
+    fn base_scale_only_scales_up_base() {
+        assert_eq!(get_base_scale(6, 6).unwrap(), 1);
+        assert_eq!(get_base_scale(9, 6).unwrap(), 1);
+        assert_eq!(get_base_scale(6, 9).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn swap_amounts_match_constant_product() {
+        for (base_decimal, quote_decimal) in DECIMAL_PAIRS {
+            let base_scale = get_base_scale(base_decimal, quote_decimal).unwrap();
+            let (virtual_quote, virtual_base) = virtual_reserves(base_decimal, quote_decimal);
+            let (vq, vb) = (virtual_quote.get() as u128, virtual_base.get() as u128);
+
+            // 1 quote token in, base out = vb * in / (vq + in), rounded down
+            let quote_in = 10u64.pow(quote_decimal as u32);
+            let base_out = get_swap_amount_from_quote_to_base(
+                virtual_quote,
+                virtual_base,
+                QuoteAmount(quote_in),
+                base_scale,
+            )
+            .unwrap();
+            let expected = vb * quote_in as u128 / (vq + quote_in as u128);
+            assert_eq!(base_out.get() as u128, expected);
+
+            // 1M base tokens in, quote out = vq * in / (vb + in)
+            let base_in = 1_000_000 * 10u64.pow(base_decimal as u32);
+            let numerator = vq * base_in as u128;
+            let denominator = vb + base_in as u128;
+            let quote_out_down = get_swap_amount_from_base_to_quote(
+                virtual_quote,
+                virtual_base,
+                BaseAmount(base_in),
+                base_scale,
+                Rounding::Down,
+            )
+            .unwrap();
+            assert_eq!(quote_out_down.get() as u128, numerator / denominator);
+            let quote_out_up = get_swap_amount_from_base_to_quote(
+                virtual_quote,
+                virtual_base,
+                BaseAmount(base_in),
+                base_scale,
+                Rounding::Up,
+            )
+            .unwrap();
+            assert_eq!(quote_out_up.get() as u128, numerator.div_ceil(denominator));
+        }
+    }
+
+    #[test]
+    fn round_trip_never_returns_more_quote() {
+        for (base_decimal, quote_decimal) in DECIMAL_PAIRS {
+            let base_scale = get_base_scale(base_decimal, quote_decimal).unwrap();
+            let (virtual_quote, virtual_base) = virtual_reserves(base_decimal, quote_decimal);
+
+            for quote_in in [
+                1,
+                999,
+                10u64.pow(quote_decimal as u32),
+                7 * 10u64.pow(quote_decimal as u32),
+            ] {
+                let base_out = get_swap_amount_from_quote_to_base(
+                    virtual_quote,
+                    virtual_base,
+                    QuoteAmount(quote_in),
+                    base_scale,
+                )
+                .unwrap();
+                let quote_back = get_swap_amount_from_base_to_quote(
+                    virtual_quote.safe_add(QuoteAmount(quote_in)).unwrap(),
+                    virtual_base.safe_sub(base_out).unwrap(),
+                    base_out,
+                    base_scale,
+                    Rounding::Down,
+                )
+                .unwrap();
+                assert!(quote_back.get() <= quote_in);
+            }
+        }
+    }
+
+    #[test]
+    fn exact_out_inputs_are_the_smallest_that_reach_the_output() {
+        for (base_decimal, quote_decimal) in DECIMAL_PAIRS {
+            let base_scale = get_base_scale(base_decimal, quote_decimal).unwrap();
+            let (virtual_quote, virtual_base) = virtual_reserves(base_decimal, quote_decimal);
+
+            for base_out in [1, 12_345, 10u64.pow(base_decimal as u32 + 6)] {
+                let quote_in = get_swap_amount_in_from_quote_to_base(
+                    virtual_quote,
+                    virtual_base,
+                    BaseAmount(base_out),
+                    base_scale,
+                )
+                .unwrap();
+                let buy = |quote_in: u64| {
+                    get_swap_amount_from_quote_to_base(
+                        virtual_quote,
+                        virtual_base,
+                        QuoteAmount(quote_in),
+                        base_scale,
+                    )
+                    .unwrap()
+                    .get()
+                };
+                assert!(buy(quote_in.get()) >= base_out);
+                assert!(buy(quote_in.get() - 1) < base_out);
+            }
+
+            for quote_out in [1, 12_345, 10u64.pow(quote_decimal as u32)] {
+                let base_in = get_swap_amount_in_from_base_to_quote(
+                    virtual_quote,
+                    virtual_base,
+                    QuoteAmount(quote_out),
+                    base_scale,
+                )
+                .unwrap();
+                let sell = |base_in: u64| {
+                    get_swap_amount_from_base_to_quote(
+                        virtual_quote,
+                        virtual_base,
+                        BaseAmount(base_in),
+                        base_scale,
+                        Rounding::Down,
+                    )
+                    .unwrap()
+                    .get()
+                };
+                assert!(sell(base_in.get()) >= quote_out);
+                assert!(sell(base_in.get() - 1) < quote_out);
+            }
+        }
+    }
+
+    #[test]
+    fn exact_out_beyond_the_reserves_fails() {
+        let (virtual_quote, virtual_base) = virtual_reserves(6, 9);
+        assert!(get_swap_amount_in_from_quote_to_base(
+            virtual_quote,
+            virtual_base,
+            virtual_base,
+            1_000
+        )
+        .is_err());
+        assert!(get_swap_amount_in_from_base_to_quote(
+            virtual_quote,
+            virtual_base,
+            virtual_quote,
+            1_000
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn base_side_fees_are_valued_through_the_curve() {
+        let (virtual_quote, virtual_base) = virtual_reserves(6, 9);
+        let base_scale = get_base_scale(6, 9).unwrap();
+        let curve = BondingCurve {
+            virtual_quote_reserve: virtual_quote.get(),
+            virtual_base_reserve: virtual_base.get(),
+            base_reserve: virtual_base.get(),
+            ..Default::default()
+        };
+        let mut config = Config {
+            base_decimal: 6,
+            quote_decimal: 9,
+            fee_basis_points: 1_500,
+            creator_fee_basis_points: 500,
+            ..Default::default()
+        };
+        let swap = |config: &Config, amount_in: u64, trade_direction: TradeDirection| {
+            curve
+                .get_swap_result(
+                    config,
+                    amount_in,
+                    trade_direction,
+                    false,
+                    false,
+                    false,
+                    None,
+                )
+                .unwrap()
+        };
+        let (quote_in, base_in) = (1_000_000_000, 10_000_000_000);
+        let quote_buy = swap(&config, quote_in, TradeDirection::QuoteToBase);
+        let quote_sell = swap(&config, base_in, TradeDirection::BaseToQuote);
+
+        // the buy still pays the fee on quote, the sell fee comes off the base input
+        config.fee_collection_mode = FeeCollectionMode::FeeOnInput.into();
+        assert_eq!(
+            swap(&config, quote_in, TradeDirection::QuoteToBase),
+            quote_buy
+        );
+        let sell = swap(&config, base_in, TradeDirection::BaseToQuote);
+        let sell_quote = |base_in: u64| {
+            get_swap_amount_from_base_to_quote(
+                virtual_quote,
+                virtual_base,
+                BaseAmount(base_in),
+                base_scale,
+                Rounding::Down,
+            )
+            .unwrap()
+            .get()
+        };
+        assert_eq!(sell.actual_input_amount, base_in);
+        assert_eq!(
+            sell.output_amount,
+            sell_quote(base_in - base_in * 1_500 / 100_000)
+        );
+        assert_eq!(sell.output_amount + sell.trading_fee, sell_quote(base_in));
+        assert_eq!(sell.creator_fee, sell.trading_fee * 500 / 1_500);
+
+        // the sell still pays the fee on quote, the buy fee comes off the base output
+        config.fee_collection_mode = FeeCollectionMode::FeeOnOutput.into();
+        assert_eq!(
+            swap(&config, base_in, TradeDirection::BaseToQuote),
+            quote_sell
+        );
+        let buy = swap(&config, quote_in, TradeDirection::QuoteToBase);
+        let gross_out = get_swap_amount_from_quote_to_base(
+            virtual_quote,
+            virtual_base,
+            QuoteAmount(quote_in),
+            base_scale,
+        )
+        .unwrap()
+        .get();
+        assert_eq!(buy.output_amount, gross_out - gross_out * 1_500 / 100_000);
+        assert_eq!(
+            buy.get_total_input_amount(TradeDirection::QuoteToBase)
+                .unwrap(),
+            quote_in
+        );
+        assert_eq!(buy.creator_fee, buy.trading_fee * 500 / 1_500);
+
+        // exact out inputs still reach the output
+        for mode in [
+            FeeCollectionMode::FeeOnInput,
+            FeeCollectionMode::FeeOnOutput,
+        ] {
+            config.fee_collection_mode = mode.into();
+            for (amount_out, trade_direction) in [
+                (12_345_678_900, TradeDirection::QuoteToBase),
+                (123_456_789, TradeDirection::BaseToQuote),
+            ] {
+                let amount_in = curve
+                    .get_amount_in_for_exact_out(
+                        &config,
+                        amount_out,
+                        trade_direction,
+                        false,
+                        false,
+                        false,
+                        None,
+                    )
+                    .unwrap();
+                assert!(swap(&config, amount_in, trade_direction).output_amount >= amount_out);
+            }
+        }
+    }
+
+    #[test]
+    fn cashback_follows_the_direction_multipliers() {
+        let (virtual_quote, virtual_base) = virtual_reserves(6, 9);
+        let curve = BondingCurve {
+            virtual_quote_reserve: virtual_quote.get(),
+            virtual_base_reserve: virtual_base.get(),
+            base_reserve: virtual_base.get(),
+            ..Default::default()
+        };
+        let mut config = Config {
+            base_decimal: 6,
+            quote_decimal: 9,
+            fee_basis_points: 1_500,
+            buy_cashback_multiplier_basis_points: 100_000,
+            sell_cashback_multiplier_basis_points: 50_000,
+            ..Default::default()
+        };
+        let swap = |config: &Config, amount_in: u64, trade_direction: TradeDirection| {
+            curve
+                .get_swap_result(
+                    config,
+                    amount_in,
+                    trade_direction,
+                    false,
+                    false,
+                    false,
+                    Some(CashbackTier::Champion.into()),
+                )
+                .unwrap()
+        };
+        let champion_bps = CashbackTier::Champion.get_cashback_bps() as u64;
+
+        let buy = swap(&config, 1_000_000_000, TradeDirection::QuoteToBase);
+        assert_eq!(buy.cashback_fee, 1_000_000_000 * champion_bps / 100_000);
+
+        let sell = swap(&config, 10_000_000_000, TradeDirection::BaseToQuote);
+        let sell_quote = sell.output_amount + sell.trading_fee;
+        assert_eq!(sell.cashback_fee, sell_quote * (champion_bps / 2) / 100_000);
+
+        // the protocol keeps the cashback sells no longer earn, the trading fee is unchanged
+        config.sell_cashback_multiplier_basis_points = 0;
+        let no_cashback_sell = swap(&config, 10_000_000_000, TradeDirection::BaseToQuote);
+        assert_eq!(no_cashback_sell.cashback_fee, 0);
+        assert_eq!(no_cashback_sell.trading_fee, sell.trading_fee);
+        assert_eq!(
+            no_cashback_sell.protocol_fee,
+            sell.protocol_fee + sell.cashback_fee
+        );
+    }
+
+    #[test]
+    fn tier_fee_discount_comes_out_of_the_protocol_fee() {
+        let (virtual_quote, virtual_base) = virtual_reserves(6, 9);
+        let curve = BondingCurve {
+            virtual_quote_reserve: virtual_quote.get(),
+            virtual_base_reserve: virtual_base.get(),
+            base_reserve: virtual_base.get(),
+            ..Default::default()
+        };
+        let config = Config {
+            base_decimal: 6,
+            quote_decimal: 9,
+            fee_basis_points: 1_500,
+            buy_cashback_multiplier_basis_points: 100_000,
+            ..Default::default()
+        };
+        let buy = |fee_discount_basis_points: u16| {
+            let cashback_tier = CashbackFeeTier {
+                tier: CashbackTier::Champion,
+                fee_discount_basis_points,
+            };
+            let swap_result = curve
+                .get_swap_result(
+                    &config,
+                    1_000_000_000,
+                    TradeDirection::QuoteToBase,
+                    false,
+                    false,
+                    false,
+                    Some(cashback_tier),
+                )
+                .unwrap();
+            let schedule = config
+                .get_fee_schedule(
+                    false,
+                    false,
+                    false,
+                    Some(cashback_tier),
+                    TradeDirection::QuoteToBase,
+                )
+                .unwrap();
+            (swap_result, schedule)
+        };
+
+        let (full_fee, _) = buy(0);
+        assert_eq!(full_fee.trading_fee, 15_000_000);
+        assert_eq!(full_fee.cashback_fee, 2_500_000);
+        assert_eq!(full_fee.protocol_fee, 12_500_000);
+
+        // the trader saves what the protocol gives up, the cashback is unchanged
+        let (discounted, schedule) = buy(500);
+        assert_eq!(discounted.trading_fee, 10_000_000);
+        assert_eq!(discounted.cashback_fee, full_fee.cashback_fee);
+        assert_eq!(discounted.protocol_fee, 7_500_000);
+        assert!(discounted.output_amount > full_fee.output_amount);
+        assert_eq!(schedule.trading_fee_basis_points, 1_000);
+        assert_eq!(schedule.fee_discount_basis_points, 500);
+        assert_eq!(schedule.protocol_fee_basis_points, 750);
+
+        // a discount larger than the protocol's part only takes the protocol fee to zero
+        let (capped, schedule) = buy(2_000);
+        assert_eq!(capped.protocol_fee, 0);
+        assert_eq!(capped.trading_fee, capped.cashback_fee);
+        assert_eq!(schedule.fee_discount_basis_points, 1_250);
+        assert_eq!(schedule.protocol_fee_basis_points, 0);
+    }
+
+    /// Curve and config with a 15% trading fee and a champion tier discounted by 5%, on a 6
+    /// decimal base and a 9 decimal quote
+    fn discounted_tier_setup() -> (BondingCurve, Config, CashbackFeeTier) {
+        let (virtual_quote, virtual_base) = virtual_reserves(6, 9);
+        let curve = BondingCurve {
+            virtual_quote_reserve: virtual_quote.get(),
+            virtual_base_reserve: virtual_base.get(),
+            base_reserve: virtual_base.get(),
+            ..Default::default()
+        };
+        let config = Config {
+            base_decimal: 6,
+            quote_decimal: 9,
+            fee_basis_points: 1_500,
+            creator_fee_basis_points: 500,
+            buy_cashback_multiplier_basis_points: 100_000,
+            sell_cashback_multiplier_basis_points: 100_000,
+            ..Default::default()
+        };
+        let cashback_tier = CashbackFeeTier {
+            tier: CashbackTier::Champion,
+            fee_discount_basis_points: 500,
+        };
+        (curve, config, cashback_tier)
+    }
+
+    #[test]
+    fn exact_out_inputs_follow_the_tier_discount() {
+        let (curve, mut config, cashback_tier) = discounted_tier_setup();
+        let swap = |config: &Config, amount_in: u64, trade_direction: TradeDirection| {
+            curve
+                .get_swap_result(
+                    config,
+                    amount_in,
+                    trade_direction,
+                    false,
+                    false,
+                    false,
+                    Some(cashback_tier),
+                )
+                .unwrap()
+                .output_amount
+        };
+
+        for mode in [
+            FeeCollectionMode::AlwaysOnQuote,
+            FeeCollectionMode::FeeOnInput,
+            FeeCollectionMode::FeeOnOutput,
+        ] {
+            config.fee_collection_mode = mode.into();
+            for (amount_in, trade_direction) in [
+                (1_000_000_000, TradeDirection::QuoteToBase),
+                (10_000_000_000, TradeDirection::BaseToQuote),
+            ] {
+                let amount_out = swap(&config, amount_in, trade_direction);
+                let exact_out_amount_in = curve
+                    .get_amount_in_for_exact_out(
+                        &config,
+                        amount_out,
+                        trade_direction,
+                        false,
+                        false,
+                        false,
+                        Some(cashback_tier),
+                    )
+                    .unwrap();
+                // the exact out input costs what the exact in swap it came from did, up to the
+                // rounding of the fee, a full rate inverse would overpay by the discount
+                assert!(exact_out_amount_in <= amount_in + 1);
+                assert!(swap(&config, exact_out_amount_in, trade_direction) >= amount_out);
+            }
+        }
+    }
+
+    #[test]
+    fn tier_fee_discount_applies_to_sell_fees_taken_in_base() {
+        let (curve, mut config, cashback_tier) = discounted_tier_setup();
+        config.fee_collection_mode = FeeCollectionMode::FeeOnInput.into();
+        let base_in = 10_000_000_000;
+        let sell = curve
+            .get_swap_result(
+                &config,
+                base_in,
+                TradeDirection::BaseToQuote,
+                false,
+                false,
+                false,
+                Some(cashback_tier),
+            )
+            .unwrap();
+        let sell_quote = |base_in: u64| {
+            get_swap_amount_from_base_to_quote(
+                curve.get_virtual_quote_reserve(),
+                curve.get_virtual_base_reserve(),
+                BaseAmount(base_in),
+                config.get_base_scale().unwrap(),
+                Rounding::Down,
+            )
+            .unwrap()
+            .get()
+        };
+
+        // the base fee is taken at the discounted 10% and split along the discounted rates
+        assert_eq!(
+            sell.output_amount,
+            sell_quote(base_in - base_in * 1_000 / 100_000)
+        );
+        assert_eq!(sell.output_amount + sell.trading_fee, sell_quote(base_in));
+        assert_eq!(sell.creator_fee, sell.trading_fee * 500 / 1_000);
+        assert_eq!(sell.cashback_fee, sell.trading_fee * 250 / 1_000);
+        assert_eq!(
+            sell.protocol_fee,
+            sell.trading_fee - sell.creator_fee - sell.cashback_fee
+        );
+    }
+
+    #[test]
+    fn tier_fee_discount_applies_to_buy_fees_taken_in_base() {
+        let (curve, mut config, cashback_tier) = discounted_tier_setup();
+        config.fee_collection_mode = FeeCollectionMode::FeeOnOutput.into();
+        let quote_in = 1_000_000_000;
+        let buy = curve
+            .get_swap_result(
+                &config,
+                quote_in,
+                TradeDirection::QuoteToBase,
+                false,
+                false,
+                false,
+                Some(cashback_tier),
+            )
+            .unwrap();
+        let gross_out = get_swap_amount_from_quote_to_base(
+            curve.get_virtual_quote_reserve(),
+            curve.get_virtual_base_reserve(),
+            QuoteAmount(quote_in),
+            config.get_base_scale().unwrap(),
+        )
+        .unwrap()
+        .get();
+
+        // the base fee is taken at the discounted 10% and split along the discounted rates
+        assert_eq!(buy.output_amount, gross_out - gross_out * 1_000 / 100_000);
+        assert_eq!(
+            buy.get_total_input_amount(TradeDirection::QuoteToBase)
+                .unwrap(),
+            quote_in
+        );
+        assert_eq!(buy.creator_fee, buy.trading_fee * 500 / 1_000);
+        assert_eq!(buy.cashback_fee, buy.trading_fee * 250 / 1_000);
+        assert_eq!(
+            buy.protocol_fee,
+            buy.trading_fee - buy.creator_fee - buy.cashback_fee
+        );
+    }
+
+    #[test]
+    fn fee_parts_always_sum_to_the_trading_fee() {
+        let mut config = Config {
+            fee_basis_points: 1_337,
+            referee_discount_basis_points: 111,
+            l1_referral_fee_basis_points: 97,
+            l2_referral_fee_basis_points: 53,
+            l3_referral_fee_basis_points: 31,
+            creator_fee_basis_points: 211,
+            buy_cashback_multiplier_basis_points: 77_777,
+            ..Default::default()
+        };
+        let cashback_tier = Some(CashbackFeeTier {
+            tier: CashbackTier::Champion,
+            fee_discount_basis_points: 123,
+        });
+
+        for fee_remainder_mode in [FeeRemainderMode::Protocol, FeeRemainderMode::Cashback] {
+            config.fee_remainder_mode = fee_remainder_mode.into();
+            for amount_in in (1..5_000).chain([999_999_999, 10u64.pow(18)]) {
+                for (has_referral, cashback_tier) in [
+                    (false, None),
+                    (true, None),
+                    (false, cashback_tier),
+                    (true, cashback_tier),
+                ] {
+                    let amount_in = QuoteAmount(amount_in);
+                    let fee_breakdown = config
+                        .get_fee_on_amount(
+                            amount_in,
+                            has_referral,
+                            has_referral,
+                            has_referral,
+                            cashback_tier,
+                            TradeDirection::QuoteToBase,
+                        )
+                        .unwrap();
+                    let total_fee = amount_in.safe_sub(fee_breakdown.amount).unwrap();
+                    assert_eq!(fee_breakdown.sum().unwrap(), total_fee);
+                    // at most one unit lost by each of the six parts rounded down
+                    assert!(fee_breakdown.rounding_remainder < QuoteAmount(6));
+
+                    let schedule = config
+                        .get_fee_schedule(
+                            has_referral,
+                            has_referral,
+                            has_referral,
+                            cashback_tier,
+                            TradeDirection::QuoteToBase,
+                        )
+                        .unwrap();
+                    let fee_split = config
+                        .get_fee_split(fee_breakdown.amount, total_fee, &schedule)
+                        .unwrap();
+                    assert_eq!(fee_split.sum().unwrap(), total_fee);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn fee_rounding_remainder_goes_to_the_configured_fee() {
+        let mut config = Config {
+            fee_basis_points: 1_500,
+            creator_fee_basis_points: 500,
+            buy_cashback_multiplier_basis_points: 100_000,
+            ..Default::default()
+        };
+        let get_fee_breakdown = |config: &Config, cashback_tier: Option<CashbackFeeTier>| {
+            config
+                .get_fee_on_amount(
+                    QuoteAmount(999),
+                    false,
+                    false,
+                    false,
+                    cashback_tier,
+                    TradeDirection::QuoteToBase,
+                )
+                .unwrap()
+        };
+        let cashback_tier = Some(CashbackTier::Champion.into());
+
+        // 1.5% of 999 rounds down to 14, the creator (4), cashback (2) and protocol (7) parts
+        // rounded down on their own leave 1
+        let fee_breakdown = get_fee_breakdown(&config, cashback_tier);
+        assert_eq!(fee_breakdown.rounding_remainder, QuoteAmount(1));
+        assert_eq!(fee_breakdown.creator_fee, QuoteAmount(4));
+        assert_eq!(fee_breakdown.cashback_fee, QuoteAmount(2));
+        assert_eq!(fee_breakdown.protocol_fee, QuoteAmount(8));
+
+        config.fee_remainder_mode = FeeRemainderMode::Cashback.into();
+        let fee_breakdown = get_fee_breakdown(&config, cashback_tier);
+        assert_eq!(fee_breakdown.cashback_fee, QuoteAmount(3));
+        assert_eq!(fee_breakdown.protocol_fee, QuoteAmount(7));
+
+        // swaps without cashback leave it to the protocol
+        let fee_breakdown = get_fee_breakdown(&config, None);
+        assert_eq!(fee_breakdown.rounding_remainder, QuoteAmount(1));
+        assert_eq!(fee_breakdown.cashback_fee, QuoteAmount(0));
+        assert_eq!(fee_breakdown.protocol_fee, QuoteAmount(10));
+    }
+
+    #[test]
+    fn partner_fee_is_a_share_of_the_protocol_fee() {
+        let mut curve = BondingCurve::default();
+        assert!(!curve.has_partner());
+        assert_eq!(curve.get_partner_fee_amount(1_000_000).unwrap(), 0);
+
+        // 20% of the protocol fee, the protocol keeps the rounding
+        curve.set_partner(Pubkey::new_unique(), 20_000);
+        assert!(curve.has_partner());
+        assert_eq!(curve.get_partner_fee_amount(1_000_000).unwrap(), 200_000);
+        assert_eq!(curve.get_partner_fee_amount(9).unwrap(), 1);
+    }
+
+    #[test]
+    fn creator_fee_top_up_is_capped_by_the_migration_fee() {
+        let mut curve = BondingCurve {
+            quote_reserve: 100_000,
+            total_creator_fee: 400,
+            ..Default::default()
+        };
+        // 5% migration fee = 5_000 quote
+        assert_eq!(curve.get_creator_fee_top_up(0, 5_000).unwrap(), 0);
+        assert_eq!(curve.get_creator_fee_top_up(300, 5_000).unwrap(), 0);
+        assert_eq!(curve.get_creator_fee_top_up(1_000, 5_000).unwrap(), 600);
+        assert_eq!(curve.get_creator_fee_top_up(10_000, 5_000).unwrap(), 5_000);
+        assert_eq!(curve.get_creator_fee_top_up(10_000, 0).unwrap(), 0);
+
+        curve.add_creator_fee_top_up(600).unwrap();
+        assert_eq!(curve.creator_fee, 600);
+        assert_eq!(curve.total_creator_fee, 1_000);
+        assert_eq!(curve.get_creator_fee_top_up(1_000, 5_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn meme_fees_accrue_apart_from_the_creator_fee() {
+        let mut curve = BondingCurve::default();
+        curve.accrue_creator_fee(100).unwrap();
+
+        curve.set_fee_type(FeeType::Meme).unwrap();
+        assert!(curve.set_fee_type(FeeType::Meme).is_err());
+        curve.accrue_creator_fee(250).unwrap();
+        assert_eq!(curve.creator_fee, 100);
+        assert_eq!(curve.total_creator_fee, 100);
+        assert_eq!(curve.meme_fee, 250);
+
+        // switching back leaves the meme fee owed to the community
+        curve.set_fee_type(FeeType::Creator).unwrap();
+        curve.accrue_creator_fee(50).unwrap();
+        assert_eq!(curve.claim_creator_fee(), 150);
+        assert_eq!(curve.claim_meme_fee(), 250);
+        assert_eq!(curve.meme_fee, 0);
+    }
+
+    #[test]
+    fn holder_count_follows_balances_going_to_and_from_zero() {
+        let mut curve = BondingCurve::default();
+        // first buy, a second buy of the same wallet, then another wallet's buy
+        curve.update_holder_count(0, 100).unwrap();
+        curve.update_holder_count(100, 250).unwrap();
+        curve.update_holder_count(0, 40).unwrap();
+        assert_eq!(curve.holder_count, 2);
+
+        // a partial sell keeps the holder, a full sell drops it
+        curve.update_holder_count(250, 10).unwrap();
+        assert_eq!(curve.holder_count, 2);
+        curve.update_holder_count(40, 0).unwrap();
+        assert_eq!(curve.holder_count, 1);
+
+        // buying back counts the wallet again, the unique trader estimate counts every entry
+        curve.update_holder_count(0, 40).unwrap();
+        assert_eq!(curve.holder_count, 2);
+        assert_eq!(curve.unique_trader_estimate, 3);
+
+        // the count never goes below zero
+        let mut curve = BondingCurve::default();
+        curve.update_holder_count(10, 0).unwrap();
+        assert_eq!(curve.holder_count, 0);
+    }
+
+    #[test]
+    fn settles_once_fees_are_claimed_and_orders_closed() {
+        let mut curve = BondingCurve::default();
+        assert!(curve.is_settled());
+
+        curve.accrue_creator_fee(100).unwrap();
+        curve.open_limit_order().unwrap();
+        assert!(!curve.is_settled());
+
+        curve.claim_creator_fee();
+        assert!(!curve.is_settled());
+        curve.close_limit_order();
+        assert!(curve.is_settled());
+
+        // the creator share of the position fees is claimed through the curve for good
+        curve.set_creator_lp_fee_share(1_000);
+        assert!(!curve.is_settled());
+        curve.set_creator_lp_fee_share(0);
+
+        // orders placed before the counter close without underflowing it
+        curve.close_limit_order();
+        assert_eq!(curve.open_limit_order_count, 0);
+    }
+
+    #[test]
+    fn trading_end_closes_buys_then_sells() {
+        let mut curve = BondingCurve::default();
+        assert!(curve
+            .check_trading_end(u64::MAX, TradeDirection::QuoteToBase)
+            .is_ok());
+
+        curve.set_trading_end_timestamp(1_000);
+        let grace_end = 1_000 + TRADING_END_SELL_GRACE_SECONDS;
+        for (timestamp, buy_open, sell_open) in [
+            (999, true, true),
+            (1_000, false, true),
+            (grace_end - 1, false, true),
+            (grace_end, false, false),
+        ] {
+            assert_eq!(
+                curve
+                    .check_trading_end(timestamp, TradeDirection::QuoteToBase)
+                    .is_ok(),
+                buy_open
+            );
+            assert_eq!(
+                curve
+                    .check_trading_end(timestamp, TradeDirection::BaseToQuote)
+                    .is_ok(),
+                sell_open
+            );
+            assert_eq!(curve.is_trading_closed(timestamp), !sell_open);
+        }
+    }
+
+    #[test]
+    fn refunds_pay_the_quote_reserve_pro_rata() {
+        let config = Config {
+            initial_virtual_base_reserve: 1_000,
+            ..Default::default()
+        };
+        // 400 base sold for 200 quote
+        let mut curve = BondingCurve {
+            base_reserve: 600,
+            virtual_base_reserve: 600,
+            quote_reserve: 200,
+            virtual_quote_reserve: 300,
+            ..Default::default()
+        };
+        curve.set_trading_end_timestamp(1_000);
+        let grace_end = 1_000 + TRADING_END_SELL_GRACE_SECONDS;
+        assert!(curve.refund(&config, 100, grace_end - 1).is_err());
+
+        assert_eq!(curve.refund(&config, 100, grace_end).unwrap(), 50);
+        assert_eq!(curve.base_reserve, 700);
+        assert_eq!(curve.quote_reserve, 150);
+        assert!(curve.refund(&config, 301, grace_end).is_err());
+        // the last holder gets the rest of the reserve
+        assert_eq!(curve.refund(&config, 300, grace_end).unwrap(), 150);
+        assert_eq!(curve.quote_reserve, 0);
+        assert_eq!(curve.virtual_quote_reserve, 100);
+
+        curve.is_dual_listed = 1;
+        assert!(curve.refund(&config, 1, grace_end).is_err());
+    }
+
+    #[test]
+    fn launch_windows_start_at_the_activation() {
+        let mut curve = BondingCurve {
+            creation_timestamp: 1_000,
+            creation_slot: 500,
+            ..Default::default()
+        };
+        assert!(curve.check_activated(0, 0).is_ok());
+
+        curve.set_activation(ActivationType::Timestamp, 2_000);
+        assert!(curve.check_activated(1_999, u64::MAX).is_err());
+        assert!(curve.check_activated(2_000, 0).is_ok());
+        assert!(curve.is_in_early_buyer_window(2_059, 60));
+        assert!(!curve.is_in_early_buyer_window(2_060, 60));
+        // the slot window can't be moved by a timestamp activation
+        assert!(!curve.is_in_anti_snipe_window(510, 10));
+
+        curve.set_activation(ActivationType::Slot, 800);
+        assert!(curve.check_activated(u64::MAX, 799).is_err());
+        assert!(curve.check_activated(0, 800).is_ok());
+        assert!(curve.is_in_anti_snipe_window(809, 10));
+        assert!(!curve.is_in_anti_snipe_window(810, 10));
+        assert!(!curve.is_in_early_buyer_window(1_060, 60));
+    }
+
+    #[test]
+    fn launch_fee_decays_to_the_trading_fee() {
+        let mut curve = BondingCurve {
+            creation_timestamp: 1_000,
+            ..Default::default()
+        };
+        let mut config = Config {
+            fee_basis_points: 1_000,
+            launch_fee_basis_points: 21_000,
+            launch_fee_decay_seconds: 100,
+            ..Default::default()
+        };
+        let fee_at = |config: &Config, curve: &BondingCurve, timestamp: u64| {
+            config
+                .get_launch_fee_basis_points(curve.get_launch_elapsed_seconds(timestamp))
+                .unwrap()
+        };
+
+        assert_eq!(fee_at(&config, &curve, 1_000), 21_000);
+        assert_eq!(fee_at(&config, &curve, 1_025), 16_000);
+        assert_eq!(fee_at(&config, &curve, 1_099), 1_200);
+        assert_eq!(fee_at(&config, &curve, 1_100), 1_000);
+
+        // stepwise, the fee holds for each period
+        config.launch_fee_periods = 4;
+        assert_eq!(fee_at(&config, &curve, 1_024), 21_000);
+        assert_eq!(fee_at(&config, &curve, 1_025), 16_000);
+        assert_eq!(fee_at(&config, &curve, 1_099), 6_000);
+
+        // a scheduled launch decays from its activation
+        curve.set_activation(ActivationType::Timestamp, 2_000);
+        assert_eq!(fee_at(&config, &curve, 1_500), 21_000);
+        assert_eq!(fee_at(&config, &curve, 2_050), 11_000);
+
+        // the launch fee only ever adds to the trading fee
+        let launch_fee_config = config.get_launch_fee_config(0).unwrap();
+        assert_eq!(launch_fee_config.fee_basis_points, 21_000);
+        config.launch_fee_basis_points = 0;
+        assert_eq!(fee_at(&config, &curve, 2_000), 1_000);
+    }
+
+    #[test]
+    fn slippage_is_the_shortfall_against_the_spot_price() {
+        let curve = BondingCurve {
+            virtual_quote_reserve: 1_100,
+            virtual_base_reserve: 1_000_000,
+            ..Default::default()
+        };
+        // 100 quote on a 1_000 quote reserve gets 100 / 1_100 less than the spot price gives
+        assert_eq!(
+            curve
+                .get_slippage_basis_points(
+                    QuoteAmount(1_000),
+                    BaseAmount(1_100_000),
+                    TradeDirection::QuoteToBase
+                )
+                .unwrap(),
+            9_091
+        );
+        let curve = BondingCurve {
+            virtual_quote_reserve: 900,
+            virtual_base_reserve: 1_250_000,
+            ..Default::default()
+        };
+        assert_eq!(
+            curve
+                .get_slippage_basis_points(
+                    QuoteAmount(1_125),
+                    BaseAmount(1_000_000),
+                    TradeDirection::BaseToQuote
+                )
+                .unwrap(),
+            20_000
+        );
+    }
+
+    #[test]
+    fn base_until_migration_stops_at_the_threshold() {
+        let mut curve = BondingCurve {
+            base_reserve: 1_000,
+            ..Default::default()
+        };
+        assert_eq!(
+            curve.get_base_until_migration(BaseAmount(200)),
+            BaseAmount(800)
+        );
+        // a buy capped at the threshold leaves nothing, and nothing can go below it
+        curve.base_reserve = 200;
+        assert_eq!(
+            curve.get_base_until_migration(BaseAmount(200)),
+            BaseAmount::ZERO
+        );
+        curve.base_reserve = 100;
+        assert_eq!(
+            curve.get_base_until_migration(BaseAmount(200)),
+            BaseAmount::ZERO
+        );
+    }
+
+    #[test]
+    fn invariants_hold_until_the_vault_falls_short() {
+        // a buy of 100 quote for 50 base on a 1_000 / 2_000 curve, 3 protocol and 2 creator fee
+        let mut curve = BondingCurve {
+            base_reserve: 1_950,
+            virtual_base_reserve: 1_950,
+            quote_reserve: 100,
+            virtual_quote_reserve: 1_100,
+            protocol_fee: 3,
+            creator_fee: 2,
+            total_creator_fee: 2,
+            ..Default::default()
+        };
+        let report = curve
+            .verify_invariants(0, Pubkey::default(), 1_000, 2_000, 1_950, 105)
+            .unwrap();
+        assert!(report.passed);
+        assert_eq!(report.k, 1_100 * 1_950);
+
+        let report = curve
+            .verify_invariants(0, Pubkey::default(), 1_000, 2_000, 1_950, 104)
+            .unwrap();
+        assert!(!report.passed);
+        assert!(!report.quote_vault_covers_reserve_and_fees);
+        assert!(report.k_holds && report.base_vault_covers_reserve);
+
+        // the vaults are emptied at migration, only the reserves are still checked
+        curve.is_migrated = 1;
+        let report = curve
+            .verify_invariants(0, Pubkey::default(), 1_000, 2_000, 0, 0)
+            .unwrap();
+        assert!(report.passed);
+
+        curve.virtual_base_reserve = 1_800;
+        let report = curve
+            .verify_invariants(0, Pubkey::default(), 1_000, 2_000, 0, 0)
+            .unwrap();
+        assert!(!report.k_holds && !report.passed);
+    }
+
+    #[test]
+    fn dual_listed_curves_keep_the_price_path_on_their_share() {
+        let config = Config {
+            base_decimal: 6,
+            quote_decimal: 9,
+            initial_virtual_quote_reserve: 30_000_000_000,
+            initial_virtual_base_reserve: 1_073_000_000_000_000,
+            migration_base_threshold: 200_000_000_000_000,
+            migration_quote_threshold: 115_005_359_056,
+            ..Default::default()
+        };
+        let total_supply = config.get_token_total_supply().unwrap();
+        let full_curve = BondingCurve {
+            base_reserve: total_supply,
+            virtual_quote_reserve: config.initial_virtual_quote_reserve,
+            virtual_base_reserve: config.initial_virtual_base_reserve,
+            ..Default::default()
+        };
+        let mut curve = full_curve;
+        curve
+            .set_supply_share(&config, total_supply / 5 * 2)
+            .unwrap();
+
+        assert_eq!(
+            curve.get_virtual_price().unwrap(),
+            full_curve.get_virtual_price().unwrap()
+        );
+        assert_eq!(
+            curve.get_migration_base_threshold(&config).get(),
+            config.migration_base_threshold / 5 * 2
+        );
+        assert_eq!(
+            curve.get_migration_quote_threshold(&config).get(),
+            config.migration_quote_threshold / 5 * 2
+        );
+        // curves on the config's threshold aren't scaled
+        assert_eq!(
+            full_curve.get_migration_base_threshold(&config),
+            config.get_migration_base_threshold()
+        );
+
+        // a buy of everything stops at the scaled threshold
+        let buy = curve
+            .get_swap_result(
+                &config,
+                u64::MAX / 2,
+                TradeDirection::QuoteToBase,
+                false,
+                false,
+                false,
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            buy.output_amount,
+            curve.base_reserve - curve.migration_base_threshold
+        );
+        let full_buy = full_curve
+            .get_swap_result(
+                &config,
+                u64::MAX / 2,
+                TradeDirection::QuoteToBase,
+                false,
+                false,
+                false,
+                None,
+            )
+            .unwrap();
+        assert_eq!(
+            buy.actual_input_amount,
+            full_buy.actual_input_amount / 5 * 2
+        );
+    }
+
+    #[test]
+    fn curves_complete_at_the_base_threshold() {
+        let config = Config {
+            migration_base_threshold: 200,
+            migration_quote_threshold: 1_000,
+            ..Default::default()
+        };
+        let mut curve = BondingCurve {
+            base_reserve: 201,
+            ..Default::default()
+        };
+        assert_eq!(
+            curve.get_completed_quote_threshold(&config, 0).unwrap(),
+            None
+        );
+
+        curve.base_reserve = 200;
+        assert_eq!(
+            curve.get_completed_quote_threshold(&config, 0).unwrap(),
+            Some(QuoteAmount(1_000))
+        );
+
+        // the vault has to hold the base for the migration
+        assert!(curve.complete(&config, 199, 10).is_err());
+        curve.complete(&config, 200, 10).unwrap();
+        assert_eq!(curve.curve_finish_timestamp, 10);
+        assert_eq!(
+            curve.get_migration_progress().unwrap(),
+            MigrationStatus::PostBondingCurve
+        );
+    }
+
+    #[test]
+    fn curves_created_before_the_version_migrate_once() {
+        let mut curve = BondingCurve::default();
+        curve.migrate_layout(1_000, 500).unwrap();
+        assert_eq!(curve.version, CURVE_VERSION);
+        assert!(curve.migrate_layout(1_000, 500).is_err());
+
+        // new curves start on the current layout
+        let mut curve = BondingCurve::default();
+        curve.init(CurveInitParams::default());
+        assert!(curve.migrate_layout(1_000, 500).is_err());
+    }
+
+    #[test]
+    fn migrated_curves_backfill_their_creation_time() {
+        // from the clock when the curve has no activation point
+        let mut curve = BondingCurve::default();
+        curve.migrate_layout(1_000, 500).unwrap();
+        assert_eq!(curve.creation_timestamp, 1_000);
+        assert_eq!(curve.creation_slot, 500);
+
+        // from the activation point of the same unit, capped at the clock
+        let mut curve = BondingCurve::default();
+        curve.set_activation(ActivationType::Slot, 400);
+        curve.migrate_layout(1_000, 500).unwrap();
+        assert_eq!(curve.creation_timestamp, 1_000);
+        assert_eq!(curve.creation_slot, 400);
+
+        let mut curve = BondingCurve::default();
+        curve.set_activation(ActivationType::Timestamp, 2_000);
+        curve.migrate_layout(1_000, 500).unwrap();
+        assert_eq!(curve.creation_timestamp, 1_000);
+
+        // recorded creation times are kept
+        let mut curve = BondingCurve {
+            creation_timestamp: 10,
+            creation_slot: 5,
+            ..Default::default()
+        };
+        curve.migrate_layout(1_000, 500).unwrap();
+        assert_eq!(curve.creation_timestamp, 10);
+        assert_eq!(curve.creation_slot, 5);
+    }
+
+    #[test]
+    fn creator_lp_fee_share_rounds_in_favor_of_the_protocol() {
+        let mut curve = BondingCurve::default();
+        assert_eq!(curve.get_creator_lp_fee(1_000).unwrap(), 0);
+
+        curve.set_creator_lp_fee_share(33_333);
+        assert_eq!(curve.get_creator_lp_fee(1_000).unwrap(), 333);
+        assert_eq!(curve.get_creator_lp_fee(2).unwrap(), 0);
+    }
+
+    #[test]
+    fn migration_amounts_keep_the_fee_from_the_configured_sides() {
+        let curve = BondingCurve {
+            base_reserve: 200_000_001,
+            quote_reserve: 85_000_003,
+            ..Default::default()
+        };
+
+        // 5% kept from both sides, the deposits round up: 95% of 200_000_001 is 190_000_000.95
+        // and of 85_000_003 is 80_750_002.85
+        let both_sides = curve
+            .get_migration_amount(5_000, MigrationFeeMode::BothSides)
+            .unwrap();
+        assert_eq!(both_sides.base_amount, 190_000_001);
+        assert_eq!(both_sides.quote_amount, 80_750_003);
+
+        // only the quote fee is kept, the whole base reserve reaches the pool
+        let quote_only = curve
+            .get_migration_amount(5_000, MigrationFeeMode::QuoteOnly)
+            .unwrap();
+        assert_eq!(quote_only.base_amount, 200_000_001);
+        assert_eq!(quote_only.quote_amount, 80_750_003);
+        assert_eq!(curve.get_migration_fee(5_000).unwrap(), 4_250_000);
+
+        // without a fee everything is deposited
+        let no_fee = curve
+            .get_migration_amount(0, MigrationFeeMode::BothSides)
+            .unwrap();
+        assert_eq!(no_fee.base_amount, 200_000_001);
+        assert_eq!(no_fee.quote_amount, 85_000_003);
+    }
 }
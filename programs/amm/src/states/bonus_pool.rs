@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    safe_math::{safe_mul_div_cast_u64, SafeMath},
+    u128x128_math::Rounding,
+};
+
+/// Creator funded quote pool shared pro-rata by early buyers once the curve graduates
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct BonusPool {
+    /// bonding curve this pool belongs to
+    pub curve: Pubkey,
+    /// quote token vault holding the deposits
+    pub quote_vault: Pubkey,
+    /// total quote deposited by the creator
+    pub total_deposit: u64,
+    /// total quote bought by eligible buyers during the early buyer window
+    pub total_eligible_quote_amount: u64,
+    /// total bonus claimed so far
+    pub total_claimed: u64,
+}
+
+impl BonusPool {
+    pub fn init(&mut self, curve: Pubkey, quote_vault: Pubkey) {
+        self.curve = curve;
+        self.quote_vault = quote_vault;
+    }
+
+    pub fn deposit(&mut self, amount: u64) -> Result<()> {
+        self.total_deposit = self.total_deposit.safe_add(amount)?;
+        Ok(())
+    }
+
+    pub fn accrue_early_buy(&mut self, quote_amount: u64) -> Result<()> {
+        self.total_eligible_quote_amount =
+            self.total_eligible_quote_amount.safe_add(quote_amount)?;
+        Ok(())
+    }
+
+    /// Take off the early buys a buyer sold since
+    pub fn settle_early_buys(&mut self, quote_amount: u64) -> Result<()> {
+        self.total_eligible_quote_amount =
+            self.total_eligible_quote_amount.safe_sub(quote_amount)?;
+        Ok(())
+    }
+
+    /// Pro-rata share of the deposit for a buyer, rounded down in favor of the pool
+    pub fn get_bonus_amount(&self, early_buy_quote_amount: u64) -> Result<u64> {
+        if self.total_eligible_quote_amount == 0 {
+            return Ok(0);
+        }
+        safe_mul_div_cast_u64(
+            self.total_deposit,
+            early_buy_quote_amount,
+            self.total_eligible_quote_amount,
+            Rounding::Down,
+        )
+    }
+
+    pub fn claim(&mut self, amount: u64) -> Result<()> {
+        self.total_claimed = self.total_claimed.safe_add(amount)?;
+        Ok(())
+    }
+
+    /// Whether the whole deposit was claimed or withdrawn
+    pub fn is_settled(&self) -> bool {
+        self.total_claimed >= self.total_deposit
+    }
+
+    /// The whole deposit, only withdrawable when nobody qualified for the bonus
+    pub fn withdraw_unallocated(&mut self) -> u64 {
+        let amount = self.total_deposit.saturating_sub(self.total_claimed);
+        self.total_claimed = self.total_deposit;
+        amount
+    }
+}
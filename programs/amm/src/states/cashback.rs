@@ -1,4 +1,6 @@
-use crate::{constants::cashback::*, errors::AmmError};
+use crate::{
+    constants::cashback::*, errors::AmmError, safe_math::SafeMath, states::CashbackConfig,
+};
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::clock::Clock;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
@@ -45,40 +47,220 @@ impl Default for CashbackTier {
     }
 }
 
+/// Where the cashback of swaps on WSOL quoted curves accrues
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum CashbackMode {
+    /// in the WSOL vault (ATA) of the cashback account, like every other quote mint
+    #[default]
+    Token,
+    /// as lamports on the cashback account itself, native SOL swaps then need no cashback
+    /// token account. Other quote mints still accrue in their vaults
+    Native,
+}
+
 #[account(zero_copy)]
 #[derive(InitSpace, Debug, Default)]
 pub struct CashbackAccount {
     /// owner of the cashback account
     pub owner: Pubkey,
-    /// current cashback tier - updated by admin off-chain based on trading volume
+    /// current cashback tier - refreshed from the rolling volume, or overridden by admin
     pub current_tier: u8,
-    pub _padding: [u8; 7], // padding to align the struct size to 64 bytes
+    /// cashback mode of swaps on WSOL quoted curves, chosen by the owner
+    pub cashback_mode: u8,
+    pub _padding: [u8; 6], // padding to align the struct size to 64 bytes
     /// unix timestamp of last claim
     pub last_claim_timestamp: i64,
+    /// unix timestamp of last swap made with this cashback account, keeps the account from
+    /// being reclaimed as inactive
+    pub last_trade_timestamp: i64,
+    /// unix timestamp of last tier change, by admin or decay
+    pub tier_updated_timestamp: i64,
+    /// lifetime cashback paid into the cashback token account
+    pub total_earned: u64,
+    /// lifetime cashback claimed out of the cashback token account, relayer tips included
+    pub total_claimed: u64,
+    /// swaps made with this cashback account
+    pub trade_count: u64,
+    /// quote traded with this cashback account, fees included
+    pub volume_quote: u64,
+    /// unix timestamp the next claim is allowed at, given the tier and the cooldowns of the
+    /// cashback config when it was last written. Kept for display, claims check the cooldown
+    /// of the current cashback config
+    pub next_claim_timestamp: i64,
+    /// day of the newest volume bucket, unix timestamp divided by `CASHBACK_VOLUME_BUCKET_SECONDS`
+    pub volume_bucket_day: i64,
+    /// quote traded each day of the rolling window, fees included. Indexed by day modulo the
+    /// bucket count
+    pub volume_buckets: [u64; CASHBACK_VOLUME_BUCKET_COUNT],
+    /// sequence number of the last sequenced admin tier update, 0 before the first one
+    pub tier_update_seq: u64,
 }
 
 impl CashbackAccount {
     /// Initialize a cashback account if it hasn't been initialized yet
-    pub fn init(&mut self, owner: Pubkey) -> Result<()> {
+    pub fn init(&mut self, owner: Pubkey, cashback_config: &CashbackConfig) -> Result<()> {
         let clock = Clock::get()?;
 
         self.owner = owner;
         self.current_tier = CashbackTier::default().into();
-        self.last_claim_timestamp = clock.unix_timestamp; // Set to current time to enforce 7-day wait
+        self.last_claim_timestamp = clock.unix_timestamp; // Set to current time to enforce the claim cooldown
+        self.last_trade_timestamp = clock.unix_timestamp;
+        self.tier_updated_timestamp = clock.unix_timestamp;
+        self.update_next_claim_timestamp(cashback_config)?;
 
         Ok(())
     }
 
     /// Update the tier (admin only)
-    pub fn update_tier(&mut self, new_tier: u8) -> Result<()> {
+    pub fn update_tier(&mut self, new_tier: u8, cashback_config: &CashbackConfig) -> Result<()> {
+        let clock = Clock::get()?;
         self.current_tier = new_tier;
+        self.tier_updated_timestamp = clock.unix_timestamp;
+        self.update_next_claim_timestamp(cashback_config)
+    }
+
+    /// Accept the sequence number of an admin tier update, a retried or reordered update
+    /// carries a sequence number that isn't above the last one
+    pub fn record_tier_update_seq(&mut self, update_seq: u64) -> Result<()> {
+        require!(update_seq > self.tier_update_seq, AmmError::StaleTierUpdate);
+        self.tier_update_seq = update_seq;
+        Ok(())
+    }
+
+    /// Record a swap, which keeps the tier from decaying
+    pub fn record_trade(
+        &mut self,
+        current_timestamp: i64,
+        quote_volume: u64,
+        cashback_amount: u64,
+    ) -> Result<()> {
+        self.last_trade_timestamp = current_timestamp;
+        self.trade_count = self.trade_count.safe_add(1)?;
+        self.volume_quote = self.volume_quote.safe_add(quote_volume)?;
+        self.total_earned = self.total_earned.safe_add(cashback_amount)?;
+
+        // the newest bucket takes the volume, even if the clock went back a day
+        self.roll_volume_buckets(get_volume_bucket_day(current_timestamp));
+        let bucket = &mut self.volume_buckets[get_volume_bucket_index(self.volume_bucket_day)];
+        *bucket = bucket.safe_add(quote_volume)?;
         Ok(())
     }
 
-    /// Update last claim timestamp
-    pub fn update_claim_timestamp(&mut self) -> Result<()> {
+    /// Quote traded over the rolling window ending at `current_timestamp`
+    pub fn get_rolling_volume(&self, current_timestamp: i64) -> Result<u64> {
+        let oldest_day = get_volume_bucket_day(current_timestamp)
+            .safe_sub(CASHBACK_VOLUME_BUCKET_COUNT as i64 - 1)?;
+        let mut rolling_volume = 0u64;
+        for age in 0..CASHBACK_VOLUME_BUCKET_COUNT as i64 {
+            let day = self.volume_bucket_day - age;
+            if day < oldest_day {
+                break;
+            }
+            rolling_volume =
+                rolling_volume.safe_add(self.volume_buckets[get_volume_bucket_index(day)])?;
+        }
+        Ok(rolling_volume)
+    }
+
+    /// Set the tier to the one the rolling volume reaches, up or down, returns the new tier
+    pub fn refresh_tier(
+        &mut self,
+        current_timestamp: i64,
+        cashback_config: &CashbackConfig,
+    ) -> Result<u8> {
+        let rolling_volume = self.get_rolling_volume(current_timestamp)?;
+        let new_tier: u8 = cashback_config.get_tier_for_volume(rolling_volume)?.into();
+        if new_tier != self.current_tier {
+            self.current_tier = new_tier;
+            self.tier_updated_timestamp = current_timestamp;
+        }
+        self.update_next_claim_timestamp(cashback_config)?;
+        Ok(self.current_tier)
+    }
+
+    /// Move the newest bucket to `day`, clearing the buckets of the days in between
+    fn roll_volume_buckets(&mut self, day: i64) {
+        if day <= self.volume_bucket_day {
+            return;
+        }
+        let elapsed_days = (day - self.volume_bucket_day).min(CASHBACK_VOLUME_BUCKET_COUNT as i64);
+        for offset in 0..elapsed_days {
+            self.volume_buckets[get_volume_bucket_index(day - offset)] = 0;
+        }
+        self.volume_bucket_day = day;
+    }
+
+    /// Drop the tier by one level after `CASHBACK_TIER_DECAY_SECONDS` without swaps or tier
+    /// changes, returns the new tier
+    pub fn decay_tier(
+        &mut self,
+        current_timestamp: i64,
+        cashback_config: &CashbackConfig,
+    ) -> Result<u8> {
+        let last_activity = self.last_trade_timestamp.max(self.tier_updated_timestamp);
+        require!(
+            current_timestamp - last_activity >= CASHBACK_TIER_DECAY_SECONDS,
+            AmmError::AccountNotInactive
+        );
+
+        let tier: u8 = self.get_tier()?.into();
+        require!(
+            tier > CashbackTier::Wood.into(),
+            AmmError::CashbackTierAtMinimum
+        );
+
+        self.current_tier = tier - 1;
+        self.tier_updated_timestamp = current_timestamp;
+        self.update_next_claim_timestamp(cashback_config)?;
+        Ok(self.current_tier)
+    }
+
+    /// Record a claim of `amount` and update the last claim timestamp
+    pub fn record_claim(&mut self, amount: u64, cashback_config: &CashbackConfig) -> Result<()> {
         let clock = Clock::get()?;
         self.last_claim_timestamp = clock.unix_timestamp;
+        self.total_claimed = self.total_claimed.safe_add(amount)?;
+        self.update_next_claim_timestamp(cashback_config)
+    }
+
+    /// Unix timestamp the tier's claim cooldown of `cashback_config` ends at
+    pub fn get_next_claim_timestamp(&self, cashback_config: &CashbackConfig) -> Result<i64> {
+        let claim_cooldown = cashback_config.get_claim_cooldown(self.get_tier()?);
+        Ok(self.last_claim_timestamp.safe_add(claim_cooldown)?)
+    }
+
+    /// Whether the claim cooldown has ended at `current_timestamp`. Every quote mint vault is
+    /// claimed separately, claims of another vault at the timestamp of the last claim count as
+    /// the same claim
+    pub fn can_claim(
+        &self,
+        current_timestamp: i64,
+        cashback_config: &CashbackConfig,
+    ) -> Result<bool> {
+        let same_claim = self.total_claimed > 0 && current_timestamp == self.last_claim_timestamp;
+        Ok(same_claim || current_timestamp >= self.get_next_claim_timestamp(cashback_config)?)
+    }
+
+    /// Whether the owner neither claimed nor swapped with the account for the inactive period,
+    /// an active trader who never claims can't have the cashback reclaimed
+    pub fn is_inactive(&self, current_timestamp: i64) -> bool {
+        let last_activity_timestamp = self.last_claim_timestamp.max(self.last_trade_timestamp);
+        current_timestamp.saturating_sub(last_activity_timestamp) >= CASHBACK_INACTIVE_PERIOD
+    }
+
+    fn update_next_claim_timestamp(&mut self, cashback_config: &CashbackConfig) -> Result<()> {
+        self.next_claim_timestamp = self.get_next_claim_timestamp(cashback_config)?;
         Ok(())
     }
 
@@ -93,4 +275,124 @@ impl CashbackAccount {
 
         CashbackTier::try_from(tier_value).map_err(|_| error!(AmmError::InvalidCashbackTier))
     }
+
+    pub fn get_cashback_mode(&self) -> Result<CashbackMode> {
+        CashbackMode::try_from(self.cashback_mode)
+            .map_err(|_| error!(AmmError::InvalidCashbackMode))
+    }
+}
+
+fn get_volume_bucket_day(timestamp: i64) -> i64 {
+    timestamp.div_euclid(CASHBACK_VOLUME_BUCKET_SECONDS)
+}
+
+fn get_volume_bucket_index(day: i64) -> usize {
+    day.rem_euclid(CASHBACK_VOLUME_BUCKET_COUNT as i64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = CASHBACK_VOLUME_BUCKET_SECONDS;
+
+    #[test]
+    fn rolling_volume_drops_days_out_of_the_window() {
+        let window = CASHBACK_VOLUME_BUCKET_COUNT as i64;
+        let start = 20_000 * DAY;
+        let mut cashback = CashbackAccount::default();
+
+        cashback.record_trade(start, 100, 1).unwrap();
+        cashback.record_trade(start + DAY / 2, 50, 1).unwrap();
+        cashback.record_trade(start + 3 * DAY, 10, 1).unwrap();
+        assert_eq!(cashback.get_rolling_volume(start + 3 * DAY).unwrap(), 160);
+        assert_eq!(cashback.volume_quote, 160);
+
+        // the first day leaves the window, the bucket it shares with today is cleared
+        let today = start + window * DAY;
+        assert_eq!(cashback.get_rolling_volume(today).unwrap(), 10);
+        cashback.record_trade(today, 1, 1).unwrap();
+        assert_eq!(cashback.get_rolling_volume(today).unwrap(), 11);
+
+        // nothing traded within the window
+        assert_eq!(
+            cashback.get_rolling_volume(today + window * DAY).unwrap(),
+            0
+        );
+        cashback
+            .record_trade(today + 2 * window * DAY, 5, 1)
+            .unwrap();
+        assert_eq!(cashback.volume_buckets.iter().sum::<u64>(), 5);
+        assert_eq!(cashback.trade_count, 5);
+    }
+
+    #[test]
+    fn refresh_tier_follows_the_rolling_volume() {
+        let cashback_config = CashbackConfig {
+            tier_volume_thresholds: [0, 10, 20, 30, 40, 50, 60],
+            ..Default::default()
+        };
+        let now = 20_000 * DAY;
+        let mut cashback = CashbackAccount::default();
+
+        cashback.record_trade(now, 25, 0).unwrap();
+        assert_eq!(
+            cashback.refresh_tier(now, &cashback_config).unwrap(),
+            u8::from(CashbackTier::Silver)
+        );
+        assert_eq!(cashback.tier_updated_timestamp, now);
+
+        let later = now + CASHBACK_VOLUME_BUCKET_COUNT as i64 * DAY;
+        assert_eq!(
+            cashback.refresh_tier(later, &cashback_config).unwrap(),
+            u8::from(CashbackTier::Wood)
+        );
+    }
+
+    #[test]
+    fn vaults_of_every_quote_mint_share_the_claim_cooldown() {
+        let cashback_config = CashbackConfig {
+            claim_cooldown_seconds: [7 * DAY; CASHBACK_TIER_COUNT],
+            ..Default::default()
+        };
+        let now = 20_000 * DAY;
+        let mut cashback = CashbackAccount {
+            last_claim_timestamp: now,
+            ..Default::default()
+        };
+
+        // nothing claimed yet, the cooldown runs from the account creation
+        assert!(!cashback.can_claim(now, &cashback_config).unwrap());
+
+        // another vault claimed along the first one
+        cashback.total_claimed = 1;
+        assert!(cashback.can_claim(now, &cashback_config).unwrap());
+        assert!(!cashback.can_claim(now + 1, &cashback_config).unwrap());
+        assert!(cashback.can_claim(now + 7 * DAY, &cashback_config).unwrap());
+    }
+
+    #[test]
+    fn recent_swaps_keep_the_account_active() {
+        let mut cashback_account = CashbackAccount::default();
+        assert!(cashback_account.is_inactive(CASHBACK_INACTIVE_PERIOD));
+        assert!(!cashback_account.is_inactive(CASHBACK_INACTIVE_PERIOD - 1));
+
+        // never claimed but still trading
+        cashback_account
+            .record_trade(CASHBACK_INACTIVE_PERIOD, 1_000, 10)
+            .unwrap();
+        assert!(!cashback_account.is_inactive(CASHBACK_INACTIVE_PERIOD + 1));
+        assert!(cashback_account.is_inactive(2 * CASHBACK_INACTIVE_PERIOD));
+    }
+
+    #[test]
+    fn tier_updates_need_increasing_sequence_numbers() {
+        let mut cashback_account = CashbackAccount::default();
+        cashback_account.record_tier_update_seq(2).unwrap();
+        // a retry of the same update and an older one arriving late are both rejected
+        assert!(cashback_account.record_tier_update_seq(2).is_err());
+        assert!(cashback_account.record_tier_update_seq(1).is_err());
+        cashback_account.record_tier_update_seq(5).unwrap();
+        assert_eq!(cashback_account.tier_update_seq, 5);
+    }
 }
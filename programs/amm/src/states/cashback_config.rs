@@ -0,0 +1,209 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        cashback::{CASHBACK_TIER_COUNT, MAX_CASHBACK_CLAIM_COOLDOWN},
+        fee::MAX_FEE_BASIS_POINTS,
+    },
+    errors::AmmError,
+    states::CashbackTier,
+};
+
+/// Protocol wide cashback settings, a single PDA set by the admin. Cashback accounts are created,
+/// claimed and re-tiered against it
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct CashbackConfig {
+    /// seconds between two claims, indexed by cashback tier
+    pub claim_cooldown_seconds: [i64; CASHBACK_TIER_COUNT],
+    /// rolling quote volume a cashback account needs to reach each tier, indexed by cashback tier
+    pub tier_volume_thresholds: [u64; CASHBACK_TIER_COUNT],
+    /// trading fee discount of each tier in bps of `FEE_DENOMINATOR`, taken off the protocol's
+    /// part of the fee, indexed by cashback tier
+    pub fee_discount_basis_points: [u16; CASHBACK_TIER_COUNT],
+    pub _padding_0: [u8; 2],
+}
+
+/// Cashback tier of a trader with the trading fee discount of the tier
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CashbackFeeTier {
+    pub tier: CashbackTier,
+    /// in bps of `FEE_DENOMINATOR`, zero without a cashback config
+    pub fee_discount_basis_points: u16,
+}
+
+impl From<CashbackTier> for CashbackFeeTier {
+    fn from(tier: CashbackTier) -> Self {
+        Self {
+            tier,
+            fee_discount_basis_points: 0,
+        }
+    }
+}
+
+impl CashbackConfig {
+    /// Cooldowns are bounded and never longer for a higher tier
+    pub fn validate_claim_cooldowns(
+        claim_cooldown_seconds: &[i64; CASHBACK_TIER_COUNT],
+    ) -> Result<()> {
+        require!(
+            claim_cooldown_seconds
+                .iter()
+                .all(|cooldown| (0..=MAX_CASHBACK_CLAIM_COOLDOWN).contains(cooldown))
+                && claim_cooldown_seconds
+                    .windows(2)
+                    .all(|pair| pair[0] >= pair[1]),
+            AmmError::InvalidClaimCooldown
+        );
+        Ok(())
+    }
+
+    /// Wood needs no volume, every higher tier strictly more than the one below
+    pub fn validate_tier_volume_thresholds(
+        tier_volume_thresholds: &[u64; CASHBACK_TIER_COUNT],
+    ) -> Result<()> {
+        require!(
+            tier_volume_thresholds[0] == 0
+                && tier_volume_thresholds
+                    .windows(2)
+                    .all(|pair| pair[0] < pair[1]),
+            AmmError::InvalidTierVolumeThresholds
+        );
+        Ok(())
+    }
+
+    /// Discounts are bounded and never smaller for a higher tier
+    pub fn validate_fee_discounts(
+        fee_discount_basis_points: &[u16; CASHBACK_TIER_COUNT],
+    ) -> Result<()> {
+        require!(
+            fee_discount_basis_points
+                .iter()
+                .all(|discount| *discount <= MAX_FEE_BASIS_POINTS)
+                && fee_discount_basis_points
+                    .windows(2)
+                    .all(|pair| pair[0] <= pair[1]),
+            AmmError::InvalidFeeDiscount
+        );
+        Ok(())
+    }
+
+    pub fn get_claim_cooldown(&self, tier: CashbackTier) -> i64 {
+        self.claim_cooldown_seconds[u8::from(tier) as usize]
+    }
+
+    pub fn get_fee_tier(&self, tier: CashbackTier) -> CashbackFeeTier {
+        CashbackFeeTier {
+            tier,
+            fee_discount_basis_points: self.fee_discount_basis_points[u8::from(tier) as usize],
+        }
+    }
+
+    /// Highest tier whose volume threshold `rolling_volume` reaches
+    pub fn get_tier_for_volume(&self, rolling_volume: u64) -> Result<CashbackTier> {
+        let tier = self
+            .tier_volume_thresholds
+            .iter()
+            .rposition(|threshold| rolling_volume >= *threshold)
+            .unwrap_or_default();
+        CashbackTier::try_from(tier as u8).map_err(|_| error!(AmmError::InvalidCashbackTier))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    #[test]
+    fn claim_cooldowns_are_bounded_and_non_increasing() {
+        let mut claim_cooldown_seconds = [7 * DAY; CASHBACK_TIER_COUNT];
+        claim_cooldown_seconds[CASHBACK_TIER_COUNT - 1] = DAY;
+        assert!(CashbackConfig::validate_claim_cooldowns(&claim_cooldown_seconds).is_ok());
+
+        let cashback_config = CashbackConfig {
+            claim_cooldown_seconds,
+            ..Default::default()
+        };
+        assert_eq!(
+            cashback_config.get_claim_cooldown(CashbackTier::Wood),
+            7 * DAY
+        );
+        assert_eq!(
+            cashback_config.get_claim_cooldown(CashbackTier::Champion),
+            DAY
+        );
+
+        // a higher tier can't wait longer than a lower one
+        claim_cooldown_seconds[0] = 0;
+        assert!(CashbackConfig::validate_claim_cooldowns(&claim_cooldown_seconds).is_err());
+
+        assert!(CashbackConfig::validate_claim_cooldowns(&[-1; CASHBACK_TIER_COUNT]).is_err());
+        assert!(CashbackConfig::validate_claim_cooldowns(
+            &[MAX_CASHBACK_CLAIM_COOLDOWN + 1; CASHBACK_TIER_COUNT]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn tier_follows_the_volume_thresholds() {
+        let tier_volume_thresholds = [0, 10, 20, 30, 40, 50, 60];
+        assert!(CashbackConfig::validate_tier_volume_thresholds(&tier_volume_thresholds).is_ok());
+        // unset thresholds would put everyone at Champion
+        assert!(
+            CashbackConfig::validate_tier_volume_thresholds(&[0; CASHBACK_TIER_COUNT]).is_err()
+        );
+        assert!(
+            CashbackConfig::validate_tier_volume_thresholds(&[1, 10, 20, 30, 40, 50, 60]).is_err()
+        );
+
+        let cashback_config = CashbackConfig {
+            tier_volume_thresholds,
+            ..Default::default()
+        };
+        assert_eq!(
+            cashback_config.get_tier_for_volume(0).unwrap(),
+            CashbackTier::Wood
+        );
+        assert_eq!(
+            cashback_config.get_tier_for_volume(19).unwrap(),
+            CashbackTier::Bronze
+        );
+        assert_eq!(
+            cashback_config.get_tier_for_volume(20).unwrap(),
+            CashbackTier::Silver
+        );
+        assert_eq!(
+            cashback_config.get_tier_for_volume(u64::MAX).unwrap(),
+            CashbackTier::Champion
+        );
+    }
+
+    #[test]
+    fn fee_discounts_are_bounded_and_non_decreasing() {
+        let fee_discount_basis_points = [0, 0, 50, 100, 150, 200, 300];
+        assert!(CashbackConfig::validate_fee_discounts(&fee_discount_basis_points).is_ok());
+        assert!(CashbackConfig::validate_fee_discounts(&[0; CASHBACK_TIER_COUNT]).is_ok());
+        // a higher tier can't get a smaller discount than a lower one
+        assert!(
+            CashbackConfig::validate_fee_discounts(&[100, 50, 50, 100, 150, 200, 300]).is_err()
+        );
+        assert!(CashbackConfig::validate_fee_discounts(
+            &[MAX_FEE_BASIS_POINTS + 1; CASHBACK_TIER_COUNT]
+        )
+        .is_err());
+
+        let cashback_config = CashbackConfig {
+            fee_discount_basis_points,
+            ..Default::default()
+        };
+        assert_eq!(
+            cashback_config.get_fee_tier(CashbackTier::Gold),
+            CashbackFeeTier {
+                tier: CashbackTier::Gold,
+                fee_discount_basis_points: 100,
+            }
+        );
+    }
+}
@@ -1,12 +1,22 @@
 use anchor_lang::prelude::*;
 use num_enum::{IntoPrimitive, TryFromPrimitive};
+use ruint::aliases::U256;
 
 use crate::{
-    constants::fee::FEE_DENOMINATOR,
+    amount::{BaseAmount, QuoteAmount},
+    constants::{
+        config::CONFIG_VERSION, fee::FEE_DENOMINATOR, migration::THRESHOLD_DECAY_STEPS,
+        TOKEN_TOTAL_SUPPLY_WITHOUT_DECIMALS,
+    },
+    errors::AmmError,
     events::EvtCreateConfig,
+    params::{
+        config::{ConfigChangeParameters, ConfigParameters, LockedVestingParams},
+        swap::TradeDirection,
+    },
     safe_math::{safe_mul_div_cast_u64, SafeMath},
-    states::CashbackTier,
-    u128x128_math::Rounding,
+    states::{CashbackFeeTier, TradingPause},
+    u128x128_math::{mul_div_u256, Rounding},
 };
 
 #[repr(u8)]
@@ -25,6 +35,168 @@ pub enum TokenType {
     Token2022,
 }
 
+/// Order of the mints in the DAMM v2 pool created at migration
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum MigrationTokenOrder {
+    /// token a is always the base mint, token b the quote mint
+    BaseQuote,
+    /// token a is the mint with the smaller key, for pool configs that want canonical ordering
+    Sorted,
+}
+
+/// How the migration quote threshold decays with the age of a curve
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum ThresholdDecayMode {
+    /// the threshold never decays
+    None,
+    /// the threshold decays continuously down to the floor
+    Linear,
+    /// the threshold decays in `THRESHOLD_DECAY_STEPS` equal steps down to the floor
+    Stepwise,
+}
+
+/// Fee of the buy leg of a token to token swap, the sell leg always pays the trading fee
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum RoutedSwapFeeMode {
+    /// the buy leg pays the trading fee like any buy
+    Charged,
+    /// the buy leg is free, the quote leg of the route only pays the sell fee
+    Waived,
+}
+
+/// Side of a swap the trading fee is taken from, fees are always paid out in quote
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum FeeCollectionMode {
+    /// buys pay the fee on the quote input, sells on the quote output
+    AlwaysOnQuote,
+    /// the fee is taken from the input, the base fee of a sell is sold into the curve with it
+    FeeOnInput,
+    /// the fee is taken from the output, the base fee of a buy is never bought from the curve
+    FeeOnOutput,
+}
+
+impl FeeCollectionMode {
+    /// Whether the fee of a swap in this direction is taken from its base side
+    pub fn is_fee_on_base(&self, trade_direction: TradeDirection) -> bool {
+        matches!(
+            (self, trade_direction),
+            (FeeCollectionMode::FeeOnInput, TradeDirection::BaseToQuote)
+                | (FeeCollectionMode::FeeOnOutput, TradeDirection::QuoteToBase)
+        )
+    }
+}
+
+/// Reserves the migration fee is kept from, the rest of the reserves is deposited into the pool
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum MigrationFeeMode {
+    /// the fee is kept from both reserves, the base fee is left over in the base vault
+    BothSides,
+    /// the fee is only kept from the quote reserve, the whole base reserve goes into the pool
+    QuoteOnly,
+}
+
+/// Fee the rounding remainder of a fee split goes to. Every part of the trading fee is rounded
+/// down on its own, the remainder is what they leave of the trading fee
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum FeeRemainderMode {
+    /// the protocol fee takes the remainder
+    Protocol,
+    /// the cashback fee takes the remainder of swaps paying cashback, the protocol fee otherwise
+    Cashback,
+}
+
+/// Vesting of the creator's locked base tokens, see `LockedVestingParams`
+#[zero_copy]
+#[derive(InitSpace, Debug, Default)]
+pub struct LockedVestingConfig {
+    pub cliff_duration_seconds: u64,
+    pub period_seconds: u64,
+    pub amount_per_period: u64,
+    pub number_of_periods: u64,
+}
+
+impl From<LockedVestingParams> for LockedVestingConfig {
+    fn from(params: LockedVestingParams) -> Self {
+        Self {
+            cliff_duration_seconds: params.cliff_duration_seconds,
+            period_seconds: params.period_seconds,
+            amount_per_period: params.amount_per_period,
+            number_of_periods: params.number_of_periods,
+        }
+    }
+}
+
+impl From<LockedVestingConfig> for LockedVestingParams {
+    fn from(config: LockedVestingConfig) -> Self {
+        Self {
+            cliff_duration_seconds: config.cliff_duration_seconds,
+            period_seconds: config.period_seconds,
+            amount_per_period: config.amount_per_period,
+            number_of_periods: config.number_of_periods,
+        }
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct ProtocolAuthorityArgs {
     pub global_authority: Option<Pubkey>,
@@ -34,13 +206,83 @@ pub struct ProtocolAuthorityArgs {
 /// Encodes all results of swapping
 #[derive(Debug, PartialEq)]
 pub struct FeeBreakdown {
-    pub amount: u64,
-    pub l1_referral_fee: u64, // Goes to referrer's cashback account
-    pub l2_referral_fee: u64, // Goes to referrer's cashback account
-    pub l3_referral_fee: u64, // Goes to referrer's cashback account
-    pub creator_fee: u64,     // Goes to creator's cashback account
-    pub cashback_fee: u64,    // Goes to trader's cashback account
-    pub protocol_fee: u64,    // Goes to protocol
+    pub amount: QuoteAmount,
+    pub l1_referral_fee: QuoteAmount, // Goes to referrer's cashback account
+    pub l2_referral_fee: QuoteAmount, // Goes to referrer's cashback account
+    pub l3_referral_fee: QuoteAmount, // Goes to referrer's cashback account
+    pub creator_fee: QuoteAmount,     // Goes to creator's cashback account
+    pub cashback_fee: QuoteAmount,    // Goes to trader's cashback account
+    pub protocol_fee: QuoteAmount,    // Goes to protocol
+    /// part of the trading fee left by rounding each part down, already included in the protocol
+    /// or cashback fee per `fee_remainder_mode`
+    pub rounding_remainder: QuoteAmount,
+}
+
+/// Fee rates a wallet pays on the quote side of a swap, in bps of `FEE_DENOMINATOR`
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+pub struct FeeSchedule {
+    /// total trading fee, after the referee discount
+    pub trading_fee_basis_points: u16,
+    /// referee discount taken off the trading fee, zero without a referrer
+    pub referee_discount_basis_points: u16,
+    /// cashback tier discount taken off the trading fee, zero without a cashback config
+    pub fee_discount_basis_points: u16,
+    /// part of the trading fee paid back to the wallet's cashback account
+    pub cashback_basis_points: u16,
+    /// part of the trading fee paid to each level of the referrer chain
+    pub l1_referral_fee_basis_points: u16,
+    pub l2_referral_fee_basis_points: u16,
+    pub l3_referral_fee_basis_points: u16,
+    /// part of the trading fee paid to the creator
+    pub creator_fee_basis_points: u16,
+    /// rest of the trading fee, kept by the protocol
+    pub protocol_fee_basis_points: u16,
+}
+
+/// Layout of the keypair configs created before configs were PDAs, only read to migrate them
+#[zero_copy]
+#[derive(InitSpace, Debug, Default)]
+pub struct LegacyConfig {
+    pub quote_mint: Pubkey,
+    pub fee_claimer: Pubkey,
+
+    /* Token configurations */
+    pub base_token_flag: u8,
+    pub quote_token_flag: u8,
+    pub base_decimal: u8,
+    pub quote_decimal: u8,
+    pub _padding_1: [u8; 4],
+
+    /* Fee configurations */
+    pub fee_basis_points: u16,
+    pub l1_referral_fee_basis_points: u16,
+    pub l2_referral_fee_basis_points: u16,
+    pub l3_referral_fee_basis_points: u16,
+    pub referee_discount_basis_points: u16,
+    pub creator_fee_basis_points: u16,
+    pub migration_fee_basis_points: u16,
+    pub _padding_2: [u16; 1],
+
+    /* Price configurations */
+    pub migration_base_threshold: u64,
+    pub migration_quote_threshold: u64,
+    pub initial_virtual_quote_reserve: u64,
+    pub initial_virtual_base_reserve: u64,
+    pub _padding_3: [u64; 4],
+}
+
+impl LegacyConfig {
+    /// Read the legacy fields from the data of a config account, discriminator included
+    pub fn try_from_account_data(data: &[u8]) -> Result<Self> {
+        let start = Config::DISCRIMINATOR.len();
+        require!(
+            data.starts_with(Config::DISCRIMINATOR) && data.len() >= start + Self::INIT_SPACE,
+            AmmError::InvalidAccount
+        );
+        Ok(bytemuck::pod_read_unaligned(
+            &data[start..start + Self::INIT_SPACE],
+        ))
+    }
 }
 
 #[account(zero_copy)]
@@ -50,6 +292,13 @@ pub struct Config {
     pub quote_mint: Pubkey,
     /// fee claimer
     pub fee_claimer: Pubkey,
+    /// approved DAMM v2 config for migrations of this config's curves
+    pub damm_config: Pubkey,
+    /// receiver of the base tokens left in a curve's vault after migration, all zeros burns them
+    pub leftover_receiver: Pubkey,
+    /// hash of the admin-published curve creation template, creations have to commit to it.
+    /// All zeros when the config has no template
+    pub curve_template_hash: [u8; 32],
 
     /* Token configurations */
     /// base token flag (0 | 1), 0: SPL Token, 1: Token2022
@@ -60,8 +309,18 @@ pub struct Config {
     pub base_decimal: u8,
     /// quote token decimal, (6 | 9)
     pub quote_decimal: u8,
-    /// padding 1
-    _padding_1: [u8; 4],
+    /// mint order of the migrated pool (0 | 1), 0: base/quote, 1: sorted by key
+    pub migration_token_order: u8,
+    /// trading pause of every curve of this config (0: None, 1: Buys, 2: Sells, 3: All)
+    pub trading_pause: u8,
+    /// migration quote threshold decay mode (0: None, 1: Linear, 2: Stepwise)
+    pub threshold_decay_mode: u8,
+    /// whether anyone can crank the migration of a complete curve (0 | 1)
+    pub permissionless_migration: u8,
+    /// fee of the buy leg of token to token swaps into this config's curves (0: Charged, 1: Waived)
+    pub routed_swap_fee_mode: u8,
+    /// side of a swap the trading fee is taken from (0: AlwaysOnQuote, 1: FeeOnInput, 2: FeeOnOutput)
+    pub fee_collection_mode: u8,
 
     /* Fee configurations */
     /// Trading fee in bps
@@ -78,8 +337,18 @@ pub struct Config {
     pub creator_fee_basis_points: u16,
     /// migration fee in bps (quote token fee)
     pub migration_fee_basis_points: u16,
-    /// padding 2
-    _padding_2: [u16; 1],
+    /// share of the protocol fee routed to the insurance fund, in bps
+    pub insurance_fund_basis_points: u16,
+    /// protocol's cut of creator fee claims, in bps
+    pub creator_fee_protocol_basis_points: u16,
+    /// floor of a decaying migration quote threshold, in bps of the threshold
+    pub threshold_decay_floor_basis_points: u16,
+    /// share of the migration fee routed to the curve's support fund, in bps
+    pub support_fund_basis_points: u16,
+    /// share of the tier cashback paid on buys, in bps of `FEE_DENOMINATOR`
+    pub buy_cashback_multiplier_basis_points: u32,
+    /// share of the tier cashback paid on sells, in bps of `FEE_DENOMINATOR`
+    pub sell_cashback_multiplier_basis_points: u32,
 
     /* Price configurations */
     /// migration base threshold (the amount of token to migrate)
@@ -90,65 +359,271 @@ pub struct Config {
     pub initial_virtual_quote_reserve: u64,
     /// initial virtual base reserve to boost the initial liquidity
     pub initial_virtual_base_reserve: u64,
-    /// padding, but we can also use them for future uses.
-    _padding_3: [u64; 4],
+    /// monotonic sequence number of the last event emitted under this config
+    pub event_sequence: u64,
+
+    /* Migration configurations */
+    /// seconds to wait after the curve completes before it can be migrated
+    pub pre_migration_cooldown_seconds: u64,
+    /// quote paid from the curve's protocol fee to whoever cranks a permissionless migration
+    pub migration_crank_reward: u64,
+    /// quote fee the creator is guaranteed by graduation, shortfalls are topped up from the
+    /// migration fee. 0 disables the guarantee
+    pub min_creator_fee_amount: u64,
+
+    /* Bonus configurations */
+    /// seconds after curve creation during which buys are eligible for the creator bonus pool
+    pub early_buyer_window_seconds: u64,
+
+    /* Protection configurations */
+    /// seconds a wallet has to hold after a buy before it can sell, 0 disables the lockup
+    pub min_hold_seconds: u64,
+    /// slots after curve creation during which buys of a wallet are capped, 0 disables the cap
+    pub anti_snipe_window_slots: u64,
+    /// max quote amount a wallet can spend on buys during the anti-snipe window
+    pub max_buy_amount_first_window: u64,
+    /// seconds after a curve's launch for its trading fee to decay from the launch fee to
+    /// `fee_basis_points`
+    pub launch_fee_decay_seconds: u64,
+
+    /* Governance configurations */
+    /// minimum delay between proposing and executing a fee or migration parameter change
+    pub param_change_delay_seconds: u64,
+    /// seconds after curve creation for the migration quote threshold to decay to its floor
+    pub threshold_decay_seconds: u64,
+
+    /* Vesting configurations */
+    /// vesting of the creator's locked base tokens
+    pub locked_vesting_config: LockedVestingConfig,
+
+    /* Versioning */
+    /// layout version of the account, see `CONFIG_VERSION`
+    pub version: u8,
+    /// whether the config is a legacy keypair account migrated to the current layout (0 | 1)
+    pub is_legacy: u8,
+    /// index of the config among the PDA configs of its quote mint, 0 for legacy configs
+    pub index: u16,
+    /// emergency pause of the config (0 | 1), halts curve creation and every swap of its curves
+    pub paused: u8,
+    /// whether the migration CPIs of its curves log `EvtCpiTelemetry` (0 | 1)
+    pub cpi_telemetry: u8,
+    /// highest share of the DAMM position fees a curve creator can pick at creation, in bps
+    pub max_creator_lp_fee_share_basis_points: u16,
+    /// share of the migrated liquidity put in a second, unlocked position handed to the curve
+    /// creator, in bps. 0 migrates everything into the locked first position
+    pub creator_lp_basis_points: u16,
+    /// reserves the migration fee is kept from (0: BothSides, 1: QuoteOnly)
+    pub migration_fee_mode: u8,
+    /// whether only creators with a `WhitelistEntry` on the config can create curves (0 | 1)
+    pub creator_whitelist_mode: u8,
+    /// trading fee in bps of a freshly launched curve, 0 disables the launch fee
+    pub launch_fee_basis_points: u16,
+    /// number of equal steps the launch fee decays in, 0 decays it linearly
+    pub launch_fee_periods: u16,
+    /// fee the rounding remainder of a fee split goes to (0: Protocol, 1: Cashback)
+    pub fee_remainder_mode: u8,
+    /// padding 0
+    pub _padding_0: [u8; 7],
 }
 
 impl Config {
+    pub fn has_curve_template(&self) -> bool {
+        self.curve_template_hash != [0u8; 32]
+    }
+
+    pub fn is_creator_whitelist_mode(&self) -> bool {
+        self.creator_whitelist_mode != 0
+    }
+
+    /// In creator whitelist mode only creators with a whitelist entry on the config create curves
+    pub fn validate_creator_whitelisted(&self, has_whitelist_entry: bool) -> Result<()> {
+        require!(
+            !self.is_creator_whitelist_mode() || has_whitelist_entry,
+            AmmError::CreatorNotWhitelisted
+        );
+        Ok(())
+    }
+
+    pub fn has_leftover_receiver(&self) -> bool {
+        self.leftover_receiver != Pubkey::default()
+    }
+
     pub fn init(
         &mut self,
         quote_mint: &Pubkey,
         fee_claimer: &Pubkey,
-
-        /* Token configurations */
-        base_token_flag: u8,
+        damm_config: &Pubkey,
         quote_token_flag: u8,
-        base_decimal: u8,
         quote_decimal: u8,
-
-        /* Fee configurations */
-        fee_basis_points: u16,
-        l1_referral_fee_basis_points: u16,
-        l2_referral_fee_basis_points: u16,
-        l3_referral_fee_basis_points: u16,
-        referee_discount_basis_points: u16,
-        creator_fee_basis_points: u16,
-        migration_fee_basis_points: u16,
-
-        /* Price configurations */
-        migration_base_threshold: u64,
-        migration_quote_threshold: u64,
-        initial_virtual_quote_reserve: u64,
-        initial_virtual_base_reserve: u64,
+        params: &ConfigParameters,
     ) {
+        self.version = CONFIG_VERSION;
         self.quote_mint = *quote_mint;
         self.fee_claimer = *fee_claimer;
+        self.damm_config = *damm_config;
 
         /* Token configurations */
-        self.base_token_flag = base_token_flag;
+        self.base_token_flag = params.base_token_flag;
         self.quote_token_flag = quote_token_flag;
-        self.base_decimal = base_decimal;
+        self.base_decimal = params.base_decimal;
         self.quote_decimal = quote_decimal;
 
         /* Fee configurations */
-        self.fee_basis_points = fee_basis_points;
-        self.l1_referral_fee_basis_points = l1_referral_fee_basis_points;
-        self.l2_referral_fee_basis_points = l2_referral_fee_basis_points;
-        self.l3_referral_fee_basis_points = l3_referral_fee_basis_points;
-        self.referee_discount_basis_points = referee_discount_basis_points;
-        self.creator_fee_basis_points = creator_fee_basis_points;
-        self.migration_fee_basis_points = migration_fee_basis_points;
+        self.fee_basis_points = params.fee_basis_points;
+        self.l1_referral_fee_basis_points = params.l1_referral_fee_basis_points;
+        self.l2_referral_fee_basis_points = params.l2_referral_fee_basis_points;
+        self.l3_referral_fee_basis_points = params.l3_referral_fee_basis_points;
+        self.referee_discount_basis_points = params.referee_discount_basis_points;
+        self.creator_fee_basis_points = params.creator_fee_basis_points;
+        self.migration_fee_basis_points = params.migration_fee_basis_points;
+        self.insurance_fund_basis_points = params.insurance_fund_basis_points;
+        self.creator_fee_protocol_basis_points = params.creator_fee_protocol_basis_points;
+        self.support_fund_basis_points = params.support_fund_basis_points;
+        self.max_creator_lp_fee_share_basis_points = params.max_creator_lp_fee_share_basis_points;
+        self.creator_lp_basis_points = params.creator_lp_basis_points;
+        self.buy_cashback_multiplier_basis_points = params.buy_cashback_multiplier_basis_points;
+        self.sell_cashback_multiplier_basis_points = params.sell_cashback_multiplier_basis_points;
+        self.fee_collection_mode = params.fee_collection_mode;
+        self.migration_fee_mode = params.migration_fee_mode;
+        self.fee_remainder_mode = params.fee_remainder_mode;
 
         /* Price configurations */
-        self.migration_base_threshold = migration_base_threshold;
-        self.migration_quote_threshold = migration_quote_threshold;
-        self.initial_virtual_quote_reserve = initial_virtual_quote_reserve;
-        self.initial_virtual_base_reserve = initial_virtual_base_reserve;
+        self.migration_base_threshold = params.migration_base_threshold;
+        self.migration_quote_threshold = params.migration_quote_threshold;
+        self.initial_virtual_quote_reserve = params.initial_virtual_quote_reserve;
+        self.initial_virtual_base_reserve = params.initial_virtual_base_reserve;
+
+        /* Migration configurations */
+        self.pre_migration_cooldown_seconds = params.pre_migration_cooldown_seconds;
+        self.permissionless_migration = params.permissionless_migration;
+        self.migration_crank_reward = params.migration_crank_reward;
+        self.min_creator_fee_amount = params.min_creator_fee_amount;
+        self.migration_token_order = params.migration_token_order;
+        self.routed_swap_fee_mode = params.routed_swap_fee_mode;
+        self.threshold_decay_mode = params.threshold_decay_mode;
+        self.threshold_decay_floor_basis_points = params.threshold_decay_floor_basis_points;
+        self.threshold_decay_seconds = params.threshold_decay_seconds;
+
+        /* Bonus configurations */
+        self.early_buyer_window_seconds = params.early_buyer_window_seconds;
+
+        /* Protection configurations */
+        self.min_hold_seconds = params.min_hold_seconds;
+        self.anti_snipe_window_slots = params.anti_snipe_window_slots;
+        self.max_buy_amount_first_window = params.max_buy_amount_first_window;
+        self.launch_fee_basis_points = params.launch_fee_basis_points;
+        self.launch_fee_periods = params.launch_fee_periods;
+        self.launch_fee_decay_seconds = params.launch_fee_decay_seconds;
+
+        /* Governance configurations */
+        self.param_change_delay_seconds = params.param_change_delay_seconds;
+
+        /* Vesting configurations */
+        self.locked_vesting_config = params.locked_vesting.into();
     }
 
-    pub fn event(&self, config_key: Pubkey) -> EvtCreateConfig {
+    /// Apply a config change that went through the propose/execute timelock
+    pub fn apply_change(&mut self, params: &ConfigChangeParameters) {
+        /* Fee configurations */
+        self.fee_basis_points = params.fee_basis_points;
+        self.l1_referral_fee_basis_points = params.l1_referral_fee_basis_points;
+        self.l2_referral_fee_basis_points = params.l2_referral_fee_basis_points;
+        self.l3_referral_fee_basis_points = params.l3_referral_fee_basis_points;
+        self.referee_discount_basis_points = params.referee_discount_basis_points;
+        self.creator_fee_basis_points = params.creator_fee_basis_points;
+        self.migration_fee_basis_points = params.migration_fee_basis_points;
+
+        /* Migration configurations */
+        self.migration_base_threshold = params.migration_base_threshold;
+        self.migration_quote_threshold = params.migration_quote_threshold;
+        self.pre_migration_cooldown_seconds = params.pre_migration_cooldown_seconds;
+    }
+
+    /// Invariants between the fields of the config, checked on the config a creation or a
+    /// timelocked change leaves behind so neither path can install one the other rejects
+    pub fn validate_invariants(&self) -> Result<()> {
+        require!(
+            self.initial_virtual_quote_reserve > 0
+                && self.initial_virtual_base_reserve > 0
+                && self.migration_base_threshold > 0
+                && self.migration_quote_threshold > 0,
+            AmmError::InvalidAmmConfig
+        );
+
+        // the migration threshold has to leave something to sell on the curve
+        let token_total_supply = self.get_token_total_supply()?;
+        require!(
+            self.migration_base_threshold < token_total_supply,
+            AmmError::InvalidAmmConfig
+        );
+
+        // a fully decayed threshold still has to be above the starting virtual quote reserve
+        if self.get_threshold_decay_mode()? != ThresholdDecayMode::None {
+            let floor: u64 = safe_mul_div_cast_u64(
+                self.migration_quote_threshold,
+                self.threshold_decay_floor_basis_points as u64,
+                FEE_DENOMINATOR,
+                Rounding::Up,
+            )?;
+            require!(
+                floor > self.initial_virtual_quote_reserve,
+                AmmError::InvalidAmmConfig
+            );
+        }
+
+        // the launch fee decays down to the trading fee
+        require!(
+            self.launch_fee_basis_points == 0
+                || self.launch_fee_basis_points > self.fee_basis_points,
+            AmmError::InvalidAmmConfig
+        );
+
+        LockedVestingParams::from(self.locked_vesting_config)
+            .validate(token_total_supply, self.migration_base_threshold)
+    }
+
+    /// Rewrite a legacy keypair config, reallocated to the current layout, from its legacy
+    /// fields. The legacy bytes don't line up with the current fields, everything the legacy
+    /// layout doesn't have starts zeroed
+    pub fn migrate_legacy(&mut self, legacy: &LegacyConfig) -> Result<()> {
+        require!(self.version == 0, AmmError::ConfigAlreadyMigrated);
+        *self = Config {
+            quote_mint: legacy.quote_mint,
+            fee_claimer: legacy.fee_claimer,
+            base_token_flag: legacy.base_token_flag,
+            quote_token_flag: legacy.quote_token_flag,
+            base_decimal: legacy.base_decimal,
+            quote_decimal: legacy.quote_decimal,
+            fee_basis_points: legacy.fee_basis_points,
+            l1_referral_fee_basis_points: legacy.l1_referral_fee_basis_points,
+            l2_referral_fee_basis_points: legacy.l2_referral_fee_basis_points,
+            l3_referral_fee_basis_points: legacy.l3_referral_fee_basis_points,
+            referee_discount_basis_points: legacy.referee_discount_basis_points,
+            creator_fee_basis_points: legacy.creator_fee_basis_points,
+            migration_fee_basis_points: legacy.migration_fee_basis_points,
+            migration_base_threshold: legacy.migration_base_threshold,
+            migration_quote_threshold: legacy.migration_quote_threshold,
+            initial_virtual_quote_reserve: legacy.initial_virtual_quote_reserve,
+            initial_virtual_base_reserve: legacy.initial_virtual_base_reserve,
+            version: CONFIG_VERSION,
+            is_legacy: 1,
+            ..Default::default()
+        };
+        Ok(())
+    }
+
+    /// Bump the event sequence and return the value to attach to the next event
+    pub fn next_event_sequence(&mut self) -> Result<u64> {
+        self.event_sequence = self.event_sequence.safe_add(1)?;
+        Ok(self.event_sequence)
+    }
+
+    pub fn event(&self, config_key: Pubkey, event_sequence: u64) -> EvtCreateConfig {
         EvtCreateConfig {
             config: config_key,
+            event_sequence,
+            index: self.index,
+            version: self.version,
 
             /* Token configurations */
             base_token_flag: self.base_token_flag,
@@ -164,6 +639,16 @@ impl Config {
             l3_referral_fee_basis_points: self.l3_referral_fee_basis_points,
             creator_fee_basis_points: self.creator_fee_basis_points,
             migration_fee_basis_points: self.migration_fee_basis_points,
+            insurance_fund_basis_points: self.insurance_fund_basis_points,
+            creator_fee_protocol_basis_points: self.creator_fee_protocol_basis_points,
+            support_fund_basis_points: self.support_fund_basis_points,
+            max_creator_lp_fee_share_basis_points: self.max_creator_lp_fee_share_basis_points,
+            creator_lp_basis_points: self.creator_lp_basis_points,
+            buy_cashback_multiplier_basis_points: self.buy_cashback_multiplier_basis_points,
+            sell_cashback_multiplier_basis_points: self.sell_cashback_multiplier_basis_points,
+            fee_collection_mode: self.fee_collection_mode,
+            migration_fee_mode: self.migration_fee_mode,
+            fee_remainder_mode: self.fee_remainder_mode,
             fee_claimer: self.fee_claimer,
 
             /* Price configurations */
@@ -171,85 +656,537 @@ impl Config {
             migration_quote_threshold: self.migration_quote_threshold,
             initial_virtual_quote_reserve: self.initial_virtual_quote_reserve,
             initial_virtual_base_reserve: self.initial_virtual_base_reserve,
+
+            /* Migration configurations */
+            pre_migration_cooldown_seconds: self.pre_migration_cooldown_seconds,
+            permissionless_migration: self.permissionless_migration,
+            migration_crank_reward: self.migration_crank_reward,
+            min_creator_fee_amount: self.min_creator_fee_amount,
+            migration_token_order: self.migration_token_order,
+            routed_swap_fee_mode: self.routed_swap_fee_mode,
+            damm_config: self.damm_config,
+            leftover_receiver: self.leftover_receiver,
+            threshold_decay_mode: self.threshold_decay_mode,
+            threshold_decay_floor_basis_points: self.threshold_decay_floor_basis_points,
+            threshold_decay_seconds: self.threshold_decay_seconds,
+
+            /* Bonus configurations */
+            early_buyer_window_seconds: self.early_buyer_window_seconds,
+
+            /* Protection configurations */
+            min_hold_seconds: self.min_hold_seconds,
+            anti_snipe_window_slots: self.anti_snipe_window_slots,
+            max_buy_amount_first_window: self.max_buy_amount_first_window,
+            launch_fee_basis_points: self.launch_fee_basis_points,
+            launch_fee_periods: self.launch_fee_periods,
+            launch_fee_decay_seconds: self.launch_fee_decay_seconds,
+
+            /* Governance configurations */
+            param_change_delay_seconds: self.param_change_delay_seconds,
+
+            /* Vesting configurations */
+            locked_vesting: self.locked_vesting_config.into(),
+        }
+    }
+
+    pub fn get_migration_token_order(&self) -> Result<MigrationTokenOrder> {
+        Ok(MigrationTokenOrder::try_from(self.migration_token_order)
+            .map_err(|_| AmmError::TypeCastFailed)?)
+    }
+
+    pub fn get_threshold_decay_mode(&self) -> Result<ThresholdDecayMode> {
+        Ok(ThresholdDecayMode::try_from(self.threshold_decay_mode)
+            .map_err(|_| AmmError::TypeCastFailed)?)
+    }
+
+    pub fn is_permissionless_migration(&self) -> bool {
+        self.permissionless_migration == 1
+    }
+
+    /// Crank reward of a permissionless migration, capped by the protocol fee the curve holds
+    pub fn get_migration_crank_reward(&self, protocol_fee: u64) -> u64 {
+        self.migration_crank_reward.min(protocol_fee)
+    }
+
+    pub fn get_routed_swap_fee_mode(&self) -> Result<RoutedSwapFeeMode> {
+        Ok(RoutedSwapFeeMode::try_from(self.routed_swap_fee_mode)
+            .map_err(|_| AmmError::TypeCastFailed)?)
+    }
+
+    /// Config the buy leg of a token to token swap is priced with, a waived buy fee zeroes the
+    /// whole fee split so the quote leg is only charged once
+    pub fn get_routed_buy_config(&self) -> Result<Config> {
+        if self.get_routed_swap_fee_mode()? == RoutedSwapFeeMode::Waived {
+            return Ok(self.get_fee_free_config());
+        }
+        Ok(*self)
+    }
+
+    /// Config with the whole fee split zeroed, the allocation of a launch raffle is bought at the
+    /// curve price without a trading fee
+    pub fn get_fee_free_config(&self) -> Config {
+        let mut config = *self;
+        config.fee_basis_points = 0;
+        config.l1_referral_fee_basis_points = 0;
+        config.l2_referral_fee_basis_points = 0;
+        config.l3_referral_fee_basis_points = 0;
+        config.referee_discount_basis_points = 0;
+        config.creator_fee_basis_points = 0;
+        config
+    }
+
+    /// Trading fee in bps of a curve launched `elapsed_seconds` ago. The launch fee decays to
+    /// `fee_basis_points` over `launch_fee_decay_seconds`, linearly or in `launch_fee_periods`
+    /// equal steps
+    pub fn get_launch_fee_basis_points(&self, elapsed_seconds: u64) -> Result<u16> {
+        if self.launch_fee_basis_points <= self.fee_basis_points
+            || elapsed_seconds >= self.launch_fee_decay_seconds
+        {
+            return Ok(self.fee_basis_points);
         }
+        let decayed_seconds = if self.launch_fee_periods == 0 {
+            elapsed_seconds
+        } else {
+            let period_seconds = self.launch_fee_decay_seconds / self.launch_fee_periods as u64;
+            elapsed_seconds / period_seconds * period_seconds
+        };
+
+        let fee_range = self
+            .launch_fee_basis_points
+            .safe_sub(self.fee_basis_points)?;
+        let decay: u16 = safe_mul_div_cast_u64(
+            fee_range as u64,
+            decayed_seconds,
+            self.launch_fee_decay_seconds,
+            Rounding::Down,
+        )?;
+        Ok(self.launch_fee_basis_points.safe_sub(decay)?)
+    }
+
+    /// Config the swaps of a curve launched `elapsed_seconds` ago are priced with, the launch
+    /// fee tops up the trading fee and goes to the protocol
+    pub fn get_launch_fee_config(&self, elapsed_seconds: u64) -> Result<Config> {
+        let mut config = *self;
+        config.fee_basis_points = self.get_launch_fee_basis_points(elapsed_seconds)?;
+        Ok(config)
+    }
+
+    pub fn get_fee_remainder_mode(&self) -> Result<FeeRemainderMode> {
+        Ok(FeeRemainderMode::try_from(self.fee_remainder_mode)
+            .map_err(|_| AmmError::TypeCastFailed)?)
+    }
+
+    /// Adds the rounding remainder of a fee split to the fee `fee_remainder_mode` picks, the
+    /// cashback fee only takes it on swaps paying cashback. Returns the protocol and cashback fees
+    fn assign_fee_remainder(
+        &self,
+        protocol_fee: QuoteAmount,
+        cashback_fee: QuoteAmount,
+        rounding_remainder: QuoteAmount,
+        pays_cashback: bool,
+    ) -> Result<(QuoteAmount, QuoteAmount)> {
+        if pays_cashback && self.get_fee_remainder_mode()? == FeeRemainderMode::Cashback {
+            return Ok((protocol_fee, cashback_fee.safe_add(rounding_remainder)?));
+        }
+        Ok((protocol_fee.safe_add(rounding_remainder)?, cashback_fee))
+    }
+
+    pub fn get_fee_collection_mode(&self) -> Result<FeeCollectionMode> {
+        Ok(FeeCollectionMode::try_from(self.fee_collection_mode)
+            .map_err(|_| AmmError::TypeCastFailed)?)
+    }
+
+    pub fn get_migration_fee_mode(&self) -> Result<MigrationFeeMode> {
+        Ok(MigrationFeeMode::try_from(self.migration_fee_mode)
+            .map_err(|_| AmmError::TypeCastFailed)?)
+    }
+
+    pub fn get_trading_pause(&self) -> Result<TradingPause> {
+        Ok(TradingPause::try_from(self.trading_pause).map_err(|_| AmmError::TypeCastFailed)?)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused != 0
+    }
+
+    pub fn has_cpi_telemetry(&self) -> bool {
+        self.cpi_telemetry != 0
+    }
+
+    /// Total supply minted for every curve of this config, in base token units
+    pub fn get_token_total_supply(&self) -> Result<u64> {
+        get_token_total_supply(self.base_decimal)
+    }
+
+    /// Scale applied to base amounts in the constant product math
+    pub fn get_base_scale(&self) -> Result<u128> {
+        get_base_scale(self.base_decimal, self.quote_decimal)
+    }
+
+    /// Portion of a protocol fee claim that goes to the insurance fund
+    pub fn get_insurance_fund_amount(&self, protocol_fee: u64) -> Result<u64> {
+        safe_mul_div_cast_u64(
+            protocol_fee,
+            self.insurance_fund_basis_points as u64,
+            FEE_DENOMINATOR,
+            Rounding::Down,
+        )
+    }
+
+    /// Portion of the migration fee that goes to the support fund of the curve
+    pub fn get_support_fund_amount(&self, migration_fee: u64) -> Result<u64> {
+        safe_mul_div_cast_u64(
+            migration_fee,
+            self.support_fund_basis_points as u64,
+            FEE_DENOMINATOR,
+            Rounding::Down,
+        )
+    }
+
+    /// Portion of a creator fee claim that goes to the fee claimer
+    pub fn get_creator_fee_protocol_amount(&self, creator_fee: u64) -> Result<u64> {
+        safe_mul_div_cast_u64(
+            creator_fee,
+            self.creator_fee_protocol_basis_points as u64,
+            FEE_DENOMINATOR,
+            Rounding::Down,
+        )
+    }
+
+    /// Migration base threshold as a base token amount
+    pub fn get_migration_base_threshold(&self) -> BaseAmount {
+        BaseAmount(self.migration_base_threshold)
+    }
+
+    /// Migration quote threshold as a quote token amount
+    pub fn get_migration_quote_threshold(&self) -> QuoteAmount {
+        QuoteAmount(self.migration_quote_threshold)
+    }
+
+    /// `threshold`, the migration quote threshold of a curve created `elapsed_seconds` ago,
+    /// decayed. `None` if the threshold doesn't decay
+    pub fn get_decayed_migration_quote_threshold(
+        &self,
+        threshold: QuoteAmount,
+        elapsed_seconds: u64,
+    ) -> Result<Option<QuoteAmount>> {
+        let decayed_seconds = match self.get_threshold_decay_mode()? {
+            ThresholdDecayMode::None => return Ok(None),
+            _ if elapsed_seconds >= self.threshold_decay_seconds => self.threshold_decay_seconds,
+            ThresholdDecayMode::Linear => elapsed_seconds,
+            ThresholdDecayMode::Stepwise => {
+                let step_seconds = self.threshold_decay_seconds / THRESHOLD_DECAY_STEPS;
+                elapsed_seconds / step_seconds * step_seconds
+            }
+        };
+
+        let floor = threshold.safe_mul_div(
+            self.threshold_decay_floor_basis_points as u64,
+            FEE_DENOMINATOR,
+            Rounding::Up,
+        )?;
+        let decay = threshold.safe_sub(floor)?.safe_mul_div(
+            decayed_seconds,
+            self.threshold_decay_seconds,
+            Rounding::Down,
+        )?;
+        Ok(Some(threshold.safe_sub(decay)?))
+    }
+
+    /// Trading fee in bps, referred trades get the referee discount
+    pub fn get_trading_fee_basis_points(&self, has_referral: bool) -> Result<u64> {
+        Ok(if has_referral {
+            self.fee_basis_points
+                .safe_sub(self.referee_discount_basis_points)? as u64
+        } else {
+            self.fee_basis_points as u64
+        })
+    }
+
+    /// Fee rates `get_fee_on_amount` splits the trading fee with, for the same referrals and tier
+    pub fn get_fee_schedule(
+        &self,
+        has_l1_referral: bool,
+        has_l2_referral: bool,
+        has_l3_referral: bool,
+        cashback_tier: Option<CashbackFeeTier>,
+        trade_direction: TradeDirection,
+    ) -> Result<FeeSchedule> {
+        let has_referral = has_l1_referral || has_l2_referral || has_l3_referral;
+        let referral_fee_basis_points = |has_referral: bool, basis_points: u16| {
+            if has_referral {
+                basis_points
+            } else {
+                0
+            }
+        };
+        let l1_referral_fee_basis_points =
+            referral_fee_basis_points(has_l1_referral, self.l1_referral_fee_basis_points);
+        let l2_referral_fee_basis_points =
+            referral_fee_basis_points(has_l2_referral, self.l2_referral_fee_basis_points);
+        let l3_referral_fee_basis_points =
+            referral_fee_basis_points(has_l3_referral, self.l3_referral_fee_basis_points);
+        let cashback_basis_points =
+            self.get_cashback_basis_points(cashback_tier, trade_direction)?;
+
+        let undiscounted_fee_basis_points = self.get_trading_fee_basis_points(has_referral)? as u16;
+        let protocol_fee_basis_points = undiscounted_fee_basis_points
+            .safe_sub(l1_referral_fee_basis_points)?
+            .safe_sub(l2_referral_fee_basis_points)?
+            .safe_sub(l3_referral_fee_basis_points)?
+            .safe_sub(self.creator_fee_basis_points)?
+            .safe_sub(cashback_basis_points)?;
+        // the tier discount only comes out of the protocol's part
+        let fee_discount_basis_points = cashback_tier
+            .map_or(0, |cashback_tier| cashback_tier.fee_discount_basis_points)
+            .min(protocol_fee_basis_points);
+        let trading_fee_basis_points =
+            undiscounted_fee_basis_points.safe_sub(fee_discount_basis_points)?;
+        let protocol_fee_basis_points =
+            protocol_fee_basis_points.safe_sub(fee_discount_basis_points)?;
+
+        Ok(FeeSchedule {
+            trading_fee_basis_points,
+            referee_discount_basis_points: self
+                .fee_basis_points
+                .safe_sub(undiscounted_fee_basis_points)?,
+            fee_discount_basis_points,
+            cashback_basis_points,
+            l1_referral_fee_basis_points,
+            l2_referral_fee_basis_points,
+            l3_referral_fee_basis_points,
+            creator_fee_basis_points: self.creator_fee_basis_points,
+            protocol_fee_basis_points,
+        })
+    }
+
+    /// Smallest amount that is still worth `amount` once the trading fee of `schedule` is taken,
+    /// the inverse of `get_fee_on_amount`
+    pub fn get_amount_before_fee(
+        &self,
+        amount: QuoteAmount,
+        schedule: &FeeSchedule,
+    ) -> Result<QuoteAmount> {
+        amount.safe_mul_div(
+            FEE_DENOMINATOR,
+            FEE_DENOMINATOR.safe_sub(schedule.trading_fee_basis_points as u64)?,
+            Rounding::Up,
+        )
+    }
+
+    /// Trading fee of `schedule` taken from a base amount, when the fee is on the base side of
+    /// a swap
+    pub fn get_base_fee_on_amount(
+        &self,
+        amount: BaseAmount,
+        schedule: &FeeSchedule,
+    ) -> Result<BaseAmount> {
+        amount.safe_mul_div(
+            schedule.trading_fee_basis_points as u64,
+            FEE_DENOMINATOR,
+            Rounding::Down,
+        )
+    }
+
+    /// Smallest base amount that is still worth `amount` once the trading fee of `schedule` is
+    /// taken, the inverse of `get_base_fee_on_amount`
+    pub fn get_base_amount_before_fee(
+        &self,
+        amount: BaseAmount,
+        schedule: &FeeSchedule,
+    ) -> Result<BaseAmount> {
+        amount.safe_mul_div(
+            FEE_DENOMINATOR,
+            FEE_DENOMINATOR.safe_sub(schedule.trading_fee_basis_points as u64)?,
+            Rounding::Up,
+        )
+    }
+
+    /// Split a trading fee that was already valued in quote in proportion to the fee rates of
+    /// `schedule`, the rounding remainder goes where `fee_remainder_mode` says
+    pub fn get_fee_split(
+        &self,
+        amount: QuoteAmount,
+        total_fee: QuoteAmount,
+        schedule: &FeeSchedule,
+    ) -> Result<FeeBreakdown> {
+        let trading_fee_basis_points = schedule.trading_fee_basis_points as u64;
+        let share = |basis_points: u16| -> Result<QuoteAmount> {
+            if trading_fee_basis_points == 0 {
+                return Ok(QuoteAmount::ZERO);
+            }
+            total_fee.safe_mul_div(
+                basis_points as u64,
+                trading_fee_basis_points,
+                Rounding::Down,
+            )
+        };
+
+        let l1_referral_fee = share(schedule.l1_referral_fee_basis_points)?;
+        let l2_referral_fee = share(schedule.l2_referral_fee_basis_points)?;
+        let l3_referral_fee = share(schedule.l3_referral_fee_basis_points)?;
+        let creator_fee = share(schedule.creator_fee_basis_points)?;
+        let cashback_fee = share(schedule.cashback_basis_points)?;
+        let protocol_fee = share(schedule.protocol_fee_basis_points)?;
+        let rounding_remainder = total_fee
+            .safe_sub(l1_referral_fee)?
+            .safe_sub(l2_referral_fee)?
+            .safe_sub(l3_referral_fee)?
+            .safe_sub(creator_fee)?
+            .safe_sub(cashback_fee)?
+            .safe_sub(protocol_fee)?;
+        let (protocol_fee, cashback_fee) = self.assign_fee_remainder(
+            protocol_fee,
+            cashback_fee,
+            rounding_remainder,
+            schedule.cashback_basis_points > 0,
+        )?;
+
+        Ok(FeeBreakdown {
+            amount,
+            protocol_fee,
+            cashback_fee,
+            creator_fee,
+            l1_referral_fee,
+            l2_referral_fee,
+            l3_referral_fee,
+            rounding_remainder,
+        })
+    }
+
+    /// Part of the migrated liquidity that goes into the creator's position, rounded down
+    pub fn get_creator_liquidity(&self, liquidity: u128) -> Result<u128> {
+        let creator_liquidity = mul_div_u256(
+            U256::from(liquidity),
+            U256::from(self.creator_lp_basis_points),
+            U256::from(FEE_DENOMINATOR),
+            Rounding::Down,
+        )
+        .ok_or(AmmError::MathOverflow)?;
+        Ok(creator_liquidity
+            .try_into()
+            .map_err(|_| AmmError::TypeCastFailed)?)
+    }
+
+    /// Cashback rate of the tier, scaled by the multiplier of the trade direction
+    pub fn get_cashback_basis_points(
+        &self,
+        cashback_tier: Option<CashbackFeeTier>,
+        trade_direction: TradeDirection,
+    ) -> Result<u16> {
+        let Some(cashback_tier) = cashback_tier else {
+            return Ok(0);
+        };
+        let multiplier_basis_points = match trade_direction {
+            TradeDirection::QuoteToBase => self.buy_cashback_multiplier_basis_points,
+            TradeDirection::BaseToQuote => self.sell_cashback_multiplier_basis_points,
+        };
+        safe_mul_div_cast_u64(
+            cashback_tier.tier.get_cashback_bps() as u64,
+            multiplier_basis_points as u64,
+            FEE_DENOMINATOR,
+            Rounding::Down,
+        )
     }
 
     pub fn get_fee_on_amount(
         &self,
-        amount_in: u64,
+        amount_in: QuoteAmount,
         has_l1_referral: bool,
         has_l2_referral: bool,
         has_l3_referral: bool,
-        cashback_tier: Option<CashbackTier>,
+        cashback_tier: Option<CashbackFeeTier>,
+        trade_direction: TradeDirection,
     ) -> Result<FeeBreakdown> {
         let l1_referral_fee = if has_l1_referral {
-            safe_mul_div_cast_u64(
-                amount_in,
+            amount_in.safe_mul_div(
                 self.l1_referral_fee_basis_points as u64,
                 FEE_DENOMINATOR,
                 Rounding::Down,
             )?
         } else {
-            0u64
+            QuoteAmount::ZERO
         };
 
         let l2_referral_fee = if has_l2_referral {
-            safe_mul_div_cast_u64(
-                amount_in,
+            amount_in.safe_mul_div(
                 self.l2_referral_fee_basis_points as u64,
                 FEE_DENOMINATOR,
                 Rounding::Down,
             )?
         } else {
-            0u64
+            QuoteAmount::ZERO
         };
 
         let l3_referral_fee = if has_l3_referral {
-            safe_mul_div_cast_u64(
-                amount_in,
+            amount_in.safe_mul_div(
                 self.l3_referral_fee_basis_points as u64,
                 FEE_DENOMINATOR,
                 Rounding::Down,
             )?
         } else {
-            0u64
+            QuoteAmount::ZERO
         };
 
-        let cashback_bps = cashback_tier
-            .map(|tier| tier.get_cashback_bps())
-            .unwrap_or(0);
-        let cashback_fee: u64 = safe_mul_div_cast_u64(
-            amount_in,
-            cashback_bps as u64,
-            FEE_DENOMINATOR,
-            Rounding::Down,
-        )?;
+        let cashback_bps = self.get_cashback_basis_points(cashback_tier, trade_direction)?;
+        let cashback_fee =
+            amount_in.safe_mul_div(cashback_bps as u64, FEE_DENOMINATOR, Rounding::Down)?;
 
-        let creator_fee: u64 = safe_mul_div_cast_u64(
-            amount_in,
+        let creator_fee = amount_in.safe_mul_div(
             self.creator_fee_basis_points as u64,
             FEE_DENOMINATOR,
             Rounding::Down,
         )?;
 
         let has_referral = has_l1_referral || has_l2_referral || has_l3_referral;
-        let total_fee: u64 = safe_mul_div_cast_u64(
-            amount_in,
+        let trading_fee_basis_points = self.get_trading_fee_basis_points(has_referral)?;
+        let total_fee =
+            amount_in.safe_mul_div(trading_fee_basis_points, FEE_DENOMINATOR, Rounding::Down)?;
+
+        // the protocol's part is rounded down like the others, the remainder is what they leave
+        let mut shared_basis_points =
+            (self.creator_fee_basis_points as u64).safe_add(cashback_bps as u64)?;
+        for (has_referral, basis_points) in [
+            (has_l1_referral, self.l1_referral_fee_basis_points),
+            (has_l2_referral, self.l2_referral_fee_basis_points),
+            (has_l3_referral, self.l3_referral_fee_basis_points),
+        ] {
             if has_referral {
-                self.fee_basis_points
-                    .safe_sub(self.referee_discount_basis_points)? as u64
-            } else {
-                self.fee_basis_points as u64
-            },
+                shared_basis_points = shared_basis_points.safe_add(basis_points as u64)?;
+            }
+        }
+        let protocol_fee = amount_in.safe_mul_div(
+            trading_fee_basis_points.safe_sub(shared_basis_points)?,
             FEE_DENOMINATOR,
             Rounding::Down,
         )?;
-        let protocol_fee = total_fee
+        let rounding_remainder = total_fee
             .safe_sub(l1_referral_fee)?
             .safe_sub(l2_referral_fee)?
             .safe_sub(l3_referral_fee)?
             .safe_sub(creator_fee)?
-            .safe_sub(cashback_fee)?;
+            .safe_sub(cashback_fee)?
+            .safe_sub(protocol_fee)?;
+        let (undiscounted_protocol_fee, cashback_fee) = self.assign_fee_remainder(
+            protocol_fee,
+            cashback_fee,
+            rounding_remainder,
+            cashback_bps > 0,
+        )?;
+
+        // the tier discount is paid by the protocol alone and never turns its fee negative
+        let fee_discount = match cashback_tier {
+            Some(cashback_tier) => amount_in
+                .safe_mul_div(
+                    cashback_tier.fee_discount_basis_points as u64,
+                    FEE_DENOMINATOR,
+                    Rounding::Down,
+                )?
+                .min(undiscounted_protocol_fee),
+            None => QuoteAmount::ZERO,
+        };
+        let protocol_fee = undiscounted_protocol_fee.safe_sub(fee_discount)?;
+        let total_fee = total_fee.safe_sub(fee_discount)?;
 
         let amount = amount_in.safe_sub(total_fee)?;
 
@@ -261,17 +1198,90 @@ impl Config {
             l1_referral_fee,
             l2_referral_fee,
             l3_referral_fee,
+            rounding_remainder,
         })
     }
 }
 
+/// 1B tokens expressed with `base_decimal` decimals
+pub fn get_token_total_supply(base_decimal: u8) -> Result<u64> {
+    let decimal_multiplier = 10u64
+        .checked_pow(base_decimal as u32)
+        .ok_or(AmmError::MathOverflow)?;
+    Ok(TOKEN_TOTAL_SUPPLY_WITHOUT_DECIMALS.safe_mul(decimal_multiplier)?)
+}
+
+/// Base amounts are scaled up by the decimal gap to the quote token so that the constant product
+/// math keeps the same precision on both sides, eg: 1000 for a 6 decimal base and a 9 decimal quote.
+pub fn get_base_scale(base_decimal: u8, quote_decimal: u8) -> Result<u128> {
+    10u128
+        .checked_pow(quote_decimal.saturating_sub(base_decimal) as u32)
+        .ok_or(AmmError::MathOverflow.into())
+}
+
 impl FeeBreakdown {
-    pub fn sum(&self) -> u64 {
-        self.l1_referral_fee
-            + self.l2_referral_fee
-            + self.l3_referral_fee
-            + self.creator_fee
-            + self.cashback_fee
-            + self.protocol_fee
+    pub fn sum(&self) -> Result<QuoteAmount> {
+        Ok(self
+            .l1_referral_fee
+            .safe_add(self.l2_referral_fee)?
+            .safe_add(self.l3_referral_fee)?
+            .safe_add(self.creator_fee)?
+            .safe_add(self.cashback_fee)?
+            .safe_add(self.protocol_fee)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_configs_migrate_from_their_own_layout() {
+        let quote_mint = Pubkey::new_unique();
+        let fee_claimer = Pubkey::new_unique();
+
+        // a baseline config account, reallocated to the current size with a zeroed tail
+        let mut data = Config::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(quote_mint.as_ref());
+        data.extend_from_slice(fee_claimer.as_ref());
+        data.extend_from_slice(&[0, 0, 6, 9, 0, 0, 0, 0]);
+        for fee in [1_000u16, 300, 200, 100, 50, 400, 2_000, 0] {
+            data.extend_from_slice(&fee.to_le_bytes());
+        }
+        for amount in [
+            200_000_000_000_000u64,
+            85_000_000_000,
+            30_000_000_000,
+            1_073_000_000_000_000,
+            0,
+            0,
+            0,
+            0,
+        ] {
+            data.extend_from_slice(&amount.to_le_bytes());
+        }
+        assert_eq!(data.len(), 8 + LegacyConfig::INIT_SPACE);
+        data.resize(8 + Config::INIT_SPACE, 0);
+
+        let legacy = LegacyConfig::try_from_account_data(&data).unwrap();
+        let mut config: Config = bytemuck::pod_read_unaligned(&data[8..]);
+        config.migrate_legacy(&legacy).unwrap();
+
+        assert_eq!(config.quote_mint, quote_mint);
+        assert_eq!(config.fee_claimer, fee_claimer);
+        assert_eq!(config.damm_config, Pubkey::default());
+        assert_eq!(config.curve_template_hash, [0u8; 32]);
+        assert_eq!((config.base_decimal, config.quote_decimal), (6, 9));
+        assert_eq!(config.fee_basis_points, 1_000);
+        assert_eq!(config.l3_referral_fee_basis_points, 100);
+        assert_eq!(config.migration_fee_basis_points, 2_000);
+        assert_eq!(config.insurance_fund_basis_points, 0);
+        assert_eq!(config.migration_base_threshold, 200_000_000_000_000);
+        assert_eq!(config.initial_virtual_base_reserve, 1_073_000_000_000_000);
+        assert_eq!(config.event_sequence, 0);
+        assert_eq!((config.version, config.is_legacy), (CONFIG_VERSION, 1));
+
+        // migrated configs can't be migrated again
+        assert!(config.migrate_legacy(&legacy).is_err());
     }
 }
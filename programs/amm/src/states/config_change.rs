@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::params::config::ConfigChangeParameters;
+
+/// A proposed fee/migration parameter change waiting for the config's timelock to pass
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct ConfigChange {
+    /// config the change will be applied to
+    pub config: Pubkey,
+    /// the earliest time the change can be executed
+    pub execute_after: u64,
+
+    /* Migration configurations */
+    /// proposed migration base threshold
+    pub migration_base_threshold: u64,
+    /// proposed migration quote threshold
+    pub migration_quote_threshold: u64,
+    /// proposed pre-migration cooldown
+    pub pre_migration_cooldown_seconds: u64,
+
+    /* Fee configurations */
+    /// proposed trading fee in bps
+    pub fee_basis_points: u16,
+    /// proposed level 1 referral fee in bps
+    pub l1_referral_fee_basis_points: u16,
+    /// proposed level 2 referral fee in bps
+    pub l2_referral_fee_basis_points: u16,
+    /// proposed level 3 referral fee in bps
+    pub l3_referral_fee_basis_points: u16,
+    /// proposed referee discount in bps
+    pub referee_discount_basis_points: u16,
+    /// proposed creator fee in bps
+    pub creator_fee_basis_points: u16,
+    /// proposed migration fee in bps
+    pub migration_fee_basis_points: u16,
+    /// padding 1
+    pub _padding_1: [u8; 2],
+}
+
+impl ConfigChange {
+    pub fn init(&mut self, config: Pubkey, execute_after: u64, params: &ConfigChangeParameters) {
+        self.config = config;
+        self.execute_after = execute_after;
+
+        /* Migration configurations */
+        self.migration_base_threshold = params.migration_base_threshold;
+        self.migration_quote_threshold = params.migration_quote_threshold;
+        self.pre_migration_cooldown_seconds = params.pre_migration_cooldown_seconds;
+
+        /* Fee configurations */
+        self.fee_basis_points = params.fee_basis_points;
+        self.l1_referral_fee_basis_points = params.l1_referral_fee_basis_points;
+        self.l2_referral_fee_basis_points = params.l2_referral_fee_basis_points;
+        self.l3_referral_fee_basis_points = params.l3_referral_fee_basis_points;
+        self.referee_discount_basis_points = params.referee_discount_basis_points;
+        self.creator_fee_basis_points = params.creator_fee_basis_points;
+        self.migration_fee_basis_points = params.migration_fee_basis_points;
+    }
+
+    pub fn is_executable(&self, current_timestamp: u64) -> bool {
+        current_timestamp >= self.execute_after
+    }
+
+    pub fn params(&self) -> ConfigChangeParameters {
+        ConfigChangeParameters {
+            fee_basis_points: self.fee_basis_points,
+            l1_referral_fee_basis_points: self.l1_referral_fee_basis_points,
+            l2_referral_fee_basis_points: self.l2_referral_fee_basis_points,
+            l3_referral_fee_basis_points: self.l3_referral_fee_basis_points,
+            referee_discount_basis_points: self.referee_discount_basis_points,
+            creator_fee_basis_points: self.creator_fee_basis_points,
+            migration_fee_basis_points: self.migration_fee_basis_points,
+            migration_base_threshold: self.migration_base_threshold,
+            migration_quote_threshold: self.migration_quote_threshold,
+            pre_migration_cooldown_seconds: self.pre_migration_cooldown_seconds,
+        }
+    }
+}
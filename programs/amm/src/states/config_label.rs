@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::config::MAX_CONFIG_LABEL_LENGTH, errors::AmmError};
+
+/// Human-readable label of a config, so tooling can tell configs apart without a mapping of
+/// pubkeys to purposes kept somewhere else
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct ConfigLabel {
+    /// config the label belongs to
+    pub config: Pubkey,
+    /// UTF-8 label, zero padded
+    pub label: [u8; MAX_CONFIG_LABEL_LENGTH],
+    /// length of the label in bytes
+    pub label_length: u8,
+}
+
+impl ConfigLabel {
+    pub fn init(&mut self, config: Pubkey) {
+        self.config = config;
+    }
+
+    pub fn get_label(&self) -> String {
+        String::from_utf8_lossy(&self.label[..self.label_length as usize]).into_owned()
+    }
+
+    /// Replaces the label and returns the previous one, empty on the first set
+    pub fn set_label(&mut self, label: &str) -> Result<String> {
+        require!(
+            !label.is_empty() && label.len() <= MAX_CONFIG_LABEL_LENGTH,
+            AmmError::InvalidConfigLabel
+        );
+
+        let old_label = self.get_label();
+        self.label = [0; MAX_CONFIG_LABEL_LENGTH];
+        self.label[..label.len()].copy_from_slice(label.as_bytes());
+        self.label_length = label.len() as u8;
+        Ok(old_label)
+    }
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+
+use crate::{params::swap::TradeDirection, safe_math::SafeMath, states::SwapResult};
+
+/// Counters of the curves of a config, the per-config counterpart of [`ProtocolStats`]. The only
+/// stats swaps write to, indexers sum them over the configs for the protocol wide totals
+///
+/// [`ProtocolStats`]: crate::states::ProtocolStats
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct ConfigStats {
+    /// config the stats belong to
+    pub config: Pubkey,
+    /// bonding curves created
+    pub total_curves_created: u64,
+    /// bonding curves that reached their migration threshold
+    pub total_curves_graduated: u64,
+    /// quote traded, fees included
+    pub total_quote_volume: u64,
+    /// protocol fee charged on swaps
+    pub total_protocol_fee: u64,
+    /// swaps, initial buys at creation, routed swap legs and limit order fills included
+    pub total_swaps: u64,
+    /// creator fee charged on swaps
+    pub total_creator_fee: u64,
+    /// referral fees paid to the referrer chains, all levels
+    pub total_referral_fee: u64,
+}
+
+impl ConfigStats {
+    pub fn init(&mut self, config: Pubkey) {
+        self.config = config;
+    }
+
+    pub fn record_curve_created(&mut self) -> Result<()> {
+        self.total_curves_created = self.total_curves_created.safe_add(1)?;
+        Ok(())
+    }
+
+    pub fn record_curve_graduated(&mut self) -> Result<()> {
+        self.total_curves_graduated = self.total_curves_graduated.safe_add(1)?;
+        Ok(())
+    }
+
+    pub fn record_swap(
+        &mut self,
+        swap_result: &SwapResult,
+        trade_direction: TradeDirection,
+    ) -> Result<()> {
+        self.total_swaps = self.total_swaps.safe_add(1)?;
+        self.total_quote_volume = self
+            .total_quote_volume
+            .safe_add(swap_result.get_quote_volume(trade_direction)?)?;
+        self.total_protocol_fee = self.total_protocol_fee.safe_add(swap_result.protocol_fee)?;
+        self.total_creator_fee = self.total_creator_fee.safe_add(swap_result.creator_fee)?;
+        self.total_referral_fee = self.total_referral_fee.safe_add(
+            swap_result
+                .l1_referral_fee
+                .safe_add(swap_result.l2_referral_fee)?
+                .safe_add(swap_result.l3_referral_fee)?,
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+
+use crate::states::BondingCurve;
+
+/// What is left of a graduated curve once `archive_curve` closed it and its vaults, its
+/// identity and lifetime totals
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct CurveArchive {
+    /// archived bonding curve, the account itself is closed
+    pub curve: Pubkey,
+    /// config the curve belonged to
+    pub config: Pubkey,
+    /// creator of the curve when it was archived
+    pub creator: Pubkey,
+    /// base mint
+    pub base_mint: Pubkey,
+    /// The time curve was created
+    pub creation_timestamp: u64,
+    /// The time curve was finished
+    pub curve_finish_timestamp: u64,
+    /// The time curve was archived
+    pub archive_timestamp: u64,
+    /// virtual base reserve at graduation
+    pub virtual_base_reserve: u64,
+    /// virtual quote reserve at graduation
+    pub virtual_quote_reserve: u64,
+    /// quote traded on the curve, fees included
+    pub total_quote_volume: u64,
+    /// creator fee accrued over the life of the curve
+    pub total_creator_fee: u64,
+    /// number of buys
+    pub buy_count: u64,
+    /// number of sells
+    pub sell_count: u64,
+    /// approximate number of unique holders at graduation
+    pub holder_count: u64,
+    /// approximate number of unique traders
+    pub unique_trader_estimate: u64,
+    /// quote of the migration fee
+    pub migration_quote_fee: u64,
+    /// base of the migration fee
+    pub migration_base_fee: u64,
+    /// curve type, spl token or token2022
+    pub curve_type: u8,
+    /// layout version of the curve when it was archived
+    pub curve_version: u8,
+    /// padding 0
+    pub _padding_0: [u8; 6],
+}
+
+impl CurveArchive {
+    pub fn init(&mut self, curve_key: Pubkey, curve: &BondingCurve, archive_timestamp: u64) {
+        self.curve = curve_key;
+        self.config = curve.config;
+        self.creator = curve.creator;
+        self.base_mint = curve.base_mint;
+        self.creation_timestamp = curve.creation_timestamp;
+        self.curve_finish_timestamp = curve.curve_finish_timestamp;
+        self.archive_timestamp = archive_timestamp;
+        self.virtual_base_reserve = curve.virtual_base_reserve;
+        self.virtual_quote_reserve = curve.virtual_quote_reserve;
+        self.total_quote_volume = curve.total_quote_volume;
+        self.total_creator_fee = curve.total_creator_fee;
+        self.buy_count = curve.buy_count;
+        self.sell_count = curve.sell_count;
+        self.holder_count = curve.holder_count;
+        self.unique_trader_estimate = curve.unique_trader_estimate;
+        self.migration_quote_fee = curve.migration_quote_fee;
+        self.migration_base_fee = curve.migration_base_fee;
+        self.curve_type = curve.curve_type;
+        self.curve_version = curve.version;
+    }
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::curve_metadata::{MAX_EXTENDED_URI_LENGTH, MAX_METADATA_EXTENSION_LENGTH},
+    errors::AmmError,
+};
+
+/// Metadata of a curve created in two steps, for payloads that don't fit the token metadata.
+/// Written once by `finalize_curve_metadata`, which also enables trading on the curve
+#[account(zero_copy)]
+#[derive(InitSpace, Debug)]
+pub struct CurveMetadata {
+    /// bonding curve the metadata belongs to
+    pub curve: Pubkey,
+    /// UTF-8 metadata URI, zero padded
+    pub uri: [u8; MAX_EXTENDED_URI_LENGTH],
+    /// partner defined extension data, zero padded
+    pub extension: [u8; MAX_METADATA_EXTENSION_LENGTH],
+    /// length of the URI in bytes
+    pub uri_length: u16,
+    /// length of the extension data in bytes
+    pub extension_length: u16,
+    /// padding 1
+    pub _padding_1: [u8; 4],
+}
+
+impl CurveMetadata {
+    pub fn init(&mut self, curve: Pubkey, uri: &str, extension: &[u8]) -> Result<()> {
+        require!(
+            !uri.is_empty() && uri.len() <= MAX_EXTENDED_URI_LENGTH,
+            AmmError::InvalidExtendedUri
+        );
+        require!(
+            extension.len() <= MAX_METADATA_EXTENSION_LENGTH,
+            AmmError::MetadataExtensionTooLong
+        );
+
+        self.curve = curve;
+        self.uri[..uri.len()].copy_from_slice(uri.as_bytes());
+        self.uri_length = uri.len() as u16;
+        self.extension[..extension.len()].copy_from_slice(extension);
+        self.extension_length = extension.len() as u16;
+        Ok(())
+    }
+}
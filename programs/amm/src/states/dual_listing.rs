@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::curve::DUAL_LISTING_GRADUATION_WINDOW_SECONDS, errors::AmmError, safe_math::SafeMath,
+};
+
+/// Shared supply accounting of a base mint launched on two configs with different quote mints
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct DualListing {
+    /// base mint shared by both curves
+    pub base_mint: Pubkey,
+    /// curve the base mint was launched on
+    pub primary_curve: Pubkey,
+    /// curve created from a share of the primary curve's supply
+    pub secondary_curve: Pubkey,
+    /// base supply left on the primary curve at listing
+    pub primary_base_supply: u64,
+    /// base supply moved to the secondary curve at listing
+    pub secondary_base_supply: u64,
+    /// time the primary curve migrated, 0 if it hasn't
+    pub primary_migration_timestamp: u64,
+    /// time the secondary curve migrated, 0 if it hasn't
+    pub secondary_migration_timestamp: u64,
+}
+
+impl DualListing {
+    pub fn init(
+        &mut self,
+        base_mint: Pubkey,
+        primary_curve: Pubkey,
+        secondary_curve: Pubkey,
+        primary_base_supply: u64,
+        secondary_base_supply: u64,
+    ) {
+        self.base_mint = base_mint;
+        self.primary_curve = primary_curve;
+        self.secondary_curve = secondary_curve;
+        self.primary_base_supply = primary_base_supply;
+        self.secondary_base_supply = secondary_base_supply;
+    }
+
+    /// Record the migration of one of the listed curves
+    pub fn record_migration(&mut self, curve: Pubkey, current_timestamp: u64) -> Result<()> {
+        if curve == self.primary_curve {
+            self.primary_migration_timestamp = current_timestamp;
+        } else if curve == self.secondary_curve {
+            self.secondary_migration_timestamp = current_timestamp;
+        } else {
+            return Err(AmmError::InvalidAccount.into());
+        }
+        Ok(())
+    }
+
+    /// The other curve of the listing
+    pub fn get_sibling_curve(&self, curve: Pubkey) -> Result<Pubkey> {
+        if curve == self.primary_curve {
+            Ok(self.secondary_curve)
+        } else if curve == self.secondary_curve {
+            Ok(self.primary_curve)
+        } else {
+            Err(AmmError::InvalidAccount.into())
+        }
+    }
+
+    /// Whether a listed curve that completed at `curve_finish_timestamp` can migrate. Both curves
+    /// graduate together once the sibling completed too, a curve only migrates alone when its
+    /// sibling is still bonding after the graduation window
+    pub fn can_migrate(
+        sibling_completed: bool,
+        curve_finish_timestamp: u64,
+        current_timestamp: u64,
+    ) -> Result<bool> {
+        Ok(sibling_completed
+            || current_timestamp
+                >= curve_finish_timestamp.safe_add(DUAL_LISTING_GRADUATION_WINDOW_SECONDS)?)
+    }
+
+    /// Whether both listed curves migrated
+    pub fn is_fully_migrated(&self) -> bool {
+        self.primary_migration_timestamp > 0 && self.secondary_migration_timestamp > 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curves_migrate_alone_only_after_the_graduation_window() {
+        let finish = 1_000;
+        assert!(DualListing::can_migrate(true, finish, finish).unwrap());
+        assert!(!DualListing::can_migrate(false, finish, finish).unwrap());
+        assert!(!DualListing::can_migrate(
+            false,
+            finish,
+            finish + DUAL_LISTING_GRADUATION_WINDOW_SECONDS - 1
+        )
+        .unwrap());
+        assert!(DualListing::can_migrate(
+            false,
+            finish,
+            finish + DUAL_LISTING_GRADUATION_WINDOW_SECONDS
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn sibling_of_a_listed_curve() {
+        let mut dual_listing = DualListing::default();
+        let (primary, secondary) = (Pubkey::new_unique(), Pubkey::new_unique());
+        dual_listing.init(Pubkey::new_unique(), primary, secondary, 1, 1);
+        assert_eq!(dual_listing.get_sibling_curve(primary).unwrap(), secondary);
+        assert_eq!(dual_listing.get_sibling_curve(secondary).unwrap(), primary);
+        assert!(dual_listing
+            .get_sibling_curve(Pubkey::new_unique())
+            .is_err());
+    }
+}
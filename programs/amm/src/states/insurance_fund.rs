@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::safe_math::SafeMath;
+
+/// Per-config fund accrued from a share of the protocol fee, paid out by admins after incidents
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct InsuranceFund {
+    /// config this fund belongs to
+    pub config: Pubkey,
+    /// quote token vault holding the fund
+    pub quote_vault: Pubkey,
+    /// total quote accrued from protocol fees
+    pub total_accrued: u64,
+    /// total quote paid out
+    pub total_paid_out: u64,
+
+    /* Pending payout */
+    /// token account receiving the pending payout
+    pub pending_payout_recipient: Pubkey,
+    /// amount of the pending payout, 0 if there is none
+    pub pending_payout_amount: u64,
+    /// the earliest time the pending payout can be executed
+    pub pending_payout_execute_after: u64,
+}
+
+impl InsuranceFund {
+    pub fn init(&mut self, config: Pubkey, quote_vault: Pubkey) {
+        self.config = config;
+        self.quote_vault = quote_vault;
+    }
+
+    pub fn accrue(&mut self, amount: u64) -> Result<()> {
+        self.total_accrued = self.total_accrued.safe_add(amount)?;
+        Ok(())
+    }
+
+    pub fn has_pending_payout(&self) -> bool {
+        self.pending_payout_amount > 0
+    }
+
+    pub fn propose_payout(&mut self, recipient: Pubkey, amount: u64, execute_after: u64) {
+        self.pending_payout_recipient = recipient;
+        self.pending_payout_amount = amount;
+        self.pending_payout_execute_after = execute_after;
+    }
+
+    pub fn is_payout_executable(&self, current_timestamp: u64) -> bool {
+        self.has_pending_payout() && current_timestamp >= self.pending_payout_execute_after
+    }
+
+    /// Clear the pending payout and return its amount
+    pub fn clear_pending_payout(&mut self) -> u64 {
+        let amount = self.pending_payout_amount;
+        self.pending_payout_recipient = Pubkey::default();
+        self.pending_payout_amount = 0;
+        self.pending_payout_execute_after = 0;
+        amount
+    }
+
+    pub fn execute_payout(&mut self) -> Result<u64> {
+        let amount = self.clear_pending_payout();
+        self.total_paid_out = self.total_paid_out.safe_add(amount)?;
+        Ok(amount)
+    }
+}
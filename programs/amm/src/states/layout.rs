@@ -0,0 +1,165 @@
+//! Discriminators, sizes and field offsets of the program accounts
+//!
+//! Meant for `getProgramAccounts` `dataSize`/`memcmp` filters, so backends don't hardcode byte
+//! offsets that silently break on layout changes. Offsets include the 8 byte discriminator.
+
+use anchor_lang::{Discriminator, Space};
+use static_assertions::const_assert_eq;
+
+use crate::states::{
+    BondingCurve, BonusPool, CashbackAccount, CashbackConfig, Config, ConfigChange, ConfigLabel,
+    ConfigStats, CurveArchive, CurveMetadata, DualListing, InsuranceFund, LimitOrder,
+    MigrationDelegate, MigrationProgress, MintIndex, Partner, PartnerEarnings, Protection,
+    ProtocolAuthority, ProtocolStats, Raffle, RaffleTicket, ReferralAccount, RewardClaim,
+    RewardVault, SupportFund, WhitelistEntry,
+};
+
+macro_rules! account_layout {
+    ($module:ident, $account:ty $(, $offset:ident => $field:ident)* $(,)?) => {
+        #[doc = concat!("Layout of [`", stringify!($account), "`]")]
+        pub mod $module {
+            use super::*;
+
+            pub const DISCRIMINATOR: &[u8] = <$account as Discriminator>::DISCRIMINATOR;
+            /// Account data length, discriminator included
+            pub const SIZE: usize = DISCRIMINATOR.len() + <$account as Space>::INIT_SPACE;
+            $(
+                #[doc = concat!("Offset of `", stringify!($field), "` in the account data")]
+                pub const $offset: usize =
+                    DISCRIMINATOR.len() + core::mem::offset_of!($account, $field);
+            )*
+
+            // zero copy accounts are read straight from the data, InitSpace has to match
+            const_assert_eq!(SIZE, DISCRIMINATOR.len() + core::mem::size_of::<$account>());
+        }
+    };
+}
+
+account_layout!(
+    bonding_curve,
+    BondingCurve,
+    CONFIG_OFFSET => config,
+    CREATOR_OFFSET => creator,
+    BASE_MINT_OFFSET => base_mint,
+    MIGRATION_STATUS_OFFSET => migration_status,
+    PARTNER_OFFSET => partner,
+);
+account_layout!(
+    config,
+    Config,
+    QUOTE_MINT_OFFSET => quote_mint,
+    FEE_CLAIMER_OFFSET => fee_claimer,
+);
+account_layout!(cashback_account, CashbackAccount, OWNER_OFFSET => owner);
+account_layout!(cashback_config, CashbackConfig);
+account_layout!(bonus_pool, BonusPool, CURVE_OFFSET => curve);
+account_layout!(config_change, ConfigChange, CONFIG_OFFSET => config);
+account_layout!(config_label, ConfigLabel, CONFIG_OFFSET => config);
+account_layout!(config_stats, ConfigStats, CONFIG_OFFSET => config);
+account_layout!(
+    curve_archive,
+    CurveArchive,
+    CURVE_OFFSET => curve,
+    CONFIG_OFFSET => config,
+    CREATOR_OFFSET => creator,
+);
+account_layout!(curve_metadata, CurveMetadata, CURVE_OFFSET => curve);
+account_layout!(dual_listing, DualListing, BASE_MINT_OFFSET => base_mint);
+account_layout!(insurance_fund, InsuranceFund, CONFIG_OFFSET => config);
+account_layout!(
+    limit_order,
+    LimitOrder,
+    CURVE_OFFSET => curve,
+    OWNER_OFFSET => owner,
+);
+account_layout!(
+    migration_delegate,
+    MigrationDelegate,
+    CURVE_OFFSET => curve,
+    OPERATOR_OFFSET => operator,
+);
+account_layout!(migration_progress, MigrationProgress, CURVE_OFFSET => curve);
+account_layout!(
+    mint_index,
+    MintIndex,
+    BASE_MINT_OFFSET => base_mint,
+    CURVE_OFFSET => curve,
+    CONFIG_OFFSET => config,
+);
+account_layout!(partner, Partner, PARTNER_OFFSET => partner);
+account_layout!(
+    partner_earnings,
+    PartnerEarnings,
+    PARTNER_OFFSET => partner,
+    QUOTE_MINT_OFFSET => quote_mint,
+);
+account_layout!(protection, Protection, CURVE_OFFSET => curve, OWNER_OFFSET => owner);
+account_layout!(protocol_authority, ProtocolAuthority);
+account_layout!(
+    protocol_stats,
+    ProtocolStats,
+    TOTAL_CURVES_CREATED_OFFSET => total_curves_created,
+);
+account_layout!(raffle, Raffle, CURVE_OFFSET => curve);
+account_layout!(
+    raffle_ticket,
+    RaffleTicket,
+    RAFFLE_OFFSET => raffle,
+    OWNER_OFFSET => owner,
+);
+account_layout!(referral_account, ReferralAccount, USER_OFFSET => user);
+account_layout!(reward_vault, RewardVault, CURVE_OFFSET => curve);
+account_layout!(
+    reward_claim,
+    RewardClaim,
+    REWARD_VAULT_OFFSET => reward_vault,
+    OWNER_OFFSET => owner,
+);
+account_layout!(support_fund, SupportFund, CURVE_OFFSET => curve);
+account_layout!(
+    whitelist_entry,
+    WhitelistEntry,
+    CONFIG_OFFSET => config,
+    CREATOR_OFFSET => creator,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::Pubkey;
+
+    #[test]
+    fn offsets_match_account_data() {
+        let curve = BondingCurve {
+            config: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            base_mint: Pubkey::new_unique(),
+            migration_status: 2,
+            partner: Pubkey::new_unique(),
+            ..Default::default()
+        };
+
+        let mut data = bonding_curve::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(bytemuck::bytes_of(&curve));
+        assert_eq!(data.len(), bonding_curve::SIZE);
+
+        let pubkey_at = |offset: usize| &data[offset..offset + 32];
+        assert_eq!(
+            pubkey_at(bonding_curve::CONFIG_OFFSET),
+            curve.config.as_ref()
+        );
+        assert_eq!(
+            pubkey_at(bonding_curve::CREATOR_OFFSET),
+            curve.creator.as_ref()
+        );
+        assert_eq!(
+            pubkey_at(bonding_curve::BASE_MINT_OFFSET),
+            curve.base_mint.as_ref()
+        );
+        assert_eq!(data[bonding_curve::MIGRATION_STATUS_OFFSET], 2);
+        assert_eq!(
+            pubkey_at(bonding_curve::PARTNER_OFFSET),
+            curve.partner.as_ref()
+        );
+    }
+}
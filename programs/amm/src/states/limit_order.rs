@@ -0,0 +1,142 @@
+use anchor_lang::prelude::*;
+use ruint::aliases::U256;
+
+use crate::{params::swap::TradeDirection, safe_math::SafeMath, states::SwapResult, AmmError};
+
+/// Quote or base escrowed by a user to swap on a curve once its virtual price reaches a target.
+/// Anyone can fill it, for the filler reward
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct LimitOrder {
+    /// bonding curve the order trades on
+    pub curve: Pubkey,
+    /// user that placed the order, receives the output and the rent back
+    pub owner: Pubkey,
+    /// token vault escrowing the input and the filler reward
+    pub escrow_vault: Pubkey,
+    /// virtual price the order fills at, quote per base as a Q64.64 of raw amounts
+    pub target_price: u128,
+    /// input swapped once the target price is reached
+    pub amount_in: u64,
+    /// paid to the filler from the escrow, in the input token
+    pub filler_reward: u64,
+    /// picked by the owner, tells the orders of an owner on a curve apart
+    pub order_id: u64,
+    pub created_at: u64,
+    /// trade direction of the order (0: BaseToQuote, 1: QuoteToBase)
+    pub trade_direction: u8,
+    /// padding 1
+    pub _padding_1: [u8; 15],
+}
+
+impl LimitOrder {
+    pub fn init(
+        &mut self,
+        curve: Pubkey,
+        owner: Pubkey,
+        escrow_vault: Pubkey,
+        order_id: u64,
+        created_at: u64,
+    ) {
+        self.curve = curve;
+        self.owner = owner;
+        self.escrow_vault = escrow_vault;
+        self.order_id = order_id;
+        self.created_at = created_at;
+    }
+
+    pub fn set_order(
+        &mut self,
+        trade_direction: TradeDirection,
+        amount_in: u64,
+        target_price: u128,
+        filler_reward: u64,
+    ) {
+        self.trade_direction = trade_direction.into();
+        self.amount_in = amount_in;
+        self.target_price = target_price;
+        self.filler_reward = filler_reward;
+    }
+
+    pub fn get_trade_direction(&self) -> Result<TradeDirection> {
+        let trade_direction =
+            TradeDirection::try_from(self.trade_direction).map_err(|_| AmmError::TypeCastFailed)?;
+        Ok(trade_direction)
+    }
+
+    /// Buys fill once the price fell to the target, sells once it rose to it
+    pub fn is_price_reached(&self, virtual_price: u128) -> Result<bool> {
+        Ok(match self.get_trade_direction()? {
+            TradeDirection::QuoteToBase => virtual_price <= self.target_price,
+            TradeDirection::BaseToQuote => virtual_price >= self.target_price,
+        })
+    }
+
+    /// Whether the swap paid at most the target price, fees included, so a fill never trades
+    /// worse than the owner asked for
+    pub fn is_filled_at_target(&self, swap_result: &SwapResult) -> Result<bool> {
+        let trade_direction = self.get_trade_direction()?;
+        // input << 64 against output * target, quote always on the left
+        let (quote_amount, base_amount) = match trade_direction {
+            TradeDirection::QuoteToBase => (
+                swap_result.get_total_input_amount(trade_direction)?,
+                swap_result.output_amount,
+            ),
+            TradeDirection::BaseToQuote => (swap_result.output_amount, self.amount_in),
+        };
+        let quote_scaled = U256::from(quote_amount).safe_shl(64)?;
+        let base_at_target = U256::from(base_amount).safe_mul(U256::from(self.target_price))?;
+        Ok(match trade_direction {
+            TradeDirection::QuoteToBase => quote_scaled <= base_at_target,
+            TradeDirection::BaseToQuote => quote_scaled >= base_at_target,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ONE_Q64: u128 = 1 << 64;
+
+    fn swap_result(actual_input_amount: u64, trading_fee: u64, output_amount: u64) -> SwapResult {
+        SwapResult {
+            actual_input_amount,
+            output_amount,
+            trading_fee,
+            protocol_fee: 0,
+            cashback_fee: 0,
+            creator_fee: 0,
+            l1_referral_fee: 0,
+            l2_referral_fee: 0,
+            l3_referral_fee: 0,
+        }
+    }
+
+    #[test]
+    fn fills_only_at_or_better_than_the_target() {
+        let mut order = LimitOrder::default();
+        // buy 1_000 quote worth of base at 2 quote per base at most, fees included
+        order.set_order(TradeDirection::QuoteToBase, 1_000, 2 * ONE_Q64, 0);
+        assert!(order.is_price_reached(2 * ONE_Q64).unwrap());
+        assert!(order.is_price_reached(ONE_Q64).unwrap());
+        assert!(!order.is_price_reached(2 * ONE_Q64 + 1).unwrap());
+        assert!(order
+            .is_filled_at_target(&swap_result(990, 10, 500))
+            .unwrap());
+        assert!(!order
+            .is_filled_at_target(&swap_result(990, 10, 499))
+            .unwrap());
+
+        // sell 500 base at 2 quote per base at least
+        order.set_order(TradeDirection::BaseToQuote, 500, 2 * ONE_Q64, 0);
+        assert!(order.is_price_reached(3 * ONE_Q64).unwrap());
+        assert!(!order.is_price_reached(ONE_Q64).unwrap());
+        assert!(order
+            .is_filled_at_target(&swap_result(500, 10, 1_000))
+            .unwrap());
+        assert!(!order
+            .is_filled_at_target(&swap_result(500, 10, 999))
+            .unwrap());
+    }
+}
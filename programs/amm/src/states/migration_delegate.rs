@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+/// Migration rights of an operator over a single curve, granted by an admin
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct MigrationDelegate {
+    /// bonding curve the operator can migrate
+    pub curve: Pubkey,
+    /// operator allowed to migrate the curve
+    pub operator: Pubkey,
+    /// admin who granted the rights, receives the first position of the migrated pool
+    pub delegator: Pubkey,
+    /// the operator can't migrate the curve from this time on
+    pub expiry_timestamp: u64,
+}
+
+impl MigrationDelegate {
+    pub fn init(&mut self, curve: Pubkey) {
+        self.curve = curve;
+    }
+
+    pub fn delegate(&mut self, operator: Pubkey, delegator: Pubkey, expiry_timestamp: u64) {
+        self.operator = operator;
+        self.delegator = delegator;
+        self.expiry_timestamp = expiry_timestamp;
+    }
+
+    pub fn is_expired(&self, current_timestamp: u64) -> bool {
+        current_timestamp >= self.expiry_timestamp
+    }
+}
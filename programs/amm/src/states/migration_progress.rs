@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+use crate::AmmError;
+
+// Migration step flows, each step is its own instruction so a crank can resume:
+// NotStarted -> PoolCreated -> LiquidityLocked -> Finalized
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    PartialOrd,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum MigrationStep {
+    NotStarted,
+    PoolCreated,
+    LiquidityLocked,
+    Finalized,
+}
+
+/// Progress of the DAMM v2 migration of a curve, what a step needs from the previous ones
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct MigrationProgress {
+    /// bonding curve being migrated
+    pub curve: Pubkey,
+    /// DAMM v2 pool created for the curve
+    pub pool: Pubkey,
+    /// first position of the pool, holding the migrated liquidity
+    pub first_position: Pubkey,
+    /// nft account of the first position
+    pub first_position_nft_account: Pubkey,
+    /// receives the first position once the migration is finalized, the curve authority keeps it
+    /// when the creator takes a share of its fees
+    pub position_owner: Pubkey,
+    /// DAMM v2 config the pool was created with
+    pub damm_config: Pubkey,
+    /// liquidity deposited in the first position
+    pub initial_liquidity: u128,
+    /// sqrt price of token b in token a
+    pub sqrt_price: u128,
+    pub deposited_base_amount: u64,
+    pub deposited_quote_amount: u64,
+    /// migration step enum (0: NotStarted, 1: PoolCreated, 2: LiquidityLocked, 3: Finalized)
+    pub step: u8,
+    /// whether the base mint is token a of the pool (0 | 1)
+    pub is_base_token_a: u8,
+    /// whether the admin migrated with a DAMM config other than the approved one (0 | 1)
+    pub damm_config_overridden: u8,
+    /// padding 1
+    pub _padding_1: [u8; 13],
+    /// second position of the pool, handed to the curve creator, default when the config gives
+    /// the creator no liquidity
+    pub second_position: Pubkey,
+    /// liquidity deposited in the second position
+    pub creator_liquidity: u128,
+}
+
+impl MigrationProgress {
+    pub fn init(&mut self, curve: Pubkey) {
+        self.curve = curve;
+    }
+
+    pub fn get_step(&self) -> Result<MigrationStep> {
+        let step = MigrationStep::try_from(self.step).map_err(|_| AmmError::TypeCastFailed)?;
+        Ok(step)
+    }
+
+    pub fn set_step(&mut self, step: MigrationStep) {
+        self.step = step.into();
+    }
+
+    /// Whether `step` still has to run, a step that already ran is skipped so a crank can resend
+    /// it. A step can't run before the previous one
+    pub fn should_run(&self, step: MigrationStep) -> Result<bool> {
+        let current_step = self.get_step()?;
+        require!(
+            u8::from(current_step) + 1 >= u8::from(step),
+            AmmError::MigrationStepNotReady
+        );
+        Ok(current_step < step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_run_once_and_in_order() {
+        let mut progress = MigrationProgress::default();
+        assert!(progress.should_run(MigrationStep::PoolCreated).unwrap());
+        assert!(progress.should_run(MigrationStep::LiquidityLocked).is_err());
+        assert!(progress.should_run(MigrationStep::Finalized).is_err());
+
+        progress.set_step(MigrationStep::PoolCreated);
+        assert!(!progress.should_run(MigrationStep::PoolCreated).unwrap());
+        assert!(progress.should_run(MigrationStep::LiquidityLocked).unwrap());
+        assert!(progress.should_run(MigrationStep::Finalized).is_err());
+
+        progress.set_step(MigrationStep::Finalized);
+        assert!(!progress.should_run(MigrationStep::PoolCreated).unwrap());
+        assert!(!progress.should_run(MigrationStep::LiquidityLocked).unwrap());
+        assert!(!progress.should_run(MigrationStep::Finalized).unwrap());
+    }
+}
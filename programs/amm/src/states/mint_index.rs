@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+/// Lookup of the curve a base mint launched on, derived from the base mint alone so wallets and
+/// aggregators resolve a mint with one fetch instead of scanning every curve. Created with the
+/// curve, the secondary curve of a dual listing is found through its `DualListing` account
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct MintIndex {
+    /// base mint the index is derived from
+    pub base_mint: Pubkey,
+    /// bonding curve the base mint launched on
+    pub curve: Pubkey,
+    /// config the curve belongs to
+    pub config: Pubkey,
+}
+
+impl MintIndex {
+    pub fn init(&mut self, base_mint: Pubkey, curve: Pubkey, config: Pubkey) {
+        self.base_mint = base_mint;
+        self.curve = curve;
+        self.config = config;
+    }
+}
@@ -1,7 +1,54 @@
 pub mod bonding_curve;
+pub mod bonus_pool;
 pub mod cashback;
+pub mod cashback_config;
 pub mod config;
+pub mod config_change;
+pub mod config_label;
+pub mod config_stats;
+pub mod curve_archive;
+pub mod curve_metadata;
+pub mod dual_listing;
+pub mod insurance_fund;
+pub mod layout;
+pub mod limit_order;
+pub mod migration_delegate;
+pub mod migration_progress;
+pub mod mint_index;
+pub mod partner;
+pub mod protection;
+pub mod protocol_authority;
+pub mod protocol_stats;
+pub mod raffle;
+pub mod referral;
+pub mod reward_vault;
+pub mod support_fund;
+pub mod test_hooks;
+pub mod whitelist_entry;
 
 pub use bonding_curve::*;
+pub use bonus_pool::*;
 pub use cashback::*;
+pub use cashback_config::*;
 pub use config::*;
+pub use config_change::*;
+pub use config_label::*;
+pub use config_stats::*;
+pub use curve_archive::*;
+pub use curve_metadata::*;
+pub use dual_listing::*;
+pub use insurance_fund::*;
+pub use limit_order::*;
+pub use migration_delegate::*;
+pub use migration_progress::*;
+pub use mint_index::*;
+pub use partner::*;
+pub use protection::*;
+pub use protocol_authority::*;
+pub use protocol_stats::*;
+pub use raffle::*;
+pub use referral::*;
+pub use reward_vault::*;
+pub use support_fund::*;
+pub use test_hooks::*;
+pub use whitelist_entry::*;
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+
+use crate::safe_math::SafeMath;
+
+/// Frontend approved by the admin to bring creators, curves created with it as their partner
+/// pay it a share of their protocol fee. The entry existing is the approval
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct Partner {
+    /// wallet of the partner, signs its claims
+    pub partner: Pubkey,
+    /// share of the protocol fee of its curves, in bps of `FEE_DENOMINATOR`. Curves keep the
+    /// share they were created with
+    pub fee_share_basis_points: u16,
+    pub _padding_0: [u8; 6],
+}
+
+impl Partner {
+    pub fn init(&mut self, partner: Pubkey, fee_share_basis_points: u16) {
+        self.partner = partner;
+        self.fee_share_basis_points = fee_share_basis_points;
+    }
+}
+
+/// Earnings of a partner in one quote mint, accrued from the protocol fee claims of its curves
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct PartnerEarnings {
+    /// partner the earnings belong to
+    pub partner: Pubkey,
+    /// quote mint of the earnings
+    pub quote_mint: Pubkey,
+    /// quote token vault holding the unclaimed earnings
+    pub quote_vault: Pubkey,
+    /// total quote accrued from protocol fees
+    pub total_accrued: u64,
+    /// total quote claimed by the partner
+    pub total_claimed: u64,
+}
+
+impl PartnerEarnings {
+    pub fn init(&mut self, partner: Pubkey, quote_mint: Pubkey, quote_vault: Pubkey) {
+        self.partner = partner;
+        self.quote_mint = quote_mint;
+        self.quote_vault = quote_vault;
+    }
+
+    pub fn accrue(&mut self, amount: u64) -> Result<()> {
+        self.total_accrued = self.total_accrued.safe_add(amount)?;
+        Ok(())
+    }
+
+    /// Claim everything accrued so far, returns the claimed amount
+    pub fn claim(&mut self) -> Result<u64> {
+        let amount = self.total_accrued.safe_sub(self.total_claimed)?;
+        self.total_claimed = self.total_accrued;
+        Ok(amount)
+    }
+}
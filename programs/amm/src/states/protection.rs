@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::AmmError,
+    safe_math::{safe_mul_div_cast_u64, SafeMath},
+    u128x128_math::Rounding,
+};
+
+/// Per-(curve, wallet) record used for launch protection features
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct Protection {
+    /// bonding curve this record belongs to
+    pub curve: Pubkey,
+    /// owner of the record
+    pub owner: Pubkey,
+    /// quote amount bought during the early buyer window
+    pub early_buy_quote_amount: u64,
+    /// whether the creator bonus has been claimed (0 | 1)
+    pub bonus_claimed: u8,
+    /// padding 1
+    pub _padding_1: [u8; 7],
+    /// time of the last buy on the curve, used for the diamond hands sell lockup
+    pub last_buy_timestamp: u64,
+    /// quote amount bought during the anti-snipe window
+    pub anti_snipe_buy_quote_amount: u64,
+    /// base amount bought during the early buyer window that the wallet still holds
+    pub early_buy_base_amount: u64,
+}
+
+impl Protection {
+    pub fn init(&mut self, curve: Pubkey, owner: Pubkey) {
+        self.curve = curve;
+        self.owner = owner;
+    }
+
+    pub fn accrue_early_buy(&mut self, quote_amount: u64, base_amount: u64) -> Result<()> {
+        self.early_buy_quote_amount = self.early_buy_quote_amount.safe_add(quote_amount)?;
+        self.early_buy_base_amount = self.early_buy_base_amount.safe_add(base_amount)?;
+        Ok(())
+    }
+
+    /// Cut the early buys down pro-rata to the early bought base the wallet still holds, so sold
+    /// tokens bought back in the window don't count twice. Returns the quote amount taken off
+    pub fn settle_early_buys(&mut self, base_balance: u64) -> Result<u64> {
+        if base_balance >= self.early_buy_base_amount {
+            return Ok(0);
+        }
+        let early_buy_quote_amount = safe_mul_div_cast_u64(
+            self.early_buy_quote_amount,
+            base_balance,
+            self.early_buy_base_amount,
+            Rounding::Down,
+        )?;
+        let settled_quote_amount = self
+            .early_buy_quote_amount
+            .safe_sub(early_buy_quote_amount)?;
+        self.early_buy_quote_amount = early_buy_quote_amount;
+        self.early_buy_base_amount = base_balance;
+        Ok(settled_quote_amount)
+    }
+
+    /// Accrue a buy during the anti-snipe window, failing once the wallet's total exceeds the cap
+    pub fn accrue_anti_snipe_buy(&mut self, quote_amount: u64, max_buy_amount: u64) -> Result<()> {
+        self.anti_snipe_buy_quote_amount =
+            self.anti_snipe_buy_quote_amount.safe_add(quote_amount)?;
+        require!(
+            self.anti_snipe_buy_quote_amount <= max_buy_amount,
+            AmmError::AntiSnipeBuyCapExceeded
+        );
+        Ok(())
+    }
+
+    pub fn record_buy(&mut self, current_timestamp: u64) {
+        self.last_buy_timestamp = current_timestamp;
+    }
+
+    pub fn is_bonus_claimed(&self) -> bool {
+        self.bonus_claimed == 1
+    }
+
+    pub fn mark_bonus_claimed(&mut self) {
+        self.bonus_claimed = 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::BonusPool;
+
+    #[test]
+    fn buy_sell_buy_loop_does_not_raise_the_bonus() {
+        let mut bonus_pool = BonusPool::default();
+        bonus_pool.deposit(1_000).unwrap();
+        let mut holder = Protection::default();
+        let mut flipper = Protection::default();
+        let buy = |protection: &mut Protection, bonus_pool: &mut BonusPool, base_balance: u64| {
+            let settled_quote_amount = protection.settle_early_buys(base_balance).unwrap();
+            bonus_pool.settle_early_buys(settled_quote_amount).unwrap();
+            protection.accrue_early_buy(100, 1_000).unwrap();
+            bonus_pool.accrue_early_buy(100).unwrap();
+        };
+
+        buy(&mut holder, &mut bonus_pool, 0);
+        buy(&mut flipper, &mut bonus_pool, 0);
+        let bonus_amount = bonus_pool
+            .get_bonus_amount(flipper.early_buy_quote_amount)
+            .unwrap();
+        assert_eq!(bonus_amount, 500);
+
+        // sold everything, then bought back the same amount
+        for _ in 0..3 {
+            buy(&mut flipper, &mut bonus_pool, 0);
+        }
+        assert_eq!(flipper.early_buy_quote_amount, 100);
+        assert_eq!(bonus_pool.total_eligible_quote_amount, 200);
+        assert_eq!(
+            bonus_pool
+                .get_bonus_amount(flipper.early_buy_quote_amount)
+                .unwrap(),
+            bonus_amount
+        );
+
+        // a partial sell keeps the share of what's still held
+        let settled_quote_amount = flipper.settle_early_buys(250).unwrap();
+        bonus_pool.settle_early_buys(settled_quote_amount).unwrap();
+        assert_eq!(flipper.early_buy_quote_amount, 25);
+        assert_eq!(flipper.early_buy_base_amount, 250);
+        assert_eq!(
+            bonus_pool
+                .get_bonus_amount(holder.early_buy_quote_amount)
+                .unwrap(),
+            800
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+use crate::errors::AmmError;
+
+/// Role of a protocol authority, picks the key a transfer moves
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum AuthorityType {
+    /// admin of configs, curves and the protocol wide settings
+    Global,
+    /// migrates curves to DAMM v2 without a delegation
+    Migration,
+    /// switches the fee type of curves and claims the meme fee
+    FeeType,
+}
+
+/// Keys allowed to administer the protocol, a single PDA so they rotate without a redeploy.
+/// Every role moves in two steps, the new key has to accept a transfer
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct ProtocolAuthority {
+    pub global_authority: Pubkey,
+    pub migration_authority: Pubkey,
+    pub fee_type_authority: Pubkey,
+    /// keys a transfer nominated for each role, default when none is pending
+    pub pending_global_authority: Pubkey,
+    pub pending_migration_authority: Pubkey,
+    pub pending_fee_type_authority: Pubkey,
+}
+
+impl ProtocolAuthority {
+    pub fn init(
+        &mut self,
+        global_authority: Pubkey,
+        migration_authority: Pubkey,
+        fee_type_authority: Pubkey,
+    ) {
+        self.global_authority = global_authority;
+        self.migration_authority = migration_authority;
+        self.fee_type_authority = fee_type_authority;
+    }
+
+    pub fn is_global_authority(&self, authority: Pubkey) -> bool {
+        self.global_authority == authority
+    }
+
+    /// The global authority can migrate as well
+    pub fn is_migration_authority(&self, authority: Pubkey) -> bool {
+        self.migration_authority == authority || self.is_global_authority(authority)
+    }
+
+    /// The global authority can switch fee types as well
+    pub fn is_fee_type_authority(&self, authority: Pubkey) -> bool {
+        self.fee_type_authority == authority || self.is_global_authority(authority)
+    }
+
+    pub fn get_authority(&self, authority_type: AuthorityType) -> Pubkey {
+        match authority_type {
+            AuthorityType::Global => self.global_authority,
+            AuthorityType::Migration => self.migration_authority,
+            AuthorityType::FeeType => self.fee_type_authority,
+        }
+    }
+
+    fn authority_mut(&mut self, authority_type: AuthorityType) -> &mut Pubkey {
+        match authority_type {
+            AuthorityType::Global => &mut self.global_authority,
+            AuthorityType::Migration => &mut self.migration_authority,
+            AuthorityType::FeeType => &mut self.fee_type_authority,
+        }
+    }
+
+    fn pending_authority_mut(&mut self, authority_type: AuthorityType) -> &mut Pubkey {
+        match authority_type {
+            AuthorityType::Global => &mut self.pending_global_authority,
+            AuthorityType::Migration => &mut self.pending_migration_authority,
+            AuthorityType::FeeType => &mut self.pending_fee_type_authority,
+        }
+    }
+
+    /// Nominates the next key of a role, default cancels a pending transfer
+    pub fn transfer(&mut self, authority_type: AuthorityType, new_authority: Pubkey) {
+        *self.pending_authority_mut(authority_type) = new_authority;
+    }
+
+    /// Moves a role to its nominated key, returns the old key
+    pub fn accept(
+        &mut self,
+        authority_type: AuthorityType,
+        new_authority: Pubkey,
+    ) -> Result<Pubkey> {
+        let pending_authority = self.pending_authority_mut(authority_type);
+        require!(
+            new_authority != Pubkey::default() && *pending_authority == new_authority,
+            AmmError::Unauthorized
+        );
+        *pending_authority = Pubkey::default();
+
+        let authority = self.authority_mut(authority_type);
+        let old_authority = *authority;
+        *authority = new_authority;
+        Ok(old_authority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transfer_waits_for_the_new_authority_to_accept() {
+        let admin = Pubkey::new_unique();
+        let new_admin = Pubkey::new_unique();
+        let mut protocol_authority = ProtocolAuthority::default();
+        protocol_authority.init(admin, admin, admin);
+
+        protocol_authority.transfer(AuthorityType::Global, new_admin);
+        assert!(protocol_authority.is_global_authority(admin));
+        assert!(protocol_authority
+            .accept(AuthorityType::Global, Pubkey::new_unique())
+            .is_err());
+        assert!(protocol_authority
+            .accept(AuthorityType::Migration, new_admin)
+            .is_err());
+
+        assert_eq!(
+            protocol_authority
+                .accept(AuthorityType::Global, new_admin)
+                .unwrap(),
+            admin
+        );
+        assert!(protocol_authority.is_global_authority(new_admin));
+        assert_eq!(
+            protocol_authority.pending_global_authority,
+            Pubkey::default()
+        );
+        // the old global authority keeps the roles it held on its own
+        assert!(protocol_authority.is_fee_type_authority(admin));
+        assert!(!protocol_authority.is_global_authority(admin));
+
+        // a cancelled transfer can't be accepted
+        protocol_authority.transfer(AuthorityType::FeeType, new_admin);
+        protocol_authority.transfer(AuthorityType::FeeType, Pubkey::default());
+        assert!(protocol_authority
+            .accept(AuthorityType::FeeType, new_admin)
+            .is_err());
+    }
+}
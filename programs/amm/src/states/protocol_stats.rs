@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::safe_math::SafeMath;
+
+/// Protocol wide counters, a single PDA created with the first config. Every field is a plain
+/// u64 so indexers can read them at fixed offsets. Swaps and graduations are only counted into
+/// the `ConfigStats` of each config, so trades never write lock this account, the protocol
+/// totals of those are the sum over the configs
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct ProtocolStats {
+    /// bonding curves created
+    pub total_curves_created: u64,
+    /// quote claimed by the fee claimers with `claim_protocol_fee`
+    pub total_protocol_fee_claimed: u64,
+    /// creator fee claimed with `claim_creator_fee`, the protocol's cut included
+    pub total_creator_fee_claimed: u64,
+}
+
+impl ProtocolStats {
+    pub fn record_curve_created(&mut self) -> Result<()> {
+        self.total_curves_created = self.total_curves_created.safe_add(1)?;
+        Ok(())
+    }
+
+    pub fn record_protocol_fee_claim(&mut self, amount: u64) -> Result<()> {
+        self.total_protocol_fee_claimed = self.total_protocol_fee_claimed.safe_add(amount)?;
+        Ok(())
+    }
+
+    pub fn record_creator_fee_claim(&mut self, amount: u64) -> Result<()> {
+        self.total_creator_fee_claimed = self.total_creator_fee_claimed.safe_add(amount)?;
+        Ok(())
+    }
+}
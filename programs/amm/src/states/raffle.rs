@@ -0,0 +1,335 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        fee::FEE_DENOMINATOR,
+        raffle::{RAFFLE_DRAW_TIMEOUT_SECONDS, RAFFLE_RANDOMNESS_BOND_BASIS_POINTS},
+    },
+    instructions::CreateRaffleParams,
+    safe_math::{safe_mul_div_cast_u64, SafeMath},
+    u128x128_math::Rounding,
+};
+
+/// Launch raffle of a curve. Wallets buy tickets with quote before trading opens, the draw buys
+/// the allocation of the winning tickets on the fresh curve in a single buy and refunds the rest
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct Raffle {
+    /// bonding curve the raffle launches
+    pub curve: Pubkey,
+    /// quote token vault holding the ticket deposits
+    pub quote_vault: Pubkey,
+    /// base token vault holding the allocation bought for the winning tickets
+    pub base_vault: Pubkey,
+    /// randomness account the creator committed for the draw, the default pubkey until then
+    pub randomness_account: Pubkey,
+    /// quote paid per ticket
+    pub ticket_price: u64,
+    /// tickets winning an allocation, all tickets win when fewer are sold
+    pub winning_ticket_count: u64,
+    /// tickets a wallet can buy
+    pub max_tickets_per_wallet: u64,
+    /// time ticket sales close
+    pub deposit_end_timestamp: u64,
+    /// tickets sold, numbered in the order they were bought
+    pub total_tickets: u64,
+    /// seed slot of the committed randomness account
+    pub randomness_seed_slot: u64,
+    /// slot the randomness account was committed at
+    pub randomness_commit_slot: u64,
+    /// first winning ticket, the winners are the `drawn_ticket_count` tickets from it, wrapping
+    /// around the ticket numbers
+    pub winning_offset: u64,
+    /// tickets that won an allocation, 0 for a cancelled raffle
+    pub drawn_ticket_count: u64,
+    /// base bought for the winning tickets
+    pub total_base_amount: u64,
+    /// whether the raffle was drawn (0 | 1)
+    pub is_drawn: u8,
+    /// padding 0
+    pub _padding_0: [u8; 7],
+    /// quote bond the creator put up with the randomness commit, only left in the raffle when
+    /// the randomness wasn't revealed in time, then it is refunded with the tickets
+    pub randomness_bond: u64,
+}
+
+impl Raffle {
+    pub fn init(
+        &mut self,
+        curve: Pubkey,
+        quote_vault: Pubkey,
+        base_vault: Pubkey,
+        params: &CreateRaffleParams,
+    ) {
+        self.curve = curve;
+        self.quote_vault = quote_vault;
+        self.base_vault = base_vault;
+        self.ticket_price = params.ticket_price;
+        self.winning_ticket_count = params.winning_ticket_count;
+        self.max_tickets_per_wallet = params.max_tickets_per_wallet;
+        self.deposit_end_timestamp = params.deposit_end_timestamp;
+    }
+
+    pub fn is_deposit_open(&self, current_timestamp: u64) -> bool {
+        current_timestamp < self.deposit_end_timestamp
+    }
+
+    pub fn is_drawn(&self) -> bool {
+        self.is_drawn != 0
+    }
+
+    /// Quote of the winning tickets when every ticket sold wins
+    pub fn get_max_allocation_quote_amount(&self) -> Result<u64> {
+        Ok(self.winning_ticket_count.safe_mul(self.ticket_price)?)
+    }
+
+    /// Number the tickets up, returns the first ticket of the purchase
+    pub fn buy_tickets(&mut self, ticket_count: u64) -> Result<u64> {
+        let first_ticket = self.total_tickets;
+        self.total_tickets = self.total_tickets.safe_add(ticket_count)?;
+        Ok(first_ticket)
+    }
+
+    /// Only a raffle selling more tickets than it has winners needs randomness
+    pub fn is_oversubscribed(&self) -> bool {
+        self.total_tickets > self.winning_ticket_count
+    }
+
+    /// Randomness is committed once, a committer that could replace it could also pick among
+    /// the outcomes
+    pub fn is_randomness_committed(&self) -> bool {
+        self.randomness_account != Pubkey::default()
+    }
+
+    /// An oversubscribed raffle not drawn in time is cancelled, so a committer holding the
+    /// reveal back can't keep the deposits locked. A committed randomness forfeits its bond then
+    pub fn is_draw_expired(&self, current_timestamp: u64) -> bool {
+        current_timestamp
+            >= self
+                .deposit_end_timestamp
+                .saturating_add(RAFFLE_DRAW_TIMEOUT_SECONDS)
+    }
+
+    /// Bond of a randomness commit, rounded up in favor of the ticket holders
+    pub fn get_randomness_bond_amount(&self) -> Result<u64> {
+        safe_mul_div_cast_u64(
+            self.get_max_allocation_quote_amount()?,
+            RAFFLE_RANDOMNESS_BOND_BASIS_POINTS,
+            FEE_DENOMINATOR,
+            Rounding::Up,
+        )
+    }
+
+    pub fn commit_randomness(
+        &mut self,
+        randomness_account: Pubkey,
+        seed_slot: u64,
+        current_slot: u64,
+        randomness_bond: u64,
+    ) {
+        self.randomness_account = randomness_account;
+        self.randomness_seed_slot = seed_slot;
+        self.randomness_commit_slot = current_slot;
+        self.randomness_bond = randomness_bond;
+    }
+
+    /// Bond to return to the creator, a draw that waited for the randomness leaves none to the
+    /// ticket holders
+    pub fn release_randomness_bond(&mut self) -> u64 {
+        std::mem::take(&mut self.randomness_bond)
+    }
+
+    /// Winners of the raffle, a window of `drawn_ticket_count` tickets starting at a random
+    /// ticket. Every ticket has the same odds, so a wallet's odds follow its ticket count
+    pub fn draw(&mut self, random_value: u64) -> Result<()> {
+        self.drawn_ticket_count = self.total_tickets.min(self.winning_ticket_count);
+        self.winning_offset = if self.is_oversubscribed() {
+            random_value.safe_rem(self.total_tickets)?
+        } else {
+            0
+        };
+        self.is_drawn = 1;
+        Ok(())
+    }
+
+    /// Draw without winners, every ticket is refunded
+    pub fn cancel(&mut self) {
+        self.drawn_ticket_count = 0;
+        self.winning_offset = 0;
+        self.is_drawn = 1;
+    }
+
+    pub fn get_drawn_quote_amount(&self) -> Result<u64> {
+        Ok(self.drawn_ticket_count.safe_mul(self.ticket_price)?)
+    }
+
+    pub fn set_total_base_amount(&mut self, total_base_amount: u64) {
+        self.total_base_amount = total_base_amount;
+    }
+
+    /// Winning tickets among the `ticket_count` tickets from `first_ticket`
+    pub fn get_won_ticket_count(&self, first_ticket: u64, ticket_count: u64) -> Result<u64> {
+        let ticket_end = first_ticket.safe_add(ticket_count)?;
+        let window_end = self.winning_offset.safe_add(self.drawn_ticket_count)?;
+        if window_end <= self.total_tickets {
+            return Ok(get_overlap(
+                first_ticket,
+                ticket_end,
+                self.winning_offset,
+                window_end,
+            ));
+        }
+        // the window wraps around to the first tickets
+        Ok(get_overlap(
+            first_ticket,
+            ticket_end,
+            self.winning_offset,
+            self.total_tickets,
+        )
+        .safe_add(get_overlap(
+            first_ticket,
+            ticket_end,
+            0,
+            window_end.safe_sub(self.total_tickets)?,
+        ))?)
+    }
+
+    /// Base allocation and quote refund of a ticket purchase, a forfeited randomness bond is
+    /// shared pro-rata to the tickets. Both round down in favor of the raffle
+    pub fn get_claim_amounts(&self, first_ticket: u64, ticket_count: u64) -> Result<(u64, u64)> {
+        let won_ticket_count = self.get_won_ticket_count(first_ticket, ticket_count)?;
+        let base_amount = if won_ticket_count == 0 {
+            0
+        } else {
+            safe_mul_div_cast_u64(
+                self.total_base_amount,
+                won_ticket_count,
+                self.drawn_ticket_count,
+                Rounding::Down,
+            )?
+        };
+        let bond_amount = safe_mul_div_cast_u64(
+            self.randomness_bond,
+            ticket_count,
+            self.total_tickets,
+            Rounding::Down,
+        )?;
+        let refund_amount = ticket_count
+            .safe_sub(won_ticket_count)?
+            .safe_mul(self.ticket_price)?
+            .safe_add(bond_amount)?;
+        Ok((base_amount, refund_amount))
+    }
+}
+
+fn get_overlap(start: u64, end: u64, other_start: u64, other_end: u64) -> u64 {
+    end.min(other_end).saturating_sub(start.max(other_start))
+}
+
+/// Tickets a wallet bought in a raffle, closed once its allocation and refund are claimed
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct RaffleTicket {
+    /// raffle the tickets were bought in
+    pub raffle: Pubkey,
+    /// wallet that bought the tickets
+    pub owner: Pubkey,
+    /// number of the first ticket, the wallet's tickets are consecutive
+    pub first_ticket: u64,
+    /// tickets bought
+    pub ticket_count: u64,
+}
+
+impl RaffleTicket {
+    pub fn init(&mut self, raffle: Pubkey, owner: Pubkey, first_ticket: u64, ticket_count: u64) {
+        self.raffle = raffle;
+        self.owner = owner;
+        self.first_ticket = first_ticket;
+        self.ticket_count = ticket_count;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drawn_raffle(ticket_counts: &[u64], winning_ticket_count: u64, random_value: u64) -> Raffle {
+        let mut raffle = Raffle::default();
+        raffle.init(
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            &CreateRaffleParams {
+                ticket_price: 10,
+                winning_ticket_count,
+                max_tickets_per_wallet: u64::MAX,
+                deposit_end_timestamp: 1,
+            },
+        );
+        for ticket_count in ticket_counts {
+            raffle.buy_tickets(*ticket_count).unwrap();
+        }
+        raffle.draw(random_value).unwrap();
+        raffle.set_total_base_amount(1_000);
+        raffle
+    }
+
+    #[test]
+    fn winning_window_wraps_around_the_tickets() {
+        // wallets own tickets [0, 3), [3, 5) and [5, 10), the window is [8, 10) + [0, 2)
+        let raffle = drawn_raffle(&[3, 2, 5], 4, 18);
+        assert_eq!(raffle.winning_offset, 8);
+        assert_eq!(raffle.get_won_ticket_count(0, 3).unwrap(), 2);
+        assert_eq!(raffle.get_won_ticket_count(3, 2).unwrap(), 0);
+        assert_eq!(raffle.get_won_ticket_count(5, 5).unwrap(), 2);
+
+        assert_eq!(raffle.get_claim_amounts(0, 3).unwrap(), (500, 10));
+        assert_eq!(raffle.get_claim_amounts(3, 2).unwrap(), (0, 20));
+        assert_eq!(raffle.get_claim_amounts(5, 5).unwrap(), (500, 30));
+    }
+
+    #[test]
+    fn undersubscribed_raffle_allocates_every_ticket() {
+        let raffle = drawn_raffle(&[1, 2], 5, 7);
+        assert!(!raffle.is_oversubscribed());
+        assert_eq!(raffle.drawn_ticket_count, 3);
+        assert_eq!(raffle.get_drawn_quote_amount().unwrap(), 30);
+        assert_eq!(raffle.get_claim_amounts(0, 1).unwrap(), (333, 0));
+        assert_eq!(raffle.get_claim_amounts(1, 2).unwrap(), (666, 0));
+    }
+
+    #[test]
+    fn cancelled_raffle_refunds_every_ticket() {
+        let mut raffle = drawn_raffle(&[3, 2], 2, 1);
+        assert!(!raffle.is_draw_expired(RAFFLE_DRAW_TIMEOUT_SECONDS));
+        assert!(raffle.is_draw_expired(1 + RAFFLE_DRAW_TIMEOUT_SECONDS));
+
+        raffle.cancel();
+        raffle.set_total_base_amount(0);
+        assert_eq!(raffle.get_claim_amounts(0, 3).unwrap(), (0, 30));
+        assert_eq!(raffle.get_claim_amounts(3, 2).unwrap(), (0, 20));
+    }
+
+    #[test]
+    fn withheld_randomness_forfeits_the_bond_to_the_tickets() {
+        let mut raffle = drawn_raffle(&[3, 2], 2, 1);
+        // 20% of the 2 winning tickets at 10
+        let randomness_bond = raffle.get_randomness_bond_amount().unwrap();
+        assert_eq!(randomness_bond, 4);
+        raffle.commit_randomness(Pubkey::new_unique(), 1, 2, randomness_bond);
+
+        // a revealed draw returns the whole bond
+        let mut revealed_raffle = raffle;
+        assert_eq!(revealed_raffle.release_randomness_bond(), 4);
+        assert_eq!(
+            revealed_raffle.get_claim_amounts(0, 3).unwrap(),
+            (1_000, 10)
+        );
+
+        // a timed out draw refunds it with the tickets
+        raffle.cancel();
+        raffle.set_total_base_amount(0);
+        assert_eq!(raffle.get_claim_amounts(0, 3).unwrap(), (0, 32));
+        assert_eq!(raffle.get_claim_amounts(3, 2).unwrap(), (0, 21));
+    }
+}
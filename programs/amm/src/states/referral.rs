@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+/// Referrer chain of a user, the referral fees of the user's swaps can only go to this chain
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct ReferralAccount {
+    /// user who was referred
+    pub user: Pubkey,
+    /// direct referrer of the user
+    pub l1_referrer: Pubkey,
+    /// referrer of the l1 referrer when the user was referred, default if none
+    pub l2_referrer: Pubkey,
+    /// referrer of the l2 referrer when the user was referred, default if none
+    pub l3_referrer: Pubkey,
+}
+
+impl ReferralAccount {
+    /// The chain is snapshotted from the referrer's own chain, which is never longer than two
+    pub fn init(
+        &mut self,
+        user: Pubkey,
+        referrer: Pubkey,
+        referrer_referral: Option<&ReferralAccount>,
+    ) {
+        self.user = user;
+        self.l1_referrer = referrer;
+        if let Some(referrer_referral) = referrer_referral {
+            self.l2_referrer = referrer_referral.l1_referrer;
+            self.l3_referrer = referrer_referral.l2_referrer;
+        }
+    }
+
+    /// Referrers by level, none past the end of the chain
+    pub fn get_referrers(&self) -> [Option<Pubkey>; 3] {
+        [self.l1_referrer, self.l2_referrer, self.l3_referrer]
+            .map(|referrer| (referrer != Pubkey::default()).then_some(referrer))
+    }
+
+    /// Whether the user would be paying referral fees to itself
+    pub fn is_self_referral(&self) -> bool {
+        self.get_referrers().contains(&Some(self.user))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chain_extends_the_referrer_chain() {
+        let [user, l1, l2, l3, l4] = [(); 5].map(|_| Pubkey::new_unique());
+
+        let mut l3_referral = ReferralAccount::default();
+        l3_referral.init(l3, l4, None);
+        let mut l2_referral = ReferralAccount::default();
+        l2_referral.init(l2, l3, Some(&l3_referral));
+        let mut l1_referral = ReferralAccount::default();
+        l1_referral.init(l1, l2, Some(&l2_referral));
+        assert_eq!(l2_referral.get_referrers(), [Some(l3), Some(l4), None]);
+
+        // only three levels are kept
+        let mut referral = ReferralAccount::default();
+        referral.init(user, l1, Some(&l1_referral));
+        assert_eq!(referral.get_referrers(), [Some(l1), Some(l2), Some(l3)]);
+        assert!(!referral.is_self_referral());
+    }
+
+    #[test]
+    fn self_referral() {
+        let [user, referrer] = [(); 2].map(|_| Pubkey::new_unique());
+
+        let mut referral = ReferralAccount::default();
+        referral.init(user, user, None);
+        assert!(referral.is_self_referral());
+
+        // referred by someone the user referred
+        let mut referrer_referral = ReferralAccount::default();
+        referrer_referral.init(referrer, user, None);
+        referral.init(user, referrer, Some(&referrer_referral));
+        assert!(referral.is_self_referral());
+    }
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+use crate::safe_math::SafeMath;
+
+/// Domain separators, so an inner node can never be passed off as a leaf
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Protocol funded rewards paid to the holders of a graduated curve's token
+///
+/// The distribution is a merkle root over `(holder, amount)` leaves built off-chain from a
+/// holder snapshot, each holder claims its leaf once.
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct RewardVault {
+    /// bonding curve this vault belongs to
+    pub curve: Pubkey,
+    /// mint of the reward token
+    pub reward_mint: Pubkey,
+    /// token vault holding the rewards
+    pub token_vault: Pubkey,
+    /// root of the distribution merkle tree, zeroed until the admin sets it
+    pub merkle_root: [u8; 32],
+    /// total rewards funded by admins
+    pub total_funded: u64,
+    /// total rewards claimed so far
+    pub total_claimed: u64,
+}
+
+impl RewardVault {
+    pub fn init(&mut self, curve: Pubkey, reward_mint: Pubkey, token_vault: Pubkey) {
+        self.curve = curve;
+        self.reward_mint = reward_mint;
+        self.token_vault = token_vault;
+    }
+
+    pub fn fund(&mut self, amount: u64) -> Result<()> {
+        self.total_funded = self.total_funded.safe_add(amount)?;
+        Ok(())
+    }
+
+    pub fn has_distribution(&self) -> bool {
+        self.merkle_root != [0u8; 32]
+    }
+
+    pub fn get_unclaimed_amount(&self) -> Result<u64> {
+        Ok(self.total_funded.safe_sub(self.total_claimed)?)
+    }
+
+    pub fn claim(&mut self, amount: u64) -> Result<()> {
+        self.total_claimed = self.total_claimed.safe_add(amount)?;
+        Ok(())
+    }
+
+    /// Whether every funded reward was claimed
+    pub fn is_settled(&self) -> bool {
+        self.total_claimed >= self.total_funded
+    }
+
+    /// Whether `(holder, amount)` is a leaf of the distribution, pairs are hashed sorted
+    pub fn verify_claim(&self, holder: &Pubkey, amount: u64, proof: &[[u8; 32]]) -> bool {
+        let leaf = hashv(&[LEAF_PREFIX, holder.as_ref(), &amount.to_le_bytes()]).to_bytes();
+        let root = proof.iter().fold(leaf, |node, sibling| {
+            let (left, right) = if node <= *sibling {
+                (node, *sibling)
+            } else {
+                (*sibling, node)
+            };
+            hashv(&[NODE_PREFIX, &left, &right]).to_bytes()
+        });
+        self.has_distribution() && root == self.merkle_root
+    }
+}
+
+/// Receipt of a holder's reward claim, its existence prevents claiming twice
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct RewardClaim {
+    /// reward vault the claim was paid from
+    pub reward_vault: Pubkey,
+    /// holder who claimed
+    pub owner: Pubkey,
+    /// amount claimed
+    pub amount: u64,
+}
+
+impl RewardClaim {
+    pub fn init(&mut self, reward_vault: Pubkey, owner: Pubkey, amount: u64) {
+        self.reward_vault = reward_vault;
+        self.owner = owner;
+        self.amount = amount;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(holder: &Pubkey, amount: u64) -> [u8; 32] {
+        hashv(&[LEAF_PREFIX, holder.as_ref(), &amount.to_le_bytes()]).to_bytes()
+    }
+
+    fn node(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        hashv(&[NODE_PREFIX, &left, &right]).to_bytes()
+    }
+
+    #[test]
+    fn verify_claim_against_a_merkle_root() {
+        let holders: Vec<(Pubkey, u64)> =
+            (1..=3).map(|i| (Pubkey::new_unique(), i * 100)).collect();
+        let leaves: Vec<[u8; 32]> = holders.iter().map(|(h, a)| leaf(h, *a)).collect();
+        // odd leaf out is promoted as is
+        let root = node(node(leaves[0], leaves[1]), leaves[2]);
+
+        let mut vault = RewardVault::default();
+        let (holder, amount) = holders[0];
+        assert!(!vault.verify_claim(&holder, amount, &[leaves[1], leaves[2]]));
+
+        vault.merkle_root = root;
+        assert!(vault.verify_claim(&holder, amount, &[leaves[1], leaves[2]]));
+        assert!(vault.verify_claim(&holders[1].0, holders[1].1, &[leaves[0], leaves[2]]));
+        let inner = node(leaves[0], leaves[1]);
+        assert!(vault.verify_claim(&holders[2].0, holders[2].1, &[inner]));
+
+        // wrong amount, wrong holder or wrong proof
+        assert!(!vault.verify_claim(&holder, amount + 1, &[leaves[1], leaves[2]]));
+        assert!(!vault.verify_claim(&holders[1].0, amount, &[leaves[1], leaves[2]]));
+        assert!(!vault.verify_claim(&holder, amount, &[leaves[2]]));
+    }
+}
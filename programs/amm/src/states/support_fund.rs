@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::safe_math::SafeMath;
+
+/// Quote set aside from the migration fee of a curve to support the price of its DAMM v2 pool
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct SupportFund {
+    /// bonding curve this fund belongs to
+    pub curve: Pubkey,
+    /// quote token vault holding the fund
+    pub quote_vault: Pubkey,
+    /// total quote routed from the migration fee
+    pub total_deposit: u64,
+    /// total quote spent on buybacks
+    pub total_deployed: u64,
+    /// total base bought back
+    pub total_bought_base: u64,
+}
+
+impl SupportFund {
+    pub fn init(&mut self, curve: Pubkey, quote_vault: Pubkey) {
+        self.curve = curve;
+        self.quote_vault = quote_vault;
+    }
+
+    pub fn deposit(&mut self, amount: u64) -> Result<()> {
+        self.total_deposit = self.total_deposit.safe_add(amount)?;
+        Ok(())
+    }
+
+    pub fn deploy(&mut self, quote_amount: u64, base_amount: u64) -> Result<()> {
+        self.total_deployed = self.total_deployed.safe_add(quote_amount)?;
+        self.total_bought_base = self.total_bought_base.safe_add(base_amount)?;
+        Ok(())
+    }
+
+    /// Whether the whole fund was spent on buybacks
+    pub fn is_settled(&self) -> bool {
+        self.total_deployed >= self.total_deposit
+    }
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+
+/// Points of the migration where the `test-hooks` feature can make the instruction fail
+#[repr(u8)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    IntoPrimitive,
+    TryFromPrimitive,
+    AnchorDeserialize,
+    AnchorSerialize,
+)]
+pub enum FaultPoint {
+    #[default]
+    None,
+    /// right after the DAMM v2 pool is created, before the create pool payouts
+    AfterPoolCreation,
+    /// right before the liquidity of the first position is locked
+    BeforeLiquidityLock,
+}
+
+/// Fault injection state read by the migration instructions, a single PDA only the `test-hooks`
+/// feature can create. Passed as the last remaining account, builds without the feature never
+/// look for it
+#[cfg(feature = "test-hooks")]
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct TestHooks {
+    /// fault point the migration fails at, 0 to fail nowhere
+    pub fault_point: u8,
+    /// padding 1
+    pub _padding_1: [u8; 7],
+    /// seconds added to the clock the migration reads, negative to go back in time
+    pub clock_skew_seconds: i64,
+}
+
+#[cfg(feature = "test-hooks")]
+impl TestHooks {
+    pub fn get_fault_point(&self) -> Result<FaultPoint> {
+        Ok(FaultPoint::try_from(self.fault_point).map_err(|_| crate::AmmError::TypeCastFailed)?)
+    }
+}
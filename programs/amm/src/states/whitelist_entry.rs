@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+/// Marks a creator as allowed to launch curves on a config in creator whitelist mode, the
+/// entry existing is the membership
+#[account(zero_copy)]
+#[derive(InitSpace, Debug, Default)]
+pub struct WhitelistEntry {
+    /// config the creator is whitelisted on
+    pub config: Pubkey,
+    /// whitelisted creator
+    pub creator: Pubkey,
+}
+
+impl WhitelistEntry {
+    pub fn init(&mut self, config: Pubkey, creator: Pubkey) {
+        self.config = config;
+        self.creator = creator;
+    }
+}
@@ -1,5 +1,13 @@
 pub mod process_create_token_metadata;
+pub mod randomness;
+pub mod stats;
+pub mod swap_budget;
+pub mod test_hooks;
 pub mod token;
 
 pub use process_create_token_metadata::*;
+pub use randomness::*;
+pub use stats::*;
+pub use swap_budget::*;
+pub use test_hooks::*;
 pub use token::*;
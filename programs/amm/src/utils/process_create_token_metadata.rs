@@ -1,6 +1,16 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    system_program::{transfer, Transfer},
+};
+use anchor_spl::token_interface::{
+    spl_pod::optional_keys::OptionalNonZeroPubkey,
+    spl_token_metadata_interface::state::TokenMetadata, token_metadata_initialize,
+    token_metadata_update_authority, TokenMetadataInitialize, TokenMetadataUpdateAuthority,
+};
 use mpl_token_metadata::types::DataV2;
 
+use crate::safe_math::SafeMath;
+
 pub struct ProcessCreateTokenMetadataParams<'a, 'info> {
     pub system_program: AccountInfo<'info>,
     pub payer: AccountInfo<'info>,
@@ -53,3 +63,83 @@ pub fn process_create_token_metadata(params: ProcessCreateTokenMetadataParams) -
 
     Ok(())
 }
+
+pub struct ProcessCreateToken2022MetadataParams<'a, 'info> {
+    pub system_program: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub curve_authority: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub name: &'a str,
+    pub symbol: &'a str,
+    pub uri: &'a str,
+    pub curve_authority_bump: u8,
+}
+
+/// Writes the metadata into the mint itself, the mint's metadata pointer has to point to the mint
+pub fn process_create_token_2022_metadata(
+    params: ProcessCreateToken2022MetadataParams,
+) -> Result<()> {
+    let seeds = curve_authority_seeds!(params.curve_authority_bump);
+
+    // token 2022 reallocs the mint for the metadata TLV entry but doesn't fund it
+    let token_metadata = TokenMetadata {
+        name: params.name.to_string(),
+        symbol: params.symbol.to_string(),
+        uri: params.uri.to_string(),
+        ..Default::default()
+    };
+    let new_len = params
+        .mint
+        .data_len()
+        .safe_add(token_metadata.tlv_size_of()?)?;
+    let lamports = Rent::get()?
+        .minimum_balance(new_len)
+        .saturating_sub(params.mint.lamports());
+    if lamports > 0 {
+        transfer(
+            CpiContext::new(
+                params.system_program.clone(),
+                Transfer {
+                    from: params.payer.clone(),
+                    to: params.mint.clone(),
+                },
+            ),
+            lamports,
+        )?;
+    }
+
+    token_metadata_initialize(
+        CpiContext::new_with_signer(
+            params.token_program.clone(),
+            TokenMetadataInitialize {
+                program_id: params.token_program.clone(),
+                metadata: params.mint.clone(),
+                update_authority: params.curve_authority.clone(),
+                mint_authority: params.curve_authority.clone(),
+                mint: params.mint.clone(),
+            },
+            &[&seeds[..]],
+        ),
+        token_metadata.name,
+        token_metadata.symbol,
+        token_metadata.uri,
+    )?;
+
+    // same as the mpl flow, nobody can update the metadata afterwards
+    token_metadata_update_authority(
+        CpiContext::new_with_signer(
+            params.token_program.clone(),
+            TokenMetadataUpdateAuthority {
+                program_id: params.token_program.clone(),
+                metadata: params.mint.clone(),
+                current_authority: params.curve_authority.clone(),
+                new_authority: params.system_program,
+            },
+            &[&seeds[..]],
+        ),
+        OptionalNonZeroPubkey::default(),
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::raffle::RANDOMNESS_PROGRAM_ID, errors::AmmError};
+
+/// Anchor discriminator of the Switchboard on-demand `RandomnessAccountData` account
+const RANDOMNESS_DISCRIMINATOR: [u8; 8] = [10, 66, 229, 135, 220, 239, 217, 114];
+/// after the discriminator, the authority, queue and seed slothash pubkeys
+const SEED_SLOT_OFFSET: usize = 8 + 32 * 3;
+/// after the seed slot, the oracle pubkey
+const REVEAL_SLOT_OFFSET: usize = SEED_SLOT_OFFSET + 8 + 32;
+const VALUE_OFFSET: usize = REVEAL_SLOT_OFFSET + 8;
+
+/// Fields of a Switchboard on-demand randomness account a raffle is drawn with. Read by offset,
+/// so the program doesn't pull in the Switchboard crate for three fields
+pub struct RandomnessData {
+    /// slot whose slothash seeds the randomness, set when the account is committed
+    pub seed_slot: u64,
+    /// slot the oracle revealed the value at, behind the seed slot until it is revealed
+    pub reveal_slot: u64,
+    pub value: [u8; 32],
+}
+
+impl RandomnessData {
+    pub fn load(randomness_account: &AccountInfo) -> Result<Self> {
+        require_keys_eq!(
+            *randomness_account.owner,
+            RANDOMNESS_PROGRAM_ID,
+            AmmError::InvalidRandomnessAccount
+        );
+        let data = randomness_account.try_borrow_data()?;
+        require!(
+            data.len() >= VALUE_OFFSET + 32 && data[..8] == RANDOMNESS_DISCRIMINATOR,
+            AmmError::InvalidRandomnessAccount
+        );
+        let read_u64 = |offset: usize| -> Result<u64> {
+            Ok(u64::from_le_bytes(
+                data[offset..offset + 8]
+                    .try_into()
+                    .map_err(|_| AmmError::InvalidRandomnessAccount)?,
+            ))
+        };
+        Ok(Self {
+            seed_slot: read_u64(SEED_SLOT_OFFSET)?,
+            reveal_slot: read_u64(REVEAL_SLOT_OFFSET)?,
+            value: data[VALUE_OFFSET..VALUE_OFFSET + 32]
+                .try_into()
+                .map_err(|_| AmmError::InvalidRandomnessAccount)?,
+        })
+    }
+
+    pub fn is_revealed(&self) -> bool {
+        self.reveal_slot > self.seed_slot
+    }
+
+    pub fn get_random_u64(&self) -> u64 {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&self.value[..8]);
+        u64::from_le_bytes(bytes)
+    }
+}
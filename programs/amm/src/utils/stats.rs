@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    params::swap::TradeDirection,
+    states::{ConfigStats, ProtocolStats, SwapResult},
+};
+
+/// Counts a new curve into the protocol and config stats
+pub fn record_curve_created(
+    protocol_stats: &AccountLoader<ProtocolStats>,
+    config_stats: &AccountLoader<ConfigStats>,
+) -> Result<()> {
+    protocol_stats.load_mut()?.record_curve_created()?;
+    config_stats.load_mut()?.record_curve_created()
+}
+
+/// Counts a swap into the config stats, the protocol wide totals are the sum over the configs
+/// so swaps of different configs don't write lock a shared account
+pub fn record_swap(
+    config_stats: &AccountLoader<ConfigStats>,
+    swap_result: &SwapResult,
+    trade_direction: TradeDirection,
+) -> Result<()> {
+    config_stats
+        .load_mut()?
+        .record_swap(swap_result, trade_direction)
+}
+
+/// Counts a curve reaching its migration threshold into the config stats
+pub fn record_curve_graduated(config_stats: &AccountLoader<ConfigStats>) -> Result<()> {
+    config_stats.load_mut()?.record_curve_graduated()
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::compute_units::sol_remaining_compute_units;
+
+use crate::constants::swap_budget::{MAX_SWAP_ACCOUNTS, MAX_SWAP_COMPUTE_UNITS};
+use crate::errors::AmmError;
+use crate::safe_math::SafeMath;
+
+/// Tracks the compute units and accounts used by a swap against the `swap_budget` limits.
+///
+/// Only wired into `handle_swap` by the `swap-budget-guard` feature, staging builds enable it so
+/// a change that pushes the hot path over its limits fails every swap instead of shipping.
+pub struct SwapBudgetGuard {
+    start_compute_units: u64,
+}
+
+impl SwapBudgetGuard {
+    pub fn start() -> Self {
+        Self {
+            start_compute_units: sol_remaining_compute_units(),
+        }
+    }
+
+    pub fn check(&self, account_count: usize) -> Result<()> {
+        let used_compute_units = self
+            .start_compute_units
+            .safe_sub(sol_remaining_compute_units())?;
+        msg!(
+            "swap budget: {}/{} CU, {}/{} accounts",
+            used_compute_units,
+            MAX_SWAP_COMPUTE_UNITS,
+            account_count,
+            MAX_SWAP_ACCOUNTS
+        );
+        require!(
+            used_compute_units <= MAX_SWAP_COMPUTE_UNITS && account_count <= MAX_SWAP_ACCOUNTS,
+            AmmError::SwapBudgetExceeded
+        );
+        Ok(())
+    }
+}
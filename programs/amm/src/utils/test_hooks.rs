@@ -0,0 +1,91 @@
+//! Fault injection points of the migration, driven by the `TestHooks` account of the
+//! `test-hooks` feature. Without the feature every function is a no-op over the real clock and
+//! remaining accounts
+
+use anchor_lang::prelude::*;
+
+use crate::states::FaultPoint;
+
+#[cfg(feature = "test-hooks")]
+mod hooks {
+    use super::*;
+    use anchor_lang::Discriminator;
+
+    use crate::{constants::seeds::TEST_HOOKS_PREFIX, states::TestHooks, AmmError};
+
+    /// Test hooks passed as the last remaining account
+    fn load_test_hooks(remaining_accounts: &[AccountInfo]) -> Result<Option<TestHooks>> {
+        let (test_hooks_pda, _bump) =
+            Pubkey::find_program_address(&[TEST_HOOKS_PREFIX], &crate::ID);
+        match remaining_accounts.last() {
+            Some(account) if account.key() == test_hooks_pda => {
+                let data = account.try_borrow_data()?;
+                require!(
+                    account.owner == &crate::ID && data.starts_with(TestHooks::DISCRIMINATOR),
+                    AmmError::InvalidAccount
+                );
+                Ok(Some(*bytemuck::from_bytes::<TestHooks>(
+                    &data[TestHooks::DISCRIMINATOR.len()..],
+                )))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Remaining accounts the instruction expects, the test hooks left out
+    pub fn without_test_hooks<'a, 'info>(
+        remaining_accounts: &'a [AccountInfo<'info>],
+    ) -> Result<&'a [AccountInfo<'info>]> {
+        Ok(match load_test_hooks(remaining_accounts)? {
+            Some(_) => &remaining_accounts[..remaining_accounts.len() - 1],
+            None => remaining_accounts,
+        })
+    }
+
+    /// Fails the instruction when the test hooks are set to fail at `fault_point`
+    pub fn inject_fault(remaining_accounts: &[AccountInfo], fault_point: FaultPoint) -> Result<()> {
+        if let Some(test_hooks) = load_test_hooks(remaining_accounts)? {
+            if test_hooks.get_fault_point()? == fault_point {
+                msg!("injected fault: {:?}", fault_point);
+                return err!(AmmError::InjectedFault);
+            }
+        }
+        Ok(())
+    }
+
+    /// Clock timestamp shifted by the clock skew of the test hooks
+    pub fn get_current_timestamp(remaining_accounts: &[AccountInfo]) -> Result<u64> {
+        let unix_timestamp = Clock::get()?.unix_timestamp;
+        let clock_skew_seconds = load_test_hooks(remaining_accounts)?
+            .map(|test_hooks| test_hooks.clock_skew_seconds)
+            .unwrap_or_default();
+        Ok(unix_timestamp.saturating_add(clock_skew_seconds) as u64)
+    }
+}
+
+#[cfg(not(feature = "test-hooks"))]
+mod hooks {
+    use super::*;
+
+    #[inline(always)]
+    pub fn without_test_hooks<'a, 'info>(
+        remaining_accounts: &'a [AccountInfo<'info>],
+    ) -> Result<&'a [AccountInfo<'info>]> {
+        Ok(remaining_accounts)
+    }
+
+    #[inline(always)]
+    pub fn inject_fault(
+        _remaining_accounts: &[AccountInfo],
+        _fault_point: FaultPoint,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn get_current_timestamp(_remaining_accounts: &[AccountInfo]) -> Result<u64> {
+        Ok(Clock::get()?.unix_timestamp as u64)
+    }
+}
+
+pub use hooks::*;
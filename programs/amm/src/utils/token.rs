@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 use anchor_lang::{
     prelude::InterfaceAccount,
     solana_program::program::{invoke, invoke_signed},
+    solana_program::program_option::COption,
     solana_program::system_instruction::transfer,
 };
 use anchor_spl::{
@@ -10,10 +11,14 @@ use anchor_spl::{
         self,
         extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions},
     },
-    token_interface::{Mint, TokenAccount, TokenInterface},
+    token_interface::{
+        burn, close_account, sync_native, Burn, CloseAccount, Mint, SyncNative, TokenAccount,
+        TokenInterface,
+    },
 };
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 
+use crate::constants::quote_mint::WHITELISTED_QUOTE_MINTS;
 use crate::errors::AmmError;
 use crate::safe_math::SafeMath;
 
@@ -36,6 +41,18 @@ pub fn get_token_program_flags(token_mint: &InterfaceAccount<Mint>) -> TokenProg
     }
 }
 
+/// Whether `token_program` owns `mint`. A mismatched pairing otherwise only fails deep inside
+/// the token CPIs, so contexts check it before their token constraints run
+pub fn is_token_program_of(mint: &AccountInfo, token_program: &Pubkey) -> bool {
+    mint.owner == token_program
+}
+
+/// Transfer tokens from a user owned token account.
+///
+/// `authority` must be a signer of the instruction. When the swap is invoked by another program
+/// on behalf of a PDA, the signer privilege is inherited from the caller's `invoke_signed`, so no
+/// extra seeds are needed here. The account infos are passed in the order expected by
+/// `transfer_checked`: source, mint, destination, authority.
 pub fn transfer_from_user<'a, 'c: 'info, 'info>(
     authority: &'a Signer<'info>,
     token_mint: &'a InterfaceAccount<'info, Mint>,
@@ -107,6 +124,54 @@ pub fn transfer_from_curve<'c: 'info, 'info>(
     Ok(())
 }
 
+/// Burn tokens held by a curve vault, signed by the curve authority
+pub fn burn_from_curve<'info>(
+    curve_authority: AccountInfo<'info>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_vault: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+    bump: u8,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let signer_seeds = curve_authority_seeds!(bump);
+    burn(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Burn {
+                mint: token_mint.to_account_info(),
+                from: token_vault.to_account_info(),
+                authority: curve_authority,
+            },
+            &[&signer_seeds[..]],
+        ),
+        amount,
+    )
+}
+
+/// Close an emptied curve owned token account, signed by the curve authority
+pub fn close_from_curve<'info>(
+    curve_authority: AccountInfo<'info>,
+    token_account: &InterfaceAccount<'info, TokenAccount>,
+    destination: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    bump: u8,
+) -> Result<()> {
+    let signer_seeds = curve_authority_seeds!(bump);
+    close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: token_account.to_account_info(),
+            destination,
+            authority: curve_authority,
+        },
+        &[&signer_seeds[..]],
+    ))
+}
+
 pub fn is_supported_quote_mint(mint_account: &InterfaceAccount<Mint>) -> Result<bool> {
     let mint_info = mint_account.to_account_info();
     if *mint_info.owner == Token::id() {
@@ -118,16 +183,95 @@ pub fn is_supported_quote_mint(mint_account: &InterfaceAccount<Mint>) -> Result<
     }
 
     let mint_data = mint_info.try_borrow_data()?;
-    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    is_supported_token_2022_quote_mint(&mint_account.key(), &mint_data, WHITELISTED_QUOTE_MINTS)
+}
+
+/// Token-2022 quote mints may only carry metadata extensions. A permanent delegate, a mint close
+/// authority or a transfer hook would let a third party move, freeze out or brick the quote held
+/// in curve vaults, so those are only accepted for mints in `whitelist`.
+fn is_supported_token_2022_quote_mint(
+    mint_key: &Pubkey,
+    mint_data: &[u8],
+    whitelist: &[Pubkey],
+) -> Result<bool> {
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(mint_data)?;
+    let is_whitelisted = whitelist.contains(mint_key);
     let extensions = mint.get_extension_types()?;
     for e in extensions {
-        if e != ExtensionType::MetadataPointer && e != ExtensionType::TokenMetadata {
+        let is_supported = match e {
+            ExtensionType::MetadataPointer | ExtensionType::TokenMetadata => true,
+            ExtensionType::PermanentDelegate
+            | ExtensionType::MintCloseAuthority
+            | ExtensionType::TransferHook => is_whitelisted,
+            _ => false,
+        };
+        if !is_supported {
             return Ok(false);
         }
     }
     Ok(true)
 }
 
+/// Sync a native (WSOL) token account so lamports sent to it directly count towards its token
+/// balance, and reload it. No-op for other mints or when the balance is already in sync.
+pub fn sync_native_if_needed<'info>(
+    token_account: &mut InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let COption::Some(rent_exempt_reserve) = token_account.is_native else {
+        return Ok(());
+    };
+    let wrapped_lamports = token_account
+        .to_account_info()
+        .lamports()
+        .safe_sub(rent_exempt_reserve)?;
+    if wrapped_lamports <= token_account.amount {
+        return Ok(());
+    }
+
+    sync_native(CpiContext::new(
+        token_program.to_account_info(),
+        SyncNative {
+            account: token_account.to_account_info(),
+        },
+    ))?;
+    token_account.reload()
+}
+
+/// Wrap SOL: send lamports from `payer` to a native (WSOL) token account and sync it
+pub fn wrap_sol<'info>(
+    payer: AccountInfo<'info>,
+    token_account: &mut InterfaceAccount<'info, TokenAccount>,
+    system_program: AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    lamports: u64,
+) -> Result<()> {
+    require!(token_account.is_native.is_some(), AmmError::InvalidAccount);
+    invoke(
+        &transfer(payer.key, &token_account.key(), lamports),
+        &[payer, token_account.to_account_info(), system_program],
+    )?;
+    sync_native_if_needed(token_account, token_program)
+}
+
+/// Unwrap SOL: close a native (WSOL) token account of `owner`, its whole balance and rent go
+/// back to `owner` as lamports
+pub fn unwrap_sol<'info>(
+    owner: AccountInfo<'info>,
+    token_account: &InterfaceAccount<'info, TokenAccount>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    require!(token_account.is_native.is_some(), AmmError::InvalidAccount);
+    close_account(CpiContext::new(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: token_account.to_account_info(),
+            destination: owner.clone(),
+            authority: owner,
+        },
+    ))
+}
+
 pub fn update_account_lamports_to_minimum_balance<'info>(
     account: AccountInfo<'info>,
     payer: AccountInfo<'info>,
@@ -145,3 +289,90 @@ pub fn update_account_lamports_to_minimum_balance<'info>(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::token_2022::spl_token_2022::{
+        extension::{
+            metadata_pointer::MetadataPointer, mint_close_authority::MintCloseAuthority,
+            permanent_delegate::PermanentDelegate, transfer_fee::TransferFeeConfig,
+            transfer_hook::TransferHook, BaseStateWithExtensionsMut, StateWithExtensionsMut,
+        },
+        state::Mint as Token2022Mint,
+    };
+
+    fn craft_mint(extension_types: &[ExtensionType]) -> Vec<u8> {
+        let space =
+            ExtensionType::try_calculate_account_len::<Token2022Mint>(extension_types).unwrap();
+        let mut data = vec![0u8; space];
+        let mut state =
+            StateWithExtensionsMut::<Token2022Mint>::unpack_uninitialized(&mut data).unwrap();
+        for extension_type in extension_types {
+            match extension_type {
+                ExtensionType::MetadataPointer => {
+                    state.init_extension::<MetadataPointer>(true).unwrap();
+                }
+                ExtensionType::PermanentDelegate => {
+                    state.init_extension::<PermanentDelegate>(true).unwrap();
+                }
+                ExtensionType::MintCloseAuthority => {
+                    state.init_extension::<MintCloseAuthority>(true).unwrap();
+                }
+                ExtensionType::TransferHook => {
+                    state.init_extension::<TransferHook>(true).unwrap();
+                }
+                ExtensionType::TransferFeeConfig => {
+                    state.init_extension::<TransferFeeConfig>(true).unwrap();
+                }
+                _ => unreachable!(),
+            }
+        }
+        state.base = Token2022Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    #[test]
+    fn test_quote_mint_with_metadata_is_supported() {
+        let mint_key = Pubkey::new_unique();
+        assert!(is_supported_token_2022_quote_mint(&mint_key, &craft_mint(&[]), &[]).unwrap());
+        let data = craft_mint(&[ExtensionType::MetadataPointer]);
+        assert!(is_supported_token_2022_quote_mint(&mint_key, &data, &[]).unwrap());
+    }
+
+    #[test]
+    fn test_quote_mint_with_authority_extensions_is_rejected() {
+        let mint_key = Pubkey::new_unique();
+        for extension_type in [
+            ExtensionType::PermanentDelegate,
+            ExtensionType::MintCloseAuthority,
+            ExtensionType::TransferHook,
+        ] {
+            let data = craft_mint(&[ExtensionType::MetadataPointer, extension_type]);
+            assert!(
+                !is_supported_token_2022_quote_mint(&mint_key, &data, &[]).unwrap(),
+                "{:?} should be rejected",
+                extension_type
+            );
+            assert!(
+                is_supported_token_2022_quote_mint(&mint_key, &data, &[mint_key]).unwrap(),
+                "{:?} should be accepted for a whitelisted mint",
+                extension_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_quote_mint_with_other_extensions_is_rejected_even_if_whitelisted() {
+        let mint_key = Pubkey::new_unique();
+        let data = craft_mint(&[ExtensionType::TransferFeeConfig]);
+        assert!(!is_supported_token_2022_quote_mint(&mint_key, &data, &[]).unwrap());
+        assert!(!is_supported_token_2022_quote_mint(&mint_key, &data, &[mint_key]).unwrap());
+    }
+}
@@ -0,0 +1,133 @@
+#![allow(unexpected_cfgs)]
+
+use amm::{cpi::accounts::SwapCtx, program::Amm, SwapParameters};
+use anchor_lang::prelude::*;
+
+declare_id!("6RiHGK1FHsVN5bfNW2kDeFY1gycqDuQo219kbYwn6g4q");
+
+pub const VAULT_AUTHORITY_PREFIX: &[u8] = b"vault_authority";
+
+/// A minimal vault/strategy program that trades on bonding curves with a PDA authority.
+/// It only exists so the integration tests can exercise `swap` through CPI.
+#[program]
+pub mod mock_strategy {
+    use super::*;
+
+    /// Swap on a bonding curve, signing for the vault authority PDA
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - The accounts needed by the instruction.
+    /// * `amount_in` - The amount of input token to swap.
+    /// * `minimum_amount_out` - The minimum amount of output token to receive.
+    ///
+    pub fn swap_via_vault(
+        ctx: Context<SwapViaVaultCtx>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+    ) -> Result<()> {
+        let bump = [ctx.bumps.vault_authority];
+        let signer_seeds: &[&[u8]] = &[VAULT_AUTHORITY_PREFIX, &bump];
+
+        let swap_result = amm::cpi::swap(
+            CpiContext::new_with_signer(
+                ctx.accounts.amm_program.to_account_info(),
+                SwapCtx {
+                    curve_authority: ctx.accounts.curve_authority.to_account_info(),
+                    config: ctx.accounts.config.to_account_info(),
+                    curve: ctx.accounts.curve.to_account_info(),
+                    input_token_account: ctx.accounts.input_token_account.to_account_info(),
+                    output_token_account: ctx.accounts.output_token_account.to_account_info(),
+                    base_vault: ctx.accounts.base_vault.to_account_info(),
+                    quote_vault: ctx.accounts.quote_vault.to_account_info(),
+                    base_mint: ctx.accounts.base_mint.to_account_info(),
+                    quote_mint: ctx.accounts.quote_mint.to_account_info(),
+                    payer: ctx.accounts.vault_authority.to_account_info(),
+                    token_base_program: ctx.accounts.token_base_program.to_account_info(),
+                    token_quote_program: ctx.accounts.token_quote_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    cashback: None,
+                    cashback_token_account: None,
+                    l1_referral_cashback_token_account: None,
+                    l2_referral_cashback_token_account: None,
+                    l3_referral_cashback_token_account: None,
+                    protection: None,
+                    bonus_pool: None,
+                    referral: None,
+                    config_stats: ctx.accounts.config_stats.to_account_info(),
+                    cashback_config: None,
+                    event_authority: ctx.accounts.amm_event_authority.to_account_info(),
+                    program: ctx.accounts.amm_program.to_account_info(),
+                },
+                &[signer_seeds],
+            ),
+            SwapParameters {
+                amount_in,
+                minimum_amount_out,
+            },
+        )?
+        .get();
+        // the swap result comes back with the return data of the swap
+        msg!(
+            "swapped {} for {}",
+            swap_result.actual_input_amount,
+            swap_result.output_amount
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct SwapViaVaultCtx<'info> {
+    /// CHECK: vault authority PDA, owns the vault token accounts and signs the swap
+    #[account(seeds = [VAULT_AUTHORITY_PREFIX], bump)]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the amm program
+    pub curve_authority: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the amm program
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the amm program
+    #[account(mut)]
+    pub curve: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the amm program
+    #[account(mut)]
+    pub input_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the amm program
+    #[account(mut)]
+    pub output_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the amm program
+    #[account(mut)]
+    pub base_vault: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the amm program
+    #[account(mut)]
+    pub quote_vault: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the amm program
+    pub base_mint: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the amm program
+    pub quote_mint: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the amm program
+    pub token_base_program: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the amm program
+    pub token_quote_program: UncheckedAccount<'info>,
+
+    /// CHECK: validated by the amm program
+    #[account(mut)]
+    pub config_stats: UncheckedAccount<'info>,
+
+    /// CHECK: amm event authority
+    pub amm_event_authority: UncheckedAccount<'info>,
+
+    pub amm_program: Program<'info, Amm>,
+    pub system_program: Program<'info, System>,
+}
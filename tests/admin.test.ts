@@ -1,7 +1,9 @@
 import { beforeAll, describe, expect, test } from 'bun:test'
 import type { Address } from 'gill'
 import type { Config, CreateConfigInstructionDataArgs, fetchConfig } from '~/clients'
+import { AuthorityType, getAmmConfigPda } from './utils/accounts.ts'
 import {
+  CONFIG_VERSION,
   CREATOR_FEE_BASIS_POINTS,
   DEFAULT_CONFIG_ARGS,
   FEE_BASIS_POINTS,
@@ -9,7 +11,9 @@ import {
   L2_REFERRAL_FEE_BASIS_POINTS,
   L3_REFERRAL_FEE_BASIS_POINTS,
   MIGRATION_FEE_BASIS_POINTS,
+  NO_LOCKED_VESTING,
   REFEREE_DISCOUNT_BASIS_POINTS,
+  TOKEN_TOTAL_SUPPLY,
   WSOL_MINT,
 } from './utils/constants'
 import { TestContextClass } from './utils/context.ts'
@@ -134,12 +138,100 @@ describe('Admin Config Tests', () => {
     })
   })
 
+  test('create config - stores the locked vesting', async () => {
+    const lockedVesting = {
+      cliffDurationSeconds: 30n * 86_400n,
+      periodSeconds: 86_400n,
+      amountPerPeriod: 1_000_000_000_000n,
+      numberOfPeriods: 90n,
+    }
+    const { configAddress } = await ctx.createConfig({ ...DEFAULT_CONFIG_ARGS, lockedVesting }, WSOL_MINT)
+    const config = await ctx.getConfigData({ configAddress })
+
+    expect(config.data.lockedVestingConfig).toEqual(lockedVesting)
+  })
+
+  test('create config - derives the config from its index and quote mint', async () => {
+    const { index, configAddress: expectedConfigAddress } = await ctx.getFreeConfigIndex(WSOL_MINT)
+    const [derivedConfigAddress] = await getAmmConfigPda({ index, quoteMint: WSOL_MINT, programId: ctx.programId })
+    expect(derivedConfigAddress).toBe(expectedConfigAddress)
+
+    const { configAddress } = await ctx.createConfig(DEFAULT_CONFIG_ARGS, WSOL_MINT)
+    const config = await ctx.getConfigData({ configAddress })
+    const [configPda] = await getAmmConfigPda({
+      index: config.data.index,
+      quoteMint: WSOL_MINT,
+      programId: ctx.programId,
+    })
+    expect(configPda).toBe(configAddress)
+    expect(config.data.version).toBe(CONFIG_VERSION)
+    expect(config.data.isLegacy).toBe(0)
+
+    // only legacy keypair configs need the migration
+    expect(ctx.migrateConfig({ configAddress })).rejects.toThrow()
+  })
+
+  test('config label - set then change the label of a config', async () => {
+    const { configAddress } = await ctx.createConfig(DEFAULT_CONFIG_ARGS, WSOL_MINT)
+
+    await ctx.setConfigLabel({ label: 'SOL-mainnet-default', configAddress })
+    expect(await ctx.getConfigLabel({ configAddress })).toBe('SOL-mainnet-default')
+
+    await ctx.setConfigLabel({ label: 'SOL-highfee', configAddress })
+    expect(await ctx.getConfigLabel({ configAddress })).toBe('SOL-highfee')
+
+    expect(ctx.setConfigLabel({ label: '', configAddress })).rejects.toThrow()
+    expect(ctx.setConfigLabel({ label: 'x'.repeat(33), configAddress })).rejects.toThrow()
+
+    const nonOwner = await ctx.createTestTrader()
+    expect(ctx.setConfigLabel({ label: 'USDC-highfee', configAddress, signer: nonOwner })).rejects.toThrow()
+  })
+
   test('create config - rejects non-owner creation attempts', async () => {
     const nonOwner = await ctx.createTestTrader()
 
     expect(ctx.createConfig(DEFAULT_CONFIG_ARGS, WSOL_MINT, nonOwner)).rejects.toThrow()
   })
 
+  test('protocol authority - global authority moves once the new key accepts', async () => {
+    const newAdmin = await ctx.createTestTrader()
+    const stranger = await ctx.createTestTrader()
+
+    await expect(
+      ctx.transferAuthority({
+        authorityType: AuthorityType.Global,
+        newAuthority: stranger.address,
+        authority: stranger,
+      }),
+    ).rejects.toThrow()
+
+    await ctx.transferAuthority({ authorityType: AuthorityType.Global, newAuthority: newAdmin.address })
+    expect((await ctx.getProtocolAuthority()).pendingGlobalAuthority).toBe(newAdmin.address)
+    // nominated keys aren't admins until they accept
+    await expect(ctx.createConfig(DEFAULT_CONFIG_ARGS, WSOL_MINT, newAdmin)).rejects.toThrow()
+    await expect(
+      ctx.acceptAuthority({ authorityType: AuthorityType.Global, newAuthority: stranger }),
+    ).rejects.toThrow()
+
+    try {
+      await ctx.acceptAuthority({ authorityType: AuthorityType.Global, newAuthority: newAdmin })
+      const protocolAuthority = await ctx.getProtocolAuthority()
+      expect(protocolAuthority.globalAuthority).toBe(newAdmin.address)
+      expect(protocolAuthority.feeTypeAuthority).toBe(ctx.owner.address)
+      await expect(
+        ctx.transferAuthority({ authorityType: AuthorityType.Global, newAuthority: ctx.owner.address }),
+      ).rejects.toThrow()
+    } finally {
+      await ctx.transferAuthority({
+        authorityType: AuthorityType.Global,
+        newAuthority: ctx.owner.address,
+        authority: newAdmin,
+      })
+      await ctx.acceptAuthority({ authorityType: AuthorityType.Global, newAuthority: ctx.owner })
+    }
+    expect((await ctx.getProtocolAuthority()).globalAuthority).toBe(ctx.owner.address)
+  })
+
   describe('create config - validation', () => {
     const baseValidArgs = DEFAULT_CONFIG_ARGS
 
@@ -238,6 +330,268 @@ describe('Admin Config Tests', () => {
         quoteMint: WSOL_MINT,
         expectedError: 'InvalidQuoteThreshold',
       },
+      {
+        name: 'rejects migration base threshold >= total supply',
+        args: {
+          ...baseValidArgs,
+          migrationBaseThreshold: 1_000_000_000_000_000n,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects virtual base reserve that overflows the curve math',
+        args: {
+          ...baseValidArgs,
+          initialVirtualBaseReserve: 18_000_000_000_000_000_000n,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects pre-migration cooldown > 1 day',
+        args: {
+          ...baseValidArgs,
+          preMigrationCooldownSeconds: 86_401n,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects unknown migration token order',
+        args: {
+          ...baseValidArgs,
+          migrationTokenOrder: 2,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects early buyer window > 1 day',
+        args: {
+          ...baseValidArgs,
+          earlyBuyerWindowSeconds: 86_401n,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects min hold time > 1 day',
+        args: {
+          ...baseValidArgs,
+          minHoldSeconds: 86_401n,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects an anti-snipe window without a buy cap',
+        args: {
+          ...baseValidArgs,
+          antiSnipeWindowSlots: 100n,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects an anti-snipe window > 9000 slots',
+        args: {
+          ...baseValidArgs,
+          antiSnipeWindowSlots: 9_001n,
+          maxBuyAmountFirstWindow: 1_000_000_000n,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects a launch fee below the trading fee',
+        args: {
+          ...baseValidArgs,
+          launchFeeBasisPoints: baseValidArgs.feeBasisPoints,
+          launchFeeDecaySeconds: 3_600n,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects a launch fee decay > 1 day',
+        args: {
+          ...baseValidArgs,
+          launchFeeBasisPoints: 20_000,
+          launchFeeDecaySeconds: 86_401n,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects insurance fund share > 20% of protocol fee',
+        args: {
+          ...baseValidArgs,
+          insuranceFundBasisPoints: 20_001,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects creator fee protocol cut > 50% of creator claims',
+        args: {
+          ...baseValidArgs,
+          creatorFeeProtocolBasisPoints: 50_001,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects an unknown fee collection mode',
+        args: {
+          ...baseValidArgs,
+          feeCollectionMode: 3,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects support fund share > 50% of the migration fee',
+        args: {
+          ...baseValidArgs,
+          supportFundBasisPoints: 50_001,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects a cashback multiplier > 1x the tier cashback',
+        args: {
+          ...baseValidArgs,
+          sellCashbackMultiplierBasisPoints: 100_001,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects a creator LP fee share bound > 50%',
+        args: {
+          ...baseValidArgs,
+          maxCreatorLpFeeShareBasisPoints: 50_001,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects a creator LP share > 50% of the migrated liquidity',
+        args: {
+          ...baseValidArgs,
+          creatorLpBasisPoints: 50_001,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects a migration fee > 10%',
+        args: {
+          ...baseValidArgs,
+          migrationFeeBasisPoints: 10_001,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects unknown migration fee mode',
+        args: {
+          ...baseValidArgs,
+          migrationFeeMode: 2,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects unknown fee remainder mode',
+        args: {
+          ...baseValidArgs,
+          feeRemainderMode: 2,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects unknown threshold decay mode',
+        args: {
+          ...baseValidArgs,
+          thresholdDecayMode: 3,
+          thresholdDecayFloorBasisPoints: 80_000,
+          thresholdDecaySeconds: 86_400n,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects threshold decay floor < 50% of the threshold',
+        args: {
+          ...baseValidArgs,
+          thresholdDecayMode: 1,
+          thresholdDecayFloorBasisPoints: 49_999,
+          thresholdDecaySeconds: 86_400n,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects threshold decay > 90 days',
+        args: {
+          ...baseValidArgs,
+          thresholdDecayMode: 2,
+          thresholdDecayFloorBasisPoints: 80_000,
+          thresholdDecaySeconds: 7_776_001n,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects locked vesting without periods',
+        args: {
+          ...baseValidArgs,
+          lockedVesting: { ...NO_LOCKED_VESTING, cliffDurationSeconds: 86_400n, amountPerPeriod: 1_000_000n },
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects locked vesting > 2 years',
+        args: {
+          ...baseValidArgs,
+          lockedVesting: {
+            cliffDurationSeconds: 1n,
+            periodSeconds: 86_400n,
+            amountPerPeriod: 1n,
+            numberOfPeriods: 730n, // 2 years of daily unlocks
+          },
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects locked vesting eating into the migration amount',
+        args: {
+          ...baseValidArgs,
+          lockedVesting: {
+            cliffDurationSeconds: 0n,
+            periodSeconds: 86_400n,
+            amountPerPeriod: 100_000_000_000_000n, // 10% of the supply
+            numberOfPeriods: 8n,
+          },
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
+      {
+        name: 'rejects param change delay > 7 days',
+        args: {
+          ...baseValidArgs,
+          paramChangeDelaySeconds: 604_801n,
+        },
+        quoteMint: WSOL_MINT,
+        expectedError: 'InvalidAmmConfig',
+      },
     ]
 
     for (const { name, args, quoteMint = WSOL_MINT, expectedError } of validationTests) {
@@ -246,4 +600,90 @@ describe('Admin Config Tests', () => {
       })
     }
   })
+
+  describe('config change - timelock', () => {
+    const changeParams = {
+      feeBasisPoints: FEE_BASIS_POINTS + 100,
+      l1ReferralFeeBasisPoints: L1_REFERRAL_FEE_BASIS_POINTS,
+      l2ReferralFeeBasisPoints: L2_REFERRAL_FEE_BASIS_POINTS,
+      l3ReferralFeeBasisPoints: L3_REFERRAL_FEE_BASIS_POINTS,
+      refereeDiscountBasisPoints: REFEREE_DISCOUNT_BASIS_POINTS,
+      creatorFeeBasisPoints: CREATOR_FEE_BASIS_POINTS,
+      migrationFeeBasisPoints: MIGRATION_FEE_BASIS_POINTS,
+      migrationBaseThreshold: DEFAULT_CONFIG_ARGS.migrationBaseThreshold,
+      migrationQuoteThreshold: DEFAULT_CONFIG_ARGS.migrationQuoteThreshold,
+      preMigrationCooldownSeconds: 60n,
+    }
+
+    test('config change - propose then execute applies the change', async () => {
+      const { configAddress } = await ctx.createConfig(DEFAULT_CONFIG_ARGS, WSOL_MINT)
+      await ctx.proposeConfigChange({ configAddress, params: changeParams })
+
+      // nothing changes until the change is executed
+      const proposedConfig = await ctx.getConfigData({ configAddress })
+      expect(proposedConfig.data.feeBasisPoints).toEqual(FEE_BASIS_POINTS)
+
+      await ctx.executeConfigChange({ configAddress })
+      const executedConfig = await ctx.getConfigData({ configAddress })
+      expect(executedConfig.data.feeBasisPoints).toEqual(FEE_BASIS_POINTS + 100)
+      expect(executedConfig.data.preMigrationCooldownSeconds).toEqual(60n)
+    })
+
+    test('config change - cannot execute before the delay', async () => {
+      const { configAddress } = await ctx.createConfig(
+        { ...DEFAULT_CONFIG_ARGS, paramChangeDelaySeconds: 3_600n },
+        WSOL_MINT,
+      )
+      await ctx.proposeConfigChange({ configAddress, params: changeParams })
+
+      expect(ctx.executeConfigChange({ configAddress })).rejects.toThrow()
+
+      // the pending change can still be cancelled
+      await ctx.cancelConfigChange({ configAddress })
+      const config = await ctx.getConfigData({ configAddress })
+      expect(config.data.feeBasisPoints).toEqual(FEE_BASIS_POINTS)
+    })
+
+    test('config change - rejects non-admin proposals', async () => {
+      const { configAddress } = await ctx.createConfig(DEFAULT_CONFIG_ARGS, WSOL_MINT)
+      const nonOwner = await ctx.createTestTrader()
+
+      expect(ctx.proposeConfigChange({ configAddress, params: changeParams, signer: nonOwner })).rejects.toThrow()
+    })
+
+    test('config change - rejects invalid fee split', async () => {
+      const { configAddress } = await ctx.createConfig(DEFAULT_CONFIG_ARGS, WSOL_MINT)
+
+      expect(
+        ctx.proposeConfigChange({ configAddress, params: { ...changeParams, feeBasisPoints: 0 } }),
+      ).rejects.toThrow()
+    })
+
+    test('config change - rejects changes that break the config invariants', async () => {
+      const lockedVesting = {
+        cliffDurationSeconds: 0n,
+        periodSeconds: 86_400n,
+        amountPerPeriod: 1_000_000_000_000n,
+        numberOfPeriods: 90n,
+      }
+      const { configAddress } = await ctx.createConfig({ ...DEFAULT_CONFIG_ARGS, lockedVesting }, WSOL_MINT)
+
+      // the migration amount has to leave something to sell on the curve
+      await expect(
+        ctx.proposeConfigChange({
+          configAddress,
+          params: { ...changeParams, migrationBaseThreshold: TOKEN_TOTAL_SUPPLY },
+        }),
+      ).rejects.toThrow()
+
+      // and room for the creator's locked tokens
+      const lockedAmount = lockedVesting.amountPerPeriod * lockedVesting.numberOfPeriods
+      await expect(
+        ctx.proposeConfigChange({
+          configAddress,
+          params: { ...changeParams, migrationBaseThreshold: TOKEN_TOTAL_SUPPLY - lockedAmount },
+        }),
+      ).rejects.toThrow()
+    })
+  })
 })
@@ -0,0 +1,158 @@
+import { beforeAll, beforeEach, describe, expect, test } from 'bun:test'
+import { type Address, type KeyPairSigner, LAMPORTS_PER_SOL } from 'gill'
+import { DEFAULT_CONFIG_ARGS, SINGLE_BUY_AMOUNT, WSOL_MINT } from './utils/constants.ts'
+import { TestContextClass } from './utils/context.ts'
+import { TradeDirection } from './utils/swap-quote.ts'
+
+const bonusAmount = BigInt(LAMPORTS_PER_SOL)
+const largeBuyAmount = BigInt(100 * LAMPORTS_PER_SOL) // Large enough to trigger graduation
+
+describe('Bonus Pool Tests', () => {
+  let ctx: TestContextClass
+  let token: Address
+  let curve: Address
+  let creator: KeyPairSigner
+  let trader: KeyPairSigner
+
+  beforeAll(async () => {
+    ctx = await TestContextClass.create()
+    await ctx.createConfigOnce(DEFAULT_CONFIG_ARGS)
+  })
+
+  beforeEach(async () => {
+    creator = await ctx.createTestTrader(BigInt(5 * LAMPORTS_PER_SOL))
+    const result = await ctx.createFreshBondingCurve(undefined, creator)
+    token = result.token
+    curve = result.curvePda
+    trader = await ctx.createTestTrader(BigInt(200 * LAMPORTS_PER_SOL))
+    await ctx.depositBonusPool({ creator, baseMint: token, amount: bonusAmount })
+    await ctx.createProtection({ user: trader, curve })
+  })
+
+  test('bonus pool - accrues early buys', async () => {
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: SINGLE_BUY_AMOUNT,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      withBonusPool: true,
+    })
+
+    const [protection, bonusPool] = await Promise.all([
+      ctx.getProtectionData({ curve, user: trader.address }),
+      ctx.getBonusPoolData({ curve }),
+    ])
+
+    expect(bonusPool.data.totalDeposit).toBe(bonusAmount)
+    expect(protection.data.earlyBuyQuoteAmount).toBeGreaterThan(0n)
+    expect(bonusPool.data.totalEligibleQuoteAmount).toBe(protection.data.earlyBuyQuoteAmount)
+  })
+
+  test('bonus pool - cannot claim before graduation', async () => {
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: SINGLE_BUY_AMOUNT,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      withBonusPool: true,
+    })
+
+    expect(ctx.claimBonus({ user: trader, baseMint: token })).rejects.toThrow()
+  })
+
+  test('bonus pool - sole early buyer claims the whole deposit after graduation', async () => {
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      withBonusPool: true,
+    })
+
+    const preClaimBalance = await ctx.getTokenBalance({ address: trader.address, mint: WSOL_MINT })
+    await ctx.claimBonus({ user: trader, baseMint: token })
+
+    const [postClaimBalance, protection, bonusPool] = await Promise.all([
+      ctx.getTokenBalance({ address: trader.address, mint: WSOL_MINT }),
+      ctx.getProtectionData({ curve, user: trader.address }),
+      ctx.getBonusPoolData({ curve }),
+    ])
+
+    expect(postClaimBalance).toBe(preClaimBalance + bonusAmount)
+    expect(protection.data.bonusClaimed).toBe(1)
+    expect(bonusPool.data.totalClaimed).toBe(bonusAmount)
+
+    // claiming twice is not allowed
+    expect(ctx.claimBonus({ user: trader, baseMint: token })).rejects.toThrow()
+  })
+
+  test('bonus pool - early buys sold without the bonus accounts earn nothing', async () => {
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: SINGLE_BUY_AMOUNT,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      withBonusPool: true,
+    })
+    // the sell leaves out the protection and bonus pool accounts
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: await ctx.getTokenBalance({ address: trader.address, mint: token }),
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.BaseToQuote,
+    })
+    const graduator = await ctx.createTestTrader(BigInt(200 * LAMPORTS_PER_SOL))
+    await ctx.swap({
+      trader: graduator,
+      baseMint: token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    // the claim settles against the base still held
+    await expect(ctx.claimBonus({ user: trader, baseMint: token })).rejects.toThrow()
+    const bonusPool = await ctx.getBonusPoolData({ curve })
+    expect(bonusPool.data.totalClaimed).toBe(0n)
+  })
+
+  test('bonus pool - lamports sent to the creator WSOL account are synced before the deposit', async () => {
+    const { bonusVault } = await ctx.depositBonusPool({
+      creator,
+      baseMint: token,
+      amount: bonusAmount,
+      syncNative: false,
+    })
+
+    const [bonusPool, bonusVaultBalance, creatorQuoteBalance] = await Promise.all([
+      ctx.getBonusPoolData({ curve }),
+      ctx.getTokenAccountBalance(bonusVault),
+      ctx.getTokenBalance({ address: creator.address, mint: WSOL_MINT }),
+    ])
+    expect(bonusPool.data.totalDeposit).toBe(2n * bonusAmount)
+    expect(bonusVaultBalance).toBe(2n * bonusAmount)
+    expect(creatorQuoteBalance).toBe(0n)
+  })
+
+  test('bonus pool - deposit wraps the creator lamports the WSOL account is short of', async () => {
+    const preDepositLamports = await ctx.getBalance(creator.address)
+    const { bonusVault } = await ctx.depositBonusPool({
+      creator,
+      baseMint: token,
+      amount: bonusAmount,
+      wrappedAmount: 0n,
+    })
+
+    const [bonusVaultBalance, postDepositLamports] = await Promise.all([
+      ctx.getTokenAccountBalance(bonusVault),
+      ctx.getBalance(creator.address),
+    ])
+    expect(bonusVaultBalance).toBe(2n * bonusAmount)
+    expect(postDepositLamports).toBeLessThanOrEqual(preDepositLamports - bonusAmount)
+  })
+})
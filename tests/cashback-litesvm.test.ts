@@ -115,6 +115,14 @@ describe('cashback litesvm tests', () => {
         migrationQuoteThreshold: new BN(DEFAULT_CONFIG_ARGS.migrationQuoteThreshold),
         initialVirtualQuoteReserve: new BN(DEFAULT_CONFIG_ARGS.initialVirtualQuoteReserve),
         initialVirtualBaseReserve: new BN(DEFAULT_CONFIG_ARGS.initialVirtualBaseReserve),
+        preMigrationCooldownSeconds: new BN(DEFAULT_CONFIG_ARGS.preMigrationCooldownSeconds),
+        minCreatorFeeAmount: new BN(DEFAULT_CONFIG_ARGS.minCreatorFeeAmount),
+        earlyBuyerWindowSeconds: new BN(DEFAULT_CONFIG_ARGS.earlyBuyerWindowSeconds),
+        minHoldSeconds: new BN(DEFAULT_CONFIG_ARGS.minHoldSeconds),
+        antiSnipeWindowSlots: new BN(DEFAULT_CONFIG_ARGS.antiSnipeWindowSlots),
+        maxBuyAmountFirstWindow: new BN(DEFAULT_CONFIG_ARGS.maxBuyAmountFirstWindow),
+        launchFeeDecaySeconds: new BN(DEFAULT_CONFIG_ARGS.launchFeeDecaySeconds),
+        paramChangeDelaySeconds: new BN(DEFAULT_CONFIG_ARGS.paramChangeDelaySeconds),
       })
       .accounts({
         config: configKeyPair.publicKey,
@@ -193,7 +201,7 @@ describe('cashback litesvm tests', () => {
       .createCashback()
       .accounts({
         payer: trader.publicKey,
-        wsolMint: quoteMint.publicKey,
+        quoteMint: quoteMint.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
       .signers([trader])
@@ -1,7 +1,14 @@
 import { beforeAll, beforeEach, describe, expect, test } from 'bun:test'
 import type { Address, KeyPairSigner } from 'gill'
+import { TOKEN_PROGRAM_ADDRESS, getAssociatedTokenAccountAddress } from 'gill/programs'
 import { fetchBondingCurve } from '~/clients'
-import { DEFAULT_CONFIG_ARGS, SINGLE_BUY_AMOUNT, WSOL_MINT } from './utils/constants.ts'
+import {
+  DEFAULT_CASHBACK_CLAIM_COOLDOWNS,
+  DEFAULT_CONFIG_ARGS,
+  SINGLE_BUY_AMOUNT,
+  WSOL_MINT,
+} from './utils/constants.ts'
+import { CashbackMode } from './utils/accounts.ts'
 import { TestContextClass } from './utils/context.ts'
 import { TradeDirection, getSwapResult } from './utils/swap-quote.ts'
 
@@ -165,6 +172,240 @@ describe('Cashback Tests', () => {
     },
   )
 
+  test('cashback - tier does not decay while the user is active', async () => {
+    await ctx.createCashbackAccount(trader)
+    await ctx.updateCashbackTier({ user: trader.address, newTier: 3 })
+    const cranker = await ctx.createTestTrader()
+
+    // the tier was just updated, the decay period hasn't started yet
+    expect(ctx.decayCashbackTier({ cranker, user: trader.address })).rejects.toThrow()
+
+    const accountData = await ctx.getCashbackAccountData(trader.address)
+    expect(accountData.data.currentTier).toBe(3)
+    expect(accountData.data.tierUpdatedTimestamp).toBeGreaterThanOrEqual(accountData.data.lastTradeTimestamp)
+  })
+
+  test('cashback - sequenced tier updates reject stale retries', async () => {
+    await ctx.createCashbackAccount(trader)
+    await ctx.updateCashbackTier({ user: trader.address, newTier: 3, updateSeq: 2n })
+
+    // a retry and an older update landing late leave the tier alone
+    expect(ctx.updateCashbackTier({ user: trader.address, newTier: 3, updateSeq: 2n })).rejects.toThrow()
+    expect(ctx.updateCashbackTier({ user: trader.address, newTier: 1, updateSeq: 1n })).rejects.toThrow()
+    await ctx.updateCashbackTier({ user: trader.address, newTier: 5, updateSeq: 3n })
+
+    const accountData = await ctx.getCashbackAccountData(trader.address)
+    expect(accountData.data.currentTier).toBe(5)
+    expect(accountData.data.tierUpdateSeq).toBe(3n)
+  })
+
+  test('cashback - lifetime stats accrue with every trade', async () => {
+    await ctx.createCashbackAccount(trader)
+    const initialData = await ctx.getCashbackAccountData(trader.address)
+    expect(initialData.data.tradeCount).toBe(0n)
+    expect(initialData.data.totalEarned).toBe(0n)
+
+    for (let i = 0; i < 2; i++) {
+      await ctx.swap({
+        trader,
+        baseMint: token,
+        amountIn: SINGLE_BUY_AMOUNT,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        cashbackAddress: trader.address,
+      })
+    }
+
+    const [accountData, cashbackTokenBalance] = await Promise.all([
+      ctx.getCashbackAccountData(trader.address),
+      ctx.getCashbackTokenAccountBalance({ user: trader.address }),
+    ])
+    expect(accountData.data.tradeCount).toBe(2n)
+    expect(accountData.data.volumeQuote).toBe(SINGLE_BUY_AMOUNT * 2n)
+    expect(accountData.data.totalEarned).toBe(cashbackTokenBalance)
+    expect(accountData.data.totalClaimed).toBe(0n)
+  })
+
+  test('cashback - claim cooldown follows the tier', async () => {
+    await ctx.createCashbackAccount(trader)
+    const woodData = await ctx.getCashbackAccountData(trader.address)
+    expect(woodData.data.nextClaimTimestamp).toBe(
+      woodData.data.lastClaimTimestamp + DEFAULT_CASHBACK_CLAIM_COOLDOWNS[0]!,
+    )
+
+    await ctx.updateCashbackTier({ user: trader.address, newTier: 6 })
+    const championData = await ctx.getCashbackAccountData(trader.address)
+    expect(championData.data.nextClaimTimestamp).toBe(
+      championData.data.lastClaimTimestamp + DEFAULT_CASHBACK_CLAIM_COOLDOWNS[6]!,
+    )
+
+    // still within the Champion cooldown
+    expect(ctx.claimCashback(trader)).rejects.toThrow()
+  })
+
+  test('cashback - rejects claim cooldowns increasing with the tier', async () => {
+    const claimCooldownSeconds = [...DEFAULT_CASHBACK_CLAIM_COOLDOWNS].reverse()
+    expect(ctx.setCashbackConfig({ claimCooldownSeconds })).rejects.toThrow()
+  })
+
+  test('cashback - higher tiers get a trading fee discount off the protocol fee', async () => {
+    const feeDiscountBasisPoints = [0, 0, 100, 200, 300, 400, 10_000]
+    await ctx.setCashbackConfig({ feeDiscountBasisPoints })
+    try {
+      await ctx.createCashbackAccount(trader)
+      const wood = await ctx.getFeeSchedule({ wallet: trader.address, withCashback: true })
+      expect(wood.feeDiscountBasisPoints).toBe(0)
+
+      await ctx.updateCashbackTier({ user: trader.address, newTier: 3 })
+      const gold = await ctx.getFeeSchedule({ wallet: trader.address, withCashback: true })
+      expect(gold.feeDiscountBasisPoints).toBe(200)
+      expect(gold.tradingFeeBasisPoints).toBe(wood.tradingFeeBasisPoints - 200)
+      expect(gold.creatorFeeBasisPoints).toBe(wood.creatorFeeBasisPoints)
+
+      // the Champion discount is larger than the protocol's part, it stops at a zero protocol fee
+      await ctx.updateCashbackTier({ user: trader.address, newTier: 6 })
+      const champion = await ctx.getFeeSchedule({ wallet: trader.address, withCashback: true })
+      expect(champion.protocolFeeBasisPoints).toBe(0)
+      expect(champion.tradingFeeBasisPoints).toBe(champion.creatorFeeBasisPoints + champion.cashbackBasisPoints)
+    } finally {
+      await ctx.setCashbackConfig({})
+    }
+  })
+
+  test('cashback - rejects fee discounts decreasing with the tier', async () => {
+    const feeDiscountBasisPoints = [300, 200, 100, 0, 0, 0, 0]
+    expect(ctx.setCashbackConfig({ feeDiscountBasisPoints })).rejects.toThrow()
+  })
+
+  test('cashback - tier follows the rolling volume', async () => {
+    await ctx.createCashbackAccount(trader)
+    await ctx.updateCashbackTier({ user: trader.address, newTier: 4 })
+    const cranker = await ctx.createTestTrader()
+
+    // the admin override lasts until the next refresh
+    await ctx.refreshCashbackTier({ cranker, user: trader.address })
+    expect((await ctx.getCashbackAccountData(trader.address)).data.currentTier).toBe(0)
+
+    // two 1 SOL buys, fees included, pass the Bronze threshold
+    for (let i = 0; i < 2; i++) {
+      await ctx.swap({
+        trader,
+        baseMint: token,
+        amountIn: SINGLE_BUY_AMOUNT,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        cashbackAddress: trader.address,
+      })
+    }
+    await ctx.refreshCashbackTier({ cranker, user: trader.address })
+
+    const accountData = await ctx.getCashbackAccountData(trader.address)
+    expect(accountData.data.currentTier).toBe(1)
+    expect(accountData.data.volumeBuckets.reduce((sum, volume) => sum + volume, 0n)).toBe(SINGLE_BUY_AMOUNT * 2n)
+  })
+
+  test('cashback - vaults of other quote mints', async () => {
+    await ctx.createCashbackAccount(trader)
+    const payer = await ctx.createTestTrader()
+
+    // any mint can get a vault, here the token of the fresh curve
+    await ctx.createCashbackVault({ payer, user: trader.address, quoteMint: token })
+    expect(await ctx.getCashbackTokenAccountBalance({ user: trader.address, quoteMint: token })).toBe(0n)
+
+    // opening it again is a no-op
+    await ctx.createCashbackVault({ payer: trader, user: trader.address, quoteMint: token })
+
+    // nothing accrued in the vault
+    expect(ctx.claimCashback(trader, { quoteMint: token })).rejects.toThrow()
+  })
+
+  test('cashback - native mode accrues lamports on the cashback account', async () => {
+    const cashbackPda = await ctx.createCashbackAccount(trader)
+    const swapArgs = {
+      trader,
+      baseMint: token,
+      amountIn: SINGLE_BUY_AMOUNT,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      cashbackAddress: trader.address,
+      useNativeSol: true,
+      nativeCashback: true,
+    }
+
+    // the token mode needs the WSOL vault
+    expect(ctx.swap(swapArgs)).rejects.toThrow()
+
+    await ctx.setCashbackMode({ user: trader, cashbackMode: CashbackMode.Native })
+    const [configState, curveState, initialLamports, initialCashbackTokenBalance] = await Promise.all([
+      ctx.getConfigData({}),
+      fetchBondingCurve(ctx.rpc, curve),
+      ctx.getBalance(cashbackPda),
+      ctx.getCashbackTokenAccountBalance({ user: trader.address }),
+    ])
+    const swapResult = getSwapResult({
+      curveState: curveState.data,
+      configState: configState.data,
+      amountIn: SINGLE_BUY_AMOUNT,
+      tradeDirection: TradeDirection.QuoteToBase,
+      hasL1Referral: false,
+      hasL2Referral: false,
+      hasL3Referral: false,
+      cashbackTier: 0,
+    })
+
+    await ctx.swap(swapArgs)
+
+    const [finalLamports, finalCashbackTokenBalance] = await Promise.all([
+      ctx.getBalance(cashbackPda),
+      ctx.getCashbackTokenAccountBalance({ user: trader.address }),
+    ])
+    expect(finalLamports - initialLamports).toBe(swapResult.cashbackFee)
+    expect(finalCashbackTokenBalance).toBe(initialCashbackTokenBalance)
+  })
+
+  test('cashback - close refunds the rent', async () => {
+    const cashbackPda = await ctx.createCashbackAccount(trader)
+    const cashbackVault = await getAssociatedTokenAccountAddress(WSOL_MINT, cashbackPda, TOKEN_PROGRAM_ADDRESS)
+    const [cashbackRent, vaultRent, initialBalance] = await Promise.all([
+      ctx.getBalance(cashbackPda),
+      ctx.getBalance(cashbackVault),
+      ctx.getBalance(trader.address),
+    ])
+
+    await ctx.closeCashback(trader)
+
+    const [cashbackInfo, vaultInfo, finalBalance] = await Promise.all([
+      ctx.rpc.getAccountInfo(cashbackPda, { encoding: 'base64' }).send(),
+      ctx.rpc.getAccountInfo(cashbackVault, { encoding: 'base64' }).send(),
+      ctx.getBalance(trader.address),
+    ])
+    expect(cashbackInfo.value).toBeNull()
+    expect(vaultInfo.value).toBeNull()
+    // minus the transaction fee
+    expect(finalBalance).toBeGreaterThan(initialBalance + cashbackRent + vaultRent - 10_000n)
+
+    // the account can be created again
+    await ctx.createCashbackAccount(trader)
+  })
+
+  test('cashback - close waits for the claim cooldown to sweep the vault', async () => {
+    await ctx.createCashbackAccount(trader)
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: SINGLE_BUY_AMOUNT,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      cashbackAddress: trader.address,
+    })
+
+    expect(ctx.closeCashback(trader)).rejects.toThrow()
+  })
+
+  test.skip('cashback - tier decay', async () => {
+    // TODO: time travel past the decay period, maybe with liteSVM?
+  })
+
   test.skip('cashback - claim', async () => {
     // TODO: time travel to claim cashback, maybe with liteSVM?
   })
@@ -1,10 +1,11 @@
 import { beforeAll, describe, expect, test } from 'bun:test'
 import { fetchMint } from '@solana-program/token-2022'
-import { type Address, type KeyPairSigner, generateKeyPairSigner } from 'gill'
+import { type Address, type KeyPairSigner, LAMPORTS_PER_SOL, generateKeyPairSigner } from 'gill'
 import { fetchMetadata } from 'gill/programs'
-import { type BondingCurve, fetchBondingCurve } from '~/clients'
-import { getCurveVaultPda, getMetadataPda } from './utils/accounts'
+import { type BondingCurve, fetchBondingCurve, fetchCurveMetadata, fetchMintIndex } from '~/clients'
+import { getCurveVaultPda, getMetadataPda, getMintIndexPda } from './utils/accounts'
 import {
+  CURVE_VERSION,
   DEFAULT_CONFIG_ARGS,
   DEFAULT_TOKEN,
   TOKEN_DECIMALS,
@@ -13,6 +14,7 @@ import {
   WSOL_MINT,
 } from './utils/constants'
 import { TestContextClass } from './utils/context.ts'
+import { TradeDirection } from './utils/swap-quote.ts'
 
 const expectBondingCurveValues = (
   bondingCurve: Awaited<ReturnType<typeof fetchBondingCurve>>,
@@ -298,6 +300,250 @@ describe('Bonding Curve Creation Tests', () => {
     },
   ]
 
+  test('curve - should create a Token-2022 token with the metadata in the mint', async () => {
+    const { configAddress: token2022Config } = await ctx.createConfig({ ...DEFAULT_CONFIG_ARGS, baseTokenFlag: 1 })
+    const mintKeypair = await generateKeyPairSigner()
+
+    await ctx.createBondingCurveAndMintToken({
+      configAddress: token2022Config,
+      creator,
+      mintKeypair,
+      tokenMetadata: DEFAULT_TOKEN,
+    })
+
+    const bondingCurve = await ctx.getBondingCurveData({
+      baseMint: mintKeypair.address,
+      configAddress: token2022Config,
+    })
+    expect(bondingCurve.data.curveType).toBe(1)
+    expect(await ctx.getTokenAccountBalance(bondingCurve.data.baseVault)).toBe(TOKEN_TOTAL_SUPPLY)
+    await verifyTokenMint(mintKeypair.address, { decimals: TOKEN_DECIMALS, supply: TOKEN_TOTAL_SUPPLY })
+
+    const mint = await fetchMint(ctx.rpc, mintKeypair.address)
+    const extensions = mint.data.extensions.__option === 'Some' ? mint.data.extensions.value : []
+    const metadataPointer = extensions.find((extension) => extension.__kind === 'MetadataPointer')
+    const metadata = extensions.find((extension) => extension.__kind === 'TokenMetadata')
+    expect(metadataPointer?.metadataAddress).toEqual({ __option: 'Some', value: mintKeypair.address })
+    expect(metadata?.name).toBe(DEFAULT_TOKEN.name)
+    expect(metadata?.symbol).toBe(DEFAULT_TOKEN.symbol)
+    expect(metadata?.uri).toBe(DEFAULT_TOKEN.uri)
+    expect(metadata?.updateAuthority.__option).toBe('None')
+  })
+
+  test('curve - the base mint resolves to its curve through the mint index', async () => {
+    const { configAddress: token2022Config } = await ctx.createConfig({ ...DEFAULT_CONFIG_ARGS, baseTokenFlag: 1 })
+    for (const configAddress of [config, token2022Config]) {
+      const mintKeypair = await generateKeyPairSigner()
+      const { curvePda } = await ctx.createBondingCurveAndMintToken({ configAddress, creator, mintKeypair })
+
+      const [mintIndexPda] = await getMintIndexPda({ baseMint: mintKeypair.address, programId: ctx.programId })
+      const { data: mintIndex } = await fetchMintIndex(ctx.rpc, mintIndexPda)
+      expect(mintIndex.baseMint).toBe(mintKeypair.address)
+      expect(mintIndex.curve).toBe(curvePda)
+      expect(mintIndex.config).toBe(configAddress)
+    }
+  })
+
+  test('curve - new curves are created on the current layout and need no realloc', async () => {
+    const { curvePda } = await ctx.createBondingCurveAndMintToken({
+      configAddress: config,
+      creator,
+      mintKeypair: await generateKeyPairSigner(),
+    })
+    const { data: curve } = await fetchBondingCurve(ctx.rpc, curvePda)
+    expect(curve.version).toBe(CURVE_VERSION)
+
+    // only curves created on an older layout are grown
+    expect(ctx.reallocCurve({ curve: curvePda, configAddress: config })).rejects.toThrow()
+  })
+
+  test('curve - creator picks its share of the position fees within the config bound', async () => {
+    const maxShare = DEFAULT_CONFIG_ARGS.maxCreatorLpFeeShareBasisPoints
+    expect(
+      ctx.createBondingCurveAndMintToken({
+        configAddress: config,
+        creator,
+        mintKeypair: await generateKeyPairSigner(),
+        creatorLpFeeShareBasisPoints: maxShare + 1,
+      }),
+    ).rejects.toThrow()
+
+    const { curvePda } = await ctx.createBondingCurveAndMintToken({
+      configAddress: config,
+      creator,
+      mintKeypair: await generateKeyPairSigner(),
+      creatorLpFeeShareBasisPoints: maxShare,
+    })
+    const { data: curve } = await fetchBondingCurve(ctx.rpc, curvePda)
+    expect(curve.creatorLpFeeShareBasisPoints).toBe(maxShare)
+  })
+
+  test('curve - whitelist mode only lets whitelisted creators create curves', async () => {
+    const { configAddress: whitelistConfig } = await ctx.createConfig(DEFAULT_CONFIG_ARGS)
+    await ctx.setCreatorWhitelistMode({ enabled: true, configAddress: whitelistConfig })
+    const createCurve = async (whitelisted: boolean) =>
+      ctx.createBondingCurveAndMintToken({
+        configAddress: whitelistConfig,
+        creator,
+        whitelisted,
+        mintKeypair: await generateKeyPairSigner(),
+      })
+
+    expect(createCurve(false)).rejects.toThrow()
+    // the entry has to exist, passing its address isn't enough
+    expect(createCurve(true)).rejects.toThrow()
+
+    await ctx.addWhitelistedCreator({ creator: creator.address, configAddress: whitelistConfig })
+    const { curvePda } = await createCurve(true)
+    const { data: curve } = await fetchBondingCurve(ctx.rpc, curvePda)
+    expect(curve.creator).toBe(creator.address)
+    expect(createCurve(false)).rejects.toThrow()
+
+    await ctx.removeWhitelistedCreator({ creator: creator.address, configAddress: whitelistConfig })
+    expect(createCurve(true)).rejects.toThrow()
+
+    // lifting the mode opens the config to every creator again
+    await ctx.setCreatorWhitelistMode({ enabled: false, configAddress: whitelistConfig })
+    await createCurve(false)
+  })
+
+  test('curve - creations have to commit to the config template', async () => {
+    const { configAddress: templateConfig } = await ctx.createConfig(DEFAULT_CONFIG_ARGS)
+    const templateHash = Array.from({ length: 32 }, (_, i) => i + 1)
+    await ctx.setCurveTemplate({ curveTemplateHash: templateHash, configAddress: templateConfig })
+
+    const configData = await ctx.getConfigData({ configAddress: templateConfig })
+    expect(configData.data.curveTemplateHash).toEqual(templateHash)
+
+    // missing or tampered hashes are rejected
+    expect(
+      ctx.createBondingCurveAndMintToken({
+        configAddress: templateConfig,
+        creator,
+        mintKeypair: await generateKeyPairSigner(),
+      }),
+    ).rejects.toThrow()
+    expect(
+      ctx.createBondingCurveAndMintToken({
+        configAddress: templateConfig,
+        creator,
+        mintKeypair: await generateKeyPairSigner(),
+        paramsHash: templateHash.toReversed(),
+      }),
+    ).rejects.toThrow()
+
+    const { curvePda } = await ctx.createBondingCurveAndMintToken({
+      configAddress: templateConfig,
+      creator,
+      mintKeypair: await generateKeyPairSigner(),
+      paramsHash: templateHash,
+    })
+    expect(curvePda).toBeDefined()
+
+    // configs without a template don't take a hash
+    expect(
+      ctx.createBondingCurveAndMintToken({
+        configAddress: config,
+        creator,
+        mintKeypair: await generateKeyPairSigner(),
+        paramsHash: templateHash,
+      }),
+    ).rejects.toThrow()
+
+    // only admin can publish templates
+    expect(
+      ctx.setCurveTemplate({ curveTemplateHash: templateHash, configAddress: templateConfig, signer: creator }),
+    ).rejects.toThrow()
+  })
+
+  test('curve - creator can buy within the creation instruction', async () => {
+    const amountIn = BigInt(LAMPORTS_PER_SOL)
+
+    // the output of a fresh curve is known up front, so an unreachable minimum fails the whole creation
+    expect(
+      ctx.createBondingCurveAndMintToken({
+        configAddress: config,
+        creator,
+        mintKeypair: await generateKeyPairSigner(),
+        initialBuy: { amountIn, minimumAmountOut: TOKEN_TOTAL_SUPPLY },
+      }),
+    ).rejects.toThrow()
+
+    const mintKeypair = await generateKeyPairSigner()
+    const { curvePda } = await ctx.createBondingCurveAndMintToken({
+      configAddress: config,
+      creator,
+      mintKeypair,
+      initialBuy: { amountIn, minimumAmountOut: 1n },
+    })
+
+    const [bondingCurve, creatorBalance] = await Promise.all([
+      fetchBondingCurve(ctx.rpc, curvePda),
+      ctx.getTokenBalance({ address: creator.address, mint: mintKeypair.address }),
+    ])
+    expect(creatorBalance).toBeGreaterThan(0n)
+    expect(bondingCurve.data.baseReserve).toBe(TOKEN_TOTAL_SUPPLY - creatorBalance)
+    expect(bondingCurve.data.quoteReserve).toBeGreaterThan(0n)
+    expect(bondingCurve.data.holderCount).toBe(1n)
+
+    // the creator's protection account is created with the curve and counts the buy
+    const protection = await ctx.getProtectionData({ curve: curvePda, user: creator.address })
+    expect(protection.data.owner).toBe(creator.address)
+    expect(protection.data.curve).toBe(curvePda)
+  })
+
+  test('curve - the initial buy waits for the activation', async () => {
+    const now = BigInt(Math.floor(Date.now() / 1000))
+    expect(
+      ctx.createBondingCurveAndMintToken({
+        configAddress: config,
+        creator,
+        mintKeypair: await generateKeyPairSigner(),
+        initialBuy: { amountIn: BigInt(LAMPORTS_PER_SOL), minimumAmountOut: 1n },
+        activation: { activationPoint: now + 3_600n },
+      }),
+    ).rejects.toThrow()
+  })
+
+  test('curve - two step creation enables trading once the metadata is written', async () => {
+    const mintKeypair = await generateKeyPairSigner()
+    const { curvePda } = await ctx.createBondingCurveAndMintToken({
+      configAddress: config,
+      creator,
+      mintKeypair,
+      metadataPending: true,
+    })
+    expect((await fetchBondingCurve(ctx.rpc, curvePda)).data.isMetadataPending).toBe(1)
+
+    const buy = () =>
+      ctx.swap({
+        trader: creator,
+        baseMint: mintKeypair.address,
+        amountIn: BigInt(LAMPORTS_PER_SOL / 10),
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+      })
+    expect(buy()).rejects.toThrow()
+
+    // longer than the token metadata URI allows
+    const uri = `https://example.com/${'a'.repeat(300)}.json`
+    const extension = new TextEncoder().encode('{"twitter":"@example"}')
+    const nonCreator = await ctx.createTestTrader()
+    expect(ctx.finalizeCurveMetadata({ creator: nonCreator, curvePda, uri, extension })).rejects.toThrow()
+
+    const curveMetadataAddress = await ctx.finalizeCurveMetadata({ creator, curvePda, uri, extension })
+    const curveMetadata = await fetchCurveMetadata(ctx.rpc, curveMetadataAddress)
+    expect(curveMetadata.data.curve).toBe(curvePda)
+    const { uri: uriBytes, uriLength } = curveMetadata.data
+    expect(new TextDecoder().decode(Uint8Array.from(uriBytes.slice(0, uriLength)))).toBe(uri)
+    expect(curveMetadata.data.extensionLength).toBe(extension.length)
+    expect((await fetchBondingCurve(ctx.rpc, curvePda)).data.isMetadataPending).toBe(0)
+
+    await buy()
+    expect(await ctx.getTokenBalance({ address: creator.address, mint: mintKeypair.address })).toBeGreaterThan(0n)
+    expect(ctx.finalizeCurveMetadata({ creator, curvePda, uri, extension })).rejects.toThrow()
+  })
+
   for (const { name, metadata } of invalidMetadataTests) {
     test(name, async () => {
       const mintKeypair = await generateKeyPairSigner()
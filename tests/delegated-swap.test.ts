@@ -0,0 +1,147 @@
+import { beforeAll, beforeEach, describe, expect, test } from 'bun:test'
+import { createHash } from 'node:crypto'
+import {
+  AccountRole,
+  type Address,
+  type IInstruction,
+  type KeyPairSigner,
+  LAMPORTS_PER_SOL,
+  createTransaction,
+  getProgramDerivedAddress,
+  getU64Encoder,
+  signTransactionMessageWithSigners,
+} from 'gill'
+import { SYSTEM_PROGRAM_ADDRESS, getTransferSolInstruction } from 'gill/programs'
+import { TOKEN_PROGRAM_ADDRESS, getSyncNativeInstruction } from 'gill/programs/token'
+import {
+  getConfigStatsPda,
+  getCurveAuthority,
+  getCurveVaultPda,
+  prepareTokenAccounts,
+} from './utils/accounts.ts'
+import { DEFAULT_CONFIG_ARGS, MOCK_STRATEGY_PROGRAM_ID, SEEDS, WSOL_MINT } from './utils/constants.ts'
+import { TestContextClass } from './utils/context.ts'
+import { TradeDirection, getSwapResult } from './utils/swap-quote.ts'
+
+const VAULT_AUTHORITY_PREFIX = 'vault_authority'
+
+function getSwapViaVaultData(amountIn: bigint, minimumAmountOut: bigint) {
+  const discriminator = createHash('sha256').update('global:swap_via_vault').digest().subarray(0, 8)
+  const u64 = getU64Encoder()
+  return new Uint8Array([...discriminator, ...u64.encode(amountIn), ...u64.encode(minimumAmountOut)])
+}
+
+describe('Delegated swap via CPI', () => {
+  let ctx: TestContextClass
+  let token: Address
+  let curve: Address
+  let operator: KeyPairSigner
+  let vaultAuthority: Address
+
+  beforeAll(async () => {
+    ctx = await TestContextClass.create()
+    await ctx.createConfigOnce(DEFAULT_CONFIG_ARGS)
+    ;[vaultAuthority] = await getProgramDerivedAddress({
+      programAddress: MOCK_STRATEGY_PROGRAM_ID,
+      seeds: [Buffer.from(VAULT_AUTHORITY_PREFIX)],
+    })
+  })
+
+  beforeEach(async () => {
+    const result = await ctx.createFreshBondingCurve()
+    token = result.token
+    curve = result.curvePda
+    operator = await ctx.createTestTrader()
+  })
+
+  test('a PDA owned by another program can buy through CPI', async () => {
+    const amountIn = BigInt(LAMPORTS_PER_SOL)
+    const [curveState, configState] = await Promise.all([
+      ctx.getBondingCurveData({ baseMint: token }),
+      ctx.getConfigData({}),
+    ])
+    const expected = getSwapResult({
+      curveState: curveState.data,
+      configState: configState.data,
+      amountIn,
+      tradeDirection: TradeDirection.QuoteToBase,
+      hasL1Referral: false,
+      hasL2Referral: false,
+      hasL3Referral: false,
+    })
+
+    const [
+      { ataTokenA: vaultQuoteAccount, ataTokenB: vaultBaseAccount, instructions: preInstructions },
+      [baseVault],
+      [quoteVault],
+      [curveAuthority],
+      [configStats],
+      [ammEventAuthority],
+    ] = await Promise.all([
+      prepareTokenAccounts({
+        rpc: ctx.rpc,
+        owner: vaultAuthority,
+        payer: operator,
+        tokenAMint: WSOL_MINT,
+        tokenBMint: token,
+        tokenAProgram: TOKEN_PROGRAM_ADDRESS,
+        tokenBProgram: TOKEN_PROGRAM_ADDRESS,
+      }),
+      getCurveVaultPda({ curvePda: curve, mint: token, programId: ctx.programId }),
+      getCurveVaultPda({ curvePda: curve, mint: WSOL_MINT, programId: ctx.programId }),
+      getCurveAuthority({ programId: ctx.programId }),
+      getConfigStatsPda({ configAddress: ctx.currentConfig!, programId: ctx.programId }),
+      getProgramDerivedAddress({ programAddress: ctx.programId, seeds: [Buffer.from(SEEDS.EVENT_AUTHORITY)] }),
+    ])
+
+    // fund the strategy vault with WSOL
+    preInstructions.push(
+      getTransferSolInstruction({ source: operator, destination: vaultQuoteAccount, amount: amountIn }),
+      getSyncNativeInstruction({ account: vaultQuoteAccount }, { programAddress: TOKEN_PROGRAM_ADDRESS }),
+    )
+
+    // account ordering must match `SwapViaVaultCtx`
+    const swapViaVaultIx: IInstruction = {
+      programAddress: MOCK_STRATEGY_PROGRAM_ID,
+      accounts: [
+        { address: vaultAuthority, role: AccountRole.READONLY },
+        { address: curveAuthority, role: AccountRole.READONLY },
+        { address: ctx.currentConfig!, role: AccountRole.READONLY },
+        { address: curve, role: AccountRole.WRITABLE },
+        { address: vaultQuoteAccount, role: AccountRole.WRITABLE },
+        { address: vaultBaseAccount, role: AccountRole.WRITABLE },
+        { address: baseVault, role: AccountRole.WRITABLE },
+        { address: quoteVault, role: AccountRole.WRITABLE },
+        { address: token, role: AccountRole.READONLY },
+        { address: WSOL_MINT, role: AccountRole.READONLY },
+        { address: TOKEN_PROGRAM_ADDRESS, role: AccountRole.READONLY },
+        { address: TOKEN_PROGRAM_ADDRESS, role: AccountRole.READONLY },
+        { address: configStats, role: AccountRole.WRITABLE },
+        { address: ammEventAuthority, role: AccountRole.READONLY },
+        { address: ctx.programId, role: AccountRole.READONLY },
+        { address: SYSTEM_PROGRAM_ADDRESS, role: AccountRole.READONLY },
+      ],
+      data: getSwapViaVaultData(amountIn, expected.outputAmount),
+    }
+
+    const { value: latestBlockhash } = await ctx.rpc.getLatestBlockhash().send()
+    const tx = createTransaction({
+      version: 0,
+      feePayer: operator,
+      instructions: [...preInstructions, swapViaVaultIx],
+      latestBlockhash,
+      computeUnitLimit: 400_000,
+    })
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await ctx.sendAndConfirmTransaction(signedTx)
+
+    const [vaultBaseBalance, curveStateAfter] = await Promise.all([
+      ctx.getTokenAccountBalance(vaultBaseAccount),
+      ctx.getBondingCurveData({ baseMint: token }),
+    ])
+    expect(vaultBaseBalance).toEqual(expected.outputAmount)
+    expect(curveStateAfter.data.virtualQuoteReserve).toEqual(
+      curveState.data.virtualQuoteReserve + expected.actualInputAmount,
+    )
+  })
+})
@@ -0,0 +1,170 @@
+import { beforeAll, beforeEach, describe, expect, test } from 'bun:test'
+import { type Address, type KeyPairSigner, LAMPORTS_PER_SOL } from 'gill'
+import { fetchBondingCurve, fetchDualListing } from '~/clients'
+import { DEFAULT_CONFIG_ARGS, getTokenTotalSupply } from './utils/constants.ts'
+import { TestContextClass } from './utils/context.ts'
+import { TradeDirection } from './utils/swap-quote.ts'
+
+const secondaryBaseSupply = 400_000_000_000_000n // 40% of the supply
+
+describe('Dual listing', () => {
+  let ctx: TestContextClass
+  let secondaryConfig: Address
+  let secondaryQuoteMint: Address
+  let creator: KeyPairSigner
+  let token: Address
+  let curve: Address
+
+  beforeAll(async () => {
+    ctx = await TestContextClass.create()
+    await ctx.createConfigOnce(DEFAULT_CONFIG_ARGS)
+
+    // any SPL mint can quote a config, a launched token stands in for a stablecoin here
+    const { token: stableMint } = await ctx.createFreshBondingCurve()
+    secondaryQuoteMint = stableMint
+    ;({ configAddress: secondaryConfig } = await ctx.createConfig(DEFAULT_CONFIG_ARGS, secondaryQuoteMint))
+  })
+
+  beforeEach(async () => {
+    creator = await ctx.createTestTrader()
+    const result = await ctx.createFreshBondingCurve(undefined, creator)
+    token = result.token
+    curve = result.curvePda
+  })
+
+  test('dual listing - splits the supply between both curves', async () => {
+    const { secondaryCurve, dualListing } = await ctx.createDualListing({
+      creator,
+      baseMint: token,
+      secondaryConfig,
+      secondaryQuoteMint,
+      secondaryBaseSupply,
+    })
+
+    const totalSupply = getTokenTotalSupply(DEFAULT_CONFIG_ARGS.baseDecimal)
+    const [primaryState, secondaryState, dualListingState] = await Promise.all([
+      fetchBondingCurve(ctx.rpc, curve),
+      fetchBondingCurve(ctx.rpc, secondaryCurve),
+      fetchDualListing(ctx.rpc, dualListing),
+    ])
+    expect(primaryState.data.baseReserve).toBe(totalSupply - secondaryBaseSupply)
+    expect(secondaryState.data.baseReserve).toBe(secondaryBaseSupply)
+    // both curves keep the price path of their config on their share of the supply
+    expect(secondaryState.data.virtualBaseReserve).toBe(
+      (DEFAULT_CONFIG_ARGS.initialVirtualBaseReserve * secondaryBaseSupply) / totalSupply,
+    )
+    expect(secondaryState.data.migrationBaseThreshold).toBe(
+      (DEFAULT_CONFIG_ARGS.migrationBaseThreshold * secondaryBaseSupply) / totalSupply,
+    )
+    expect(primaryState.data.migrationQuoteThreshold).toBe(
+      (DEFAULT_CONFIG_ARGS.migrationQuoteThreshold * (totalSupply - secondaryBaseSupply)) / totalSupply,
+    )
+    expect(secondaryState.data.config).toBe(secondaryConfig)
+    expect(secondaryState.data.baseMint).toBe(token)
+    expect(primaryState.data.isDualListed).toBe(1)
+    expect(secondaryState.data.isDualListed).toBe(1)
+    expect(dualListingState.data.primaryCurve).toBe(curve)
+    expect(dualListingState.data.secondaryCurve).toBe(secondaryCurve)
+
+    const [secondaryBaseBalance, primaryBaseBalance] = await Promise.all([
+      ctx.getTokenAccountBalance(secondaryState.data.baseVault),
+      ctx.getTokenAccountBalance(primaryState.data.baseVault),
+    ])
+    expect(secondaryBaseBalance).toBe(secondaryBaseSupply)
+    expect(primaryBaseBalance).toBe(totalSupply - secondaryBaseSupply)
+
+    // a base mint is listed at most twice
+    expect(
+      ctx.createDualListing({ creator, baseMint: token, secondaryConfig, secondaryQuoteMint, secondaryBaseSupply }),
+    ).rejects.toThrow()
+  })
+
+  test('dual listing - both curves trade to graduation and migrate together', async () => {
+    const { secondaryCurve, dualListing } = await ctx.createDualListing({
+      creator,
+      baseMint: token,
+      secondaryConfig,
+      secondaryQuoteMint,
+      secondaryBaseSupply,
+    })
+    const trader = await ctx.createTestTrader(BigInt(200 * LAMPORTS_PER_SOL))
+
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: BigInt(100 * LAMPORTS_PER_SOL), // capped at the primary curve's threshold
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    expect((await fetchBondingCurve(ctx.rpc, curve)).data.migrationStatus).toBe(1)
+
+    // the primary curve waits for the secondary one to graduate
+    await expect(ctx.migrate({ curve, baseMint: token })).rejects.toThrow()
+
+    // the secondary quote mint is bought on its own curve, then spent on the secondary curve
+    await ctx.swap({
+      trader,
+      baseMint: secondaryQuoteMint,
+      amountIn: BigInt(LAMPORTS_PER_SOL),
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: await ctx.getTokenBalance({ address: trader.address, mint: secondaryQuoteMint }),
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      quoteMint: secondaryQuoteMint,
+      configAddress: secondaryConfig,
+    })
+    const secondaryState = await fetchBondingCurve(ctx.rpc, secondaryCurve)
+    expect(secondaryState.data.migrationStatus).toBe(1)
+    expect(secondaryState.data.baseReserve).toBe(secondaryState.data.migrationBaseThreshold)
+
+    await ctx.migrate({ curve, baseMint: token })
+    await ctx.migrate({
+      curve: secondaryCurve,
+      baseMint: token,
+      quoteMint: secondaryQuoteMint,
+      config: secondaryConfig,
+    })
+
+    const [primaryState, migratedSecondaryState, dualListingState] = await Promise.all([
+      fetchBondingCurve(ctx.rpc, curve),
+      fetchBondingCurve(ctx.rpc, secondaryCurve),
+      fetchDualListing(ctx.rpc, dualListing),
+    ])
+    expect(primaryState.data.migrationStatus).toBe(2)
+    expect(migratedSecondaryState.data.migrationStatus).toBe(2)
+    expect(dualListingState.data.primaryMigrationTimestamp).toBeGreaterThan(0n)
+    expect(dualListingState.data.secondaryMigrationTimestamp).toBeGreaterThan(0n)
+  })
+
+  test('dual listing - rejects a split below a migration threshold', async () => {
+    expect(
+      ctx.createDualListing({
+        creator,
+        baseMint: token,
+        secondaryConfig,
+        secondaryQuoteMint,
+        secondaryBaseSupply: DEFAULT_CONFIG_ARGS.migrationBaseThreshold,
+      }),
+    ).rejects.toThrow()
+  })
+
+  test('dual listing - rejects curves that already traded', async () => {
+    const trader = await ctx.createTestTrader(BigInt(2 * LAMPORTS_PER_SOL))
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: BigInt(LAMPORTS_PER_SOL),
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    expect(
+      ctx.createDualListing({ creator, baseMint: token, secondaryConfig, secondaryQuoteMint, secondaryBaseSupply }),
+    ).rejects.toThrow()
+  })
+})
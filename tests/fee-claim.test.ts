@@ -1,6 +1,8 @@
 import { beforeAll, beforeEach, describe, expect, test } from 'bun:test'
-import { type Address, type KeyPairSigner, LAMPORTS_PER_SOL } from 'gill'
-import { DEFAULT_CONFIG_ARGS, WSOL_MINT } from './utils/constants.ts'
+import { type Address, type KeyPairSigner, LAMPORTS_PER_SOL, generateKeyPairSigner } from 'gill'
+import { TOKEN_PROGRAM_ADDRESS, getAssociatedTokenAccountAddress } from 'gill/programs/token'
+import { CreatorFeeTransferMode, FeeType } from './utils/accounts.ts'
+import { BASIS_POINTS_DIVISOR, DEFAULT_CONFIG_ARGS, WSOL_MINT } from './utils/constants.ts'
 import { TestContextClass } from './utils/context.ts'
 import { TradeDirection, getSwapResult } from './utils/swap-quote.ts'
 
@@ -108,6 +110,43 @@ describe('Fee Claim Tests', () => {
     expect(finalFeeClaimerTokenBalance).toBe(postClaimFeeBalance + sellExpected.protocolFee)
   })
 
+  test('claim fees - protocol fees of many curves in one batch', async () => {
+    const [second, untraded] = await Promise.all([ctx.createFreshBondingCurve(), ctx.createFreshBondingCurve()])
+    for (const baseMint of [token, second.token]) {
+      await ctx.swap({
+        trader,
+        baseMint,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+      })
+    }
+
+    const [firstCurve, secondCurve, feeClaimerTokenBalance] = await Promise.all([
+      ctx.getBondingCurveData({ baseMint: token }),
+      ctx.getBondingCurveData({ baseMint: second.token }),
+      ctx.getTokenBalance({ address: feeClaimer.address, mint: WSOL_MINT }),
+    ])
+
+    // the untraded curve has nothing to claim and is skipped
+    await ctx.claimProtocolFeeBatch({ feeClaimer, baseMints: [token, second.token, untraded.token] })
+
+    const [postClaimFeeBalance, ...curves] = await Promise.all([
+      ctx.getTokenBalance({ address: feeClaimer.address, mint: WSOL_MINT }),
+      ctx.getBondingCurveData({ baseMint: token }),
+      ctx.getBondingCurveData({ baseMint: second.token }),
+    ])
+    expect(postClaimFeeBalance).toBe(
+      feeClaimerTokenBalance + firstCurve.data.protocolFee + secondCurve.data.protocolFee,
+    )
+    for (const curve of curves) {
+      expect(curve.data.protocolFee).toBe(0n)
+    }
+
+    // a batch with nothing left to claim fails
+    expect(ctx.claimProtocolFeeBatch({ feeClaimer, baseMints: [token, second.token] })).rejects.toThrow()
+  })
+
   test('claim fees - creator fees', async () => {
     const creator = await ctx.createTestTrader()
     const result = await ctx.createFreshBondingCurve(undefined, creator)
@@ -240,4 +279,412 @@ describe('Fee Claim Tests', () => {
     const curveStateAfterFailedClaim = await ctx.getBondingCurveData({ baseMint: token })
     expect(curveStateAfterFailedClaim.data.creatorFee).toBeGreaterThan(0n)
   })
+
+  test('claim fees - protocol fee share goes to the insurance fund', async () => {
+    const { configAddress, feeClaimer: insuredFeeClaimer } = await ctx.createConfig({
+      ...DEFAULT_CONFIG_ARGS,
+      insuranceFundBasisPoints: 10_000, // 10% of protocol fee
+    })
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    token = result.token
+
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+
+    const [curveState, feeClaimerTokenBalance] = await Promise.all([
+      ctx.getBondingCurveData({ baseMint: token, configAddress }),
+      ctx.getTokenBalance({ address: insuredFeeClaimer.address, mint: WSOL_MINT }),
+    ])
+    const protocolFee = curveState.data.protocolFee
+    const insuranceFundAmount = (protocolFee * 10_000n) / BASIS_POINTS_DIVISOR
+
+    // the fund is created by the admin, claims can't go through before it exists
+    await expect(
+      ctx.claimProtocolFees({ feeClaimer: insuredFeeClaimer, baseMint: token, configAddress }),
+    ).rejects.toThrow()
+    await expect(ctx.createInsuranceFund({ configAddress, signer: trader })).rejects.toThrow()
+    await ctx.createInsuranceFund({ configAddress })
+
+    await ctx.claimProtocolFees({ feeClaimer: insuredFeeClaimer, baseMint: token, configAddress })
+
+    const [postClaimFeeBalance, insuranceFund] = await Promise.all([
+      ctx.getTokenBalance({ address: insuredFeeClaimer.address, mint: WSOL_MINT }),
+      ctx.getInsuranceFundData({ configAddress }),
+    ])
+    expect(postClaimFeeBalance).toBe(feeClaimerTokenBalance + protocolFee - insuranceFundAmount)
+    expect(insuranceFund.data.totalAccrued).toBe(insuranceFundAmount)
+
+    // admin pays the fund out (the default config has no timelock)
+    const recipientTokenAccount = await getAssociatedTokenAccountAddress(
+      WSOL_MINT,
+      insuredFeeClaimer.address,
+      TOKEN_PROGRAM_ADDRESS,
+    )
+    await ctx.proposeInsurancePayout({ recipientTokenAccount, amount: insuranceFundAmount, configAddress })
+    await ctx.executeInsurancePayout({ recipientTokenAccount, configAddress })
+
+    const [finalFeeClaimerBalance, finalInsuranceFund] = await Promise.all([
+      ctx.getTokenBalance({ address: insuredFeeClaimer.address, mint: WSOL_MINT }),
+      ctx.getInsuranceFundData({ configAddress }),
+    ])
+    expect(finalFeeClaimerBalance).toBe(postClaimFeeBalance + insuranceFundAmount)
+    expect(finalInsuranceFund.data.totalPaidOut).toBe(insuranceFundAmount)
+    expect(finalInsuranceFund.data.pendingPayoutAmount).toBe(0n)
+  })
+
+  test('claim fees - partner frontend takes its share of the protocol fee', async () => {
+    const partner = await ctx.createTestTrader()
+    const createPartneredCurve = async () => {
+      const mintKeypair = await generateKeyPairSigner()
+      await ctx.createBondingCurveAndMintToken({
+        configAddress: ctx.currentConfig!,
+        creator: await ctx.createTestTrader(BigInt(0.1 * LAMPORTS_PER_SOL)),
+        mintKeypair,
+        partner: partner.address,
+      })
+      return mintKeypair.address
+    }
+
+    // only partners approved by the admin can be attached to a curve
+    await expect(createPartneredCurve()).rejects.toThrow()
+
+    await ctx.addPartner({ partner: partner.address, feeShareBasisPoints: 25_000 }) // 25% of protocol fee
+    await ctx.createPartnerEarnings({ partner: partner.address })
+    token = await createPartneredCurve()
+
+    const curve = await ctx.getBondingCurveData({ baseMint: token })
+    expect(curve.data.partner).toBe(partner.address)
+    expect(curve.data.partnerFeeShareBasisPoints).toBe(25_000)
+
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    // a batch skips the partnered curve instead of failing, it is claimed with the partner accounts
+    const unpartnered = await ctx.createFreshBondingCurve()
+    await ctx.swap({
+      trader,
+      baseMint: unpartnered.token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    await ctx.claimProtocolFeeBatch({ feeClaimer, baseMints: [token, unpartnered.token] })
+
+    const [curveState, unpartneredCurveState, feeClaimerTokenBalance] = await Promise.all([
+      ctx.getBondingCurveData({ baseMint: token }),
+      ctx.getBondingCurveData({ baseMint: unpartnered.token }),
+      ctx.getTokenBalance({ address: feeClaimer.address, mint: WSOL_MINT }),
+    ])
+    expect(unpartneredCurveState.data.protocolFee).toBe(0n)
+    const protocolFee = curveState.data.protocolFee
+    expect(protocolFee).toBeGreaterThan(0n)
+    const partnerFeeAmount = (protocolFee * 25_000n) / BASIS_POINTS_DIVISOR
+
+    await ctx.claimProtocolFees({ feeClaimer, baseMint: token })
+
+    const [postClaimFeeBalance, partnerEarnings] = await Promise.all([
+      ctx.getTokenBalance({ address: feeClaimer.address, mint: WSOL_MINT }),
+      ctx.getPartnerEarningsData({ partner: partner.address }),
+    ])
+    expect(postClaimFeeBalance).toBe(feeClaimerTokenBalance + protocolFee - partnerFeeAmount)
+    expect(partnerEarnings.data.totalAccrued).toBe(partnerFeeAmount)
+
+    const configBefore = await ctx.getConfigData({})
+    await ctx.claimPartnerEarnings({ partner })
+
+    const [partnerBalance, finalPartnerEarnings, configAfter] = await Promise.all([
+      ctx.getTokenBalance({ address: partner.address, mint: WSOL_MINT }),
+      ctx.getPartnerEarningsData({ partner: partner.address }),
+      ctx.getConfigData({}),
+    ])
+    // the claim is sequenced within the config it is passed with
+    expect(configAfter.data.eventSequence).toBe(configBefore.data.eventSequence + 1n)
+    expect(partnerBalance).toBe(partnerFeeAmount)
+    expect(finalPartnerEarnings.data.totalClaimed).toBe(partnerFeeAmount)
+    await expect(ctx.claimPartnerEarnings({ partner })).rejects.toThrow()
+
+    // removing the partner keeps the share of curves already created with it
+    await ctx.removePartner({ partner: partner.address })
+    await expect(createPartneredCurve()).rejects.toThrow()
+  })
+
+  test('claim fees - protocol takes its cut of creator claims', async () => {
+    const { configAddress, feeClaimer: cutFeeClaimer } = await ctx.createConfig({
+      ...DEFAULT_CONFIG_ARGS,
+      creatorFeeProtocolBasisPoints: 20_000, // 20% of creator claims
+    })
+    const creator = await ctx.createTestTrader()
+    const result = await ctx.createFreshBondingCurve(configAddress, creator)
+    token = result.token
+
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+
+    const [curveState, feeClaimerTokenBalance] = await Promise.all([
+      ctx.getBondingCurveData({ baseMint: token, configAddress }),
+      ctx.getTokenBalance({ address: cutFeeClaimer.address, mint: WSOL_MINT }),
+    ])
+    const creatorFee = curveState.data.creatorFee
+    const protocolCut = (creatorFee * 20_000n) / BASIS_POINTS_DIVISOR
+
+    await ctx.claimCreatorFee({ creator, baseMint: token, configAddress })
+
+    const [creatorBalance, postClaimFeeClaimerBalance, postClaimCurveState] = await Promise.all([
+      ctx.getTokenBalance({ address: creator.address, mint: WSOL_MINT }),
+      ctx.getTokenBalance({ address: cutFeeClaimer.address, mint: WSOL_MINT }),
+      ctx.getBondingCurveData({ baseMint: token, configAddress }),
+    ])
+    expect(creatorBalance).toBe(creatorFee - protocolCut)
+    expect(postClaimFeeClaimerBalance).toBe(feeClaimerTokenBalance + protocolCut)
+    expect(postClaimCurveState.data.creatorFee).toBe(0n)
+    // the cut is paid out directly, it doesn't accrue as protocol fee on the curve
+    expect(postClaimCurveState.data.protocolFee).toBe(curveState.data.protocolFee)
+  })
+
+  test('claim fees - fee claimer can rotate its key', async () => {
+    const { configAddress, feeClaimer: oldFeeClaimer } = await ctx.createConfig(DEFAULT_CONFIG_ARGS)
+    const newFeeClaimer = await ctx.createTestTrader()
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    token = result.token
+
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+
+    // only the current fee claimer can hand over the key
+    expect(
+      ctx.rotateFeeClaimer({ feeClaimer: newFeeClaimer, newFeeClaimer: newFeeClaimer.address, configAddress }),
+    ).rejects.toThrow()
+
+    await ctx.rotateFeeClaimer({
+      feeClaimer: oldFeeClaimer,
+      newFeeClaimer: newFeeClaimer.address,
+      admin: ctx.owner,
+      configAddress,
+    })
+    const configState = await ctx.getConfigData({ configAddress })
+    expect(configState.data.feeClaimer).toBe(newFeeClaimer.address)
+
+    // the old key lost its claim rights
+    expect(ctx.claimProtocolFees({ feeClaimer: oldFeeClaimer, baseMint: token, configAddress })).rejects.toThrow()
+
+    const curveState = await ctx.getBondingCurveData({ baseMint: token, configAddress })
+    await ctx.claimProtocolFees({ feeClaimer: newFeeClaimer, baseMint: token, configAddress })
+    const newFeeClaimerBalance = await ctx.getTokenBalance({ address: newFeeClaimer.address, mint: WSOL_MINT })
+    expect(newFeeClaimerBalance).toBe(curveState.data.protocolFee)
+  })
+
+  test('claim fees - fee claimer token account can be created at claim time', async () => {
+    const { configAddress, feeClaimer: lateFeeClaimer } = await ctx.createConfig(
+      DEFAULT_CONFIG_ARGS,
+      WSOL_MINT,
+      undefined,
+      false,
+    )
+    const feeClaimerTokenAccount = await getAssociatedTokenAccountAddress(
+      WSOL_MINT,
+      lateFeeClaimer.address,
+      TOKEN_PROGRAM_ADDRESS,
+    )
+    const { value: accountInfo } = await ctx.rpc.getAccountInfo(feeClaimerTokenAccount, { encoding: 'base64' }).send()
+    expect(accountInfo).toBeNull()
+
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+
+    const curveState = await ctx.getBondingCurveData({ baseMint: result.token, configAddress })
+    await ctx.claimProtocolFees({ feeClaimer: lateFeeClaimer, baseMint: result.token, configAddress })
+    const feeClaimerBalance = await ctx.getTokenBalance({ address: lateFeeClaimer.address, mint: WSOL_MINT })
+    expect(feeClaimerBalance).toBe(curveState.data.protocolFee)
+  })
+
+  test('claim fees - accrued creator fee moves with the creator role', async () => {
+    const creator = await ctx.createTestTrader()
+    const newCreator = await ctx.createTestTrader()
+    const result = await ctx.createFreshBondingCurve(undefined, creator)
+
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    const { data: curveState } = await ctx.getBondingCurveData({ baseMint: result.token })
+    expect(curveState.creatorFee).toBeGreaterThan(0n)
+
+    // only the current creator can hand the role over
+    expect(
+      ctx.transferCreator({
+        creator: newCreator,
+        newCreator: newCreator.address,
+        creatorFeeTransferMode: CreatorFeeTransferMode.MoveWithRole,
+        baseMint: result.token,
+      }),
+    ).rejects.toThrow()
+
+    await ctx.transferCreator({
+      creator,
+      newCreator: newCreator.address,
+      creatorFeeTransferMode: CreatorFeeTransferMode.MoveWithRole,
+      baseMint: result.token,
+    })
+    const { data: transferredCurveState } = await ctx.getBondingCurveData({ baseMint: result.token })
+    expect(transferredCurveState.creator).toBe(newCreator.address)
+    expect(transferredCurveState.creatorFee).toBe(curveState.creatorFee)
+
+    // the old creator lost its claim rights, the new one gets the accrued fee
+    expect(ctx.claimCreatorFee({ creator, baseMint: result.token })).rejects.toThrow()
+    await ctx.claimCreatorFee({ creator: newCreator, baseMint: result.token })
+    const newCreatorBalance = await ctx.getTokenBalance({ address: newCreator.address, mint: WSOL_MINT })
+    expect(newCreatorBalance).toBe(curveState.creatorFee)
+  })
+
+  test('claim fees - creator fee is claimed to the recipient set by the creator', async () => {
+    const creator = await ctx.createTestTrader()
+    const recipient = await ctx.createTestTrader()
+    const result = await ctx.createFreshBondingCurve(undefined, creator)
+    const buy = () =>
+      ctx.swap({
+        trader,
+        baseMint: result.token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+      })
+
+    // only the creator picks the recipient
+    expect(
+      ctx.setCreatorFeeRecipient({ creator: recipient, baseMint: result.token, recipient: recipient.address }),
+    ).rejects.toThrow()
+    await ctx.setCreatorFeeRecipient({ creator, baseMint: result.token, recipient: recipient.address })
+
+    await buy()
+    const { data: curveState } = await ctx.getBondingCurveData({ baseMint: result.token })
+    const outsider = await ctx.createTestTrader()
+    expect(ctx.claimCreatorFee({ creator: outsider, baseMint: result.token })).rejects.toThrow()
+
+    // the recipient claims by itself
+    await ctx.claimCreatorFee({ creator: recipient, baseMint: result.token })
+    expect(await ctx.getTokenBalance({ address: recipient.address, mint: WSOL_MINT })).toBe(curveState.creatorFee)
+
+    // claims of the creator are paid to the recipient too
+    await buy()
+    const { data: curveStateAfterBuy } = await ctx.getBondingCurveData({ baseMint: result.token })
+    await ctx.claimCreatorFee({ creator, baseMint: result.token })
+    expect(await ctx.getTokenBalance({ address: recipient.address, mint: WSOL_MINT })).toBe(
+      curveState.creatorFee + curveStateAfterBuy.creatorFee,
+    )
+  })
+
+  test('claim fees - accrued creator fee can be claimed to the old creator on transfer', async () => {
+    const creator = await ctx.createTestTrader()
+    const newCreator = await ctx.createTestTrader()
+    const result = await ctx.createFreshBondingCurve(undefined, creator)
+
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    const { data: curveState } = await ctx.getBondingCurveData({ baseMint: result.token })
+
+    expect(
+      ctx.transferCreator({
+        creator,
+        newCreator: newCreator.address,
+        creatorFeeTransferMode: 2,
+        baseMint: result.token,
+      }),
+    ).rejects.toThrow()
+
+    await ctx.transferCreator({
+      creator,
+      newCreator: newCreator.address,
+      creatorFeeTransferMode: CreatorFeeTransferMode.ClaimToOldCreator,
+      baseMint: result.token,
+    })
+    const [{ data: transferredCurveState }, oldCreatorBalance] = await Promise.all([
+      ctx.getBondingCurveData({ baseMint: result.token }),
+      ctx.getTokenBalance({ address: creator.address, mint: WSOL_MINT }),
+    ])
+    expect(transferredCurveState.creator).toBe(newCreator.address)
+    expect(transferredCurveState.creatorFee).toBe(0n)
+    // CREATOR_FEE_PROTOCOL_BASIS_POINTS is 0 in the default config, the old creator gets it all
+    expect(oldCreatorBalance).toBe(curveState.creatorFee)
+  })
+
+  test('claim fees - meme fee accrues apart from the creator fee', async () => {
+    const creator = await ctx.createTestTrader()
+    const result = await ctx.createFreshBondingCurve(undefined, creator)
+    const buy = () =>
+      ctx.swap({
+        trader,
+        baseMint: result.token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+      })
+
+    await buy()
+    const { data: creatorCurveState } = await ctx.getBondingCurveData({ baseMint: result.token })
+    expect(creatorCurveState.memeFee).toBe(0n)
+
+    // only the fee type admin switches, and only to another fee type
+    expect(ctx.setFeeType({ feeType: FeeType.Creator, baseMint: result.token })).rejects.toThrow()
+    await ctx.setFeeType({ feeType: FeeType.Meme, baseMint: result.token })
+
+    await buy()
+    const { data: memeCurveState } = await ctx.getBondingCurveData({ baseMint: result.token })
+    expect(memeCurveState.feeType).toBe(FeeType.Meme)
+    expect(memeCurveState.creatorFee).toBe(creatorCurveState.creatorFee)
+    expect(memeCurveState.memeFee).toBeGreaterThan(0n)
+
+    // the creator keeps what accrued before the switch, the meme fee only goes to the community
+    expect(ctx.claimMemeFee({ claimer: creator, baseMint: result.token })).rejects.toThrow()
+    const creatorBalance = await ctx.getTokenBalance({ address: creator.address, mint: WSOL_MINT })
+    await ctx.claimCreatorFee({ creator, baseMint: result.token })
+    expect(await ctx.getTokenBalance({ address: creator.address, mint: WSOL_MINT })).toBe(
+      creatorBalance + creatorCurveState.creatorFee,
+    )
+
+    const ownerBalance = await ctx.getTokenBalance({ address: ctx.owner.address, mint: WSOL_MINT })
+    await ctx.claimMemeFee({ baseMint: result.token })
+    expect(await ctx.getTokenBalance({ address: ctx.owner.address, mint: WSOL_MINT })).toBe(
+      ownerBalance + memeCurveState.memeFee,
+    )
+    const { data: claimedCurveState } = await ctx.getBondingCurveData({ baseMint: result.token })
+    expect(claimedCurveState.memeFee).toBe(0n)
+  })
 })
@@ -0,0 +1,127 @@
+import { beforeAll, beforeEach, describe, expect, test } from 'bun:test'
+import { type Address, type KeyPairSigner, LAMPORTS_PER_SOL } from 'gill'
+import { DEFAULT_CONFIG_ARGS, SINGLE_BUY_AMOUNT, WSOL_MINT } from './utils/constants.ts'
+import { TestContextClass } from './utils/context.ts'
+import { TradeDirection, getVirtualPrice } from './utils/swap-quote.ts'
+
+const orderAmount = BigInt(LAMPORTS_PER_SOL)
+const fillerReward = orderAmount / 200n // 0.5% of the order
+
+describe('Limit Order Tests', () => {
+  let ctx: TestContextClass
+  let token: Address
+  let curve: Address
+  let owner: KeyPairSigner
+  let filler: KeyPairSigner
+
+  beforeAll(async () => {
+    ctx = await TestContextClass.create()
+    await ctx.createConfigOnce(DEFAULT_CONFIG_ARGS)
+  })
+
+  beforeEach(async () => {
+    const result = await ctx.createFreshBondingCurve()
+    token = result.token
+    curve = result.curvePda
+    owner = await ctx.createTestTrader()
+    filler = await ctx.createTestTrader()
+  })
+
+  const getCurrentPrice = async () => getVirtualPrice((await ctx.getCurveData({ curveAddress: curve })).data)
+
+  const isClosed = async (account: Address) => {
+    const { value: accountInfo } = await ctx.rpc.getAccountInfo(account, { encoding: 'base64' }).send()
+    return accountInfo === null
+  }
+
+  test('limit order - a buy at or above the price fills right away', async () => {
+    const { limitOrder, escrowVault } = await ctx.placeLimitOrder({
+      owner,
+      baseMint: token,
+      tradeDirection: TradeDirection.QuoteToBase,
+      amountIn: orderAmount,
+      targetPrice: (await getCurrentPrice()) * 2n,
+      fillerReward,
+    })
+    expect(await ctx.getTokenAccountBalance(escrowVault)).toBe(orderAmount + fillerReward)
+
+    await ctx.fillLimitOrder({ filler, limitOrder, baseMint: token })
+
+    expect(await ctx.getTokenBalance({ address: owner.address, mint: token })).toBeGreaterThan(0n)
+    expect(await ctx.getTokenBalance({ address: filler.address, mint: WSOL_MINT })).toBe(fillerReward)
+    expect(await isClosed(limitOrder)).toBe(true)
+    expect(await isClosed(escrowVault)).toBe(true)
+  })
+
+  test('limit order - a sell waits for the curve to reach the target price', async () => {
+    await ctx.swap({
+      trader: owner,
+      baseMint: token,
+      amountIn: SINGLE_BUY_AMOUNT,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    const baseAmount = (await ctx.getTokenBalance({ address: owner.address, mint: token })) / 2n
+    const { limitOrder } = await ctx.placeLimitOrder({
+      owner,
+      baseMint: token,
+      tradeDirection: TradeDirection.BaseToQuote,
+      amountIn: baseAmount,
+      targetPrice: ((await getCurrentPrice()) * 11n) / 10n,
+    })
+
+    expect(ctx.fillLimitOrder({ filler, limitOrder, baseMint: token })).rejects.toThrow()
+
+    // a large buy pushes the price past the target
+    const trader = await ctx.createTestTrader(BigInt(20 * LAMPORTS_PER_SOL))
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: BigInt(10 * LAMPORTS_PER_SOL),
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    const quoteBefore = await ctx.getTokenBalance({ address: owner.address, mint: WSOL_MINT })
+    await ctx.fillLimitOrder({ filler, limitOrder, baseMint: token })
+
+    expect(await ctx.getTokenBalance({ address: owner.address, mint: WSOL_MINT })).toBeGreaterThan(quoteBefore)
+    expect(await isClosed(limitOrder)).toBe(true)
+  })
+
+  test('limit order - cancel refunds the escrow', async () => {
+    const { limitOrder, escrowVault } = await ctx.placeLimitOrder({
+      owner,
+      baseMint: token,
+      tradeDirection: TradeDirection.QuoteToBase,
+      amountIn: orderAmount,
+      targetPrice: (await getCurrentPrice()) / 2n,
+      fillerReward,
+    })
+    expect(ctx.fillLimitOrder({ filler, limitOrder, baseMint: token })).rejects.toThrow()
+
+    const nonOwner = await ctx.createTestTrader()
+    expect(ctx.cancelLimitOrder({ owner: nonOwner, limitOrder, baseMint: token })).rejects.toThrow()
+
+    const quoteBefore = await ctx.getTokenBalance({ address: owner.address, mint: WSOL_MINT })
+    await ctx.cancelLimitOrder({ owner, limitOrder, baseMint: token })
+
+    expect(await ctx.getTokenBalance({ address: owner.address, mint: WSOL_MINT })).toBe(
+      quoteBefore + orderAmount + fillerReward,
+    )
+    expect(await isClosed(limitOrder)).toBe(true)
+    expect(await isClosed(escrowVault)).toBe(true)
+  })
+
+  test('limit order - rejects a filler reward above the maximum', async () => {
+    expect(
+      ctx.placeLimitOrder({
+        owner,
+        baseMint: token,
+        tradeDirection: TradeDirection.QuoteToBase,
+        amountIn: orderAmount,
+        targetPrice: await getCurrentPrice(),
+        fillerReward: orderAmount / 50n, // 2% of the order
+      }),
+    ).rejects.toThrow()
+  })
+})
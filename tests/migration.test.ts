@@ -1,8 +1,31 @@
 import { beforeEach, describe, expect, test } from 'bun:test'
-import { type Address, type KeyPairSigner, LAMPORTS_PER_SOL } from 'gill'
-import { fetchBondingCurve } from '~/clients'
+import { createHash } from 'node:crypto'
+import { fetchMint, fetchToken } from '@solana-program/token-2022'
+import { type Address, type KeyPairSigner, LAMPORTS_PER_SOL, generateKeyPairSigner } from 'gill'
+import { TOKEN_2022_PROGRAM_ADDRESS } from 'gill/programs'
+import { TOKEN_PROGRAM_ADDRESS, getAssociatedTokenAccountAddress } from 'gill/programs/token'
+import { fetchBondingCurve, fetchCurveArchive, fetchSupportFund } from '~/clients'
 import { fetchPool, fetchPosition } from '../clients/damm/src/generated'
-import { BASIS_POINTS_DIVISOR, DEFAULT_CONFIG_ARGS, SINGLE_BUY_AMOUNT, WSOL_MINT } from './utils/constants.ts'
+import {
+  FaultPoint,
+  MigrationStage,
+  MigrationStep,
+  MigrationTokenOrder,
+  ThresholdDecayMode,
+  derivePositionAddress,
+  derivePositionNftAccount,
+  getCurveAuthority,
+  getSupportFundPda,
+  isBaseTokenA,
+} from './utils/accounts.ts'
+import {
+  BASIS_POINTS_DIVISOR,
+  DAMM_CONFIG_ACCOUNT,
+  DEFAULT_CONFIG_ARGS,
+  SINGLE_BUY_AMOUNT,
+  WSOL_MINT,
+  getTokenTotalSupply,
+} from './utils/constants.ts'
 import { TestContextClass } from './utils/context.ts'
 import { TradeDirection, getSwapResult } from './utils/swap-quote.ts'
 
@@ -76,6 +99,20 @@ describe('Migration Test', () => {
     expect(finalPoolData.data.metrics.totalProtocolBFee).toBeGreaterThan(0)
   })
 
+  test('migration - completing the curve counts a graduation', async () => {
+    const configStatsBefore = await ctx.getConfigStats()
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    const configStatsAfter = await ctx.getConfigStats()
+    expect(configStatsAfter.totalCurvesGraduated).toBe(configStatsBefore.totalCurvesGraduated + 1n)
+  })
+
   test('migration - price stays similar', async () => {
     await ctx.swap({
       trader,
@@ -209,23 +246,34 @@ describe('Migration Test', () => {
     expect(postMigrationQuoteVaultBalance).toBeGreaterThan(feeAmount)
     expect(postMigrationQuoteVaultBalance).toBeLessThan(feeAmount + feeAmount / 2n) // Allow some tolerance
 
-    // Claim protocol fee (which should include migration fee after migration)
+    // Claim protocol fee, the migration fee is left in the vault for its own claim
+    const migrationFeeCollected = postMigrationCurveState.data.migrationFeeCollected
+    expect(migrationFeeCollected).toBeGreaterThan(0n)
     await ctx.claimProtocolFees({
       feeClaimer,
       baseMint: token,
     })
+    expect(await ctx.getTokenAccountBalance(postMigrationCurveState.data.quoteVault)).toBe(migrationFeeCollected)
 
-    const [finalFeeClaimerBalance, finalQuoteVaultBalance] = await Promise.all([
+    await ctx.claimMigrationFee({
+      feeClaimer,
+      baseMint: token,
+    })
+
+    const [finalFeeClaimerBalance, finalQuoteVaultBalance, finalCurveState] = await Promise.all([
       ctx.getTokenBalance({
         address: feeClaimer.address,
         mint: WSOL_MINT,
       }),
       ctx.getTokenAccountBalance(postMigrationCurveState.data.quoteVault),
+      fetchBondingCurve(ctx.rpc, curve),
     ])
 
-    // Verify the fee claimer received the migration fee
+    // Verify the fee claimer received the trading and migration fees
     expect(finalFeeClaimerBalance).toBe(postMigrationQuoteVaultBalance)
     expect(finalQuoteVaultBalance).toBe(0n)
+    expect(finalCurveState.data.migrationFeeCollected).toBe(0n)
+    expect(ctx.claimMigrationFee({ feeClaimer, baseMint: token })).rejects.toThrow()
   })
 
   test('migration - can claim fees after graduation', async () => {
@@ -329,4 +377,860 @@ describe('Migration Test', () => {
     // Verify migration status is still in graduated state
     expect(finalCurveState.data.migrationStatus).toBe(1)
   })
+
+  test('migration - 9 decimal base token graduates', async () => {
+    // base side amounts scale with the extra 3 decimals, quote side stays the same
+    const { configAddress } = await ctx.createConfig({
+      ...DEFAULT_CONFIG_ARGS,
+      baseDecimal: 9,
+      migrationBaseThreshold: DEFAULT_CONFIG_ARGS.migrationBaseThreshold * 1_000n,
+      initialVirtualBaseReserve: DEFAULT_CONFIG_ARGS.initialVirtualBaseReserve * 1_000n,
+    })
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    const [initialCurveState, configState] = await Promise.all([
+      fetchBondingCurve(ctx.rpc, result.curvePda),
+      ctx.getConfigData({ configAddress }),
+    ])
+    expect(initialCurveState.data.baseReserve).toBe(getTokenTotalSupply(9))
+
+    const buyExpected = getSwapResult({
+      curveState: initialCurveState.data,
+      configState: configState.data,
+      amountIn: SINGLE_BUY_AMOUNT,
+      tradeDirection: TradeDirection.QuoteToBase,
+      hasL1Referral: false,
+      hasL2Referral: false,
+      hasL3Referral: false,
+    })
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: SINGLE_BUY_AMOUNT,
+      minimumAmountOut: buyExpected.outputAmount,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+
+    // same quote buys the same share of the supply as a 6 decimal token
+    const traderTokenBalance = await ctx.getTokenBalance({ address: trader.address, mint: result.token })
+    expect(traderTokenBalance).toBe(buyExpected.outputAmount)
+
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+
+    const completedCurveState = await fetchBondingCurve(ctx.rpc, result.curvePda)
+    expect(completedCurveState.data.migrationStatus).toBe(1)
+
+    await ctx.migrate({ curve: result.curvePda, baseMint: result.token, config: configAddress })
+    const migratedCurveState = await fetchBondingCurve(ctx.rpc, result.curvePda)
+    expect(migratedCurveState.data.migrationStatus).toBe(2)
+  })
+
+  test('migration - sorted token order puts the smaller mint first', async () => {
+    const { configAddress } = await ctx.createConfig({
+      ...DEFAULT_CONFIG_ARGS,
+      migrationTokenOrder: MigrationTokenOrder.Sorted,
+    })
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+
+    const migrationResult = await ctx.migrate({ curve: result.curvePda, baseMint: result.token, config: configAddress })
+    const poolData = await fetchPool(ctx.rpc, migrationResult.pool)
+    const baseIsTokenA = isBaseTokenA({
+      baseMint: result.token,
+      quoteMint: WSOL_MINT,
+      migrationTokenOrder: MigrationTokenOrder.Sorted,
+    })
+    expect(poolData.data.tokenAMint).toEqual(baseIsTokenA ? result.token : WSOL_MINT)
+    expect(poolData.data.tokenBMint).toEqual(baseIsTokenA ? WSOL_MINT : result.token)
+
+    // the pool trades at the curve's last price whichever way the mints are ordered
+    const traderTokenBalance = await ctx.getTokenBalance({ address: trader.address, mint: result.token })
+    await ctx.swapWithDammV2({
+      trader,
+      dammPool: migrationResult.pool,
+      amountIn: SINGLE_BUY_AMOUNT,
+      minimumAmountOut: 0n,
+      inputTokenMint: WSOL_MINT,
+      outputTokenMint: result.token,
+    })
+    const finalTraderTokenBalance = await ctx.getTokenBalance({ address: trader.address, mint: result.token })
+    expect(finalTraderTokenBalance).toBeGreaterThan(traderTokenBalance)
+  })
+  test('migration - only the approved DAMM config is accepted', async () => {
+    const configData = await ctx.getConfigData({ configAddress: ctx.currentConfig! })
+    expect(configData.data.dammConfig).toEqual(DAMM_CONFIG_ACCOUNT)
+
+    // the approved DAMM config has to be owned by the DAMM v2 program
+    expect(ctx.setDammConfig({ dammConfig: ctx.currentConfig! })).rejects.toThrow()
+
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    const { address: otherDammConfig } = await generateKeyPairSigner()
+    expect(ctx.migrate({ curve, baseMint: token, dammConfig: otherDammConfig })).rejects.toThrow()
+
+    const migrationResult = await ctx.migrate({ curve, baseMint: token })
+    const poolData = await fetchPool(ctx.rpc, migrationResult.pool)
+    expect(poolData.data.tokenAMint).toEqual(token)
+  })
+
+  test('migration - decayed threshold graduates a stale curve', async () => {
+    const { configAddress } = await ctx.createConfig({
+      ...DEFAULT_CONFIG_ARGS,
+      thresholdDecayMode: ThresholdDecayMode.Linear,
+      thresholdDecayFloorBasisPoints: 60_000, // 60% of the quote threshold
+      thresholdDecaySeconds: 10n,
+    })
+    const result = await ctx.createFreshBondingCurve(configAddress)
+
+    // wait for the threshold to fully decay
+    await new Promise((resolve) => setTimeout(resolve, 12_000))
+
+    // far below the undecayed threshold, enough to raise the virtual quote reserve above the floor
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: BigInt(50 * LAMPORTS_PER_SOL),
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+
+    const curveState = await fetchBondingCurve(ctx.rpc, result.curvePda)
+    expect(curveState.data.migrationStatus).toBe(1)
+    expect(curveState.data.baseReserve).toBeGreaterThan(DEFAULT_CONFIG_ARGS.migrationBaseThreshold)
+
+    // the completed curve doesn't trade anymore
+    expect(
+      ctx.swap({
+        trader,
+        baseMint: result.token,
+        amountIn: SINGLE_BUY_AMOUNT,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        configAddress,
+      }),
+    ).rejects.toThrow()
+
+    const migrationResult = await ctx.migrate({ curve: result.curvePda, baseMint: result.token, config: configAddress })
+    const poolData = await fetchPool(ctx.rpc, migrationResult.pool)
+    expect(poolData.data.tokenAMint).toEqual(result.token)
+  }, 30_000)
+
+  test('migration - anyone can advance a curve completed by the threshold decay', async () => {
+    const { configAddress } = await ctx.createConfig({
+      ...DEFAULT_CONFIG_ARGS,
+      thresholdDecayMode: ThresholdDecayMode.Linear,
+      thresholdDecayFloorBasisPoints: 60_000, // 60% of the quote threshold
+      thresholdDecaySeconds: 10n,
+    })
+    const result = await ctx.createFreshBondingCurve(configAddress)
+
+    // enough to pass the decay floor, not the undecayed threshold
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: BigInt(50 * LAMPORTS_PER_SOL),
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+    const cranker = await ctx.createTestTrader()
+    expect(ctx.advanceMigrationStatus({ curve: result.curvePda, payer: cranker })).rejects.toThrow()
+
+    // wait for the threshold to fully decay, no swap completes the curve
+    await new Promise((resolve) => setTimeout(resolve, 12_000))
+    await ctx.advanceMigrationStatus({ curve: result.curvePda, payer: cranker })
+
+    const curveState = await fetchBondingCurve(ctx.rpc, result.curvePda)
+    expect(curveState.data.migrationStatus).toBe(1)
+    expect(curveState.data.curveFinishTimestamp).toBeGreaterThan(0n)
+
+    // there is nothing left to advance
+    expect(ctx.advanceMigrationStatus({ curve: result.curvePda, payer: cranker })).rejects.toThrow()
+
+    const migrationResult = await ctx.migrate({ curve: result.curvePda, baseMint: result.token, config: configAddress })
+    const poolData = await fetchPool(ctx.rpc, migrationResult.pool)
+    expect(poolData.data.tokenAMint).toEqual(result.token)
+  }, 30_000)
+
+  test('migration - leftover base tokens are burned without a leftover receiver', async () => {
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    // the leftover is only withdrawn once the pool exists
+    expect(ctx.withdrawLeftover({ curve })).rejects.toThrow()
+
+    await ctx.migrate({ curve, baseMint: token })
+    const curveState = await fetchBondingCurve(ctx.rpc, curve)
+    expect(await ctx.getTokenAccountBalance(curveState.data.baseVault)).toBeGreaterThan(0n)
+
+    const cranker = await ctx.createTestTrader()
+    await ctx.withdrawLeftover({ curve, payer: cranker })
+    expect(await ctx.getTokenAccountBalance(curveState.data.baseVault)).toBe(0n)
+
+    // nothing left to withdraw
+    expect(ctx.withdrawLeftover({ curve, payer: cranker })).rejects.toThrow()
+  })
+
+  test('migration - a settled curve is archived and its rent returned to the creator', async () => {
+    const creator = await ctx.createTestTrader()
+    const result = await ctx.createFreshBondingCurve(undefined, creator)
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    await ctx.migrate({ curve: result.curvePda, baseMint: result.token })
+
+    // the fees and the leftover are still owed out of the curve
+    const cranker = await ctx.createTestTrader()
+    expect(ctx.archiveCurve({ curve: result.curvePda, payer: cranker })).rejects.toThrow()
+
+    await Promise.all([
+      ctx.claimProtocolFees({ feeClaimer, baseMint: result.token }),
+      ctx.claimCreatorFee({ creator, baseMint: result.token }),
+      ctx.withdrawLeftover({ curve: result.curvePda, payer: cranker }),
+    ])
+    const { data: settledCurveState } = await fetchBondingCurve(ctx.rpc, result.curvePda)
+    if (settledCurveState.migrationFeeCollected > 0n) {
+      await ctx.claimMigrationFee({ feeClaimer, baseMint: result.token })
+    }
+
+    const [creatorBalanceBefore, curveRent] = await Promise.all([
+      ctx.getBalance(creator.address),
+      ctx.getBalance(result.curvePda),
+    ])
+    const { curveArchive } = await ctx.archiveCurve({ curve: result.curvePda, payer: cranker })
+
+    const [archive, curveBalance, creatorBalanceAfter] = await Promise.all([
+      fetchCurveArchive(ctx.rpc, curveArchive),
+      ctx.getBalance(result.curvePda),
+      ctx.getBalance(creator.address),
+    ])
+    expect(curveBalance).toBe(0n)
+    // the curve and both vaults
+    expect(creatorBalanceAfter - creatorBalanceBefore).toBeGreaterThan(curveRent)
+    expect(archive.data.curve).toBe(result.curvePda)
+    expect(archive.data.creator).toBe(creator.address)
+    expect(archive.data.baseMint).toBe(result.token)
+    expect(archive.data.totalQuoteVolume).toBe(settledCurveState.totalQuoteVolume)
+    expect(archive.data.buyCount).toBe(settledCurveState.buyCount)
+    expect(archive.data.curveFinishTimestamp).toBe(settledCurveState.curveFinishTimestamp)
+
+    // a curve is archived once
+    expect(ctx.archiveCurve({ curve: result.curvePda, payer: cranker })).rejects.toThrow()
+  }, 30_000)
+
+  test('migration - the creator burns part of the leftover base tokens', async () => {
+    const creator = await ctx.createTestTrader()
+    const result = await ctx.createFreshBondingCurve(undefined, creator)
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    // nothing can be burned before the pool exists
+    expect(ctx.burnCurveBaseTokens({ curve: result.curvePda, creator, amount: 1n })).rejects.toThrow()
+
+    await ctx.migrate({ curve: result.curvePda, baseMint: result.token })
+    const curveState = await fetchBondingCurve(ctx.rpc, result.curvePda)
+    const [leftoverAmount, { data: mint }] = await Promise.all([
+      ctx.getTokenAccountBalance(curveState.data.baseVault),
+      fetchMint(ctx.rpc, result.token),
+    ])
+    expect(leftoverAmount).toBeGreaterThan(1n)
+
+    // only the creator burns, at most what the vault holds
+    expect(ctx.burnCurveBaseTokens({ curve: result.curvePda, creator: trader, amount: 1n })).rejects.toThrow()
+    expect(ctx.burnCurveBaseTokens({ curve: result.curvePda, creator, amount: leftoverAmount + 1n })).rejects.toThrow()
+
+    const amount = leftoverAmount / 2n
+    await ctx.burnCurveBaseTokens({ curve: result.curvePda, creator, amount })
+    const [vaultBalance, { data: mintAfter }] = await Promise.all([
+      ctx.getTokenAccountBalance(curveState.data.baseVault),
+      fetchMint(ctx.rpc, result.token),
+    ])
+    expect(vaultBalance).toBe(leftoverAmount - amount)
+    expect(mintAfter.supply).toBe(mint.supply - amount)
+  })
+
+  test('migration - leftover base tokens go to the leftover receiver of the config', async () => {
+    const receiver = await ctx.createTestTrader(BigInt(200 * LAMPORTS_PER_SOL))
+    const { configAddress } = await ctx.createConfig(DEFAULT_CONFIG_ARGS, WSOL_MINT, undefined, true, receiver.address)
+    const result = await ctx.createFreshBondingCurve(configAddress)
+
+    // both buy on the curve, which also creates their base token accounts
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: SINGLE_BUY_AMOUNT,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+    await ctx.swap({
+      trader: receiver,
+      baseMint: result.token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+    await ctx.migrate({ curve: result.curvePda, baseMint: result.token, config: configAddress })
+
+    const curveState = await fetchBondingCurve(ctx.rpc, result.curvePda)
+    const [leftoverAmount, receiverBalance] = await Promise.all([
+      ctx.getTokenAccountBalance(curveState.data.baseVault),
+      ctx.getTokenBalance({ address: receiver.address, mint: result.token }),
+    ])
+    const receiverTokenAccount = await getAssociatedTokenAccountAddress(
+      result.token,
+      receiver.address,
+      TOKEN_PROGRAM_ADDRESS,
+    )
+
+    // the leftover can't be burned or redirected
+    const otherTokenAccount = await getAssociatedTokenAccountAddress(
+      result.token,
+      trader.address,
+      TOKEN_PROGRAM_ADDRESS,
+    )
+    expect(ctx.withdrawLeftover({ curve: result.curvePda })).rejects.toThrow()
+    expect(
+      ctx.withdrawLeftover({ curve: result.curvePda, leftoverTokenAccount: otherTokenAccount }),
+    ).rejects.toThrow()
+
+    await ctx.withdrawLeftover({ curve: result.curvePda, leftoverTokenAccount: receiverTokenAccount })
+    expect(await ctx.getTokenAccountBalance(curveState.data.baseVault)).toBe(0n)
+    expect(await ctx.getTokenBalance({ address: receiver.address, mint: result.token })).toBe(
+      receiverBalance + leftoverAmount,
+    )
+  })
+
+  test('migration - a delegated operator migrates its curve', async () => {
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    const operator = await ctx.createTestTrader()
+
+    // no delegation yet
+    expect(ctx.migrate({ curve, baseMint: token, migrationAuthority: operator })).rejects.toThrow()
+
+    // the expiry has to be in the future
+    const now = BigInt(Math.floor(Date.now() / 1000))
+    expect(
+      ctx.delegateMigrationAuthority({ curve, operator: operator.address, expiryTimestamp: now - 1n }),
+    ).rejects.toThrow()
+
+    await ctx.delegateMigrationAuthority({ curve, operator: operator.address, expiryTimestamp: now + 3600n })
+
+    // operators can't opt out of the approved DAMM config
+    const { address: otherDammConfig } = await generateKeyPairSigner()
+    expect(
+      ctx.migrate({
+        curve,
+        baseMint: token,
+        migrationAuthority: operator,
+        dammConfig: otherDammConfig,
+        allowDammConfigOverride: true,
+      }),
+    ).rejects.toThrow()
+
+    // the delegation only covers its operator
+    expect(ctx.migrate({ curve, baseMint: token, migrationAuthority: trader })).rejects.toThrow()
+
+    const migrationResult = await ctx.migrate({ curve, baseMint: token, migrationAuthority: operator })
+    const [curveState, poolData] = await Promise.all([
+      fetchBondingCurve(ctx.rpc, curve),
+      fetchPool(ctx.rpc, migrationResult.pool),
+    ])
+    expect(curveState.data.migrationStatus).toBe(2)
+    expect(poolData.data.tokenAMint).toEqual(token)
+  })
+
+  test('migration - a revoked delegation can not migrate', async () => {
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    const operator = await ctx.createTestTrader()
+    const expiryTimestamp = BigInt(Math.floor(Date.now() / 1000) + 3600)
+
+    await ctx.delegateMigrationAuthority({ curve, operator: operator.address, expiryTimestamp })
+    await ctx.revokeMigrationAuthority({ curve })
+
+    expect(ctx.migrate({ curve, baseMint: token, migrationAuthority: operator })).rejects.toThrow()
+  })
+
+  test('migration - staged steps run in order and can be resent', async () => {
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    const { pool } = await ctx.migrate({ curve, baseMint: token, createPoolOnly: true })
+    const [progress, curveState] = await Promise.all([
+      ctx.getMigrationProgressData({ curve }),
+      fetchBondingCurve(ctx.rpc, curve),
+    ])
+    expect(progress.data.step).toBe(MigrationStep.PoolCreated)
+    expect(progress.data.pool).toBe(pool)
+    expect(progress.data.positionOwner).toBe(ctx.owner.address)
+    expect(curveState.data.migrationStatus).toBe(1)
+
+    // the position can't be handed over before its liquidity is locked
+    expect(ctx.migrateFinalize({ curve, baseMint: token })).rejects.toThrow()
+
+    await ctx.migrateLockLiquidity({ curve, payer: trader })
+    await ctx.migrateFinalize({ curve, baseMint: token, payer: trader })
+
+    const [finalProgress, finalCurveState] = await Promise.all([
+      ctx.getMigrationProgressData({ curve }),
+      fetchBondingCurve(ctx.rpc, curve),
+    ])
+    expect(finalProgress.data.step).toBe(MigrationStep.Finalized)
+    expect(finalCurveState.data.migrationStatus).toBe(2)
+    expect(finalCurveState.data.isMigrated).toBe(1)
+
+    // resending a step that already ran is a no-op
+    await ctx.migrateLockLiquidity({ curve })
+    await ctx.migrateFinalize({ curve, baseMint: token })
+    expect((await ctx.getMigrationProgressData({ curve })).data.step).toBe(MigrationStep.Finalized)
+  })
+
+  test('migration - CPI telemetry logs the compute units around the liquidity lock', async () => {
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    await ctx.migrate({ curve, baseMint: token, createPoolOnly: true })
+
+    await ctx.setCpiTelemetry({ enabled: true })
+    const signature = await ctx.migrateLockLiquidity({ curve })
+    await ctx.setCpiTelemetry({ enabled: false })
+
+    const transaction = await ctx.rpc
+      .getTransaction(signature, { encoding: 'json', maxSupportedTransactionVersion: 0 })
+      .send()
+    const discriminator = createHash('sha256').update('event:EvtCpiTelemetry').digest().subarray(0, 8)
+    const telemetryEvents = (transaction?.meta?.logMessages ?? [])
+      .filter((log) => log.startsWith('Program data: '))
+      .map((log) => Buffer.from(log.slice('Program data: '.length), 'base64'))
+      .filter((data) => data.subarray(0, 8).equals(discriminator))
+
+    // one event before the CPI, one after it with the compute units it consumed
+    expect(telemetryEvents.length).toBe(2)
+    // both are sequenced within the config, the event sequence comes right after the discriminator
+    const [before, after] = telemetryEvents.map((data) => data.readBigUInt64LE(8))
+    expect(after).toBe(before + 1n)
+  })
+
+  test('migration - the trace logs every stage of a full migration', async () => {
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    const { signature } = await ctx.migrate({ curve, baseMint: token })
+
+    const transaction = await ctx.rpc
+      .getTransaction(signature, { encoding: 'json', maxSupportedTransactionVersion: 0 })
+      .send()
+    const discriminator = createHash('sha256').update('event:EvtMigrationAttempt').digest().subarray(0, 8)
+    const attempts = (transaction?.meta?.logMessages ?? [])
+      .filter((log) => log.startsWith('Program data: '))
+      .map((log) => Buffer.from(log.slice('Program data: '.length), 'base64'))
+      .filter((data) => data.subarray(0, 8).equals(discriminator))
+      // curve and config keys, then the stage and the optional error code
+      .map((data) => ({ stage: data[72], failed: data[73] === 1 }))
+
+    // checks are only logged when they fail, the CPI stages are logged before they run
+    expect(attempts).toEqual([
+      { stage: MigrationStage.CreatePool, failed: false },
+      { stage: MigrationStage.LockLiquidity, failed: false },
+      { stage: MigrationStage.TransferPosition, failed: false },
+    ])
+  })
+
+  test('migration - a failed step leaves the migration resumable', async () => {
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    // a fault after the pool creation rolls the whole step back
+    await ctx.setTestHooks({ faultPoint: FaultPoint.AfterPoolCreation })
+    await expect(ctx.migrate({ curve, baseMint: token, createPoolOnly: true, withTestHooks: true })).rejects.toThrow()
+    expect((await fetchBondingCurve(ctx.rpc, curve)).data.migrationStatus).toBe(1)
+
+    // a fault before the lock leaves the pool created by the previous step
+    await ctx.setTestHooks({ faultPoint: FaultPoint.BeforeLiquidityLock })
+    await ctx.migrate({ curve, baseMint: token, createPoolOnly: true, withTestHooks: true })
+    await expect(ctx.migrateLockLiquidity({ curve, withTestHooks: true })).rejects.toThrow()
+    expect((await ctx.getMigrationProgressData({ curve })).data.step).toBe(MigrationStep.PoolCreated)
+
+    await ctx.setTestHooks({ faultPoint: FaultPoint.None })
+    await ctx.migrateLockLiquidity({ curve, withTestHooks: true })
+    await ctx.migrateFinalize({ curve, baseMint: token })
+
+    const [progress, curveState] = await Promise.all([
+      ctx.getMigrationProgressData({ curve }),
+      fetchBondingCurve(ctx.rpc, curve),
+    ])
+    expect(progress.data.step).toBe(MigrationStep.Finalized)
+    expect(curveState.data.migrationStatus).toBe(2)
+  })
+
+  test('migration - clock skew of the test hooks moves past the pre-migration cooldown', async () => {
+    const preMigrationCooldownSeconds = 3_600n
+    const { configAddress } = await ctx.createConfig({ ...DEFAULT_CONFIG_ARGS, preMigrationCooldownSeconds })
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+
+    const migrateArgs = {
+      curve: result.curvePda,
+      baseMint: result.token,
+      config: configAddress,
+      createPoolOnly: true,
+      withTestHooks: true,
+    }
+    await ctx.setTestHooks({ faultPoint: FaultPoint.None })
+    await expect(ctx.migrate(migrateArgs)).rejects.toThrow()
+
+    await ctx.setTestHooks({ faultPoint: FaultPoint.None, clockSkewSeconds: preMigrationCooldownSeconds })
+    await ctx.migrate(migrateArgs)
+    await ctx.setTestHooks({ faultPoint: FaultPoint.None })
+
+    expect((await ctx.getMigrationProgressData({ curve: result.curvePda })).data.step).toBe(MigrationStep.PoolCreated)
+  })
+
+  test('migration - anyone can crank a permissionless config for the crank reward', async () => {
+    const migrationCrankReward = 1_000_000n
+    const { configAddress, feeClaimer: configFeeClaimer } = await ctx.createConfig({
+      ...DEFAULT_CONFIG_ARGS,
+      permissionlessMigration: 1,
+      migrationCrankReward,
+    })
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    const otherTrader = await ctx.createTestTrader(BigInt(200 * LAMPORTS_PER_SOL))
+    await Promise.all([
+      ctx.swap({
+        trader,
+        baseMint: token,
+        amountIn: largeBuyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+      }),
+      ctx.swap({
+        trader: otherTrader,
+        baseMint: result.token,
+        amountIn: largeBuyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        configAddress,
+      }),
+    ])
+
+    // permissionless configs only, the default config still needs an admin or an operator
+    const cranker = await ctx.createTestTrader()
+    expect(ctx.migrate({ curve, baseMint: token, migrationAuthority: cranker, permissionless: true })).rejects.toThrow()
+
+    const curveState = await fetchBondingCurve(ctx.rpc, result.curvePda)
+    await ctx.migrate({
+      curve: result.curvePda,
+      baseMint: result.token,
+      config: configAddress,
+      migrationAuthority: cranker,
+      permissionless: true,
+    })
+
+    const [progress, finalCurveState, crankerBalance] = await Promise.all([
+      ctx.getMigrationProgressData({ curve: result.curvePda }),
+      fetchBondingCurve(ctx.rpc, result.curvePda),
+      ctx.getTokenBalance({ address: cranker.address, mint: WSOL_MINT }),
+    ])
+    expect(finalCurveState.data.migrationStatus).toBe(2)
+    expect(progress.data.positionOwner).toBe(configFeeClaimer.address)
+    expect(crankerBalance).toBe(migrationCrankReward)
+    expect(finalCurveState.data.protocolFee).toBe(curveState.data.protocolFee - migrationCrankReward)
+  })
+
+  test('migration - tops the creator fee up to the guarantee from the migration fee', async () => {
+    const minCreatorFeeAmount = BigInt(10 * LAMPORTS_PER_SOL)
+    const { configAddress } = await ctx.createConfig({ ...DEFAULT_CONFIG_ARGS, minCreatorFeeAmount })
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+
+    const curveState = await fetchBondingCurve(ctx.rpc, result.curvePda)
+    const { quoteReserve, creatorFee, totalCreatorFee } = curveState.data
+    const migrationFeeBasisPoints = BigInt(DEFAULT_CONFIG_ARGS.migrationFeeBasisPoints)
+    const migrationQuoteAmount =
+      (quoteReserve * (BASIS_POINTS_DIVISOR - migrationFeeBasisPoints) + BASIS_POINTS_DIVISOR - 1n) /
+      BASIS_POINTS_DIVISOR
+    const migrationFee = quoteReserve - migrationQuoteAmount
+    const shortfall = minCreatorFeeAmount - totalCreatorFee
+    const expectedTopUp = shortfall < migrationFee ? shortfall : migrationFee
+    expect(expectedTopUp).toBeGreaterThan(0n)
+
+    await ctx.migrate({ curve: result.curvePda, baseMint: result.token, config: configAddress })
+
+    const finalCurveState = await fetchBondingCurve(ctx.rpc, result.curvePda)
+    expect(finalCurveState.data.creatorFee).toBe(creatorFee + expectedTopUp)
+    expect(finalCurveState.data.totalCreatorFee).toBe(totalCreatorFee + expectedTopUp)
+  })
+
+  test('migration - routes part of the migration fee to the support fund for buybacks', async () => {
+    const supportFundBasisPoints = 50_000 // 50% of the migration fee
+    const { configAddress } = await ctx.createConfig({ ...DEFAULT_CONFIG_ARGS, supportFundBasisPoints })
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+
+    const { quoteReserve } = (await fetchBondingCurve(ctx.rpc, result.curvePda)).data
+    const migrationFeeBasisPoints = BigInt(DEFAULT_CONFIG_ARGS.migrationFeeBasisPoints)
+    const migrationQuoteAmount =
+      (quoteReserve * (BASIS_POINTS_DIVISOR - migrationFeeBasisPoints) + BASIS_POINTS_DIVISOR - 1n) /
+      BASIS_POINTS_DIVISOR
+    const expectedDeposit =
+      ((quoteReserve - migrationQuoteAmount) * BigInt(supportFundBasisPoints)) / BASIS_POINTS_DIVISOR
+    expect(expectedDeposit).toBeGreaterThan(0n)
+
+    await ctx.migrate({ curve: result.curvePda, baseMint: result.token, config: configAddress })
+
+    const [supportFundPda] = await getSupportFundPda({ curve: result.curvePda, programId: ctx.programId })
+    const supportFund = await fetchSupportFund(ctx.rpc, supportFundPda)
+    expect(supportFund.data.totalDeposit).toBe(expectedDeposit)
+    expect(await ctx.getVaultTokenBalance({ mint: WSOL_MINT, curvePda: supportFundPda })).toBe(expectedDeposit)
+
+    // only admin can deploy the fund
+    expect(
+      ctx.deploySupportFund({ curve: result.curvePda, amountIn: expectedDeposit, admin: trader }),
+    ).rejects.toThrow()
+
+    const baseVaultBalance = await ctx.getVaultTokenBalance({ mint: result.token, curvePda: result.curvePda })
+    const amountIn = expectedDeposit / 2n
+    await ctx.deploySupportFund({ curve: result.curvePda, amountIn })
+
+    const [finalSupportFund, finalBaseVaultBalance, finalFundBalance] = await Promise.all([
+      fetchSupportFund(ctx.rpc, supportFundPda),
+      ctx.getVaultTokenBalance({ mint: result.token, curvePda: result.curvePda }),
+      ctx.getVaultTokenBalance({ mint: WSOL_MINT, curvePda: supportFundPda }),
+    ])
+    expect(finalSupportFund.data.totalDeployed).toBe(amountIn)
+    expect(finalSupportFund.data.totalBoughtBase).toBe(finalBaseVaultBalance - baseVaultBalance)
+    expect(finalSupportFund.data.totalBoughtBase).toBeGreaterThan(0n)
+    expect(finalFundBalance).toBe(expectedDeposit - amountIn)
+  })
+
+  test('migration - the creator takes its share of the first position fees', async () => {
+    const creatorLpFeeShareBasisPoints = DEFAULT_CONFIG_ARGS.maxCreatorLpFeeShareBasisPoints
+    const creator = await ctx.createTestTrader(BigInt(LAMPORTS_PER_SOL))
+    const mintKeypair = await generateKeyPairSigner()
+    const { curvePda } = await ctx.createBondingCurveAndMintToken({
+      configAddress: ctx.currentConfig!,
+      creator,
+      mintKeypair,
+      creatorLpFeeShareBasisPoints,
+    })
+    await ctx.swap({
+      trader,
+      baseMint: mintKeypair.address,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    const migrationResult = await ctx.migrate({ curve: curvePda, baseMint: mintKeypair.address })
+
+    // the program keeps the position to split its fees
+    const [curveAuthority] = await getCurveAuthority({ programId: ctx.programId })
+    const progress = await ctx.getMigrationProgressData({ curve: curvePda })
+    expect(progress.data.positionOwner).toBe(curveAuthority)
+    expect(ctx.claimPositionFee({ curve: curvePda })).rejects.toThrow()
+
+    await ctx.swapWithDammV2({
+      trader,
+      dammPool: migrationResult.pool,
+      amountIn: SINGLE_BUY_AMOUNT,
+      minimumAmountOut: 0n,
+      inputTokenMint: WSOL_MINT,
+      outputTokenMint: mintKeypair.address,
+    })
+
+    const { quoteVault } = (await fetchBondingCurve(ctx.rpc, curvePda)).data
+    const [creatorBalance, feeClaimerBalance, quoteVaultBalance] = await Promise.all([
+      ctx.getTokenBalance({ address: creator.address, mint: WSOL_MINT }),
+      ctx.getTokenBalance({ address: feeClaimer.address, mint: WSOL_MINT }),
+      ctx.getTokenAccountBalance(quoteVault),
+    ])
+    await ctx.claimPositionFee({ curve: curvePda })
+    const [finalCreatorBalance, finalFeeClaimerBalance, finalQuoteVaultBalance] = await Promise.all([
+      ctx.getTokenBalance({ address: creator.address, mint: WSOL_MINT }),
+      ctx.getTokenBalance({ address: feeClaimer.address, mint: WSOL_MINT }),
+      ctx.getTokenAccountBalance(quoteVault),
+    ])
+
+    const creatorQuoteAmount = finalCreatorBalance - creatorBalance
+    const protocolQuoteAmount = finalFeeClaimerBalance - feeClaimerBalance
+    const quoteFee = creatorQuoteAmount + protocolQuoteAmount
+    expect(quoteFee).toBeGreaterThan(0n)
+    expect(creatorQuoteAmount).toBe((quoteFee * BigInt(creatorLpFeeShareBasisPoints)) / BASIS_POINTS_DIVISOR)
+    // the fees only pass through the curve vault
+    expect(finalQuoteVaultBalance).toBe(quoteVaultBalance)
+  })
+
+  test('migration - the creator gets an unlocked second position with its share of the liquidity', async () => {
+    const creatorLpBasisPoints = 10_000 // 10% of the migrated liquidity
+    const { configAddress } = await ctx.createConfig({ ...DEFAULT_CONFIG_ARGS, creatorLpBasisPoints })
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+
+    const { secondPositionNftKP } = await ctx.migrate({
+      curve: result.curvePda,
+      baseMint: result.token,
+      config: configAddress,
+    })
+
+    const [{ data: progress }, { data: curveState }, [secondPosition], [secondPositionNftAccount]] = await Promise.all([
+      ctx.getMigrationProgressData({ curve: result.curvePda }),
+      fetchBondingCurve(ctx.rpc, result.curvePda),
+      derivePositionAddress(secondPositionNftKP.address),
+      derivePositionNftAccount(secondPositionNftKP.address),
+    ])
+    expect(progress.secondPosition).toBe(secondPosition)
+    const totalLiquidity = progress.initialLiquidity + progress.creatorLiquidity
+    expect(progress.creatorLiquidity).toBe((totalLiquidity * BigInt(creatorLpBasisPoints)) / BASIS_POINTS_DIVISOR)
+
+    // the first position is locked, the creator's one is not and belongs to the creator
+    const [firstPosition, creatorPosition, creatorPositionNftAccount] = await Promise.all([
+      fetchPosition(ctx.rpc, progress.firstPosition),
+      fetchPosition(ctx.rpc, secondPosition),
+      fetchToken(ctx.rpc, secondPositionNftAccount),
+    ])
+    expect(firstPosition.data.permanentLockedLiquidity).toBe(progress.initialLiquidity)
+    expect(creatorPosition.data.unlockedLiquidity).toBe(progress.creatorLiquidity)
+    expect(creatorPosition.data.permanentLockedLiquidity).toBe(0n)
+    expect(creatorPositionNftAccount.data.owner).toBe(curveState.creator)
+  })
+
+  test('migration - the migration fee is paid to the fee claimer and recorded on the curve', async () => {
+    const { configAddress, feeClaimer } = await ctx.createConfig(DEFAULT_CONFIG_ARGS)
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+    const { data: preMigrationCurveState } = await fetchBondingCurve(ctx.rpc, result.curvePda)
+
+    await ctx.migrate({
+      curve: result.curvePda,
+      baseMint: result.token,
+      config: configAddress,
+      payMigrationFee: true,
+    })
+
+    // both sides keep the fee by default, the deposits round up so the fees round down
+    const migrationFeeBasisPoints = BigInt(DEFAULT_CONFIG_ARGS.migrationFeeBasisPoints)
+    const expectedFee = (reserve: bigint) =>
+      reserve -
+      (reserve * (BASIS_POINTS_DIVISOR - migrationFeeBasisPoints) + BASIS_POINTS_DIVISOR - 1n) / BASIS_POINTS_DIVISOR
+    const [{ data: curveState }, quoteAccount, baseAccount] = await Promise.all([
+      fetchBondingCurve(ctx.rpc, result.curvePda),
+      getAssociatedTokenAccountAddress(WSOL_MINT, feeClaimer.address, TOKEN_PROGRAM_ADDRESS),
+      getAssociatedTokenAccountAddress(
+        result.token,
+        feeClaimer.address,
+        preMigrationCurveState.curveType === 0 ? TOKEN_PROGRAM_ADDRESS : TOKEN_2022_PROGRAM_ADDRESS,
+      ),
+    ])
+    expect(curveState.migrationQuoteFee).toBe(expectedFee(preMigrationCurveState.quoteReserve))
+    expect(curveState.migrationFeeCollected).toBe(0n)
+    expect(curveState.migrationBaseFee).toBe(expectedFee(preMigrationCurveState.baseReserve))
+    expect(curveState.migrationBaseFee).toBeGreaterThan(0n)
+
+    const [quoteBalance, baseBalance] = await Promise.all([
+      fetchToken(ctx.rpc, quoteAccount),
+      fetchToken(ctx.rpc, baseAccount),
+    ])
+    expect(quoteBalance.data.amount).toBe(curveState.migrationQuoteFee)
+    expect(baseBalance.data.amount).toBe(curveState.migrationBaseFee)
+  })
 })
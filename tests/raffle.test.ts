@@ -0,0 +1,138 @@
+import { beforeAll, beforeEach, describe, expect, test } from 'bun:test'
+import { type Address, type KeyPairSigner, LAMPORTS_PER_SOL } from 'gill'
+import { fetchBondingCurve, fetchRaffle } from '~/clients'
+import { DEFAULT_CONFIG_ARGS, SINGLE_BUY_AMOUNT, WSOL_MINT } from './utils/constants.ts'
+import { TestContextClass } from './utils/context.ts'
+import { TradeDirection } from './utils/swap-quote.ts'
+
+const ticketPrice = BigInt(LAMPORTS_PER_SOL / 10)
+
+describe('Raffle Tests', () => {
+  let ctx: TestContextClass
+  let token: Address
+  let curve: Address
+  let creator: KeyPairSigner
+  let traders: KeyPairSigner[]
+
+  beforeAll(async () => {
+    ctx = await TestContextClass.create()
+    await ctx.createConfigOnce(DEFAULT_CONFIG_ARGS)
+  })
+
+  beforeEach(async () => {
+    creator = await ctx.createTestTrader(BigInt(5 * LAMPORTS_PER_SOL))
+    const result = await ctx.createFreshBondingCurve(undefined, creator)
+    token = result.token
+    curve = result.curvePda
+    traders = await Promise.all([
+      ctx.createTestTrader(BigInt(5 * LAMPORTS_PER_SOL)),
+      ctx.createTestTrader(BigInt(5 * LAMPORTS_PER_SOL)),
+    ])
+  })
+
+  test('raffle - undersubscribed raffle allocates every ticket and opens trading', async () => {
+    const depositEndTimestamp = BigInt(Math.floor(Date.now() / 1000)) + 4n
+    const { rafflePda } = await ctx.createRaffle({
+      creator,
+      baseMint: token,
+      ticketPrice,
+      winningTicketCount: 10n,
+      maxTicketsPerWallet: 5n,
+      depositEndTimestamp,
+    })
+    await ctx.buyRaffleTickets({ owner: traders[0], baseMint: token, ticketCount: 2n })
+    await ctx.buyRaffleTickets({ owner: traders[1], baseMint: token, ticketCount: 4n })
+
+    // a wallet buys once and within the cap, trading waits for the draw
+    expect(ctx.buyRaffleTickets({ owner: traders[0], baseMint: token, ticketCount: 1n })).rejects.toThrow()
+    expect(ctx.buyRaffleTickets({ owner: creator, baseMint: token, ticketCount: 6n })).rejects.toThrow()
+    expect(
+      ctx.swap({
+        trader: traders[0],
+        baseMint: token,
+        amountIn: SINGLE_BUY_AMOUNT,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+      }),
+    ).rejects.toThrow()
+    expect(ctx.drawRaffle({ baseMint: token })).rejects.toThrow()
+
+    // wait for the ticket sales to close
+    await new Promise((resolve) => setTimeout(resolve, 6_000))
+    await ctx.drawRaffle({ baseMint: token })
+
+    const [raffle, curveState] = await Promise.all([fetchRaffle(ctx.rpc, rafflePda), fetchBondingCurve(ctx.rpc, curve)])
+    expect(raffle.data.isDrawn).toBe(1)
+    expect(raffle.data.totalTickets).toBe(6n)
+    expect(raffle.data.drawnTicketCount).toBe(6n)
+    expect(raffle.data.totalBaseAmount).toBeGreaterThan(0n)
+    expect(curveState.data.isRafflePending).toBe(0)
+    expect(curveState.data.buyCount).toBe(1n)
+    // the allocation is bought without a trading fee
+    expect(curveState.data.protocolFee).toBe(0n)
+    expect(ctx.drawRaffle({ baseMint: token })).rejects.toThrow()
+
+    const quoteBefore = await ctx.getTokenBalance({ address: traders[1].address, mint: WSOL_MINT })
+    await Promise.all(traders.map((owner) => ctx.claimRaffle({ owner, baseMint: token })))
+    const [firstBalance, secondBalance, quoteAfter] = await Promise.all([
+      ctx.getTokenBalance({ address: traders[0].address, mint: token }),
+      ctx.getTokenBalance({ address: traders[1].address, mint: token }),
+      ctx.getTokenBalance({ address: traders[1].address, mint: WSOL_MINT }),
+    ])
+    expect(firstBalance).toBe((raffle.data.totalBaseAmount * 2n) / 6n)
+    expect(secondBalance).toBe((raffle.data.totalBaseAmount * 4n) / 6n)
+    // every ticket won, nothing is refunded
+    expect(quoteAfter).toBe(quoteBefore)
+    expect(ctx.claimRaffle({ owner: traders[0], baseMint: token })).rejects.toThrow()
+
+    await ctx.swap({
+      trader: traders[0],
+      baseMint: token,
+      amountIn: SINGLE_BUY_AMOUNT,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+  }, 30_000)
+
+  test('raffle - oversubscribed raffle waits for its randomness', async () => {
+    const depositEndTimestamp = BigInt(Math.floor(Date.now() / 1000)) + 4n
+    const { rafflePda } = await ctx.createRaffle({
+      creator,
+      baseMint: token,
+      ticketPrice,
+      winningTicketCount: 2n,
+      maxTicketsPerWallet: 5n,
+      depositEndTimestamp,
+    })
+    await Promise.all(traders.map((owner) => ctx.buyRaffleTickets({ owner, baseMint: token, ticketCount: 3n })))
+
+    // no randomness is committed and the draw deadline is an hour away
+    await new Promise((resolve) => setTimeout(resolve, 6_000))
+    expect(ctx.drawRaffle({ baseMint: token })).rejects.toThrow()
+
+    const [raffle, curveState] = await Promise.all([fetchRaffle(ctx.rpc, rafflePda), fetchBondingCurve(ctx.rpc, curve)])
+    expect(raffle.data.isDrawn).toBe(0)
+    expect(raffle.data.totalTickets).toBe(6n)
+    expect(curveState.data.isRafflePending).toBe(1)
+  }, 30_000)
+
+  test('raffle - only a curve that has not traded can hold one', async () => {
+    await ctx.swap({
+      trader: traders[0],
+      baseMint: token,
+      amountIn: SINGLE_BUY_AMOUNT,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    expect(
+      ctx.createRaffle({
+        creator,
+        baseMint: token,
+        ticketPrice,
+        winningTicketCount: 2n,
+        maxTicketsPerWallet: 1n,
+        depositEndTimestamp: BigInt(Math.floor(Date.now() / 1000)) + 60n,
+      }),
+    ).rejects.toThrow()
+  })
+})
@@ -0,0 +1,92 @@
+import { beforeAll, beforeEach, describe, expect, test } from 'bun:test'
+import { type Address, type KeyPairSigner, LAMPORTS_PER_SOL } from 'gill'
+import { DEFAULT_CONFIG_ARGS, SINGLE_BUY_AMOUNT, WSOL_MINT } from './utils/constants.ts'
+import { TestContextClass } from './utils/context.ts'
+import { buildRewardMerkleTree } from './utils/reward-merkle.ts'
+import { TradeDirection } from './utils/swap-quote.ts'
+
+const rewardAmount = BigInt(LAMPORTS_PER_SOL)
+const largeBuyAmount = BigInt(100 * LAMPORTS_PER_SOL) // Large enough to trigger graduation
+
+describe('Reward Vault Tests', () => {
+  let ctx: TestContextClass
+  let token: Address
+  let curve: Address
+  let holder: KeyPairSigner
+  let otherHolder: KeyPairSigner
+
+  beforeAll(async () => {
+    ctx = await TestContextClass.create()
+    await ctx.createConfigOnce(DEFAULT_CONFIG_ARGS)
+  })
+
+  beforeEach(async () => {
+    const result = await ctx.createFreshBondingCurve()
+    token = result.token
+    curve = result.curvePda
+    holder = await ctx.createTestTrader(BigInt(200 * LAMPORTS_PER_SOL))
+    otherHolder = await ctx.createTestTrader()
+    await ctx.fundRewardVault({ curve, amount: rewardAmount })
+  })
+
+  test('reward vault - only admin can fund and set the distribution', async () => {
+    const rewardVault = await ctx.getRewardVaultData({ curve })
+    expect(rewardVault.data.totalFunded).toBe(rewardAmount)
+    expect(rewardVault.data.rewardMint).toBe(WSOL_MINT)
+
+    expect(ctx.fundRewardVault({ curve, amount: rewardAmount, signer: holder })).rejects.toThrow()
+    expect(ctx.setRewardDistribution({ curve, merkleRoot: new Array(32).fill(1), signer: holder })).rejects.toThrow()
+  })
+
+  test('reward vault - distribution waits for graduation', async () => {
+    await ctx.swap({
+      trader: holder,
+      baseMint: token,
+      amountIn: SINGLE_BUY_AMOUNT,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    expect(ctx.setRewardDistribution({ curve, merkleRoot: new Array(32).fill(1) })).rejects.toThrow()
+  })
+
+  test('reward vault - holders claim their snapshot share once', async () => {
+    await ctx.swap({
+      trader: holder,
+      baseMint: token,
+      amountIn: largeBuyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    const leaves = [
+      { holder: holder.address, amount: (rewardAmount * 3n) / 4n },
+      { holder: otherHolder.address, amount: rewardAmount / 4n },
+    ]
+    const { root, getProof } = buildRewardMerkleTree(leaves)
+    await ctx.setRewardDistribution({ curve, merkleRoot: root })
+
+    // a leaf is only valid for its own holder and amount
+    expect(
+      ctx.claimReward({ user: holder, curve, amount: leaves[0].amount + 1n, proof: getProof(0) }),
+    ).rejects.toThrow()
+    expect(
+      ctx.claimReward({ user: otherHolder, curve, amount: leaves[0].amount, proof: getProof(0) }),
+    ).rejects.toThrow()
+
+    const preClaimBalance = await ctx.getTokenBalance({ address: holder.address, mint: WSOL_MINT })
+    await ctx.claimReward({ user: holder, curve, amount: leaves[0].amount, proof: getProof(0) })
+    await ctx.claimReward({ user: otherHolder, curve, amount: leaves[1].amount, proof: getProof(1) })
+
+    const [postClaimBalance, rewardVault] = await Promise.all([
+      ctx.getTokenBalance({ address: holder.address, mint: WSOL_MINT }),
+      ctx.getRewardVaultData({ curve }),
+    ])
+    expect(postClaimBalance).toBe(preClaimBalance + leaves[0].amount)
+    expect(rewardVault.data.totalClaimed).toBe(rewardAmount)
+
+    // claiming twice is not allowed, nor swapping the root once claims started
+    expect(ctx.claimReward({ user: holder, curve, amount: leaves[0].amount, proof: getProof(0) })).rejects.toThrow()
+    expect(ctx.setRewardDistribution({ curve, merkleRoot: root })).rejects.toThrow()
+  })
+})
@@ -0,0 +1,156 @@
+import { beforeAll, beforeEach, describe, expect, test } from 'bun:test'
+import { type Address, type KeyPairSigner, LAMPORTS_PER_SOL } from 'gill'
+import { fetchBondingCurve } from '~/clients'
+import { RoutedSwapFeeMode } from './utils/accounts.ts'
+import { DEFAULT_CONFIG_ARGS } from './utils/constants.ts'
+import { TestContextClass } from './utils/context.ts'
+import { TradeDirection, getSwapResult } from './utils/swap-quote.ts'
+
+const buyAmount = BigInt(LAMPORTS_PER_SOL)
+
+describe('Token to token swap', () => {
+  let ctx: TestContextClass
+  let tokenA: Address
+  let tokenB: Address
+  let curveB: Address
+  let trader: KeyPairSigner
+
+  beforeAll(async () => {
+    ctx = await TestContextClass.create()
+    await ctx.createConfigOnce(DEFAULT_CONFIG_ARGS)
+  })
+
+  beforeEach(async () => {
+    const [resultA, resultB] = await Promise.all([ctx.createFreshBondingCurve(), ctx.createFreshBondingCurve()])
+    tokenA = resultA.token
+    tokenB = resultB.token
+    curveB = resultB.curvePda
+    trader = await ctx.createTestTrader()
+    await ctx.swap({
+      trader,
+      baseMint: tokenA,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+  })
+
+  test('token to token - sells on one curve and buys on the other', async () => {
+    const [amountIn, curveAState, curveBState, configState] = await Promise.all([
+      ctx.getTokenBalance({ address: trader.address, mint: tokenA }),
+      ctx.getBondingCurveData({ baseMint: tokenA }),
+      ctx.getBondingCurveData({ baseMint: tokenB }),
+      ctx.getConfigData({}),
+    ])
+    const sellExpected = getSwapResult({
+      curveState: curveAState.data,
+      configState: configState.data,
+      amountIn,
+      tradeDirection: TradeDirection.BaseToQuote,
+      hasL1Referral: false,
+      hasL2Referral: false,
+      hasL3Referral: false,
+    })
+    const buyExpected = getSwapResult({
+      curveState: curveBState.data,
+      configState: configState.data,
+      amountIn: sellExpected.outputAmount,
+      tradeDirection: TradeDirection.QuoteToBase,
+      hasL1Referral: false,
+      hasL2Referral: false,
+      hasL3Referral: false,
+    })
+
+    // the slippage check is on the final output
+    expect(
+      ctx.swapTokenToToken({
+        trader,
+        baseMintA: tokenA,
+        baseMintB: tokenB,
+        amountIn,
+        minimumAmountOut: buyExpected.outputAmount + 1n,
+      }),
+    ).rejects.toThrow()
+
+    await ctx.swapTokenToToken({
+      trader,
+      baseMintA: tokenA,
+      baseMintB: tokenB,
+      amountIn,
+      minimumAmountOut: buyExpected.outputAmount,
+    })
+
+    const [balanceA, balanceB, finalCurveBState] = await Promise.all([
+      ctx.getTokenBalance({ address: trader.address, mint: tokenA }),
+      ctx.getTokenBalance({ address: trader.address, mint: tokenB }),
+      fetchBondingCurve(ctx.rpc, curveB),
+    ])
+    expect(balanceA).toBe(0n)
+    expect(balanceB).toBe(buyExpected.outputAmount)
+    expect(finalCurveBState.data.protocolFee).toBe(buyExpected.protocolFee)
+  })
+
+  test('token to token - waived buy fee only charges the sell leg', async () => {
+    const { configAddress } = await ctx.createConfig({
+      ...DEFAULT_CONFIG_ARGS,
+      routedSwapFeeMode: RoutedSwapFeeMode.Waived,
+    })
+    const { token: waivedToken, curvePda: waivedCurve } = await ctx.createFreshBondingCurve(configAddress)
+    const amountIn = await ctx.getTokenBalance({ address: trader.address, mint: tokenA })
+
+    await ctx.swapTokenToToken({
+      trader,
+      baseMintA: tokenA,
+      baseMintB: waivedToken,
+      amountIn,
+      minimumAmountOut: 0n,
+      configB: configAddress,
+    })
+
+    const waivedCurveState = await fetchBondingCurve(ctx.rpc, waivedCurve)
+    expect(await ctx.getTokenBalance({ address: trader.address, mint: waivedToken })).toBeGreaterThan(0n)
+    expect(waivedCurveState.data.protocolFee).toBe(0n)
+    expect(waivedCurveState.data.creatorFee).toBe(0n)
+  })
+
+  test('token to token - the sell leg settles early buys out of the bonus pool', async () => {
+    const creator = await ctx.createTestTrader(BigInt(5 * LAMPORTS_PER_SOL))
+    const { token: bonusToken, curvePda: bonusCurve } = await ctx.createFreshBondingCurve(undefined, creator)
+    await ctx.depositBonusPool({ creator, baseMint: bonusToken, amount: buyAmount })
+    await ctx.createProtection({ user: trader, curve: bonusCurve })
+    await ctx.swap({
+      trader,
+      baseMint: bonusToken,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      withBonusPool: true,
+    })
+    const bonusPoolBefore = await ctx.getBonusPoolData({ curve: bonusCurve })
+    expect(bonusPoolBefore.data.totalEligibleQuoteAmount).toBeGreaterThan(0n)
+
+    await ctx.swapTokenToToken({
+      trader,
+      baseMintA: bonusToken,
+      baseMintB: tokenB,
+      amountIn: await ctx.getTokenBalance({ address: trader.address, mint: bonusToken }),
+      minimumAmountOut: 0n,
+      withBonusPoolA: true,
+    })
+
+    const [protection, bonusPool] = await Promise.all([
+      ctx.getProtectionData({ curve: bonusCurve, user: trader.address }),
+      ctx.getBonusPoolData({ curve: bonusCurve }),
+    ])
+    expect(protection.data.earlyBuyQuoteAmount).toBe(0n)
+    expect(bonusPool.data.totalEligibleQuoteAmount).toBe(0n)
+  })
+
+  test('token to token - rejects routing into the same curve', async () => {
+    const amountIn = await ctx.getTokenBalance({ address: trader.address, mint: tokenA })
+
+    expect(
+      ctx.swapTokenToToken({ trader, baseMintA: tokenA, baseMintB: tokenA, amountIn, minimumAmountOut: 0n }),
+    ).rejects.toThrow()
+  })
+})
@@ -1,10 +1,26 @@
 import { beforeAll, beforeEach, describe, expect, test } from 'bun:test'
-import { type Address, type KeyPairSigner, LAMPORTS_PER_SOL } from 'gill'
+import { createHash } from 'node:crypto'
+import {
+  type Address,
+  type KeyPairSigner,
+  LAMPORTS_PER_SOL,
+  generateKeyPairSigner,
+  getAddressEncoder,
+  getBase58Encoder,
+} from 'gill'
+import { TOKEN_2022_PROGRAM_ADDRESS } from 'gill/programs'
 import { TOKEN_PROGRAM_ADDRESS, getAssociatedTokenAccountAddress } from 'gill/programs/token'
 import { fetchBondingCurve } from '~/clients'
-import { DEFAULT_CONFIG_ARGS, TOKEN_TOTAL_SUPPLY, WSOL_MINT } from './utils/constants.ts'
+import { ActivationType, FaultPoint, TradingPause, getCashbackAccounts, getCurveVaultPda } from './utils/accounts.ts'
+import {
+  CASHBACK_WOOD_BPS,
+  DEFAULT_CONFIG_ARGS,
+  TOKEN_TOTAL_SUPPLY,
+  TRADING_END_SELL_GRACE_SECONDS,
+  WSOL_MINT,
+} from './utils/constants.ts'
 import { TestContextClass } from './utils/context.ts'
-import { TradeDirection, getSwapResult } from './utils/swap-quote.ts'
+import { FeeCollectionMode, TradeDirection, getSwapResult } from './utils/swap-quote.ts'
 
 // Test Constants
 const buyAmount = BigInt(LAMPORTS_PER_SOL)
@@ -28,6 +44,14 @@ describe('Swap Tests', () => {
     trader = await ctx.createTestTrader()
   })
 
+  // Record the chain from the top so each level extends the one above it
+  const recordReferrerChain = async (user: KeyPairSigner, referrers: KeyPairSigner[]) => {
+    const chain = [user, ...referrers]
+    for (let i = chain.length - 2; i >= 0; i--) {
+      await ctx.setReferrer({ user: chain[i], referrer: chain[i + 1].address })
+    }
+  }
+
   test('swap - overall no cashback no referral', async () => {
     // Get initial balances before any trading
     const [initialCurveState, configState, traderInitialSolBalance, curveInitialQuoteBalance, curveInitialBaseBalance] =
@@ -231,6 +255,7 @@ describe('Swap Tests', () => {
       ctx.createCashbackAccount(l2Referrer),
       ctx.createCashbackAccount(l3Referrer),
     ])
+    await recordReferrerChain(trader, [l1Referrer, l2Referrer, l3Referrer])
 
     const [
       initialCurveState,
@@ -383,6 +408,7 @@ describe('Swap Tests', () => {
       ctx.createCashbackAccount(l2Referrer),
       ctx.createCashbackAccount(l3Referrer),
     ])
+    await recordReferrerChain(trader, [l1Referrer, l2Referrer, l3Referrer])
 
     const [
       initialCurveState,
@@ -586,6 +612,89 @@ describe('Swap Tests', () => {
     expect(tokenBalanceAfter).toBe(0n)
   })
 
+  test('swap - native SOL is wrapped and unwrapped by the program', async () => {
+    const wsolTokenAccount = await getAssociatedTokenAccountAddress(WSOL_MINT, trader.address, TOKEN_PROGRAM_ADDRESS)
+    const initialTraderSolBalance = await ctx.getBalance(trader.address)
+
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      useNativeSol: true,
+    })
+
+    const [solBalanceAfterBuy, wsolBalanceAfterBuy, traderTokenBalance] = await Promise.all([
+      ctx.getBalance(trader.address),
+      ctx.getBalance(wsolTokenAccount),
+      ctx.getTokenBalance({ address: trader.address, mint: token }),
+    ])
+    // the WSOL account is closed, its rent is back with the trader
+    expect(wsolBalanceAfterBuy).toBe(0n)
+    expect(initialTraderSolBalance - solBalanceAfterBuy).toBeGreaterThanOrEqual(buyAmount)
+    expect(traderTokenBalance).toBeGreaterThan(0n)
+
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: traderTokenBalance,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.BaseToQuote,
+      useNativeSol: true,
+    })
+
+    const [solBalanceAfterSell, wsolBalanceAfterSell] = await Promise.all([
+      ctx.getBalance(trader.address),
+      ctx.getBalance(wsolTokenAccount),
+    ])
+    expect(wsolBalanceAfterSell).toBe(0n)
+    expect(solBalanceAfterSell).toBeGreaterThan(solBalanceAfterBuy)
+    expect(await ctx.getTokenBalance({ address: trader.address, mint: token })).toBe(0n)
+  })
+
+  test('swap - rejects swaps landing after their valid until slot', async () => {
+    const currentSlot = await ctx.rpc.getSlot().send()
+
+    expect(
+      ctx.swap({
+        trader,
+        baseMint: token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        validUntilSlot: currentSlot - 1n,
+      }),
+    ).rejects.toThrow()
+
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      validUntilSlot: currentSlot + 1_000n,
+    })
+    expect(await ctx.getTokenBalance({ address: trader.address, mint: token })).toBeGreaterThan(0n)
+  })
+
+  test('swap - bounds the slippage against the spot price at execution', async () => {
+    // 1 SOL on the 30 SOL virtual quote reserve gets ~3.2% less than the spot price gives
+    const swap = (maxSlippageBasisPoints: number) =>
+      ctx.swap({
+        trader,
+        baseMint: token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        maxSlippageBasisPoints,
+      })
+
+    expect(swap(3_000)).rejects.toThrow()
+    await swap(3_300)
+    expect(await ctx.getTokenBalance({ address: trader.address, mint: token })).toBeGreaterThan(0n)
+  })
+
   test('swap - slippage', async () => {
     await ctx.createCashbackAccount(trader)
 
@@ -758,6 +867,94 @@ describe('Swap Tests', () => {
     expect(curveStateAfter.data.baseReserve).toBe(curveBaseVaultBalance)
   })
 
+  test('swap - bonding curve trade stats update correctly', async () => {
+    const [curveStateBefore, configState] = await Promise.all([
+      fetchBondingCurve(ctx.rpc, curve),
+      ctx.getConfigData({}),
+    ])
+    const buyResult = getSwapResult({
+      curveState: curveStateBefore.data,
+      configState: configState.data,
+      amountIn: buyAmount,
+      tradeDirection: TradeDirection.QuoteToBase,
+      hasL1Referral: false,
+      hasL2Referral: false,
+      hasL3Referral: false,
+    })
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    const curveStateAfterBuy = await fetchBondingCurve(ctx.rpc, curve)
+    expect(curveStateAfterBuy.data.totalQuoteVolume).toBe(buyResult.actualInputAmount + buyResult.tradingFee)
+    expect(curveStateAfterBuy.data.buyCount).toBe(1n)
+    expect(curveStateAfterBuy.data.sellCount).toBe(0n)
+    expect(curveStateAfterBuy.data.uniqueTraderEstimate).toBe(1n)
+    expect(curveStateAfterBuy.data.lastTradeTimestamp).toBeGreaterThan(0n)
+
+    // selling out and buying back counts the trader again
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: await ctx.getTokenBalance({ address: trader.address, mint: token }),
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.BaseToQuote,
+    })
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    const curveStateAfter = await fetchBondingCurve(ctx.rpc, curve)
+    expect(curveStateAfter.data.totalQuoteVolume).toBeGreaterThan(curveStateAfterBuy.data.totalQuoteVolume)
+    expect(curveStateAfter.data.buyCount).toBe(2n)
+    expect(curveStateAfter.data.sellCount).toBe(1n)
+    expect(curveStateAfter.data.uniqueTraderEstimate).toBe(2n)
+    expect(curveStateAfter.data.holderCount).toBe(1n)
+  })
+
+  test('swap - protocol stats count curves, config stats count swaps', async () => {
+    const [statsBefore, configStatsBefore] = await Promise.all([ctx.getProtocolStats(), ctx.getConfigStats()])
+    const [curveState, configState] = await Promise.all([fetchBondingCurve(ctx.rpc, curve), ctx.getConfigData({})])
+    const swapResult = getSwapResult({
+      curveState: curveState.data,
+      configState: configState.data,
+      amountIn: buyAmount,
+      tradeDirection: TradeDirection.QuoteToBase,
+      hasL1Referral: false,
+      hasL2Referral: false,
+      hasL3Referral: false,
+    })
+
+    await ctx.createFreshBondingCurve()
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    const [statsAfter, configStatsAfter] = await Promise.all([ctx.getProtocolStats(), ctx.getConfigStats()])
+    expect(statsAfter.totalCurvesCreated).toBe(statsBefore.totalCurvesCreated + 1n)
+
+    // swaps only write to the stats of their config
+    expect(configStatsAfter.totalCurvesCreated).toBe(configStatsBefore.totalCurvesCreated + 1n)
+    expect(configStatsAfter.totalSwaps).toBe(configStatsBefore.totalSwaps + 1n)
+    expect(configStatsAfter.totalQuoteVolume).toBe(
+      configStatsBefore.totalQuoteVolume + swapResult.actualInputAmount + swapResult.tradingFee,
+    )
+    expect(configStatsAfter.totalProtocolFee).toBe(configStatsBefore.totalProtocolFee + swapResult.protocolFee)
+    expect(configStatsAfter.totalCreatorFee).toBe(configStatsBefore.totalCreatorFee + swapResult.creatorFee)
+  })
+
   test('swap - multiple small buys vs single large buy price impact', async () => {
     const [trader1, trader2, { token: tokenA }, { token: tokenB }] = await Promise.all([
       ctx.createTestTrader(),
@@ -845,6 +1042,269 @@ describe('Swap Tests', () => {
     ).rejects.toThrow()
   })
 
+  test('swap - rejects a token program that does not own the mint', async () => {
+    expect(
+      ctx.swap({
+        trader: trader,
+        baseMint: token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        tokenBaseProgram: TOKEN_2022_PROGRAM_ADDRESS,
+      }),
+    ).rejects.toThrow()
+  })
+
+  test('swap - referral fees only go to the recorded referrer chain', async () => {
+    const [l1Referrer, l2Referrer, impostor] = await Promise.all([
+      ctx.createTestTrader(),
+      ctx.createTestTrader(),
+      ctx.createTestTrader(),
+    ])
+    await Promise.all([
+      ctx.createCashbackAccount(l1Referrer),
+      ctx.createCashbackAccount(l2Referrer),
+      ctx.createCashbackAccount(impostor),
+    ])
+    await recordReferrerChain(trader, [l1Referrer, l2Referrer])
+
+    const swapWithReferrers = (l1: Address, l2?: Address) =>
+      ctx.swap({
+        trader,
+        baseMint: token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        l1Referrer: l1,
+        l2Referrer: l2,
+      })
+
+    // neither redirected nor partially skipped
+    expect(swapWithReferrers(impostor.address, l2Referrer.address)).rejects.toThrow()
+    expect(swapWithReferrers(l1Referrer.address)).rejects.toThrow()
+    await swapWithReferrers(l1Referrer.address, l2Referrer.address)
+
+    // the chain is set once and can't loop back to the user
+    expect(ctx.setReferrer({ user: trader, referrer: impostor.address })).rejects.toThrow()
+    expect(ctx.setReferrer({ user: l2Referrer, referrer: trader.address })).rejects.toThrow()
+  })
+
+  test('swap - rejects spoofed referral token accounts', async () => {
+    const [l1Referrer, impostor] = await Promise.all([ctx.createTestTrader(), ctx.createTestTrader()])
+    await Promise.all([ctx.createCashbackAccount(l1Referrer), ctx.createCashbackAccount(impostor)])
+    await recordReferrerChain(trader, [l1Referrer])
+
+    // quote token accounts of the right mint that aren't the ATA of the referrer's cashback PDA
+    const [{ cashbackTokenAccount: impostorCashbackTokenAccount }, [quoteVault]] = await Promise.all([
+      getCashbackAccounts({ cashbackAddress: impostor.address, quoteMint: WSOL_MINT, programId: ctx.programId }),
+      getCurveVaultPda({ curvePda: curve, mint: WSOL_MINT, programId: ctx.programId }),
+    ])
+
+    const swapWithL1ReferralTokenAccount = (l1ReferralTokenAccount?: Address) =>
+      ctx.swap({
+        trader,
+        baseMint: token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        l1Referrer: l1Referrer.address,
+        l1ReferralTokenAccount,
+      })
+
+    await expect(swapWithL1ReferralTokenAccount(impostorCashbackTokenAccount!)).rejects.toThrow()
+    await expect(swapWithL1ReferralTokenAccount(quoteVault)).rejects.toThrow()
+
+    const referrerBalanceBefore = await ctx.getCashbackTokenBalance({ user: l1Referrer.address })
+    await swapWithL1ReferralTokenAccount()
+    expect(await ctx.getCashbackTokenBalance({ user: l1Referrer.address })).toBeGreaterThan(referrerBalanceBefore)
+  })
+
+  test('swap - fee schedule reports the fee rates of a wallet', async () => {
+    const {
+      feeBasisPoints,
+      refereeDiscountBasisPoints,
+      l1ReferralFeeBasisPoints,
+      l2ReferralFeeBasisPoints,
+      creatorFeeBasisPoints,
+    } = DEFAULT_CONFIG_ARGS
+
+    const anonymous = await ctx.getFeeSchedule({ wallet: trader.address })
+    expect(anonymous).toEqual({
+      tradingFeeBasisPoints: feeBasisPoints,
+      refereeDiscountBasisPoints: 0,
+      feeDiscountBasisPoints: 0,
+      cashbackBasisPoints: 0,
+      l1ReferralFeeBasisPoints: 0,
+      l2ReferralFeeBasisPoints: 0,
+      l3ReferralFeeBasisPoints: 0,
+      creatorFeeBasisPoints,
+      protocolFeeBasisPoints: feeBasisPoints - creatorFeeBasisPoints,
+    })
+
+    const [l1Referrer, l2Referrer] = await Promise.all([ctx.createTestTrader(), ctx.createTestTrader()])
+    await Promise.all([
+      ctx.createCashbackAccount(trader),
+      ctx.createCashbackAccount(l1Referrer),
+      ctx.createCashbackAccount(l2Referrer),
+    ])
+    await recordReferrerChain(trader, [l1Referrer, l2Referrer])
+
+    // a wood tier wallet referred by a two level chain
+    const tradingFeeBasisPoints = feeBasisPoints - refereeDiscountBasisPoints
+    const cashbackBasisPoints = Number(CASHBACK_WOOD_BPS)
+    const personalized = await ctx.getFeeSchedule({ wallet: trader.address, withCashback: true, withReferral: true })
+    expect(personalized).toEqual({
+      tradingFeeBasisPoints,
+      refereeDiscountBasisPoints,
+      feeDiscountBasisPoints: 0,
+      cashbackBasisPoints,
+      l1ReferralFeeBasisPoints,
+      l2ReferralFeeBasisPoints,
+      l3ReferralFeeBasisPoints: 0,
+      creatorFeeBasisPoints,
+      protocolFeeBasisPoints:
+        tradingFeeBasisPoints -
+        l1ReferralFeeBasisPoints -
+        l2ReferralFeeBasisPoints -
+        creatorFeeBasisPoints -
+        cashbackBasisPoints,
+    })
+  })
+
+  test('swap - cashback follows the per-direction multipliers of the config', async () => {
+    const { configAddress } = await ctx.createConfig({
+      ...DEFAULT_CONFIG_ARGS,
+      buyCashbackMultiplierBasisPoints: 50_000,
+      sellCashbackMultiplierBasisPoints: 0,
+    })
+    await ctx.createCashbackAccount(trader)
+
+    const [buy, sell] = await Promise.all([
+      ctx.getFeeSchedule({ wallet: trader.address, withCashback: true, configAddress }),
+      ctx.getFeeSchedule({
+        wallet: trader.address,
+        withCashback: true,
+        tradeDirection: TradeDirection.BaseToQuote,
+        configAddress,
+      }),
+    ])
+    // half the tier cashback on buys, none on sells
+    expect(buy.cashbackBasisPoints).toBe(Number(CASHBACK_WOOD_BPS) / 2)
+    expect(sell.cashbackBasisPoints).toBe(0)
+    expect(sell.protocolFeeBasisPoints).toBe(sell.tradingFeeBasisPoints - sell.creatorFeeBasisPoints)
+  })
+
+  test('swap - launch fee decays to the trading fee', async () => {
+    const launchFeeBasisPoints = 20_000
+    const { configAddress } = await ctx.createConfig({
+      ...DEFAULT_CONFIG_ARGS,
+      launchFeeBasisPoints,
+      launchFeePeriods: 1, // held for the whole day
+      launchFeeDecaySeconds: 86_400n,
+    })
+    const result = await ctx.createFreshBondingCurve(configAddress)
+
+    const [base, launch] = await Promise.all([
+      ctx.getFeeSchedule({ wallet: trader.address, configAddress }),
+      ctx.getFeeSchedule({ wallet: trader.address, configAddress, curveAddress: result.curvePda }),
+    ])
+    expect(base.tradingFeeBasisPoints).toBe(DEFAULT_CONFIG_ARGS.feeBasisPoints)
+    // the launch fee tops up the protocol fee, the other shares are unchanged
+    expect(launch.tradingFeeBasisPoints).toBe(launchFeeBasisPoints)
+    expect(launch.creatorFeeBasisPoints).toBe(base.creatorFeeBasisPoints)
+    expect(launch.protocolFeeBasisPoints).toBe(launchFeeBasisPoints - base.creatorFeeBasisPoints)
+
+    const simulation = await ctx.simulateSwap({
+      wallet: trader.address,
+      baseMint: result.token,
+      amountIn: buyAmount,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+    expect(simulation.swapResult.tradingFee).toBe((buyAmount * BigInt(launchFeeBasisPoints)) / 100_000n)
+  })
+
+  test('swap - simulated swaps match the executed ones', async () => {
+    await ctx.createCashbackAccount(trader)
+    const simulation = await ctx.simulateSwap({
+      wallet: trader.address,
+      baseMint: token,
+      amountIn: buyAmount,
+      tradeDirection: TradeDirection.QuoteToBase,
+      withCashback: true,
+    })
+
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: simulation.swapResult.outputAmount,
+      tradeDirection: TradeDirection.QuoteToBase,
+      cashbackAddress: trader.address,
+    })
+
+    const curveState = await ctx.getBondingCurveData({ baseMint: token })
+    expect(await ctx.getTokenBalance({ address: trader.address, mint: token })).toBe(
+      simulation.swapResult.outputAmount,
+    )
+    expect(curveState.data.virtualBaseReserve).toBe(simulation.virtualBaseReserve)
+    expect(curveState.data.virtualQuoteReserve).toBe(simulation.virtualQuoteReserve)
+    expect(simulation.completesCurve).toBe(false)
+
+    // a buy of the whole migration cap graduates the curve
+    const graduating = await ctx.simulateSwap({
+      wallet: trader.address,
+      baseMint: token,
+      amountIn: 1_000n * BigInt(LAMPORTS_PER_SOL),
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    expect(graduating.completesCurve).toBe(true)
+  })
+
+  test('swap - the curve invariants hold after a buy', async () => {
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    const configBefore = await ctx.getConfigData({})
+    const signature = await ctx.verifyCurveInvariants({ baseMint: token })
+    const transaction = await ctx.rpc
+      .getTransaction(signature, { encoding: 'json', maxSupportedTransactionVersion: 0 })
+      .send()
+    // emit_cpi events are the data of a self CPI: event ix tag, event discriminator, event
+    const discriminator = createHash('sha256').update('event:EvtVerifyCurveInvariants').digest().subarray(0, 8)
+    const [event] = (transaction?.meta?.innerInstructions ?? [])
+      .flatMap(({ instructions }) => instructions)
+      .map(({ data }) => Buffer.from(getBase58Encoder().encode(data)))
+      .filter((data) => data.subarray(8, 16).equals(discriminator))
+      .map((data) => data.subarray(16))
+
+    expect(event).toBeDefined()
+    // the event sequence, curve and config come first, then whether every invariant holds
+    expect(event.readBigUInt64LE(0)).toBe(configBefore.data.eventSequence + 1n)
+    expect(event.subarray(8, 40)).toEqual(Buffer.from(getAddressEncoder().encode(curve)))
+    expect(event[72]).toBe(1)
+  })
+
+  test('swap - increments the config event sequence', async () => {
+    const configBefore = await ctx.getConfigData({})
+
+    await ctx.swap({
+      trader: trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    const configAfter = await ctx.getConfigData({})
+    expect(configAfter.data.eventSequence).toEqual(configBefore.data.eventSequence + 1n)
+  })
+
   /**
    * Creating a fresh cashback account takes about extra 34000 compute units.
    */
@@ -858,4 +1318,513 @@ describe('Swap Tests', () => {
     })
     // TODO: Check the compute units used for the first swap
   })
+
+  test('swap - tracks the approximate holder count', async () => {
+    const otherTrader = await ctx.createTestTrader()
+    const initialCurveState = await ctx.getBondingCurveData({ baseMint: token })
+    expect(initialCurveState.data.holderCount).toBe(0n)
+
+    for (const buyer of [trader, trader, otherTrader]) {
+      await ctx.swap({
+        trader: buyer,
+        baseMint: token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+      })
+    }
+
+    // a repeat buy from the same wallet is not counted twice
+    const postBuyCurveState = await ctx.getBondingCurveData({ baseMint: token })
+    expect(postBuyCurveState.data.holderCount).toBe(2n)
+
+    const otherTraderBalance = await ctx.getTokenBalance({ address: otherTrader.address, mint: token })
+    await ctx.swap({
+      trader: otherTrader,
+      baseMint: token,
+      amountIn: otherTraderBalance,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.BaseToQuote,
+    })
+
+    const postSellCurveState = await ctx.getBondingCurveData({ baseMint: token })
+    expect(postSellCurveState.data.holderCount).toBe(1n)
+
+    // buying back after a full sell counts the wallet as a holder again
+    await ctx.swap({
+      trader: otherTrader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    const postRebuyCurveState = await ctx.getBondingCurveData({ baseMint: token })
+    expect(postRebuyCurveState.data.holderCount).toBe(2n)
+  })
+
+  test('swap - anti-snipe caps the buys of a wallet during the launch window', async () => {
+    const { configAddress } = await ctx.createConfig({
+      ...DEFAULT_CONFIG_ARGS,
+      antiSnipeWindowSlots: 1_000n,
+      maxBuyAmountFirstWindow: buyAmount,
+    })
+    const result = await ctx.createFreshBondingCurve(configAddress)
+
+    // the protection account tracks the wallet's buys, so it is required during the window
+    expect(
+      ctx.swap({
+        trader,
+        baseMint: result.token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        configAddress,
+      }),
+    ).rejects.toThrow()
+
+    await ctx.createProtection({ user: trader, curve: result.curvePda })
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+      withProtection: true,
+    })
+    const protection = await ctx.getProtectionData({ user: trader.address, curve: result.curvePda })
+    expect(protection.data.antiSnipeBuyQuoteAmount).toBe(buyAmount)
+
+    // the cap is cumulative per wallet
+    expect(
+      ctx.swap({
+        trader,
+        baseMint: result.token,
+        amountIn: 1_000n,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        configAddress,
+        withProtection: true,
+      }),
+    ).rejects.toThrow()
+
+    // sells are not capped
+    const traderBalance = await ctx.getTokenBalance({ address: trader.address, mint: result.token })
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: traderBalance,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.BaseToQuote,
+      configAddress,
+    })
+  })
+
+  test('swap - fee collection mode takes the fee from the configured side', async () => {
+    for (const feeCollectionMode of [FeeCollectionMode.FeeOnInput, FeeCollectionMode.FeeOnOutput]) {
+      const { configAddress } = await ctx.createConfig({ ...DEFAULT_CONFIG_ARGS, feeCollectionMode })
+      const result = await ctx.createFreshBondingCurve(configAddress)
+      const configState = await ctx.getConfigData({ configAddress })
+      expect(configState.data.feeCollectionMode).toBe(feeCollectionMode)
+
+      for (const tradeDirection of [TradeDirection.QuoteToBase, TradeDirection.BaseToQuote]) {
+        const curveState = await ctx.getBondingCurveData({ baseMint: result.token, configAddress })
+        const amountIn =
+          tradeDirection === TradeDirection.QuoteToBase
+            ? buyAmount
+            : await ctx.getTokenBalance({ address: trader.address, mint: result.token })
+        const expected = getSwapResult({
+          curveState: curveState.data,
+          configState: configState.data,
+          amountIn,
+          tradeDirection,
+          hasL1Referral: false,
+          hasL2Referral: false,
+          hasL3Referral: false,
+        })
+        expect(expected.tradingFee).toBeGreaterThan(0n)
+
+        await ctx.swap({
+          trader,
+          baseMint: result.token,
+          amountIn,
+          minimumAmountOut: expected.outputAmount,
+          tradeDirection,
+          configAddress,
+        })
+
+        const finalCurveState = await ctx.getBondingCurveData({ baseMint: result.token, configAddress })
+        expect(finalCurveState.data.protocolFee - curveState.data.protocolFee).toBe(expected.protocolFee)
+        expect(finalCurveState.data.creatorFee - curveState.data.creatorFee).toBe(expected.creatorFee)
+        if (tradeDirection === TradeDirection.QuoteToBase) {
+          expect(curveState.data.baseReserve - finalCurveState.data.baseReserve).toBe(expected.outputAmount)
+          expect(expected.actualInputAmount + expected.tradingFee).toBe(amountIn)
+        } else {
+          expect(finalCurveState.data.baseReserve - curveState.data.baseReserve).toBe(amountIn)
+        }
+      }
+    }
+  })
+
+  test('swap - diamond hands locks sells after a buy', async () => {
+    const { configAddress } = await ctx.createConfig({ ...DEFAULT_CONFIG_ARGS, minHoldSeconds: 3_600n })
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    const curveState = await ctx.getBondingCurveData({ baseMint: result.token, configAddress })
+    expect(curveState.data.minHoldSeconds).toBe(3_600n)
+
+    // the protection account is required to trade on a curve with a lockup
+    expect(
+      ctx.swap({
+        trader,
+        baseMint: result.token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        configAddress,
+      }),
+    ).rejects.toThrow()
+
+    await ctx.createProtection({ user: trader, curve: result.curvePda })
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+      withProtection: true,
+    })
+    const protection = await ctx.getProtectionData({ user: trader.address, curve: result.curvePda })
+    expect(protection.data.lastBuyTimestamp).toBeGreaterThan(0n)
+
+    const traderBalance = await ctx.getTokenBalance({ address: trader.address, mint: result.token })
+    expect(
+      ctx.swap({
+        trader,
+        baseMint: result.token,
+        amountIn: traderBalance,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.BaseToQuote,
+        configAddress,
+        withProtection: true,
+      }),
+    ).rejects.toThrow()
+
+    // selling without the protection account does not bypass the lockup
+    expect(
+      ctx.swap({
+        trader,
+        baseMint: result.token,
+        amountIn: traderBalance,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.BaseToQuote,
+        configAddress,
+      }),
+    ).rejects.toThrow()
+  })
+
+  test('swap - paused buys still let holders sell', async () => {
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    await ctx.setTradingPause({ tradingPause: TradingPause.Buys, curve })
+
+    expect(
+      ctx.swap({
+        trader,
+        baseMint: token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+      }),
+    ).rejects.toThrow()
+
+    const traderBalance = await ctx.getTokenBalance({ address: trader.address, mint: token })
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: traderBalance,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.BaseToQuote,
+    })
+    expect(await ctx.getTokenBalance({ address: trader.address, mint: token })).toBe(0n)
+
+    const curveState = await fetchBondingCurve(ctx.rpc, curve)
+    expect(curveState.data.tradingPause).toBe(TradingPause.Buys)
+  })
+
+  test('swap - trading end closes buys and leaves sells open', async () => {
+    const now = BigInt(Math.floor(Date.now() / 1000))
+    expect(ctx.setTradingEnd({ tradingEndTimestamp: now - 10n, curve })).rejects.toThrow()
+    expect(
+      ctx.createBondingCurveAndMintToken({
+        configAddress: ctx.currentConfig!,
+        creator: trader,
+        mintKeypair: await generateKeyPairSigner(),
+        tradingEndTimestamp: now - 10n,
+      }),
+    ).rejects.toThrow()
+
+    await ctx.setTradingEnd({ tradingEndTimestamp: now + 3n, curve })
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+
+    // wait for the trading end to pass
+    await new Promise((resolve) => setTimeout(resolve, 5_000))
+    expect(
+      ctx.swap({
+        trader,
+        baseMint: token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+      }),
+    ).rejects.toThrow()
+
+    // holders exit during the grace period
+    const traderBalance = await ctx.getTokenBalance({ address: trader.address, mint: token })
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: traderBalance,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.BaseToQuote,
+    })
+    expect(await ctx.getTokenBalance({ address: trader.address, mint: token })).toBe(0n)
+
+    const curveState = await fetchBondingCurve(ctx.rpc, curve)
+    expect(curveState.data.tradingEndTimestamp).toBe(now + 3n)
+  })
+
+  test('swap - holders are refunded pro rata once trading closes', async () => {
+    const now = BigInt(Math.floor(Date.now() / 1000))
+    await ctx.setTradingEnd({ tradingEndTimestamp: now + 3n, curve })
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+    })
+    const traderBalance = await ctx.getTokenBalance({ address: trader.address, mint: token })
+    const baseAmount = traderBalance / 2n
+
+    // wait for the trading end to pass, refunds wait for the sell grace period too
+    await new Promise((resolve) => setTimeout(resolve, 5_000))
+    expect(ctx.refund({ owner: trader, baseMint: token, baseAmount })).rejects.toThrow()
+
+    await ctx.setTestHooks({ faultPoint: FaultPoint.None, clockSkewSeconds: TRADING_END_SELL_GRACE_SECONDS })
+    try {
+      const { data: curveState } = await fetchBondingCurve(ctx.rpc, curve)
+      const soldBase = DEFAULT_CONFIG_ARGS.initialVirtualBaseReserve - curveState.virtualBaseReserve
+      const expectedQuoteAmount = (baseAmount * curveState.quoteReserve) / soldBase
+      const quoteBalance = await ctx.getTokenBalance({ address: trader.address, mint: WSOL_MINT })
+
+      await ctx.refund({ owner: trader, baseMint: token, baseAmount, withTestHooks: true })
+
+      const [finalCurveState, finalTraderBalance, finalQuoteBalance] = await Promise.all([
+        fetchBondingCurve(ctx.rpc, curve),
+        ctx.getTokenBalance({ address: trader.address, mint: token }),
+        ctx.getTokenBalance({ address: trader.address, mint: WSOL_MINT }),
+      ])
+      expect(finalTraderBalance).toBe(traderBalance - baseAmount)
+      expect(finalQuoteBalance - quoteBalance).toBe(expectedQuoteAmount)
+      expect(finalCurveState.data.quoteReserve).toBe(curveState.quoteReserve - expectedQuoteAmount)
+      expect(finalCurveState.data.baseReserve).toBe(curveState.baseReserve + baseAmount)
+    } finally {
+      await ctx.setTestHooks({ faultPoint: FaultPoint.None })
+    }
+  })
+
+  test('swap - scheduled launches reject trades before their activation', async () => {
+    const now = BigInt(Math.floor(Date.now() / 1000))
+    const createCurve = async (activation: { activationPoint: bigint; activationType?: number }) =>
+      ctx.createBondingCurveAndMintToken({
+        configAddress: ctx.currentConfig!,
+        creator: trader,
+        mintKeypair: await generateKeyPairSigner(),
+        activation,
+      })
+    expect(createCurve({ activationPoint: now - 10n })).rejects.toThrow()
+    // launches can't be scheduled beyond the maximum activation delay
+    expect(createCurve({ activationPoint: now + 30n * 24n * 3600n })).rejects.toThrow()
+    expect(createCurve({ activationPoint: now, activationType: 2 })).rejects.toThrow()
+
+    const mintKeypair = await generateKeyPairSigner()
+    const { curvePda } = await ctx.createBondingCurveAndMintToken({
+      configAddress: ctx.currentConfig!,
+      creator: trader,
+      mintKeypair,
+      activation: { activationPoint: now + 3n, activationType: ActivationType.Timestamp },
+    })
+    const buy = () =>
+      ctx.swap({
+        trader,
+        baseMint: mintKeypair.address,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+      })
+    expect(buy()).rejects.toThrow()
+
+    // wait for the activation
+    await new Promise((resolve) => setTimeout(resolve, 5_000))
+    await buy()
+    expect(await ctx.getTokenBalance({ address: trader.address, mint: mintKeypair.address })).toBeGreaterThan(0n)
+
+    const curveState = await fetchBondingCurve(ctx.rpc, curvePda)
+    expect(curveState.data.activationPoint).toBe(now + 3n)
+    expect(curveState.data.activationType).toBe(ActivationType.Timestamp)
+  })
+
+  test('swap - config pause halts every curve of the config', async () => {
+    const { configAddress } = await ctx.createConfig(DEFAULT_CONFIG_ARGS)
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    await ctx.setTradingPause({ tradingPause: TradingPause.All, configAddress })
+
+    expect(
+      ctx.swap({
+        trader,
+        baseMint: result.token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        configAddress,
+      }),
+    ).rejects.toThrow()
+
+    await ctx.setTradingPause({ tradingPause: TradingPause.None, configAddress })
+    await ctx.swap({
+      trader,
+      baseMint: result.token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      configAddress,
+    })
+    expect(await ctx.getTokenBalance({ address: trader.address, mint: result.token })).toBeGreaterThan(0n)
+  })
+
+  test('swap - emergency pause halts the swaps of a curve and the curve creation of a config', async () => {
+    const { configAddress } = await ctx.createConfig(DEFAULT_CONFIG_ARGS)
+    const result = await ctx.createFreshBondingCurve(configAddress)
+    const buy = () =>
+      ctx.swap({
+        trader,
+        baseMint: result.token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        configAddress,
+      })
+
+    await ctx.setPause({ paused: true, curve: result.curvePda, configAddress })
+    await expect(buy()).rejects.toThrow()
+    expect((await fetchBondingCurve(ctx.rpc, result.curvePda)).data.paused).toBe(1)
+    await ctx.setPause({ paused: false, curve: result.curvePda, configAddress })
+
+    await ctx.setPause({ paused: true, configAddress })
+    await expect(buy()).rejects.toThrow()
+    await expect(ctx.createFreshBondingCurve(configAddress)).rejects.toThrow()
+
+    await ctx.setPause({ paused: false, configAddress })
+    await buy()
+    expect(await ctx.getTokenBalance({ address: trader.address, mint: result.token })).toBeGreaterThan(0n)
+  })
+
+  test('swap - rejects an unknown trading pause', async () => {
+    expect(ctx.setTradingPause({ tradingPause: 4, curve })).rejects.toThrow()
+  })
+
+  test('swap - versioned params enforce the price impact guard', async () => {
+    // 1 SOL into 30 virtual SOL moves the spot price by ~6.8%
+    expect(
+      ctx.swap({
+        trader,
+        baseMint: token,
+        amountIn: buyAmount,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        maxPriceImpactBasisPoints: 5_000, // 5%
+      }),
+    ).rejects.toThrow()
+
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: buyAmount,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      maxPriceImpactBasisPoints: 10_000, // 10%
+    })
+    expect(await ctx.getTokenBalance({ address: trader.address, mint: token })).toBeGreaterThan(0n)
+  })
+
+  test('swap - exact out buys and sells', async () => {
+    const tokensOut = 1_000_000_000_000n // 1M tokens
+    // a maximum input below the required one is rejected
+    expect(
+      ctx.swap({
+        trader,
+        baseMint: token,
+        amountIn: 1_000n,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.QuoteToBase,
+        exactAmountOut: tokensOut,
+      }),
+    ).rejects.toThrow()
+
+    const curveQuoteBefore = await ctx.getVaultTokenBalance({ curvePda: curve, mint: WSOL_MINT })
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: BigInt(LAMPORTS_PER_SOL),
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.QuoteToBase,
+      exactAmountOut: tokensOut,
+    })
+    const [traderTokens, curveQuoteAfter] = await Promise.all([
+      ctx.getTokenBalance({ address: trader.address, mint: token }),
+      ctx.getVaultTokenBalance({ curvePda: curve, mint: WSOL_MINT }),
+    ])
+    expect(traderTokens).toBeGreaterThanOrEqual(tokensOut)
+    // only the required input is pulled, well below the maximum
+    expect(curveQuoteAfter - curveQuoteBefore).toBeLessThan(BigInt(LAMPORTS_PER_SOL))
+
+    // sell just enough tokens for 0.001 SOL after fees
+    const quoteOut = 1_000_000n
+    await ctx.swap({
+      trader,
+      baseMint: token,
+      amountIn: traderTokens,
+      minimumAmountOut: 0n,
+      tradeDirection: TradeDirection.BaseToQuote,
+      exactAmountOut: quoteOut,
+    })
+    const tokensLeft = await ctx.getTokenBalance({ address: trader.address, mint: token })
+    expect(tokensLeft).toBeGreaterThan(0n)
+    expect(tokensLeft).toBeLessThan(traderTokens)
+
+    // the output can't exceed what the curve holds
+    expect(
+      ctx.swap({
+        trader,
+        baseMint: token,
+        amountIn: tokensLeft,
+        minimumAmountOut: 0n,
+        tradeDirection: TradeDirection.BaseToQuote,
+        exactAmountOut: curveQuoteAfter * 2n,
+      }),
+    ).rejects.toThrow()
+  })
 })
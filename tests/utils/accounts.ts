@@ -73,14 +73,22 @@ export function getSecondKey(key1: Address, key2: Address) {
   return buf1
 }
 
-export async function getAmmConfigPda(programId: Address, index: number) {
-  // Convert index to big-endian bytes (2 bytes for u16)
+export async function getAmmConfigPda({
+  index,
+  quoteMint,
+  programId,
+}: {
+  index: number
+  quoteMint: Address
+  programId: Address
+}) {
+  // Convert index to little-endian bytes (2 bytes for u16)
   const indexBuffer = Buffer.allocUnsafe(2)
-  indexBuffer.writeUInt16BE(index, 0)
+  indexBuffer.writeUInt16LE(index, 0)
 
   return await getProgramDerivedAddress({
     programAddress: programId,
-    seeds: [Buffer.from(SEEDS.CONFIG_PREFIX), indexBuffer],
+    seeds: [Buffer.from(SEEDS.CONFIG_PREFIX), indexBuffer, addressEncoder.encode(quoteMint)],
   })
 }
 
@@ -136,6 +144,13 @@ export async function getCurveVaultPda({
   })
 }
 
+export async function getCurveMetadataPda({ curvePda, programId }: { curvePda: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.CURVE_METADATA_PREFIX), addressEncoder.encode(curvePda)],
+  })
+}
+
 export async function getMetadataPda({ mint }: { mint: Address }) {
   return getProgramDerivedAddress({
     programAddress: TOKEN_METADATA_PROGRAM_ADDRESS,
@@ -251,6 +266,131 @@ export function deriveDammV2TokenVaultAddress({
   })
 }
 
+// Helper to derive the pending config change PDA of a config
+export async function getConfigChangePda({ configAddress, programId }: { configAddress: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.CONFIG_CHANGE_PREFIX), addressEncoder.encode(configAddress)],
+  })
+}
+
+// Helper to derive the insurance fund PDA of a config
+export async function getInsuranceFundPda({ configAddress, programId }: { configAddress: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.INSURANCE_FUND_PREFIX), addressEncoder.encode(configAddress)],
+  })
+}
+
+export async function getDualListingPda({ baseMint, programId }: { baseMint: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.DUAL_LISTING_PREFIX), addressEncoder.encode(baseMint)],
+  })
+}
+
+export async function getMintIndexPda({ baseMint, programId }: { baseMint: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.MINT_INDEX_PREFIX), addressEncoder.encode(baseMint)],
+  })
+}
+
+export async function getWhitelistEntryPda({
+  configAddress,
+  creator,
+  programId,
+}: {
+  configAddress: Address
+  creator: Address
+  programId: Address
+}) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [
+      Buffer.from(SEEDS.WHITELIST_ENTRY_PREFIX),
+      addressEncoder.encode(configAddress),
+      addressEncoder.encode(creator),
+    ],
+  })
+}
+
+export async function getMigrationDelegatePda({ curve, programId }: { curve: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.MIGRATION_DELEGATE_PREFIX), addressEncoder.encode(curve)],
+  })
+}
+
+export async function getMigrationProgressPda({ curve, programId }: { curve: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.MIGRATION_PROGRESS_PREFIX), addressEncoder.encode(curve)],
+  })
+}
+
+export async function getSupportFundPda({ curve, programId }: { curve: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.SUPPORT_FUND_PREFIX), addressEncoder.encode(curve)],
+  })
+}
+
+export async function getConfigLabelPda({
+  configAddress,
+  programId,
+}: {
+  configAddress: Address
+  programId: Address
+}) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.CONFIG_LABEL_PREFIX), addressEncoder.encode(configAddress)],
+  })
+}
+
+export async function getProtocolAuthorityPda({ programId }: { programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.PROTOCOL_AUTHORITY_PREFIX)],
+  })
+}
+
+export async function getProtocolStatsPda({ programId }: { programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.PROTOCOL_STATS_PREFIX)],
+  })
+}
+
+export async function getCashbackConfigPda({ programId }: { programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.CASHBACK_CONFIG_PREFIX)],
+  })
+}
+
+export async function getConfigStatsPda({ configAddress, programId }: { configAddress: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.CONFIG_STATS_PREFIX), addressEncoder.encode(configAddress)],
+  })
+}
+
+export async function getTestHooksPda({ programId }: { programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.TEST_HOOKS_PREFIX)],
+  })
+}
+
+export async function getReferralPda({ userAddress, programId }: { userAddress: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.REFERRAL_PREFIX), addressEncoder.encode(userAddress)],
+  })
+}
+
 // Helper to derive user cashback account PDA
 export async function getUserCashbackAccountPda({
   userAddress,
@@ -262,6 +402,132 @@ export async function getUserCashbackAccountPda({
   })
 }
 
+// Helper to derive user protection account PDA of a curve
+export async function getProtectionPda({
+  curvePda,
+  userAddress,
+  programId,
+}: { curvePda: Address; userAddress: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.PROTECTION_PREFIX), addressEncoder.encode(curvePda), addressEncoder.encode(userAddress)],
+  })
+}
+
+export async function getLimitOrderPda({
+  curvePda,
+  owner,
+  orderId,
+  programId,
+}: {
+  curvePda: Address
+  owner: Address
+  orderId: bigint
+  programId: Address
+}) {
+  const orderIdBuffer = Buffer.allocUnsafe(8)
+  orderIdBuffer.writeBigUInt64LE(orderId, 0)
+
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [
+      Buffer.from(SEEDS.LIMIT_ORDER_PREFIX),
+      addressEncoder.encode(curvePda),
+      addressEncoder.encode(owner),
+      orderIdBuffer,
+    ],
+  })
+}
+
+// Helper to derive the bonus pool PDA of a curve
+export async function getBonusPoolPda({ curvePda, programId }: { curvePda: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.BONUS_POOL_PREFIX), addressEncoder.encode(curvePda)],
+  })
+}
+
+export async function getCurveArchivePda({ curvePda, programId }: { curvePda: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.CURVE_ARCHIVE_PREFIX), addressEncoder.encode(curvePda)],
+  })
+}
+
+export async function getRafflePda({ curvePda, programId }: { curvePda: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.RAFFLE_PREFIX), addressEncoder.encode(curvePda)],
+  })
+}
+
+export async function getRaffleTicketPda({
+  rafflePda,
+  ownerAddress,
+  programId,
+}: {
+  rafflePda: Address
+  ownerAddress: Address
+  programId: Address
+}) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [
+      Buffer.from(SEEDS.RAFFLE_TICKET_PREFIX),
+      addressEncoder.encode(rafflePda),
+      addressEncoder.encode(ownerAddress),
+    ],
+  })
+}
+
+export async function getPartnerPda({ partner, programId }: { partner: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.PARTNER_PREFIX), addressEncoder.encode(partner)],
+  })
+}
+
+export async function getPartnerEarningsPda({
+  partner,
+  quoteMint,
+  programId,
+}: {
+  partner: Address
+  quoteMint: Address
+  programId: Address
+}) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [
+      Buffer.from(SEEDS.PARTNER_EARNINGS_PREFIX),
+      addressEncoder.encode(partner),
+      addressEncoder.encode(quoteMint),
+    ],
+  })
+}
+
+export async function getRewardVaultPda({ curvePda, programId }: { curvePda: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [Buffer.from(SEEDS.REWARD_VAULT_PREFIX), addressEncoder.encode(curvePda)],
+  })
+}
+
+export async function getRewardClaimPda({
+  rewardVault,
+  userAddress,
+  programId,
+}: { rewardVault: Address; userAddress: Address; programId: Address }) {
+  return getProgramDerivedAddress({
+    programAddress: programId,
+    seeds: [
+      Buffer.from(SEEDS.REWARD_CLAIM_PREFIX),
+      addressEncoder.encode(rewardVault),
+      addressEncoder.encode(userAddress),
+    ],
+  })
+}
+
 export async function prepareTokenAccounts({
   rpc,
   owner,
@@ -290,11 +556,112 @@ export async function prepareTokenAccounts({
   return { ataTokenA, ataTokenB, instructions }
 }
 
+export enum AuthorityType {
+  Global = 0,
+  Migration = 1,
+  FeeType = 2,
+}
+
 export enum TokenType {
   SPL = 0,
   Token2022 = 1,
 }
 
+export enum MigrationStep {
+  NotStarted = 0,
+  PoolCreated = 1,
+  LiquidityLocked = 2,
+  Finalized = 3,
+}
+
+export enum MigrationStage {
+  ValidateDammConfig = 0,
+  CheckMigrationStatus = 1,
+  CheckCurveComplete = 2,
+  CheckCooldown = 3,
+  ComputeLiquidity = 4,
+  CreatePool = 5,
+  LockLiquidity = 6,
+  TransferPosition = 7,
+  CreateCreatorPosition = 8,
+}
+
+export enum FaultPoint {
+  None = 0,
+  AfterPoolCreation = 1,
+  BeforeLiquidityLock = 2,
+}
+
+export enum CashbackMode {
+  Token = 0,
+  Native = 1,
+}
+
+export enum MigrationTokenOrder {
+  BaseQuote = 0,
+  Sorted = 1,
+}
+
+export enum ThresholdDecayMode {
+  None = 0,
+  Linear = 1,
+  Stepwise = 2,
+}
+
+export enum CreatorFeeTransferMode {
+  MoveWithRole = 0,
+  ClaimToOldCreator = 1,
+}
+
+export enum ActivationType {
+  Timestamp = 0,
+  Slot = 1,
+}
+
+export enum FeeType {
+  Creator = 0,
+  Meme = 1,
+}
+
+export enum SwapMode {
+  ExactIn = 0,
+  ExactOut = 1,
+}
+
+export enum RoutedSwapFeeMode {
+  Charged = 0,
+  Waived = 1,
+}
+
+export enum TradingPause {
+  None = 0,
+  Buys = 1,
+  Sells = 2,
+  All = 3,
+}
+
+/**
+ * Whether the base mint is token a of the DAMM v2 pool created at migration
+ * @param baseMint - The base mint
+ * @param quoteMint - The quote mint
+ * @param migrationTokenOrder - The token order of the config
+ * @returns true if the base mint is token a
+ */
+export function isBaseTokenA({
+  baseMint,
+  quoteMint,
+  migrationTokenOrder,
+}: {
+  baseMint: Address
+  quoteMint: Address
+  migrationTokenOrder: MigrationTokenOrder
+}) {
+  if (migrationTokenOrder === MigrationTokenOrder.BaseQuote) {
+    return true
+  }
+  return Buffer.compare(Buffer.from(addressEncoder.encode(baseMint)), Buffer.from(addressEncoder.encode(quoteMint))) < 0
+}
+
 export interface PrepareSwapParams {
   inputMint: Address
   outputMint: Address
@@ -3,6 +3,7 @@ import { LAMPORTS_PER_SOL, address } from 'gill'
 // Bonding curve constants
 export const TOKEN_TOTAL_SUPPLY = BigInt(1_000_000_000_000_000)
 export const TOKEN_DECIMALS = 6
+export const getTokenTotalSupply = (decimals: number) => 1_000_000_000n * 10n ** BigInt(decimals)
 
 // Fee constants
 export const FEE_BASIS_POINTS = 1_500 // 1.3% fee
@@ -13,6 +14,15 @@ export const L2_REFERRAL_FEE_BASIS_POINTS = 30
 export const L3_REFERRAL_FEE_BASIS_POINTS = 20
 export const CREATOR_FEE_BASIS_POINTS = 500 // 0.5% of sol amount
 export const MIGRATION_FEE_BASIS_POINTS = 5_000 // 5% of quote amount
+export const INSURANCE_FUND_BASIS_POINTS = 0 // share of protocol fee, disabled by default
+export const CREATOR_FEE_PROTOCOL_BASIS_POINTS = 0 // protocol cut of creator claims, disabled by default
+
+export const NO_LOCKED_VESTING = {
+  cliffDurationSeconds: 0n,
+  periodSeconds: 0n,
+  amountPerPeriod: 0n,
+  numberOfPeriods: 0n,
+}
 
 export const DEFAULT_CONFIG_ARGS = {
   baseTokenFlag: 0, // Token 2022
@@ -24,14 +34,56 @@ export const DEFAULT_CONFIG_ARGS = {
   refereeDiscountBasisPoints: REFEREE_DISCOUNT_BASIS_POINTS,
   creatorFeeBasisPoints: CREATOR_FEE_BASIS_POINTS,
   migrationFeeBasisPoints: MIGRATION_FEE_BASIS_POINTS,
+  insuranceFundBasisPoints: INSURANCE_FUND_BASIS_POINTS,
+  creatorFeeProtocolBasisPoints: CREATOR_FEE_PROTOCOL_BASIS_POINTS,
+  supportFundBasisPoints: 0,
+  maxCreatorLpFeeShareBasisPoints: 20_000, // creators can take up to 20% of the DAMM position fees
+  buyCashbackMultiplierBasisPoints: 100_000, // the full tier cashback on buys
+  sellCashbackMultiplierBasisPoints: 100_000, // and on sells
+  feeCollectionMode: 0, // buys pay the fee on the quote input, sells on the quote output
   /* price configs */
   migrationBaseThreshold: 200_000_000_000_000n, // 20% of the supply
   migrationQuoteThreshold: 115_005_359_056n, // virtual quote reserves
   initialVirtualQuoteReserve: 30n * BigInt(LAMPORTS_PER_SOL),
   initialVirtualBaseReserve: 1_073_000_000_000_000n,
+  /* migration configs */
+  preMigrationCooldownSeconds: 0n,
+  permissionlessMigration: 0, // admin or delegated operator only
+  creatorLpBasisPoints: 0, // all the migrated liquidity is locked in the first position
+  migrationFeeMode: 0, // the migration fee is kept from both reserves
+  feeRemainderMode: 0, // the protocol fee gets the rounding remainder
+  migrationCrankReward: 0n,
+  minCreatorFeeAmount: 0n, // no creator fee guarantee
+  migrationTokenOrder: 0, // base/quote
+  routedSwapFeeMode: 0, // routed buys pay the trading fee
+  thresholdDecayMode: 0, // no decay
+  thresholdDecayFloorBasisPoints: 0,
+  thresholdDecaySeconds: 0n,
+  /* bonus configs */
+  earlyBuyerWindowSeconds: 600n, // 10 minutes
+  /* protection configs */
+  minHoldSeconds: 0n,
+  antiSnipeWindowSlots: 0n, // no anti-snipe buy cap
+  maxBuyAmountFirstWindow: 0n,
+  launchFeeBasisPoints: 0, // no launch fee
+  launchFeePeriods: 0,
+  launchFeeDecaySeconds: 0n,
+  /* governance configs */
+  paramChangeDelaySeconds: 0n,
+  /* vesting configs */
+  lockedVesting: NO_LOCKED_VESTING,
 }
 export const WSOL_MINT = address('So11111111111111111111111111111111111111112')
 
+// Layout version of config accounts
+export const CONFIG_VERSION = 1
+
+// Layout version of bonding curve accounts
+export const CURVE_VERSION = 8
+
+// Sells stay open for this long after the trading end of a curve, refunds open after it
+export const TRADING_END_SELL_GRACE_SECONDS = 259_200n // 3 days
+
 // Validation constants
 export const VALIDATION = {
   MIN_SUPPLY: 1_000_000, // 1M minimum
@@ -78,6 +130,17 @@ export const CASHBACK_BPS = {
   6: CASHBACK_CHAMPION_BPS, // Champion
 }
 
+// Claim cooldown of each cashback tier, Wood to Champion
+const DAY_SECONDS = 24n * 60n * 60n
+export const DEFAULT_CASHBACK_CLAIM_COOLDOWNS = [7n, 7n, 7n, 7n, 7n, 7n, 1n].map((days) => days * DAY_SECONDS)
+// Rolling 30 day quote volume each cashback tier needs, Wood to Champion
+export const DEFAULT_CASHBACK_TIER_VOLUME_THRESHOLDS = [0n, 1n, 10n, 50n, 100n, 500n, 1000n].map(
+  (sol) => sol * BigInt(LAMPORTS_PER_SOL),
+)
+
+// Trading fee discount of each cashback tier, Wood to Champion, none by default
+export const DEFAULT_CASHBACK_FEE_DISCOUNTS = [0, 0, 0, 0, 0, 0, 0]
+
 export const SEEDS = {
   CONFIG_PREFIX: 'config',
   CURVE_PREFIX: 'curve',
@@ -85,6 +148,32 @@ export const SEEDS = {
   TOKEN_VAULT: 'token_vault',
   CASHBACK_PREFIX: 'cashback',
   CASHBACK_VAULT_PREFIX: 'cashback_vault',
+  PROTECTION_PREFIX: 'protection',
+  BONUS_POOL_PREFIX: 'bonus_pool',
+  CONFIG_CHANGE_PREFIX: 'config_change',
+  INSURANCE_FUND_PREFIX: 'insurance_fund',
+  DUAL_LISTING_PREFIX: 'dual_listing',
+  MIGRATION_DELEGATE_PREFIX: 'migration_delegate',
+  MIGRATION_PROGRESS_PREFIX: 'migration_progress',
+  REFERRAL_PREFIX: 'referral',
+  REWARD_VAULT_PREFIX: 'reward_vault',
+  REWARD_CLAIM_PREFIX: 'reward_claim',
+  SUPPORT_FUND_PREFIX: 'support_fund',
+  CONFIG_LABEL_PREFIX: 'config_label',
+  LIMIT_ORDER_PREFIX: 'limit_order',
+  PROTOCOL_STATS_PREFIX: 'protocol_stats',
+  CONFIG_STATS_PREFIX: 'config_stats',
+  TEST_HOOKS_PREFIX: 'test_hooks',
+  CURVE_METADATA_PREFIX: 'curve_metadata',
+  CASHBACK_CONFIG_PREFIX: 'cashback_config',
+  MINT_INDEX_PREFIX: 'mint_index',
+  WHITELIST_ENTRY_PREFIX: 'whitelist_entry',
+  CURVE_ARCHIVE_PREFIX: 'curve_archive',
+  RAFFLE_PREFIX: 'raffle',
+  RAFFLE_TICKET_PREFIX: 'raffle_ticket',
+  PARTNER_PREFIX: 'partner',
+  PARTNER_EARNINGS_PREFIX: 'partner_earnings',
+  PROTOCOL_AUTHORITY_PREFIX: 'protocol_authority',
   POOL_AUTHORITY: 'pool_authority',
   EVENT_AUTHORITY: '__event_authority',
   DAMM_V2_MIGRATION_METADATA: 'damm_v2',
@@ -95,4 +184,5 @@ export const SEEDS = {
 export const DAMM_V2_PROGRAM_ID = address('cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG')
 export const DYNAMIC_BONDING_CURVE_PROGRAM_ID = address('dbcij3LWUppWqq96dh6gJWwBifmcGfLSB5D4DuSMaqN')
 export const DAMM_CONFIG_ACCOUNT = address('AeLtDKgw3XnXbr3Kgfbcb7KiZULVCQ5mXaFDiG9n7EgW')
+export const MOCK_STRATEGY_PROGRAM_ID = address('6RiHGK1FHsVN5bfNW2kDeFY1gycqDuQo219kbYwn6g4q')
 export const METAPLEX_PROGRAM_ID = address('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s')
@@ -8,6 +8,8 @@ import {
   appendTransactionMessageInstructions,
   createTransactionMessage,
   generateKeyPairSigner,
+  getBase64EncodedWireTransaction,
+  getBase64Encoder,
   lamports,
   setTransactionMessageFeePayerSigner,
   setTransactionMessageLifetimeUsingBlockhash,
@@ -15,8 +17,14 @@ import {
 } from '@solana/kit'
 import { LAMPORTS_PER_SOL, type SolanaClient, createSolanaClient, createTransaction, getExplorerLink } from 'gill'
 import { loadKeypairSignerFromFile } from 'gill/node'
-import { TOKEN_2022_PROGRAM_ADDRESS, getSetComputeUnitLimitInstruction, getTransferSolInstruction } from 'gill/programs'
 import {
+  SYSTEM_PROGRAM_ADDRESS,
+  TOKEN_2022_PROGRAM_ADDRESS,
+  getSetComputeUnitLimitInstruction,
+  getTransferSolInstruction,
+} from 'gill/programs'
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ADDRESS,
   TOKEN_PROGRAM_ADDRESS,
   getAssociatedTokenAccountAddress,
   getCloseAccountInstruction,
@@ -24,20 +32,111 @@ import {
 } from 'gill/programs/token'
 import {
   AMM_PROGRAM_ADDRESS,
+  type ConfigChangeParametersArgs,
   type CreateConfigInstructionDataArgs,
   fetchBondingCurve,
+  fetchBonusPool,
   fetchCashbackAccount,
   fetchConfig,
+  fetchConfigLabel,
+  fetchConfigStats,
+  fetchDualListing,
+  fetchInsuranceFund,
+  fetchLimitOrder,
+  fetchMaybeInsuranceFund,
+  fetchMigrationProgress,
+  fetchPartnerEarnings,
+  fetchProtection,
+  fetchProtocolAuthority,
+  fetchProtocolStats,
+  fetchRaffle,
+  fetchRewardVault,
   getBondingCurveSize,
   getCashbackAccountSize,
+  getAcceptAuthorityInstructionAsync,
+  getAddPartnerInstructionAsync,
+  getAddWhitelistedCreatorInstructionAsync,
+  getArchiveCurveInstructionAsync,
+  getBurnCurveBaseTokensInstructionAsync,
+  getBuyRaffleTicketsInstructionAsync,
+  getClaimBonusInstructionAsync,
   getClaimCashbackInstructionAsync,
   getClaimCreatorFeeInstructionAsync,
+  getClaimMemeFeeInstructionAsync,
+  getClaimMigrationFeeInstructionAsync,
+  getClaimPartnerEarningsInstructionAsync,
+  getClaimPositionFeeInstructionAsync,
+  getClaimProtocolFeeBatchInstructionAsync,
   getClaimProtocolFeeInstructionAsync,
+  getClaimRaffleInstructionAsync,
+  getCloseCashbackInstructionAsync,
+  getClaimRewardInstructionAsync,
   getCreateCashbackInstructionAsync,
+  getCreateCashbackVaultInstructionAsync,
+  getCancelConfigChangeInstructionAsync,
+  getCancelLimitOrderInstructionAsync,
   getCreateConfigInstructionAsync,
+  getCreateDualListingInstructionAsync,
+  getCreateInsuranceFundInstructionAsync,
+  getCreatePartnerEarningsInstructionAsync,
+  getDecayCashbackTierInstructionAsync,
+  getDelegateMigrationAuthorityInstructionAsync,
+  getExecuteConfigChangeInstructionAsync,
+  getExecuteInsurancePayoutInstructionAsync,
+  getFeeScheduleDecoder,
+  getFillLimitOrderInstructionAsync,
+  getFinalizeCurveMetadataInstructionAsync,
+  getFundRewardVaultInstructionAsync,
+  getInitializeAuthorityInstructionAsync,
+  getMigrateConfigInstructionAsync,
+  getReallocCurveInstructionAsync,
+  getRefundInstructionAsync,
+  getGetFeeScheduleInstruction,
+  getInitCurveInstructionAsync,
+  getPlaceLimitOrderInstructionAsync,
+  getProposeConfigChangeInstructionAsync,
+  getProposeInsurancePayoutInstructionAsync,
+  getRemovePartnerInstructionAsync,
+  getRemoveWhitelistedCreatorInstructionAsync,
+  getRevokeMigrationAuthorityInstructionAsync,
+  getRotateFeeClaimerInstructionAsync,
+  getSetConfigLabelInstructionAsync,
+  getRefreshCashbackTierInstructionAsync,
+  getSetCashbackConfigInstructionAsync,
+  getSetCashbackModeInstructionAsync,
+  getSetCreatorFeeRecipientInstructionAsync,
+  getSetCpiTelemetryInstructionAsync,
+  getSetCreatorWhitelistModeInstructionAsync,
+  getSetCurveTemplateInstructionAsync,
+  getSetDammConfigInstructionAsync,
+  getSetFeeTypeInstructionAsync,
+  getSetReferrerInstructionAsync,
+  getSetRewardDistributionInstructionAsync,
+  getSetPauseInstructionAsync,
+  getSetTestHooksInstructionAsync,
+  getSetTradingEndInstructionAsync,
+  getSetTradingPauseInstructionAsync,
+  getSimulateSwapInstruction,
+  getVerifyCurveInvariantsInstructionAsync,
   getCreateCurveWithSplTokenInstructionAsync,
+  getCreateCurveWithToken2022InstructionAsync,
+  getCreateProtectionInstructionAsync,
+  getCreateRaffleInstructionAsync,
+  getDepositBonusPoolInstructionAsync,
+  getAdvanceMigrationStatusInstructionAsync,
+  getMigrateDammV2CreatePoolInstructionAsync,
+  getMigrateDammV2FinalizeInstructionAsync,
   getMigrateDammV2InstructionAsync,
+  getMigrateDammV2LockLiquidityInstructionAsync,
+  getWithdrawLeftoverInstructionAsync,
+  getDeploySupportFundInstructionAsync,
+  getDrawRaffleInstructionAsync,
   getSwapInstructionAsync,
+  getSwapSimulationDecoder,
+  getSwapTokenToTokenInstructionAsync,
+  getSwapVersionedInstructionAsync,
+  getTransferAuthorityInstructionAsync,
+  getTransferCreatorInstructionAsync,
   getUpdateCashbackTierInstructionAsync,
 } from '~/clients'
 import { fetchPool, getSwapInstructionAsync as getDammSwapInstructionAsync } from '../../clients/damm/src/generated'
@@ -49,24 +148,57 @@ import {
   deriveDammV2TokenVaultAddress,
   derivePositionAddress,
   derivePositionNftAccount,
+  getAmmConfigPda,
+  getBonusPoolPda,
+  getCurveArchivePda,
   getCashbackAccounts,
+  getCashbackConfigPda,
+  getConfigChangePda,
+  getConfigLabelPda,
+  getDualListingPda,
+  getInsuranceFundPda,
+  getLimitOrderPda,
   getCurveAuthority,
+  getCurveMetadataPda,
   getCurvePda,
   getCurveVaultPda,
   getMetadataPda,
+  getMigrationDelegatePda,
+  getMigrationProgressPda,
+  getOrCreateATAInstruction,
+  getPartnerEarningsPda,
+  getPartnerPda,
+  getProtectionPda,
+  getProtocolAuthorityPda,
+  getConfigStatsPda,
+  getProtocolStatsPda,
+  getRafflePda,
+  getRaffleTicketPda,
+  getTestHooksPda,
+  getReferralPda,
+  getRewardClaimPda,
+  getRewardVaultPda,
+  getSupportFundPda,
   getUserCashbackAccountPda,
+  getWhitelistEntryPda,
+  isBaseTokenA,
   prepareSwapParams,
   prepareTokenAccounts,
+  AuthorityType,
+  SwapMode,
 } from './accounts.ts'
 import {
   DAMM_CONFIG_ACCOUNT,
   DAMM_V2_PROGRAM_ID,
+  DEFAULT_CASHBACK_CLAIM_COOLDOWNS,
+  DEFAULT_CASHBACK_FEE_DISCOUNTS,
+  DEFAULT_CASHBACK_TIER_VOLUME_THRESHOLDS,
   DEFAULT_CONFIG_ARGS,
   DEFAULT_TOKEN,
   TRADER_INITIAL_SOL_AMOUNT,
   WSOL_MINT,
 } from './constants.ts'
-import type { TradeDirection } from './swap-quote.ts'
+import { TradeDirection } from './swap-quote.ts'
 
 const network = 'localnet' as const
 
@@ -134,7 +266,9 @@ export class TestContextClass {
   ======================================
 `)
 
-    return new TestContextClass(programId, owner, rpc, rpcSubscriptions, sendAndConfirmTransaction, network)
+    const ctx = new TestContextClass(programId, owner, rpc, rpcSubscriptions, sendAndConfirmTransaction, network)
+    await ctx.initializeAuthorityOnce()
+    return ctx
   }
 
   /******************************* Basic Solana Helper functions *******************************/
@@ -189,13 +323,101 @@ export class TestContextClass {
   }
 
   /******************************* Admin functions *******************************/
+  /** creates the protocol authority on a fresh validator, the owner holds every role */
+  async initializeAuthorityOnce() {
+    const [protocolAuthority] = await getProtocolAuthorityPda({ programId: this.programId })
+    const [{ value: account }, { value: latestBlockhash }] = await Promise.all([
+      this.rpc.getAccountInfo(protocolAuthority, { encoding: 'base64' }).send(),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    if (account) {
+      return
+    }
+
+    const ix = await getInitializeAuthorityInstructionAsync({
+      protocolAuthority,
+      payer: this.owner,
+      globalAuthority: this.owner.address,
+      migrationAuthority: this.owner.address,
+      feeTypeAuthority: this.owner.address,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async transferAuthority({
+    authorityType,
+    newAuthority,
+    authority = this.owner,
+  }: {
+    authorityType: AuthorityType
+    newAuthority: Address
+    authority?: KeyPairSigner
+  }) {
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const ix = await getTransferAuthorityInstructionAsync({
+      authority,
+      authorityType,
+      newAuthority,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(authority, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async acceptAuthority({
+    authorityType,
+    newAuthority,
+  }: {
+    authorityType: AuthorityType
+    newAuthority: KeyPairSigner
+  }) {
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const ix = await getAcceptAuthorityInstructionAsync({
+      newAuthority,
+      authorityType,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(newAuthority, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async getProtocolAuthority() {
+    const [protocolAuthority] = await getProtocolAuthorityPda({ programId: this.programId })
+    return (await fetchProtocolAuthority(this.rpc, protocolAuthority)).data
+  }
+
   async getConfigData({ configAddress }: { configAddress?: Address }) {
     const config = configAddress ? address(configAddress) : this.currentConfig
     return await fetchConfig(this.rpc, config)
   }
 
   async createConfigOnce(
-    args: CreateConfigInstructionDataArgs,
+    args: Omit<CreateConfigInstructionDataArgs, 'index'>,
     quoteMint: Address = WSOL_MINT,
     signer?: KeyPairSigner,
   ) {
@@ -205,15 +427,22 @@ export class TestContextClass {
     }
 
     const { configAddress, feeClaimer } = await this.createConfig(args, quoteMint, signer)
+    await this.setCashbackConfig({})
     this.currentConfig = configAddress
     this.currentFeeClaimer = feeClaimer
     return { configAddress, feeClaimer }
   }
 
-  async createConfig(args: CreateConfigInstructionDataArgs, quoteMint: Address = WSOL_MINT, signer?: KeyPairSigner) {
+  async createConfig(
+    args: Omit<CreateConfigInstructionDataArgs, 'index'>,
+    quoteMint: Address = WSOL_MINT,
+    signer?: KeyPairSigner,
+    createFeeClaimerTokenAccount = true,
+    leftoverReceiver?: Address,
+  ) {
     const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
     const feePayer = signer || this.owner
-    const configKeyPair = await generateKeyPairSigner()
+    const { index, configAddress } = await this.getFreeConfigIndex(quoteMint)
     const feeClaimer = await this.createTestTrader()
     const feeClaimerTokenAccount = await getAssociatedTokenAccountAddress(
       quoteMint,
@@ -223,9 +452,12 @@ export class TestContextClass {
 
     const ix = await getCreateConfigInstructionAsync({
       /* Protocol configurations */
-      config: configKeyPair,
+      config: configAddress,
+      index,
       feeClaimer: feeClaimer.address,
-      feeClaimerTokenAccount,
+      feeClaimerTokenAccount: createFeeClaimerTokenAccount ? feeClaimerTokenAccount : undefined,
+      dammConfig: DAMM_CONFIG_ACCOUNT,
+      leftoverReceiver,
       quoteMint,
       payer: feePayer,
       program: this.programId,
@@ -253,624 +485,3290 @@ export class TestContextClass {
       )
     }
 
-    return { configAddress: configKeyPair.address, feeClaimer: feeClaimer }
+    return { configAddress, feeClaimer: feeClaimer }
   }
 
-  /******************************* Curve functions *******************************/
-  async createFreshBondingCurve(configAddress?: Address, creator?: KeyPairSigner) {
-    const config = configAddress
-      ? configAddress
-      : this.currentConfig || (await this.createConfig(DEFAULT_CONFIG_ARGS)).configAddress
-    const mintKeypair = await generateKeyPairSigner()
-    const creatorSigner = creator ?? (await this.createTestTrader(BigInt(0.1 * LAMPORTS_PER_SOL)))
-    const bondingCurve = await this.createBondingCurveAndMintToken({
-      configAddress: config,
-      creator: creatorSigner,
-      mintKeypair,
+  /**
+   * Finds an unused config index of the quote mint, probing from a random index so test files
+   * sharing a validator rarely probe the same indexes
+   */
+  async getFreeConfigIndex(quoteMint: Address) {
+    const start = Math.floor(Math.random() * 0x10000)
+    for (let offset = 0; offset < 0x10000; offset++) {
+      const index = (start + offset) % 0x10000
+      const [configAddress] = await getAmmConfigPda({ index, quoteMint, programId: this.programId })
+      const { value } = await this.rpc.getAccountInfo(configAddress, { encoding: 'base64' }).send()
+      if (!value) {
+        return { index, configAddress }
+      }
+    }
+    throw new Error(`no free config index for ${quoteMint}`)
+  }
+
+  async migrateConfig({ configAddress, admin = this.owner }: { configAddress: Address; admin?: KeyPairSigner }) {
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+    const ix = await getMigrateConfigInstructionAsync({
+      config: configAddress,
+      admin,
+      program: this.programId,
     })
 
-    return {
-      token: mintKeypair.address,
-      curvePda: bondingCurve.curvePda,
-    }
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(admin, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
   }
 
-  async createBondingCurveAndMintToken({
+  async reallocCurve({
+    curve,
     configAddress,
-    creator,
-    mintKeypair,
-    quoteMintAddress = WSOL_MINT,
-    tokenMetadata,
+    sponsor = this.owner,
   }: {
+    curve: Address
     configAddress: Address
-    creator: KeyPairSigner
-    mintKeypair: KeyPairSigner
-    quoteMintAddress?: Address
-    tokenMetadata?: {
-      name: string
-      symbol: string
-      uri: string
-    }
+    sponsor?: KeyPairSigner
   }) {
-    const [curvePda] = await getCurvePda({
-      configAddress,
-      baseMint: mintKeypair.address,
-      quoteMint: quoteMintAddress,
-      programId: this.programId,
-    })
-    const [metadataPda] = await getMetadataPda({ mint: mintKeypair.address })
-
-    const createIx = await getCreateCurveWithSplTokenInstructionAsync({
-      creator,
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+    const ix = await getReallocCurveInstructionAsync({
       config: configAddress,
-      baseMint: mintKeypair,
-      quoteMint: quoteMintAddress,
-      curve: curvePda,
-      metadata: metadataPda,
-      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      curve,
+      sponsor,
       program: this.programId,
-      name: tokenMetadata?.name ?? DEFAULT_TOKEN.name,
-      symbol: tokenMetadata?.symbol ?? DEFAULT_TOKEN.symbol,
-      uri: tokenMetadata?.uri ?? DEFAULT_TOKEN.uri,
-    })
-
-    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
-
-    const tx = createTransaction({
-      version: 'legacy',
-      feePayer: creator,
-      instructions: [createIx],
-      latestBlockhash,
     })
 
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(sponsor, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
     const signedTx = await signTransactionMessageWithSigners(tx)
-
-    const signature = await this.sendAndConfirmTransaction(signedTx)
-
-    if (this.network === 'devnet') {
-      console.log(
-        'Explorer (create-bonding-curve):',
-        getExplorerLink({
-          cluster: 'devnet',
-          transaction: signature,
-        }),
-      )
-    }
-
-    return {
-      mint: mintKeypair.address,
-      curvePda,
-    }
-  }
-
-  async getBondingCurveRentExempt() {
-    const rentExempt = await this.rpc.getMinimumBalanceForRentExemption(BigInt(getBondingCurveSize())).send()
-    return BigInt(rentExempt)
+    await this.sendAndConfirmTransaction(signedTx)
   }
 
-  async getBondingCurveData({
-    baseMint,
-    quoteMint = WSOL_MINT,
+  async proposeConfigChange({
+    params,
     configAddress,
+    signer,
   }: {
-    baseMint: Address
-    quoteMint?: Address
+    params: ConfigChangeParametersArgs
     configAddress?: Address
+    signer?: KeyPairSigner
   }) {
     const config = configAddress ?? this.currentConfig!
-    if (!config) {
-      throw new Error('AMM config not initialized')
-    }
-
-    const [bondingCurvePda] = await getCurvePda({
-      configAddress: config,
-      baseMint,
-      quoteMint,
-      programId: this.programId,
-    })
-
-    return await fetchBondingCurve(this.rpc, bondingCurvePda)
-  }
-
-  async getVaultTokenBalance({
-    mint,
-    curvePda,
-  }: {
-    mint: Address
-    curvePda: Address
-  }) {
-    const [vaultAddress] = await getCurveVaultPda({
-      curvePda,
-      mint,
-      programId: this.programId,
-    })
-
-    const { value: vaultBalance } = await this.rpc.getTokenAccountBalance(vaultAddress).send()
-    return BigInt(vaultBalance.amount)
-  }
-
-  /******************************* Cashback Functions *******************************/
-  async getCashbackBalance({ user }: { user: Address }) {
-    const [cashbackAccount] = await getUserCashbackAccountPda({
-      userAddress: user,
-      programId: this.programId,
-    })
-    const { value: cashbackBalance } = await this.rpc.getBalance(cashbackAccount).send()
-    return BigInt(cashbackBalance)
-  }
-
-  async getCashbackTokenBalance({
-    user,
-    quoteMint = WSOL_MINT,
-  }: {
-    user: Address
-    quoteMint?: Address
-  }) {
-    const [cashbackPda] = await getUserCashbackAccountPda({
-      userAddress: user,
-      programId: this.programId,
-    })
-    const cashbackAta = await getAssociatedTokenAccountAddress(quoteMint, cashbackPda, TOKEN_PROGRAM_ADDRESS)
-    return await this.getTokenAccountBalance(cashbackAta)
-  }
-
-  async createCashbackAccount(user: KeyPairSigner) {
-    if (!this.currentConfig) {
-      throw new Error('AMM config not initialized')
-    }
-
-    const [userCashbackPda] = await getUserCashbackAccountPda({
-      userAddress: user.address,
-      programId: this.programId,
-    })
+    const admin = signer || this.owner
+    const [[configChange], { value: latestBlockhash }] = await Promise.all([
+      getConfigChangePda({ configAddress: config, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
 
-    const ix = await getCreateCashbackInstructionAsync({
-      payer: user,
-      cashbackAccount: userCashbackPda,
-      wsolMint: WSOL_MINT,
+    const ix = await getProposeConfigChangeInstructionAsync({
+      config,
+      configChange,
+      admin,
       program: this.programId,
+      params,
     })
 
-    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
-
     const tx = pipe(
       createTransactionMessage({ version: 0 }),
       (tx) => appendTransactionMessageInstructions([ix], tx),
-      (tx) => setTransactionMessageFeePayerSigner(user, tx),
+      (tx) => setTransactionMessageFeePayerSigner(admin, tx),
       (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
     )
     const signedTx = await signTransactionMessageWithSigners(tx)
     await this.sendAndConfirmTransaction(signedTx)
 
-    return userCashbackPda
+    return configChange
   }
 
-  async updateCashbackTier({ user, newTier }: { user: Address; newTier: number }) {
-    if (!this.currentConfig) {
-      throw new Error('AMM config not initialized')
-    }
-
-    const [cashbackPda] = await getUserCashbackAccountPda({
-      userAddress: user,
-      programId: this.programId,
-    })
+  async executeConfigChange({ configAddress, signer }: { configAddress?: Address; signer?: KeyPairSigner }) {
+    const config = configAddress ?? this.currentConfig!
+    const admin = signer || this.owner
+    const [[configChange], { value: latestBlockhash }] = await Promise.all([
+      getConfigChangePda({ configAddress: config, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
 
-    const ix = await getUpdateCashbackTierInstructionAsync({
-      admin: this.owner,
-      cashbackAccount: cashbackPda,
-      user,
-      newTier,
+    const ix = await getExecuteConfigChangeInstructionAsync({
+      config,
+      configChange,
+      admin,
       program: this.programId,
     })
 
-    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
-
     const tx = pipe(
       createTransactionMessage({ version: 0 }),
       (tx) => appendTransactionMessageInstructions([ix], tx),
-      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageFeePayerSigner(admin, tx),
       (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
     )
-
     const signedTx = await signTransactionMessageWithSigners(tx)
     await this.sendAndConfirmTransaction(signedTx)
   }
 
-  async claimCashback(user: KeyPairSigner): Promise<void> {
+  async cancelConfigChange({ configAddress, signer }: { configAddress?: Address; signer?: KeyPairSigner }) {
+    const config = configAddress ?? this.currentConfig!
+    const admin = signer || this.owner
+    const [[configChange], { value: latestBlockhash }] = await Promise.all([
+      getConfigChangePda({ configAddress: config, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getCancelConfigChangeInstructionAsync({
+      config,
+      configChange,
+      admin,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(admin, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async rotateFeeClaimer({
+    feeClaimer,
+    newFeeClaimer,
+    admin,
+    configAddress,
+  }: {
+    feeClaimer: KeyPairSigner
+    newFeeClaimer: Address
+    admin?: KeyPairSigner
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const ix = await getRotateFeeClaimerInstructionAsync({
+      config,
+      feeClaimer,
+      newFeeClaimer,
+      admin,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(feeClaimer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async setTradingPause({
+    tradingPause,
+    curve,
+    configAddress,
+  }: {
+    tradingPause: number
+    curve?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const ix = await getSetTradingPauseInstructionAsync({
+      config,
+      curve,
+      admin: this.owner,
+      tradingPause,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async setFeeType({
+    feeType,
+    baseMint,
+    configAddress,
+  }: {
+    feeType: number
+    baseMint: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [curveData, { value: latestBlockhash }] = await Promise.all([
+      this.getBondingCurveData({ baseMint, configAddress: config }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getSetFeeTypeInstructionAsync({
+      config,
+      curve: curveData.address,
+      admin: this.owner,
+      feeType,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async setTradingEnd({
+    tradingEndTimestamp,
+    curve,
+    configAddress,
+  }: {
+    tradingEndTimestamp: bigint
+    curve: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const ix = await getSetTradingEndInstructionAsync({
+      config,
+      curve,
+      admin: this.owner,
+      tradingEndTimestamp,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async refund({
+    owner,
+    baseMint,
+    baseAmount,
+    quoteMint = WSOL_MINT,
+    configAddress,
+    withTestHooks = false,
+  }: {
+    owner: KeyPairSigner
+    baseMint: Address
+    baseAmount: bigint
+    quoteMint?: Address
+    configAddress?: Address
+    // pass the test hooks so their clock skew moves past the sell grace period
+    withTestHooks?: boolean
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [curveData, quoteAta, { value: latestBlockhash }] = await Promise.all([
+      this.getBondingCurveData({ baseMint, quoteMint, configAddress: config }),
+      getOrCreateATAInstruction(this.rpc, quoteMint, owner.address, owner),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    const tokenBaseProgram = curveData.data.curveType === 0 ? TOKEN_PROGRAM_ADDRESS : TOKEN_2022_PROGRAM_ADDRESS
+
+    const ix = await getRefundInstructionAsync({
+      config,
+      curve: curveData.address,
+      baseTokenAccount: await getAssociatedTokenAccountAddress(baseMint, owner.address, tokenBaseProgram),
+      quoteTokenAccount: quoteAta.ata,
+      baseVault: curveData.data.baseVault,
+      quoteVault: curveData.data.quoteVault,
+      baseMint,
+      quoteMint,
+      owner,
+      tokenBaseProgram,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+      baseAmount,
+    })
+    if (withTestHooks) {
+      ix.accounts.push({
+        address: (await getTestHooksPda({ programId: this.programId }))[0],
+        role: AccountRole.READONLY,
+      })
+    }
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions(quoteAta.ix ? [quoteAta.ix, ix] : [ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async setTestHooks({ faultPoint, clockSkewSeconds = 0n }: { faultPoint: number; clockSkewSeconds?: bigint }) {
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const ix = await getSetTestHooksInstructionAsync({
+      admin: this.owner,
+      faultPoint,
+      clockSkewSeconds,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async setPause({ paused, curve, configAddress }: { paused: boolean; curve?: Address; configAddress?: Address }) {
+    const config = configAddress ?? this.currentConfig!
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const ix = await getSetPauseInstructionAsync({
+      config,
+      curve,
+      admin: this.owner,
+      paused,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async setCpiTelemetry({ enabled, configAddress }: { enabled: boolean; configAddress?: Address }) {
+    const config = configAddress ?? this.currentConfig!
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const ix = await getSetCpiTelemetryInstructionAsync({
+      config,
+      admin: this.owner,
+      enabled,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async setCreatorWhitelistMode({ enabled, configAddress }: { enabled: boolean; configAddress?: Address }) {
+    const config = configAddress ?? this.currentConfig!
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const ix = await getSetCreatorWhitelistModeInstructionAsync({
+      config,
+      admin: this.owner,
+      enabled,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async addWhitelistedCreator({ creator, configAddress }: { creator: Address; configAddress?: Address }) {
+    const config = configAddress ?? this.currentConfig!
+    const [[whitelistEntry], { value: latestBlockhash }] = await Promise.all([
+      getWhitelistEntryPda({ configAddress: config, creator, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getAddWhitelistedCreatorInstructionAsync({
+      config,
+      whitelistEntry,
+      admin: this.owner,
+      creator,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async removeWhitelistedCreator({ creator, configAddress }: { creator: Address; configAddress?: Address }) {
+    const config = configAddress ?? this.currentConfig!
+    const [[whitelistEntry], { value: latestBlockhash }] = await Promise.all([
+      getWhitelistEntryPda({ configAddress: config, creator, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getRemoveWhitelistedCreatorInstructionAsync({
+      config,
+      whitelistEntry,
+      admin: this.owner,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async addPartner({
+    partner,
+    feeShareBasisPoints,
+    configAddress,
+  }: {
+    partner: Address
+    feeShareBasisPoints: number
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [[partnerEntry], { value: latestBlockhash }] = await Promise.all([
+      getPartnerPda({ partner, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getAddPartnerInstructionAsync({
+      config,
+      partnerEntry,
+      admin: this.owner,
+      partner,
+      feeShareBasisPoints,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async removePartner({ partner, configAddress }: { partner: Address; configAddress?: Address }) {
+    const config = configAddress ?? this.currentConfig!
+    const [[partnerEntry], { value: latestBlockhash }] = await Promise.all([
+      getPartnerPda({ partner, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getRemovePartnerInstructionAsync({
+      config,
+      partnerEntry,
+      admin: this.owner,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async setDammConfig({ dammConfig, configAddress }: { dammConfig: Address; configAddress?: Address }) {
+    const config = configAddress ?? this.currentConfig!
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const ix = await getSetDammConfigInstructionAsync({
+      config,
+      dammConfig,
+      admin: this.owner,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async delegateMigrationAuthority({
+    curve,
+    operator,
+    expiryTimestamp,
+    configAddress,
+  }: {
+    curve: Address
+    operator: Address
+    expiryTimestamp: bigint
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [[migrationDelegate], { value: latestBlockhash }] = await Promise.all([
+      getMigrationDelegatePda({ curve, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getDelegateMigrationAuthorityInstructionAsync({
+      config,
+      curve,
+      migrationDelegate,
+      operator,
+      admin: this.owner,
+      program: this.programId,
+      expiryTimestamp,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    return { migrationDelegate }
+  }
+
+  async revokeMigrationAuthority({ curve, configAddress }: { curve: Address; configAddress?: Address }) {
+    const config = configAddress ?? this.currentConfig!
+    const [[migrationDelegate], { value: latestBlockhash }] = await Promise.all([
+      getMigrationDelegatePda({ curve, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getRevokeMigrationAuthorityInstructionAsync({
+      config,
+      curve,
+      migrationDelegate,
+      admin: this.owner,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async setConfigLabel({
+    label,
+    configAddress,
+    signer,
+  }: {
+    label: string
+    configAddress?: Address
+    signer?: KeyPairSigner
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const admin = signer || this.owner
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const ix = await getSetConfigLabelInstructionAsync({
+      config,
+      admin,
+      program: this.programId,
+      label,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(admin, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async getConfigLabel({ configAddress }: { configAddress?: Address } = {}) {
+    const [configLabelAddress] = await getConfigLabelPda({
+      configAddress: configAddress ?? this.currentConfig!,
+      programId: this.programId,
+    })
+    const configLabel = await fetchConfigLabel(this.rpc, configLabelAddress)
+    const { label, labelLength } = configLabel.data
+    return new TextDecoder().decode(Uint8Array.from(label.slice(0, labelLength)))
+  }
+
+  async getProtocolStats() {
+    const [protocolStatsAddress] = await getProtocolStatsPda({ programId: this.programId })
+    const protocolStats = await fetchProtocolStats(this.rpc, protocolStatsAddress)
+    return protocolStats.data
+  }
+
+  async getConfigStats({ configAddress }: { configAddress?: Address } = {}) {
+    const [configStatsAddress] = await getConfigStatsPda({
+      configAddress: configAddress ?? this.currentConfig!,
+      programId: this.programId,
+    })
+    const configStats = await fetchConfigStats(this.rpc, configStatsAddress)
+    return configStats.data
+  }
+
+  async setCurveTemplate({
+    curveTemplateHash,
+    configAddress,
+    signer,
+  }: {
+    curveTemplateHash: number[]
+    configAddress?: Address
+    signer?: KeyPairSigner
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const admin = signer || this.owner
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const ix = await getSetCurveTemplateInstructionAsync({
+      config,
+      admin,
+      program: this.programId,
+      curveTemplateHash,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(admin, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async createInsuranceFund({
+    quoteMint = WSOL_MINT,
+    configAddress,
+    signer,
+  }: {
+    quoteMint?: Address
+    configAddress?: Address
+    signer?: KeyPairSigner
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const admin = signer || this.owner
+    const [insuranceFund] = await getInsuranceFundPda({ configAddress: config, programId: this.programId })
+    const [[quoteVault], { value: latestBlockhash }] = await Promise.all([
+      getCurveVaultPda({ curvePda: insuranceFund, mint: quoteMint, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getCreateInsuranceFundInstructionAsync({
+      config,
+      insuranceFund,
+      quoteVault,
+      quoteMint,
+      admin,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(admin, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    return { insuranceFund, quoteVault }
+  }
+
+  // The insurance fund accounts of a config, only passed to fee claims once the fund exists
+  private async getInsuranceFundAccounts({ config, quoteMint }: { config: Address; quoteMint: Address }) {
+    const [insuranceFund] = await getInsuranceFundPda({ configAddress: config, programId: this.programId })
+    const maybeInsuranceFund = await fetchMaybeInsuranceFund(this.rpc, insuranceFund)
+    if (!maybeInsuranceFund.exists) {
+      return {}
+    }
+    const [insuranceVault] = await getCurveVaultPda({
+      curvePda: insuranceFund,
+      mint: quoteMint,
+      programId: this.programId,
+    })
+    return { insuranceFund, insuranceVault }
+  }
+
+  async proposeInsurancePayout({
+    recipientTokenAccount,
+    amount,
+    quoteMint = WSOL_MINT,
+    configAddress,
+    signer,
+  }: {
+    recipientTokenAccount: Address
+    amount: bigint
+    quoteMint?: Address
+    configAddress?: Address
+    signer?: KeyPairSigner
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const admin = signer || this.owner
+    const [insuranceFund] = await getInsuranceFundPda({ configAddress: config, programId: this.programId })
+    const [[quoteVault], { value: latestBlockhash }] = await Promise.all([
+      getCurveVaultPda({ curvePda: insuranceFund, mint: quoteMint, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getProposeInsurancePayoutInstructionAsync({
+      config,
+      insuranceFund,
+      quoteVault,
+      recipientTokenAccount,
+      quoteMint,
+      admin,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+      amount,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(admin, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async executeInsurancePayout({
+    recipientTokenAccount,
+    quoteMint = WSOL_MINT,
+    configAddress,
+    signer,
+  }: {
+    recipientTokenAccount: Address
+    quoteMint?: Address
+    configAddress?: Address
+    signer?: KeyPairSigner
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const admin = signer || this.owner
+    const [insuranceFund] = await getInsuranceFundPda({ configAddress: config, programId: this.programId })
+    const [[quoteVault], { value: latestBlockhash }] = await Promise.all([
+      getCurveVaultPda({ curvePda: insuranceFund, mint: quoteMint, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getExecuteInsurancePayoutInstructionAsync({
+      config,
+      insuranceFund,
+      quoteVault,
+      recipientTokenAccount,
+      quoteMint,
+      admin,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(admin, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async getInsuranceFundData({ configAddress }: { configAddress?: Address }) {
+    const config = configAddress ?? this.currentConfig!
+    const [insuranceFund] = await getInsuranceFundPda({ configAddress: config, programId: this.programId })
+    return fetchInsuranceFund(this.rpc, insuranceFund)
+  }
+
+  /******************************* Curve functions *******************************/
+  async createFreshBondingCurve(configAddress?: Address, creator?: KeyPairSigner) {
+    const config = configAddress
+      ? configAddress
+      : this.currentConfig || (await this.createConfig(DEFAULT_CONFIG_ARGS)).configAddress
+    const mintKeypair = await generateKeyPairSigner()
+    const creatorSigner = creator ?? (await this.createTestTrader(BigInt(0.1 * LAMPORTS_PER_SOL)))
+    const bondingCurve = await this.createBondingCurveAndMintToken({
+      configAddress: config,
+      creator: creatorSigner,
+      mintKeypair,
+    })
+
+    return {
+      token: mintKeypair.address,
+      curvePda: bondingCurve.curvePda,
+    }
+  }
+
+  async createBondingCurveAndMintToken({
+    configAddress,
+    creator,
+    mintKeypair,
+    quoteMintAddress = WSOL_MINT,
+    tokenMetadata,
+    paramsHash,
+    initialBuy,
+    metadataPending = false,
+    creatorLpFeeShareBasisPoints,
+    tradingEndTimestamp,
+    activation,
+    whitelisted = false,
+    partner,
+  }: {
+    configAddress: Address
+    creator: KeyPairSigner
+    mintKeypair: KeyPairSigner
+    quoteMintAddress?: Address
+    tokenMetadata?: {
+      name: string
+      symbol: string
+      uri: string
+    }
+    paramsHash?: number[]
+    /** creator's buy within the creation instruction */
+    initialBuy?: { amountIn: bigint; minimumAmountOut?: bigint }
+    /** create with `init_curve`, trading waits for `finalizeCurveMetadata` */
+    metadataPending?: boolean
+    /** creator's share of the DAMM position fees once migrated, in bps */
+    creatorLpFeeShareBasisPoints?: number
+    /** time buys close, sells stay open for a grace period after it */
+    tradingEndTimestamp?: bigint
+    /** timestamp or slot trading opens at, per the activation type */
+    activation?: { activationPoint: bigint; activationType?: number }
+    /** pass the creator's whitelist entry, for configs in creator whitelist mode */
+    whitelisted?: boolean
+    /** approved partner frontend receiving a share of the curve's protocol fee */
+    partner?: Address
+  }) {
+    const [curvePda] = await getCurvePda({
+      configAddress,
+      baseMint: mintKeypair.address,
+      quoteMint: quoteMintAddress,
+      programId: this.programId,
+    })
+    const curveParams = {
+      name: tokenMetadata?.name ?? DEFAULT_TOKEN.name,
+      symbol: tokenMetadata?.symbol ?? DEFAULT_TOKEN.symbol,
+      uri: tokenMetadata?.uri ?? DEFAULT_TOKEN.uri,
+      paramsHash: paramsHash ?? null,
+      initialBuyAmountIn: initialBuy?.amountIn ?? null,
+      minimumAmountOut: initialBuy?.minimumAmountOut ?? null,
+      creatorLpFeeShareBasisPoints: creatorLpFeeShareBasisPoints ?? null,
+      tradingEndTimestamp: tradingEndTimestamp ?? null,
+      activationPoint: activation?.activationPoint ?? null,
+      activationType: activation?.activationType ?? null,
+      partner: partner ?? null,
+    }
+    const partnerAccounts = partner
+      ? { partnerEntry: (await getPartnerPda({ partner, programId: this.programId }))[0] }
+      : {}
+    const whitelistAccounts = whitelisted
+      ? {
+          whitelistEntry: (
+            await getWhitelistEntryPda({ configAddress, creator: creator.address, programId: this.programId })
+          )[0],
+        }
+      : {}
+
+    // the initial buy pays from the creator's quote account, the program creates the base ATA
+    const preInstructions = []
+    const postInstructions = []
+    let initialBuyAccounts = {}
+    if (initialBuy) {
+      const [
+        { ata: creatorQuoteTokenAccount, ix: createQuoteAtaIx },
+        creatorBaseTokenAccount,
+        [creatorProtection],
+      ] = await Promise.all([
+        getOrCreateATAInstruction(this.rpc, quoteMintAddress, creator.address, creator),
+        getAssociatedTokenAccountAddress(mintKeypair.address, creator.address, TOKEN_PROGRAM_ADDRESS),
+        getProtectionPda({ curvePda, userAddress: creator.address, programId: this.programId }),
+      ])
+      createQuoteAtaIx && preInstructions.push(createQuoteAtaIx)
+      if (quoteMintAddress === WSOL_MINT) {
+        preInstructions.push(...this.createWrapSOLInstructions(creator, creatorQuoteTokenAccount, initialBuy.amountIn))
+        const unwrapIx = await this.createUnwrapSOLInstruction(creator, creator.address)
+        unwrapIx && postInstructions.push(unwrapIx)
+      }
+      initialBuyAccounts = {
+        creatorQuoteTokenAccount,
+        creatorBaseTokenAccount,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ADDRESS,
+        // the initial buy starts the creator's sell lockup and anti-snipe cap like any buy
+        creatorProtection,
+      }
+    }
+
+    // the config decides the base token program, Token-2022 mints hold their own metadata
+    const configData = await this.getConfigData({ configAddress })
+    const createIx =
+      configData.data.baseTokenFlag === 1
+        ? await getCreateCurveWithToken2022InstructionAsync({
+            creator,
+            config: configAddress,
+            baseMint: mintKeypair,
+            quoteMint: quoteMintAddress,
+            curve: curvePda,
+            configStats: (await getConfigStatsPda({ configAddress, programId: this.programId }))[0],
+            tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+            program: this.programId,
+            ...whitelistAccounts,
+            ...partnerAccounts,
+            ...curveParams,
+          })
+        : await (metadataPending ? getInitCurveInstructionAsync : getCreateCurveWithSplTokenInstructionAsync)({
+            creator,
+            config: configAddress,
+            baseMint: mintKeypair,
+            quoteMint: quoteMintAddress,
+            curve: curvePda,
+            metadata: (await getMetadataPda({ mint: mintKeypair.address }))[0],
+            configStats: (await getConfigStatsPda({ configAddress, programId: this.programId }))[0],
+            tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+            program: this.programId,
+            ...initialBuyAccounts,
+            ...whitelistAccounts,
+            ...partnerAccounts,
+            ...curveParams,
+          })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = createTransaction({
+      version: 'legacy',
+      feePayer: creator,
+      instructions: [...preInstructions, createIx, ...postInstructions],
+      latestBlockhash,
+    })
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+
+    const signature = await this.sendAndConfirmTransaction(signedTx)
+
+    if (this.network === 'devnet') {
+      console.log(
+        'Explorer (create-bonding-curve):',
+        getExplorerLink({
+          cluster: 'devnet',
+          transaction: signature,
+        }),
+      )
+    }
+
+    return {
+      mint: mintKeypair.address,
+      curvePda,
+    }
+  }
+
+  async finalizeCurveMetadata({
+    creator,
+    curvePda,
+    uri,
+    extension = new Uint8Array(),
+  }: {
+    creator: KeyPairSigner
+    curvePda: Address
+    uri: string
+    extension?: Uint8Array
+  }) {
+    const curveState = await fetchBondingCurve(this.rpc, curvePda)
+    const finalizeIx = await getFinalizeCurveMetadataInstructionAsync({
+      config: curveState.data.config,
+      curve: curvePda,
+      creator,
+      uri,
+      extension,
+      program: this.programId,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+    const tx = createTransaction({
+      version: 'legacy',
+      feePayer: creator,
+      instructions: [finalizeIx],
+      latestBlockhash,
+    })
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    const [curveMetadataAddress] = await getCurveMetadataPda({ curvePda, programId: this.programId })
+    return curveMetadataAddress
+  }
+
+  async createDualListing({
+    creator,
+    baseMint,
+    primaryConfig = this.currentConfig!,
+    secondaryConfig,
+    secondaryQuoteMint,
+    secondaryBaseSupply,
+  }: {
+    creator: KeyPairSigner
+    baseMint: Address
+    primaryConfig?: Address
+    secondaryConfig: Address
+    secondaryQuoteMint: Address
+    secondaryBaseSupply: bigint
+  }) {
+    const [[primaryCurve], [secondaryCurve], [dualListing], { value: latestBlockhash }] = await Promise.all([
+      getCurvePda({ configAddress: primaryConfig, baseMint, quoteMint: WSOL_MINT, programId: this.programId }),
+      getCurvePda({
+        configAddress: secondaryConfig,
+        baseMint,
+        quoteMint: secondaryQuoteMint,
+        programId: this.programId,
+      }),
+      getDualListingPda({ baseMint, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    const [primaryCurveData, [secondaryBaseVault], [secondaryQuoteVault]] = await Promise.all([
+      fetchBondingCurve(this.rpc, primaryCurve),
+      getCurveVaultPda({ curvePda: secondaryCurve, mint: baseMint, programId: this.programId }),
+      getCurveVaultPda({ curvePda: secondaryCurve, mint: secondaryQuoteMint, programId: this.programId }),
+    ])
+
+    const ix = await getCreateDualListingInstructionAsync({
+      creator,
+      primaryConfig,
+      primaryCurve,
+      primaryBaseVault: primaryCurveData.data.baseVault,
+      secondaryConfig,
+      baseMint,
+      quoteMint: secondaryQuoteMint,
+      secondaryCurve,
+      secondaryBaseVault,
+      secondaryQuoteVault,
+      dualListing,
+      tokenBaseProgram: TOKEN_PROGRAM_ADDRESS,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+      secondaryBaseSupply,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(creator, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    return { primaryCurve, secondaryCurve, dualListing }
+  }
+
+  async getBondingCurveRentExempt() {
+    const rentExempt = await this.rpc.getMinimumBalanceForRentExemption(BigInt(getBondingCurveSize())).send()
+    return BigInt(rentExempt)
+  }
+
+  async getBondingCurveData({
+    baseMint,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    baseMint: Address
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    if (!config) {
+      throw new Error('AMM config not initialized')
+    }
+
+    const [bondingCurvePda] = await getCurvePda({
+      configAddress: config,
+      baseMint,
+      quoteMint,
+      programId: this.programId,
+    })
+
+    return await fetchBondingCurve(this.rpc, bondingCurvePda)
+  }
+
+  async getVaultTokenBalance({
+    mint,
+    curvePda,
+  }: {
+    mint: Address
+    curvePda: Address
+  }) {
+    const [vaultAddress] = await getCurveVaultPda({
+      curvePda,
+      mint,
+      programId: this.programId,
+    })
+
+    const { value: vaultBalance } = await this.rpc.getTokenAccountBalance(vaultAddress).send()
+    return BigInt(vaultBalance.amount)
+  }
+
+  /******************************* Cashback Functions *******************************/
+  async getCashbackBalance({ user }: { user: Address }) {
+    const [cashbackAccount] = await getUserCashbackAccountPda({
+      userAddress: user,
+      programId: this.programId,
+    })
+    const { value: cashbackBalance } = await this.rpc.getBalance(cashbackAccount).send()
+    return BigInt(cashbackBalance)
+  }
+
+  async getCashbackTokenBalance({
+    user,
+    quoteMint = WSOL_MINT,
+  }: {
+    user: Address
+    quoteMint?: Address
+  }) {
+    const [cashbackPda] = await getUserCashbackAccountPda({
+      userAddress: user,
+      programId: this.programId,
+    })
+    const cashbackAta = await getAssociatedTokenAccountAddress(quoteMint, cashbackPda, TOKEN_PROGRAM_ADDRESS)
+    return await this.getTokenAccountBalance(cashbackAta)
+  }
+
+  async createCashbackAccount(user: KeyPairSigner) {
+    if (!this.currentConfig) {
+      throw new Error('AMM config not initialized')
+    }
+
+    const [userCashbackPda] = await getUserCashbackAccountPda({
+      userAddress: user.address,
+      programId: this.programId,
+    })
+
+    const ix = await getCreateCashbackInstructionAsync({
+      payer: user,
+      cashbackAccount: userCashbackPda,
+      quoteMint: WSOL_MINT,
+      program: this.programId,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(user, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    return userCashbackPda
+  }
+
+  async createCashbackVault({ payer, user, quoteMint }: { payer: KeyPairSigner; user: Address; quoteMint: Address }) {
+    const [cashbackPda] = await getUserCashbackAccountPda({
+      userAddress: user,
+      programId: this.programId,
+    })
+
+    const ix = await getCreateCashbackVaultInstructionAsync({
+      payer,
+      user,
+      cashbackAccount: cashbackPda,
+      quoteMint,
+      program: this.programId,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(payer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async setReferrer({ user, referrer }: { user: KeyPairSigner; referrer: Address }) {
+    const [[referral], [referrerReferral], [referrerCashback], { value: latestBlockhash }] = await Promise.all([
+      getReferralPda({ userAddress: user.address, programId: this.programId }),
+      getReferralPda({ userAddress: referrer, programId: this.programId }),
+      getUserCashbackAccountPda({ userAddress: referrer, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getSetReferrerInstructionAsync({
+      referral,
+      referrerCashback,
+      referrer,
+      referrerReferral,
+      user,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(user, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    return referral
+  }
+
+  async updateCashbackTier({ user, newTier, updateSeq }: { user: Address; newTier: number; updateSeq?: bigint }) {
+    if (!this.currentConfig) {
+      throw new Error('AMM config not initialized')
+    }
+
+    const [cashbackPda] = await getUserCashbackAccountPda({
+      userAddress: user,
+      programId: this.programId,
+    })
+
+    const ix = await getUpdateCashbackTierInstructionAsync({
+      admin: this.owner,
+      cashbackAccount: cashbackPda,
+      user,
+      newTier,
+      updateSeq: updateSeq ?? null,
+      program: this.programId,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async setCashbackMode({ user, cashbackMode }: { user: KeyPairSigner; cashbackMode: number }) {
+    const [cashbackPda] = await getUserCashbackAccountPda({
+      userAddress: user.address,
+      programId: this.programId,
+    })
+
+    const ix = await getSetCashbackModeInstructionAsync({
+      user,
+      cashbackAccount: cashbackPda,
+      cashbackMode,
+      program: this.programId,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(user, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async setCashbackConfig({
+    claimCooldownSeconds = DEFAULT_CASHBACK_CLAIM_COOLDOWNS,
+    tierVolumeThresholds = DEFAULT_CASHBACK_TIER_VOLUME_THRESHOLDS,
+    feeDiscountBasisPoints = DEFAULT_CASHBACK_FEE_DISCOUNTS,
+  }: { claimCooldownSeconds?: bigint[]; tierVolumeThresholds?: bigint[]; feeDiscountBasisPoints?: number[] }) {
+    const ix = await getSetCashbackConfigInstructionAsync({
+      admin: this.owner,
+      claimCooldownSeconds,
+      tierVolumeThresholds,
+      feeDiscountBasisPoints,
+      program: this.programId,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async decayCashbackTier({ cranker, user }: { cranker: KeyPairSigner; user: Address }) {
+    const [cashbackPda] = await getUserCashbackAccountPda({
+      userAddress: user,
+      programId: this.programId,
+    })
+
+    const ix = await getDecayCashbackTierInstructionAsync({
+      cranker,
+      cashbackAccount: cashbackPda,
+      user,
+      program: this.programId,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(cranker, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async refreshCashbackTier({ cranker, user }: { cranker: KeyPairSigner; user: Address }) {
+    const [cashbackPda] = await getUserCashbackAccountPda({
+      userAddress: user,
+      programId: this.programId,
+    })
+
+    const ix = await getRefreshCashbackTierInstructionAsync({
+      cranker,
+      cashbackAccount: cashbackPda,
+      user,
+      program: this.programId,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(cranker, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async claimCashback(
+    user: KeyPairSigner,
+    {
+      relayer,
+      relayerTip = 0n,
+      quoteMint = WSOL_MINT,
+    }: { relayer?: KeyPairSigner; relayerTip?: bigint; quoteMint?: Address } = {},
+  ): Promise<void> {
     if (!this.currentConfig) {
       throw new Error('AMM config not initialized')
     }
 
-    const [cashbackPda] = await getUserCashbackAccountPda({
-      userAddress: user.address,
-      programId: this.programId,
+    const [cashbackPda] = await getUserCashbackAccountPda({
+      userAddress: user.address,
+      programId: this.programId,
+    })
+
+    const userTokenAccount = await getAssociatedTokenAccountAddress(quoteMint, user.address, TOKEN_PROGRAM_ADDRESS)
+    const relayerTokenAccount = relayer
+      ? await getAssociatedTokenAccountAddress(quoteMint, relayer.address, TOKEN_PROGRAM_ADDRESS)
+      : undefined
+
+    const ix = await getClaimCashbackInstructionAsync({
+      user,
+      cashbackAccount: cashbackPda,
+      quoteMint,
+      userTokenAccount,
+      relayerTokenAccount,
+      program: this.programId,
+      relayerTip,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    // a relayer pays the transaction fees while the user only signs the claim
+    const tx = createTransaction({
+      version: 'legacy',
+      feePayer: relayer ?? user,
+      instructions: [ix],
+      latestBlockhash,
+    })
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async closeCashback(user: KeyPairSigner, { quoteMint = WSOL_MINT }: { quoteMint?: Address } = {}) {
+    const [cashbackPda] = await getUserCashbackAccountPda({
+      userAddress: user.address,
+      programId: this.programId,
+    })
+
+    const ix = await getCloseCashbackInstructionAsync({
+      user,
+      cashbackAccount: cashbackPda,
+      quoteMint,
+      userTokenAccount: await getAssociatedTokenAccountAddress(quoteMint, user.address, TOKEN_PROGRAM_ADDRESS),
+      program: this.programId,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(user, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async getCashbackAccountData(user: Address) {
+    const [cashbackPda] = await getUserCashbackAccountPda({
+      userAddress: user,
+      programId: this.programId,
+    })
+    return await fetchCashbackAccount(this.rpc, cashbackPda)
+  }
+
+  async getCashbackTokenAccountBalance({
+    user,
+    quoteMint = WSOL_MINT,
+  }: {
+    user: Address
+    quoteMint?: Address
+  }) {
+    const [cashbackPda] = await getUserCashbackAccountPda({
+      userAddress: user,
+      programId: this.programId,
+    })
+    const cashbackAta = await getAssociatedTokenAccountAddress(quoteMint, cashbackPda, TOKEN_PROGRAM_ADDRESS)
+    return await this.getTokenAccountBalance(cashbackAta)
+  }
+
+  async getCashbackAccountRentExempt() {
+    const rentExempt = await this.rpc.getMinimumBalanceForRentExemption(BigInt(getCashbackAccountSize())).send()
+    return BigInt(rentExempt)
+  }
+
+  async verifyReferrerRewards(referrer: Address, expectedRewards: bigint) {
+    const [referrerCashbackBalance, rentExempt] = await Promise.all([
+      this.getCashbackTokenAccountBalance({ user: referrer }),
+      this.getCashbackAccountRentExempt(),
+    ])
+
+    const actualRewards = referrerCashbackBalance - rentExempt
+    expect(Math.abs(Number(actualRewards - expectedRewards))).toBeLessThan(100n)
+
+    return { referrerCashbackBalance, rentExempt }
+  }
+
+  /**
+   * Simulates get_fee_schedule and decodes the fee rates the program returns for the wallet
+   */
+  async getFeeSchedule({
+    wallet,
+    withCashback = false,
+    withReferral = false,
+    tradeDirection = TradeDirection.QuoteToBase,
+    configAddress,
+    curveAddress,
+  }: {
+    wallet: Address
+    withCashback?: boolean
+    withReferral?: boolean
+    tradeDirection?: TradeDirection
+    configAddress?: Address
+    curveAddress?: Address
+  }) {
+    const config = configAddress || this.currentConfig
+    if (!config) {
+      throw new Error('AMM config not initialized')
+    }
+
+    const [[cashback], [cashbackConfig], [referral], { value: latestBlockhash }] = await Promise.all([
+      getUserCashbackAccountPda({ userAddress: wallet, programId: this.programId }),
+      getCashbackConfigPda({ programId: this.programId }),
+      getReferralPda({ userAddress: wallet, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = getGetFeeScheduleInstruction({
+      config,
+      wallet,
+      cashback: withCashback ? cashback : undefined,
+      cashbackConfig: withCashback ? cashbackConfig : undefined,
+      referral: withReferral ? referral : undefined,
+      curve: curveAddress,
+      tradeDirection,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    const { value } = await this.rpc
+      .simulateTransaction(getBase64EncodedWireTransaction(signedTx), { encoding: 'base64' })
+      .send()
+    if (value.err || !value.returnData) {
+      throw new Error(`get_fee_schedule failed: ${JSON.stringify(value.err)}`)
+    }
+
+    const [data] = value.returnData.data
+    return getFeeScheduleDecoder().decode(getBase64Encoder().encode(data))
+  }
+  async simulateSwap({
+    wallet,
+    baseMint,
+    amountIn,
+    tradeDirection,
+    withCashback = false,
+    withReferral = false,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    wallet: Address
+    baseMint: Address
+    amountIn: bigint
+    tradeDirection: TradeDirection
+    withCashback?: boolean
+    withReferral?: boolean
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress || this.currentConfig
+    if (!config) {
+      throw new Error('AMM config not initialized')
+    }
+
+    const [[curve], [cashback], [referral], { value: latestBlockhash }] = await Promise.all([
+      getCurvePda({ configAddress: config, baseMint, quoteMint, programId: this.programId }),
+      getUserCashbackAccountPda({ userAddress: wallet, programId: this.programId }),
+      getReferralPda({ userAddress: wallet, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = getSimulateSwapInstruction({
+      config,
+      curve,
+      wallet,
+      cashback: withCashback ? cashback : undefined,
+      referral: withReferral ? referral : undefined,
+      amountIn,
+      tradeDirection,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    const { value } = await this.rpc
+      .simulateTransaction(getBase64EncodedWireTransaction(signedTx), { encoding: 'base64' })
+      .send()
+    if (value.err || !value.returnData) {
+      throw new Error(`simulate_swap failed: ${JSON.stringify(value.err)}`)
+    }
+
+    const [data] = value.returnData.data
+    return getSwapSimulationDecoder().decode(getBase64Encoder().encode(data))
+  }
+
+  async verifyCurveInvariants({
+    baseMint,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    baseMint: Address
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [[curve], { value: latestBlockhash }] = await Promise.all([
+      getCurvePda({ configAddress: config, baseMint, quoteMint, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    const [[baseVault], [quoteVault]] = await Promise.all([
+      getCurveVaultPda({ curvePda: curve, mint: baseMint, programId: this.programId }),
+      getCurveVaultPda({ curvePda: curve, mint: quoteMint, programId: this.programId }),
+    ])
+
+    const ix = await getVerifyCurveInvariantsInstructionAsync({
+      config,
+      curve,
+      baseVault,
+      quoteVault,
+      program: this.programId,
+    })
+
+    const tx = createTransaction({
+      version: 'legacy',
+      feePayer: this.owner,
+      instructions: [ix],
+      latestBlockhash,
+    })
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    return await this.sendAndConfirmTransaction(signedTx)
+  }
+
+
+  /******************************* Helper Functions *******************************/
+  /**
+   * Creates instructions to wrap native SOL into WSOL tokens
+   * Equivalent to the legacy Solana web3.js wrapSOLInstruction
+   */
+  private createWrapSOLInstructions(from: KeyPairSigner, to: Address, amount: bigint) {
+    // Create transfer SOL instruction - source should be the KeyPairSigner
+    const transferIx = getTransferSolInstruction({
+      source: from,
+      destination: to,
+      amount,
+    })
+
+    // Create sync native instruction - only needs the account parameter
+    const syncNativeIx = getSyncNativeInstruction({ account: to }, { programAddress: TOKEN_PROGRAM_ADDRESS })
+
+    return [transferIx, syncNativeIx]
+  }
+
+  /**
+   * Creates instruction to unwrap WSOL back to native SOL
+   * Equivalent to the legacy createCloseAccountInstruction
+   */
+  private async createUnwrapSOLInstruction(owner: KeyPairSigner, receiver: Address) {
+    const wSolATAAccount = await getAssociatedTokenAccountAddress(WSOL_MINT, owner.address, TOKEN_PROGRAM_ADDRESS)
+    if (wSolATAAccount) {
+      return getCloseAccountInstruction(
+        {
+          account: wSolATAAccount,
+          destination: receiver,
+          owner,
+        },
+        {
+          programAddress: TOKEN_PROGRAM_ADDRESS,
+        },
+      )
+    }
+    return null
+  }
+
+  /******************************* Trade Functions *******************************/
+  async swap({
+    trader,
+    baseMint,
+    amountIn,
+    minimumAmountOut,
+    tradeDirection,
+    cashbackAddress,
+    quoteMint = WSOL_MINT,
+    configAddress,
+    l1Referrer,
+    l2Referrer,
+    l3Referrer,
+    withBonusPool = false,
+    withProtection = false,
+    maxPriceImpactBasisPoints,
+    exactAmountOut,
+    useNativeSol = false,
+    validUntilSlot,
+    maxSlippageBasisPoints,
+    tokenBaseProgram = TOKEN_PROGRAM_ADDRESS,
+    l1ReferralTokenAccount,
+    nativeCashback = false,
+  }: {
+    trader: KeyPairSigner
+    baseMint: Address
+    /** the maximum input with `exactAmountOut` */
+    amountIn: bigint
+    minimumAmountOut: bigint
+    tradeDirection: TradeDirection
+    cashbackAddress?: Address
+    quoteMint?: Address
+    configAddress?: Address
+    l1Referrer?: Address
+    l2Referrer?: Address
+    l3Referrer?: Address
+    withBonusPool?: boolean
+    withProtection?: boolean
+    maxPriceImpactBasisPoints?: number
+    /** swaps in exact out mode, `minimumAmountOut` is then ignored */
+    exactAmountOut?: bigint
+    /** lets the program wrap and unwrap SOL instead of the client */
+    useNativeSol?: boolean
+    /** last slot the swap may land in */
+    validUntilSlot?: bigint
+    /** max shortfall against the spot price at execution, checked by the program */
+    maxSlippageBasisPoints?: number
+    /** token program passed for the base mint, only overridden to test mismatches */
+    tokenBaseProgram?: Address
+    /** l1 referral token account passed instead of the referrer's cashback ATA, to test spoofing */
+    l1ReferralTokenAccount?: Address
+    /** leaves the cashback token account out, the cashback accrues as lamports in the native mode */
+    nativeCashback?: boolean
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [[curve], curveState, configState] = await Promise.all([
+      getCurvePda({
+        configAddress: config,
+        baseMint,
+        quoteMint,
+        programId: this.programId,
+      }),
+      this.getBondingCurveData({ baseMint, quoteMint, configAddress: config }),
+      this.getConfigData({ configAddress: config }),
+    ])
+
+    const { inputMint, outputMint, inputTokenProgram, outputTokenProgram } = prepareSwapParams(
+      tradeDirection,
+      curveState.data,
+      configState.data,
+    )
+
+    // Calculate accounts and instructions
+    const [
+      { ataTokenA: inputTokenAccount, ataTokenB: outputTokenAccount, instructions: preInstructions },
+      [baseVaultAddress],
+      [quoteVaultAddress],
+      { cashbackPda, cashbackTokenAccount },
+      { cashbackTokenAccount: l1ReferrerCashbackTokenAccount },
+      { cashbackTokenAccount: l2ReferrerCashbackTokenAccount },
+      { cashbackTokenAccount: l3ReferrerCashbackTokenAccount },
+      [curveAuthority],
+      [protectionPda],
+      [bonusPoolPda],
+      [referralPda],
+      { value: latestBlockhash },
+      unwrapIx,
+    ] = await Promise.all([
+      prepareTokenAccounts({
+        rpc: this.rpc,
+        owner: trader.address,
+        payer: trader,
+        tokenAMint: inputMint,
+        tokenBMint: outputMint,
+        tokenAProgram: inputTokenProgram,
+        tokenBProgram: inputTokenProgram,
+      }),
+      getCurveVaultPda({
+        curvePda: curve,
+        mint: baseMint,
+        programId: this.programId,
+      }),
+      getCurveVaultPda({
+        curvePda: curve,
+        mint: quoteMint,
+        programId: this.programId,
+      }),
+      getCashbackAccounts({
+        cashbackAddress,
+        quoteMint,
+        programId: this.programId,
+      }),
+      getCashbackAccounts({
+        cashbackAddress: l1Referrer,
+        quoteMint,
+        programId: this.programId,
+      }),
+      getCashbackAccounts({
+        cashbackAddress: l2Referrer,
+        quoteMint,
+        programId: this.programId,
+      }),
+      getCashbackAccounts({
+        cashbackAddress: l3Referrer,
+        quoteMint,
+        programId: this.programId,
+      }),
+      getCurveAuthority({ programId: this.programId }),
+      getProtectionPda({ curvePda: curve, userAddress: trader.address, programId: this.programId }),
+      getBonusPoolPda({ curvePda: curve, programId: this.programId }),
+      getReferralPda({ userAddress: trader.address, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+      // If we're dealing with WSOL, add unwrap instruction to close the account and get native SOL back
+      !useNativeSol && (inputMint === WSOL_MINT || outputMint === WSOL_MINT)
+        ? this.createUnwrapSOLInstruction(trader, trader.address)
+        : null,
+    ])
+
+    if (!useNativeSol && inputMint === WSOL_MINT && amountIn > 0n) {
+      preInstructions.push(...this.createWrapSOLInstructions(trader, inputTokenAccount, amountIn))
+    }
+    const postInstructions = unwrapIx ? [unwrapIx] : []
+
+    const params = {
+      amountIn,
+      minimumAmountOut,
+    }
+
+    const accounts = {
+      curveAuthority,
+      config,
+      curve,
+      inputTokenAccount,
+      outputTokenAccount,
+      baseVault: baseVaultAddress,
+      quoteVault: quoteVaultAddress,
+      baseMint,
+      quoteMint,
+      payer: trader,
+      tokenBaseProgram,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+
+      /* cashback PDAs */
+      cashback: cashbackPda ?? null,
+      cashbackTokenAccount: nativeCashback ? null : (cashbackTokenAccount ?? null),
+      l1ReferralCashbackTokenAccount: l1ReferralTokenAccount ?? l1ReferrerCashbackTokenAccount ?? null,
+      l2ReferralCashbackTokenAccount: l2ReferrerCashbackTokenAccount ?? null,
+      l3ReferralCashbackTokenAccount: l3ReferrerCashbackTokenAccount ?? null,
+
+      /* protection and bonus pool PDAs */
+      protection: withBonusPool || withProtection ? protectionPda : null,
+      bonusPool: withBonusPool ? bonusPoolPda : null,
+
+      /* referrer chain of the trader, required to pay referral fees */
+      referral: l1Referrer ? referralPda : null,
+
+      configStats: (await getConfigStatsPda({ configAddress: config, programId: this.programId }))[0],
+      program: this.programId,
+    }
+
+    // options past the v1 parameters go through the versioned swap
+    const withV6Options = useNativeSol || validUntilSlot !== undefined || maxSlippageBasisPoints !== undefined
+    const ix = withV6Options
+      ? await getSwapVersionedInstructionAsync({
+          ...accounts,
+          params: {
+            __kind: 'V6',
+            fields: [
+              {
+                amount: exactAmountOut ?? amountIn,
+                otherAmountThreshold: exactAmountOut === undefined ? minimumAmountOut : amountIn,
+                maxPriceImpactBasisPoints: maxPriceImpactBasisPoints ?? 0,
+                swapMode: exactAmountOut === undefined ? SwapMode.ExactIn : SwapMode.ExactOut,
+                useNativeSol,
+                validUntilSlot: validUntilSlot ?? 0n,
+                maxSlippageBasisPoints: maxSlippageBasisPoints ?? 0,
+              },
+            ],
+          },
+        })
+      : exactAmountOut !== undefined
+        ? await getSwapVersionedInstructionAsync({
+            ...accounts,
+            params: {
+              __kind: 'V3',
+              fields: [
+                {
+                  amount: exactAmountOut,
+                  otherAmountThreshold: amountIn,
+                  maxPriceImpactBasisPoints: maxPriceImpactBasisPoints ?? 0,
+                  swapMode: SwapMode.ExactOut,
+                },
+              ],
+            },
+          })
+        : maxPriceImpactBasisPoints === undefined
+          ? await getSwapInstructionAsync({ ...accounts, params })
+          : await getSwapVersionedInstructionAsync({
+              ...accounts,
+              params: { __kind: 'V2', fields: [{ ...params, maxPriceImpactBasisPoints }] },
+            })
+
+    // Estimate compute units before sending
+    const transactionMessage = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([...preInstructions, ix, ...postInstructions], tx),
+      (tx) => setTransactionMessageFeePayerSigner(trader, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(transactionMessage)
+    const signature = await this.sendAndConfirmTransaction(signedTx)
+
+    if (this.network === 'devnet') {
+      console.log(
+        'Explorer (buy):',
+        getExplorerLink({
+          cluster: 'devnet',
+          transaction: signature,
+        }),
+      )
+    }
+
+    // Get transaction details to extract compute units used
+    const txDetails = await this.rpc
+      .getTransaction(signature, {
+        encoding: 'jsonParsed',
+        maxSupportedTransactionVersion: 0,
+      })
+      .send()
+    return txDetails?.meta?.computeUnitsConsumed ? Number(txDetails.meta.computeUnitsConsumed) : 0
+  }
+
+  async swapTokenToToken({
+    trader,
+    baseMintA,
+    baseMintB,
+    amountIn,
+    minimumAmountOut,
+    quoteMint = WSOL_MINT,
+    configA = this.currentConfig!,
+    configB = this.currentConfig!,
+    withBonusPoolA = false,
+  }: {
+    trader: KeyPairSigner
+    baseMintA: Address
+    baseMintB: Address
+    amountIn: bigint
+    minimumAmountOut: bigint
+    quoteMint?: Address
+    configA?: Address
+    configB?: Address
+    /** settles the early buys sold on curve a out of its bonus pool */
+    withBonusPoolA?: boolean
+  }) {
+    const [[curveA], [curveB], [curveAuthority], { value: latestBlockhash }] = await Promise.all([
+      getCurvePda({ configAddress: configA, baseMint: baseMintA, quoteMint, programId: this.programId }),
+      getCurvePda({ configAddress: configB, baseMint: baseMintB, quoteMint, programId: this.programId }),
+      getCurveAuthority({ programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    const [
+      { ataTokenA: inputTokenAccount, ataTokenB: outputTokenAccount, instructions: preInstructions },
+      [baseVaultA],
+      [quoteVaultA],
+      [baseVaultB],
+      [quoteVaultB],
+      [protectionA],
+      [bonusPoolA],
+    ] = await Promise.all([
+      prepareTokenAccounts({
+        rpc: this.rpc,
+        owner: trader.address,
+        payer: trader,
+        tokenAMint: baseMintA,
+        tokenBMint: baseMintB,
+        tokenAProgram: TOKEN_PROGRAM_ADDRESS,
+        tokenBProgram: TOKEN_PROGRAM_ADDRESS,
+      }),
+      getCurveVaultPda({ curvePda: curveA, mint: baseMintA, programId: this.programId }),
+      getCurveVaultPda({ curvePda: curveA, mint: quoteMint, programId: this.programId }),
+      getCurveVaultPda({ curvePda: curveB, mint: baseMintB, programId: this.programId }),
+      getCurveVaultPda({ curvePda: curveB, mint: quoteMint, programId: this.programId }),
+      getProtectionPda({ curvePda: curveA, userAddress: trader.address, programId: this.programId }),
+      getBonusPoolPda({ curvePda: curveA, programId: this.programId }),
+    ])
+
+    const ix = await getSwapTokenToTokenInstructionAsync({
+      curveAuthority,
+      configA,
+      curveA,
+      baseVaultA,
+      quoteVaultA,
+      baseMintA,
+      configB,
+      curveB,
+      baseVaultB,
+      quoteVaultB,
+      baseMintB,
+      quoteMint,
+      inputTokenAccount,
+      outputTokenAccount,
+      payer: trader,
+      tokenBaseAProgram: TOKEN_PROGRAM_ADDRESS,
+      tokenBaseBProgram: TOKEN_PROGRAM_ADDRESS,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      protectionA: withBonusPoolA ? protectionA : null,
+      bonusPoolA: withBonusPoolA ? bonusPoolA : null,
+      protectionB: null,
+      configStatsA: (await getConfigStatsPda({ configAddress: configA, programId: this.programId }))[0],
+      configStatsB: (await getConfigStatsPda({ configAddress: configB, programId: this.programId }))[0],
+      program: this.programId,
+      params: { amountIn, minimumAmountOut },
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([...preInstructions, ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(trader, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  /******************************* Fee Claimer Functions *******************************/
+  async claimProtocolFees({
+    feeClaimer,
+    baseMint,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    feeClaimer: KeyPairSigner
+    baseMint: Address
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    if (!config) {
+      throw new Error('AMM config not initialized')
+    }
+
+    const [[curvePda], feeClaimerAta, insuranceAccounts, { value: latestBlockhash }] = await Promise.all([
+      getCurvePda({
+        configAddress: config,
+        baseMint,
+        quoteMint,
+        programId: this.programId,
+      }),
+      // the program only validates the destination, it doesn't create it
+      getOrCreateATAInstruction(this.rpc, quoteMint, feeClaimer.address, feeClaimer),
+      this.getInsuranceFundAccounts({ config, quoteMint }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    const { ata: feeClaimerTokenAccount, ix: createAtaIx } = feeClaimerAta
+    const curveData = await this.getBondingCurveData({ baseMint, quoteMint, configAddress: config })
+
+    // partnered curves pay the partner's share into its earnings vault
+    const partner = curveData.data.partner
+    let partnerAccounts = {}
+    if (partner !== SYSTEM_PROGRAM_ADDRESS) {
+      const [partnerEarnings] = await getPartnerEarningsPda({ partner, quoteMint, programId: this.programId })
+      const [partnerVault] = await getCurveVaultPda({
+        curvePda: partnerEarnings,
+        mint: quoteMint,
+        programId: this.programId,
+      })
+      partnerAccounts = { partnerEarnings, partnerVault }
+    }
+
+    const ix = await getClaimProtocolFeeInstructionAsync({
+      config,
+      curve: curvePda,
+      feeClaimerTokenAccount,
+      ...insuranceAccounts,
+      ...partnerAccounts,
+      quoteVault: curveData.data.quoteVault,
+      quoteMint: quoteMint,
+      feeClaimer,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions(createAtaIx ? [createAtaIx, ix] : [ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(feeClaimer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async claimMemeFee({
+    claimer = this.owner,
+    baseMint,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    claimer?: KeyPairSigner
+    baseMint: Address
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    if (!config) {
+      throw new Error('AMM config not initialized')
+    }
+
+    const [curveData, claimerAta, { value: latestBlockhash }] = await Promise.all([
+      this.getBondingCurveData({ baseMint, quoteMint, configAddress: config }),
+      getOrCreateATAInstruction(this.rpc, quoteMint, claimer.address, claimer),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    const { ata: claimerTokenAccount, ix: createAtaIx } = claimerAta
+
+    const ix = await getClaimMemeFeeInstructionAsync({
+      config,
+      curve: curveData.address,
+      claimerTokenAccount,
+      quoteVault: curveData.data.quoteVault,
+      quoteMint,
+      claimer,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions(createAtaIx ? [createAtaIx, ix] : [ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(claimer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async claimMigrationFee({
+    feeClaimer,
+    baseMint,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    feeClaimer: KeyPairSigner
+    baseMint: Address
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    if (!config) {
+      throw new Error('AMM config not initialized')
+    }
+
+    const [curveData, feeClaimerAta, { value: latestBlockhash }] = await Promise.all([
+      this.getBondingCurveData({ baseMint, quoteMint, configAddress: config }),
+      getOrCreateATAInstruction(this.rpc, quoteMint, feeClaimer.address, feeClaimer),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    const { ata: feeClaimerTokenAccount, ix: createAtaIx } = feeClaimerAta
+
+    const ix = await getClaimMigrationFeeInstructionAsync({
+      config,
+      curve: curveData.address,
+      feeClaimerTokenAccount,
+      quoteVault: curveData.data.quoteVault,
+      quoteMint,
+      feeClaimer,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions(createAtaIx ? [createAtaIx, ix] : [ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(feeClaimer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async claimProtocolFeeBatch({
+    feeClaimer,
+    baseMints,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    feeClaimer: KeyPairSigner
+    baseMints: Address[]
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    if (!config) {
+      throw new Error('AMM config not initialized')
+    }
+
+    const [feeClaimerAta, insuranceAccounts, curves, { value: latestBlockhash }] = await Promise.all([
+      getOrCreateATAInstruction(this.rpc, quoteMint, feeClaimer.address, feeClaimer),
+      this.getInsuranceFundAccounts({ config, quoteMint }),
+      Promise.all(
+        baseMints.map((baseMint) => this.getBondingCurveData({ baseMint, quoteMint, configAddress: config })),
+      ),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    const { ata: feeClaimerTokenAccount, ix: createAtaIx } = feeClaimerAta
+
+    const ix = await getClaimProtocolFeeBatchInstructionAsync({
+      config,
+      feeClaimerTokenAccount,
+      ...insuranceAccounts,
+      quoteMint,
+      feeClaimer,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+    })
+
+    // the curves and their quote vaults as remaining account pairs
+    for (const curve of curves) {
+      ix.accounts.push(
+        { address: curve.address, role: AccountRole.WRITABLE },
+        { address: curve.data.quoteVault, role: AccountRole.WRITABLE },
+      )
+    }
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions(createAtaIx ? [createAtaIx, ix] : [ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(feeClaimer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async claimCreatorFee({
+    creator,
+    baseMint,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    /** creator of the curve or its creator fee recipient, signs the claim */
+    creator: KeyPairSigner
+    baseMint: Address
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    if (!config) {
+      throw new Error('AMM config not initialized')
+    }
+
+    const [[curvePda], { value: latestBlockhash }] = await Promise.all([
+      getCurvePda({
+        configAddress: config,
+        baseMint,
+        quoteMint,
+        programId: this.programId,
+      }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const [curveData, configData] = await Promise.all([
+      this.getBondingCurveData({ baseMint, quoteMint, configAddress: config }),
+      this.getConfigData({ configAddress: config }),
+    ])
+    // the default pubkey pays the creator
+    const { creator: curveCreator, creatorFeeRecipient: recipient } = curveData.data
+    const creatorFeeRecipient = recipient === SYSTEM_PROGRAM_ADDRESS ? curveCreator : recipient
+    const [recipientTokenAccount, feeClaimerTokenAccount] = await Promise.all([
+      getAssociatedTokenAccountAddress(quoteMint, creatorFeeRecipient, TOKEN_PROGRAM_ADDRESS),
+      getAssociatedTokenAccountAddress(quoteMint, configData.data.feeClaimer, TOKEN_PROGRAM_ADDRESS),
+    ])
+
+    const ix = await getClaimCreatorFeeInstructionAsync({
+      config,
+      curve: curvePda,
+      creatorFeeRecipient,
+      recipientTokenAccount,
+      feeClaimer: configData.data.feeClaimer,
+      feeClaimerTokenAccount,
+      quoteVault: curveData.data.quoteVault,
+      quoteMint: quoteMint,
+      signer: creator,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(creator, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async setCreatorFeeRecipient({
+    creator,
+    baseMint,
+    recipient,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    creator: KeyPairSigner
+    baseMint: Address
+    /** the system program address (default pubkey) sends the fee back to the creator */
+    recipient: Address
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [[curvePda], { value: latestBlockhash }] = await Promise.all([
+      getCurvePda({ configAddress: config, baseMint, quoteMint, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getSetCreatorFeeRecipientInstructionAsync({
+      config,
+      curve: curvePda,
+      creator,
+      recipient,
+      program: this.programId,
+    })
+
+    const tx = createTransaction({
+      version: 'legacy',
+      feePayer: creator,
+      instructions: [ix],
+      latestBlockhash,
+    })
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async transferCreator({
+    creator,
+    newCreator,
+    creatorFeeTransferMode,
+    baseMint,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    creator: KeyPairSigner
+    newCreator: Address
+    creatorFeeTransferMode: number
+    baseMint: Address
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [[curvePda], creatorTokenAccount, curveData, configData, { value: latestBlockhash }] = await Promise.all([
+      getCurvePda({
+        configAddress: config,
+        baseMint,
+        quoteMint,
+        programId: this.programId,
+      }),
+      getAssociatedTokenAccountAddress(quoteMint, creator.address, TOKEN_PROGRAM_ADDRESS),
+      this.getBondingCurveData({ baseMint, quoteMint, configAddress: config }),
+      this.getConfigData({ configAddress: config }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    const feeClaimerTokenAccount = await getAssociatedTokenAccountAddress(
+      quoteMint,
+      configData.data.feeClaimer,
+      TOKEN_PROGRAM_ADDRESS,
+    )
+
+    const ix = await getTransferCreatorInstructionAsync({
+      config,
+      curve: curvePda,
+      newCreator,
+      creatorTokenAccount,
+      feeClaimer: configData.data.feeClaimer,
+      feeClaimerTokenAccount,
+      quoteVault: curveData.data.quoteVault,
+      quoteMint,
+      creator,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+      creatorFeeTransferMode,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(creator, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  /******************************* Bonus Pool Functions *******************************/
+  async createProtection({ user, curve }: { user: KeyPairSigner; curve: Address }) {
+    const [[protectionPda], curveData] = await Promise.all([
+      getProtectionPda({
+        curvePda: curve,
+        userAddress: user.address,
+        programId: this.programId,
+      }),
+      fetchBondingCurve(this.rpc, curve),
+    ])
+
+    const ix = await getCreateProtectionInstructionAsync({
+      payer: user,
+      config: curveData.data.config,
+      curve,
+      protection: protectionPda,
+      program: this.programId,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(user, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    return protectionPda
+  }
+
+  async depositBonusPool({
+    creator,
+    baseMint,
+    amount,
+    quoteMint = WSOL_MINT,
+    configAddress,
+    wrappedAmount = amount,
+    syncNative = true,
+  }: {
+    creator: KeyPairSigner
+    baseMint: Address
+    amount: bigint
+    quoteMint?: Address
+    configAddress?: Address
+    // lamports sent to the creator's WSOL account up front, the program wraps the rest
+    wrappedAmount?: bigint
+    // false leaves syncing the sent lamports to the program
+    syncNative?: boolean
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [curvePda] = await getCurvePda({ configAddress: config, baseMint, quoteMint, programId: this.programId })
+    const [[bonusPoolPda], { ataTokenA: creatorTokenAccount, instructions: preInstructions }] = await Promise.all([
+      getBonusPoolPda({ curvePda, programId: this.programId }),
+      prepareTokenAccounts({
+        rpc: this.rpc,
+        owner: creator.address,
+        payer: creator,
+        tokenAMint: quoteMint,
+        tokenBMint: baseMint,
+        tokenAProgram: TOKEN_PROGRAM_ADDRESS,
+        tokenBProgram: TOKEN_PROGRAM_ADDRESS,
+      }),
+    ])
+    const [bonusVault] = await getCurveVaultPda({ curvePda: bonusPoolPda, mint: quoteMint, programId: this.programId })
+
+    if (quoteMint === WSOL_MINT && wrappedAmount > 0n) {
+      const wrapInstructions = this.createWrapSOLInstructions(creator, creatorTokenAccount, wrappedAmount)
+      preInstructions.push(...(syncNative ? wrapInstructions : wrapInstructions.slice(0, 1)))
+    }
+
+    const ix = await getDepositBonusPoolInstructionAsync({
+      config,
+      curve: curvePda,
+      bonusPool: bonusPoolPda,
+      bonusVault,
+      creatorTokenAccount,
+      quoteMint,
+      creator,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+      amount,
     })
 
-    const userWsolAccount = await getAssociatedTokenAccountAddress(WSOL_MINT, user.address, TOKEN_PROGRAM_ADDRESS)
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
 
-    const ix = await getClaimCashbackInstructionAsync({
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([...preInstructions, ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(creator, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    return { bonusPoolPda, bonusVault }
+  }
+
+  async claimBonus({
+    user,
+    baseMint,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    user: KeyPairSigner
+    baseMint: Address
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [curvePda] = await getCurvePda({ configAddress: config, baseMint, quoteMint, programId: this.programId })
+    const [[bonusPoolPda], [protectionPda], userTokenAccount, curveData] = await Promise.all([
+      getBonusPoolPda({ curvePda, programId: this.programId }),
+      getProtectionPda({ curvePda, userAddress: user.address, programId: this.programId }),
+      getAssociatedTokenAccountAddress(quoteMint, user.address, TOKEN_PROGRAM_ADDRESS),
+      fetchBondingCurve(this.rpc, curvePda),
+    ])
+    const tokenBaseProgram = curveData.data.curveType === 0 ? TOKEN_PROGRAM_ADDRESS : TOKEN_2022_PROGRAM_ADDRESS
+    const [[bonusVault], userBaseTokenAccount] = await Promise.all([
+      getCurveVaultPda({ curvePda: bonusPoolPda, mint: quoteMint, programId: this.programId }),
+      getAssociatedTokenAccountAddress(baseMint, user.address, tokenBaseProgram),
+    ])
+
+    const ix = await getClaimBonusInstructionAsync({
+      config,
+      curve: curvePda,
+      bonusPool: bonusPoolPda,
+      protection: protectionPda,
+      userBaseTokenAccount,
+      bonusVault,
+      userTokenAccount,
+      quoteMint,
       user,
-      cashbackAccount: cashbackPda,
-      wsolMint: WSOL_MINT,
-      userWsolAccount: userWsolAccount,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
       program: this.programId,
     })
 
     const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
 
-    const tx = createTransaction({
-      version: 'legacy',
-      feePayer: user,
-      instructions: [ix],
-      latestBlockhash,
-    })
-
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(user, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
     const signedTx = await signTransactionMessageWithSigners(tx)
     await this.sendAndConfirmTransaction(signedTx)
   }
 
-  async getCashbackAccountData(user: Address) {
-    const [cashbackPda] = await getUserCashbackAccountPda({
-      userAddress: user,
-      programId: this.programId,
+  /******************************* Partners *******************************/
+  async createPartnerEarnings({
+    partner,
+    quoteMint = WSOL_MINT,
+    payer = this.owner,
+    configAddress,
+  }: {
+    partner: Address
+    quoteMint?: Address
+    payer?: KeyPairSigner
+    // a config in the quote mint, sequences the event
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [partnerEarnings] = await getPartnerEarningsPda({ partner, quoteMint, programId: this.programId })
+    const [[quoteVault], { value: latestBlockhash }] = await Promise.all([
+      getCurveVaultPda({ curvePda: partnerEarnings, mint: quoteMint, programId: this.programId }),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getCreatePartnerEarningsInstructionAsync({
+      config,
+      partner,
+      partnerEarnings,
+      quoteVault,
+      quoteMint,
+      payer,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
     })
-    return await fetchCashbackAccount(this.rpc, cashbackPda)
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(payer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    return { partnerEarnings, quoteVault }
   }
 
-  async getCashbackTokenAccountBalance({
-    user,
+  async claimPartnerEarnings({
+    partner,
     quoteMint = WSOL_MINT,
+    configAddress,
   }: {
-    user: Address
+    partner: KeyPairSigner
     quoteMint?: Address
+    // a config in the quote mint, sequences the event
+    configAddress?: Address
   }) {
-    const [cashbackPda] = await getUserCashbackAccountPda({
-      userAddress: user,
+    const config = configAddress ?? this.currentConfig!
+    const [[partnerEarnings], partnerAta, { value: latestBlockhash }] = await Promise.all([
+      getPartnerEarningsPda({ partner: partner.address, quoteMint, programId: this.programId }),
+      getOrCreateATAInstruction(this.rpc, quoteMint, partner.address, partner),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    const { ata: partnerTokenAccount, ix: createAtaIx } = partnerAta
+    const [quoteVault] = await getCurveVaultPda({
+      curvePda: partnerEarnings,
+      mint: quoteMint,
       programId: this.programId,
     })
-    const cashbackAta = await getAssociatedTokenAccountAddress(quoteMint, cashbackPda, TOKEN_PROGRAM_ADDRESS)
-    return await this.getTokenAccountBalance(cashbackAta)
+
+    const ix = await getClaimPartnerEarningsInstructionAsync({
+      config,
+      partnerEarnings,
+      quoteVault,
+      partnerTokenAccount,
+      quoteMint,
+      partner,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions(createAtaIx ? [createAtaIx, ix] : [ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(partner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    return { partnerTokenAccount }
   }
 
-  async getCashbackAccountRentExempt() {
-    const rentExempt = await this.rpc.getMinimumBalanceForRentExemption(BigInt(getCashbackAccountSize())).send()
-    return BigInt(rentExempt)
+  async getPartnerEarningsData({ partner, quoteMint = WSOL_MINT }: { partner: Address; quoteMint?: Address }) {
+    const [partnerEarnings] = await getPartnerEarningsPda({ partner, quoteMint, programId: this.programId })
+    return fetchPartnerEarnings(this.rpc, partnerEarnings)
   }
 
-  async verifyReferrerRewards(referrer: Address, expectedRewards: bigint) {
-    const [referrerCashbackBalance, rentExempt] = await Promise.all([
-      this.getCashbackTokenAccountBalance({ user: referrer }),
-      this.getCashbackAccountRentExempt(),
+  /******************************* Raffles *******************************/
+  async createRaffle({
+    creator,
+    baseMint,
+    ticketPrice,
+    winningTicketCount,
+    maxTicketsPerWallet,
+    depositEndTimestamp,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    creator: KeyPairSigner
+    baseMint: Address
+    ticketPrice: bigint
+    winningTicketCount: bigint
+    maxTicketsPerWallet: bigint
+    depositEndTimestamp: bigint
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [curvePda] = await getCurvePda({ configAddress: config, baseMint, quoteMint, programId: this.programId })
+    const [rafflePda] = await getRafflePda({ curvePda, programId: this.programId })
+    const [[raffleQuoteVault], [raffleBaseVault]] = await Promise.all([
+      getCurveVaultPda({ curvePda: rafflePda, mint: quoteMint, programId: this.programId }),
+      getCurveVaultPda({ curvePda: rafflePda, mint: baseMint, programId: this.programId }),
     ])
 
-    const actualRewards = referrerCashbackBalance - rentExempt
-    expect(Math.abs(Number(actualRewards - expectedRewards))).toBeLessThan(100n)
+    const ix = await getCreateRaffleInstructionAsync({
+      config,
+      curve: curvePda,
+      raffle: rafflePda,
+      raffleQuoteVault,
+      raffleBaseVault,
+      baseMint,
+      quoteMint,
+      creator,
+      tokenBaseProgram: TOKEN_PROGRAM_ADDRESS,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+      params: { ticketPrice, winningTicketCount, maxTicketsPerWallet, depositEndTimestamp },
+    })
 
-    return { referrerCashbackBalance, rentExempt }
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(creator, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    return { rafflePda, raffleQuoteVault, raffleBaseVault }
   }
 
-  /******************************* Helper Functions *******************************/
-  /**
-   * Creates instructions to wrap native SOL into WSOL tokens
-   * Equivalent to the legacy Solana web3.js wrapSOLInstruction
-   */
-  private createWrapSOLInstructions(from: KeyPairSigner, to: Address, amount: bigint) {
-    // Create transfer SOL instruction - source should be the KeyPairSigner
-    const transferIx = getTransferSolInstruction({
-      source: from,
-      destination: to,
-      amount,
+  async buyRaffleTickets({
+    owner,
+    baseMint,
+    ticketCount,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    owner: KeyPairSigner
+    baseMint: Address
+    ticketCount: bigint
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [curvePda] = await getCurvePda({ configAddress: config, baseMint, quoteMint, programId: this.programId })
+    const [rafflePda] = await getRafflePda({ curvePda, programId: this.programId })
+    const [raffle, [raffleTicket], { ataTokenA: ownerTokenAccount, instructions: preInstructions }] =
+      await Promise.all([
+        fetchRaffle(this.rpc, rafflePda),
+        getRaffleTicketPda({ rafflePda, ownerAddress: owner.address, programId: this.programId }),
+        prepareTokenAccounts({
+          rpc: this.rpc,
+          owner: owner.address,
+          payer: owner,
+          tokenAMint: quoteMint,
+          tokenBMint: baseMint,
+          tokenAProgram: TOKEN_PROGRAM_ADDRESS,
+          tokenBProgram: TOKEN_PROGRAM_ADDRESS,
+        }),
+      ])
+
+    if (quoteMint === WSOL_MINT) {
+      const amount = ticketCount * raffle.data.ticketPrice
+      preInstructions.push(...this.createWrapSOLInstructions(owner, ownerTokenAccount, amount))
+    }
+
+    const ix = await getBuyRaffleTicketsInstructionAsync({
+      config,
+      curve: curvePda,
+      raffle: rafflePda,
+      raffleTicket,
+      quoteVault: raffle.data.quoteVault,
+      ownerTokenAccount,
+      quoteMint,
+      owner,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+      ticketCount,
     })
 
-    // Create sync native instruction - only needs the account parameter
-    const syncNativeIx = getSyncNativeInstruction({ account: to }, { programAddress: TOKEN_PROGRAM_ADDRESS })
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
 
-    return [transferIx, syncNativeIx]
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([...preInstructions, ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    return { raffleTicket }
   }
 
-  /**
-   * Creates instruction to unwrap WSOL back to native SOL
-   * Equivalent to the legacy createCloseAccountInstruction
-   */
-  private async createUnwrapSOLInstruction(owner: KeyPairSigner, receiver: Address) {
-    const wSolATAAccount = await getAssociatedTokenAccountAddress(WSOL_MINT, owner.address, TOKEN_PROGRAM_ADDRESS)
-    if (wSolATAAccount) {
-      return getCloseAccountInstruction(
-        {
-          account: wSolATAAccount,
-          destination: receiver,
-          owner,
-        },
-        {
-          programAddress: TOKEN_PROGRAM_ADDRESS,
-        },
-      )
-    }
-    return null
+  async drawRaffle({
+    baseMint,
+    payer = this.owner,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    baseMint: Address
+    payer?: KeyPairSigner
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [curvePda] = await getCurvePda({ configAddress: config, baseMint, quoteMint, programId: this.programId })
+    const [rafflePda] = await getRafflePda({ curvePda, programId: this.programId })
+    const [curve, raffle, [configStats]] = await Promise.all([
+      fetchBondingCurve(this.rpc, curvePda),
+      fetchRaffle(this.rpc, rafflePda),
+      getConfigStatsPda({ configAddress: config, programId: this.programId }),
+    ])
+    // the randomness bond goes back to the creator on the draw
+    const creatorTokenAccount =
+      raffle.data.randomnessBond > 0n
+        ? await getAssociatedTokenAccountAddress(quoteMint, curve.data.creator, TOKEN_PROGRAM_ADDRESS)
+        : undefined
+
+    const ix = await getDrawRaffleInstructionAsync({
+      config,
+      curve: curvePda,
+      raffle: rafflePda,
+      baseVault: curve.data.baseVault,
+      quoteVault: curve.data.quoteVault,
+      raffleQuoteVault: raffle.data.quoteVault,
+      raffleBaseVault: raffle.data.baseVault,
+      creatorTokenAccount,
+      baseMint,
+      quoteMint,
+      configStats,
+      tokenBaseProgram: TOKEN_PROGRAM_ADDRESS,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(payer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async claimRaffle({
+    owner,
+    baseMint,
+    quoteMint = WSOL_MINT,
+    configAddress,
+  }: {
+    owner: KeyPairSigner
+    baseMint: Address
+    quoteMint?: Address
+    configAddress?: Address
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const [curvePda] = await getCurvePda({ configAddress: config, baseMint, quoteMint, programId: this.programId })
+    const [rafflePda] = await getRafflePda({ curvePda, programId: this.programId })
+    const [raffle, [raffleTicket], ownerBaseTokenAccount, ownerQuoteTokenAccount] = await Promise.all([
+      fetchRaffle(this.rpc, rafflePda),
+      getRaffleTicketPda({ rafflePda, ownerAddress: owner.address, programId: this.programId }),
+      getAssociatedTokenAccountAddress(baseMint, owner.address, TOKEN_PROGRAM_ADDRESS),
+      getAssociatedTokenAccountAddress(quoteMint, owner.address, TOKEN_PROGRAM_ADDRESS),
+    ])
+
+    const ix = await getClaimRaffleInstructionAsync({
+      config,
+      curve: curvePda,
+      raffle: rafflePda,
+      raffleTicket,
+      raffleQuoteVault: raffle.data.quoteVault,
+      raffleBaseVault: raffle.data.baseVault,
+      ownerBaseTokenAccount,
+      ownerQuoteTokenAccount,
+      baseMint,
+      quoteMint,
+      owner,
+      tokenBaseProgram: TOKEN_PROGRAM_ADDRESS,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
   }
 
-  /******************************* Trade Functions *******************************/
-  async swap({
-    trader,
+  /******************************* Limit orders *******************************/
+  async placeLimitOrder({
+    owner,
     baseMint,
-    amountIn,
-    minimumAmountOut,
     tradeDirection,
-    cashbackAddress,
+    amountIn,
+    targetPrice,
+    fillerReward = 0n,
+    orderId = BigInt(Math.floor(Math.random() * 1_000_000)),
     quoteMint = WSOL_MINT,
     configAddress,
-    l1Referrer,
-    l2Referrer,
-    l3Referrer,
   }: {
-    trader: KeyPairSigner
+    owner: KeyPairSigner
     baseMint: Address
-    amountIn: bigint
-    minimumAmountOut: bigint
     tradeDirection: TradeDirection
-    cashbackAddress?: Address
+    amountIn: bigint
+    /** quote per base as a Q64.64 of raw amounts */
+    targetPrice: bigint
+    fillerReward?: bigint
+    orderId?: bigint
     quoteMint?: Address
     configAddress?: Address
-    l1Referrer?: Address
-    l2Referrer?: Address
-    l3Referrer?: Address
   }) {
     const config = configAddress ?? this.currentConfig!
-    const [[curve], curveState, configState] = await Promise.all([
-      getCurvePda({
-        configAddress: config,
-        baseMint,
-        quoteMint,
-        programId: this.programId,
+    const inputMint = tradeDirection === TradeDirection.QuoteToBase ? quoteMint : baseMint
+    const [curvePda] = await getCurvePda({ configAddress: config, baseMint, quoteMint, programId: this.programId })
+    const [[limitOrder], { ataTokenA: ownerTokenAccount, instructions: preInstructions }] = await Promise.all([
+      getLimitOrderPda({ curvePda, owner: owner.address, orderId, programId: this.programId }),
+      prepareTokenAccounts({
+        rpc: this.rpc,
+        owner: owner.address,
+        payer: owner,
+        tokenAMint: inputMint,
+        tokenBMint: inputMint === quoteMint ? baseMint : quoteMint,
+        tokenAProgram: TOKEN_PROGRAM_ADDRESS,
+        tokenBProgram: TOKEN_PROGRAM_ADDRESS,
       }),
-      this.getBondingCurveData({ baseMint, quoteMint, configAddress: config }),
-      this.getConfigData({ configAddress: config }),
     ])
+    const [escrowVault] = await getCurveVaultPda({ curvePda: limitOrder, mint: inputMint, programId: this.programId })
 
-    const { inputMint, outputMint, inputTokenProgram, outputTokenProgram } = prepareSwapParams(
-      tradeDirection,
-      curveState.data,
-      configState.data,
+    if (inputMint === WSOL_MINT) {
+      preInstructions.push(...this.createWrapSOLInstructions(owner, ownerTokenAccount, amountIn + fillerReward))
+    }
+
+    const ix = await getPlaceLimitOrderInstructionAsync({
+      config,
+      curve: curvePda,
+      limitOrder,
+      escrowVault,
+      ownerTokenAccount,
+      inputMint,
+      owner,
+      tokenProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+      params: { orderId, tradeDirection, amountIn, targetPrice, fillerReward },
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([...preInstructions, ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
     )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    return { limitOrder, escrowVault, orderId }
+  }
+
+  async cancelLimitOrder({
+    owner,
+    limitOrder,
+    baseMint,
+    quoteMint = WSOL_MINT,
+  }: {
+    owner: KeyPairSigner
+    limitOrder: Address
+    baseMint: Address
+    quoteMint?: Address
+  }) {
+    const order = await fetchLimitOrder(this.rpc, limitOrder)
+    const curveState = await this.getCurveData({ curveAddress: order.data.curve })
+    const inputMint = order.data.tradeDirection === TradeDirection.QuoteToBase ? quoteMint : baseMint
+    const ownerTokenAccount = await getAssociatedTokenAccountAddress(inputMint, owner.address, TOKEN_PROGRAM_ADDRESS)
+
+    const ix = await getCancelLimitOrderInstructionAsync({
+      config: curveState.data.config,
+      curve: order.data.curve,
+      limitOrder,
+      escrowVault: order.data.escrowVault,
+      ownerTokenAccount,
+      inputMint,
+      owner,
+      tokenProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(owner, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async fillLimitOrder({
+    filler,
+    limitOrder,
+    baseMint,
+    quoteMint = WSOL_MINT,
+  }: {
+    filler: KeyPairSigner
+    limitOrder: Address
+    baseMint: Address
+    quoteMint?: Address
+  }) {
+    const order = await fetchLimitOrder(this.rpc, limitOrder)
+    const curveState = await this.getCurveData({ curveAddress: order.data.curve })
+    const isBuy = order.data.tradeDirection === TradeDirection.QuoteToBase
+    const [inputMint, outputMint] = isBuy ? [quoteMint, baseMint] : [baseMint, quoteMint]
 
-    // Calculate accounts and instructions
     const [
-      { ataTokenA: inputTokenAccount, ataTokenB: outputTokenAccount, instructions: preInstructions },
-      [baseVaultAddress],
-      [quoteVaultAddress],
-      { cashbackPda, cashbackTokenAccount },
-      { cashbackTokenAccount: l1ReferrerCashbackTokenAccount },
-      { cashbackTokenAccount: l2ReferrerCashbackTokenAccount },
-      { cashbackTokenAccount: l3ReferrerCashbackTokenAccount },
-      [curveAuthority],
-      { value: latestBlockhash },
-      unwrapIx,
+      { ataTokenA: ownerOutputTokenAccount, instructions: ownerInstructions },
+      { ataTokenA: fillerTokenAccount, instructions: fillerInstructions },
+      [baseVault],
+      [quoteVault],
     ] = await Promise.all([
       prepareTokenAccounts({
         rpc: this.rpc,
-        owner: trader.address,
-        payer: trader,
+        owner: order.data.owner,
+        payer: filler,
+        tokenAMint: outputMint,
+        tokenBMint: inputMint,
+        tokenAProgram: TOKEN_PROGRAM_ADDRESS,
+        tokenBProgram: TOKEN_PROGRAM_ADDRESS,
+      }),
+      prepareTokenAccounts({
+        rpc: this.rpc,
+        owner: filler.address,
+        payer: filler,
         tokenAMint: inputMint,
         tokenBMint: outputMint,
-        tokenAProgram: inputTokenProgram,
-        tokenBProgram: inputTokenProgram,
-      }),
-      getCurveVaultPda({
-        curvePda: curve,
-        mint: baseMint,
-        programId: this.programId,
-      }),
-      getCurveVaultPda({
-        curvePda: curve,
-        mint: quoteMint,
-        programId: this.programId,
-      }),
-      getCashbackAccounts({
-        cashbackAddress,
-        quoteMint,
-        programId: this.programId,
-      }),
-      getCashbackAccounts({
-        cashbackAddress: l1Referrer,
-        quoteMint,
-        programId: this.programId,
-      }),
-      getCashbackAccounts({
-        cashbackAddress: l2Referrer,
-        quoteMint,
-        programId: this.programId,
-      }),
-      getCashbackAccounts({
-        cashbackAddress: l3Referrer,
-        quoteMint,
-        programId: this.programId,
+        tokenAProgram: TOKEN_PROGRAM_ADDRESS,
+        tokenBProgram: TOKEN_PROGRAM_ADDRESS,
       }),
-      getCurveAuthority({ programId: this.programId }),
-      this.rpc.getLatestBlockhash().send(),
-      // If we're dealing with WSOL, add unwrap instruction to close the account and get native SOL back
-      inputMint === WSOL_MINT || outputMint === WSOL_MINT
-        ? this.createUnwrapSOLInstruction(trader, trader.address)
-        : null,
+      getCurveVaultPda({ curvePda: order.data.curve, mint: baseMint, programId: this.programId }),
+      getCurveVaultPda({ curvePda: order.data.curve, mint: quoteMint, programId: this.programId }),
     ])
 
-    if (inputMint === WSOL_MINT && amountIn > 0n) {
-      preInstructions.push(...this.createWrapSOLInstructions(trader, inputTokenAccount, amountIn))
-    }
-    const postInstructions = unwrapIx ? [unwrapIx] : []
-
-    const params = {
-      amountIn,
-      minimumAmountOut,
-    }
-
-    const ix = await getSwapInstructionAsync({
-      curveAuthority,
-      config,
-      curve,
-      inputTokenAccount,
-      outputTokenAccount,
-      baseVault: baseVaultAddress,
-      quoteVault: quoteVaultAddress,
+    const ix = await getFillLimitOrderInstructionAsync({
+      config: curveState.data.config,
+      curve: order.data.curve,
+      limitOrder,
+      escrowVault: order.data.escrowVault,
+      owner: order.data.owner,
+      ownerOutputTokenAccount,
+      fillerTokenAccount,
+      baseVault,
+      quoteVault,
       baseMint,
       quoteMint,
-      payer: trader,
+      filler,
       tokenBaseProgram: TOKEN_PROGRAM_ADDRESS,
       tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
-
-      /* cashback PDAs */
-      cashback: cashbackPda ?? null,
-      cashbackTokenAccount: cashbackTokenAccount ?? null,
-      l1ReferralCashbackTokenAccount: l1ReferrerCashbackTokenAccount ?? null,
-      l2ReferralCashbackTokenAccount: l2ReferrerCashbackTokenAccount ?? null,
-      l3ReferralCashbackTokenAccount: l3ReferrerCashbackTokenAccount ?? null,
-
-      /* params */
+      protection: null,
+      configStats: (await getConfigStatsPda({ configAddress: curveState.data.config, programId: this.programId }))[0],
       program: this.programId,
-      params,
     })
 
-    // Estimate compute units before sending
-    const transactionMessage = pipe(
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
       createTransactionMessage({ version: 0 }),
-      (tx) => appendTransactionMessageInstructions([...preInstructions, ix, ...postInstructions], tx),
-      (tx) => setTransactionMessageFeePayerSigner(trader, tx),
+      (tx) => appendTransactionMessageInstructions([...ownerInstructions, ...fillerInstructions, ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(filler, tx),
       (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
     )
-    const signedTx = await signTransactionMessageWithSigners(transactionMessage)
-    const signature = await this.sendAndConfirmTransaction(signedTx)
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
 
-    if (this.network === 'devnet') {
-      console.log(
-        'Explorer (buy):',
-        getExplorerLink({
-          cluster: 'devnet',
-          transaction: signature,
-        }),
-      )
-    }
+    return { ownerOutputTokenAccount, fillerTokenAccount }
+  }
 
-    // Get transaction details to extract compute units used
-    const txDetails = await this.rpc
-      .getTransaction(signature, {
-        encoding: 'jsonParsed',
-        maxSupportedTransactionVersion: 0,
-      })
-      .send()
-    return txDetails?.meta?.computeUnitsConsumed ? Number(txDetails.meta.computeUnitsConsumed) : 0
+  async getProtectionData({ curve, user }: { curve: Address; user: Address }) {
+    const [protectionPda] = await getProtectionPda({ curvePda: curve, userAddress: user, programId: this.programId })
+    return fetchProtection(this.rpc, protectionPda)
   }
 
-  /******************************* Fee Claimer Functions *******************************/
-  async claimProtocolFees({
-    feeClaimer,
-    baseMint,
-    quoteMint = WSOL_MINT,
+  async getBonusPoolData({ curve }: { curve: Address }) {
+    const [bonusPoolPda] = await getBonusPoolPda({ curvePda: curve, programId: this.programId })
+    return fetchBonusPool(this.rpc, bonusPoolPda)
+  }
+
+  /******************************* Holder rewards *******************************/
+  async fundRewardVault({
+    curve,
+    amount,
+    rewardMint = WSOL_MINT,
     configAddress,
+    signer,
   }: {
-    feeClaimer: KeyPairSigner
-    baseMint: Address
-    quoteMint?: Address
+    curve: Address
+    amount: bigint
+    rewardMint?: Address
     configAddress?: Address
+    signer?: KeyPairSigner
   }) {
     const config = configAddress ?? this.currentConfig!
-    if (!config) {
-      throw new Error('AMM config not initialized')
+    const admin = signer || this.owner
+    const [[rewardVault], { ata: adminTokenAccount, ix: createAtaIx }] = await Promise.all([
+      getRewardVaultPda({ curvePda: curve, programId: this.programId }),
+      getOrCreateATAInstruction(this.rpc, rewardMint, admin.address, admin),
+    ])
+    const [rewardTokenVault] = await getCurveVaultPda({
+      curvePda: rewardVault,
+      mint: rewardMint,
+      programId: this.programId,
+    })
+
+    const preInstructions = []
+    createAtaIx && preInstructions.push(createAtaIx)
+    if (rewardMint === WSOL_MINT) {
+      preInstructions.push(...this.createWrapSOLInstructions(admin, adminTokenAccount, amount))
     }
 
-    const [[curvePda], feeClaimerTokenAccount, { value: latestBlockhash }] = await Promise.all([
-      getCurvePda({
-        configAddress: config,
-        baseMint,
-        quoteMint,
-        programId: this.programId,
-      }),
-      getAssociatedTokenAccountAddress(quoteMint, feeClaimer.address, TOKEN_PROGRAM_ADDRESS),
+    const ix = await getFundRewardVaultInstructionAsync({
+      config,
+      curve,
+      rewardVault,
+      rewardTokenVault,
+      adminTokenAccount,
+      rewardMint,
+      admin,
+      tokenProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+      amount,
+    })
+
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([...preInstructions, ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(admin, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+
+    return { rewardVault, rewardTokenVault }
+  }
+
+  async setRewardDistribution({
+    curve,
+    merkleRoot,
+    configAddress,
+    signer,
+  }: {
+    curve: Address
+    merkleRoot: number[]
+    configAddress?: Address
+    signer?: KeyPairSigner
+  }) {
+    const config = configAddress ?? this.currentConfig!
+    const admin = signer || this.owner
+    const [[rewardVault], { value: latestBlockhash }] = await Promise.all([
+      getRewardVaultPda({ curvePda: curve, programId: this.programId }),
       this.rpc.getLatestBlockhash().send(),
     ])
-    const curveData = await this.getBondingCurveData({ baseMint, quoteMint, configAddress: config })
 
-    const ix = await getClaimProtocolFeeInstructionAsync({
+    const ix = await getSetRewardDistributionInstructionAsync({
       config,
-      curve: curvePda,
-      feeClaimerTokenAccount,
-      quoteVault: curveData.data.quoteVault,
-      quoteMint: quoteMint,
-      feeClaimer,
-      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      curve,
+      rewardVault,
+      admin,
       program: this.programId,
+      merkleRoot,
     })
 
     const tx = pipe(
       createTransactionMessage({ version: 0 }),
       (tx) => appendTransactionMessageInstructions([ix], tx),
-      (tx) => setTransactionMessageFeePayerSigner(feeClaimer, tx),
+      (tx) => setTransactionMessageFeePayerSigner(admin, tx),
       (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
     )
-
     const signedTx = await signTransactionMessageWithSigners(tx)
     await this.sendAndConfirmTransaction(signedTx)
   }
 
-  async claimCreatorFee({
-    creator,
-    baseMint,
-    quoteMint = WSOL_MINT,
+  async claimReward({
+    user,
+    curve,
+    amount,
+    proof,
+    rewardMint = WSOL_MINT,
     configAddress,
   }: {
-    creator: KeyPairSigner
-    baseMint: Address
-    quoteMint?: Address
+    user: KeyPairSigner
+    curve: Address
+    amount: bigint
+    proof: number[][]
+    rewardMint?: Address
     configAddress?: Address
   }) {
     const config = configAddress ?? this.currentConfig!
-    if (!config) {
-      throw new Error('AMM config not initialized')
-    }
-
-    const [[curvePda], creatorTokenAccount, { value: latestBlockhash }] = await Promise.all([
-      getCurvePda({
-        configAddress: config,
-        baseMint,
-        quoteMint,
-        programId: this.programId,
-      }),
-      getAssociatedTokenAccountAddress(quoteMint, creator.address, TOKEN_PROGRAM_ADDRESS),
-      this.rpc.getLatestBlockhash().send(),
+    const [[rewardVault], userTokenAccount] = await Promise.all([
+      getRewardVaultPda({ curvePda: curve, programId: this.programId }),
+      getAssociatedTokenAccountAddress(rewardMint, user.address, TOKEN_PROGRAM_ADDRESS),
+    ])
+    const [[rewardClaim], [rewardTokenVault]] = await Promise.all([
+      getRewardClaimPda({ rewardVault, userAddress: user.address, programId: this.programId }),
+      getCurveVaultPda({ curvePda: rewardVault, mint: rewardMint, programId: this.programId }),
     ])
 
-    const curveData = await this.getBondingCurveData({ baseMint, quoteMint, configAddress: config })
-
-    const ix = await getClaimCreatorFeeInstructionAsync({
-      curve: curvePda,
-      creatorTokenAccount,
-      quoteVault: curveData.data.quoteVault,
-      quoteMint: quoteMint,
-      creator,
-      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+    const ix = await getClaimRewardInstructionAsync({
+      config,
+      curve,
+      rewardVault,
+      rewardClaim,
+      rewardTokenVault,
+      userTokenAccount,
+      rewardMint,
+      user,
+      tokenProgram: TOKEN_PROGRAM_ADDRESS,
       program: this.programId,
+      amount,
+      proof,
     })
 
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
     const tx = pipe(
       createTransactionMessage({ version: 0 }),
       (tx) => appendTransactionMessageInstructions([ix], tx),
-      (tx) => setTransactionMessageFeePayerSigner(creator, tx),
+      (tx) => setTransactionMessageFeePayerSigner(user, tx),
       (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
     )
-
     const signedTx = await signTransactionMessageWithSigners(tx)
     await this.sendAndConfirmTransaction(signedTx)
   }
 
+  async getRewardVaultData({ curve }: { curve: Address }) {
+    const [rewardVault] = await getRewardVaultPda({ curvePda: curve, programId: this.programId })
+    return fetchRewardVault(this.rpc, rewardVault)
+  }
+
   /******************************* Migration *******************************/
   async migrate({
     curve,
     baseMint,
     quoteMint = WSOL_MINT,
     config = this.currentConfig,
+    dammConfig = DAMM_CONFIG_ACCOUNT,
+    allowDammConfigOverride = false,
+    migrationAuthority = this.owner,
+    createPoolOnly = false,
+    permissionless = false,
+    withTestHooks = false,
+    payMigrationFee = false,
   }: {
     curve: Address
     baseMint: Address
     quoteMint?: Address
     config?: Address
+    dammConfig?: Address
+    allowDammConfigOverride?: boolean
+    migrationAuthority?: KeyPairSigner
+    // only run the first step of a staged migration
+    createPoolOnly?: boolean
+    // crank without a delegation, the reward goes to the quote ATA of the migration authority
+    permissionless?: boolean
+    // pass the test hooks after the DAMM config so their fault point and clock skew apply
+    withTestHooks?: boolean
+    // pay the migration fee to the fee claimer's quote and base ATAs, created when missing
+    payMigrationFee?: boolean
   }) {
     const [
       [curveAuthority],
@@ -889,7 +3787,7 @@ export class TestContextClass {
       deriveDammV2EventAuthority(),
       deriveDammV2MigrationMetadataAddress(curve),
       deriveDammV2PoolAddress({
-        config: DAMM_CONFIG_ACCOUNT,
+        config: dammConfig,
         tokenAMint: baseMint,
         tokenBMint: quoteMint,
       }),
@@ -920,23 +3818,68 @@ export class TestContextClass {
       this.rpc.getLatestBlockhash().send(),
     ])
 
+    const baseIsTokenA = isBaseTokenA({
+      baseMint,
+      quoteMint,
+      migrationTokenOrder: configData.data.migrationTokenOrder,
+    })
     const [tokenAVault] = await deriveDammV2TokenVaultAddress({
       pool: dammPool,
-      mint: baseMint,
+      mint: baseIsTokenA ? baseMint : quoteMint,
     })
     const [tokenBVault] = await deriveDammV2TokenVaultAddress({
       pool: dammPool,
-      mint: quoteMint,
+      mint: baseIsTokenA ? quoteMint : baseMint,
     })
 
     const tokenBaseProgram = curveData.data.curveType === 0 ? TOKEN_PROGRAM_ADDRESS : TOKEN_2022_PROGRAM_ADDRESS
     const tokenQuoteProgram = TOKEN_PROGRAM_ADDRESS
+    const [dualListing] = curveData.data.isDualListed
+      ? await getDualListingPda({ baseMint, programId: this.programId })
+      : [undefined]
+    // dual listed curves migrate with the other curve of the listing, they graduate together
+    const siblingCurve = dualListing
+      ? await fetchDualListing(this.rpc, dualListing).then(({ data }) =>
+          data.primaryCurve === curve ? data.secondaryCurve : data.primaryCurve,
+        )
+      : undefined
+    // operators migrate through the delegation of the curve, admins and permissionless cranks don't need one
+    const [migrationDelegate] =
+      migrationAuthority.address !== this.owner.address && !permissionless
+        ? await getMigrationDelegatePda({ curve, programId: this.programId })
+        : [undefined]
+    // configs with a support fund share route part of the migration fee to the curve's fund
+    const [supportFund] =
+      configData.data.supportFundBasisPoints > 0
+        ? await getSupportFundPda({ curve, programId: this.programId })
+        : [undefined]
+    const [supportFundVault] = supportFund
+      ? await getCurveVaultPda({ curvePda: supportFund, mint: quoteMint, programId: this.programId })
+      : [undefined]
+    const { ata: crankRewardTokenAccount, ix: createAtaIx } = permissionless
+      ? await getOrCreateATAInstruction(this.rpc, quoteMint, migrationAuthority.address, migrationAuthority)
+      : { ata: undefined, ix: undefined }
+    const [feeClaimerQuote, feeClaimerBase] = payMigrationFee
+      ? await Promise.all([
+          getOrCreateATAInstruction(this.rpc, quoteMint, configData.data.feeClaimer, migrationAuthority),
+          getOrCreateATAInstruction(
+            this.rpc,
+            baseMint,
+            configData.data.feeClaimer,
+            migrationAuthority,
+            tokenBaseProgram,
+          ),
+        ])
+      : [undefined, undefined]
 
-    const ix = await getMigrateDammV2InstructionAsync({
+    const getMigrateInstructionAsync = createPoolOnly
+      ? getMigrateDammV2CreatePoolInstructionAsync
+      : getMigrateDammV2InstructionAsync
+    const ix = await getMigrateInstructionAsync({
       curve,
       curveAuthority,
       config,
-      migrationAuthority: this.owner,
+      migrationAuthority,
       pool: dammPool,
       firstPositionNftMint: firstPositionNftKP,
       firstPositionNftAccount,
@@ -955,19 +3898,38 @@ export class TestContextClass {
       tokenQuoteProgram,
       token2022Program: TOKEN_2022_PROGRAM_ADDRESS,
       dammEventAuthority,
+      dualListing,
+      siblingCurve,
+      migrationDelegate,
+      crankRewardTokenAccount,
+      supportFund,
+      supportFundVault,
+      feeClaimerQuoteTokenAccount: feeClaimerQuote?.ata,
+      feeClaimerBaseTokenAccount: feeClaimerBase?.ata,
       program: this.programId,
+      allowDammConfigOverride,
     })
 
     // Add remaining accounts to the instruction
     ix.accounts.push({
-      address: DAMM_CONFIG_ACCOUNT,
+      address: dammConfig,
       role: AccountRole.READONLY,
     })
+    if (withTestHooks) {
+      ix.accounts.push({
+        address: (await getTestHooksPda({ programId: this.programId }))[0],
+        role: AccountRole.READONLY,
+      })
+    }
 
     const tx = pipe(
       createTransactionMessage({ version: 0 }),
-      (tx) => appendTransactionMessageInstructions([ix], tx),
-      (tx) => setTransactionMessageFeePayerSigner(this.owner, tx),
+      (tx) =>
+        appendTransactionMessageInstructions(
+          [createAtaIx, feeClaimerQuote?.ix, feeClaimerBase?.ix, ix].filter((ix) => ix !== undefined),
+          tx,
+        ),
+      (tx) => setTransactionMessageFeePayerSigner(migrationAuthority, tx),
       (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
     )
 
@@ -992,7 +3954,353 @@ export class TestContextClass {
       pool: dammPool,
       firstPositionNftKP,
       secondPositionNftKP,
+      signature,
+    }
+  }
+
+  async advanceMigrationStatus({ curve, payer = this.owner }: { curve: Address; payer?: KeyPairSigner }) {
+    const [curveData, { value: latestBlockhash }] = await Promise.all([
+      fetchBondingCurve(this.rpc, curve),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getAdvanceMigrationStatusInstructionAsync({
+      curve,
+      config: curveData.data.config,
+      baseVault: curveData.data.baseVault,
+      configStats: (await getConfigStatsPda({ configAddress: curveData.data.config, programId: this.programId }))[0],
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(payer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async migrateLockLiquidity({
+    curve,
+    payer = this.owner,
+    withTestHooks = false,
+  }: {
+    curve: Address
+    payer?: KeyPairSigner
+    withTestHooks?: boolean
+  }) {
+    const [[dammEventAuthority], migrationProgress, curveState, { value: latestBlockhash }] = await Promise.all([
+      deriveDammV2EventAuthority(),
+      this.getMigrationProgressData({ curve }),
+      fetchBondingCurve(this.rpc, curve),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getMigrateDammV2LockLiquidityInstructionAsync({
+      curve,
+      config: curveState.data.config,
+      pool: migrationProgress.data.pool,
+      firstPosition: migrationProgress.data.firstPosition,
+      firstPositionNftAccount: migrationProgress.data.firstPositionNftAccount,
+      dammEventAuthority,
+      program: this.programId,
+    })
+    if (withTestHooks) {
+      ix.accounts.push({
+        address: (await getTestHooksPda({ programId: this.programId }))[0],
+        role: AccountRole.READONLY,
+      })
     }
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(payer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    const signature = await this.sendAndConfirmTransaction(signedTx)
+    return signature
+  }
+
+  async migrateFinalize({
+    curve,
+    baseMint,
+    config = this.currentConfig,
+    payer = this.owner,
+  }: {
+    curve: Address
+    baseMint: Address
+    config?: Address
+    payer?: KeyPairSigner
+  }) {
+    const [migrationProgress, curveData, { value: latestBlockhash }] = await Promise.all([
+      this.getMigrationProgressData({ curve }),
+      fetchBondingCurve(this.rpc, curve),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    const [dualListing] = curveData.data.isDualListed
+      ? await getDualListingPda({ baseMint, programId: this.programId })
+      : [undefined]
+
+    const ix = await getMigrateDammV2FinalizeInstructionAsync({
+      curve,
+      config: config!,
+      firstPositionNftAccount: migrationProgress.data.firstPositionNftAccount,
+      baseMint,
+      token2022Program: TOKEN_2022_PROGRAM_ADDRESS,
+      dualListing,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(payer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async withdrawLeftover({
+    curve,
+    leftoverTokenAccount,
+    payer = this.owner,
+  }: {
+    curve: Address
+    leftoverTokenAccount?: Address
+    payer?: KeyPairSigner
+  }) {
+    const [curveData, { value: latestBlockhash }] = await Promise.all([
+      fetchBondingCurve(this.rpc, curve),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getWithdrawLeftoverInstructionAsync({
+      config: curveData.data.config,
+      curve,
+      baseVault: curveData.data.baseVault,
+      baseMint: curveData.data.baseMint,
+      leftoverTokenAccount,
+      tokenBaseProgram: curveData.data.curveType === 0 ? TOKEN_PROGRAM_ADDRESS : TOKEN_2022_PROGRAM_ADDRESS,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(payer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  /**
+   * Archives a settled, migrated curve, the fee claimer's quote ATA receives the quote nobody is owed
+   */
+  async archiveCurve({ curve, payer = this.owner }: { curve: Address; payer?: KeyPairSigner }) {
+    const { data: curveData } = await fetchBondingCurve(this.rpc, curve)
+    const { data: configData } = await fetchConfig(this.rpc, curveData.config)
+    const [[curveArchive], [bonusPool], [supportFund], [rewardVault], feeClaimerTokenAccount] = await Promise.all([
+      getCurveArchivePda({ curvePda: curve, programId: this.programId }),
+      getBonusPoolPda({ curvePda: curve, programId: this.programId }),
+      getSupportFundPda({ curve, programId: this.programId }),
+      getRewardVaultPda({ curvePda: curve, programId: this.programId }),
+      getAssociatedTokenAccountAddress(configData.quoteMint, configData.feeClaimer, TOKEN_PROGRAM_ADDRESS),
+    ])
+    const { value: latestBlockhash } = await this.rpc.getLatestBlockhash().send()
+
+    const ix = await getArchiveCurveInstructionAsync({
+      config: curveData.config,
+      curve,
+      curveArchive,
+      baseVault: curveData.baseVault,
+      quoteVault: curveData.quoteVault,
+      bonusPool,
+      supportFund,
+      rewardVault,
+      feeClaimerTokenAccount,
+      feeClaimer: configData.feeClaimer,
+      baseMint: curveData.baseMint,
+      quoteMint: configData.quoteMint,
+      creator: curveData.creator,
+      payer,
+      tokenBaseProgram: curveData.curveType === 0 ? TOKEN_PROGRAM_ADDRESS : TOKEN_2022_PROGRAM_ADDRESS,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(payer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+    return { curveArchive }
+  }
+
+  async burnCurveBaseTokens({ curve, creator, amount }: { curve: Address; creator: KeyPairSigner; amount: bigint }) {
+    const [curveData, { value: latestBlockhash }] = await Promise.all([
+      fetchBondingCurve(this.rpc, curve),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+
+    const ix = await getBurnCurveBaseTokensInstructionAsync({
+      config: curveData.data.config,
+      curve,
+      baseVault: curveData.data.baseVault,
+      baseMint: curveData.data.baseMint,
+      creator,
+      tokenBaseProgram: curveData.data.curveType === 0 ? TOKEN_PROGRAM_ADDRESS : TOKEN_2022_PROGRAM_ADDRESS,
+      program: this.programId,
+      amount,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(creator, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+  }
+
+  async deploySupportFund({
+    curve,
+    amountIn,
+    minimumAmountOut = 0n,
+    admin = this.owner,
+  }: {
+    curve: Address
+    amountIn: bigint
+    minimumAmountOut?: bigint
+    admin?: KeyPairSigner
+  }) {
+    const [curveData, [supportFund], [dammPoolAuthority], [dammEventAuthority], [migrationProgress]] =
+      await Promise.all([
+        fetchBondingCurve(this.rpc, curve),
+        getSupportFundPda({ curve, programId: this.programId }),
+        deriveDammV2PoolAuthority(),
+        deriveDammV2EventAuthority(),
+        getMigrationProgressPda({ curve, programId: this.programId }),
+      ])
+    const [configData, progress, { value: latestBlockhash }] = await Promise.all([
+      this.getConfigData({ configAddress: curveData.data.config }),
+      fetchMigrationProgress(this.rpc, migrationProgress),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    const [[supportFundVault], dammPool] = await Promise.all([
+      getCurveVaultPda({ curvePda: supportFund, mint: configData.data.quoteMint, programId: this.programId }),
+      fetchPool(this.rpc, progress.data.pool),
+    ])
+
+    const ix = await getDeploySupportFundInstructionAsync({
+      admin,
+      config: curveData.data.config,
+      curve,
+      supportFund,
+      supportFundVault,
+      baseVault: curveData.data.baseVault,
+      migrationProgress,
+      pool: progress.data.pool,
+      dammPoolAuthority,
+      tokenAVault: dammPool.data.tokenAVault,
+      tokenBVault: dammPool.data.tokenBVault,
+      baseMint: curveData.data.baseMint,
+      quoteMint: configData.data.quoteMint,
+      tokenBaseProgram: curveData.data.curveType === 0 ? TOKEN_PROGRAM_ADDRESS : TOKEN_2022_PROGRAM_ADDRESS,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      dammEventAuthority,
+      program: this.programId,
+      amountIn,
+      minimumAmountOut,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(admin, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+    return { supportFund, supportFundVault }
+  }
+
+  async claimPositionFee({ curve, payer = this.owner }: { curve: Address; payer?: KeyPairSigner }) {
+    const [curveData, [dammPoolAuthority], [dammEventAuthority], [migrationProgress]] = await Promise.all([
+      fetchBondingCurve(this.rpc, curve),
+      deriveDammV2PoolAuthority(),
+      deriveDammV2EventAuthority(),
+      getMigrationProgressPda({ curve, programId: this.programId }),
+    ])
+    const [configData, progress, { value: latestBlockhash }] = await Promise.all([
+      this.getConfigData({ configAddress: curveData.data.config }),
+      fetchMigrationProgress(this.rpc, migrationProgress),
+      this.rpc.getLatestBlockhash().send(),
+    ])
+    const { creator, creatorFeeRecipient: recipient, baseMint } = curveData.data
+    const { quoteMint, feeClaimer } = configData.data
+    const creatorFeeRecipient = recipient === SYSTEM_PROGRAM_ADDRESS ? creator : recipient
+    const tokenBaseProgram = curveData.data.curveType === 0 ? TOKEN_PROGRAM_ADDRESS : TOKEN_2022_PROGRAM_ADDRESS
+    const [dammPool, creatorBaseAta, creatorQuoteAta, feeClaimerBaseAta, feeClaimerQuoteAta] = await Promise.all([
+      fetchPool(this.rpc, progress.data.pool),
+      getOrCreateATAInstruction(this.rpc, baseMint, creatorFeeRecipient, payer, tokenBaseProgram),
+      getOrCreateATAInstruction(this.rpc, quoteMint, creatorFeeRecipient, payer),
+      getOrCreateATAInstruction(this.rpc, baseMint, feeClaimer, payer, tokenBaseProgram),
+      getOrCreateATAInstruction(this.rpc, quoteMint, feeClaimer, payer),
+    ])
+    const createAtaIxs = [creatorBaseAta, creatorQuoteAta, feeClaimerBaseAta, feeClaimerQuoteAta].flatMap(({ ix }) =>
+      ix ? [ix] : [],
+    )
+
+    const ix = await getClaimPositionFeeInstructionAsync({
+      config: curveData.data.config,
+      curve,
+      migrationProgress,
+      pool: progress.data.pool,
+      firstPosition: progress.data.firstPosition,
+      firstPositionNftAccount: progress.data.firstPositionNftAccount,
+      dammPoolAuthority,
+      tokenAVault: dammPool.data.tokenAVault,
+      tokenBVault: dammPool.data.tokenBVault,
+      baseVault: curveData.data.baseVault,
+      quoteVault: curveData.data.quoteVault,
+      creatorFeeRecipient,
+      creatorBaseTokenAccount: creatorBaseAta.ata,
+      creatorQuoteTokenAccount: creatorQuoteAta.ata,
+      feeClaimer,
+      feeClaimerBaseTokenAccount: feeClaimerBaseAta.ata,
+      feeClaimerQuoteTokenAccount: feeClaimerQuoteAta.ata,
+      baseMint,
+      quoteMint,
+      tokenBaseProgram,
+      tokenQuoteProgram: TOKEN_PROGRAM_ADDRESS,
+      dammEventAuthority,
+      program: this.programId,
+    })
+
+    const tx = pipe(
+      createTransactionMessage({ version: 0 }),
+      (tx) => appendTransactionMessageInstructions([...createAtaIxs, ix], tx),
+      (tx) => setTransactionMessageFeePayerSigner(payer, tx),
+      (tx) => setTransactionMessageLifetimeUsingBlockhash(latestBlockhash, tx),
+    )
+    const signedTx = await signTransactionMessageWithSigners(tx)
+    await this.sendAndConfirmTransaction(signedTx)
+    return { creatorFeeRecipient, feeClaimer }
+  }
+
+  async getMigrationProgressData({ curve }: { curve: Address }) {
+    const [migrationProgress] = await getMigrationProgressPda({ curve, programId: this.programId })
+    return fetchMigrationProgress(this.rpc, migrationProgress)
   }
 
   async swapWithDammV2({
@@ -0,0 +1,49 @@
+import { createHash } from 'node:crypto'
+import { type Address, getAddressEncoder } from 'gill'
+
+const addressEncoder = getAddressEncoder()
+
+// Mirrors `RewardVault::verify_claim`, domain separated leaves and sorted pairs
+const LEAF_PREFIX = Buffer.from([0])
+const NODE_PREFIX = Buffer.from([1])
+
+export type RewardLeaf = { holder: Address; amount: bigint }
+
+function hashLeaf({ holder, amount }: RewardLeaf) {
+  const amountBytes = Buffer.alloc(8)
+  amountBytes.writeBigUInt64LE(amount)
+  return createHash('sha256').update(Buffer.concat([LEAF_PREFIX, addressEncoder.encode(holder), amountBytes])).digest()
+}
+
+function hashNode(a: Buffer, b: Buffer) {
+  const [left, right] = Buffer.compare(a, b) <= 0 ? [a, b] : [b, a]
+  return createHash('sha256').update(Buffer.concat([NODE_PREFIX, left, right])).digest()
+}
+
+/**
+ * Build the reward distribution tree of a holder snapshot, an odd node out is promoted as is
+ * @returns The merkle root and a function returning the proof of a leaf by index
+ */
+export function buildRewardMerkleTree(leaves: RewardLeaf[]) {
+  const levels: Buffer[][] = [leaves.map(hashLeaf)]
+  while (levels[levels.length - 1].length > 1) {
+    const level = levels[levels.length - 1]
+    const next: Buffer[] = []
+    for (let i = 0; i < level.length; i += 2) {
+      next.push(i + 1 < level.length ? hashNode(level[i], level[i + 1]) : level[i])
+    }
+    levels.push(next)
+  }
+
+  const getProof = (index: number) => {
+    const proof: number[][] = []
+    for (const level of levels.slice(0, -1)) {
+      const sibling = index ^ 1
+      if (sibling < level.length) proof.push([...level[sibling]])
+      index >>= 1
+    }
+    return proof
+  }
+
+  return { root: [...levels[levels.length - 1][0]], getProof }
+}
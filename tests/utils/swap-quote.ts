@@ -6,6 +6,19 @@ export enum TradeDirection {
   QuoteToBase = 1,
 }
 
+export enum FeeCollectionMode {
+  AlwaysOnQuote = 0,
+  FeeOnInput = 1,
+  FeeOnOutput = 2,
+}
+
+// Implements FeeCollectionMode::is_fee_on_base
+export function isFeeOnBase(configState: Config, tradeDirection: TradeDirection): boolean {
+  return tradeDirection === TradeDirection.BaseToQuote
+    ? configState.feeCollectionMode === FeeCollectionMode.FeeOnInput
+    : configState.feeCollectionMode === FeeCollectionMode.FeeOnOutput
+}
+
 export interface FeeBreakdown {
   amount: bigint
   l1ReferralFee: bigint
@@ -71,8 +84,8 @@ function safeMulDiv(x: bigint, y: bigint, denominator: bigint, roundUp: boolean)
   return safeDiv(prod, denominator)
 }
 
-// Get cashback BPS based on tier
-function getCashbackBps(cashbackTier?: number): bigint {
+// Implements Config::get_cashback_basis_points, the tier rate scaled by the multiplier of the direction
+function getCashbackBps(config: Config, tradeDirection: TradeDirection, cashbackTier?: number): bigint {
   const CASHBACK_BPS_MAP: Record<number, bigint> = {
     0: 50n, // Wood
     1: 100n, // Bronze
@@ -82,17 +95,25 @@ function getCashbackBps(cashbackTier?: number): bigint {
     5: 200n, // Diamond
     6: 250n, // Champion
   }
-  return cashbackTier !== undefined ? CASHBACK_BPS_MAP[cashbackTier] || 0n : 0n
+  if (cashbackTier === undefined) {
+    return 0n
+  }
+  const multiplierBasisPoints =
+    tradeDirection === TradeDirection.QuoteToBase
+      ? config.buyCashbackMultiplierBasisPoints
+      : config.sellCashbackMultiplierBasisPoints
+  return safeMulDiv(CASHBACK_BPS_MAP[cashbackTier] || 0n, BigInt(multiplierBasisPoints), FEE_DENOMINATOR, false)
 }
 
-// Implements Config::get_fee_on_amount
+// Implements Config::get_fee_on_amount with FeeRemainderMode::Protocol
 export function getFeeOnAmount(
   config: Config,
   amountIn: bigint,
   hasL1Referral: boolean,
   hasL2Referral: boolean,
   hasL3Referral: boolean,
-  cashbackTier?: number,
+  cashbackTier: number | undefined,
+  tradeDirection: TradeDirection,
 ): FeeBreakdown {
   const l1ReferralFee = hasL1Referral
     ? safeMulDiv(amountIn, BigInt(config.l1ReferralFeeBasisPoints), FEE_DENOMINATOR, false)
@@ -106,7 +127,7 @@ export function getFeeOnAmount(
     ? safeMulDiv(amountIn, BigInt(config.l3ReferralFeeBasisPoints), FEE_DENOMINATOR, false)
     : 0n
 
-  const cashbackBps = getCashbackBps(cashbackTier)
+  const cashbackBps = getCashbackBps(config, tradeDirection, cashbackTier)
   const cashbackFee = safeMulDiv(amountIn, cashbackBps, FEE_DENOMINATOR, false)
   const creatorFee = safeMulDiv(amountIn, BigInt(config.creatorFeeBasisPoints), FEE_DENOMINATOR, false)
 
@@ -135,6 +156,42 @@ export function getFeeOnAmount(
   }
 }
 
+// Implements Config::get_fee_split, the protocol gets the rounding remainder (FeeRemainderMode::Protocol)
+export function getFeeSplit(
+  config: Config,
+  amount: bigint,
+  totalFee: bigint,
+  hasL1Referral: boolean,
+  hasL2Referral: boolean,
+  hasL3Referral: boolean,
+  cashbackTier: number | undefined,
+  tradeDirection: TradeDirection,
+): FeeBreakdown {
+  const hasReferral = hasL1Referral || hasL2Referral || hasL3Referral
+  const tradingFeeBasisPoints = hasReferral
+    ? BigInt(config.feeBasisPoints - config.refereeDiscountBasisPoints)
+    : BigInt(config.feeBasisPoints)
+  const share = (basisPoints: bigint) =>
+    tradingFeeBasisPoints === 0n ? 0n : safeMulDiv(totalFee, basisPoints, tradingFeeBasisPoints, false)
+
+  const l1ReferralFee = hasL1Referral ? share(BigInt(config.l1ReferralFeeBasisPoints)) : 0n
+  const l2ReferralFee = hasL2Referral ? share(BigInt(config.l2ReferralFeeBasisPoints)) : 0n
+  const l3ReferralFee = hasL3Referral ? share(BigInt(config.l3ReferralFeeBasisPoints)) : 0n
+  const creatorFee = share(BigInt(config.creatorFeeBasisPoints))
+  const cashbackFee = share(getCashbackBps(config, tradeDirection, cashbackTier))
+  const protocolFee = totalFee - l1ReferralFee - l2ReferralFee - l3ReferralFee - creatorFee - cashbackFee
+
+  return {
+    amount,
+    protocolFee,
+    cashbackFee,
+    creatorFee,
+    l1ReferralFee,
+    l2ReferralFee,
+    l3ReferralFee,
+  }
+}
+
 // Sum all fees in FeeBreakdown
 function sumFeeBreakdown(feeBreakdown: FeeBreakdown): bigint {
   return safeAdd(
@@ -149,35 +206,158 @@ function sumFeeBreakdown(feeBreakdown: FeeBreakdown): bigint {
   )
 }
 
+// Implements BondingCurve::get_virtual_price, quote per base as a Q64.64 of raw amounts
+export function getVirtualPrice(curveState: BondingCurve): bigint {
+  return (curveState.virtualQuoteReserve << 64n) / curveState.virtualBaseReserve
+}
+
+// Implements get_base_scale
+export function getBaseScale(configState: Config): bigint {
+  const decimalGap =
+    configState.quoteDecimal > configState.baseDecimal ? configState.quoteDecimal - configState.baseDecimal : 0
+  return 10n ** BigInt(decimalGap)
+}
+
 // Implements get_swap_amount_from_quote_to_base (aka buy)
-export function getSwapAmountFromQuoteToBase(virtualQuote: bigint, virtualBase: bigint, amountIn: bigint): bigint {
-  // Scale tokens for precision
-  // Assuming quote token has 9 decimals and base token has 6 decimals
-  const virtualBaseScaled = safeMul(virtualBase, 1000n)
+export function getSwapAmountFromQuoteToBase(
+  virtualQuote: bigint,
+  virtualBase: bigint,
+  amountIn: bigint,
+  baseScale = 1000n,
+): bigint {
+  // Scale base tokens by the decimal gap to the quote token for precision
+  const virtualBaseScaled = safeMul(virtualBase, baseScale)
   const k = safeMul(virtualQuote, virtualBaseScaled)
   const newVirtualQuote = safeAdd(virtualQuote, amountIn)
-  const newVirtualBaseScaled = safeDiv(k, newVirtualQuote)
-  const baseOutAmount = safeDiv(safeSub(virtualBaseScaled, newVirtualBaseScaled), 1000n)
+  // the reserve left in the curve rounds up so the output rounds down, whatever the scale
+  const newVirtualBaseScaled = safeMulDiv(k, 1n, newVirtualQuote, true)
+  const baseOutAmount = safeDiv(safeSub(virtualBaseScaled, newVirtualBaseScaled), baseScale)
 
   return baseOutAmount
 }
 
 // Implements get_swap_amount_from_base_to_quote (aka sell)
-export function getSwapAmountFromBaseToQuote(virtualQuote: bigint, virtualBase: bigint, amountIn: bigint): bigint {
-  // Scale tokens for precision
-  // Assuming quote token has 9 decimals and base token has 6 decimals
-  const virtualBaseScaled = safeMul(virtualBase, 1000n)
-  const amountInScaled = safeMul(amountIn, 1000n)
+export function getSwapAmountFromBaseToQuote(
+  virtualQuote: bigint,
+  virtualBase: bigint,
+  amountIn: bigint,
+  baseScale = 1000n,
+  roundUp = false,
+): bigint {
+  // Scale base tokens by the decimal gap to the quote token for precision
+  const virtualBaseScaled = safeMul(virtualBase, baseScale)
+  const amountInScaled = safeMul(amountIn, baseScale)
   const newVirtualBaseScaled = safeAdd(virtualBaseScaled, amountInScaled)
 
   // Calculate using x*y=k
   const k = safeMul(virtualBaseScaled, virtualQuote)
-  const newQuote = safeDiv(k, newVirtualBaseScaled)
+  // roundUp is the rounding of the quote output, the reserve left rounds the other way
+  const newQuote = safeMulDiv(k, 1n, newVirtualBaseScaled, !roundUp)
   const quoteOutAmount = safeSub(virtualQuote, newQuote)
 
   return quoteOutAmount
 }
 
+// Implements get_swap_amount_in_from_quote_to_base, the smallest quote input of a buy that gets amountOut
+export function getSwapAmountInFromQuoteToBase(
+  virtualQuote: bigint,
+  virtualBase: bigint,
+  amountOut: bigint,
+  baseScale = 1000n,
+): bigint {
+  const virtualBaseScaled = safeMul(virtualBase, baseScale)
+  const k = safeMul(virtualQuote, virtualBaseScaled)
+  const newVirtualBaseScaled = safeSub(virtualBaseScaled, safeMul(amountOut, baseScale))
+  const newVirtualQuote = safeMulDiv(k, 1n, newVirtualBaseScaled, true)
+  return safeSub(newVirtualQuote, virtualQuote)
+}
+
+// Implements BondingCurve::get_buy_result_with_base_fee and get_sell_result_with_base_fee, the base fee is
+// valued in quote through the curve
+function getSwapResultWithBaseFee({
+  curveState,
+  configState,
+  amountIn,
+  tradeDirection,
+  hasL1Referral,
+  hasL2Referral,
+  hasL3Referral,
+  cashbackTier,
+}: Parameters<typeof getSwapResult>[0]): SwapResult {
+  const baseScale = getBaseScale(configState)
+  const { virtualQuoteReserve, virtualBaseReserve } = curveState
+  const hasReferral = hasL1Referral || hasL2Referral || hasL3Referral
+  const tradingFeeBasisPoints = hasReferral
+    ? BigInt(configState.feeBasisPoints - configState.refereeDiscountBasisPoints)
+    : BigInt(configState.feeBasisPoints)
+  const baseFeeOn = (amount: bigint) => safeMulDiv(amount, tradingFeeBasisPoints, FEE_DENOMINATOR, false)
+
+  let actualAmountIn: bigint
+  let actualAmountOut: bigint
+  let totalFee: bigint
+  if (tradeDirection === TradeDirection.QuoteToBase) {
+    const quoteInFor = (amountOut: bigint) =>
+      getSwapAmountInFromQuoteToBase(virtualQuoteReserve, virtualBaseReserve, amountOut, baseScale)
+    const grossAmountOut = getSwapAmountFromQuoteToBase(virtualQuoteReserve, virtualBaseReserve, amountIn, baseScale)
+    const outputAmount = safeSub(grossAmountOut, baseFeeOn(grossAmountOut))
+
+    if (
+      outputAmount >= curveState.baseReserve ||
+      safeSub(curveState.baseReserve, outputAmount) < configState.migrationBaseThreshold
+    ) {
+      const newBaseOutputAmount = safeSub(curveState.baseReserve, configState.migrationBaseThreshold)
+      const newVirtualBase = safeSub(virtualBaseReserve, newBaseOutputAmount)
+      actualAmountIn = getSwapAmountFromBaseToQuote(
+        configState.migrationQuoteThreshold,
+        newVirtualBase,
+        newBaseOutputAmount,
+        baseScale,
+        true,
+      )
+      actualAmountOut = newBaseOutputAmount
+      const cappedGrossAmountOut = safeMulDiv(
+        newBaseOutputAmount,
+        FEE_DENOMINATOR,
+        FEE_DENOMINATOR - tradingFeeBasisPoints,
+        true,
+      )
+      totalFee = safeSub(quoteInFor(cappedGrossAmountOut), quoteInFor(newBaseOutputAmount))
+    } else {
+      actualAmountIn = quoteInFor(outputAmount)
+      actualAmountOut = outputAmount
+      totalFee = safeSub(amountIn, actualAmountIn)
+    }
+  } else {
+    const quoteOutFor = (amount: bigint) =>
+      getSwapAmountFromBaseToQuote(virtualQuoteReserve, virtualBaseReserve, amount, baseScale)
+    actualAmountIn = amountIn
+    actualAmountOut = quoteOutFor(safeSub(amountIn, baseFeeOn(amountIn)))
+    totalFee = safeSub(quoteOutFor(amountIn), actualAmountOut)
+  }
+
+  const feeBreakdown = getFeeSplit(
+    configState,
+    actualAmountOut,
+    totalFee,
+    hasL1Referral,
+    hasL2Referral,
+    hasL3Referral,
+    cashbackTier,
+    tradeDirection,
+  )
+  return {
+    actualInputAmount: actualAmountIn,
+    outputAmount: actualAmountOut,
+    tradingFee: sumFeeBreakdown(feeBreakdown),
+    protocolFee: feeBreakdown.protocolFee,
+    cashbackFee: feeBreakdown.cashbackFee,
+    creatorFee: feeBreakdown.creatorFee,
+    l1ReferralFee: feeBreakdown.l1ReferralFee,
+    l2ReferralFee: feeBreakdown.l2ReferralFee,
+    l3ReferralFee: feeBreakdown.l3ReferralFee,
+  }
+}
+
 // Implements BondingCurve::get_swap_result
 export function getSwapResult({
   curveState,
@@ -198,6 +378,19 @@ export function getSwapResult({
   hasL3Referral: boolean
   cashbackTier?: number
 }): SwapResult {
+  if (isFeeOnBase(configState, tradeDirection)) {
+    return getSwapResultWithBaseFee({
+      curveState,
+      configState,
+      amountIn,
+      tradeDirection,
+      hasL1Referral,
+      hasL2Referral,
+      hasL3Referral,
+      cashbackTier,
+    })
+  }
+
   let protocolFee = 0n
   let tradingFee = 0n
   let l1ReferralFee = 0n
@@ -217,6 +410,7 @@ export function getSwapResult({
       hasL2Referral,
       hasL3Referral,
       cashbackTier,
+      tradeDirection,
     )
 
     protocolFee = feeBreakdown.protocolFee
@@ -231,10 +425,21 @@ export function getSwapResult({
   }
 
   // Calculate swap output amount
+  const baseScale = getBaseScale(configState)
   const outputAmount =
     tradeDirection === TradeDirection.QuoteToBase
-      ? getSwapAmountFromQuoteToBase(curveState.virtualQuoteReserve, curveState.virtualBaseReserve, actualAmountIn)
-      : getSwapAmountFromBaseToQuote(curveState.virtualQuoteReserve, curveState.virtualBaseReserve, actualAmountIn)
+      ? getSwapAmountFromQuoteToBase(
+          curveState.virtualQuoteReserve,
+          curveState.virtualBaseReserve,
+          actualAmountIn,
+          baseScale,
+        )
+      : getSwapAmountFromBaseToQuote(
+          curveState.virtualQuoteReserve,
+          curveState.virtualBaseReserve,
+          actualAmountIn,
+          baseScale,
+        )
 
   let actualAmountOut = outputAmount
 
@@ -248,11 +453,13 @@ export function getSwapResult({
       const newBaseOutputAmount = safeSub(curveState.baseReserve, configState.migrationBaseThreshold)
       const newVirtualBase = safeSub(curveState.virtualBaseReserve, newBaseOutputAmount)
 
-      // Recalculate the capped input amount
+      // Recalculate the capped input amount, rounded in favor of the curve
       const cappedAmountIn = getSwapAmountFromBaseToQuote(
         configState.migrationQuoteThreshold,
         newVirtualBase,
         newBaseOutputAmount,
+        baseScale,
+        true,
       )
 
       // Recalculate fees with capped amount
@@ -263,6 +470,7 @@ export function getSwapResult({
         hasL2Referral,
         hasL3Referral,
         cashbackTier,
+        tradeDirection,
       )
 
       protocolFee = feeBreakdown.protocolFee
@@ -285,6 +493,7 @@ export function getSwapResult({
       hasL2Referral,
       hasL3Referral,
       cashbackTier,
+      tradeDirection,
     )
 
     protocolFee = feeBreakdown.protocolFee